@@ -0,0 +1,508 @@
+//! HTTP client running a story against a provider API.
+//!
+//! The model is sent the story prompt and the file tools, and each tool call
+//! it makes is executed and its result sent back, until it replies without
+//! calling a tool. Token usage is summed from every response.
+
+use std::path::Path;
+
+use reqwest::Client;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use super::tools::{execute_tool, tool_definitions};
+use super::{ApiAgentSpec, ApiProvider};
+
+/// Turns allowed before a run is abandoned, unless `RALPH_API_MAX_TURNS` is set.
+const DEFAULT_MAX_TURNS: u32 = 50;
+
+/// Output tokens allowed per response.
+const DEFAULT_MAX_TOKENS: u32 = 8192;
+
+/// Anthropic API version sent with every request.
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Instructions sent ahead of the story prompt.
+const SYSTEM_PROMPT: &str = "You are implementing a user story in a software project. \
+Use the tools to inspect and edit files; paths are relative to the project root. \
+Do not commit: changes are checked and committed after you finish. \
+When the story is implemented, reply with a short summary and no tool calls.";
+
+/// Errors from an API agent run.
+#[derive(Debug, Error)]
+pub enum ApiAgentError {
+    /// The backend is not configured (e.g. no API key)
+    #[error("API agent configuration error: {0}")]
+    Config(String),
+    /// The request could not be sent or its response read
+    #[error("API request failed: {0}")]
+    Http(String),
+    /// The provider answered with an error status
+    #[error("{provider} API returned HTTP {status}: {message}")]
+    Api {
+        provider: ApiProvider,
+        status: u16,
+        message: String,
+    },
+    /// The response did not have the expected shape
+    #[error("Unexpected API response: {0}")]
+    Protocol(String),
+    /// The model kept calling tools past the turn limit
+    #[error("Agent did not finish within {0} turns")]
+    TurnLimit(u32),
+}
+
+/// Result type for API agent operations.
+pub type ApiAgentResult<T> = Result<T, ApiAgentError>;
+
+/// Settings of an API agent.
+#[derive(Debug, Clone)]
+pub struct ApiAgentConfig {
+    /// Provider to call
+    pub provider: ApiProvider,
+    /// Model to use
+    pub model: String,
+    /// API key, if the provider needs one
+    pub api_key: Option<String>,
+    /// Base URL of the API, without a trailing slash
+    pub base_url: String,
+    /// Turns allowed before the run is abandoned
+    pub max_turns: u32,
+    /// Output tokens allowed per response
+    pub max_tokens: u32,
+}
+
+impl ApiAgentConfig {
+    /// Build the config for `spec` from environment variables.
+    ///
+    /// Reads the provider's API key and base URL variables, `RALPH_API_MODEL`
+    /// when the spec names no model, and `RALPH_API_MAX_TURNS`.
+    pub fn from_spec(spec: &ApiAgentSpec) -> ApiAgentResult<Self> {
+        let provider = spec.provider;
+        let api_key = match provider.api_key_env() {
+            Some(env) => Some(
+                std::env::var(env)
+                    .ok()
+                    .filter(|key| !key.is_empty())
+                    .ok_or_else(|| ApiAgentError::Config(format!("{} is not set", env)))?,
+            ),
+            None => None,
+        };
+        let model = spec
+            .model
+            .clone()
+            .or_else(|| std::env::var("RALPH_API_MODEL").ok())
+            .unwrap_or_else(|| provider.default_model().to_string());
+        let mut base_url = std::env::var(provider.base_url_env())
+            .unwrap_or_else(|_| provider.default_base_url().to_string())
+            .trim_end_matches('/')
+            .to_string();
+        // OLLAMA_HOST is commonly set as a bare host:port
+        if !base_url.contains("://") {
+            base_url = format!("http://{}", base_url);
+        }
+        let max_turns = std::env::var("RALPH_API_MAX_TURNS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MAX_TURNS);
+        Ok(Self {
+            provider,
+            model,
+            api_key,
+            base_url,
+            max_turns,
+            max_tokens: DEFAULT_MAX_TOKENS,
+        })
+    }
+}
+
+/// Outcome of an API agent run.
+#[derive(Debug, Clone, Default)]
+pub struct ApiAgentRun {
+    /// Text the model replied with and the tool calls it made, line by line
+    pub output: Vec<String>,
+    /// Input tokens used across all turns
+    pub input_tokens: u64,
+    /// Output tokens used across all turns
+    pub output_tokens: u64,
+    /// Number of requests made
+    pub turns: u32,
+}
+
+/// A tool call requested by the model.
+#[derive(Debug, Clone, PartialEq)]
+struct ToolCall {
+    id: String,
+    name: String,
+    input: Value,
+}
+
+/// One response of the model.
+#[derive(Debug, Clone, PartialEq)]
+struct Reply {
+    /// Message to append to the conversation
+    message: Value,
+    text: Vec<String>,
+    tool_calls: Vec<ToolCall>,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+/// Agent implementing stories through a provider API.
+pub struct ApiAgent {
+    client: Client,
+    config: ApiAgentConfig,
+}
+
+impl ApiAgent {
+    /// Create an agent with the given config.
+    pub fn new(config: ApiAgentConfig) -> ApiAgentResult<Self> {
+        let client = Client::builder()
+            .build()
+            .map_err(|e| ApiAgentError::Config(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self { client, config })
+    }
+
+    /// Implement `prompt` in `project_root`, passing each line of output to
+    /// `on_output` as it is produced.
+    pub async fn run(
+        &self,
+        prompt: &str,
+        project_root: &Path,
+        mut on_output: impl FnMut(&str),
+    ) -> ApiAgentResult<ApiAgentRun> {
+        let provider = self.config.provider;
+        let mut messages = vec![json!({"role": "user", "content": prompt})];
+        let mut run = ApiAgentRun::default();
+        let mut emit = |run: &mut ApiAgentRun, line: String| {
+            on_output(&line);
+            run.output.push(line);
+        };
+
+        while run.turns < self.config.max_turns {
+            let reply = self.send(&messages).await?;
+            run.turns += 1;
+            run.input_tokens += reply.input_tokens;
+            run.output_tokens += reply.output_tokens;
+            for line in reply.text.iter().flat_map(|text| text.lines()) {
+                emit(&mut run, line.to_string());
+            }
+            messages.push(reply.message);
+            if reply.tool_calls.is_empty() {
+                return Ok(run);
+            }
+
+            let mut results = Vec::new();
+            for call in reply.tool_calls {
+                let path = call.input.get("path").and_then(Value::as_str).unwrap_or("");
+                emit(&mut run, format!("[tool] {} {}", call.name, path));
+                let result = execute_tool(project_root, &call.name, &call.input);
+                if let Err(ref e) = result {
+                    emit(&mut run, format!("[tool] {} failed: {}", call.name, e));
+                }
+                results.push((call, result));
+            }
+            messages.extend(tool_result_messages(provider, &results));
+        }
+        Err(ApiAgentError::TurnLimit(self.config.max_turns))
+    }
+
+    /// Send the conversation so far and parse the model's reply.
+    async fn send(&self, messages: &[Value]) -> ApiAgentResult<Reply> {
+        let provider = self.config.provider;
+        let request = match provider {
+            ApiProvider::Anthropic => self
+                .client
+                .post(format!("{}/v1/messages", self.config.base_url))
+                .header(
+                    "x-api-key",
+                    self.config.api_key.as_deref().unwrap_or_default(),
+                )
+                .header("anthropic-version", ANTHROPIC_VERSION),
+            ApiProvider::OpenAi => self
+                .client
+                .post(format!("{}/chat/completions", self.config.base_url)),
+            ApiProvider::Ollama => self
+                .client
+                .post(format!("{}/v1/chat/completions", self.config.base_url)),
+        };
+        let request = match (provider, &self.config.api_key) {
+            (ApiProvider::Anthropic, _) | (_, None) => request,
+            (_, Some(key)) => request.bearer_auth(key),
+        };
+        let response = request
+            .json(&request_body(&self.config, messages))
+            .send()
+            .await
+            .map_err(|e| ApiAgentError::Http(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(ApiAgentError::Api {
+                provider,
+                status: status.as_u16(),
+                message,
+            });
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| ApiAgentError::Http(format!("Failed to read response: {}", e)))?;
+        parse_reply(provider, &body)
+    }
+}
+
+/// Request body sending `messages` with the file tools.
+fn request_body(config: &ApiAgentConfig, messages: &[Value]) -> Value {
+    let tools = tool_definitions();
+    match config.provider {
+        ApiProvider::Anthropic => json!({
+            "model": config.model,
+            "max_tokens": config.max_tokens,
+            "system": SYSTEM_PROMPT,
+            "messages": messages,
+            "tools": tools
+                .iter()
+                .map(|tool| json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "input_schema": tool.schema,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        ApiProvider::OpenAi | ApiProvider::Ollama => {
+            let mut all_messages = vec![json!({"role": "system", "content": SYSTEM_PROMPT})];
+            all_messages.extend(messages.iter().cloned());
+            // Ollama's compatibility layer predates `max_completion_tokens`
+            let max_tokens_key = match config.provider {
+                ApiProvider::Ollama => "max_tokens",
+                _ => "max_completion_tokens",
+            };
+            json!({
+                "model": config.model,
+                max_tokens_key: config.max_tokens,
+                "messages": all_messages,
+                "tools": tools
+                    .iter()
+                    .map(|tool| json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description,
+                            "parameters": tool.schema,
+                        },
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        }
+    }
+}
+
+/// Parse a response body into the model's reply.
+fn parse_reply(provider: ApiProvider, body: &Value) -> ApiAgentResult<Reply> {
+    let usage = |input: &str, output: &str| {
+        let count = |key: &str| body["usage"][key].as_u64().unwrap_or(0);
+        (count(input), count(output))
+    };
+    match provider {
+        ApiProvider::Anthropic => {
+            let content = body["content"]
+                .as_array()
+                .ok_or_else(|| ApiAgentError::Protocol("response has no content".to_string()))?;
+            let mut text = Vec::new();
+            let mut tool_calls = Vec::new();
+            for block in content {
+                match block["type"].as_str() {
+                    Some("text") => text.extend(block["text"].as_str().map(String::from)),
+                    Some("tool_use") => tool_calls.push(ToolCall {
+                        id: block["id"].as_str().unwrap_or_default().to_string(),
+                        name: block["name"].as_str().unwrap_or_default().to_string(),
+                        input: block["input"].clone(),
+                    }),
+                    _ => {}
+                }
+            }
+            let (input_tokens, output_tokens) = usage("input_tokens", "output_tokens");
+            Ok(Reply {
+                message: json!({"role": "assistant", "content": content}),
+                text,
+                tool_calls,
+                input_tokens,
+                output_tokens,
+            })
+        }
+        ApiProvider::OpenAi | ApiProvider::Ollama => {
+            let message = &body["choices"][0]["message"];
+            if !message.is_object() {
+                return Err(ApiAgentError::Protocol(
+                    "response has no message".to_string(),
+                ));
+            }
+            let tool_calls = message["tool_calls"]
+                .as_array()
+                .map(|calls| {
+                    calls
+                        .iter()
+                        .map(|call| ToolCall {
+                            id: call["id"].as_str().unwrap_or_default().to_string(),
+                            name: call["function"]["name"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                            // Arguments arrive as a JSON string; a malformed one
+                            // becomes a tool error the model can correct
+                            input: call["function"]["arguments"]
+                                .as_str()
+                                .and_then(|arguments| serde_json::from_str(arguments).ok())
+                                .unwrap_or(Value::Null),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let (input_tokens, output_tokens) = usage("prompt_tokens", "completion_tokens");
+            Ok(Reply {
+                message: message.clone(),
+                text: message["content"]
+                    .as_str()
+                    .map(String::from)
+                    .into_iter()
+                    .collect(),
+                tool_calls,
+                input_tokens,
+                output_tokens,
+            })
+        }
+    }
+}
+
+/// Messages reporting the results of tool calls back to the model.
+fn tool_result_messages(
+    provider: ApiProvider,
+    results: &[(ToolCall, Result<String, String>)],
+) -> Vec<Value> {
+    match provider {
+        ApiProvider::Anthropic => vec![json!({
+            "role": "user",
+            "content": results
+                .iter()
+                .map(|(call, result)| json!({
+                    "type": "tool_result",
+                    "tool_use_id": call.id,
+                    "content": result.as_ref().unwrap_or_else(|e| e),
+                    "is_error": result.is_err(),
+                }))
+                .collect::<Vec<_>>(),
+        })],
+        ApiProvider::OpenAi | ApiProvider::Ollama => results
+            .iter()
+            .map(|(call, result)| {
+                let content = match result {
+                    Ok(output) => output.clone(),
+                    Err(e) => format!("Error: {}", e),
+                };
+                json!({"role": "tool", "tool_call_id": call.id, "content": content})
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_anthropic_reply() {
+        let body = json!({
+            "content": [
+                {"type": "text", "text": "Reading the file"},
+                {"type": "tool_use", "id": "toolu_1", "name": "read_file", "input": {"path": "src/lib.rs"}}
+            ],
+            "stop_reason": "tool_use",
+            "usage": {"input_tokens": 120, "output_tokens": 30}
+        });
+        let reply = parse_reply(ApiProvider::Anthropic, &body).unwrap();
+        assert_eq!(reply.text, vec!["Reading the file"]);
+        assert_eq!(reply.tool_calls[0].name, "read_file");
+        assert_eq!(reply.tool_calls[0].input["path"], "src/lib.rs");
+        assert_eq!((reply.input_tokens, reply.output_tokens), (120, 30));
+        assert_eq!(reply.message["role"], "assistant");
+
+        let results = vec![(reply.tool_calls[0].clone(), Err("not found".to_string()))];
+        let messages = tool_result_messages(ApiProvider::Anthropic, &results);
+        assert_eq!(messages[0]["content"][0]["tool_use_id"], "toolu_1");
+        assert_eq!(messages[0]["content"][0]["is_error"], true);
+    }
+
+    #[test]
+    fn test_parse_openai_reply() {
+        let body = json!({
+            "choices": [{"message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "write_file", "arguments": "{\"path\":\"a.txt\",\"content\":\"hi\"}"}
+                }]
+            }}],
+            "usage": {"prompt_tokens": 200, "completion_tokens": 40, "total_tokens": 240}
+        });
+        let reply = parse_reply(ApiProvider::OpenAi, &body).unwrap();
+        assert!(reply.text.is_empty());
+        assert_eq!(reply.tool_calls[0].input["content"], "hi");
+        assert_eq!((reply.input_tokens, reply.output_tokens), (200, 40));
+
+        let results = vec![(reply.tool_calls[0].clone(), Ok("Wrote 2 bytes".to_string()))];
+        let messages = tool_result_messages(ApiProvider::OpenAi, &results);
+        assert_eq!(messages[0]["role"], "tool");
+        assert_eq!(messages[0]["tool_call_id"], "call_1");
+
+        assert!(parse_reply(ApiProvider::OpenAi, &json!({"error": "bad"})).is_err());
+    }
+
+    #[test]
+    fn test_request_body_includes_tools() {
+        let config = ApiAgentConfig {
+            provider: ApiProvider::OpenAi,
+            model: "gpt-4.1".to_string(),
+            api_key: Some("key".to_string()),
+            base_url: "http://localhost".to_string(),
+            max_turns: 5,
+            max_tokens: 100,
+        };
+        let messages = vec![json!({"role": "user", "content": "Implement US-001"})];
+        let body = request_body(&config, &messages);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "Implement US-001");
+        assert_eq!(body["tools"][0]["function"]["name"], "list_files");
+
+        let config = ApiAgentConfig {
+            provider: ApiProvider::Anthropic,
+            ..config
+        };
+        let body = request_body(&config, &messages);
+        assert_eq!(body["system"], SYSTEM_PROMPT);
+        assert_eq!(body["tools"][1]["name"], "read_file");
+
+        let config = ApiAgentConfig {
+            provider: ApiProvider::Ollama,
+            api_key: None,
+            ..config
+        };
+        let body = request_body(&config, &messages);
+        assert_eq!(body["max_tokens"], 100);
+        assert_eq!(body["tools"][0]["type"], "function");
+    }
+
+    #[test]
+    fn test_ollama_config_needs_no_key() {
+        let spec = ApiAgentSpec::parse("api:ollama").unwrap().unwrap();
+        let config = ApiAgentConfig::from_spec(&spec).unwrap();
+        assert_eq!(config.api_key, None);
+        assert!(config.base_url.starts_with("http"));
+    }
+}
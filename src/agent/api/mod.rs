@@ -0,0 +1,230 @@
+//! Direct provider API backend.
+//!
+//! `--agent api:anthropic` or `--agent api:openai`, optionally naming a model
+//! (`api:openai:gpt-4.1`), implements stories by calling the provider's API
+//! with a small set of file tools instead of shelling out to an agent CLI.
+//! `--agent api:ollama` does the same against a local Ollama server, needing
+//! no API key or network access.
+//! The client is only compiled with the `api-agent` feature; without it an
+//! `api:` agent fails with a message saying so.
+
+#[cfg(feature = "api-agent")]
+mod client;
+#[cfg(feature = "api-agent")]
+mod tools;
+
+#[cfg(feature = "api-agent")]
+pub use client::{ApiAgent, ApiAgentConfig, ApiAgentError, ApiAgentResult, ApiAgentRun};
+
+/// Prefix of agent commands that select an API backend.
+pub const API_AGENT_PREFIX: &str = "api:";
+
+/// Provider an API backend talks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiProvider {
+    /// Anthropic Messages API
+    Anthropic,
+    /// OpenAI Chat Completions API (or a compatible server)
+    OpenAi,
+    /// A local Ollama server, through its OpenAI-compatible API
+    Ollama,
+}
+
+impl ApiProvider {
+    /// Every provider, in the order they are tried when detecting an agent.
+    pub const ALL: [ApiProvider; 3] = [
+        ApiProvider::Anthropic,
+        ApiProvider::OpenAi,
+        ApiProvider::Ollama,
+    ];
+
+    /// Parse a provider name as used in `api:<provider>`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "anthropic" | "claude" => Some(ApiProvider::Anthropic),
+            "openai" => Some(ApiProvider::OpenAi),
+            "ollama" => Some(ApiProvider::Ollama),
+            _ => None,
+        }
+    }
+
+    /// Name used in agent commands and token usage reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApiProvider::Anthropic => "anthropic",
+            ApiProvider::OpenAi => "openai",
+            ApiProvider::Ollama => "ollama",
+        }
+    }
+
+    /// Environment variable holding the API key, or `None` for providers
+    /// that need none.
+    pub fn api_key_env(&self) -> Option<&'static str> {
+        match self {
+            ApiProvider::Anthropic => Some("ANTHROPIC_API_KEY"),
+            ApiProvider::OpenAi => Some("OPENAI_API_KEY"),
+            ApiProvider::Ollama => None,
+        }
+    }
+
+    /// Whether the model runs on this machine, costing nothing per token.
+    pub fn is_local(&self) -> bool {
+        matches!(self, ApiProvider::Ollama)
+    }
+
+    /// Environment variable overriding the API base URL.
+    pub fn base_url_env(&self) -> &'static str {
+        match self {
+            ApiProvider::Anthropic => "ANTHROPIC_BASE_URL",
+            ApiProvider::OpenAi => "OPENAI_BASE_URL",
+            ApiProvider::Ollama => "OLLAMA_HOST",
+        }
+    }
+
+    /// Base URL used when none is configured.
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            ApiProvider::Anthropic => "https://api.anthropic.com",
+            ApiProvider::OpenAi => "https://api.openai.com/v1",
+            ApiProvider::Ollama => "http://localhost:11434",
+        }
+    }
+
+    /// Model used when the agent command and `RALPH_API_MODEL` name none.
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            ApiProvider::Anthropic => "claude-sonnet-4-5",
+            ApiProvider::OpenAi => "gpt-4.1",
+            ApiProvider::Ollama => "qwen2.5-coder:7b",
+        }
+    }
+}
+
+impl std::fmt::Display for ApiProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiProvider::Anthropic => write!(f, "Anthropic"),
+            ApiProvider::OpenAi => write!(f, "OpenAI"),
+            ApiProvider::Ollama => write!(f, "Ollama"),
+        }
+    }
+}
+
+/// An API backend selected by an agent command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiAgentSpec {
+    /// Provider to call
+    pub provider: ApiProvider,
+    /// Model named in the agent command, if any
+    pub model: Option<String>,
+}
+
+impl ApiAgentSpec {
+    /// Parse an agent command of the form `api:<provider>[:<model>]`.
+    ///
+    /// The model may itself contain colons, as Ollama tags do
+    /// (`api:ollama:qwen2.5-coder:7b`).
+    ///
+    /// Returns `Ok(None)` for commands that do not select an API backend and
+    /// an error for an unknown provider.
+    pub fn parse(agent_command: &str) -> Result<Option<Self>, String> {
+        let Some(rest) = agent_command.strip_prefix(API_AGENT_PREFIX) else {
+            return Ok(None);
+        };
+        let (provider, model) = match rest.split_once(':') {
+            Some((provider, model)) => (provider, Some(model.to_string())),
+            None => (rest, None),
+        };
+        let provider = ApiProvider::parse(provider).ok_or_else(|| {
+            format!(
+                "Unknown API provider '{}' (expected anthropic, openai, or ollama)",
+                provider
+            )
+        })?;
+        Ok(Some(Self {
+            provider,
+            model: model.filter(|model| !model.is_empty()),
+        }))
+    }
+
+    /// The API backend to fall back to when no agent CLI is installed: the
+    /// first provider whose API key is set.
+    ///
+    /// Local providers are never picked, as a running server cannot be
+    /// assumed. Always `None` unless built with the `api-agent` feature.
+    pub fn from_env() -> Option<Self> {
+        if !cfg!(feature = "api-agent") {
+            return None;
+        }
+        ApiProvider::ALL
+            .into_iter()
+            .find(|provider| {
+                provider
+                    .api_key_env()
+                    .is_some_and(|env| std::env::var(env).is_ok_and(|key| !key.is_empty()))
+            })
+            .map(|provider| Self {
+                provider,
+                model: None,
+            })
+    }
+
+    /// The agent command selecting this backend.
+    pub fn command(&self) -> String {
+        match self.model {
+            Some(ref model) => format!("{}{}:{}", API_AGENT_PREFIX, self.provider.name(), model),
+            None => format!("{}{}", API_AGENT_PREFIX, self.provider.name()),
+        }
+    }
+
+    /// This spec with `model` in place of the one it names, e.g. a story's
+    /// own model choice.
+    pub fn with_model(self, model: Option<&str>) -> Self {
+        match model {
+            Some(model) => Self {
+                model: Some(model.to_string()),
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(ApiAgentSpec::parse("claude"), Ok(None));
+        assert_eq!(
+            ApiAgentSpec::parse("api:anthropic"),
+            Ok(Some(ApiAgentSpec {
+                provider: ApiProvider::Anthropic,
+                model: None,
+            }))
+        );
+
+        let spec = ApiAgentSpec::parse("api:openai:gpt-4.1").unwrap().unwrap();
+        assert_eq!(spec.provider, ApiProvider::OpenAi);
+        assert_eq!(spec.model.as_deref(), Some("gpt-4.1"));
+        assert_eq!(spec.command(), "api:openai:gpt-4.1");
+
+        assert!(ApiAgentSpec::parse("api:gemini").is_err());
+    }
+
+    #[test]
+    fn test_parse_ollama_spec() {
+        let spec = ApiAgentSpec::parse("api:ollama:qwen2.5-coder:7b")
+            .unwrap()
+            .unwrap();
+        assert_eq!(spec.provider, ApiProvider::Ollama);
+        assert_eq!(spec.model.as_deref(), Some("qwen2.5-coder:7b"));
+        assert!(spec.provider.is_local());
+        assert_eq!(spec.provider.api_key_env(), None);
+
+        let spec = spec.with_model(Some("llama3.1:8b"));
+        assert_eq!(spec.command(), "api:ollama:llama3.1:8b");
+        assert_eq!(spec.clone().with_model(None), spec);
+    }
+}
@@ -0,0 +1,287 @@
+//! File tools offered to API agents.
+//!
+//! Every path is relative to the project root; absolute paths and paths
+//! leaving the root are refused. Tool failures are returned as text for the
+//! model to act on rather than ending the run.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use ignore::WalkBuilder;
+use serde_json::{json, Value};
+
+/// Largest file `read_file` returns, in bytes.
+const MAX_READ_BYTES: u64 = 256 * 1024;
+
+/// Most entries `list_files` returns.
+const MAX_LISTED_FILES: usize = 500;
+
+/// A tool as described to the model.
+pub(super) struct ToolDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema of the tool's input
+    pub schema: Value,
+}
+
+/// The tools an API agent may call.
+pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "list_files",
+            description: "List files under a directory of the project, skipping ignored files.",
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "Directory relative to the project root (default: the root)"}
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "read_file",
+            description: "Read a file of the project.",
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File path relative to the project root"}
+                },
+                "required": ["path"]
+            }),
+        },
+        ToolDefinition {
+            name: "write_file",
+            description: "Create or overwrite a file of the project with the given content.",
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File path relative to the project root"},
+                    "content": {"type": "string", "description": "Complete new file content"}
+                },
+                "required": ["path", "content"]
+            }),
+        },
+        ToolDefinition {
+            name: "replace_in_file",
+            description: "Replace the single occurrence of old_text in a file with new_text.",
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File path relative to the project root"},
+                    "old_text": {"type": "string", "description": "Exact text to replace; must occur once"},
+                    "new_text": {"type": "string", "description": "Replacement text"}
+                },
+                "required": ["path", "old_text", "new_text"]
+            }),
+        },
+    ]
+}
+
+/// Run the tool `name` with `input`, returning its output or an error to
+/// report back to the model.
+pub(super) fn execute_tool(
+    project_root: &Path,
+    name: &str,
+    input: &Value,
+) -> Result<String, String> {
+    let arg = |key: &str| -> Result<&str, String> {
+        input
+            .get(key)
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("missing string argument '{}'", key))
+    };
+    match name {
+        "list_files" => {
+            let path = input.get("path").and_then(Value::as_str).unwrap_or(".");
+            list_files(project_root, path)
+        }
+        "read_file" => {
+            let path = resolve(project_root, arg("path")?)?;
+            let size = fs::metadata(&path).map_err(|e| e.to_string())?.len();
+            if size > MAX_READ_BYTES {
+                return Err(format!(
+                    "file is {} bytes, larger than the {} byte limit",
+                    size, MAX_READ_BYTES
+                ));
+            }
+            fs::read_to_string(&path).map_err(|e| e.to_string())
+        }
+        "write_file" => {
+            let path = resolve(project_root, arg("path")?)?;
+            let content = arg("content")?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(&path, content).map_err(|e| e.to_string())?;
+            Ok(format!("Wrote {} bytes to {}", content.len(), arg("path")?))
+        }
+        "replace_in_file" => {
+            let path = resolve(project_root, arg("path")?)?;
+            let old_text = arg("old_text")?;
+            let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            match content.matches(old_text).count() {
+                1 => {
+                    fs::write(&path, content.replacen(old_text, arg("new_text")?, 1))
+                        .map_err(|e| e.to_string())?;
+                    Ok(format!("Updated {}", arg("path")?))
+                }
+                0 => Err("old_text was not found".to_string()),
+                n => Err(format!(
+                    "old_text occurs {} times; include more context to make it unique",
+                    n
+                )),
+            }
+        }
+        _ => Err(format!("unknown tool '{}'", name)),
+    }
+}
+
+/// Resolve a path given by the model against the project root.
+///
+/// Symlinks are followed: the deepest part of the path that already exists
+/// must still be inside the project once resolved.
+fn resolve(project_root: &Path, path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    let escapes = relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return Err(format!("path '{}' must stay inside the project", path));
+    }
+    let resolved = project_root.join(relative);
+    let root = fs::canonicalize(project_root).map_err(|e| e.to_string())?;
+    let existing = resolved
+        .ancestors()
+        .find(|ancestor| ancestor.symlink_metadata().is_ok())
+        .unwrap_or(project_root);
+    // A dangling symlink cannot be resolved and is refused with the rest
+    let inside = fs::canonicalize(existing).is_ok_and(|existing| existing.starts_with(&root));
+    if !inside {
+        return Err(format!("path '{}' must stay inside the project", path));
+    }
+    Ok(resolved)
+}
+
+/// Files under `path`, relative to the project root, one per line.
+fn list_files(project_root: &Path, path: &str) -> Result<String, String> {
+    let dir = resolve(project_root, path)?;
+    let mut files: Vec<String> = WalkBuilder::new(&dir)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(project_root)
+                .ok()
+                .map(|relative| relative.display().to_string())
+        })
+        .collect();
+    files.sort();
+    let total = files.len();
+    files.truncate(MAX_LISTED_FILES);
+    let mut listing = files.join("\n");
+    if total > MAX_LISTED_FILES {
+        listing.push_str(&format!(
+            "\n... {} more files; list a subdirectory",
+            total - MAX_LISTED_FILES
+        ));
+    }
+    Ok(listing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_read_and_replace() {
+        let root = TempDir::new().unwrap();
+        let call = |name: &str, input: Value| execute_tool(root.path(), name, &input);
+
+        call(
+            "write_file",
+            json!({"path": "src/lib.rs", "content": "fn a() {}\nfn b() {}\n"}),
+        )
+        .unwrap();
+        call(
+            "replace_in_file",
+            json!({"path": "src/lib.rs", "old_text": "fn b() {}", "new_text": "fn c() {}"}),
+        )
+        .unwrap();
+        assert_eq!(
+            call("read_file", json!({"path": "src/lib.rs"})).unwrap(),
+            "fn a() {}\nfn c() {}\n"
+        );
+        assert!(call(
+            "replace_in_file",
+            json!({"path": "src/lib.rs", "old_text": "fn", "new_text": "pub fn"})
+        )
+        .unwrap_err()
+        .contains("occurs 2 times"));
+        assert_eq!(call("list_files", json!({})).unwrap(), "src/lib.rs");
+    }
+
+    #[test]
+    fn test_paths_stay_inside_project() {
+        let root = TempDir::new().unwrap();
+        for path in ["../outside.txt", "/etc/passwd", "src/../../x"] {
+            let result = execute_tool(
+                root.path(),
+                "write_file",
+                &json!({"path": path, "content": "x"}),
+            );
+            assert!(result.is_err(), "{} should be refused", path);
+        }
+        assert!(execute_tool(root.path(), "delete_file", &json!({})).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_stay_inside_project() {
+        let root = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.path().join("linked")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("missing.txt"),
+            root.path().join("dangling.txt"),
+        )
+        .unwrap();
+        fs::create_dir(root.path().join("src")).unwrap();
+        std::os::unix::fs::symlink(root.path().join("src"), root.path().join("code")).unwrap();
+        let call = |name: &str, input: Value| execute_tool(root.path(), name, &input);
+
+        assert!(call("read_file", json!({"path": "linked/secret.txt"})).is_err());
+        assert!(call(
+            "write_file",
+            json!({"path": "linked/new/file.txt", "content": "x"})
+        )
+        .is_err());
+        assert!(call(
+            "replace_in_file",
+            json!({"path": "linked/secret.txt", "old_text": "secret", "new_text": "x"})
+        )
+        .is_err());
+        assert!(call(
+            "write_file",
+            json!({"path": "dangling.txt", "content": "x"})
+        )
+        .is_err());
+        assert!(!outside.path().join("new").exists());
+        assert!(!outside.path().join("missing.txt").exists());
+
+        // Links that stay inside the project still work
+        call(
+            "write_file",
+            json!({"path": "code/lib.rs", "content": "fn a() {}\n"}),
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(root.path().join("src/lib.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+    }
+}
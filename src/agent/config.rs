@@ -0,0 +1,348 @@
+//! Per-agent invocation settings.
+//!
+//! `.ralph/agents.toml` holds one section per agent CLI, keyed by program
+//! name (`claude`, `codex`, `amp`, `aider`) or, for a custom agent, by its
+//! full command:
+//!
+//! ```toml
+//! [claude]
+//! model = "claude-sonnet-4-5"
+//! permission_mode = "auto"
+//! flags = ["--max-turns", "40"]
+//! env = { CLAUDE_CODE_MAX_OUTPUT_TOKENS = "32000" }
+//!
+//! [codex]
+//! permission_mode = "bypass"
+//! ```
+//!
+//! The executor resolves the section for the agent in use when it builds the
+//! invocation, so flags such as `--dangerously-skip-permissions` are passed
+//! only where a project allows them.
+//!
+//! `[[assign]]` rules pick a different agent or model for some stories, e.g.
+//! a cheap model for documentation and a strong one for core refactors. The
+//! first rule matching a story applies:
+//!
+//! ```toml
+//! [[assign]]
+//! target_files = ["docs/**", "*.md"]
+//! model = "claude-haiku-4-5"
+//!
+//! [[assign]]
+//! stories = ["CORE-*"]
+//! heavy = true
+//! agent = "codex"
+//! model = "gpt-5-codex"
+//! ```
+//!
+//! A `[review]` section adds a reviewer pass: after the implementing agent
+//! finishes, a second agent critiques its diff against the story's
+//! acceptance criteria before the quality gates run. See [`super::review`].
+//!
+//! ```toml
+//! [review]
+//! agent = "codex"
+//! require_approval = true
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::probe::agent_program;
+
+/// File, relative to the project root, holding agent settings.
+pub const AGENTS_CONFIG_FILE: &str = ".ralph/agents.toml";
+
+/// Errors from loading agent settings.
+#[derive(Debug, Error)]
+pub enum AgentConfigError {
+    /// The settings file could not be read
+    #[error("Failed to read {path}: {message}")]
+    Read { path: PathBuf, message: String },
+    /// The settings file is not valid
+    #[error("Invalid agent settings in {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+}
+
+/// How much an agent may do without asking for approval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionMode {
+    /// Pass no approval-skipping flags; the agent's own settings decide
+    Default,
+    /// Edit and run commands without prompting (`--dangerously-skip-permissions`,
+    /// `--full-auto`, `--yes-always`)
+    #[default]
+    Auto,
+    /// Additionally drop the agent's own sandbox where it has one
+    /// (`--dangerously-bypass-approvals-and-sandbox` for Codex)
+    Bypass,
+}
+
+/// Settings for one agent CLI.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentSettings {
+    /// Model passed with `--model` (overrides `CODEX_MODEL`/`AIDER_MODEL`)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Approval mode (default: auto, or bypass for Codex with
+    /// `RALPH_CODEX_DANGEROUS` set)
+    #[serde(default)]
+    pub permission_mode: Option<PermissionMode>,
+    /// Extra arguments placed before the prompt, passed as given
+    #[serde(default)]
+    pub flags: Vec<String>,
+    /// Environment variables set for the agent process
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Continue the agent's conversation across iterations of a story where
+    /// the agent supports it (default: true)
+    #[serde(default)]
+    pub reuse_session: Option<bool>,
+}
+
+/// A rule assigning an agent or model to the stories it matches.
+///
+/// Every criterion given must match; a rule without criteria matches every
+/// story.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentAssignment {
+    /// Story ID patterns (e.g. "DOC-*")
+    #[serde(default)]
+    pub stories: Vec<String>,
+    /// File patterns; matches when any of the story's target files matches
+    #[serde(default)]
+    pub target_files: Vec<String>,
+    /// Match only heavy (or only light) stories
+    #[serde(default)]
+    pub heavy: Option<bool>,
+    /// Agent command for matching stories (default: the run's agent)
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Model for matching stories (overrides the agent's `model`)
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl AgentAssignment {
+    /// Whether this rule applies to a story.
+    pub fn matches(&self, story_id: &str, target_files: &[String], heavy: bool) -> bool {
+        let matches_any = |patterns: &[String], value: &str| {
+            patterns.iter().any(|pattern| {
+                pattern == value || Pattern::new(pattern).is_ok_and(|p| p.matches(value))
+            })
+        };
+        (self.stories.is_empty() || matches_any(&self.stories, story_id))
+            && (self.target_files.is_empty()
+                || target_files
+                    .iter()
+                    .any(|file| matches_any(&self.target_files, file)))
+            && self.heavy.map_or(true, |wanted| wanted == heavy)
+    }
+}
+
+/// Settings of the reviewer pass run before the quality gates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReviewSettings {
+    /// Agent command of the reviewer (default: the story's agent)
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Model for the reviewer (overrides the reviewer agent's `model`)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Treat a review that does not approve like a failed gate, sending the
+    /// findings back to the implementing agent (default: record only)
+    #[serde(default)]
+    pub require_approval: bool,
+}
+
+/// The agent and model a story runs with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoryAgent {
+    /// Agent command
+    pub agent_command: String,
+    /// Model overriding the agent's settings, if one was assigned
+    pub model: Option<String>,
+}
+
+impl StoryAgent {
+    /// Label attributing results to this agent, e.g. "claude (claude-haiku-4-5)".
+    pub fn label(&self) -> String {
+        match self.model {
+            Some(ref model) => format!("{} ({})", self.agent_command, model),
+            None => self.agent_command.clone(),
+        }
+    }
+}
+
+/// Agent settings of a project, by agent, and the rules assigning agents to
+/// stories.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AgentsConfig {
+    /// Rules assigning agents and models to stories, first match wins
+    #[serde(default)]
+    assign: Vec<AgentAssignment>,
+    /// Reviewer pass before the quality gates, if enabled
+    #[serde(default)]
+    review: Option<ReviewSettings>,
+    #[serde(flatten)]
+    agents: HashMap<String, AgentSettings>,
+}
+
+impl AgentsConfig {
+    /// Load `<project_root>/.ralph/agents.toml`; a missing file means no
+    /// settings.
+    pub fn load(project_root: &Path) -> Result<Self, AgentConfigError> {
+        let path = project_root.join(AGENTS_CONFIG_FILE);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(AgentConfigError::Read {
+                    path,
+                    message: e.to_string(),
+                })
+            }
+        };
+        toml::from_str(&content).map_err(|e| AgentConfigError::Parse {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Settings for the agent run by `agent_command`.
+    pub fn for_agent(&self, agent_command: &str) -> AgentSettings {
+        let key = agent_program(agent_command).unwrap_or(agent_command);
+        self.agents.get(key).cloned().unwrap_or_default()
+    }
+
+    /// Agent and model for a story, from the first matching `[[assign]]`
+    /// rule, falling back to `default_agent`.
+    pub fn assign(
+        &self,
+        default_agent: &str,
+        story_id: &str,
+        target_files: &[String],
+        heavy: bool,
+    ) -> StoryAgent {
+        let rule = self
+            .assign
+            .iter()
+            .find(|rule| rule.matches(story_id, target_files, heavy));
+        StoryAgent {
+            agent_command: rule
+                .and_then(|rule| rule.agent.clone())
+                .unwrap_or_else(|| default_agent.to_string()),
+            model: rule.and_then(|rule| rule.model.clone()),
+        }
+    }
+
+    /// Settings of the reviewer pass, if one is configured.
+    pub fn review(&self) -> Option<&ReviewSettings> {
+        self.review.as_ref()
+    }
+
+    /// Agent commands named by `[[assign]]` rules and the `[review]` section.
+    pub fn assigned_agents(&self) -> Vec<String> {
+        let mut agents: Vec<String> = Vec::new();
+        let review_agent = self.review.as_ref().and_then(|review| review.agent.as_ref());
+        for agent in self
+            .assign
+            .iter()
+            .filter_map(|rule| rule.agent.as_ref())
+            .chain(review_agent)
+        {
+            if !agents.contains(agent) {
+                agents.push(agent.clone());
+            }
+        }
+        agents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_agent_settings() {
+        let root = TempDir::new().unwrap();
+        assert_eq!(
+            AgentsConfig::load(root.path()).unwrap(),
+            AgentsConfig::default()
+        );
+
+        std::fs::create_dir_all(root.path().join(".ralph")).unwrap();
+        std::fs::write(
+            root.path().join(AGENTS_CONFIG_FILE),
+            r#"
+[claude]
+model = "claude-opus-4-1"
+permission_mode = "default"
+flags = ["--max-turns", "40"]
+env = { CLAUDE_CODE_MAX_OUTPUT_TOKENS = "32000" }
+reuse_session = false
+
+["./bin/my-agent.sh"]
+flags = ["--fast"]
+
+[[assign]]
+target_files = ["docs/**"]
+model = "claude-haiku-4-5"
+
+[[assign]]
+stories = ["CORE-*"]
+heavy = true
+agent = "codex"
+
+[review]
+agent = "./bin/reviewer.sh"
+require_approval = true
+"#,
+        )
+        .unwrap();
+        let config = AgentsConfig::load(root.path()).unwrap();
+
+        let claude = config.for_agent("/usr/local/bin/claude");
+        assert_eq!(claude.model.as_deref(), Some("claude-opus-4-1"));
+        assert_eq!(claude.permission_mode, Some(PermissionMode::Default));
+        assert_eq!(claude.flags, vec!["--max-turns", "40"]);
+        assert_eq!(claude.env["CLAUDE_CODE_MAX_OUTPUT_TOKENS"], "32000");
+        assert_eq!(claude.reuse_session, Some(false));
+        assert_eq!(config.for_agent("./bin/my-agent.sh").flags, vec!["--fast"]);
+        assert_eq!(config.for_agent("codex"), AgentSettings::default());
+
+        let docs = config.assign("claude", "US-001", &["docs/guide.md".to_string()], false);
+        assert_eq!(docs.agent_command, "claude");
+        assert_eq!(docs.label(), "claude (claude-haiku-4-5)");
+        let core = config.assign("claude", "CORE-7", &["src/lib.rs".to_string()], true);
+        assert_eq!(core.label(), "codex");
+        let light = config.assign("claude", "CORE-8", &["src/lib.rs".to_string()], false);
+        assert_eq!(light.label(), "claude");
+        assert_eq!(
+            config.assigned_agents(),
+            vec!["codex", "./bin/reviewer.sh"]
+        );
+        let review = config.review().unwrap();
+        assert_eq!(review.agent.as_deref(), Some("./bin/reviewer.sh"));
+        assert!(review.require_approval);
+
+        std::fs::write(
+            root.path().join(AGENTS_CONFIG_FILE),
+            "[codex]\npermission = \"bypass\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            AgentsConfig::load(root.path()),
+            Err(AgentConfigError::Parse { .. })
+        ));
+    }
+}
@@ -0,0 +1,374 @@
+//! Running agent CLIs inside a container.
+//!
+//! With an [`AgentContainer`] configured, each agent invocation becomes
+//! `<runtime> run` of the configured image with only the project directory
+//! (and the story's scratch directory) bind-mounted, so an autonomous agent
+//! cannot touch the rest of the host. Network access and resource limits are
+//! applied by the container runtime.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Stdio;
+use std::str::FromStr;
+
+use crate::resources::ResourceLimits;
+
+/// Host variables passed into agent containers unless configured otherwise,
+/// so agents can authenticate with their provider.
+pub const DEFAULT_AGENT_ENV: &[&str] = &[
+    "ANTHROPIC_API_KEY",
+    "ANTHROPIC_BASE_URL",
+    "OPENAI_API_KEY",
+    "OPENAI_BASE_URL",
+];
+
+/// Network access of an agent container.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AgentNetwork {
+    /// The runtime's default network, so the agent can reach its provider
+    #[default]
+    Default,
+    /// No network at all (only useful for agents needing no remote API)
+    None,
+    /// A named runtime network, e.g. one whose egress is limited to the
+    /// provider's API
+    Named(String),
+}
+
+impl FromStr for AgentNetwork {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "" => Err("network name must not be empty".to_string()),
+            "default" => Ok(AgentNetwork::Default),
+            "none" => Ok(AgentNetwork::None),
+            name => Ok(AgentNetwork::Named(name.to_string())),
+        }
+    }
+}
+
+/// Container an agent CLI runs in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentContainer {
+    /// Container runtime binary (e.g. "docker" or "podman")
+    pub runtime: String,
+    /// Image with the agent CLI installed
+    pub image: String,
+    /// Network access of the container
+    pub network: AgentNetwork,
+    /// Memory limit passed to the runtime (e.g. "8g")
+    pub memory: Option<String>,
+    /// CPU limit passed to the runtime (e.g. "2")
+    pub cpus: Option<String>,
+    /// Host environment variables passed through to the agent
+    pub env: Vec<String>,
+    /// Extra volume mounts in runtime syntax (e.g. "/home/me/.claude:/home/agent/.claude")
+    pub mounts: Vec<String>,
+}
+
+impl AgentContainer {
+    /// Run agents in `image` with Docker, the default network and the
+    /// provider credentials in [`DEFAULT_AGENT_ENV`].
+    pub fn new(image: impl Into<String>) -> Self {
+        Self {
+            runtime: "docker".to_string(),
+            image: image.into(),
+            network: AgentNetwork::Default,
+            memory: None,
+            cpus: None,
+            env: DEFAULT_AGENT_ENV
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+            mounts: Vec::new(),
+        }
+    }
+
+    /// Use `runtime` instead of Docker.
+    pub fn with_runtime(mut self, runtime: impl Into<String>) -> Self {
+        self.runtime = runtime.into();
+        self
+    }
+
+    /// Set the network access of the container.
+    pub fn with_network(mut self, network: AgentNetwork) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Set the memory limit (runtime syntax, e.g. "8g").
+    pub fn with_memory(mut self, memory: impl Into<String>) -> Self {
+        self.memory = Some(memory.into());
+        self
+    }
+
+    /// Set the CPU limit (runtime syntax, e.g. "2").
+    pub fn with_cpus(mut self, cpus: impl Into<String>) -> Self {
+        self.cpus = Some(cpus.into());
+        self
+    }
+
+    /// Pass these host variables through in addition to the defaults.
+    pub fn with_env(mut self, env: Vec<String>) -> Self {
+        for key in env {
+            if !self.env.contains(&key) {
+                self.env.push(key);
+            }
+        }
+        self
+    }
+
+    /// Add volume mounts in runtime syntax.
+    pub fn with_mounts(mut self, mounts: Vec<String>) -> Self {
+        self.mounts.extend(mounts);
+        self
+    }
+
+    /// Arguments for `<runtime> run ...` executing `program` with `args`.
+    ///
+    /// The working directory and every path in `shared` are mounted at the
+    /// same location, so paths in agent output match the host. Files are
+    /// written as the owner of the working directory rather than as root.
+    pub fn run_args(
+        &self,
+        run: &ContainerRun<'_>,
+        program: &str,
+        args: &[String],
+        limits: &ResourceLimits,
+    ) -> Vec<OsString> {
+        let mut out: Vec<OsString> = vec![
+            "run".into(),
+            "--rm".into(),
+            "--init".into(),
+            "--name".into(),
+            run.name.into(),
+        ];
+        match self.network {
+            AgentNetwork::Default => {}
+            AgentNetwork::None => out.extend(["--network".into(), "none".into()]),
+            AgentNetwork::Named(ref name) => out.extend(["--network".into(), name.into()]),
+        }
+        if let Some(ref memory) = self.memory {
+            out.extend(["--memory".into(), memory.into()]);
+        }
+        if let Some(ref cpus) = self.cpus {
+            out.extend(["--cpus".into(), cpus.into()]);
+        }
+        // Worker limits only fill in what the container config leaves unset
+        let mut limits = limits.clone();
+        if self.memory.is_some() {
+            limits.max_rss_mb = None;
+        }
+        out.extend(limits.container_args().into_iter().map(OsString::from));
+        out.extend(host_user_args(&self.runtime, run.workdir));
+        for path in std::iter::once(run.workdir).chain(run.shared.iter().copied()) {
+            out.extend(["--volume".into(), same_path_mount(path)]);
+        }
+        for mount in &self.mounts {
+            out.extend(["--volume".into(), mount.into()]);
+        }
+        out.extend(["--workdir".into(), run.workdir.as_os_str().to_os_string()]);
+        for key in &self.env {
+            out.extend(["--env".into(), key.into()]);
+        }
+        for (key, value) in &run.env {
+            out.extend(["--env".into(), format!("{}={}", key, value).into()]);
+        }
+        out.push(self.image.clone().into());
+        out.push(program.into());
+        out.extend(args.iter().map(OsString::from));
+        out
+    }
+
+    /// Handle removing the container `name` once dropped.
+    ///
+    /// Killing the runtime client does not always stop the container, so the
+    /// container is force-removed when the agent run ends for any reason.
+    pub fn guard(&self, name: impl Into<String>) -> ContainerGuard {
+        ContainerGuard {
+            runtime: self.runtime.clone(),
+            name: name.into(),
+        }
+    }
+}
+
+/// One agent invocation inside an [`AgentContainer`].
+#[derive(Debug, Clone)]
+pub struct ContainerRun<'a> {
+    /// Container name, unique per invocation
+    pub name: &'a str,
+    /// Directory the agent runs in (the project root)
+    pub workdir: &'a Path,
+    /// Further host paths the agent needs, mounted at the same location
+    pub shared: Vec<&'a Path>,
+    /// Variables set for the agent
+    pub env: Vec<(String, String)>,
+}
+
+/// Force-removes a container when dropped.
+#[derive(Debug)]
+pub struct ContainerGuard {
+    runtime: String,
+    name: String,
+}
+
+impl Drop for ContainerGuard {
+    fn drop(&mut self) {
+        // Usually the container is already gone (`--rm`); this only matters
+        // when the agent was killed on timeout or cancellation
+        let args = ["rm", "--force", self.name.as_str()];
+        if tokio::runtime::Handle::try_current().is_ok() {
+            // Not awaited: tokio reaps the process in the background
+            let _ = tokio::process::Command::new(&self.runtime)
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+        } else {
+            let _ = std::process::Command::new(&self.runtime)
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+/// Container name for an agent run of `story_id`, unique across iterations
+/// and concurrent Ralph processes.
+pub fn container_name(story_id: &str, iteration: u32) -> String {
+    let story: String = story_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("ralph-{}-{}-{}", story, iteration, std::process::id())
+}
+
+/// Arguments for `<runtime> run` running the container as the host user
+/// owning `workdir`, so files it writes there are not owned by root.
+pub fn host_user_args(runtime: &str, workdir: &Path) -> Vec<OsString> {
+    if runtime.contains("podman") {
+        // Rootless podman maps the invoking user into the container itself
+        return vec!["--userns".into(), "keep-id".into()];
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let Ok(metadata) = std::fs::metadata(workdir) {
+            return vec![
+                "--user".into(),
+                format!("{}:{}", metadata.uid(), metadata.gid()).into(),
+            ];
+        }
+    }
+    let _ = workdir;
+    Vec::new()
+}
+
+/// Volume argument mounting `path` at the same location inside the container.
+pub fn same_path_mount(path: &Path) -> OsString {
+    let mut mount = path.as_os_str().to_os_string();
+    mount.push(":");
+    mount.push(path.as_os_str());
+    mount
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_args() {
+        let container = AgentContainer::new("ghcr.io/acme/agents:1")
+            .with_runtime("podman")
+            .with_network("egress-anthropic".parse().unwrap())
+            .with_memory("8g")
+            .with_env(vec!["GH_TOKEN".to_string(), "OPENAI_API_KEY".to_string()])
+            .with_mounts(vec!["/home/me/.claude:/home/agent/.claude".to_string()]);
+        let run = ContainerRun {
+            name: "ralph-us-001-2-42",
+            workdir: Path::new("/work/proj"),
+            shared: vec![Path::new("/tmp/ralph-scratch-US-001")],
+            env: vec![(
+                "TMPDIR".to_string(),
+                "/tmp/ralph-scratch-US-001".to_string(),
+            )],
+        };
+        let limits = ResourceLimits {
+            max_rss_mb: Some(4096),
+            cpu_shares: Some(512),
+            scratch_quota_mb: None,
+        };
+        let args: Vec<String> = container
+            .run_args(
+                &run,
+                "claude",
+                &["--print".to_string(), "hi".to_string()],
+                &limits,
+            )
+            .into_iter()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "--rm",
+                "--init",
+                "--name",
+                "ralph-us-001-2-42",
+                "--network",
+                "egress-anthropic",
+                "--memory",
+                "8g",
+                "--cpu-shares",
+                "512",
+                "--userns",
+                "keep-id",
+                "--volume",
+                "/work/proj:/work/proj",
+                "--volume",
+                "/tmp/ralph-scratch-US-001:/tmp/ralph-scratch-US-001",
+                "--volume",
+                "/home/me/.claude:/home/agent/.claude",
+                "--workdir",
+                "/work/proj",
+                "--env",
+                "ANTHROPIC_API_KEY",
+                "--env",
+                "ANTHROPIC_BASE_URL",
+                "--env",
+                "OPENAI_API_KEY",
+                "--env",
+                "OPENAI_BASE_URL",
+                "--env",
+                "GH_TOKEN",
+                "--env",
+                "TMPDIR=/tmp/ralph-scratch-US-001",
+                "ghcr.io/acme/agents:1",
+                "claude",
+                "--print",
+                "hi",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_network_and_names() {
+        assert_eq!("default".parse(), Ok(AgentNetwork::Default));
+        assert_eq!("none".parse(), Ok(AgentNetwork::None));
+        assert!("".parse::<AgentNetwork>().is_err());
+        assert_eq!(
+            container_name("US/001", 3),
+            format!("ralph-us-001-3-{}", std::process::id())
+        );
+    }
+}
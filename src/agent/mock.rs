@@ -0,0 +1,382 @@
+//! Scripted mock agent for tests and demos.
+//!
+//! `--agent mock` implements stories without any agent CLI or API key: the
+//! mock streams scripted output, writes scripted files and fails when told
+//! to, so the scheduler, quality gates and UI can be exercised in CI. The
+//! script is read from `.ralph/mock-agent.toml`, or from the file named in
+//! the command (`mock:demo/agent.toml`):
+//!
+//! ```toml
+//! delay_ms = 300          # pause before each output line
+//! fail_rate = 0.2         # share of runs that fail
+//! seed = 7                # picks which runs fail
+//!
+//! [[responses]]
+//! story = "DOC-*"         # story ID pattern (default: any story)
+//! iteration = 1           # (default: any iteration)
+//! output = ["Writing docs for {story}"]
+//! files = { "docs/{story}.md" = "# {story}\n" }
+//!
+//! [[responses]]
+//! story = "US-003"
+//! fail = "simulated crash"
+//! ```
+//!
+//! The first response matching the story, iteration and role (`agent`,
+//! `review`, `merge` or `reconcile`) is played; without one the mock prints a
+//! few lines and changes nothing. Reconciliation fixes run as the story
+//! `reconciliation`. `{story}` and `{iteration}` are replaced in output, file
+//! paths and file contents.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use glob::Pattern;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Agent command selecting the mock agent.
+pub const MOCK_AGENT_COMMAND: &str = "mock";
+
+/// Script file, relative to the project root, used by a plain `mock`.
+pub const MOCK_SCRIPT_FILE: &str = ".ralph/mock-agent.toml";
+
+/// Errors from the mock agent.
+#[derive(Debug, Error)]
+pub enum MockAgentError {
+    /// The script could not be read or parsed
+    #[error("Invalid mock agent script {path}: {message}")]
+    Script { path: PathBuf, message: String },
+    /// A scripted or injected failure
+    #[error("Mock agent failed: {0}")]
+    Failed(String),
+    /// A scripted file could not be written
+    #[error("Mock agent failed to write {path}: {message}")]
+    Write { path: PathBuf, message: String },
+}
+
+/// A mock agent selected by an agent command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockAgentSpec {
+    /// Script named in the command, if any
+    pub script: Option<PathBuf>,
+}
+
+impl MockAgentSpec {
+    /// Parse an agent command of the form `mock[:<script>]`.
+    ///
+    /// Returns `None` for commands that do not select the mock agent.
+    pub fn parse(agent_command: &str) -> Option<Self> {
+        if agent_command == MOCK_AGENT_COMMAND {
+            return Some(Self { script: None });
+        }
+        agent_command
+            .strip_prefix(MOCK_AGENT_COMMAND)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .filter(|script| !script.is_empty())
+            .map(|script| Self {
+                script: Some(PathBuf::from(script)),
+            })
+    }
+}
+
+/// One scripted response.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockResponse {
+    /// Story ID pattern (default: any story)
+    #[serde(default)]
+    pub story: Option<String>,
+    /// Iteration the response is for (default: any iteration)
+    #[serde(default)]
+    pub iteration: Option<u32>,
+    /// `agent`, `review`, `merge` or `reconcile` (default: agent)
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Lines printed, one per delay
+    #[serde(default)]
+    pub output: Vec<String>,
+    /// Files written relative to the project root, by path
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    /// Fail the run with this message after printing the output
+    #[serde(default)]
+    pub fail: Option<String>,
+    /// Print nothing and never finish, to exercise agent timeouts
+    #[serde(default)]
+    pub stall: bool,
+    /// Panic with this message, to exercise crashed story tasks
+    #[serde(default)]
+    pub panic: Option<String>,
+    /// Delay before each line, overriding the script's
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
+    /// Token usage reported for the run (default: estimated)
+    #[serde(default)]
+    pub input_tokens: Option<u64>,
+    #[serde(default)]
+    pub output_tokens: Option<u64>,
+}
+
+impl MockResponse {
+    fn matches(&self, story_id: &str, iteration: u32, role: &str) -> bool {
+        self.story.as_ref().map_or(true, |pattern| {
+            pattern == story_id || Pattern::new(pattern).is_ok_and(|p| p.matches(story_id))
+        }) && self.iteration.map_or(true, |wanted| wanted == iteration)
+            && self.role.as_deref().unwrap_or("agent") == role
+    }
+}
+
+/// The behavior of the mock agent.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockScript {
+    /// Delay before each output line in milliseconds
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Share of runs, 0.0-1.0, that fail regardless of their response
+    #[serde(default)]
+    pub fail_rate: f64,
+    /// Seed choosing which runs fail
+    #[serde(default)]
+    pub seed: u64,
+    /// Scripted responses, first match wins
+    #[serde(default)]
+    pub responses: Vec<MockResponse>,
+}
+
+impl MockScript {
+    /// The script for `spec`: the file it names, resolved against
+    /// `project_root`, else `.ralph/mock-agent.toml` if there is one, else
+    /// the default behavior.
+    pub fn load(project_root: &Path, spec: &MockAgentSpec) -> Result<Self, MockAgentError> {
+        let path = match spec.script {
+            Some(ref script) => project_root.join(script),
+            None => {
+                let path = project_root.join(MOCK_SCRIPT_FILE);
+                if !path.exists() {
+                    return Ok(Self::default());
+                }
+                path
+            }
+        };
+        let content = std::fs::read_to_string(&path).map_err(|e| MockAgentError::Script {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+        toml::from_str(&content).map_err(|e| MockAgentError::Script {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Whether the injected failure rate picks this run to fail.
+    fn injects_failure(&self, story_id: &str, iteration: u32, role: &str) -> bool {
+        if self.fail_rate <= 0.0 {
+            return false;
+        }
+        let mut hasher = DefaultHasher::new();
+        (self.seed, story_id, iteration, role).hash(&mut hasher);
+        (hasher.finish() % 10_000) as f64 / 10_000.0 < self.fail_rate
+    }
+}
+
+/// Token usage of a mock run, if the script gave it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MockRun {
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+}
+
+/// The mock agent, playing a script.
+#[derive(Debug, Clone)]
+pub struct MockAgent {
+    script: MockScript,
+}
+
+impl MockAgent {
+    pub fn new(script: MockScript) -> Self {
+        Self { script }
+    }
+
+    /// Play the response for one run of `story_id`, passing each output
+    /// line to `on_line` and writing scripted files under `project_root`.
+    pub async fn run(
+        &self,
+        story_id: &str,
+        iteration: u32,
+        role: &str,
+        project_root: &Path,
+        mut on_line: impl FnMut(&str),
+    ) -> Result<MockRun, MockAgentError> {
+        let response = self
+            .script
+            .responses
+            .iter()
+            .find(|response| response.matches(story_id, iteration, role))
+            .cloned()
+            .unwrap_or_else(|| default_response(role));
+        let fill = |text: &str| {
+            text.replace("{story}", story_id)
+                .replace("{iteration}", &iteration.to_string())
+        };
+
+        if response.stall {
+            std::future::pending::<()>().await;
+        }
+        if let Some(message) = response.panic {
+            panic!("{}", fill(&message));
+        }
+        let delay = Duration::from_millis(response.delay_ms.unwrap_or(self.script.delay_ms));
+        for line in &response.output {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            on_line(&fill(line));
+        }
+        for (path, content) in &response.files {
+            let path = project_root.join(fill(path));
+            let write = |path: &Path| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, fill(content))
+            };
+            write(&path).map_err(|e| MockAgentError::Write {
+                path: path.clone(),
+                message: e.to_string(),
+            })?;
+        }
+
+        if let Some(message) = response.fail {
+            return Err(MockAgentError::Failed(fill(&message)));
+        }
+        if self.script.injects_failure(story_id, iteration, role) {
+            return Err(MockAgentError::Failed(format!(
+                "injected failure ({} iteration {})",
+                story_id, iteration
+            )));
+        }
+        Ok(MockRun {
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+        })
+    }
+}
+
+/// What the mock does for a run no response matches.
+fn default_response(role: &str) -> MockResponse {
+    let output = if role == "review" {
+        vec!["No problems found.", "VERDICT: APPROVE"]
+    } else {
+        vec![
+            "Reading story {story}",
+            "Implementing {story} (iteration {iteration})",
+            "Done.",
+        ]
+    };
+    MockResponse {
+        output: output.into_iter().map(String::from).collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(
+            MockAgentSpec::parse("mock"),
+            Some(MockAgentSpec { script: None })
+        );
+        assert_eq!(
+            MockAgentSpec::parse("mock:demo/agent.toml").unwrap().script,
+            Some(PathBuf::from("demo/agent.toml"))
+        );
+        assert_eq!(MockAgentSpec::parse("mock:"), None);
+        assert_eq!(MockAgentSpec::parse("mockingbird"), None);
+        assert_eq!(MockAgentSpec::parse("claude"), None);
+    }
+
+    #[tokio::test]
+    async fn test_mock_agent_plays_script() {
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join(".ralph")).unwrap();
+        std::fs::write(
+            root.path().join(MOCK_SCRIPT_FILE),
+            r##"
+[[responses]]
+story = "DOC-*"
+output = ["Writing {story}"]
+files = { "docs/{story}.md" = "# {story} v{iteration}\n" }
+input_tokens = 100
+output_tokens = 20
+
+[[responses]]
+story = "US-1"
+iteration = 1
+fail = "crash in {story}"
+"##,
+        )
+        .unwrap();
+        let spec = MockAgentSpec::parse("mock").unwrap();
+        let agent = MockAgent::new(MockScript::load(root.path(), &spec).unwrap());
+
+        let mut lines = Vec::new();
+        let run = agent
+            .run("DOC-2", 3, "agent", root.path(), |line| {
+                lines.push(line.to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["Writing DOC-2"]);
+        assert_eq!(run.input_tokens, Some(100));
+        assert_eq!(
+            std::fs::read_to_string(root.path().join("docs/DOC-2.md")).unwrap(),
+            "# DOC-2 v3\n"
+        );
+
+        let err = agent
+            .run("US-1", 1, "agent", root.path(), |_| {})
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Mock agent failed: crash in US-1");
+        assert!(agent
+            .run("US-1", 2, "agent", root.path(), |_| {})
+            .await
+            .is_ok());
+
+        let mut review = Vec::new();
+        agent
+            .run("US-1", 2, "review", root.path(), |line| {
+                review.push(line.to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(review.last().unwrap(), "VERDICT: APPROVE");
+    }
+
+    #[test]
+    fn test_injected_failures() {
+        let script = MockScript {
+            fail_rate: 0.5,
+            seed: 3,
+            ..Default::default()
+        };
+        let failures = (1..=200)
+            .filter(|i| script.injects_failure("US-1", *i, "agent"))
+            .count();
+        assert!((60..140).contains(&failures), "{} failures", failures);
+        assert_eq!(
+            script.injects_failure("US-1", 7, "agent"),
+            script.injects_failure("US-1", 7, "agent")
+        );
+        assert!(!MockScript::default().injects_failure("US-1", 1, "agent"));
+    }
+}
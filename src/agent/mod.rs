@@ -0,0 +1,32 @@
+//! Agent backends that run in-process instead of as a CLI subprocess, and
+//! probing, configuring and containerizing agent CLIs, continuing their
+//! sessions and reviewing their changes.
+//!
+//! Stories are normally implemented by spawning an agent CLI (Claude Code,
+//! Codex, Amp, Aider). The backends here talk to a provider directly, which
+//! removes the CLI install requirement and reports exact token usage; the
+//! [`mock`] backend plays a script instead, for tests and demos.
+
+pub mod api;
+pub mod config;
+pub mod container;
+pub mod mock;
+pub mod probe;
+pub mod review;
+pub mod roster;
+pub mod session;
+pub mod template;
+
+#[cfg(feature = "api-agent")]
+pub use api::{ApiAgent, ApiAgentConfig, ApiAgentError, ApiAgentResult, ApiAgentRun};
+pub use api::{ApiAgentSpec, ApiProvider, API_AGENT_PREFIX};
+pub use config::{
+    AgentAssignment, AgentSettings, AgentsConfig, PermissionMode, ReviewSettings, StoryAgent,
+};
+pub use container::{AgentContainer, AgentNetwork};
+pub use mock::{MockAgent, MockAgentError, MockAgentSpec, MockScript, MOCK_AGENT_COMMAND};
+pub use probe::{probe_agent, AgentCapabilities, AgentProbeError};
+pub use review::{Review, ReviewVerdict, REVIEW_EVIDENCE_KIND};
+pub use roster::{AgentRoster, AgentRosterError};
+pub use session::AgentSession;
+pub use template::{AgentCommandTemplate, TemplateValues};
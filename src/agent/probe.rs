@@ -0,0 +1,241 @@
+//! Startup probing of agent CLIs.
+//!
+//! Before the first story, Ralph runs `<agent> --version` and reads the
+//! agent's help to learn which of the flags it passes are supported. A
+//! missing agent is reported up front, and flags an older or newer CLI does
+//! not list are left out of the invocation instead of failing the story.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How long each probe command may run.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors from probing an agent CLI.
+#[derive(Debug, Error)]
+pub enum AgentProbeError {
+    /// The agent program could not be started
+    #[error("Agent '{program}' could not be started: {message}")]
+    Unavailable { program: String, message: String },
+}
+
+/// What an agent CLI reported about itself at startup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentCapabilities {
+    /// Program that was probed (e.g. "codex")
+    pub program: String,
+    /// First line of `<program> --version`, if it printed one
+    pub version: Option<String>,
+    /// Flags Ralph may pass that the help output lists, or `None` when the
+    /// help could not be read and every flag is assumed supported
+    pub flags: Option<Vec<String>>,
+}
+
+impl AgentCapabilities {
+    /// Whether `flag` may be passed to the agent.
+    pub fn supports(&self, flag: &str) -> bool {
+        self.flags
+            .as_ref()
+            .map_or(true, |flags| flags.iter().any(|f| f == flag))
+    }
+
+    /// Whether the agent can emit machine-readable output.
+    pub fn json_output(&self) -> bool {
+        match self.program.as_str() {
+            "codex" => self.supports("--json"),
+            "claude" => self.supports("--output-format"),
+            _ => false,
+        }
+    }
+
+    /// Whether the agent can run without prompting for permission to edit.
+    pub fn skips_permissions(&self) -> bool {
+        match self.program.as_str() {
+            "claude" => self.supports("--dangerously-skip-permissions"),
+            "codex" => self.supports("--full-auto"),
+            "aider" => self.supports("--yes-always"),
+            _ => true,
+        }
+    }
+}
+
+/// The known agent CLI run for `agent_command`, matched the same way the
+/// executor builds its invocation. Commands with placeholders are always
+/// custom.
+pub fn agent_program(agent_command: &str) -> Option<&'static str> {
+    if super::AgentCommandTemplate::is_template(agent_command) {
+        None
+    } else if agent_command.contains("claude") {
+        Some("claude")
+    } else if agent_command.contains("codex") {
+        Some("codex")
+    } else if agent_command.contains("amp") {
+        Some("amp")
+    } else if agent_command.contains("aider") {
+        Some("aider")
+    } else {
+        None
+    }
+}
+
+/// Arguments printing the help that documents the flags Ralph passes, and
+/// those flags.
+fn help_invocation(program: &str) -> (&'static [&'static str], &'static [&'static str]) {
+    match program {
+        "claude" => (
+            &["--help"],
+            &[
+                "--print",
+                "--dangerously-skip-permissions",
+                "--output-format",
+                "--model",
+                "--session-id",
+                "--resume",
+            ],
+        ),
+        "codex" => (
+            &["exec", "--help"],
+            &[
+                "--full-auto",
+                "--json",
+                "--dangerously-bypass-approvals-and-sandbox",
+                "--model",
+                "--oss",
+                "--local-provider",
+            ],
+        ),
+        "amp" => (&["--help"], &["--prompt"]),
+        "aider" => (
+            &["--help"],
+            &[
+                "--yes-always",
+                "--no-auto-commits",
+                "--no-dirty-commits",
+                "--no-pretty",
+                "--no-stream",
+                "--no-check-update",
+                "--message-file",
+                "--model",
+            ],
+        ),
+        _ => (&["--help"], &[]),
+    }
+}
+
+/// Probe the agent CLI run for `agent_command`.
+///
+/// Returns `Ok(None)` for custom commands, API backends and the mock agent,
+/// which are not probed: a custom script may not understand `--version` at
+/// all.
+pub async fn probe_agent(
+    agent_command: &str,
+) -> Result<Option<AgentCapabilities>, AgentProbeError> {
+    if agent_command.starts_with(super::API_AGENT_PREFIX)
+        || super::MockAgentSpec::parse(agent_command).is_some()
+    {
+        return Ok(None);
+    }
+    let Some(program) = agent_program(agent_command) else {
+        return Ok(None);
+    };
+
+    let version = run_probe(program, &["--version"])
+        .await?
+        .and_then(|output| {
+            output
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(String::from)
+        });
+    let (help_args, candidates) = help_invocation(program);
+    let flags = run_probe(program, help_args)
+        .await?
+        .map(|help| supported_flags(&help, candidates));
+
+    Ok(Some(AgentCapabilities {
+        program: program.to_string(),
+        version,
+        flags,
+    }))
+}
+
+/// Run `program` with `args`, returning its combined output, or `None` if it
+/// failed or timed out.
+async fn run_probe(program: &str, args: &[&str]) -> Result<Option<String>, AgentProbeError> {
+    let child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AgentProbeError::Unavailable {
+            program: program.to_string(),
+            message: e.to_string(),
+        })?;
+    match tokio::time::timeout(PROBE_TIMEOUT, child.wait_with_output()).await {
+        Ok(Ok(output)) if output.status.success() => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            Ok(Some(text))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The `candidates` listed as whole words in `help`.
+fn supported_flags(help: &str, candidates: &[&str]) -> Vec<String> {
+    let words: Vec<&str> = help
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | '=' | '[' | ']' | '<'))
+        .collect();
+    candidates
+        .iter()
+        .filter(|flag| words.contains(flag))
+        .map(|flag| flag.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_flags_from_help() {
+        let help = "Usage: codex exec [OPTIONS] [PROMPT]\n\
+                    \x20 -m, --model <MODEL>  Model\n\
+                    \x20     --full-auto      Low-friction sandboxed execution\n\
+                    \x20     --json-schema=FILE  Schema of the final message\n";
+        let (_, candidates) = help_invocation("codex");
+        let flags = supported_flags(help, candidates);
+        assert_eq!(flags, vec!["--full-auto", "--model"]);
+
+        let capabilities = AgentCapabilities {
+            program: "codex".to_string(),
+            version: Some("codex-cli 0.1.0".to_string()),
+            flags: Some(flags),
+        };
+        assert!(!capabilities.json_output());
+        assert!(capabilities.skips_permissions());
+        assert!(!capabilities.supports("--oss"));
+
+        // Unreadable help assumes every flag works
+        let unknown = AgentCapabilities {
+            flags: None,
+            ..capabilities
+        };
+        assert!(unknown.json_output());
+    }
+
+    #[tokio::test]
+    async fn test_probe_skips_custom_and_api_agents() {
+        assert!(probe_agent("./my-agent.sh").await.unwrap().is_none());
+        assert!(probe_agent("api:claude").await.unwrap().is_none());
+        assert_eq!(agent_program("/opt/bin/claude-wrapper"), Some("claude"));
+        assert!(probe_agent("mock:scripts/codex.toml").await.unwrap().is_none());
+        assert_eq!(agent_program("/opt/bin/claude-wrapper {prompt_file}"), None);
+    }
+}
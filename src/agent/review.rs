@@ -0,0 +1,114 @@
+//! Reviewer pass over an agent's changes.
+//!
+//! With a `[review]` section in `.ralph/agents.toml`, a second agent reads
+//! the implementing agent's diff against the story's acceptance criteria
+//! before the quality gates run. The reviewer lists its findings as bullet
+//! points and ends with a verdict line:
+//!
+//! ```text
+//! - src/auth.rs: the lockout counter is never reset on success
+//! VERDICT: REQUEST_CHANGES
+//! ```
+//!
+//! Every review is recorded as evidence. When approval is required, a review
+//! that does not approve fails the iteration like a gate would, and its
+//! findings go into the next prompt.
+
+use serde::Serialize;
+
+/// Evidence record kind of a review.
+pub const REVIEW_EVIDENCE_KIND: &str = "review";
+
+/// Prefix of the line carrying the reviewer's verdict.
+const VERDICT_PREFIX: &str = "VERDICT:";
+
+/// What the reviewer decided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewVerdict {
+    /// The changes meet the acceptance criteria
+    Approve,
+    /// The changes need more work
+    RequestChanges,
+    /// The reviewer gave no verdict line
+    Missing,
+}
+
+/// A parsed review of one iteration's changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Review {
+    /// Reviewer agent (and model)
+    pub agent: String,
+    pub verdict: ReviewVerdict,
+    /// The reviewer's findings, one per bullet point
+    pub findings: Vec<String>,
+}
+
+impl Review {
+    /// Parse the reviewer's output. The last verdict line wins; bullet
+    /// points anywhere in the output are findings.
+    pub fn parse(agent: impl Into<String>, output: &str) -> Self {
+        let mut verdict = ReviewVerdict::Missing;
+        let mut findings = Vec::new();
+        for line in output.lines().map(str::trim) {
+            if let Some(rest) = line.strip_prefix(VERDICT_PREFIX) {
+                let rest = rest.trim().to_ascii_uppercase().replace([' ', '-'], "_");
+                verdict = match rest.as_str() {
+                    "APPROVE" | "APPROVED" => ReviewVerdict::Approve,
+                    _ => ReviewVerdict::RequestChanges,
+                };
+            } else if let Some(finding) = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .map(str::trim)
+                .filter(|finding| !finding.is_empty())
+            {
+                findings.push(finding.to_string());
+            }
+        }
+        Self {
+            agent: agent.into(),
+            verdict,
+            findings,
+        }
+    }
+
+    /// Whether the reviewer approved the changes.
+    pub fn approved(&self) -> bool {
+        self.verdict == ReviewVerdict::Approve
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_review() {
+        let review = Review::parse(
+            "codex",
+            "Looked at the diff.\n\n\
+             - src/auth.rs: lockout counter never resets\n\
+             * missing test for criterion 2\n\
+             -\n\
+             VERDICT: REQUEST_CHANGES\n",
+        );
+        assert_eq!(review.verdict, ReviewVerdict::RequestChanges);
+        assert_eq!(
+            review.findings,
+            vec![
+                "src/auth.rs: lockout counter never resets",
+                "missing test for criterion 2"
+            ]
+        );
+        assert!(!review.approved());
+
+        let review = Review::parse("claude", "All criteria met.\nVERDICT: approve\n");
+        assert!(review.approved());
+        assert!(review.findings.is_empty());
+
+        let review = Review::parse("claude", "- looks fine I guess");
+        assert_eq!(review.verdict, ReviewVerdict::Missing);
+        assert!(!review.approved());
+    }
+}
@@ -0,0 +1,173 @@
+//! The agents a run uses, per story.
+//!
+//! A run has a default agent, but `[[assign]]` rules in `.ralph/agents.toml`
+//! can give some stories a different agent or model, and a story can name its
+//! own with the PRD's `agent` and `model` fields. The [`AgentRoster`] loads
+//! the rules once, probes every agent they and the PRD name up front (so a
+//! missing CLI fails the run before any story starts) and tells the runners
+//! which agent, model and probed capabilities each story gets.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use thiserror::Error;
+
+use super::config::{AgentConfigError, AgentsConfig, StoryAgent};
+use super::probe::{probe_agent, AgentCapabilities, AgentProbeError};
+use crate::mcp::tools::load_prd::PrdUserStory;
+use crate::parallel::dependency::StoryNode;
+
+/// Errors from setting up the agents of a run.
+#[derive(Debug, Error)]
+pub enum AgentRosterError {
+    /// The agent settings could not be loaded
+    #[error(transparent)]
+    Config(#[from] AgentConfigError),
+    /// An agent CLI could not be probed
+    #[error(transparent)]
+    Probe(#[from] AgentProbeError),
+}
+
+/// Agents of a run and what their CLIs reported when probed.
+#[derive(Debug, Clone)]
+pub struct AgentRoster {
+    default_agent: String,
+    config: AgentsConfig,
+    /// Probe results by agent command (None = not probed)
+    capabilities: HashMap<String, Option<AgentCapabilities>>,
+}
+
+impl AgentRoster {
+    /// Load the agent settings of `project_root` and probe `default_agent`
+    /// and every agent assigned to stories, named by `stories` or reviewing
+    /// them. Containerized agents are not installed on the host, so they are
+    /// not probed.
+    pub async fn load(
+        project_root: &Path,
+        default_agent: &str,
+        stories: &[PrdUserStory],
+        containerized: bool,
+    ) -> Result<Self, AgentRosterError> {
+        let config = AgentsConfig::load(project_root)?;
+        let mut capabilities = HashMap::new();
+        let agents = std::iter::once(default_agent.to_string())
+            .chain(config.assigned_agents())
+            .chain(stories.iter().filter_map(|story| story.agent.clone()));
+        for agent in agents {
+            if capabilities.contains_key(&agent) {
+                continue;
+            }
+            let probed = if containerized {
+                None
+            } else {
+                probe_agent(&agent).await?
+            };
+            capabilities.insert(agent, probed);
+        }
+        Ok(Self {
+            default_agent: default_agent.to_string(),
+            config,
+            capabilities,
+        })
+    }
+
+    /// The run's default agent command.
+    pub fn default_agent(&self) -> &str {
+        &self.default_agent
+    }
+
+    /// What the default agent reported when probed.
+    pub fn default_capabilities(&self) -> Option<&AgentCapabilities> {
+        self.capabilities(&self.default_agent)
+    }
+
+    /// What `agent_command` reported when probed.
+    pub fn capabilities(&self, agent_command: &str) -> Option<&AgentCapabilities> {
+        self.capabilities
+            .get(agent_command)
+            .and_then(Option::as_ref)
+    }
+
+    /// Agent and model for a story: those the PRD sets for it, else the
+    /// assigned ones. A story that names its own agent gets only its own
+    /// model, since an assigned model is meant for the assigned agent.
+    pub fn for_story(&self, story: &StoryNode) -> StoryAgent {
+        let assigned = self.config.assign(
+            &self.default_agent,
+            &story.id,
+            &story.target_files,
+            story.heavy,
+        );
+        match story.agent {
+            Some(ref agent) => StoryAgent {
+                agent_command: agent.clone(),
+                model: story.model.clone(),
+            },
+            None => StoryAgent {
+                model: story.model.clone().or(assigned.model),
+                ..assigned
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::config::AGENTS_CONFIG_FILE;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_roster_assigns_and_probes_agents() {
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join(".ralph")).unwrap();
+        std::fs::write(
+            root.path().join(AGENTS_CONFIG_FILE),
+            "[[assign]]\nstories = [\"DOC-*\"]\nagent = \"./bin/cheap-agent.sh\"\nmodel = \"small\"\n",
+        )
+        .unwrap();
+
+        let stories: Vec<PrdUserStory> = serde_json::from_str(
+            r#"[
+                {"id": "DOC-1", "title": "Docs", "priority": 1, "passes": false},
+                {"id": "US-1", "title": "Feature", "priority": 2, "passes": false},
+                {"id": "DOC-2", "title": "Docs", "priority": 3, "passes": false, "model": "tiny"},
+                {"id": "DOC-3", "title": "Docs", "priority": 4, "passes": false,
+                 "agent": "./bin/local-agent.sh"},
+                {"id": "US-2", "title": "Feature", "priority": 5, "passes": false,
+                 "agent": "./bin/local-agent.sh", "model": "big"}
+            ]"#,
+        )
+        .unwrap();
+        let story = |index: usize| StoryNode::from(&stories[index]);
+
+        // Custom commands are not probed, so this needs no agent installed
+        let roster = AgentRoster::load(root.path(), "./bin/agent.sh", &stories, false)
+            .await
+            .unwrap();
+        assert_eq!(roster.default_agent(), "./bin/agent.sh");
+        assert!(roster.default_capabilities().is_none());
+        assert!(roster.capabilities.contains_key("./bin/local-agent.sh"));
+        let agent = |index: usize| {
+            let assigned = roster.for_story(&story(index));
+            (assigned.agent_command, assigned.model)
+        };
+        let expected =
+            |agent: &str, model: Option<&str>| (agent.to_string(), model.map(str::to_string));
+        assert_eq!(agent(0), expected("./bin/cheap-agent.sh", Some("small")));
+        assert_eq!(agent(1), expected("./bin/agent.sh", None));
+        assert_eq!(agent(2), expected("./bin/cheap-agent.sh", Some("tiny")));
+        assert_eq!(agent(3), expected("./bin/local-agent.sh", None));
+        assert_eq!(agent(4), expected("./bin/local-agent.sh", Some("big")));
+
+        std::fs::write(
+            root.path().join(AGENTS_CONFIG_FILE),
+            "[[assign]]\nagents = 1\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            AgentRoster::load(root.path(), "./bin/agent.sh", &[], true).await,
+            Err(AgentRosterError::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+//! Agent conversations continued across iterations.
+//!
+//! Without a session every iteration re-sends the whole story prompt to a
+//! fresh agent. Claude Code can name a conversation up front
+//! (`--session-id`) and continue it later (`--resume`), so after the first
+//! iteration only the new gate failures need to be sent.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use super::probe::{agent_program, AgentCapabilities};
+
+/// A conversation with the agent that later iterations continue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentSession {
+    /// Session UUID passed to the agent
+    id: String,
+    /// Whether an agent run has created the conversation yet
+    established: bool,
+}
+
+impl AgentSession {
+    /// A new session for `story_id`, if the agent run by `agent_command`
+    /// can continue conversations.
+    pub fn start(
+        agent_command: &str,
+        story_id: &str,
+        capabilities: Option<&AgentCapabilities>,
+    ) -> Option<Self> {
+        let supported = agent_program(agent_command) == Some("claude")
+            && capabilities.map_or(true, |caps| {
+                caps.supports("--session-id") && caps.supports("--resume")
+            });
+        supported.then(|| Self {
+            id: new_session_id(story_id),
+            established: false,
+        })
+    }
+
+    /// The session UUID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether the next run continues an existing conversation, so only
+    /// what changed needs to be sent.
+    pub fn is_established(&self) -> bool {
+        self.established
+    }
+
+    /// Arguments naming or continuing the conversation.
+    pub fn args(&self) -> Vec<String> {
+        let flag = if self.established {
+            "--resume"
+        } else {
+            "--session-id"
+        };
+        vec![flag.to_string(), self.id.clone()]
+    }
+
+    /// Record the outcome of an agent run in this session. After a failed
+    /// run the conversation may be incomplete, so the next run starts a new
+    /// one with the full prompt.
+    pub fn record_run(&mut self, succeeded: bool) {
+        if succeeded {
+            self.established = true;
+        } else {
+            self.id = new_session_id(&self.id);
+            self.established = false;
+        }
+    }
+}
+
+/// A random-looking version 4 UUID derived from `seed`, the process and the
+/// current time.
+fn new_session_id(seed: &str) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(seed.as_bytes());
+    hasher.update(std::process::id().to_le_bytes());
+    hasher.update(nanos.to_le_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_lifecycle() {
+        assert!(AgentSession::start("codex", "US-001", None).is_none());
+        let old_claude = AgentCapabilities {
+            program: "claude".to_string(),
+            version: None,
+            flags: Some(vec!["--print".to_string()]),
+        };
+        assert!(AgentSession::start("claude", "US-001", Some(&old_claude)).is_none());
+
+        let mut session = AgentSession::start("claude", "US-001", None).unwrap();
+        let id = session.id().to_string();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert_eq!(session.args(), vec!["--session-id".to_string(), id.clone()]);
+
+        session.record_run(true);
+        assert!(session.is_established());
+        assert_eq!(session.args(), vec!["--resume".to_string(), id.clone()]);
+
+        session.record_run(false);
+        assert!(!session.is_established());
+        assert_ne!(session.id(), id);
+    }
+}
@@ -0,0 +1,140 @@
+//! Placeholders in custom agent commands.
+//!
+//! A custom agent command normally gets the prompt appended as its last
+//! argument. A command naming any of the placeholders below is instead
+//! split into words (with shell quoting rules) and expanded for each run, so
+//! an in-house wrapper can take its inputs however it likes:
+//!
+//! ```text
+//! ./bin/agent-wrapper --task {prompt_file} --id {story_id} --cwd {working_dir} --model {model}
+//! ```
+//!
+//! With `{prompt_file}` the prompt is written to a temporary file instead
+//! of being passed as an argument. `{model}` expands to the agent's
+//! configured model, or to nothing if it has none.
+
+use std::path::Path;
+
+/// Placeholder for the path of a file holding the prompt.
+pub const PROMPT_FILE_PLACEHOLDER: &str = "{prompt_file}";
+
+/// Placeholders a custom agent command may use.
+pub const PLACEHOLDERS: [&str; 4] = [
+    PROMPT_FILE_PLACEHOLDER,
+    "{story_id}",
+    "{working_dir}",
+    "{model}",
+];
+
+/// Values substituted for the placeholders of one invocation.
+#[derive(Debug, Clone, Copy)]
+pub struct TemplateValues<'a> {
+    pub prompt_file: Option<&'a Path>,
+    pub story_id: &'a str,
+    pub working_dir: &'a Path,
+    pub model: Option<&'a str>,
+}
+
+/// A custom agent command with placeholders, split into words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentCommandTemplate {
+    words: Vec<String>,
+}
+
+impl AgentCommandTemplate {
+    /// Whether `agent_command` uses any placeholder.
+    pub fn is_template(agent_command: &str) -> bool {
+        PLACEHOLDERS
+            .iter()
+            .any(|placeholder| agent_command.contains(placeholder))
+    }
+
+    /// Parse an agent command using placeholders.
+    ///
+    /// Returns `Ok(None)` for commands without placeholders and an error for
+    /// one that does not split into words.
+    pub fn parse(agent_command: &str) -> Result<Option<Self>, String> {
+        if !Self::is_template(agent_command) {
+            return Ok(None);
+        }
+        match shlex::split(agent_command) {
+            Some(words) if !words.is_empty() => Ok(Some(Self { words })),
+            _ => Err(format!(
+                "Agent command '{}' has unbalanced quotes",
+                agent_command
+            )),
+        }
+    }
+
+    /// Whether the prompt goes into a file rather than an argument.
+    pub fn uses_prompt_file(&self) -> bool {
+        self.words
+            .iter()
+            .any(|word| word.contains(PROMPT_FILE_PLACEHOLDER))
+    }
+
+    /// Program and arguments with the placeholders expanded.
+    pub fn expand(&self, values: &TemplateValues<'_>) -> (String, Vec<String>) {
+        let prompt_file = values
+            .prompt_file
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let working_dir = values.working_dir.display().to_string();
+        let mut words = self.words.iter().map(|word| {
+            word.replace(PROMPT_FILE_PLACEHOLDER, &prompt_file)
+                .replace("{story_id}", values.story_id)
+                .replace("{working_dir}", &working_dir)
+                .replace("{model}", values.model.unwrap_or_default())
+        });
+        let program = words.next().unwrap_or_default();
+        (program, words.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_template() {
+        assert_eq!(AgentCommandTemplate::parse("./bin/agent.sh"), Ok(None));
+        assert!(AgentCommandTemplate::parse("./run '{story_id}").is_err());
+
+        let template = AgentCommandTemplate::parse(
+            "./bin/wrap --task {prompt_file} --id={story_id} \"{working_dir}/out dir\" -m {model}",
+        )
+        .unwrap()
+        .unwrap();
+        assert!(template.uses_prompt_file());
+        let (program, args) = template.expand(&TemplateValues {
+            prompt_file: Some(Path::new("/tmp/US-1.md")),
+            story_id: "US-1",
+            working_dir: Path::new("/work"),
+            model: Some("small"),
+        });
+        assert_eq!(program, "./bin/wrap");
+        assert_eq!(
+            args,
+            vec![
+                "--task",
+                "/tmp/US-1.md",
+                "--id=US-1",
+                "/work/out dir",
+                "-m",
+                "small"
+            ]
+        );
+
+        let template = AgentCommandTemplate::parse("wrap {story_id} {model}")
+            .unwrap()
+            .unwrap();
+        assert!(!template.uses_prompt_file());
+        let (_, args) = template.expand(&TemplateValues {
+            prompt_file: None,
+            story_id: "US-2",
+            working_dir: Path::new("/work"),
+            model: None,
+        });
+        assert_eq!(args, vec!["US-2", ""]);
+    }
+}
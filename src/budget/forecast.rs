@@ -0,0 +1,139 @@
+//! Token forecasts from story estimates.
+//!
+//! A story's `estimate` gives the tokens it is expected to use, or the
+//! iterations it is expected to take, which are converted to tokens with the
+//! mean tokens per agent iteration of previous runs. The forecast is checked
+//! against the budget before a run starts, so a run that cannot fit is
+//! flagged up front rather than stopped partway through.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::config::TokenBudgetConfig;
+use crate::mcp::tools::load_prd::PrdUserStory;
+use crate::metrics::RunMetrics;
+
+/// Tokens the stories of a run are expected to use.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenForecast {
+    /// Expected tokens of each story with an estimate to forecast from
+    pub per_story: BTreeMap<String, u64>,
+    /// Stories without a token or iteration estimate to forecast from
+    pub unforecast: Vec<String>,
+    /// Expected tokens of all forecast stories
+    pub total: u64,
+}
+
+impl TokenForecast {
+    /// Forecast the stories that do not pass yet, converting iteration
+    /// estimates with `history`'s mean tokens per iteration.
+    pub fn new(stories: &[PrdUserStory], history: &[RunMetrics]) -> Self {
+        let per_iteration = tokens_per_iteration(history);
+        let mut forecast = Self::default();
+        for story in stories.iter().filter(|story| !story.passes) {
+            let estimate = story.estimate.unwrap_or_default();
+            let expected = estimate
+                .tokens
+                .or_else(|| Some(u64::from(estimate.iterations?) * per_iteration?));
+            match expected {
+                Some(tokens) => {
+                    forecast.total += tokens;
+                    forecast.per_story.insert(story.id.clone(), tokens);
+                }
+                None => forecast.unforecast.push(story.id.clone()),
+            }
+        }
+        forecast
+    }
+
+    /// Why the forecast does not fit `config`: stories expected to exceed the
+    /// per-story budget, and a total over the run budget.
+    pub fn over_budget(&self, config: &TokenBudgetConfig) -> Vec<String> {
+        let mut problems = Vec::new();
+        if config.story_budget > 0 {
+            for (story_id, tokens) in &self.per_story {
+                if *tokens > config.story_budget {
+                    problems.push(format!(
+                        "{} is expected to use ~{} tokens, over the per-story budget of {}",
+                        story_id, tokens, config.story_budget
+                    ));
+                }
+            }
+        }
+        if config.total_budget > 0 && self.total > config.total_budget {
+            problems.push(format!(
+                "the stories are expected to use ~{} tokens, over the total budget of {}",
+                self.total, config.total_budget
+            ));
+        }
+        problems
+    }
+}
+
+/// Mean tokens of the agent iterations in `history` that recorded tokens.
+pub fn tokens_per_iteration(history: &[RunMetrics]) -> Option<u64> {
+    let tokens: Vec<u64> = history
+        .iter()
+        .flat_map(|run| run.invocations.iter())
+        .filter(|invocation| invocation.role == "agent")
+        .filter_map(|invocation| invocation.total_tokens())
+        .collect();
+    (!tokens.is_empty()).then(|| tokens.iter().sum::<u64>() / tokens.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{AgentInvocation, RunMetricsCollector};
+
+    #[test]
+    fn test_forecast_from_estimates() {
+        let stories: Vec<PrdUserStory> = serde_json::from_str(
+            r#"[
+                {"id": "US-1", "title": "Cart", "priority": 1, "passes": false,
+                 "estimate": {"tokens": 60000, "iterations": 9}},
+                {"id": "US-2", "title": "Pay", "priority": 2, "passes": false,
+                 "estimate": {"iterations": 3}},
+                {"id": "US-3", "title": "Refund", "priority": 3, "passes": false,
+                 "estimate": {"points": 5}},
+                {"id": "US-4", "title": "Done", "priority": 4, "passes": true,
+                 "estimate": {"tokens": 90000}}
+            ]"#,
+        )
+        .unwrap();
+
+        // Without history iterations cannot be converted to tokens
+        let forecast = TokenForecast::new(&stories, &[]);
+        assert_eq!(forecast.total, 60_000);
+        assert_eq!(forecast.unforecast, vec!["US-2", "US-3"]);
+
+        let collector = RunMetricsCollector::new("run-history", 1);
+        for tokens in [8_000, 12_000] {
+            let mut invocation = AgentInvocation::new("US-0", 1, "agent", "claude");
+            invocation.output_tokens = Some(tokens);
+            collector.record_invocation(invocation);
+        }
+        let history = vec![collector.finish()];
+        assert_eq!(tokens_per_iteration(&history), Some(10_000));
+
+        let forecast = TokenForecast::new(&stories, &history);
+        assert_eq!(forecast.per_story["US-2"], 30_000);
+        assert_eq!(forecast.total, 90_000);
+        assert_eq!(forecast.unforecast, vec!["US-3"]);
+
+        let config = TokenBudgetConfig::new()
+            .with_story_budget(50_000)
+            .with_total_budget(80_000);
+        assert_eq!(
+            forecast.over_budget(&config),
+            vec![
+                "US-1 is expected to use ~60000 tokens, over the per-story budget of 50000",
+                "the stories are expected to use ~90000 tokens, over the total budget of 80000",
+            ]
+        );
+        assert!(forecast
+            .over_budget(&TokenBudgetConfig::unlimited())
+            .is_empty());
+    }
+}
@@ -9,6 +9,7 @@
 //! The token budget system consists of:
 //! - **TokenBudgetConfig**: Configuration for per-story and total budgets
 //! - **TokenEstimator**: Estimates token counts from text (since we can't get exact counts from CLI agents)
+//! - **TokenForecast**: Forecasts a run's token use from story estimates
 //! - **TokenBudget**: Tracks usage against configured budgets
 //! - **BudgetStrategy**: Adjusts behavior based on remaining budget
 //!
@@ -35,12 +36,14 @@
 
 mod config;
 mod estimator;
+mod forecast;
 mod parser;
 mod strategy;
 mod tracker;
 
 pub use config::{TokenBudgetConfig, TokenCost};
 pub use estimator::TokenEstimator;
+pub use forecast::{tokens_per_iteration, TokenForecast};
 pub use parser::{extract_or_estimate, ParsedTokenUsage, TokenUsageParser};
 pub use strategy::{BudgetAwarePromptBuilder, BudgetStrategy, PromptStrategy};
 pub use tracker::{BudgetEnforcement, BudgetStatus, SharedTokenBudget, StoryBudget, TokenBudget};
@@ -6,6 +6,7 @@
 //! - **Claude CLI**: `{"usage": {"input_tokens": N, "output_tokens": N}}`
 //! - **OpenAI/Codex**: `{"usage": {"prompt_tokens": N, "completion_tokens": N}}`
 //! - **Anthropic API**: Similar to Claude CLI
+//! - **Aider**: `Tokens: 2.4k sent, 512 received. Cost: ...` after each message
 //!
 //! The parser attempts to extract usage from any of these formats.
 
@@ -146,7 +147,7 @@ impl TokenUsageParser {
             return Some(usage);
         }
 
-        None
+        self.parse_aider_cli(output)
     }
 
     /// Try to parse a single JSON line for usage data.
@@ -258,6 +259,45 @@ impl TokenUsageParser {
         }
         None
     }
+
+    /// Parse token usage from Aider output.
+    ///
+    /// Aider reports the tokens of each message it sends on a line like
+    /// `Tokens: 2.4k sent, 512 received.`; the counts of all messages are
+    /// summed.
+    pub fn parse_aider_cli(&self, output: &str) -> Option<ParsedTokenUsage> {
+        let mut totals: Option<(u64, u64)> = None;
+        for line in output.lines() {
+            let Some(rest) = line.trim().strip_prefix("Tokens:") else {
+                continue;
+            };
+            let count_before = |label: &str| {
+                let end = rest.find(label)?;
+                parse_aider_count(rest[..end].split_whitespace().last()?)
+            };
+            if let (Some(sent), Some(received)) = (count_before(" sent"), count_before(" received"))
+            {
+                let (input, output) = totals.get_or_insert((0, 0));
+                *input += sent;
+                *output += received;
+            }
+        }
+        totals.map(|(input, output)| ParsedTokenUsage::actual(input, output, "aider"))
+    }
+}
+
+/// Parse an Aider token count such as `512`, `2.4k` or `1.2M`.
+fn parse_aider_count(count: &str) -> Option<u64> {
+    let count = count.trim();
+    let (number, scale) = if let Some(number) = count.strip_suffix('k') {
+        (number, 1_000.0)
+    } else if let Some(number) = count.strip_suffix('M') {
+        (number, 1_000_000.0)
+    } else {
+        (count, 1.0)
+    };
+    let value: f64 = number.parse().ok()?;
+    Some((value * scale).round() as u64)
 }
 
 /// Extract token usage from agent output, falling back to estimation if not found.
@@ -380,6 +420,24 @@ Some text after
         assert_eq!(count.total(), 1500);
     }
 
+    #[test]
+    fn test_parse_aider_usage() {
+        let output = "\
+Applied edit to src/lib.rs
+Tokens: 2.4k sent, 512 received. Cost: $0.01 message, $0.01 session.
+Applied edit to src/main.rs
+Tokens: 1.2M sent, 1k received. Cost: $3.00 message, $3.01 session.
+";
+        let parser = TokenUsageParser::new();
+        let usage = parser.parse(output).unwrap();
+
+        assert!(usage.is_actual);
+        assert_eq!(usage.source, "aider");
+        assert_eq!(usage.input_tokens, Some(1_202_400));
+        assert_eq!(usage.output_tokens, Some(1_512));
+        assert!(parser.parse_aider_cli("Tokens: lots").is_none());
+    }
+
     #[test]
     fn test_parse_embedded_usage() {
         // Simulates output where usage is embedded in a larger response
@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::tracker::{BudgetStatus, TokenBudget};
+use crate::quality::GateFailureDetail;
 
 /// Strategy for prompt generation based on budget.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -47,6 +48,16 @@ impl PromptStrategy {
         }
     }
 
+    /// Get maximum structured gate findings to include.
+    pub fn max_failure_details(&self) -> usize {
+        match self {
+            PromptStrategy::Full => 20,
+            PromptStrategy::Standard => 10,
+            PromptStrategy::Minimal => 5,
+            PromptStrategy::Critical => 3,
+        }
+    }
+
     /// Whether to include approach hints.
     pub fn include_hints(&self) -> bool {
         matches!(self, PromptStrategy::Full | PromptStrategy::Standard)
@@ -219,6 +230,7 @@ impl BudgetAwarePromptBuilder {
             return String::new();
         }
 
+        let latest_iteration = errors_to_include[0].iteration;
         let mut section = String::from("\n### Previous Errors\n\n");
 
         for error in errors_to_include.iter().rev() {
@@ -238,6 +250,11 @@ impl BudgetAwarePromptBuilder {
                 section.push_str(&format!("  - Failed gate: {}\n", gate));
             }
 
+            // Findings are only current for the latest failing iteration
+            if error.iteration == latest_iteration {
+                section.push_str(&self.build_failure_details(&error.failures));
+            }
+
             if !error.affected_files.is_empty() && self.strategy.max_affected_files() > 0 {
                 let files: Vec<_> = error
                     .affected_files
@@ -252,6 +269,27 @@ impl BudgetAwarePromptBuilder {
         section
     }
 
+    /// Build the findings of a failed gate as sorted, de-duplicated one-line
+    /// entries, so an unchanged failure renders identically every iteration.
+    pub fn build_failure_details(&self, failures: &[GateFailureDetail]) -> String {
+        let mut lines: Vec<String> = failures.iter().map(GateFailureDetail::compact).collect();
+        lines.sort();
+        lines.dedup();
+
+        let max_details = self.strategy.max_failure_details();
+        let mut section = String::new();
+        for line in lines.iter().take(max_details) {
+            section.push_str(&format!("    - {}\n", line));
+        }
+        if lines.len() > max_details {
+            section.push_str(&format!(
+                "    - ... and {} more\n",
+                lines.len() - max_details
+            ));
+        }
+        section
+    }
+
     /// Build hints section based on strategy.
     pub fn build_hints(&self, hints: &[crate::iteration::context::ApproachHint]) -> String {
         if !self.strategy.include_hints() || hints.is_empty() {
@@ -394,4 +432,49 @@ mod tests {
         // Iteration 1 should be excluded due to limit
         assert!(!section.contains("Iteration 1"));
     }
+
+    #[test]
+    fn test_prompt_builder_gate_failures() {
+        use crate::iteration::context::{ErrorCategory, IterationError};
+        use crate::quality::FailureCategory;
+
+        let unused = GateFailureDetail::new(FailureCategory::Lint, "unused variable: `x`\n  |")
+            .with_location("src/b.rs", 4, Some(9))
+            .with_error_code("unused_variables")
+            .with_suggestion("prefix it with an underscore: `_x`");
+        let moved = GateFailureDetail::new(FailureCategory::TypeCheck, "borrow of moved value")
+            .with_location("src/a.rs", 12, None)
+            .with_error_code("E0382");
+        let errors = vec![
+            IterationError::new(1, ErrorCategory::Lint, "Gate 'lint' failed")
+                .with_gate("lint")
+                .with_failures(vec![moved.clone()]),
+            IterationError::new(2, ErrorCategory::Lint, "Gate 'lint' failed")
+                .with_gate("lint")
+                .with_failures(vec![unused.clone(), moved, unused]),
+        ];
+
+        let section =
+            BudgetAwarePromptBuilder::new(PromptStrategy::Standard).build_error_history(&errors);
+        let iteration_2 = section.split("**Iteration 2**").nth(1).unwrap();
+        assert_eq!(
+            iteration_2,
+            " (lint): Gate 'lint' failed\n  - Failed gate: lint\n\
+             \x20   - src/a.rs:12 [E0382] borrow of moved value\n\
+             \x20   - src/b.rs:4:9 [unused_variables] unused variable: `x` \
+             (fix: prefix it with an underscore: `_x`)\n"
+        );
+        // Findings of earlier iterations are stale and left out
+        assert_eq!(section.matches("E0382").count(), 1);
+
+        let many: Vec<_> = (1..=5)
+            .map(|line| {
+                GateFailureDetail::new(FailureCategory::Test, "failed")
+                    .with_location("t.rs", line, None)
+            })
+            .collect();
+        let details =
+            BudgetAwarePromptBuilder::new(PromptStrategy::Critical).build_failure_details(&many);
+        assert!(details.ends_with("    - ... and 2 more\n"));
+    }
 }
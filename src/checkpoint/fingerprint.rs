@@ -0,0 +1,278 @@
+//! Identify the run a checkpoint belongs to.
+//!
+//! A checkpoint records a hash of the PRD it was written against, a hash of
+//! each of its stories, and the git branch and commit that were checked out.
+//! On startup these are compared with the current PRD and branch, so a
+//! matching checkpoint can be resumed automatically, one left over from
+//! different work is only offered, and one whose PRD has since been edited
+//! reports which stories changed before it is resumed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The PRD and branch a run was started from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunFingerprint {
+    /// SHA-256 of the PRD file contents, hex encoded
+    pub prd_hash: String,
+    /// Checked-out git branch (absent outside a repository or when detached)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit checked out when the checkpoint was written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// Hash of each story in the PRD, keyed by story ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stories: BTreeMap<String, String>,
+}
+
+/// Stories that changed in the PRD since a checkpoint was written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrdChanges {
+    /// Stories that were added
+    pub added: Vec<String>,
+    /// Stories that were removed
+    pub removed: Vec<String>,
+    /// Stories whose definition changed
+    pub modified: Vec<String>,
+}
+
+impl PrdChanges {
+    /// Whether no story was added, removed or modified.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// One line per kind of change, e.g. `Added: US-005, US-006`.
+    pub fn describe(&self) -> Vec<String> {
+        [
+            ("Added", &self.added),
+            ("Removed", &self.removed),
+            ("Modified", &self.modified),
+        ]
+        .into_iter()
+        .filter(|(_, ids)| !ids.is_empty())
+        .map(|(label, ids)| format!("{}: {}", label, ids.join(", ")))
+        .collect()
+    }
+}
+
+impl RunFingerprint {
+    /// Create a fingerprint from its parts.
+    pub fn new(prd_hash: impl Into<String>, branch: Option<String>) -> Self {
+        Self {
+            prd_hash: prd_hash.into(),
+            branch,
+            head: None,
+            stories: BTreeMap::new(),
+        }
+    }
+
+    /// Fingerprint the PRD at `prd_path` and the branch and commit checked
+    /// out in `working_dir`. Returns `None` if the PRD cannot be read.
+    pub fn capture(prd_path: &Path, working_dir: &Path) -> Option<Self> {
+        let contents = fs::read(prd_path).ok()?;
+        Some(Self {
+            prd_hash: hex::encode(Sha256::digest(&contents)),
+            branch: current_branch(working_dir),
+            head: git_output(working_dir, &["rev-parse", "HEAD"]),
+            stories: story_hashes(&contents),
+        })
+    }
+
+    /// Whether the PRD was edited since this fingerprint was taken.
+    pub fn prd_changed(&self, current: &RunFingerprint) -> bool {
+        self.prd_hash != current.prd_hash
+    }
+
+    /// Stories added, removed or modified in `current` relative to this
+    /// fingerprint.
+    pub fn prd_changes(&self, current: &RunFingerprint) -> PrdChanges {
+        let mut changes = PrdChanges::default();
+        for (id, hash) in &current.stories {
+            match self.stories.get(id) {
+                None => changes.added.push(id.clone()),
+                Some(saved) if saved != hash => changes.modified.push(id.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = self
+            .stories
+            .keys()
+            .filter(|id| !current.stories.contains_key(*id))
+            .cloned()
+            .collect();
+        changes
+    }
+
+    /// The saved and current commits, if HEAD moved since this fingerprint
+    /// was taken.
+    pub fn head_moved<'a>(&'a self, current: &'a RunFingerprint) -> Option<(&'a str, &'a str)> {
+        match (self.head.as_deref(), current.head.as_deref()) {
+            (Some(saved), Some(now)) if saved != now => Some((saved, now)),
+            _ => None,
+        }
+    }
+
+    /// Describe how `other` differs from this fingerprint; empty if they match.
+    pub fn differences(&self, other: &RunFingerprint) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.prd_hash != other.prd_hash {
+            differences.push("the PRD has changed".to_string());
+        }
+        if self.branch != other.branch {
+            differences.push(format!(
+                "the branch changed from {} to {}",
+                self.branch.as_deref().unwrap_or("(none)"),
+                other.branch.as_deref().unwrap_or("(none)")
+            ));
+        }
+        differences
+    }
+}
+
+/// Hash each story of a PRD by ID.
+///
+/// `passes` is left out: it records progress, not what the story asks for.
+fn story_hashes(prd: &[u8]) -> BTreeMap<String, String> {
+    let Ok(serde_json::Value::Object(prd)) = serde_json::from_slice(prd) else {
+        return BTreeMap::new();
+    };
+    let Some(serde_json::Value::Array(stories)) = prd.get("userStories") else {
+        return BTreeMap::new();
+    };
+    stories
+        .iter()
+        .filter_map(|story| {
+            let mut story = story.as_object()?.clone();
+            let id = story.get("id")?.as_str()?.to_string();
+            story.remove("passes");
+            let json = serde_json::to_vec(&story).ok()?;
+            Some((id, hex::encode(Sha256::digest(&json))))
+        })
+        .collect()
+}
+
+/// Name of the branch checked out in `working_dir`, if any.
+pub(crate) fn current_branch(working_dir: &Path) -> Option<String> {
+    // A detached HEAD has no branch to match against
+    git_output(working_dir, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .filter(|branch| branch != "HEAD")
+}
+
+/// Trimmed output of a git command, if it succeeds with any output.
+fn git_output(working_dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(working_dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if stdout.is_empty() {
+        None
+    } else {
+        Some(stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_capture_hashes_prd_contents() {
+        let temp_dir = TempDir::new().unwrap();
+        let prd_path = temp_dir.path().join("prd.json");
+        fs::write(&prd_path, r#"{"userStories": []}"#).unwrap();
+
+        let first = RunFingerprint::capture(&prd_path, temp_dir.path()).unwrap();
+        let second = RunFingerprint::capture(&prd_path, temp_dir.path()).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.prd_hash.len(), 64);
+
+        fs::write(&prd_path, r#"{"userStories": [{"id": "US-001"}]}"#).unwrap();
+        let changed = RunFingerprint::capture(&prd_path, temp_dir.path()).unwrap();
+        assert_ne!(first.prd_hash, changed.prd_hash);
+    }
+
+    #[test]
+    fn test_capture_without_prd() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(
+            RunFingerprint::capture(&temp_dir.path().join("prd.json"), temp_dir.path()).is_none()
+        );
+    }
+
+    #[test]
+    fn test_prd_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let prd_path = temp_dir.path().join("prd.json");
+        fs::write(
+            &prd_path,
+            r#"{"userStories": [
+                {"id": "US-001", "title": "One", "passes": false},
+                {"id": "US-002", "title": "Two", "passes": false},
+                {"id": "US-003", "title": "Three", "passes": false}
+            ]}"#,
+        )
+        .unwrap();
+        let saved = RunFingerprint::capture(&prd_path, temp_dir.path()).unwrap();
+
+        // Progress alone does not modify a story
+        fs::write(
+            &prd_path,
+            r#"{"userStories": [
+                {"id": "US-001", "title": "One", "passes": true},
+                {"id": "US-002", "title": "Two, revised", "passes": false},
+                {"id": "US-004", "title": "Four", "passes": false}
+            ]}"#,
+        )
+        .unwrap();
+        let current = RunFingerprint::capture(&prd_path, temp_dir.path()).unwrap();
+
+        assert!(saved.prd_changed(&current));
+        let changes = saved.prd_changes(&current);
+        assert_eq!(changes.added, vec!["US-004".to_string()]);
+        assert_eq!(changes.removed, vec!["US-003".to_string()]);
+        assert_eq!(changes.modified, vec!["US-002".to_string()]);
+        assert_eq!(
+            changes.describe(),
+            vec!["Added: US-004", "Removed: US-003", "Modified: US-002"]
+        );
+    }
+
+    #[test]
+    fn test_head_moved() {
+        let mut saved = RunFingerprint::new("abc", None);
+        let mut current = saved.clone();
+        assert!(saved.head_moved(&current).is_none());
+
+        saved.head = Some("1111".to_string());
+        current.head = Some("2222".to_string());
+        assert_eq!(saved.head_moved(&current), Some(("1111", "2222")));
+    }
+
+    #[test]
+    fn test_differences() {
+        let saved = RunFingerprint::new("abc", Some("feature/a".to_string()));
+        assert!(saved.differences(&saved.clone()).is_empty());
+
+        let other = RunFingerprint::new("def", Some("feature/b".to_string()));
+        assert_eq!(
+            saved.differences(&other),
+            vec![
+                "the PRD has changed".to_string(),
+                "the branch changed from feature/a to feature/b".to_string(),
+            ]
+        );
+    }
+}
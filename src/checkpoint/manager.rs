@@ -2,13 +2,19 @@
 //!
 //! This module provides the `CheckpointManager` which handles all checkpoint
 //! file operations with atomic writes to prevent corruption.
+//!
+//! Runs on a git branch keep their checkpoint in a namespace named after the
+//! branch (`.ralph/checkpoints/<namespace>.json`), so runs on different
+//! branches of the same checkout never resume each other's state. Outside a
+//! repository the checkpoint lives in `.ralph/checkpoint.json`.
 
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use thiserror::Error;
 
+use super::fingerprint::current_branch;
 use super::Checkpoint;
 
 /// Default name for the Ralph state directory.
@@ -17,6 +23,39 @@ const RALPH_DIR_NAME: &str = ".ralph";
 /// Default name for the checkpoint file.
 const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
 
+/// Directory under `.ralph` holding namespaced checkpoints.
+const NAMESPACES_DIR_NAME: &str = "checkpoints";
+
+/// Name under which the checkpoint in `.ralph/checkpoint.json` is listed.
+pub const DEFAULT_CHECKPOINT_NAME: &str = "default";
+
+/// Namespace for the checkpoints of runs on `branch` using the PRD at
+/// `prd_path`.
+///
+/// The PRD's file stem is appended unless it is the usual `prd`, so several
+/// PRDs run on one branch keep separate checkpoints (`main@billing`).
+/// Characters other than letters, digits, `.`, `_` and `-` become `-`.
+pub fn checkpoint_namespace(branch: &str, prd_path: Option<&Path>) -> String {
+    let prd_stem = prd_path
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy())
+        .filter(|stem| stem != "prd");
+    let namespace = match prd_stem {
+        Some(stem) => format!("{}@{}", branch, stem),
+        None => branch.to_string(),
+    };
+    namespace
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
 /// Errors that can occur during checkpoint operations.
 #[derive(Error, Debug)]
 pub enum CheckpointError {
@@ -40,6 +79,17 @@ pub enum CheckpointError {
 /// Result type for checkpoint operations.
 pub type CheckpointResult<T> = Result<T, CheckpointError>;
 
+/// A checkpoint file found on disk.
+#[derive(Debug)]
+pub struct SavedCheckpoint {
+    /// Name used to select the checkpoint (e.g. with `ralph checkpoint show`)
+    pub name: String,
+    /// Path of the checkpoint file
+    pub path: PathBuf,
+    /// The checkpoint, or why it could not be loaded
+    pub checkpoint: Result<Checkpoint, String>,
+}
+
 /// Manager for checkpoint file operations.
 ///
 /// The `CheckpointManager` coordinates saving and loading of execution state
@@ -48,6 +98,10 @@ pub type CheckpointResult<T> = Result<T, CheckpointError>;
 pub struct CheckpointManager {
     /// Path to the checkpoint file.
     checkpoint_path: PathBuf,
+    /// Path to the `.ralph` directory.
+    ralph_dir: PathBuf,
+    /// Name of the checkpoint this manager saves and loads.
+    name: String,
 }
 
 impl CheckpointManager {
@@ -71,7 +125,49 @@ impl CheckpointManager {
 
         let checkpoint_path = ralph_dir.join(CHECKPOINT_FILE_NAME);
 
-        Ok(Self { checkpoint_path })
+        Ok(Self {
+            checkpoint_path,
+            ralph_dir,
+            name: DEFAULT_CHECKPOINT_NAME.to_string(),
+        })
+    }
+
+    /// Create a `CheckpointManager` for a run in `base_dir` using the PRD at
+    /// `prd_path`.
+    ///
+    /// The checkpoint is namespaced by the checked-out branch (see
+    /// [`checkpoint_namespace`]); outside a repository, or on a detached
+    /// HEAD, the default checkpoint is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directories cannot be created.
+    pub fn for_run(base_dir: impl Into<PathBuf>, prd_path: &Path) -> CheckpointResult<Self> {
+        let base = base_dir.into();
+        let branch = current_branch(&base);
+        let manager = Self::new(base)?;
+        match branch {
+            Some(branch) => manager.with_namespace(&checkpoint_namespace(&branch, Some(prd_path))),
+            None => Ok(manager),
+        }
+    }
+
+    /// Use the checkpoint in `namespace` instead of the default one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the namespace directory cannot be created.
+    pub fn with_namespace(mut self, namespace: &str) -> CheckpointResult<Self> {
+        let namespaces_dir = self.ralph_dir.join(NAMESPACES_DIR_NAME);
+        fs::create_dir_all(&namespaces_dir)?;
+        self.checkpoint_path = namespaces_dir.join(format!("{}.json", namespace));
+        self.name = namespace.to_string();
+        Ok(self)
+    }
+
+    /// Name of the checkpoint this manager saves and loads.
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     /// Save a checkpoint atomically.
@@ -182,6 +278,68 @@ impl CheckpointManager {
     pub fn checkpoint_path(&self) -> &PathBuf {
         &self.checkpoint_path
     }
+
+    /// List the checkpoints saved on disk, in every namespace.
+    ///
+    /// Checkpoints that cannot be read are listed with their error, so they
+    /// can still be deleted.
+    pub fn list(&self) -> Vec<SavedCheckpoint> {
+        let mut paths = Vec::new();
+        let default_path = self.ralph_dir.join(CHECKPOINT_FILE_NAME);
+        if default_path.exists() {
+            paths.push((DEFAULT_CHECKPOINT_NAME.to_string(), default_path));
+        }
+        let mut namespaced: Vec<(String, PathBuf)> =
+            fs::read_dir(self.ralph_dir.join(NAMESPACES_DIR_NAME))
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if path.extension()? != "json" {
+                        return None;
+                    }
+                    let name = path.file_stem()?.to_string_lossy().to_string();
+                    Some((name, path))
+                })
+                .collect();
+        namespaced.sort();
+        paths.extend(namespaced);
+
+        paths
+            .into_iter()
+            .map(|(name, path)| {
+                let checkpoint = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|content| {
+                        serde_json::from_str::<Checkpoint>(&content).map_err(|e| e.to_string())
+                    });
+                SavedCheckpoint {
+                    name,
+                    path,
+                    checkpoint,
+                }
+            })
+            .collect()
+    }
+
+    /// Find a saved checkpoint by name.
+    pub fn find(&self, name: &str) -> Option<SavedCheckpoint> {
+        self.list().into_iter().find(|saved| saved.name == name)
+    }
+
+    /// Delete a saved checkpoint by name.
+    ///
+    /// Returns `false` if no checkpoint has that name.
+    pub fn delete(&self, name: &str) -> CheckpointResult<bool> {
+        let Some(saved) = self.find(name) else {
+            return Ok(false);
+        };
+        match fs::remove_file(&saved.path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(CheckpointError::Io(e)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -391,6 +549,87 @@ mod tests {
         assert_eq!(loaded.pause_reason, PauseReason::Timeout);
     }
 
+    #[test]
+    fn test_list_find_and_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = CheckpointManager::new(temp_dir.path()).unwrap();
+        assert!(manager.list().is_empty());
+
+        manager.save(&create_test_checkpoint()).unwrap();
+        let saved = manager.list();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].name, DEFAULT_CHECKPOINT_NAME);
+        assert_eq!(
+            saved[0].checkpoint.as_ref().unwrap().pause_reason,
+            PauseReason::RateLimited
+        );
+        assert!(manager.find("feature-b").is_none());
+
+        assert!(!manager.delete("feature-b").unwrap());
+        assert!(manager.delete(DEFAULT_CHECKPOINT_NAME).unwrap());
+        assert!(!manager.exists());
+    }
+
+    #[test]
+    fn test_checkpoint_namespace() {
+        assert_eq!(checkpoint_namespace("main", None), "main");
+        assert_eq!(
+            checkpoint_namespace("feature/login", Some(Path::new("/repo/prd.json"))),
+            "feature-login"
+        );
+        assert_eq!(
+            checkpoint_namespace("main", Some(Path::new("ralph/billing.json"))),
+            "main@billing"
+        );
+    }
+
+    #[test]
+    fn test_namespaces_keep_checkpoints_apart() {
+        let temp_dir = TempDir::new().unwrap();
+        let feature_a = CheckpointManager::new(temp_dir.path())
+            .unwrap()
+            .with_namespace("feature-a")
+            .unwrap();
+        let feature_b = CheckpointManager::new(temp_dir.path())
+            .unwrap()
+            .with_namespace("feature-b")
+            .unwrap();
+        assert_eq!(feature_a.name(), "feature-a");
+
+        feature_a.save(&create_test_checkpoint()).unwrap();
+        assert!(feature_a.load().unwrap().is_some());
+        assert!(feature_b.load().unwrap().is_none());
+
+        // Every manager lists every namespace
+        let names: Vec<String> = feature_b.list().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["feature-a"]);
+        assert!(feature_b.delete("feature-a").unwrap());
+        assert!(!feature_a.exists());
+    }
+
+    #[test]
+    fn test_for_run_outside_repository_uses_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager =
+            CheckpointManager::for_run(temp_dir.path(), &temp_dir.path().join("prd.json")).unwrap();
+        assert_eq!(manager.name(), DEFAULT_CHECKPOINT_NAME);
+        assert!(manager
+            .checkpoint_path()
+            .ends_with(".ralph/checkpoint.json"));
+    }
+
+    #[test]
+    fn test_list_includes_unreadable_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = CheckpointManager::new(temp_dir.path()).unwrap();
+        fs::write(manager.checkpoint_path(), "{ invalid json }").unwrap();
+
+        let saved = manager.list();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].checkpoint.is_err());
+        assert!(manager.delete(DEFAULT_CHECKPOINT_NAME).unwrap());
+    }
+
     #[test]
     fn test_load_invalid_json_returns_error() {
         let temp_dir = TempDir::new().unwrap();
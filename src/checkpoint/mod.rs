@@ -3,13 +3,22 @@
 //! This module provides types and functionality for saving and loading
 //! execution state, enabling resumption after interruptions.
 
+pub mod fingerprint;
 pub mod manager;
 
-pub use manager::{CheckpointError, CheckpointManager, CheckpointResult};
+pub use fingerprint::{PrdChanges, RunFingerprint};
+pub use manager::{
+    CheckpointError, CheckpointManager, CheckpointResult, SavedCheckpoint, DEFAULT_CHECKPOINT_NAME,
+};
+
+use std::collections::BTreeMap;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::metrics::RunMetrics;
+use crate::parallel::retry::RetryBudget;
+
 /// Reason why execution was paused and a checkpoint was created.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -26,12 +35,25 @@ pub enum PauseReason {
     Error(String),
     /// Checkpoint saved at iteration boundary (for recovery if interrupted)
     IterationBoundary,
+    /// Checkpoint saved periodically during a run (for recovery if the
+    /// process is killed)
+    Autosave,
+    /// The stop file was created, halting the run
+    StopRequested {
+        /// Reason written to the stop file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
     /// Circuit breaker was triggered due to consecutive failures
     CircuitBreakerTriggered {
         /// Number of consecutive failures that triggered the circuit breaker
         consecutive_failures: u32,
         /// Threshold at which the circuit breaker triggers
         threshold: u32,
+        /// Failure category that reached its threshold (e.g. "fatal"), when
+        /// failures are counted per category
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        category: Option<String>,
     },
 }
 
@@ -44,9 +66,15 @@ impl std::fmt::Display for PauseReason {
             PauseReason::Timeout => write!(f, "Timeout"),
             PauseReason::Error(msg) => write!(f, "Error: {}", msg),
             PauseReason::IterationBoundary => write!(f, "Iteration boundary"),
+            PauseReason::Autosave => write!(f, "Autosave"),
+            PauseReason::StopRequested { reason: None } => write!(f, "Stop requested"),
+            PauseReason::StopRequested {
+                reason: Some(reason),
+            } => write!(f, "Stop requested: {}", reason),
             PauseReason::CircuitBreakerTriggered {
                 consecutive_failures,
                 threshold: _,
+                category: None,
             } => {
                 write!(
                     f,
@@ -54,6 +82,17 @@ impl std::fmt::Display for PauseReason {
                     consecutive_failures
                 )
             }
+            PauseReason::CircuitBreakerTriggered {
+                consecutive_failures,
+                threshold: _,
+                category: Some(category),
+            } => {
+                write!(
+                    f,
+                    "Circuit breaker triggered after {} {} failures",
+                    consecutive_failures, category
+                )
+            }
         }
     }
 }
@@ -69,6 +108,82 @@ pub struct StoryCheckpoint {
     pub max_iterations: u32,
 }
 
+/// Execution state of a parallel run at the time it was checkpointed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParallelCheckpoint {
+    /// Stories that completed; a resumed run does not run them again
+    pub completed: Vec<String>,
+    /// Stories that failed, mapped to their last error; they are retried
+    pub failed: BTreeMap<String, String>,
+    /// Stories cancelled by the user; they stay skipped
+    pub skipped: Vec<String>,
+    /// Stories that were queued or in flight; they are run again
+    pub pending: Vec<String>,
+    /// Iteration each in-flight story had reached
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub iterations: BTreeMap<String, u32>,
+    /// Retries each in-flight story had made per error category; a resumed
+    /// story only gets the retries it has left
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub retry_budgets: BTreeMap<String, RetryBudget>,
+}
+
+impl ParallelCheckpoint {
+    /// Replace the state of the stories `selected` accepts with their state in
+    /// `update`, keeping the rest as they were.
+    ///
+    /// Used when only some stories of a checkpointed run were resumed.
+    pub fn merge_selected(
+        &self,
+        update: &ParallelCheckpoint,
+        selected: impl Fn(&str) -> bool,
+    ) -> ParallelCheckpoint {
+        let merge = |kept: &[String], updated: &[String]| {
+            let mut ids: Vec<String> = kept
+                .iter()
+                .filter(|id| !selected(id))
+                .chain(updated.iter().filter(|id| selected(id)))
+                .cloned()
+                .collect();
+            ids.sort();
+            ids.dedup();
+            ids
+        };
+        let merge_map = |kept: &BTreeMap<String, String>, updated: &BTreeMap<String, String>| {
+            kept.iter()
+                .filter(|(id, _)| !selected(id))
+                .chain(updated.iter().filter(|(id, _)| selected(id)))
+                .map(|(id, error)| (id.clone(), error.clone()))
+                .collect()
+        };
+        ParallelCheckpoint {
+            completed: merge(&self.completed, &update.completed),
+            failed: merge_map(&self.failed, &update.failed),
+            skipped: merge(&self.skipped, &update.skipped),
+            pending: merge(&self.pending, &update.pending),
+            iterations: self
+                .iterations
+                .iter()
+                .filter(|(id, _)| !selected(id))
+                .chain(update.iterations.iter().filter(|(id, _)| selected(id)))
+                .map(|(id, iteration)| (id.clone(), *iteration))
+                .collect(),
+            retry_budgets: self
+                .retry_budgets
+                .iter()
+                .filter(|(id, _)| !selected(id))
+                .chain(update.retry_budgets.iter().filter(|(id, _)| selected(id)))
+                .map(|(id, budget)| (id.clone(), budget.clone()))
+                .collect(),
+        }
+    }
+
+    /// Whether any story still has to run (it failed or was interrupted).
+    pub fn has_unfinished(&self) -> bool {
+        !self.failed.is_empty() || !self.pending.is_empty()
+    }
+}
+
 /// Main checkpoint structure containing full execution state.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Checkpoint {
@@ -82,6 +197,18 @@ pub struct Checkpoint {
     pub pause_reason: PauseReason,
     /// List of files with uncommitted changes
     pub uncommitted_files: Vec<String>,
+    /// Execution state of a parallel run (absent for sequential runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_state: Option<ParallelCheckpoint>,
+    /// PRD and branch the run was started from (absent in older checkpoints)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<RunFingerprint>,
+    /// ID of the checkpointed run, continued when it is resumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// Run metrics collected up to the checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<RunMetrics>,
 }
 
 impl Checkpoint {
@@ -100,8 +227,48 @@ impl Checkpoint {
             current_story,
             pause_reason,
             uncommitted_files,
+            parallel_state: None,
+            fingerprint: None,
+            run_id: None,
+            metrics: None,
         }
     }
+
+    /// Attach the execution state of a parallel run.
+    pub fn with_parallel_state(mut self, parallel_state: ParallelCheckpoint) -> Self {
+        self.parallel_state = Some(parallel_state);
+        self
+    }
+
+    /// Record the PRD and branch the run was started from.
+    pub fn with_fingerprint(mut self, fingerprint: Option<RunFingerprint>) -> Self {
+        self.fingerprint = fingerprint;
+        self
+    }
+
+    /// Record the run's ID and the metrics collected so far.
+    pub fn with_run_metrics(mut self, metrics: RunMetrics) -> Self {
+        self.run_id = Some(metrics.run_id.clone());
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Whether the checkpoint was written for the run described by `current`.
+    ///
+    /// Checkpoints that predate fingerprints never match.
+    pub fn matches(&self, current: &RunFingerprint) -> bool {
+        self.fingerprint
+            .as_ref()
+            .is_some_and(|saved| saved.differences(current).is_empty())
+    }
+
+    /// Number of stories the checkpointed run completed, if it was a
+    /// parallel run (sequential checkpoints only record the current story).
+    pub fn stories_completed(&self) -> Option<usize> {
+        self.parallel_state
+            .as_ref()
+            .map(|state| state.completed.len())
+    }
 }
 
 impl StoryCheckpoint {
@@ -128,9 +295,11 @@ mod tests {
             PauseReason::Timeout,
             PauseReason::Error("Connection failed".to_string()),
             PauseReason::IterationBoundary,
+            PauseReason::Autosave,
             PauseReason::CircuitBreakerTriggered {
                 consecutive_failures: 5,
                 threshold: 3,
+                category: None,
             },
         ];
 
@@ -183,6 +352,105 @@ mod tests {
         assert_eq!(checkpoint.pause_reason, deserialized.pause_reason);
     }
 
+    #[test]
+    fn test_checkpoint_parallel_state_roundtrip() {
+        let state = ParallelCheckpoint {
+            completed: vec!["US-001".to_string()],
+            failed: BTreeMap::from([("US-002".to_string(), "tests failed".to_string())]),
+            skipped: vec![],
+            pending: vec!["US-003".to_string()],
+            iterations: BTreeMap::from([("US-003".to_string(), 2)]),
+            retry_budgets: BTreeMap::from([("US-003".to_string(), RetryBudget::default())]),
+        };
+        let checkpoint =
+            Checkpoint::new(None, PauseReason::UserRequested, vec![]).with_parallel_state(state);
+
+        let json = serde_json::to_string(&checkpoint).expect("Failed to serialize Checkpoint");
+        let deserialized: Checkpoint =
+            serde_json::from_str(&json).expect("Failed to deserialize Checkpoint");
+        assert_eq!(checkpoint, deserialized);
+
+        // Checkpoints written before parallel state existed still load
+        let json = serde_json::to_string(&Checkpoint::new(None, PauseReason::Timeout, vec![]))
+            .expect("Failed to serialize Checkpoint");
+        assert!(!json.contains("parallel_state"));
+        let deserialized: Checkpoint =
+            serde_json::from_str(&json).expect("Failed to deserialize Checkpoint");
+        assert!(deserialized.parallel_state.is_none());
+    }
+
+    #[test]
+    fn test_parallel_checkpoint_merge_selected() {
+        let saved = ParallelCheckpoint {
+            completed: vec!["US-001".to_string()],
+            failed: BTreeMap::from([
+                ("US-002".to_string(), "tests failed".to_string()),
+                ("US-004".to_string(), "lint failed".to_string()),
+            ]),
+            skipped: vec![],
+            pending: vec!["US-003".to_string()],
+            iterations: BTreeMap::new(),
+            retry_budgets: BTreeMap::new(),
+        };
+        // US-004 was resumed on its own and passed
+        let update = ParallelCheckpoint {
+            completed: vec!["US-004".to_string()],
+            ..Default::default()
+        };
+
+        let merged = saved.merge_selected(&update, |id| id == "US-004");
+        assert_eq!(merged.completed, vec!["US-001", "US-004"]);
+        assert_eq!(
+            merged.failed.keys().collect::<Vec<_>>(),
+            vec!["US-002"],
+            "other stories keep their state"
+        );
+        assert_eq!(merged.pending, vec!["US-003"]);
+        assert!(merged.has_unfinished());
+
+        let update = ParallelCheckpoint {
+            completed: vec!["US-002".to_string(), "US-003".to_string()],
+            ..Default::default()
+        };
+        let merged = merged.merge_selected(&update, |id| id == "US-002" || id == "US-003");
+        assert!(!merged.has_unfinished());
+    }
+
+    #[test]
+    fn test_checkpoint_matches_fingerprint() {
+        let current = RunFingerprint::new("abc", Some("main".to_string()));
+        let checkpoint = Checkpoint::new(None, PauseReason::UserRequested, vec![]);
+        assert!(!checkpoint.matches(&current));
+
+        let checkpoint = checkpoint.with_fingerprint(Some(current.clone()));
+        assert!(checkpoint.matches(&current));
+        assert!(!checkpoint.matches(&RunFingerprint::new("abc", Some("other".to_string()))));
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let deserialized: Checkpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.fingerprint, Some(current));
+    }
+
+    #[test]
+    fn test_checkpoint_run_metrics_roundtrip() {
+        let collector = crate::metrics::RunMetricsCollector::new("run-42", 1);
+        collector.start_step("US-001");
+        let checkpoint = Checkpoint::new(None, PauseReason::UserRequested, vec![])
+            .with_run_metrics(collector.finish());
+        assert_eq!(checkpoint.run_id.as_deref(), Some("run-42"));
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let deserialized: Checkpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, checkpoint);
+
+        // Checkpoints written before run metrics existed still load
+        let json =
+            serde_json::to_string(&Checkpoint::new(None, PauseReason::Timeout, vec![])).unwrap();
+        assert!(!json.contains("run_id"));
+        let deserialized: Checkpoint = serde_json::from_str(&json).unwrap();
+        assert!(deserialized.metrics.is_none());
+    }
+
     #[test]
     fn test_checkpoint_version() {
         let checkpoint = Checkpoint::new(None, PauseReason::Timeout, vec![]);
@@ -210,6 +478,7 @@ mod tests {
         let reason = PauseReason::CircuitBreakerTriggered {
             consecutive_failures: 5,
             threshold: 3,
+            category: None,
         };
 
         let json = serde_json::to_string(&reason).expect("Failed to serialize");
@@ -228,6 +497,7 @@ mod tests {
         let reason = PauseReason::CircuitBreakerTriggered {
             consecutive_failures: 5,
             threshold: 3,
+            category: None,
         };
 
         let display = format!("{}", reason);
@@ -237,6 +507,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_circuit_breaker_triggered_category() {
+        let reason = PauseReason::CircuitBreakerTriggered {
+            consecutive_failures: 3,
+            threshold: 3,
+            category: Some("fatal".to_string()),
+        };
+        assert_eq!(
+            format!("{}", reason),
+            "Circuit breaker triggered after 3 fatal failures"
+        );
+
+        let json = serde_json::to_string(&reason).unwrap();
+        assert!(json.contains(r#""category":"fatal""#));
+        let deserialized: PauseReason = serde_json::from_str(&json).unwrap();
+        assert_eq!(reason, deserialized);
+
+        // Checkpoints written before categories were recorded still load
+        let json = r#"{"circuit_breaker_triggered":{"consecutive_failures":5,"threshold":5}}"#;
+        let deserialized: PauseReason = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            deserialized,
+            PauseReason::CircuitBreakerTriggered { category: None, .. }
+        ));
+    }
+
     #[test]
     fn test_pause_reason_display() {
         assert_eq!(
@@ -2,9 +2,12 @@
 //!
 //! This module provides types for categorizing errors into actionable recovery strategies.
 //! Errors are classified by category (transient, usage limit, fatal, timeout) and each
-//! category has specific reasons and recovery hints.
+//! category has specific reasons and recovery hints. Network, rate-limit, authentication
+//! and disk-full errors also have a subcategory, so they can be retried their own way.
 
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
 /// The primary category of an error, determining the general recovery strategy.
@@ -65,6 +68,8 @@ pub enum FatalReason {
     InternalError,
     /// Configuration error preventing operation.
     ConfigurationError,
+    /// No space left on the device being written to.
+    DiskFull,
 }
 
 /// Reasons for timeout errors.
@@ -80,6 +85,87 @@ pub enum TimeoutReason {
     IdleTimeout,
 }
 
+impl ErrorCategory {
+    /// The subcategory of the error, if it has one.
+    pub fn subcategory(&self) -> Option<ErrorSubcategory> {
+        match self {
+            Self::Transient(TransientReason::NetworkError | TransientReason::ConnectionReset) => {
+                Some(ErrorSubcategory::Network)
+            }
+            Self::UsageLimit(
+                UsageLimitReason::RateLimited | UsageLimitReason::ConcurrencyLimit,
+            ) => Some(ErrorSubcategory::RateLimit),
+            Self::Fatal(FatalReason::AuthenticationFailed) => Some(ErrorSubcategory::Auth),
+            Self::Fatal(FatalReason::DiskFull) => Some(ErrorSubcategory::DiskFull),
+            _ => None,
+        }
+    }
+}
+
+/// Finer-grained kinds of errors that are retried, or not, in their own way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorSubcategory {
+    /// The network or a connection failed; usually gone within seconds.
+    Network,
+    /// A rate or concurrency limit was hit; clears after minutes.
+    RateLimit,
+    /// Credentials were rejected; no retry helps until they are fixed.
+    Auth,
+    /// The disk filled up; no retry helps until space is freed.
+    DiskFull,
+}
+
+impl ErrorSubcategory {
+    /// Every subcategory.
+    pub const ALL: [Self; 4] = [Self::Network, Self::RateLimit, Self::Auth, Self::DiskFull];
+
+    /// Label used in retry budgets and messages.
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::RateLimit => "rate_limit",
+            Self::Auth => "auth",
+            Self::DiskFull => "disk_full",
+        }
+    }
+
+    /// What the user has to do before the error can go away, for errors
+    /// that retrying does not fix.
+    pub fn remedy(&self) -> Option<&'static str> {
+        match self {
+            Self::Network | Self::RateLimit => None,
+            Self::Auth => Some(
+                "Check the agent's credentials: log in again or set a valid API key, then resume the run",
+            ),
+            Self::DiskFull => Some(
+                "Free up disk space (e.g. `cargo clean` or remove stale worktrees), then resume the run",
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ErrorSubcategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
+impl FromStr for ErrorSubcategory {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|subcategory| subcategory.as_label() == value.trim())
+            .ok_or_else(|| {
+                format!(
+                    "unknown error subcategory '{}' (expected one of: network, rate_limit, auth, disk_full)",
+                    value.trim()
+                )
+            })
+    }
+}
+
 /// Hints for how to recover from an error.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RecoveryHint {
@@ -242,8 +328,41 @@ mod tests {
             FatalReason::UnsupportedOperation,
             FatalReason::InternalError,
             FatalReason::ConfigurationError,
+            FatalReason::DiskFull,
         ];
-        assert_eq!(reasons.len(), 7);
+        assert_eq!(reasons.len(), 8);
+    }
+
+    #[test]
+    fn test_error_subcategories() {
+        assert_eq!(
+            ErrorCategory::Transient(TransientReason::ConnectionReset).subcategory(),
+            Some(ErrorSubcategory::Network)
+        );
+        assert_eq!(
+            ErrorCategory::UsageLimit(UsageLimitReason::RateLimited).subcategory(),
+            Some(ErrorSubcategory::RateLimit)
+        );
+        assert_eq!(
+            ErrorCategory::Fatal(FatalReason::AuthenticationFailed).subcategory(),
+            Some(ErrorSubcategory::Auth)
+        );
+        assert_eq!(
+            ErrorCategory::Fatal(FatalReason::DiskFull).subcategory(),
+            Some(ErrorSubcategory::DiskFull)
+        );
+        assert_eq!(
+            ErrorCategory::Transient(TransientReason::ServerError).subcategory(),
+            None
+        );
+
+        // Only errors retrying cannot fix come with a remedy
+        assert!(ErrorSubcategory::Network.remedy().is_none());
+        assert!(ErrorSubcategory::Auth.remedy().is_some());
+        for subcategory in ErrorSubcategory::ALL {
+            assert_eq!(subcategory.as_label().parse(), Ok(subcategory));
+        }
+        assert!("dns".parse::<ErrorSubcategory>().is_err());
     }
 
     #[test]
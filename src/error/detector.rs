@@ -235,6 +235,13 @@ impl ErrorDetector {
                 RecoveryHint::WaitForUser,
                 "HTTP 401 Unauthorized",
             )?,
+            // Disk space patterns
+            ErrorPattern::new(
+                r"(?i)no\s*space\s*left\s*on\s*device|\bENOSPC\b|disk\s*(is\s*)?full",
+                ErrorCategory::Fatal(FatalReason::DiskFull),
+                RecoveryHint::WaitForUser,
+                "Disk full",
+            )?,
             // Network/transient error patterns
             ErrorPattern::new(
                 r"(?i)connection\s*(refused|reset|timed?\s*out)",
@@ -1039,6 +1046,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detect_disk_full() {
+        let detector = test_detector();
+
+        for text in [
+            "write failed: No space left on device (os error 28)",
+            "ENOSPC: no space left",
+            "Disk is full",
+        ] {
+            let result = detector.classify_error(text);
+            assert!(result.is_some(), "Should detect disk full in: '{}'", text);
+            assert_eq!(
+                result.unwrap().category,
+                ErrorCategory::Fatal(FatalReason::DiskFull),
+                "Wrong category for: '{}'",
+                text
+            );
+        }
+    }
+
     // ==================== Exit Code Classification Tests ====================
 
     #[test]
@@ -0,0 +1,285 @@
+//! Bounded capture of agent and gate output.
+//!
+//! A story's agent and quality gate output is kept in memory for error
+//! reporting and token accounting, but a single test log can run to tens of
+//! thousands of lines. [`OutputCapture`] keeps only the most recent lines
+//! within configurable caps; once output outgrows them, the complete output is
+//! written to a log file (normally under the run's evidence artifacts) instead.
+
+use std::collections::VecDeque;
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Environment variable capping the lines of output kept in memory per stream.
+pub const MAX_LINES_ENV_VAR: &str = "RALPH_OUTPUT_MAX_LINES";
+
+/// Environment variable capping the bytes of output kept in memory per stream.
+pub const MAX_BYTES_ENV_VAR: &str = "RALPH_OUTPUT_MAX_BYTES";
+
+/// Default number of lines kept in memory per stream.
+pub const DEFAULT_MAX_LINES: usize = 2_000;
+
+/// Default number of bytes kept in memory per stream (256 KiB).
+pub const DEFAULT_MAX_BYTES: usize = 256 * 1024;
+
+/// Caps on output kept in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputCaptureConfig {
+    /// Most recent lines kept in memory (0 = unlimited).
+    pub max_lines: usize,
+    /// Most recent bytes kept in memory (0 = unlimited).
+    pub max_bytes: usize,
+}
+
+impl OutputCaptureConfig {
+    /// Create a config with the given caps.
+    pub fn new(max_lines: usize, max_bytes: usize) -> Self {
+        Self {
+            max_lines,
+            max_bytes,
+        }
+    }
+
+    /// A config that keeps all output in memory.
+    pub fn unbounded() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Build config from environment variables.
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            max_lines: read(MAX_LINES_ENV_VAR, DEFAULT_MAX_LINES),
+            max_bytes: read(MAX_BYTES_ENV_VAR, DEFAULT_MAX_BYTES),
+        }
+    }
+
+    fn exceeded(&self, lines: usize, bytes: usize) -> bool {
+        (self.max_lines > 0 && lines > self.max_lines)
+            || (self.max_bytes > 0 && bytes > self.max_bytes)
+    }
+}
+
+impl Default for OutputCaptureConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Ring buffer of output lines that spills the full output to disk.
+///
+/// Lines beyond the caps are dropped from the front of the buffer. If a spill
+/// path is set, the whole output (including dropped lines) is written there
+/// from the first overflow on; write failures only lose the spill file.
+#[derive(Debug)]
+pub struct OutputCapture {
+    config: OutputCaptureConfig,
+    lines: VecDeque<String>,
+    /// Bytes held in `lines`, counting one newline per line
+    bytes: usize,
+    /// Lines dropped from the front of the buffer
+    dropped: usize,
+    spill_path: Option<PathBuf>,
+    spill: Option<BufWriter<File>>,
+    /// Whether the spill file was created successfully
+    spilled: bool,
+}
+
+impl OutputCapture {
+    /// Create an empty capture with the given caps.
+    pub fn new(config: OutputCaptureConfig) -> Self {
+        Self {
+            config,
+            lines: VecDeque::new(),
+            bytes: 0,
+            dropped: 0,
+            spill_path: None,
+            spill: None,
+            spilled: false,
+        }
+    }
+
+    /// Write the full output to `path` once it outgrows the caps.
+    pub fn with_spill_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.spill_path = Some(path.into());
+        self
+    }
+
+    /// Capture `text`, split into lines.
+    pub fn from_text(config: OutputCaptureConfig, spill_path: Option<PathBuf>, text: &str) -> Self {
+        let mut capture = Self::new(config);
+        capture.spill_path = spill_path;
+        for line in text.lines() {
+            capture.push_line(line);
+        }
+        capture.finish();
+        capture
+    }
+
+    /// Append a line of output.
+    pub fn push_line(&mut self, line: &str) {
+        if let Some(ref mut spill) = self.spill {
+            if writeln!(spill, "{}", line).is_err() {
+                self.spill = None;
+            }
+        }
+        self.bytes += line.len() + 1;
+        self.lines.push_back(line.to_string());
+
+        if !self.config.exceeded(self.lines.len(), self.bytes) {
+            return;
+        }
+        if self.dropped == 0 {
+            self.start_spill();
+        }
+        // Always keep the newest line, even if it alone exceeds the byte cap
+        while self.lines.len() > 1 && self.config.exceeded(self.lines.len(), self.bytes) {
+            if let Some(oldest) = self.lines.pop_front() {
+                self.bytes -= oldest.len() + 1;
+                self.dropped += 1;
+            }
+        }
+    }
+
+    /// Flush the spill file, if any.
+    pub fn finish(&mut self) {
+        if let Some(ref mut spill) = self.spill {
+            if spill.flush().is_err() {
+                self.spill = None;
+            }
+        }
+    }
+
+    /// Number of lines dropped from memory.
+    pub fn dropped_lines(&self) -> usize {
+        self.dropped
+    }
+
+    /// Whether any output was dropped from memory.
+    pub fn is_truncated(&self) -> bool {
+        self.dropped > 0
+    }
+
+    /// Path of the file holding the full output, if output was spilled.
+    pub fn spill_path(&self) -> Option<&Path> {
+        self.spill_path.as_deref().filter(|_| self.spilled)
+    }
+
+    /// The retained lines, newline-terminated.
+    pub fn contents(&self) -> String {
+        let mut contents = String::with_capacity(self.bytes);
+        for line in &self.lines {
+            contents.push_str(line);
+            contents.push('\n');
+        }
+        contents
+    }
+
+    /// The retained lines, preceded by a note on dropped output if any.
+    pub fn to_report(&self) -> String {
+        if !self.is_truncated() {
+            return self.contents();
+        }
+        let location = match self.spill_path() {
+            Some(path) => format!("; full output in {}", path.display()),
+            None => String::new(),
+        };
+        format!(
+            "[{} earlier lines omitted{}]\n{}",
+            self.dropped,
+            location,
+            self.contents()
+        )
+    }
+
+    /// Open the spill file and write the output captured so far.
+    fn start_spill(&mut self) {
+        let Some(ref path) = self.spill_path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let Ok(file) = File::create(path) else {
+            return;
+        };
+        let mut spill = BufWriter::new(file);
+        let written = self
+            .lines
+            .iter()
+            .try_for_each(|line| writeln!(spill, "{}", line));
+        if written.is_ok() {
+            self.spill = Some(spill);
+            self.spilled = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_keeps_most_recent_lines() {
+        let mut capture = OutputCapture::new(OutputCaptureConfig::new(3, 0));
+        for i in 1..=5 {
+            capture.push_line(&format!("line {}", i));
+        }
+        assert_eq!(capture.contents(), "line 3\nline 4\nline 5\n");
+        assert_eq!(capture.dropped_lines(), 2);
+        assert!(capture
+            .to_report()
+            .starts_with("[2 earlier lines omitted]\n"));
+    }
+
+    #[test]
+    fn test_capture_respects_byte_cap() {
+        let mut capture = OutputCapture::new(OutputCaptureConfig::new(0, 10));
+        capture.push_line("aaaa");
+        capture.push_line("bbbb");
+        capture.push_line("cccc");
+        assert_eq!(capture.contents(), "bbbb\ncccc\n");
+
+        // A single oversized line is still kept
+        capture.push_line("an oversized line");
+        assert_eq!(capture.contents(), "an oversized line\n");
+    }
+
+    #[test]
+    fn test_unbounded_capture_keeps_everything() {
+        let text = "one\ntwo\nthree";
+        let capture = OutputCapture::from_text(OutputCaptureConfig::unbounded(), None, text);
+        assert_eq!(capture.contents(), "one\ntwo\nthree\n");
+        assert!(!capture.is_truncated());
+        assert_eq!(capture.to_report(), capture.contents());
+    }
+
+    #[test]
+    fn test_capture_spills_full_output_to_disk() {
+        let dir = std::env::temp_dir().join(format!("ralph-capture-{}", std::process::id()));
+        let path = dir.join("US-001").join("agent-stdout.log");
+        let mut capture =
+            OutputCapture::new(OutputCaptureConfig::new(2, 0)).with_spill_path(path.clone());
+        capture.push_line("first");
+        assert!(capture.spill_path().is_none());
+        for line in ["second", "third", "fourth"] {
+            capture.push_line(line);
+        }
+        capture.finish();
+
+        assert_eq!(capture.spill_path(), Some(path.as_path()));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "first\nsecond\nthird\nfourth\n"
+        );
+        assert!(capture.to_report().contains("full output in"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -26,6 +26,9 @@ pub struct LifecycleEvent {
     pub error_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+    /// Epic of the step's story
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epic: Option<String>,
 }
 
 impl LifecycleEvent {
@@ -39,6 +42,7 @@ impl LifecycleEvent {
             status: None,
             error_type: None,
             error_message: None,
+            epic: None,
         }
     }
 }
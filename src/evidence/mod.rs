@@ -1,5 +1,6 @@
 //! Evidence storage module.
 
+pub mod capture;
 pub mod config;
 pub mod export;
 pub mod labels;
@@ -8,6 +9,7 @@ pub mod record;
 pub mod store;
 pub mod writer;
 
+pub use capture::{OutputCapture, OutputCaptureConfig};
 pub use config::EvidenceStoreConfig;
 pub use export::{EvidenceExporter, EvidenceRunExport, RunStatus};
 pub use labels::error_category_label;
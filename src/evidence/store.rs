@@ -13,6 +13,7 @@ const EVIDENCE_DIR_NAME: &str = "evidence";
 const RUNS_DIR_NAME: &str = "runs";
 const MANIFEST_FILE_NAME: &str = "run.json";
 const EVENTS_FILE_NAME: &str = "events.jsonl";
+const ARTIFACTS_DIR_NAME: &str = "artifacts";
 
 /// Errors that can occur during evidence storage operations.
 #[derive(Error, Debug)]
@@ -172,6 +173,13 @@ impl EvidenceStore {
         &self.root_dir
     }
 
+    /// Get the directory for file artifacts (e.g. coverage reports) of a run.
+    ///
+    /// The directory is not created; writers are expected to create it on demand.
+    pub fn artifacts_dir(&self, run_id: &str) -> PathBuf {
+        self.run_dir(run_id).join(ARTIFACTS_DIR_NAME)
+    }
+
     fn run_dir(&self, run_id: &str) -> PathBuf {
         self.root_dir.join(RUNS_DIR_NAME).join(run_id)
     }
@@ -237,6 +245,24 @@ mod tests {
         assert!(run_dir.join(MANIFEST_FILE_NAME).exists());
     }
 
+    #[test]
+    fn test_artifacts_dir_is_scoped_to_run() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let store =
+            EvidenceStore::new(temp_dir.path(), EvidenceStoreConfig::new(30)).expect("store");
+
+        let artifacts_dir = store.artifacts_dir("run-123");
+
+        assert_eq!(
+            artifacts_dir,
+            store
+                .root_dir()
+                .join(RUNS_DIR_NAME)
+                .join("run-123")
+                .join(ARTIFACTS_DIR_NAME)
+        );
+    }
+
     #[test]
     fn test_delete_run_removes_evidence() {
         let temp_dir = TempDir::new().expect("temp dir");
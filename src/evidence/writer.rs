@@ -1,18 +1,27 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use serde::Serialize;
 use serde_json::Value;
 
 use crate::evidence::config::EvidenceStoreConfig;
 use crate::evidence::lifecycle::{LifecycleEvent, LifecycleEventType};
 use crate::evidence::record::EvidenceRecord;
 use crate::evidence::store::EvidenceStore;
+use crate::mcp::tools::load_prd::PrdUserStory;
 
 /// Evidence writer that records lifecycle events to durable storage.
+///
+/// Clones write to the same run, so a writer can be handed to components
+/// (such as the quality gate checker) that record their own evidence.
+#[derive(Debug, Clone)]
 pub struct EvidenceWriter {
     run_id: String,
     root_dir: PathBuf,
     store: EvidenceStore,
+    /// Epic of each story that has one, recorded on its step events
+    story_epics: HashMap<String, String>,
 }
 
 impl EvidenceWriter {
@@ -24,6 +33,7 @@ impl EvidenceWriter {
             run_id,
             root_dir: store.root_dir().to_path_buf(),
             store,
+            story_epics: HashMap::new(),
         })
     }
 
@@ -35,6 +45,19 @@ impl EvidenceWriter {
         &self.root_dir
     }
 
+    pub fn artifacts_dir(&self) -> PathBuf {
+        self.store.artifacts_dir(&self.run_id)
+    }
+
+    /// Record the epics of `stories`, so their step events name the epic.
+    pub fn set_story_epics(&mut self, stories: &[PrdUserStory]) {
+        for story in stories {
+            if let Some(ref epic) = story.epic {
+                self.story_epics.insert(story.id.clone(), epic.clone());
+            }
+        }
+    }
+
     pub fn emit_run_start(&mut self) {
         let event = LifecycleEvent::new(
             LifecycleEventType::RunStart,
@@ -51,12 +74,11 @@ impl EvidenceWriter {
         error_type: Option<String>,
         error_message: Option<String>,
     ) {
-        let mut event = LifecycleEvent::new(
-            LifecycleEventType::Step,
-            self.run_id.clone(),
-            step_id.into(),
-        );
+        let step_id = step_id.into();
+        let epic = self.story_epics.get(&step_id).cloned();
+        let mut event = LifecycleEvent::new(LifecycleEventType::Step, self.run_id.clone(), step_id);
         event.status = Some(status.into());
+        event.epic = epic;
         event.error_type = error_type;
         event.error_message = error_message;
         self.write_event(event);
@@ -79,16 +101,17 @@ impl EvidenceWriter {
         self.write_event(event);
     }
 
-    fn write_event(&mut self, event: LifecycleEvent) {
-        let payload: Value = match serde_json::to_value(&event) {
+    /// Record an arbitrary evidence payload of the given kind for this run.
+    pub fn emit_record(&self, kind: &str, payload: &impl Serialize) {
+        let payload: Value = match serde_json::to_value(payload) {
             Ok(value) => value,
             Err(err) => {
-                eprintln!("Warning: Failed to serialize evidence event: {}", err);
+                eprintln!("Warning: Failed to serialize evidence {}: {}", kind, err);
                 return;
             }
         };
 
-        let record = EvidenceRecord::new(self.run_id.clone(), "lifecycle", payload);
+        let record = EvidenceRecord::new(self.run_id.clone(), kind, payload);
         if let Err(err) = self.store.append_record(&record) {
             eprintln!(
                 "Warning: Failed to write evidence event to {}: {}",
@@ -97,6 +120,10 @@ impl EvidenceWriter {
             );
         }
     }
+
+    fn write_event(&mut self, event: LifecycleEvent) {
+        self.emit_record("lifecycle", &event);
+    }
 }
 
 pub fn generate_run_id() -> String {
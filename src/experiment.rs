@@ -0,0 +1,406 @@
+//! Agent A/B comparison.
+//!
+//! `ralph compare` runs one story with two or more agents (or models of the
+//! same agent), each in its own git worktree, and reports which of them got
+//! the story through the quality gates, in how many iterations and at what
+//! token cost. The report is printed and saved under `.ralph/experiments/`,
+//! and the branch of every variant that passed is kept for inspection.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::watch;
+
+use crate::agent::StoryAgent;
+use crate::mcp::tools::executor::{ExecutorConfig, StoryExecutor};
+use crate::metrics::{InvocationStats, RunMetricsCollector};
+use crate::parallel::worktree::{WorktreeError, WorktreeManager};
+
+/// Directory, relative to the working directory, holding experiment reports.
+pub const EXPERIMENTS_DIR: &str = ".ralph/experiments";
+
+/// Errors that stop an experiment.
+#[derive(Debug, Error)]
+pub enum ExperimentError {
+    /// Fewer than two variants, or one that does not parse
+    #[error("Invalid experiment: {0}")]
+    Invalid(String),
+    /// A variant's worktree could not be set up
+    #[error(transparent)]
+    Worktree(#[from] WorktreeError),
+    /// The PRD or report could not be read or written
+    #[error("{0}")]
+    Io(String),
+}
+
+/// One agent (and model) taking part in an experiment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Variant {
+    /// Short name, "a", "b", ...
+    pub label: String,
+    pub agent_command: String,
+    pub model: Option<String>,
+}
+
+impl Variant {
+    /// Parse `AGENT[@MODEL]`, e.g. `claude@claude-haiku-4-5`.
+    pub fn parse(label: impl Into<String>, spec: &str) -> Result<Self, ExperimentError> {
+        let (agent, model) = match spec.rsplit_once('@') {
+            Some((agent, model)) => (agent, Some(model.to_string())),
+            None => (spec, None),
+        };
+        if agent.trim().is_empty() {
+            return Err(ExperimentError::Invalid(format!(
+                "variant '{}' names no agent",
+                spec
+            )));
+        }
+        Ok(Self {
+            label: label.into(),
+            agent_command: agent.to_string(),
+            model: model.filter(|model| !model.is_empty()),
+        })
+    }
+
+    /// Variants for `specs`, labelled "a", "b", ... in order.
+    pub fn parse_all(specs: &[String]) -> Result<Vec<Self>, ExperimentError> {
+        if specs.len() < 2 {
+            return Err(ExperimentError::Invalid(
+                "at least two variants are needed".to_string(),
+            ));
+        }
+        if specs.len() > 26 {
+            return Err(ExperimentError::Invalid(
+                "at most 26 variants are supported".to_string(),
+            ));
+        }
+        specs
+            .iter()
+            .zip('a'..='z')
+            .map(|(spec, label)| Self::parse(label.to_string(), spec))
+            .collect()
+    }
+
+    /// Agent and model label, e.g. "claude (claude-haiku-4-5)".
+    pub fn name(&self) -> String {
+        StoryAgent {
+            agent_command: self.agent_command.clone(),
+            model: self.model.clone(),
+        }
+        .label()
+    }
+}
+
+/// How one variant did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariantResult {
+    #[serde(flatten)]
+    pub variant: Variant,
+    /// Whether the story passed the quality gates
+    pub passed: bool,
+    /// Iterations the variant ran
+    pub iterations: u32,
+    /// Tokens used, when the executor reported them
+    pub tokens_used: Option<u64>,
+    /// Estimated cost in cents, when the executor reported it
+    pub cost_cents: Option<f64>,
+    pub duration: Duration,
+    /// Gates that failed in the last iteration
+    pub failed_gates: Vec<String>,
+    /// Why the variant failed
+    pub error: Option<String>,
+    /// Branch holding the variant's commit, kept when it passed
+    pub branch: Option<String>,
+}
+
+/// Results of an experiment, best variant first.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparisonReport {
+    pub experiment_id: String,
+    pub story_id: String,
+    pub results: Vec<VariantResult>,
+    /// Label of the best variant that passed, if any did
+    pub winner: Option<String>,
+}
+
+impl ComparisonReport {
+    /// A report over `results`, ranked: passing variants first, then fewer
+    /// iterations, fewer tokens and less time.
+    pub fn new(experiment_id: String, story_id: String, mut results: Vec<VariantResult>) -> Self {
+        results.sort_by_key(|result| {
+            (
+                !result.passed,
+                result.iterations,
+                result.tokens_used.unwrap_or(u64::MAX),
+                result.duration,
+            )
+        });
+        let winner = results
+            .first()
+            .filter(|result| result.passed)
+            .map(|result| result.variant.label.clone());
+        Self {
+            experiment_id,
+            story_id,
+            results,
+            winner,
+        }
+    }
+
+    /// The report as text, one line per variant.
+    pub fn render_text(&self) -> String {
+        let mut out = format!(
+            "Comparison of {} (experiment {})\n",
+            self.story_id, self.experiment_id
+        );
+        let width = self
+            .results
+            .iter()
+            .map(|result| result.variant.name().len())
+            .max()
+            .unwrap_or(0);
+        for result in &self.results {
+            let tokens = result
+                .tokens_used
+                .map_or("n/a".to_string(), |tokens| format!("{} tokens", tokens));
+            let cost = result
+                .cost_cents
+                .map_or("n/a".to_string(), |cents| format!("${:.2}", cents / 100.0));
+            out.push_str(&format!(
+                "  {}  {:<width$}  {}  {} iteration{}  {}  {}  {}s",
+                result.variant.label,
+                result.variant.name(),
+                if result.passed { "PASSED" } else { "FAILED" },
+                result.iterations,
+                if result.iterations == 1 { "" } else { "s" },
+                tokens,
+                cost,
+                result.duration.as_secs(),
+                width = width,
+            ));
+            if let Some(ref branch) = result.branch {
+                out.push_str(&format!("  branch {}", branch));
+            }
+            if !result.failed_gates.is_empty() {
+                out.push_str(&format!("  failed: {}", result.failed_gates.join(", ")));
+            } else if let Some(ref error) = result.error {
+                out.push_str(&format!("  {}", error.lines().next().unwrap_or_default()));
+            }
+            out.push('\n');
+        }
+        match self.winner.as_ref().and_then(|label| {
+            self.results
+                .iter()
+                .find(|result| &result.variant.label == label)
+        }) {
+            Some(winner) => out.push_str(&format!(
+                "Winner: {} ({})\n",
+                winner.variant.label,
+                winner.variant.name()
+            )),
+            None => out.push_str("No variant passed\n"),
+        }
+        out
+    }
+}
+
+/// Settings of an experiment.
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    /// Main working directory of the repository
+    pub working_dir: PathBuf,
+    pub prd_path: PathBuf,
+    pub story_id: String,
+    pub variants: Vec<Variant>,
+    pub max_iterations: u32,
+}
+
+impl Experiment {
+    /// Run every variant in turn and save the report to
+    /// `.ralph/experiments/<id>/report.json`.
+    pub async fn run(&self) -> Result<ComparisonReport, ExperimentError> {
+        let experiment_id = format!(
+            "{}-{}",
+            self.story_id,
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        );
+        let experiment_dir = self.working_dir.join(EXPERIMENTS_DIR).join(&experiment_id);
+        let prd = std::fs::read_to_string(&self.prd_path).map_err(|e| {
+            ExperimentError::Io(format!("Failed to read {}: {}", self.prd_path.display(), e))
+        })?;
+        let manager = WorktreeManager::new(&self.working_dir)?;
+
+        let mut results = Vec::new();
+        for variant in &self.variants {
+            let job_dir = experiment_dir.join(&variant.label);
+            std::fs::create_dir_all(&job_dir).map_err(|e| io_error(&job_dir, e))?;
+            let prd_path = job_dir.join("prd.json");
+            std::fs::write(&prd_path, &prd).map_err(|e| io_error(&prd_path, e))?;
+            results.push(self.run_variant(&manager, variant, &job_dir).await?);
+        }
+
+        let report = ComparisonReport::new(experiment_id, self.story_id.clone(), results);
+        let report_path = experiment_dir.join("report.json");
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| ExperimentError::Io(e.to_string()))?;
+        std::fs::write(&report_path, json).map_err(|e| io_error(&report_path, e))?;
+        Ok(report)
+    }
+
+    /// Run the story with one variant in a fresh worktree.
+    async fn run_variant(
+        &self,
+        manager: &WorktreeManager,
+        variant: &Variant,
+        job_dir: &Path,
+    ) -> Result<VariantResult, ExperimentError> {
+        let worktree = manager.create(&format!("compare-{}-{}", self.story_id, variant.label))?;
+        let run_metrics = RunMetricsCollector::new(format!("compare-{}", variant.label), 1);
+        let executor = StoryExecutor::new(ExecutorConfig {
+            prd_path: job_dir.join("prd.json"),
+            project_root: worktree.path.clone(),
+            progress_path: job_dir.join("progress.txt"),
+            agent_command: variant.agent_command.clone(),
+            model: variant.model.clone(),
+            max_iterations: self.max_iterations,
+            run_metrics: Some(run_metrics.clone()),
+            ..Default::default()
+        });
+
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let mut iterations = 0;
+        let start = Instant::now();
+        let outcome = executor
+            .execute_story(&self.story_id, cancel_rx, |iteration, _| {
+                iterations = iteration
+            })
+            .await;
+        let duration = start.elapsed();
+        // A story that runs out of iterations fails without a result, so
+        // its usage comes from the recorded invocations
+        let usage = InvocationStats::from_invocations(&run_metrics.finish().invocations);
+
+        let result = match outcome {
+            Ok(result) => VariantResult {
+                variant: variant.clone(),
+                passed: result.success,
+                iterations: result.iterations_used,
+                tokens_used: result.tokens_used,
+                cost_cents: result.estimated_cost_cents,
+                duration,
+                failed_gates: result
+                    .gate_results
+                    .iter()
+                    .filter(|gate| gate.is_blocking_failure())
+                    .map(|gate| gate.gate_name.clone())
+                    .collect(),
+                error: result.error,
+                branch: result.success.then(|| worktree.branch.clone()),
+            },
+            Err(e) => VariantResult {
+                variant: variant.clone(),
+                passed: false,
+                iterations,
+                tokens_used: (usage.tokens > 0).then_some(usage.tokens),
+                cost_cents: (usage.cost_cents > 0.0).then_some(usage.cost_cents),
+                duration,
+                failed_gates: Vec::new(),
+                error: Some(e.to_string()),
+                branch: None,
+            },
+        };
+
+        // Keep the branch of a passing variant so its changes can be diffed
+        let cleanup = if result.passed {
+            manager.remove_checkout(&worktree)
+        } else {
+            manager.remove(&worktree)
+        };
+        if let Err(e) = cleanup {
+            eprintln!(
+                "Warning: Failed to remove worktree for variant {}: {}",
+                variant.label, e
+            );
+        }
+        Ok(result)
+    }
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> ExperimentError {
+    ExperimentError::Io(format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(label: &str, passed: bool, iterations: u32, tokens: Option<u64>) -> VariantResult {
+        VariantResult {
+            variant: Variant::parse(label, "claude").unwrap(),
+            passed,
+            iterations,
+            tokens_used: tokens,
+            cost_cents: tokens.map(|tokens| tokens as f64 / 1000.0),
+            duration: Duration::from_secs(60),
+            failed_gates: if passed {
+                Vec::new()
+            } else {
+                vec!["clippy".to_string()]
+            },
+            error: None,
+            branch: passed.then(|| format!("ralph/compare-US-1-{}", label)),
+        }
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        let variants = Variant::parse_all(&[
+            "claude@claude-haiku-4-5".to_string(),
+            "api:ollama:qwen2.5-coder:7b".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(variants[0].label, "a");
+        assert_eq!(variants[0].agent_command, "claude");
+        assert_eq!(variants[0].name(), "claude (claude-haiku-4-5)");
+        assert_eq!(variants[1].label, "b");
+        assert_eq!(variants[1].model, None);
+
+        assert!(Variant::parse_all(&["claude".to_string()]).is_err());
+        assert!(Variant::parse("a", "@model").is_err());
+    }
+
+    #[test]
+    fn test_report_ranks_variants() {
+        let report = ComparisonReport::new(
+            "US-1-20260101-000000".to_string(),
+            "US-1".to_string(),
+            vec![
+                result("a", false, 5, None),
+                result("b", true, 3, Some(9000)),
+                result("c", true, 3, Some(4000)),
+            ],
+        );
+        let order: Vec<&str> = report
+            .results
+            .iter()
+            .map(|r| r.variant.label.as_str())
+            .collect();
+        assert_eq!(order, vec!["c", "b", "a"]);
+        assert_eq!(report.winner.as_deref(), Some("c"));
+
+        let text = report.render_text();
+        assert!(text.contains("c  claude  PASSED  3 iterations  4000 tokens  $0.04  60s"));
+        assert!(text.contains("a  claude  FAILED  5 iterations  n/a  n/a  60s  failed: clippy"));
+        assert!(text.ends_with("Winner: c (claude)\n"));
+
+        let report = ComparisonReport::new(
+            "x".to_string(),
+            "US-1".to_string(),
+            vec![result("a", false, 1, None), result("b", false, 2, None)],
+        );
+        assert_eq!(report.winner, None);
+        assert!(report.render_text().ends_with("No variant passed\n"));
+    }
+}
@@ -5,9 +5,12 @@
 //! previous failures.
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
+use super::mutation::PromptMutation;
 use crate::budget::{BudgetAwarePromptBuilder, PromptStrategy};
+use crate::quality::GateFailureDetail;
 
 /// Error information from a single iteration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +25,9 @@ pub struct IterationError {
     pub failed_gate: Option<String>,
     /// Files involved in the error (if known)
     pub affected_files: Vec<String>,
+    /// Structured findings of the failed gate (if any)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<GateFailureDetail>,
 }
 
 impl IterationError {
@@ -33,6 +39,7 @@ impl IterationError {
             message: message.into(),
             failed_gate: None,
             affected_files: Vec::new(),
+            failures: Vec::new(),
         }
     }
 
@@ -48,12 +55,63 @@ impl IterationError {
         self
     }
 
+    /// Set the structured findings of the failed gate.
+    pub fn with_failures(mut self, failures: Vec<GateFailureDetail>) -> Self {
+        self.failures = failures;
+        self
+    }
+
     /// Get a normalized representation for pattern matching.
     /// This is used to detect oscillating or repeating errors.
     pub fn signature(&self) -> String {
         let gate_part = self.failed_gate.as_deref().unwrap_or("none");
         format!("{}:{}", self.category.as_str(), gate_part)
     }
+
+    /// Get a fingerprint identifying this exact failure.
+    ///
+    /// Unlike [`signature`](Self::signature), two errors of the same gate only
+    /// share a fingerprint when they report the same findings (file, code and
+    /// message, ignoring line numbers, which shift as code is edited).
+    pub fn fingerprint(&self) -> String {
+        let mut findings: Vec<String> = if self.failures.is_empty() {
+            vec![normalize_message(&self.message)]
+        } else {
+            self.failures
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{}|{}|{}",
+                        f.file.as_deref().unwrap_or(""),
+                        f.error_code.as_deref().unwrap_or(""),
+                        normalize_message(f.message.lines().next().unwrap_or(""))
+                    )
+                })
+                .collect()
+        };
+        findings.sort();
+        findings.dedup();
+        let digest = Sha256::digest(findings.join("\n").as_bytes());
+        format!("{}:{}", self.signature(), hex::encode(&digest[..6]))
+    }
+}
+
+/// Replace digit runs so counts and positions do not affect fingerprints.
+fn normalize_message(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut in_digits = false;
+    for c in message.trim().chars() {
+        if c.is_ascii_digit() {
+            if !in_digits {
+                out.push('#');
+            }
+            in_digits = true;
+        } else {
+            out.push(c);
+            in_digits = false;
+        }
+    }
+    out
 }
 
 /// Categories of errors that can occur during iteration.
@@ -351,6 +409,47 @@ impl IterationContext {
         self.error_history.iter().map(|e| e.signature()).collect()
     }
 
+    /// Number of most recent failed iterations, counting back from the
+    /// latest, that failed with exactly the same errors (by fingerprint).
+    ///
+    /// Returns 0 without errors and 1 when the latest failure is new.
+    pub fn repeated_failure_streak(&self) -> u32 {
+        let iterations = self.failure_fingerprints();
+        let Some(latest) = iterations.last() else {
+            return 0;
+        };
+        iterations
+            .iter()
+            .rev()
+            .take_while(|fingerprints| *fingerprints == latest)
+            .count() as u32
+    }
+
+    /// Sorted fingerprints of the errors of the latest failed iteration.
+    pub fn latest_failure_fingerprints(&self) -> Vec<String> {
+        self.failure_fingerprints().pop().unwrap_or_default()
+    }
+
+    /// Sorted fingerprints of the errors of each failed iteration, in order.
+    fn failure_fingerprints(&self) -> Vec<Vec<String>> {
+        let mut iterations: Vec<(u32, Vec<String>)> = Vec::new();
+        for error in &self.error_history {
+            match iterations.last_mut() {
+                Some((iteration, fingerprints)) if *iteration == error.iteration => {
+                    fingerprints.push(error.fingerprint());
+                }
+                _ => iterations.push((error.iteration, vec![error.fingerprint()])),
+            }
+        }
+        iterations
+            .into_iter()
+            .map(|(_, mut fingerprints)| {
+                fingerprints.sort();
+                fingerprints
+            })
+            .collect()
+    }
+
     /// Build a context string to inject into agent prompts.
     ///
     /// This provides the agent with information about previous failures
@@ -416,6 +515,10 @@ impl IterationContext {
             context.push_str(&guidance.build_prompt_section());
         }
 
+        if let Some(mutation) = PromptMutation::for_context(self) {
+            context.push_str(&mutation.build_prompt_section(self));
+        }
+
         context.push_str(&format!(
             "\n**Note**: This is iteration {} of {}. Please address the issues above.\n",
             self.current_iteration, self.max_iterations
@@ -451,6 +554,11 @@ impl IterationContext {
             context.push_str(&guidance.build_prompt_section());
         }
 
+        // Change tack when the same failure keeps coming back
+        if let Some(mutation) = PromptMutation::for_context(self) {
+            context.push_str(&mutation.build_prompt_section(self));
+        }
+
         // Add iteration note
         context.push_str(&format!(
             "\n**Note**: This is iteration {} of {}. Please address the issues above.\n",
@@ -23,6 +23,14 @@ pub enum FutilityVerdict {
         /// Reason for deferring
         reason: String,
     },
+    /// Consecutive iterations failed with exactly the same errors; stop
+    /// retrying the story
+    NoProgress {
+        /// Reason for stopping
+        reason: String,
+        /// Number of identical failed iterations in a row
+        repeats: u32,
+    },
     /// Fatal error that cannot be recovered from
     Fatal {
         /// Reason for the fatal verdict
@@ -42,6 +50,7 @@ impl FutilityVerdict {
             FutilityVerdict::Continue => None,
             FutilityVerdict::PauseForGuidance { reason, .. } => Some(reason),
             FutilityVerdict::DeferStory { reason } => Some(reason),
+            FutilityVerdict::NoProgress { reason, .. } => Some(reason),
             FutilityVerdict::Fatal { reason } => Some(reason),
         }
     }
@@ -58,6 +67,10 @@ pub struct FutilityConfig {
     pub fatal_categories: Vec<ErrorCategory>,
     /// Whether to enable pattern detection
     pub enable_pattern_detection: bool,
+    /// Number of consecutive iterations failing with identical errors (by
+    /// fingerprint) before the story is stopped for making no progress
+    /// (0 = never)
+    pub no_progress_threshold: u32,
 }
 
 impl Default for FutilityConfig {
@@ -67,6 +80,9 @@ impl Default for FutilityConfig {
             stagnation_threshold: 4,
             fatal_categories: vec![ErrorCategory::Environment],
             enable_pattern_detection: true,
+            // One more than the prompt mutation ladder, so every mutation
+            // gets a chance first
+            no_progress_threshold: 5,
         }
     }
 }
@@ -99,6 +115,11 @@ impl FutileRetryDetector {
             return verdict;
         }
 
+        // Check for identical failures in a row
+        if let Some(verdict) = self.check_no_progress(context) {
+            return verdict;
+        }
+
         // Check for oscillation patterns (A -> B -> A -> B)
         if self.config.enable_pattern_detection {
             if let Some(verdict) = self.check_oscillation(context) {
@@ -131,6 +152,23 @@ impl FutileRetryDetector {
         None
     }
 
+    /// Check for iterations repeating the previous failure exactly.
+    fn check_no_progress(&self, context: &IterationContext) -> Option<FutilityVerdict> {
+        let threshold = self.config.no_progress_threshold;
+        let repeats = context.repeated_failure_streak();
+        if threshold == 0 || repeats < threshold {
+            return None;
+        }
+        Some(FutilityVerdict::NoProgress {
+            reason: format!(
+                "No progress: the last {} iterations failed with exactly the same errors ({})",
+                repeats,
+                context.latest_failure_fingerprints().join(", ")
+            ),
+            repeats,
+        })
+    }
+
     /// Check for oscillation pattern (alternating between two error types).
     fn check_oscillation(&self, context: &IterationContext) -> Option<FutilityVerdict> {
         let signatures = context.error_signature_sequence();
@@ -403,6 +441,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detector_no_progress() {
+        let detector = FutileRetryDetector::with_config(FutilityConfig {
+            no_progress_threshold: 2,
+            ..Default::default()
+        });
+        let mut context = IterationContext::new("US-001", 10);
+        context.start_iteration(2);
+        context.record_error(IterationError::new(1, ErrorCategory::Other, "error 1"));
+        context.record_error(IterationError::new(2, ErrorCategory::Other, "error 2"));
+
+        let verdict = detector.analyze(&context);
+        assert!(matches!(
+            verdict,
+            FutilityVerdict::NoProgress { repeats: 2, .. }
+        ));
+        assert!(verdict.reason().unwrap().contains("other:none:"));
+
+        // A different failure is progress
+        context.start_iteration(3);
+        context.record_error(IterationError::new(3, ErrorCategory::Lint, "crashed"));
+        assert_eq!(detector.analyze(&context), FutilityVerdict::Continue);
+    }
+
     #[test]
     fn test_detector_stagnation() {
         let detector = FutileRetryDetector::new();
@@ -1,11 +1,14 @@
 //! Iteration management module for Ralph.
 //!
 //! This module provides infrastructure for managing iteration loops during story execution,
-//! including context passing between iterations and futile retry detection.
+//! including context passing between iterations, prompt mutation for repeated failures
+//! and futile retry detection.
 
 pub mod context;
 pub mod futility;
+pub mod mutation;
 
 // Re-exports for convenience
 pub use context::{ApproachHint, IterationContext, IterationError};
 pub use futility::{FutileRetryDetector, FutilityVerdict};
+pub use mutation::PromptMutation;
@@ -0,0 +1,176 @@
+//! Prompt mutation for repeated identical failures.
+//!
+//! When an iteration fails with exactly the same errors as the one before it,
+//! repeating the same prompt is unlikely to help. The `PromptMutation` ladder
+//! changes what the agent is told each time the failure recurs: first it
+//! points the agent at the files involved, then tightens the instructions,
+//! and finally asks for a different approach. Once the ladder is exhausted
+//! the futility detector's stagnation checks take over.
+
+use super::context::IterationContext;
+
+/// Maximum number of files listed by [`PromptMutation::AddContextFiles`].
+const MAX_CONTEXT_FILES: usize = 10;
+
+/// A change to the prompt made because the same failure recurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptMutation {
+    /// Ask the agent to read the files involved in the failure in full
+    AddContextFiles,
+    /// Restrict the agent to fixing the reported findings only
+    StricterInstructions,
+    /// Ask the agent to abandon its approach and try another
+    DifferentApproach,
+}
+
+impl PromptMutation {
+    /// Mutation for the next prompt, given how many consecutive iterations
+    /// have failed identically (see
+    /// [`IterationContext::repeated_failure_streak`]).
+    pub fn for_streak(streak: u32) -> Option<Self> {
+        match streak {
+            2 => Some(PromptMutation::AddContextFiles),
+            3 => Some(PromptMutation::StricterInstructions),
+            4 => Some(PromptMutation::DifferentApproach),
+            _ => None,
+        }
+    }
+
+    /// Mutation for the next prompt of `context`, if its latest failure
+    /// repeats the previous one.
+    pub fn for_context(context: &IterationContext) -> Option<Self> {
+        Self::for_streak(context.repeated_failure_streak())
+    }
+
+    /// Get a string representation of the mutation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptMutation::AddContextFiles => "add_context_files",
+            PromptMutation::StricterInstructions => "stricter_instructions",
+            PromptMutation::DifferentApproach => "different_approach",
+        }
+    }
+
+    /// Build the prompt section applying this mutation.
+    pub fn build_prompt_section(&self, context: &IterationContext) -> String {
+        let streak = context.repeated_failure_streak();
+        let mut section = format!(
+            "\n### Repeated Failure\n\nThe last {} iterations failed with exactly the same errors.\n",
+            streak
+        );
+        match self {
+            PromptMutation::AddContextFiles => {
+                let files = failing_files(context);
+                if files.is_empty() {
+                    section.push_str(
+                        "Before editing, read the code around every reported error in full \
+                         rather than only the reported lines.\n",
+                    );
+                } else {
+                    section.push_str(
+                        "Before editing, read these files in full rather than only the \
+                         reported lines:\n",
+                    );
+                    for file in files {
+                        section.push_str(&format!("- {}\n", file));
+                    }
+                }
+            }
+            PromptMutation::StricterInstructions => {
+                section.push_str(
+                    "- Fix only the findings listed above; make no other changes\n\
+                     - Run the failing check yourself and confirm it passes before finishing\n\
+                     - Do not silence the check (no `allow` attributes, skipped tests or \
+                     lowered thresholds)\n",
+                );
+            }
+            PromptMutation::DifferentApproach => {
+                section.push_str(
+                    "Your current approach is not working. Revert your changes to the failing \
+                     code and implement it differently: reconsider the design, the types and \
+                     the APIs you rely on instead of patching the same lines again.\n",
+                );
+            }
+        }
+        section
+    }
+}
+
+/// Files involved in the latest failed iteration, as reported by the gates
+/// or changed by the agent.
+fn failing_files(context: &IterationContext) -> Vec<String> {
+    let Some(latest) = context.error_history.last().map(|e| e.iteration) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = Vec::new();
+    for error in context
+        .error_history
+        .iter()
+        .filter(|e| e.iteration == latest)
+    {
+        let reported = error.failures.iter().filter_map(|f| f.file.clone());
+        for file in reported.chain(error.affected_files.iter().cloned()) {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    files.truncate(MAX_CONTEXT_FILES);
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iteration::context::ErrorCategory;
+    use crate::iteration::IterationError;
+    use crate::quality::{FailureCategory, GateFailureDetail};
+
+    fn clippy_error(iteration: u32, line: u32, message: &str) -> IterationError {
+        let detail = GateFailureDetail::new(FailureCategory::Lint, message)
+            .with_location("src/auth.rs", line, None)
+            .with_error_code("clippy::unwrap_used");
+        IterationError::new(iteration, ErrorCategory::Lint, "Gate 'clippy' failed")
+            .with_gate("clippy")
+            .with_files(vec!["src/lib.rs".to_string()])
+            .with_failures(vec![detail])
+    }
+
+    #[test]
+    fn test_mutation_ladder() {
+        let mut context = IterationContext::new("US-001", 8);
+        assert_eq!(PromptMutation::for_context(&context), None);
+
+        context.record_error(clippy_error(1, 10, "used `unwrap()` on a `Result`"));
+        assert_eq!(context.repeated_failure_streak(), 1);
+        assert_eq!(PromptMutation::for_context(&context), None);
+
+        // Line numbers shift between attempts without making the failure new
+        context.record_error(clippy_error(2, 14, "used `unwrap()` on a `Result`"));
+        assert_eq!(
+            PromptMutation::for_context(&context),
+            Some(PromptMutation::AddContextFiles)
+        );
+        let section = PromptMutation::AddContextFiles.build_prompt_section(&context);
+        assert!(section.contains("last 2 iterations"));
+        assert!(section.contains("- src/auth.rs\n- src/lib.rs\n"));
+
+        context.record_error(clippy_error(3, 14, "used `unwrap()` on a `Result`"));
+        assert_eq!(
+            PromptMutation::for_context(&context),
+            Some(PromptMutation::StricterInstructions)
+        );
+        context.record_error(clippy_error(4, 9, "used `unwrap()` on a `Result`"));
+        assert_eq!(
+            PromptMutation::for_context(&context),
+            Some(PromptMutation::DifferentApproach)
+        );
+        context.record_error(clippy_error(5, 9, "used `unwrap()` on a `Result`"));
+        assert_eq!(PromptMutation::for_context(&context), None);
+
+        // A different finding starts over
+        context.record_error(clippy_error(6, 9, "used `expect()` on a `Result`"));
+        assert_eq!(context.repeated_failure_streak(), 1);
+        assert_eq!(PromptMutation::for_context(&context), None);
+    }
+}
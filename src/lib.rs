@@ -3,11 +3,13 @@
 //! This library exposes Ralph's internal modules for integration testing
 //! and potential use as a library.
 
+pub mod agent;
 pub mod audit;
 pub mod budget;
 pub mod checkpoint;
 pub mod error;
 pub mod evidence;
+pub mod experiment;
 pub mod integrations;
 pub mod interactive_guidance;
 pub mod iteration;
@@ -17,7 +19,10 @@ pub mod metrics;
 pub mod notification;
 pub mod parallel;
 pub mod pause;
+pub mod prd;
+pub mod prompts;
 pub mod quality;
+pub mod resources;
 pub mod runner;
 pub mod timeout;
 pub mod ui;
@@ -3,13 +3,17 @@ use rmcp::{transport::stdio, ServiceExt};
 use std::path::PathBuf;
 use std::process::ExitCode;
 
+use ralphmacchio::agent::{AgentContainer, AgentNetwork};
 use ralphmacchio::audit;
 use ralphmacchio::budget::TokenBudgetConfig;
 use ralphmacchio::checkpoint::{CheckpointManager, PauseReason};
 use ralphmacchio::logging::{init_logging, LoggingConfig};
 use ralphmacchio::mcp::RalphMcpServer;
-use ralphmacchio::runner::{Runner, RunnerConfig};
-use ralphmacchio::ui::{DisplayOptions, HelpRenderer, UiMode};
+use ralphmacchio::notification::bell::BellMode;
+use ralphmacchio::resources::ResourceLimits;
+use ralphmacchio::runner::{ExitCodePolicy, Runner, RunnerConfig};
+use ralphmacchio::ui::run_log::RunLogConfig;
+use ralphmacchio::ui::{DisplayOptions, HelpRenderer, SymbolSet, ThemeName, UiMode};
 
 /// UI mode for terminal display
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -33,6 +37,50 @@ impl From<CliUiMode> for UiMode {
     }
 }
 
+/// Color theme of the terminal UI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CliTheme {
+    /// Default colors, for dark backgrounds
+    #[default]
+    Dark,
+    /// Darker colors for light backgrounds
+    Light,
+    /// Colorblind-safe palette
+    Colorblind,
+    /// No colors
+    NoColor,
+}
+
+impl From<CliTheme> for ThemeName {
+    fn from(theme: CliTheme) -> Self {
+        match theme {
+            CliTheme::Dark => ThemeName::Dark,
+            CliTheme::Light => ThemeName::Light,
+            CliTheme::Colorblind => ThemeName::Colorblind,
+            CliTheme::NoColor => ThemeName::NoColor,
+        }
+    }
+}
+
+/// Symbols the terminal UI draws with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CliSymbols {
+    /// Unicode icons, box drawing and emoji
+    #[default]
+    Unicode,
+    /// Plain ASCII
+    Ascii,
+}
+
+impl From<CliSymbols> for SymbolSet {
+    fn from(symbols: CliSymbols) -> Self {
+        match symbols {
+            CliSymbols::Unicode => SymbolSet::Unicode,
+            CliSymbols::Ascii => SymbolSet::Ascii,
+        }
+    }
+}
+
 /// Output format for audit reports
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
 pub enum AuditOutputFormat {
@@ -47,6 +95,67 @@ pub enum AuditOutputFormat {
     All,
 }
 
+/// Output format for the standalone gate runner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GatesOutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// JSON gate report for other tools
+    Json,
+}
+
+/// Output format for the execution planner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum PlanOutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// JSON execution plan for other tools
+    Json,
+}
+
+/// Output format of a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RunOutputFormat {
+    /// Terminal UI or plain text
+    #[default]
+    Text,
+    /// One JSON event per line on stdout, for CI and wrapper scripts
+    Jsonl,
+    /// Text, plus GitHub Actions annotations, job summary and step outputs
+    Github,
+    /// No output while the run goes, then a report of stories, gate
+    /// durations, failures and cost
+    Summary,
+}
+
+/// Action for the checkpoint command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CheckpointAction {
+    /// List saved checkpoints
+    #[default]
+    List,
+    /// Show the details of a checkpoint
+    Show,
+    /// Delete a checkpoint
+    Delete,
+}
+
+/// Action for the prd command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum PrdAction {
+    /// Check the PRD against its JSON Schema
+    #[default]
+    Validate,
+    /// Check the PRD for duplicate IDs, bad dependencies and risky stories
+    Lint,
+    /// Add or update stories from labeled GitHub issues
+    Import,
+    /// Create a PRD interactively
+    Init,
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "ralph")]
 #[command(version)]
@@ -62,6 +171,14 @@ struct Cli {
     #[arg(long)]
     no_color: bool,
 
+    /// Color theme: dark (default), light, colorblind, or no-color
+    #[arg(long, default_value = "dark", value_enum)]
+    theme: CliTheme,
+
+    /// Status icons and frames: unicode (default) or ascii
+    #[arg(long, default_value = "unicode", value_enum)]
+    symbols: CliSymbols,
+
     /// Disable startup animations
     #[arg(long)]
     no_animation: bool,
@@ -82,7 +199,18 @@ struct Cli {
     #[arg(long, short = 'V')]
     version: bool,
 
-    /// Path to PRD file (for default run mode)
+    /// Options of the default run mode
+    #[command(flatten)]
+    run: RunArgs,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+/// Options of `ralph run`.
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// Path to PRD file
     #[arg(long, short, default_value = "prd.json")]
     prd: PathBuf,
 
@@ -94,7 +222,7 @@ struct Cli {
     #[arg(long, default_value = "10")]
     max_iterations: u32,
 
-    /// Agent command to use (claude, codex, amp, or custom)
+    /// Agent command to use (claude, codex, amp, aider, mock, or custom)
     #[arg(long)]
     agent: Option<String>,
 
@@ -110,10 +238,136 @@ struct Cli {
     #[arg(long, default_value = "32")]
     parallel_queue_capacity: usize,
 
-    /// Backpressure policy when parallel queue is full (block, reject, drop_oldest)
+    /// Backpressure policy when parallel queue is full (block, reject, drop_oldest,
+    /// evict_lowest_priority)
     #[arg(long, default_value = "block")]
     parallel_queue_policy: String,
 
+    /// Where parallel stories run (shared, worktree, branch)
+    #[arg(long, default_value = "shared")]
+    parallel_isolation: String,
+
+    /// Scale concurrency up and down with system load, API throttling and failure rate
+    #[arg(long)]
+    adaptive_concurrency: bool,
+
+    /// Re-plan a parallel run when the PRD changes, scheduling added and edited stories
+    #[arg(long)]
+    watch_prd: bool,
+
+    /// When parallel reconciliation runs (idle, every, end, shared_dirs)
+    #[arg(long, default_value = "idle")]
+    reconcile_mode: String,
+
+    /// Finished stories between parallel reconciliation passes (0 = when idle)
+    #[arg(long, default_value = "0")]
+    reconcile_every: usize,
+
+    /// Comma-separated strategies tried on story branches that conflict on merge
+    /// before their stories are re-run (none, priority, rerere, agent)
+    #[arg(long, value_name = "STRATEGY,...")]
+    merge_resolution: Option<String>,
+
+    /// Have an agent fix reconciliation issues before stories are re-run sequentially
+    #[arg(long)]
+    reconcile_agent: bool,
+
+    /// Serve a live dashboard of a parallel run on this address (e.g. 127.0.0.1:8080)
+    #[arg(long, value_name = "ADDR")]
+    dashboard: Option<String>,
+
+    /// Output format (text, jsonl, github, summary)
+    #[arg(long, default_value = "text", value_enum)]
+    output: RunOutputFormat,
+
+    /// Mirror the run's events and full agent and gate output to .ralph/logs
+    #[arg(long)]
+    log: bool,
+
+    /// Size in MB at which the run log is rotated
+    #[arg(long, value_name = "MB")]
+    log_max_size: Option<u64>,
+
+    /// Comma-separated URLs of `ralph worker` processes to run parallel stories on
+    #[arg(long, value_name = "URL,...")]
+    remote_workers: Option<String>,
+
+    /// Git remote (name or URL) shared with remote workers
+    #[arg(long, default_value = "origin")]
+    remote_repo: String,
+
+    /// Comma-separated story IDs to run in parallel mode (default: all)
+    #[arg(long, value_name = "ID,...")]
+    only: Option<String>,
+
+    /// Comma-separated story IDs to leave out in parallel mode
+    #[arg(long, value_name = "ID,...")]
+    skip: Option<String>,
+
+    /// Max retries for a parallel story failing with a retryable error (0 = no retries)
+    #[arg(long)]
+    story_retries: Option<u32>,
+
+    /// Retries per error category for each parallel story, e.g. "transient=5,fatal=1"
+    #[arg(long, value_name = "CATEGORY=COUNT,...")]
+    retry_budgets: Option<String>,
+
+    /// Memory limit in MB for each worker's agent and gate subprocesses
+    #[arg(long, value_name = "MB")]
+    worker_max_rss: Option<u64>,
+
+    /// Relative CPU weight for worker subprocesses (1-1024, lower = nicer)
+    #[arg(long, value_name = "N")]
+    worker_cpu_shares: Option<u32>,
+
+    /// Per-story scratch directory (TMPDIR) quota in MB
+    #[arg(long, value_name = "MB")]
+    worker_scratch_quota: Option<u64>,
+
+    /// Free memory in MB required before dispatching another heavy story
+    #[arg(long, value_name = "MB")]
+    min_memory_headroom: Option<u64>,
+
+    /// Seconds a story may wait on another story's file locks before failing (0 = no limit)
+    #[arg(long, value_name = "SECONDS")]
+    lock_wait_timeout: Option<u64>,
+
+    /// Agent launches allowed per minute across parallel workers (0 = unlimited)
+    #[arg(long, value_name = "N")]
+    agent_launches_per_minute: Option<u32>,
+
+    /// Run the agent CLI in a container from this image, with only the project mounted
+    #[arg(long, value_name = "IMAGE")]
+    agent_container: Option<String>,
+
+    /// Container runtime for --agent-container (docker or podman)
+    #[arg(long, value_name = "RUNTIME", default_value = "docker")]
+    agent_container_runtime: String,
+
+    /// Network of the agent container: default, none, or a named network
+    #[arg(long, value_name = "NETWORK", default_value = "default")]
+    agent_network: String,
+
+    /// Memory limit of the agent container (e.g. 8g)
+    #[arg(long, value_name = "SIZE")]
+    agent_memory: Option<String>,
+
+    /// CPU limit of the agent container (e.g. 2)
+    #[arg(long, value_name = "N")]
+    agent_cpus: Option<String>,
+
+    /// Host environment variable to pass into the agent container (repeatable)
+    #[arg(long = "agent-env", value_name = "VAR")]
+    agent_env: Vec<String>,
+
+    /// Extra volume for the agent container, in runtime syntax (repeatable)
+    #[arg(long = "agent-mount", value_name = "SPEC")]
+    agent_mount: Vec<String>,
+
+    /// Include the files most relevant to each story in its prompt, up to this many tokens
+    #[arg(long, value_name = "TOKENS")]
+    context_tokens: Option<u64>,
+
     /// Resume from checkpoint if available
     #[arg(long)]
     resume: bool,
@@ -122,6 +376,14 @@ struct Cli {
     #[arg(long, conflicts_with = "resume")]
     no_resume: bool,
 
+    /// Resume without prompting when the checkpoint matches the current PRD and branch
+    #[arg(long, conflicts_with = "no_resume")]
+    auto_resume: bool,
+
+    /// Resume even if the PRD changed since the checkpoint was written
+    #[arg(long, conflicts_with = "no_resume")]
+    force: bool,
+
     /// Agent timeout in seconds (overrides default)
     #[arg(long, value_name = "SECONDS")]
     timeout: Option<u64>,
@@ -147,11 +409,39 @@ struct Cli {
     #[arg(long)]
     no_checkpoint: bool,
 
+    /// Leave the PRD unchanged when stories pass instead of setting their `passes` flag
+    #[arg(long)]
+    no_writeback: bool,
+
+    /// Run the full quality gate suite once more on the integrated tree after
+    /// every story passes, failing the run if it does not pass
+    #[arg(long)]
+    final_verify: bool,
+
+    /// Seconds between checkpoint autosaves while stories run (0 = no autosave)
+    #[arg(long, value_name = "SECONDS")]
+    checkpoint_interval: Option<u64>,
+
     /// Number of consecutive failures before circuit breaker triggers.
     /// When reached, execution pauses to prevent cascading failures. (default: 5)
     #[arg(long, value_name = "COUNT")]
     circuit_breaker_threshold: Option<u32>,
 
+    /// Per-category circuit breaker thresholds for parallel runs, e.g.
+    /// "fatal=3,quality_gate=10". Listed categories no longer count toward
+    /// --circuit-breaker-threshold.
+    #[arg(long, value_name = "CATEGORY=COUNT,...")]
+    circuit_breaker_categories: Option<String>,
+
+    /// Alert the terminal when the run fails: off, audible, visual or both
+    #[arg(long, value_name = "MODE")]
+    bell: Option<String>,
+
+    /// Exit codes of failed runs: "default" (always 1), "detailed" (a code per
+    /// outcome), and OUTCOME=CODE overrides, e.g. "detailed,timeout=20"
+    #[arg(long, value_name = "POLICY")]
+    exit_codes: Option<String>,
+
     // Token budget settings
     /// Enable token budget tracking and enforcement
     #[arg(long)]
@@ -172,9 +462,6 @@ struct Cli {
     /// Use conservative budget settings (stricter limits, more warnings)
     #[arg(long)]
     budget_conservative: bool,
-
-    #[command(subcommand)]
-    command: Option<Commands>,
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -182,96 +469,8 @@ struct Cli {
 enum Commands {
     /// Run all stories until complete (default behavior if no command given)
     Run {
-        /// Path to PRD file
-        #[arg(long, short, default_value = "prd.json")]
-        prd: PathBuf,
-
-        /// Working directory
-        #[arg(long, short = 'd')]
-        dir: Option<PathBuf>,
-
-        /// Maximum iterations per story
-        #[arg(long, default_value = "10")]
-        max_iterations: u32,
-
-        /// Agent command to use (claude, codex, amp, or custom)
-        #[arg(long)]
-        agent: Option<String>,
-
-        /// Enable parallel story execution
-        #[arg(long)]
-        parallel: bool,
-
-        /// Max concurrent stories (0 = unlimited)
-        #[arg(long, default_value = "3")]
-        max_concurrency: usize,
-
-        /// Max queued stories for parallel execution
-        #[arg(long, default_value = "32")]
-        parallel_queue_capacity: usize,
-
-        /// Backpressure policy when parallel queue is full (block, reject, drop_oldest)
-        #[arg(long, default_value = "block")]
-        parallel_queue_policy: String,
-
-        /// Resume from checkpoint if available
-        #[arg(long)]
-        resume: bool,
-
-        /// Skip checkpoint prompt (do not resume)
-        #[arg(long, conflicts_with = "resume")]
-        no_resume: bool,
-
-        /// Agent timeout in seconds (overrides default)
-        #[arg(long, value_name = "SECONDS")]
-        timeout: Option<u64>,
-
-        /// Heartbeat check interval in seconds. Stall detection triggers after
-        /// interval × threshold seconds of no agent output. Recommended range: 30-120s.
-        /// Example: With interval=60s and threshold=5, stall detection triggers after 300s.
-        /// (default: 60)
-        #[arg(long, value_name = "SECONDS")]
-        heartbeat_interval: Option<u64>,
-
-        /// Number of missed heartbeats before timeout. The effective stall timeout is
-        /// heartbeat_interval × heartbeat_threshold seconds. (default: 5)
-        #[arg(long, value_name = "COUNT")]
-        heartbeat_threshold: Option<u32>,
-
-        /// Initial grace period in seconds before heartbeat monitoring starts.
-        /// Allows time for agent startup and MCP server initialization. (default: 120)
-        #[arg(long, value_name = "SECONDS")]
-        startup_grace_period: Option<u64>,
-
-        /// Disable checkpointing
-        #[arg(long)]
-        no_checkpoint: bool,
-
-        /// Number of consecutive failures before circuit breaker triggers.
-        /// When reached, execution pauses to prevent cascading failures. (default: 5)
-        #[arg(long, value_name = "COUNT")]
-        circuit_breaker_threshold: Option<u32>,
-
-        // Token budget settings
-        /// Enable token budget tracking and enforcement
-        #[arg(long)]
-        budget: bool,
-
-        /// Maximum tokens per story (0 = unlimited)
-        #[arg(long, value_name = "TOKENS", default_value = "100000")]
-        budget_per_story: u64,
-
-        /// Maximum total tokens across all stories (0 = unlimited)
-        #[arg(long, value_name = "TOKENS", default_value = "1000000")]
-        budget_total: u64,
-
-        /// Maximum cost in dollars (0 = unlimited)
-        #[arg(long, value_name = "DOLLARS")]
-        budget_max_cost: Option<f64>,
-
-        /// Use conservative budget settings (stricter limits, more warnings)
-        #[arg(long)]
-        budget_conservative: bool,
+        #[command(flatten)]
+        args: Box<RunArgs>,
 
         /// Print help information
         #[arg(long, short)]
@@ -283,6 +482,28 @@ enum Commands {
         #[arg(long, short)]
         help: bool,
     },
+    /// Run quality gates standalone and report the results
+    Gates {
+        /// Project directory to check
+        #[arg(long, short = 'd', default_value = ".")]
+        dir: PathBuf,
+
+        /// Quality profile to check against
+        #[arg(long, default_value = "standard")]
+        profile: String,
+
+        /// Quality configuration file (relative to the project directory)
+        #[arg(long, default_value = "quality/ralph-quality.toml")]
+        config: PathBuf,
+
+        /// Output format (text, json)
+        #[arg(long, short = 'o', default_value = "text", value_enum)]
+        output: GatesOutputFormat,
+
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
     /// Start MCP server mode for integration with AI assistants
     McpServer {
         /// Path to PRD file to preload (optional)
@@ -327,12 +548,179 @@ enum Commands {
         #[arg(long, short)]
         help: bool,
     },
+    /// Show how a parallel run would schedule stories, without running any agent
+    Plan {
+        /// Path to PRD file
+        #[arg(long, short, default_value = "prd.json")]
+        prd: PathBuf,
+
+        /// Working directory (where run history in .ralph is located)
+        #[arg(long, short = 'd')]
+        dir: Option<PathBuf>,
+
+        /// Max concurrent stories (0 = unlimited)
+        #[arg(long, default_value = "3")]
+        max_concurrency: usize,
+
+        /// Output format (text, json)
+        #[arg(long, short = 'o', default_value = "text", value_enum)]
+        output: PlanOutputFormat,
+
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
+    /// Show when each story of a past run queued, waited on locks and ran
+    Timeline {
+        /// Run to show (default: the most recent run)
+        run_id: Option<String>,
+
+        /// Working directory (where .ralph directory is located)
+        #[arg(long, short = 'd')]
+        dir: Option<PathBuf>,
+
+        /// Width of the chart in columns
+        #[arg(long, default_value = "60")]
+        width: usize,
+
+        /// Also write the timeline as an SVG image to this file
+        #[arg(long, value_name = "FILE")]
+        svg: Option<PathBuf>,
+
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
+    /// Check a PRD file
+    Prd {
+        /// What to do (validate, lint, import, init)
+        #[arg(value_enum, default_value = "validate")]
+        action: PrdAction,
+
+        /// Path to PRD file
+        #[arg(long, short, default_value = "prd.json")]
+        prd: PathBuf,
+
+        /// Repository to import issues from, as OWNER/NAME (default: the current one)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Label imported issues must have (repeatable)
+        #[arg(long = "label", value_name = "LABEL")]
+        labels: Vec<String>,
+
+        /// Maximum number of issues to import
+        #[arg(long, default_value = "100")]
+        limit: usize,
+
+        /// Print the PRD an import would write instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output format (text, json)
+        #[arg(long, short = 'o', default_value = "text", value_enum)]
+        output: PlanOutputFormat,
+
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
+    /// Run one story with several agents or models and compare the results
+    Compare {
+        /// Story to run
+        #[arg(long, short)]
+        story: String,
+
+        /// Agent to compare, optionally with a model: AGENT[@MODEL] (repeat, at least twice)
+        #[arg(long = "variant", value_name = "AGENT[@MODEL]")]
+        variants: Vec<String>,
+
+        /// Path to PRD file
+        #[arg(long, short, default_value = "prd.json")]
+        prd: PathBuf,
+
+        /// Working directory (a git repository)
+        #[arg(long, short = 'd')]
+        dir: Option<PathBuf>,
+
+        /// Maximum iterations per variant
+        #[arg(long, default_value = "10")]
+        max_iterations: u32,
+
+        /// Output format (text, json)
+        #[arg(long, short = 'o', default_value = "text", value_enum)]
+        output: PlanOutputFormat,
+
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
+    /// Run stories dispatched by a parallel run on another machine
+    Worker {
+        /// Address to bind to
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+
+        /// Port to listen on
+        #[arg(long, default_value = "7870")]
+        port: u16,
+
+        /// Stories to run at once
+        #[arg(long, default_value = "1")]
+        capacity: usize,
+
+        /// Directory stories are cloned into (defaults to a temporary directory)
+        #[arg(long)]
+        work_dir: Option<PathBuf>,
+
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
+    /// List, inspect or delete saved checkpoints
+    Checkpoint {
+        /// What to do (list, show, delete)
+        #[arg(value_enum, default_value = "list")]
+        action: CheckpointAction,
+
+        /// Checkpoint to show or delete (defaults to the only checkpoint)
+        name: Option<String>,
+
+        /// Delete every checkpoint
+        #[arg(long)]
+        all: bool,
+
+        /// Working directory (where .ralph directory is located)
+        #[arg(long, short = 'd')]
+        dir: Option<PathBuf>,
+
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
     /// Check execution state without starting a run
     Status {
         /// Working directory (where .ralph directory is located)
         #[arg(long, short = 'd')]
         dir: Option<PathBuf>,
 
+        /// Print help information
+        #[arg(long, short)]
+        help: bool,
+    },
+    /// Stop a running run: cancel its agents, checkpoint it and exit
+    Stop {
+        /// Why the run is being stopped (recorded with the checkpoint)
+        reason: Option<String>,
+
+        /// Withdraw a stop that has not taken effect yet
+        #[arg(long)]
+        clear: bool,
+
+        /// Working directory (where .ralph directory is located)
+        #[arg(long, short = 'd')]
+        dir: Option<PathBuf>,
+
         /// Print help information
         #[arg(long, short)]
         help: bool,
@@ -341,9 +729,20 @@ enum Commands {
 
 /// Build display options from CLI arguments
 fn build_display_options(cli: &Cli) -> DisplayOptions {
+    // RALPH_THEME and RALPH_SYMBOLS override the flags
+    let theme = std::env::var("RALPH_THEME")
+        .ok()
+        .and_then(|theme| theme.parse().ok())
+        .unwrap_or(cli.theme.into());
+    let symbols = std::env::var("RALPH_SYMBOLS")
+        .ok()
+        .and_then(|symbols| symbols.parse().ok())
+        .unwrap_or(cli.symbols.into());
     DisplayOptions::new()
         .with_ui_mode(cli.ui.into())
         .with_color(!cli.no_color)
+        .with_theme(theme)
+        .with_symbols(symbols)
         .with_quiet(cli.quiet)
         .with_verbosity(cli.verbose)
         .with_streaming(true) // Streaming is now default
@@ -378,7 +777,7 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
     let display_options = build_display_options(&cli);
 
     // Create help renderer with color and animation settings
-    let use_color = !cli.no_color && std::env::var("NO_COLOR").is_err();
+    let use_color = display_options.should_enable_colors();
     let help_renderer = HelpRenderer::new()
         .with_color(use_color)
         .with_animation(!cli.no_animation);
@@ -414,10 +813,88 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
                 "  --parallel-queue-capacity <N>  Max queued stories for parallel execution [default: 32]"
             );
             println!(
-                "  --parallel-queue-policy <POLICY>  Backpressure policy (block, reject, drop_oldest) [default: block]"
+                "  --parallel-queue-policy <POLICY>  Backpressure policy (block, reject, drop_oldest,"
+            );
+            println!("                                  evict_lowest_priority) [default: block]");
+            println!(
+                "  --parallel-isolation <MODE>  Where parallel stories run (shared, worktree, branch) [default: shared]"
+            );
+            println!(
+                "  --adaptive-concurrency   Scale concurrency with load, throttling and failures"
+            );
+            println!(
+                "  --watch-prd              Schedule stories added or edited in the PRD during the run"
+            );
+            println!(
+                "  --reconcile-mode <MODE>  When reconciliation runs (idle, every, end, shared_dirs) [default: idle]"
+            );
+            println!(
+                "  --reconcile-every <N>    Finished stories between reconciliation passes (0 = when idle) [default: 0]"
+            );
+            println!(
+                "  --merge-resolution <STRATEGY,...>  Resolve conflicting story branches (priority, rerere, agent)"
+            );
+            println!(
+                "  --reconcile-agent        Have an agent fix reconciliation issues before sequential retry"
+            );
+            println!(
+                "  --dashboard <ADDR>       Serve a live web dashboard of the run (e.g. 127.0.0.1:8080)"
+            );
+            println!(
+                "  --output <FORMAT>        Output format: text, jsonl (events as JSON lines),"
+            );
+            println!(
+                "                           github (GitHub Actions annotations and job summary),"
+            );
+            println!("                           summary (only a final report) [default: text]");
+            println!(
+                "  --log                    Mirror events and full agent/gate output to .ralph/logs"
+            );
+            println!("  --log-max-size <MB>      Rotate the run log at this size [default: 10]");
+            println!(
+                "  --remote-workers <URL,...>  Run parallel stories on `ralph worker` processes"
+            );
+            println!(
+                "  --remote-repo <REMOTE>   Git remote shared with remote workers [default: origin]"
+            );
+            println!("  --only <ID,...>          Run only these stories (parallel)");
+            println!("  --skip <ID,...>          Leave these stories out (parallel)");
+            println!(
+                "  --story-retries <N>      Max retries for stories failing with retryable errors"
+            );
+            println!("  --retry-budgets <CATEGORY=COUNT,...>  Retries per error category");
+            println!("  --worker-max-rss <MB>    Memory limit for worker subprocesses");
+            println!("  --worker-cpu-shares <N>  CPU weight for worker subprocesses (1-1024)");
+            println!("  --worker-scratch-quota <MB>  Per-story scratch directory quota");
+            println!(
+                "  --min-memory-headroom <MB>  Free memory required to dispatch heavy stories"
+            );
+            println!(
+                "  --lock-wait-timeout <SECONDS>  Fail stories waiting this long on file locks (0 = no limit)"
+            );
+            println!(
+                "  --agent-launches-per-minute <N>  Rate limit agent launches across workers (0 = unlimited)"
+            );
+            println!("  --agent-container <IMAGE>  Run the agent in a container with only the project mounted");
+            println!("  --agent-container-runtime <RUNTIME>  docker or podman [default: docker]");
+            println!(
+                "  --agent-network <NETWORK>  default, none, or a named network [default: default]"
+            );
+            println!("  --agent-memory <SIZE>    Memory limit of the agent container");
+            println!("  --agent-cpus <N>         CPU limit of the agent container");
+            println!("  --agent-env <VAR>        Pass a host variable into the agent container");
+            println!("  --agent-mount <SPEC>     Extra volume for the agent container");
+            println!(
+                "  --context-tokens <TOKENS>  Include the most relevant files in prompts, up to this many tokens"
             );
             println!("  --resume                 Resume from checkpoint if available");
             println!("  --no-resume              Skip checkpoint prompt (do not resume)");
+            println!(
+                "  --auto-resume            Resume without prompting if the checkpoint matches the PRD and branch"
+            );
+            println!(
+                "  --force                  Resume even if the PRD changed since the checkpoint"
+            );
             println!("  --timeout <SECONDS>      Agent timeout in seconds (overrides default)");
             println!("  --heartbeat-interval <SECONDS>  Heartbeat check interval [default: 60]");
             println!(
@@ -428,62 +905,33 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
                 "  --startup-grace-period <SECONDS>  Initial startup grace period [default: 120]"
             );
             println!("  --no-checkpoint          Disable checkpointing");
+            println!("  --no-writeback           Do not mark passing stories in the PRD");
+            println!(
+                "  --final-verify           Run all quality gates on the integrated tree at the end"
+            );
+            println!(
+                "  --checkpoint-interval <SECONDS>  Autosave a checkpoint this often (0 = off) [default: 300]"
+            );
             println!(
                 "  --circuit-breaker-threshold <COUNT>  Failures before circuit breaker [default: 5]"
             );
-            println!("  --agent <CMD>            Agent command (claude, codex, amp, or custom)");
+            println!(
+                "  --circuit-breaker-categories <CATEGORY=COUNT,...>  Per-category thresholds"
+            );
+            println!("                                  (quality_gate, fatal, usage_limit, timeout)");
+            println!("  --bell <MODE>            Alert on failure: off, audible, visual, both [default: off]");
+            println!(
+                "  --exit-codes <POLICY>    Exit codes of failed runs: default, detailed, OUTCOME=CODE,..."
+            );
+            println!("  --agent <CMD>            Agent command (claude, codex, amp, aider, mock, or custom)");
             println!("  -h, --help               Print help information");
             return Ok(ExitCode::SUCCESS);
         }
         Some(Commands::Run {
-            ref prd,
-            ref dir,
-            max_iterations,
-            ref agent,
-            parallel,
-            max_concurrency,
-            parallel_queue_capacity,
-            ref parallel_queue_policy,
-            resume,
-            no_resume,
-            timeout,
-            heartbeat_interval,
-            heartbeat_threshold,
-            startup_grace_period,
-            no_checkpoint,
-            circuit_breaker_threshold,
-            budget,
-            budget_per_story,
-            budget_total,
-            budget_max_cost,
-            budget_conservative,
+            ref args,
             help: false,
         }) => {
-            run_stories(
-                &cli,
-                prd.clone(),
-                dir.clone(),
-                max_iterations,
-                parallel,
-                max_concurrency,
-                parallel_queue_capacity,
-                parallel_queue_policy.clone(),
-                resume,
-                no_resume,
-                timeout,
-                heartbeat_interval,
-                heartbeat_threshold,
-                startup_grace_period,
-                no_checkpoint,
-                circuit_breaker_threshold,
-                agent.clone(),
-                budget,
-                budget_per_story,
-                budget_total,
-                budget_max_cost,
-                budget_conservative,
-            )
-            .await?;
+            return run_stories(&cli, args, args.prd.clone()).await;
         }
         Some(Commands::Quality { help: true }) => {
             println!("Run quality checks (typecheck, lint, test)");
@@ -501,6 +949,34 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
                 println!("Running quality checks...");
             }
         }
+        Some(Commands::Gates { help: true, .. }) => {
+            println!("Run quality gates standalone and report the results");
+            println!();
+            println!("Usage: ralph gates [OPTIONS]");
+            println!();
+            println!("Options:");
+            println!("  -d, --dir <DIR>        Project directory to check [default: .]");
+            println!(
+                "  --profile <NAME>       Quality profile to check against [default: standard]"
+            );
+            println!("  --config <FILE>        Quality configuration file [default: quality/ralph-quality.toml]");
+            println!("  -o, --output <FORMAT>  Output format: text, json [default: text]");
+            println!("  -h, --help             Print help information");
+            println!();
+            println!("Exit codes:");
+            println!("  0   All blocking gates passed");
+            println!("  1   A blocking gate failed");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Gates {
+            ref dir,
+            ref profile,
+            ref config,
+            output,
+            help: false,
+        }) => {
+            return run_gates(dir, profile, config, output, cli.quiet);
+        }
         Some(Commands::McpServer { help: true, .. }) => {
             println!("Start MCP server mode for integration with AI assistants");
             println!();
@@ -583,6 +1059,197 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
             )
             .await?;
         }
+        Some(Commands::Plan { help: true, .. }) => {
+            println!("Show how a parallel run would schedule stories, without running any agent");
+            println!();
+            println!("Usage: ralph plan [OPTIONS]");
+            println!();
+            println!("Options:");
+            println!("  -p, --prd <FILE>         Path to PRD file [default: prd.json]");
+            println!("  -d, --dir <DIR>          Working directory (for run history) [default: .]");
+            println!(
+                "  --max-concurrency <N>    Max concurrent stories (0 = unlimited) [default: 3]"
+            );
+            println!("  -o, --output <FORMAT>    Output format: text, json [default: text]");
+            println!("  -h, --help               Print help information");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Plan {
+            ref prd,
+            ref dir,
+            max_concurrency,
+            output,
+            help: false,
+        }) => {
+            return run_plan(prd, dir.clone(), max_concurrency, output, cli.quiet);
+        }
+        Some(Commands::Timeline { help: true, .. }) => {
+            println!("Show when each story of a past run queued, waited on locks and ran");
+            println!();
+            println!("Usage: ralph timeline [OPTIONS] [RUN_ID]");
+            println!();
+            println!("Arguments:");
+            println!("  [RUN_ID]                 Run to show [default: the most recent run]");
+            println!();
+            println!("Options:");
+            println!("  -d, --dir <DIR>          Working directory [default: .]");
+            println!("  --width <N>              Width of the chart in columns [default: 60]");
+            println!("  --svg <FILE>             Also write the timeline as an SVG image");
+            println!("  -h, --help               Print help information");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Timeline {
+            ref run_id,
+            ref dir,
+            width,
+            ref svg,
+            help: false,
+        }) => {
+            return run_timeline(run_id.as_deref(), dir.clone(), width, svg.as_deref());
+        }
+        Some(Commands::Prd { help: true, .. }) => {
+            println!("Check a PRD file");
+            println!();
+            println!("Usage: ralph prd [validate|lint|import|init] [OPTIONS]");
+            println!();
+            println!("Actions:");
+            println!("  validate   Report every schema violation with its JSON pointer and line [default]");
+            println!("  lint       Also flag duplicate IDs, unknown or cyclic dependencies, stories");
+            println!("             without acceptance criteria, broad targetFiles globs and stories");
+            println!("             estimated over the per-story token budget (--budget-per-story)");
+            println!("  import     Add or update GH-<number> stories from open GitHub issues (needs gh)");
+            println!("  init       Create a PRD by answering questions about each story");
+            println!();
+            println!("Options:");
+            println!("  -p, --prd <FILE>       Path to PRD file [default: prd.json]");
+            println!("  -o, --output <FORMAT>  Output format: text, json [default: text]");
+            println!("  --repo <OWNER/NAME>    Repository to import from [default: current]");
+            println!("  --label <LABEL>        Label imported issues must have (repeatable)");
+            println!("  --limit <N>            Maximum issues to import [default: 100]");
+            println!("  --dry-run              Print the imported PRD instead of writing it");
+            println!("  -h, --help             Print help information");
+            println!();
+            println!("Exits with 1 if the PRD is invalid or lint finds errors, so it can gate CI.");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Prd {
+            action,
+            ref prd,
+            ref repo,
+            ref labels,
+            limit,
+            dry_run,
+            output,
+            help: false,
+        }) => {
+            return match action {
+                PrdAction::Validate | PrdAction::Lint => run_prd_check(
+                    action == PrdAction::Lint,
+                    prd,
+                    cli.run.budget_per_story,
+                    output,
+                    cli.quiet,
+                ),
+                PrdAction::Import => {
+                    let query = ralphmacchio::prd::IssueQuery {
+                        repo: repo.clone(),
+                        labels: labels.clone(),
+                        limit,
+                    };
+                    run_prd_import(prd, &query, dry_run, cli.quiet)
+                }
+                PrdAction::Init => run_prd_init(prd),
+            };
+        }
+        Some(Commands::Compare { help: true, .. }) => {
+            println!("Run one story with several agents or models and compare the results");
+            println!();
+            println!("Usage: ralph compare --story <ID> --variant <AGENT[@MODEL]> --variant <AGENT[@MODEL]> [OPTIONS]");
+            println!();
+            println!("Options:");
+            println!("  -s, --story <ID>             Story to run");
+            println!("  --variant <AGENT[@MODEL]>    Agent to compare, e.g. claude@claude-haiku-4-5 (repeatable)");
+            println!("  -p, --prd <FILE>             Path to PRD file [default: prd.json]");
+            println!("  -d, --dir <DIR>              Working directory (a git repository) [default: .]");
+            println!("  --max-iterations <N>         Max iterations per variant [default: 10]");
+            println!("  -o, --output <FORMAT>        Output format: text, json [default: text]");
+            println!("  -h, --help                   Print help information");
+            println!();
+            println!("Each variant runs in its own git worktree. The report is saved under");
+            println!(".ralph/experiments/ and the branches of passing variants are kept.");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Compare {
+            ref story,
+            ref variants,
+            ref prd,
+            ref dir,
+            max_iterations,
+            output,
+            help: false,
+        }) => {
+            return run_compare(
+                story,
+                variants,
+                prd,
+                dir.clone(),
+                max_iterations,
+                output,
+                cli.quiet,
+            )
+            .await;
+        }
+        Some(Commands::Worker { help: true, .. }) => {
+            println!("Run stories dispatched by a parallel run on another machine");
+            println!();
+            println!("Usage: ralph worker [OPTIONS]");
+            println!();
+            println!("Options:");
+            println!("  --bind <ADDR>        Address to bind to [default: 127.0.0.1]");
+            println!("  --port <PORT>        Port to listen on [default: 7870]");
+            println!("  --capacity <N>       Stories to run at once [default: 1]");
+            println!("  --work-dir <DIR>     Directory stories are cloned into");
+            println!("  -h, --help           Print help information");
+            println!();
+            println!("Stories run with the global --agent (ralph --agent claude worker) or the");
+            println!("detected agent. Set RALPH_WORKER_TOKEN to require coordinators to send");
+            println!("the same token.");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Worker {
+            ref bind,
+            port,
+            capacity,
+            ref work_dir,
+            help: false,
+        }) => {
+            return run_worker(&cli, bind, port, capacity, work_dir.clone()).await;
+        }
+        Some(Commands::Checkpoint { help: true, .. }) => {
+            println!("List, inspect or delete saved checkpoints");
+            println!();
+            println!("Usage: ralph checkpoint [list|show|delete] [NAME] [OPTIONS]");
+            println!();
+            println!("Actions:");
+            println!("  list     List saved checkpoints with their pause reason and age [default]");
+            println!("  show     Show the stories and files recorded in a checkpoint");
+            println!("  delete   Delete a checkpoint (or every checkpoint with --all)");
+            println!();
+            println!("Options:");
+            println!("  --all            Delete every checkpoint");
+            println!("  -d, --dir <DIR>  Working directory [default: .]");
+            println!("  -h, --help       Print help information");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Checkpoint {
+            action,
+            ref name,
+            all,
+            ref dir,
+            help: false,
+        }) => {
+            return run_checkpoint(action, name.as_deref(), all, dir.clone(), cli.quiet);
+        }
         Some(Commands::Status { help: true, .. }) => {
             println!("Check execution state without starting a run");
             println!();
@@ -604,36 +1271,38 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
         }) => {
             return run_status(dir.clone(), cli.quiet);
         }
+        Some(Commands::Stop { help: true, .. }) => {
+            println!("Stop a running run: cancel its agents, checkpoint it and exit");
+            println!();
+            println!("Usage: ralph stop [OPTIONS] [REASON]");
+            println!();
+            println!("Creates .ralph/STOP, which running runs check every few seconds.");
+            println!("The run removes the file once it has stopped; resume with");
+            println!("'ralph --resume'.");
+            println!();
+            println!("Arguments:");
+            println!("  [REASON]         Why the run is being stopped");
+            println!();
+            println!("Options:");
+            println!("      --clear      Withdraw a stop that has not taken effect yet");
+            println!("  -d, --dir <DIR>  Working directory [default: .]");
+            println!("  -h, --help       Print help information");
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Commands::Stop {
+            ref reason,
+            clear,
+            ref dir,
+            help: false,
+        }) => {
+            return run_stop(dir.clone(), reason.as_deref(), clear, cli.quiet);
+        }
         None => {
             // Default: run stories if prd.json exists, otherwise show help
             // Check multiple locations: prd.json, ralph/prd.json
-            let prd_path = find_prd_file(&cli.prd);
+            let prd_path = find_prd_file(&cli.run.prd);
             if let Some(prd) = prd_path {
-                run_stories(
-                    &cli,
-                    prd,
-                    cli.dir.clone(),
-                    cli.max_iterations,
-                    cli.parallel,
-                    cli.max_concurrency,
-                    cli.parallel_queue_capacity,
-                    cli.parallel_queue_policy.clone(),
-                    cli.resume,
-                    cli.no_resume,
-                    cli.timeout,
-                    cli.heartbeat_interval,
-                    cli.heartbeat_threshold,
-                    cli.startup_grace_period,
-                    cli.no_checkpoint,
-                    cli.circuit_breaker_threshold,
-                    cli.agent.clone(),
-                    cli.budget,
-                    cli.budget_per_story,
-                    cli.budget_total,
-                    cli.budget_max_cost,
-                    cli.budget_conservative,
-                )
-                .await?;
+                return run_stories(&cli, &cli.run, prd).await;
             } else {
                 print!("{}", help_renderer.render_help());
             }
@@ -662,9 +1331,25 @@ fn find_prd_file(default_path: &std::path::Path) -> Option<PathBuf> {
         return Some(dot_ralph_prd);
     }
 
+    // Check for a workspace manifest of several PRDs
+    let workspace = PathBuf::from(ralphmacchio::prd::WORKSPACE_FILE);
+    if workspace.exists() {
+        return Some(workspace);
+    }
+
     None
 }
 
+/// Splits a comma-separated list of story IDs, ignoring blank entries.
+fn story_ids(list: Option<String>) -> Vec<String> {
+    list.iter()
+        .flat_map(|list| list.split(','))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 fn env_var_truthy(name: &str) -> bool {
     std::env::var(name)
         .ok()
@@ -672,38 +1357,69 @@ fn env_var_truthy(name: &str) -> bool {
         .unwrap_or(false)
 }
 
-/// Run stories from the PRD until all pass
-#[allow(clippy::too_many_arguments)]
+/// Container configuration for the agent from the `--agent-container*`
+/// flags, or `None` when no image is given.
+fn agent_container_config(
+    image: Option<String>,
+    runtime: &str,
+    network: &str,
+    memory: Option<String>,
+    cpus: Option<String>,
+    env: Vec<String>,
+    mounts: Vec<String>,
+) -> Result<Option<AgentContainer>, Box<dyn std::error::Error>> {
+    let Some(image) = std::env::var("RALPH_AGENT_CONTAINER").ok().or(image) else {
+        return Ok(None);
+    };
+    let network: AgentNetwork = network
+        .parse()
+        .map_err(|e| format!("Invalid --agent-network: {}", e))?;
+    let mut container = AgentContainer::new(image)
+        .with_runtime(runtime)
+        .with_network(network)
+        .with_env(env)
+        .with_mounts(mounts);
+    if let Some(memory) = memory {
+        container = container.with_memory(memory);
+    }
+    if let Some(cpus) = cpus {
+        container = container.with_cpus(cpus);
+    }
+    Ok(Some(container))
+}
+
+/// Run stories from the PRD at `prd` until all pass
 async fn run_stories(
     cli: &Cli,
+    args: &RunArgs,
     prd: PathBuf,
-    dir: Option<PathBuf>,
-    max_iterations: u32,
-    parallel: bool,
-    max_concurrency: usize,
-    parallel_queue_capacity: usize,
-    parallel_queue_policy: String,
-    resume: bool,
-    no_resume: bool,
-    timeout: Option<u64>,
-    heartbeat_interval: Option<u64>,
-    heartbeat_threshold: Option<u32>,
-    startup_grace_period: Option<u64>,
-    no_checkpoint: bool,
-    circuit_breaker_threshold: Option<u32>,
-    agent: Option<String>,
-    budget_enabled: bool,
-    budget_per_story: u64,
-    budget_total: u64,
-    budget_max_cost: Option<f64>,
-    budget_conservative: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
     use ralphmacchio::mcp::tools::executor::detect_agent;
+    use ralphmacchio::parallel::circuit_breaker::parse_category_thresholds;
+    use ralphmacchio::parallel::lock_wait::LockWaitPolicy;
+    use ralphmacchio::parallel::resolution::parse_resolutions;
+    use ralphmacchio::parallel::retry::{parse_retry_budgets, StoryRetryPolicy};
+    use ralphmacchio::parallel::scheduler::IsolationMode;
     use ralphmacchio::parallel::scheduler::ParallelRunnerConfig;
     use ralphmacchio::parallel::scheduler::QueuePolicy;
-
-    let working_dir = dir.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-    let display_options = build_display_options(cli);
+    use ralphmacchio::parallel::scheduler::ReconcileMode;
+
+    let working_dir = args
+        .dir
+        .clone()
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+    let output = match std::env::var("RALPH_OUTPUT").as_deref() {
+        Ok("jsonl") => RunOutputFormat::Jsonl,
+        Ok("github") => RunOutputFormat::Github,
+        Ok("summary") => RunOutputFormat::Summary,
+        Ok("text") => RunOutputFormat::Text,
+        _ => args.output,
+    };
+    let jsonl_events = output == RunOutputFormat::Jsonl;
+    let summary_report = output == RunOutputFormat::Summary;
+    // JSON lines or the final report replace every other output on stdout
+    let display_options =
+        build_display_options(cli).with_quiet(cli.quiet || jsonl_events || summary_report);
 
     // Build parallel config with the specified max_concurrency
     // 0 means unlimited, which we represent with usize::MAX
@@ -711,43 +1427,189 @@ async fn run_stories(
         .ok()
         .and_then(|value| value.parse::<usize>().ok());
     let env_queue_policy = std::env::var("RALPH_PARALLEL_QUEUE_POLICY").ok();
+    let env_isolation = std::env::var("RALPH_PARALLEL_ISOLATION").ok();
+    let env_reconcile_mode = std::env::var("RALPH_RECONCILE_MODE").ok();
+    let env_reconcile_every = std::env::var("RALPH_RECONCILE_EVERY")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok());
+    let env_story_retries = std::env::var("RALPH_STORY_RETRIES")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok());
+    let retry_policy = match env_story_retries.or(args.story_retries) {
+        Some(max_retries) => StoryRetryPolicy::default().with_max_retries(max_retries),
+        None => StoryRetryPolicy::default(),
+    };
+    let retry_policy = match std::env::var("RALPH_RETRY_BUDGETS")
+        .ok()
+        .or_else(|| args.retry_budgets.clone())
+    {
+        Some(spec) => parse_retry_budgets(&spec)
+            .map_err(|e| format!("Invalid --retry-budgets: {}", e))?
+            .into_iter()
+            .fold(retry_policy, |policy, (label, count)| {
+                policy.with_category_retries(label, count)
+            }),
+        None => retry_policy,
+    };
+    let env_u64 = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    let worker_limits = ResourceLimits {
+        max_rss_mb: env_u64("RALPH_WORKER_MAX_RSS").or(args.worker_max_rss),
+        cpu_shares: std::env::var("RALPH_WORKER_CPU_SHARES")
+            .ok()
+            .and_then(|value| value.parse::<u32>().ok())
+            .or(args.worker_cpu_shares),
+        scratch_quota_mb: env_u64("RALPH_WORKER_SCRATCH_QUOTA").or(args.worker_scratch_quota),
+    };
+    let min_memory_headroom = env_u64("RALPH_MIN_MEMORY_HEADROOM").or(args.min_memory_headroom);
+    let autosave_interval = match env_u64("RALPH_CHECKPOINT_INTERVAL").or(args.checkpoint_interval)
+    {
+        Some(secs) => (secs > 0).then(|| std::time::Duration::from_secs(secs)),
+        None => Some(RunnerConfig::DEFAULT_AUTOSAVE_INTERVAL),
+    };
+    let mut lock_wait = LockWaitPolicy::default();
+    if let Some(secs) = env_u64("RALPH_LOCK_WAIT_TIMEOUT").or(args.lock_wait_timeout) {
+        lock_wait.fail_after = (secs > 0).then(|| std::time::Duration::from_secs(secs));
+    }
+    let circuit_breaker_categories = match std::env::var("RALPH_CIRCUIT_BREAKER_CATEGORIES")
+        .ok()
+        .or_else(|| args.circuit_breaker_categories.clone())
+    {
+        Some(spec) => parse_category_thresholds(&spec)
+            .map_err(|e| format!("Invalid --circuit-breaker-categories: {}", e))?,
+        None => Default::default(),
+    };
+    let bell = match std::env::var("RALPH_BELL")
+        .ok()
+        .or_else(|| args.bell.clone())
+    {
+        Some(mode) => mode
+            .parse::<BellMode>()
+            .map_err(|e| format!("Invalid --bell: {}", e))?,
+        None => BellMode::Off,
+    };
+    let exit_codes = match std::env::var("RALPH_EXIT_CODES")
+        .ok()
+        .or_else(|| args.exit_codes.clone())
+    {
+        Some(spec) => {
+            ExitCodePolicy::parse(&spec).map_err(|e| format!("Invalid --exit-codes: {}", e))?
+        }
+        None => ExitCodePolicy::default(),
+    };
+
+    let reconcile_mode = ReconcileMode::from_label(
+        env_reconcile_mode
+            .as_deref()
+            .unwrap_or(args.reconcile_mode.as_str()),
+        env_reconcile_every.unwrap_or(args.reconcile_every),
+    )
+    .map_err(|e| format!("Invalid --reconcile-mode: {}", e))?;
+    let merge_resolution = match std::env::var("RALPH_MERGE_RESOLUTION")
+        .ok()
+        .or_else(|| args.merge_resolution.clone())
+    {
+        Some(spec) => {
+            parse_resolutions(&spec).map_err(|e| format!("Invalid --merge-resolution: {}", e))?
+        }
+        None => Vec::new(),
+    };
+
+    let dashboard = match std::env::var("RALPH_DASHBOARD")
+        .ok()
+        .or_else(|| args.dashboard.clone())
+    {
+        Some(_) if !cfg!(feature = "dashboard") => {
+            return Err("--dashboard needs Ralph built with the `dashboard` feature".into());
+        }
+        Some(addr) => Some(
+            addr.parse::<std::net::SocketAddr>()
+                .map_err(|e| format!("Invalid --dashboard: {}", e))?,
+        ),
+        None => None,
+    };
 
     let queue_policy = match env_queue_policy
         .as_deref()
-        .unwrap_or(parallel_queue_policy.as_str())
+        .unwrap_or(args.parallel_queue_policy.as_str())
     {
         "reject" => QueuePolicy::Reject,
         "drop_oldest" => QueuePolicy::DropOldest,
+        "evict_lowest_priority" => QueuePolicy::EvictLowestPriority,
         _ => QueuePolicy::Block,
     };
 
     let parallel_config = ParallelRunnerConfig {
-        max_concurrency: if max_concurrency == 0 {
+        max_concurrency: if args.max_concurrency == 0 {
             u32::MAX
         } else {
-            max_concurrency as u32
+            args.max_concurrency as u32
         },
-        queue_capacity: env_queue_capacity.unwrap_or(parallel_queue_capacity).max(1),
+        queue_capacity: env_queue_capacity
+            .unwrap_or(args.parallel_queue_capacity)
+            .max(1),
         queue_policy,
-        circuit_breaker_threshold: circuit_breaker_threshold.unwrap_or(5),
+        circuit_breaker_threshold: args.circuit_breaker_threshold.unwrap_or(5),
+        circuit_breaker_categories,
+        isolation: IsolationMode::from_label(
+            env_isolation
+                .as_deref()
+                .unwrap_or(args.parallel_isolation.as_str()),
+        ),
+        adaptive_concurrency: args.adaptive_concurrency
+            || env_var_truthy("RALPH_ADAPTIVE_CONCURRENCY"),
+        reconcile_mode,
+        merge_resolution,
+        reconcile_agent: args.reconcile_agent || env_var_truthy("RALPH_RECONCILE_AGENT"),
+        retry_policy,
+        worker_limits,
+        min_memory_headroom_mb: min_memory_headroom,
+        lock_wait,
+        agent_launches_per_minute: env_u64("RALPH_AGENT_LAUNCHES_PER_MINUTE")
+            .and_then(|launches| u32::try_from(launches).ok())
+            .or(args.agent_launches_per_minute)
+            .filter(|&launches| launches > 0),
+        remote_workers: std::env::var("RALPH_REMOTE_WORKERS")
+            .ok()
+            .or_else(|| args.remote_workers.clone())
+            .map(|urls| urls.split(',').map(str::to_string).collect())
+            .unwrap_or_default(),
+        remote_repo: std::env::var("RALPH_REMOTE_REPO")
+            .unwrap_or_else(|_| args.remote_repo.clone()),
+        remote_token: std::env::var("RALPH_WORKER_TOKEN").ok(),
+        only_stories: story_ids(
+            std::env::var("RALPH_ONLY_STORIES")
+                .ok()
+                .or_else(|| args.only.clone()),
+        ),
+        skip_stories: story_ids(
+            std::env::var("RALPH_SKIP_STORIES")
+                .ok()
+                .or_else(|| args.skip.clone()),
+        ),
+        watch_prd: args.watch_prd || env_var_truthy("RALPH_WATCH_PRD"),
+        dashboard,
         ..Default::default()
     };
 
-    let resolved_agent = agent.clone().or_else(detect_agent);
+    let resolved_agent = args.agent.clone().or_else(detect_agent);
     let codex_autonomous = resolved_agent
         .as_deref()
         .map(|value| value.contains("codex"))
         .unwrap_or(false);
     let env_autonomous = env_var_truthy("RALPH_AUTONOMOUS") || codex_autonomous;
-    let (resume, no_resume) = if !resume && !no_resume && env_autonomous {
+    let (resume, no_resume) = if !args.resume && !args.no_resume && env_autonomous {
         (true, false)
     } else {
-        (resume, no_resume)
+        (args.resume, args.no_resume)
     };
 
     // Build budget configuration if enabled
-    let budget_config = if budget_enabled {
-        let base_config = if budget_conservative {
+    let budget_config = if args.budget {
+        let base_config = if args.budget_conservative {
             TokenBudgetConfig::conservative()
         } else {
             TokenBudgetConfig::new()
@@ -755,14 +1617,24 @@ async fn run_stories(
 
         Some(
             base_config
-                .with_story_budget(budget_per_story)
-                .with_total_budget(budget_total)
-                .with_max_cost(budget_max_cost.unwrap_or(0.0) * 100.0) // Convert dollars to cents
+                .with_story_budget(args.budget_per_story)
+                .with_total_budget(args.budget_total)
+                .with_max_cost(args.budget_max_cost.unwrap_or(0.0) * 100.0), // Convert dollars to cents
         )
     } else {
         None
     };
 
+    // Mirror the run to .ralph/logs; a size limit alone also turns the log on
+    let log_max_size = env_u64("RALPH_LOG_MAX_SIZE").or(args.log_max_size);
+    let run_log = (args.log || log_max_size.is_some() || env_var_truthy("RALPH_LOG")).then(|| {
+        let mut config = RunLogConfig::default();
+        if let Some(mb) = log_max_size {
+            config.max_bytes = mb.max(1) * 1024 * 1024;
+        }
+        config
+    });
+
     let config = RunnerConfig {
         prd_path: if prd.is_absolute() {
             prd
@@ -770,37 +1642,55 @@ async fn run_stories(
             working_dir.join(&prd)
         },
         working_dir: working_dir.clone(),
-        max_iterations_per_story: max_iterations,
-        max_total_iterations: 0, // unlimited
-        agent_command: agent,    // auto-detect if None
+        max_iterations_per_story: args.max_iterations,
+        max_total_iterations: 0,           // unlimited
+        agent_command: args.agent.clone(), // auto-detect if None
         display_options,
-        parallel,
+        parallel: args.parallel,
         parallel_config: Some(parallel_config),
         resume,
         no_resume,
-        timeout_seconds: timeout,
-        heartbeat_interval_seconds: heartbeat_interval,
-        heartbeat_threshold,
-        startup_grace_period_seconds: startup_grace_period,
-        no_checkpoint,
-        circuit_breaker_threshold,
+        auto_resume: args.auto_resume || env_var_truthy("RALPH_AUTO_RESUME"),
+        force_resume: args.force,
+        timeout_seconds: args.timeout,
+        heartbeat_interval_seconds: args.heartbeat_interval,
+        heartbeat_threshold: args.heartbeat_threshold,
+        startup_grace_period_seconds: args.startup_grace_period,
+        no_checkpoint: args.no_checkpoint,
+        autosave_interval,
+        circuit_breaker_threshold: args.circuit_breaker_threshold,
         budget_config,
+        agent_container: agent_container_config(
+            args.agent_container.clone(),
+            &args.agent_container_runtime,
+            &args.agent_network,
+            args.agent_memory.clone(),
+            args.agent_cpus.clone(),
+            args.agent_env.clone(),
+            args.agent_mount.clone(),
+        )?,
+        file_context_tokens: args.context_tokens.filter(|&tokens| tokens > 0),
+        prd_writeback: !(args.no_writeback || env_var_truthy("RALPH_NO_WRITEBACK")),
+        final_verification: args.final_verify || env_var_truthy("RALPH_FINAL_VERIFY"),
+        jsonl_events,
+        github_actions: output == RunOutputFormat::Github,
+        summary_report,
+        run_log,
+        bell,
     };
 
     let runner = Runner::new(config);
     let result = runner.run().await;
 
-    if result.all_passed {
-        Ok(())
-    } else {
-        Err(format!(
-            "Failed: {}/{} stories passed. {}",
+    if !result.all_passed {
+        eprintln!(
+            "Error: Failed: {}/{} stories passed. {}",
             result.stories_passed,
             result.total_stories,
             result.error.unwrap_or_default()
-        )
-        .into())
+        );
     }
+    Ok(ExitCode::from(exit_codes.code(result.outcome)))
 }
 
 /// Run the codebase audit
@@ -1001,14 +1891,124 @@ fn write_output(path: &Option<PathBuf>, content: &str) -> Result<(), Box<dyn std
     Ok(())
 }
 
+/// Run the quality gates standalone and print the report
+fn run_gates(
+    dir: &std::path::Path,
+    profile_name: &str,
+    config: &std::path::Path,
+    output: GatesOutputFormat,
+    quiet: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::quality::{GateReport, Profile, QualityConfig, QualityGateChecker};
+
+    let project_root = if dir.is_absolute() {
+        dir.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(dir)
+    };
+    if !project_root.exists() {
+        return Err(format!("Directory not found: {}", project_root.display()).into());
+    }
+
+    // Fall back to the default profile when the project has no quality config
+    let config_path = project_root.join(config);
+    let profile = if config_path.exists() {
+        let quality_config = QualityConfig::load(&config_path)?;
+        quality_config
+            .get_profile_by_name(profile_name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown quality profile: {}", profile_name))?
+    } else {
+        if !quiet {
+            eprintln!(
+                "No quality config at {}, using default profile",
+                config_path.display()
+            );
+        }
+        Profile::default()
+    };
+
+    let checker = QualityGateChecker::new(profile, &project_root);
+    let report = GateReport::new(profile_name, checker.run_all());
+
+    match output {
+        GatesOutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        GatesOutputFormat::Text => {
+            if !quiet {
+                for result in &report.results {
+                    let icon = if result.passed {
+                        "✓"
+                    } else if result.is_warning() {
+                        "⚠"
+                    } else {
+                        "✗"
+                    };
+                    println!("{} {}: {}", icon, result.gate_name, result.message);
+                    if !result.passed {
+                        if let Some(ref details) = result.details {
+                            for line in details.lines() {
+                                println!("    {}", line);
+                            }
+                        }
+                    }
+                }
+                println!();
+                println!("{}", report.summary);
+            }
+        }
+    }
+
+    if report.passed {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
 /// Run the status command to check execution state
+/// Request (or withdraw) a stop of the run in `dir` through its stop file
+fn run_stop(
+    dir: Option<PathBuf>,
+    reason: Option<&str>,
+    clear: bool,
+    quiet: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::pause::StopFile;
+
+    let working_dir = dir.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+    let stop_file = StopFile::new(&working_dir);
+    if clear {
+        let cleared = stop_file.clear()?;
+        if !quiet {
+            if cleared {
+                println!("Stop withdrawn ({} removed)", stop_file.path().display());
+            } else {
+                println!("No stop requested");
+            }
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    stop_file.request(reason)?;
+    if !quiet {
+        println!(
+            "Stop requested ({}). Running runs stop within a few seconds;",
+            stop_file.path().display()
+        );
+        println!("resume with 'ralph --resume'.");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
 fn run_status(dir: Option<PathBuf>, quiet: bool) -> Result<ExitCode, Box<dyn std::error::Error>> {
     use chrono::Utc;
 
     let working_dir = dir.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
 
-    // Try to load checkpoint
-    let manager = match CheckpointManager::new(&working_dir) {
+    // Try to load the checkpoint for the current branch
+    let manager = match CheckpointManager::for_run(&working_dir, &working_dir.join("prd.json")) {
         Ok(m) => m,
         Err(e) => {
             if !quiet {
@@ -1026,6 +2026,9 @@ fn run_status(dir: Option<PathBuf>, quiet: bool) -> Result<ExitCode, Box<dyn std
                 println!();
 
                 // Display checkpoint info
+                if let Some(ref run_id) = checkpoint.run_id {
+                    println!("Run: {}", run_id);
+                }
                 if let Some(ref story) = checkpoint.current_story {
                     println!("Current Story: {}", story.story_id);
                     println!("  Iteration: {}/{}", story.iteration, story.max_iterations);
@@ -1038,13 +2041,24 @@ fn run_status(dir: Option<PathBuf>, quiet: bool) -> Result<ExitCode, Box<dyn std
                     PauseReason::UserRequested => "User requested".to_string(),
                     PauseReason::Timeout => "Timeout".to_string(),
                     PauseReason::IterationBoundary => "Iteration boundary".to_string(),
+                    PauseReason::Autosave => "Autosave (run was interrupted)".to_string(),
+                    PauseReason::StopRequested { reason } => match reason {
+                        Some(reason) => format!("Stop requested ({})", reason),
+                        None => "Stop requested".to_string(),
+                    },
                     PauseReason::Error(msg) => format!("Error: {}", msg),
                     PauseReason::CircuitBreakerTriggered {
                         consecutive_failures,
                         threshold,
+                        category,
                     } => format!(
-                        "Circuit breaker triggered ({}/{} failures)",
-                        consecutive_failures, threshold
+                        "Circuit breaker triggered ({}/{} {}failures)",
+                        consecutive_failures,
+                        threshold,
+                        category
+                            .as_deref()
+                            .map(|category| format!("{} ", category))
+                            .unwrap_or_default()
                     ),
                 };
                 println!("Pause Reason: {}", reason_str);
@@ -1084,9 +2098,14 @@ fn run_status(dir: Option<PathBuf>, quiet: bool) -> Result<ExitCode, Box<dyn std
                     PauseReason::Timeout => {
                         println!("  Check for stuck processes, then run 'ralph run' to resume.");
                     }
-                    PauseReason::IterationBoundary => {
+                    PauseReason::IterationBoundary | PauseReason::Autosave => {
                         println!("  Run 'ralph run' to resume execution.");
                     }
+                    PauseReason::StopRequested { .. } => {
+                        println!(
+                            "  Deal with whatever prompted the stop, then run 'ralph run' to resume."
+                        );
+                    }
                     PauseReason::Error(_) => {
                         println!(
                             "  Review the error above, fix any issues, then run 'ralph run' to resume."
@@ -1130,7 +2149,688 @@ fn run_status(dir: Option<PathBuf>, quiet: bool) -> Result<ExitCode, Box<dyn std
     }
 }
 
+/// List, show or delete the checkpoints saved in a working directory
+fn run_checkpoint(
+    action: CheckpointAction,
+    name: Option<&str>,
+    all: bool,
+    dir: Option<PathBuf>,
+    quiet: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use chrono::Utc;
+    use ralphmacchio::checkpoint::SavedCheckpoint;
+
+    let working_dir = dir.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+    let manager = CheckpointManager::for_run(&working_dir, &working_dir.join("prd.json"))?;
+    let saved = manager.list();
+
+    // Resolve the checkpoint an action applies to; without a name the only
+    // checkpoint, or else the current branch's, is used
+    let select = |saved: Vec<SavedCheckpoint>| -> Result<SavedCheckpoint, String> {
+        match name {
+            Some(name) => saved
+                .into_iter()
+                .find(|checkpoint| checkpoint.name == name)
+                .ok_or_else(|| format!("No checkpoint named '{}'", name)),
+            None if saved.len() == 1 => Ok(saved.into_iter().next().unwrap()),
+            None if saved.is_empty() => Err("No checkpoints found".to_string()),
+            None => saved
+                .into_iter()
+                .find(|checkpoint| checkpoint.name == manager.name())
+                .ok_or_else(|| "Several checkpoints found; name the one to use".to_string()),
+        }
+    };
+
+    match action {
+        CheckpointAction::List => {
+            if quiet {
+                return Ok(ExitCode::SUCCESS);
+            }
+            if saved.is_empty() {
+                println!("No checkpoints found.");
+                return Ok(ExitCode::SUCCESS);
+            }
+            println!(
+                "{:<16} {:<40} {:>9}  AGE",
+                "NAME", "PAUSE REASON", "COMPLETED"
+            );
+            for entry in &saved {
+                match &entry.checkpoint {
+                    Ok(checkpoint) => {
+                        let completed = checkpoint
+                            .stories_completed()
+                            .map(|count| count.to_string())
+                            .unwrap_or_else(|| "-".to_string());
+                        let age = Utc::now().signed_duration_since(checkpoint.created_at);
+                        println!(
+                            "{:<16} {:<40} {:>9}  {}",
+                            entry.name,
+                            truncate_for_column(&checkpoint.pause_reason.to_string(), 40),
+                            completed,
+                            format_duration(age)
+                        );
+                    }
+                    Err(e) => println!("{:<16} unreadable: {}", entry.name, e),
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        CheckpointAction::Show => {
+            let entry = match select(saved) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(ExitCode::FAILURE);
+                }
+            };
+            let checkpoint = match entry.checkpoint {
+                Ok(checkpoint) => checkpoint,
+                Err(e) => {
+                    eprintln!("Failed to load checkpoint '{}': {}", entry.name, e);
+                    return Ok(ExitCode::FAILURE);
+                }
+            };
+            if quiet {
+                return Ok(ExitCode::SUCCESS);
+            }
+            let age = Utc::now().signed_duration_since(checkpoint.created_at);
+            println!("Checkpoint: {}", entry.name);
+            println!("  Path: {}", entry.path.display());
+            println!(
+                "  Created: {} ({})",
+                checkpoint.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                format_duration(age)
+            );
+            println!("  Pause Reason: {}", checkpoint.pause_reason);
+            if let Some(ref run_id) = checkpoint.run_id {
+                println!("  Run: {}", run_id);
+            }
+            if let Some(ref metrics) = checkpoint.metrics {
+                println!(
+                    "  Run Metrics: {} attempted, {} completed, {} retries in {}s",
+                    metrics.steps_attempted,
+                    metrics.steps_completed,
+                    metrics.retries,
+                    metrics.run_duration.as_secs()
+                );
+            }
+            if let Some(ref story) = checkpoint.current_story {
+                println!(
+                    "  Current Story: {} (iteration {}/{})",
+                    story.story_id, story.iteration, story.max_iterations
+                );
+            }
+            if let Some(ref state) = checkpoint.parallel_state {
+                let list = |ids: &[String]| {
+                    if ids.is_empty() {
+                        "-".to_string()
+                    } else {
+                        ids.join(", ")
+                    }
+                };
+                println!();
+                println!(
+                    "Completed ({}): {}",
+                    state.completed.len(),
+                    list(&state.completed)
+                );
+                println!(
+                    "Pending ({}): {}",
+                    state.pending.len(),
+                    list(&state.pending)
+                );
+                println!(
+                    "Skipped ({}): {}",
+                    state.skipped.len(),
+                    list(&state.skipped)
+                );
+                println!("Failed ({}):", state.failed.len());
+                for (story_id, error) in &state.failed {
+                    println!("  - {}: {}", story_id, error);
+                }
+                if !state.iterations.is_empty() {
+                    println!("In Flight ({}):", state.iterations.len());
+                    for (story_id, iteration) in &state.iterations {
+                        println!("  - {}: iteration {}", story_id, iteration);
+                    }
+                }
+            }
+            if !checkpoint.uncommitted_files.is_empty() {
+                println!();
+                println!("Uncommitted Files:");
+                for file in &checkpoint.uncommitted_files {
+                    println!("  - {}", file);
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        CheckpointAction::Delete => {
+            let targets = if all {
+                saved
+            } else {
+                match select(saved) {
+                    Ok(entry) => vec![entry],
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            };
+            for entry in targets {
+                if manager.delete(&entry.name)? && !quiet {
+                    println!("Deleted checkpoint '{}'", entry.name);
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+/// Shorten `text` to at most `width` characters for a table column
+fn truncate_for_column(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(3)).collect();
+    truncated.push_str("...");
+    truncated
+}
+
+/// Serve stories dispatched by a coordinator's parallel run until stopped
+async fn run_worker(
+    cli: &Cli,
+    bind: &str,
+    port: u16,
+    capacity: usize,
+    work_dir: Option<PathBuf>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::mcp::tools::executor::detect_agent;
+    use ralphmacchio::parallel::remote::{serve_worker, WorkerConfig};
+
+    let agent = cli
+        .run
+        .agent
+        .clone()
+        .or_else(detect_agent)
+        .ok_or("No agent found; pass --agent to choose one")?;
+    let mut config = WorkerConfig::new(agent)
+        .with_bind_address(bind)
+        .with_port(port)
+        .with_capacity(capacity);
+    if let Some(work_dir) = work_dir {
+        config = config.with_work_dir(work_dir);
+    }
+    if let Some(token) = std::env::var("RALPH_WORKER_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+    {
+        config = config.with_token(token);
+    }
+
+    if !cli.quiet {
+        println!(
+            "Ralph worker listening on {} (agent: {}, capacity: {})",
+            config.socket_addr(),
+            config.agent_command,
+            config.capacity
+        );
+    }
+    serve_worker(config).await?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Run a story with each variant and print the comparison report
+async fn run_compare(
+    story: &str,
+    variants: &[String],
+    prd: &std::path::Path,
+    dir: Option<PathBuf>,
+    max_iterations: u32,
+    output: PlanOutputFormat,
+    quiet: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::experiment::{Experiment, Variant};
+
+    let working_dir = dir.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+    let prd_path = find_prd_file(prd).ok_or_else(|| format!("PRD not found: {}", prd.display()))?;
+    let experiment = Experiment {
+        working_dir,
+        prd_path,
+        story_id: story.to_string(),
+        variants: Variant::parse_all(variants)?,
+        max_iterations,
+    };
+    if !quiet && output == PlanOutputFormat::Text {
+        for variant in &experiment.variants {
+            println!("Variant {}: {}", variant.label, variant.name());
+        }
+    }
+
+    let report = experiment.run().await?;
+    match output {
+        PlanOutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        PlanOutputFormat::Text => {
+            if !quiet {
+                print!("{}", report.render_text());
+            }
+        }
+    }
+    Ok(if report.winner.is_some() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Validate a PRD file and, with `lint`, lint it
+fn run_prd_check(
+    lint: bool,
+    prd: &std::path::Path,
+    story_budget: u64,
+    output: PlanOutputFormat,
+    quiet: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::mcp::tools::load_prd::{validate_prd, PrdProblem, PrdValidationError};
+    use ralphmacchio::prd::{lint_prd, LintOptions, LintSeverity};
+
+    // Both actions start from a PRD that satisfies the schema
+    let prd_file = match validate_prd(prd) {
+        Ok(prd_file) => prd_file,
+        Err(e) => {
+            let problems = match e {
+                PrdValidationError::Invalid(problems) => problems,
+                e => vec![PrdProblem {
+                    pointer: String::new(),
+                    line: None,
+                    message: e.to_string(),
+                }],
+            };
+            match output {
+                PlanOutputFormat::Json => {
+                    let report = serde_json::json!({
+                        "prd": prd.display().to_string(),
+                        "valid": false,
+                        "problems": problems,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+                PlanOutputFormat::Text => {
+                    for problem in &problems {
+                        match problem.line {
+                            Some(line) => eprint!("{}:{}: ", prd.display(), line),
+                            None => eprint!("{}: ", prd.display()),
+                        }
+                        if problem.pointer.is_empty() && problem.line.is_none() {
+                            eprintln!("{}", problem.message);
+                        } else if problem.pointer.is_empty() {
+                            eprintln!("/: {}", problem.message);
+                        } else {
+                            eprintln!("{}: {}", problem.pointer, problem.message);
+                        }
+                    }
+                    eprintln!("{} problem(s) found", problems.len());
+                }
+            }
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+
+    if !lint {
+        match output {
+            PlanOutputFormat::Json => {
+                let report = serde_json::json!({
+                    "prd": prd.display().to_string(),
+                    "valid": true,
+                    "problems": [],
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+            PlanOutputFormat::Text => {
+                if !quiet {
+                    println!(
+                        "{}: valid ({} stories)",
+                        prd.display(),
+                        prd_file.user_stories.len()
+                    );
+                }
+            }
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let content = std::fs::read_to_string(prd)?;
+    let options = LintOptions {
+        project_root: prd.parent().map(|parent| parent.to_path_buf()),
+        story_budget,
+    };
+    let findings = lint_prd(&content, &prd_file, &options);
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == LintSeverity::Error)
+        .count();
+    match output {
+        PlanOutputFormat::Json => {
+            let report = serde_json::json!({
+                "prd": prd.display().to_string(),
+                "errors": errors,
+                "warnings": findings.len() - errors,
+                "findings": findings,
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        PlanOutputFormat::Text => {
+            for finding in &findings {
+                if quiet && finding.severity == LintSeverity::Warning {
+                    continue;
+                }
+                let severity = match finding.severity {
+                    LintSeverity::Error => "error",
+                    LintSeverity::Warning => "warning",
+                };
+                match finding.line {
+                    Some(line) => eprint!("{}:{}: ", prd.display(), line),
+                    None => eprint!("{}: ", prd.display()),
+                }
+                eprintln!(
+                    "{}[{}] {}: {}",
+                    severity,
+                    finding.rule.as_str(),
+                    finding.story,
+                    finding.message
+                );
+            }
+            if !quiet {
+                eprintln!("{} error(s), {} warning(s)", errors, findings.len() - errors);
+            }
+        }
+    }
+    Ok(if errors == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Create a PRD by asking about each story
+fn run_prd_init(prd: &std::path::Path) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::mcp::tools::load_prd::validate_prd_content;
+    use ralphmacchio::prd::PrdWizard;
+    use std::io::Write;
+
+    if prd.exists() {
+        print!("{} already exists. Overwrite? [y/N]: ", prd.display());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            println!("Left {} unchanged.", prd.display());
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+
+    let default_project = std::env::current_dir()
+        .ok()
+        .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "project".to_string());
+    let draft = PrdWizard::new(default_project).run()?;
+    let content = format!("{}\n", serde_json::to_string_pretty(&draft)?);
+    validate_prd_content(&content).map_err(|e| e.to_string())?;
+    std::fs::write(prd, content)?;
+
+    println!();
+    println!(
+        "Wrote {} with {} stories.",
+        prd.display(),
+        draft.user_stories.len()
+    );
+    println!("Next: ralph prd lint, then ralph to start the run.");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Add or update PRD stories from GitHub issues
+fn run_prd_import(
+    prd: &std::path::Path,
+    query: &ralphmacchio::prd::IssueQuery,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::prd::{fetch_issues, issue_to_story, merge_issue_stories, write_prd};
+
+    let issues = fetch_issues(query)?;
+    let stories: Vec<_> = issues.iter().map(issue_to_story).collect();
+
+    // Start a PRD named after the repository when there is none yet
+    let mut prd_value: serde_json::Value = if prd.exists() {
+        serde_json::from_str(&std::fs::read_to_string(prd)?)?
+    } else {
+        let project = query
+            .repo
+            .as_deref()
+            .and_then(|repo| repo.rsplit('/').next())
+            .map(String::from)
+            .or_else(|| {
+                std::env::current_dir()
+                    .ok()
+                    .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            })
+            .unwrap_or_else(|| "project".to_string());
+        serde_json::json!({
+            "project": project,
+            "branchName": "ralph/issues",
+            "description": "Stories imported from GitHub issues",
+            "userStories": [],
+        })
+    };
+    let summary = merge_issue_stories(&mut prd_value, &stories)?;
+    let content = format!("{}\n", serde_json::to_string_pretty(&prd_value)?);
+
+    if dry_run {
+        print!("{}", content);
+        return Ok(ExitCode::SUCCESS);
+    }
+    write_prd(prd, &content)?;
+    if !quiet {
+        println!(
+            "Imported {} issue(s) into {}: {} added, {} updated",
+            issues.len(),
+            prd.display(),
+            summary.added.len(),
+            summary.updated.len()
+        );
+        if !summary.added.is_empty() {
+            println!("Added: {}", summary.added.join(", "));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
 /// Format a duration in a human-readable way
+/// Print the projected schedule of a parallel run without invoking an agent
+fn run_plan(
+    prd: &std::path::Path,
+    dir: Option<PathBuf>,
+    max_concurrency: usize,
+    output: PlanOutputFormat,
+    quiet: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::budget::TokenForecast;
+    use ralphmacchio::mcp::tools::load_prd::validate_prd;
+    use ralphmacchio::metrics::RunMetricsStore;
+    use ralphmacchio::parallel::planner::{DurationEstimates, ExecutionPlan};
+    use ralphmacchio::parallel::scheduler::ParallelRunnerConfig;
+
+    let working_dir = dir.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+    let prd_path = find_prd_file(prd).ok_or_else(|| format!("PRD not found: {}", prd.display()))?;
+    let prd_file = validate_prd(&prd_path).map_err(|e| e.to_string())?;
+
+    // Estimate durations from previous runs without creating the history directory
+    let history = if working_dir.join(".ralph").join("runs").is_dir() {
+        RunMetricsStore::new(&working_dir)
+            .and_then(|store| store.load_all())
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let estimates = DurationEstimates::from_history(&history);
+    let tokens = TokenForecast::new(&prd_file.user_stories, &history);
+
+    let config = ParallelRunnerConfig {
+        max_concurrency: if max_concurrency == 0 {
+            u32::MAX
+        } else {
+            max_concurrency as u32
+        },
+        ..Default::default()
+    };
+    let plan = ExecutionPlan::build(&prd_file.user_stories, &config, &estimates, tokens)?;
+
+    match output {
+        PlanOutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&plan)?);
+        }
+        PlanOutputFormat::Text => {
+            if !quiet {
+                println!(
+                    "Execution plan: {} stories in {} batches",
+                    plan.planned_stories(),
+                    plan.batches.len()
+                );
+                if !plan.already_passing.is_empty() {
+                    println!("Already passing: {}", plan.already_passing.join(", "));
+                }
+                for batch in &plan.batches {
+                    println!();
+                    println!(
+                        "Batch {} (~{})",
+                        batch.number,
+                        format_estimate(batch.estimated_secs)
+                    );
+                    for story in &batch.stories {
+                        let basis = if story.from_history {
+                            ""
+                        } else if story.from_estimate {
+                            ", from estimate"
+                        } else {
+                            ", no history"
+                        };
+                        let tokens = story
+                            .estimated_tokens
+                            .map(|tokens| format!(", ~{} tokens", tokens))
+                            .unwrap_or_default();
+                        println!(
+                            "  {} [P{}] {} (~{}{}{})",
+                            story.id,
+                            story.priority,
+                            story.title,
+                            format_estimate(story.estimated_secs),
+                            basis,
+                            tokens
+                        );
+                    }
+                    for conflict in &batch.conflicts {
+                        println!(
+                            "  deferred {}: locked by {} ({})",
+                            conflict.deferred,
+                            conflict.blocking,
+                            conflict.files.join(", ")
+                        );
+                    }
+                    if !batch.over_limit.is_empty() {
+                        println!(
+                            "  waiting for a slot: {}",
+                            batch.over_limit.join(", ")
+                        );
+                    }
+                }
+                if !plan.unreachable.is_empty() {
+                    println!();
+                    println!(
+                        "Unreachable (dependencies never satisfied): {}",
+                        plan.unreachable.join(", ")
+                    );
+                }
+                println!();
+                println!(
+                    "Estimated duration: ~{}",
+                    format_estimate(plan.estimated_secs)
+                );
+                if !plan.tokens.per_story.is_empty() {
+                    print!("Forecast tokens: ~{}", plan.tokens.total);
+                    if !plan.tokens.unforecast.is_empty() {
+                        print!(
+                            " ({} stories could not be forecast)",
+                            plan.tokens.unforecast.len()
+                        );
+                    }
+                    println!();
+                }
+            }
+        }
+    }
+
+    if plan.unreachable.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Print the timeline of a recorded run, optionally exporting it as SVG
+fn run_timeline(
+    run_id: Option<&str>,
+    dir: Option<PathBuf>,
+    width: usize,
+    svg: Option<&std::path::Path>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    use ralphmacchio::metrics::timeline::Timeline;
+    use ralphmacchio::metrics::RunMetricsStore;
+    use ralphmacchio::parallel::trace::load_trace;
+
+    let working_dir = dir.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+    let runs_dir = working_dir.join(".ralph").join("runs");
+    if !runs_dir.is_dir() {
+        return Err(format!("No runs recorded in {}", runs_dir.display()).into());
+    }
+    let store = RunMetricsStore::new(&working_dir)?;
+    let metrics = match run_id {
+        Some(run_id) => store
+            .load(run_id)?
+            .ok_or_else(|| format!("Run not found: {}", run_id))?,
+        None => store
+            .load_all()?
+            .into_iter()
+            .max_by_key(|run| run.started_at)
+            .ok_or_else(|| format!("No runs recorded in {}", runs_dir.display()))?,
+    };
+
+    // Sequential runs have no scheduler trace, and so no lock waits
+    let trace_path = runs_dir.join(format!("{}.scheduler.jsonl", metrics.run_id));
+    let trace = if trace_path.is_file() {
+        load_trace(&trace_path)?
+    } else {
+        Vec::new()
+    };
+
+    let timeline = Timeline::build(&metrics, &trace);
+    print!("{}", timeline.render_text(width));
+    if let Some(path) = svg {
+        std::fs::write(path, timeline.render_svg())?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Format an estimated duration in seconds (e.g., "1h 05m", "12m", "40s")
+fn format_estimate(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
 fn format_duration(duration: chrono::Duration) -> String {
     let total_seconds = duration.num_seconds().unsigned_abs();
 
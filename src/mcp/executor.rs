@@ -507,16 +507,20 @@ mod tests {
                 coverage_threshold: 0,
                 unit_tests: true,
                 integration_tests: false,
+                ..Default::default()
             },
             ci: CiConfig {
                 required: true,
                 lint_check: false,
                 format_check: false,
+                type_check: false,
+                ..Default::default()
             },
             security: SecurityConfig {
                 cargo_audit: false,
                 cargo_deny: false,
                 sast: false,
+                sast_rulesets: Vec::new(),
             },
             ..Default::default()
         }
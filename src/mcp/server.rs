@@ -2030,7 +2030,7 @@ mod tests {
         assert!(json["message"]
             .as_str()
             .unwrap()
-            .contains("No user stories"));
+            .contains("/userStories: must have at least 1 item(s)"));
     }
 
     #[tokio::test]
@@ -1,6 +1,7 @@
 // Story execution engine for Ralph
 // This module handles the actual execution of user stories including:
-// - Spawning Claude Code or Amp CLI to implement stories
+// - Spawning Claude Code, Codex, Amp or Aider to implement stories
+// - Having a reviewer agent check the changes, if configured
 // - Running quality gates after implementation
 // - Updating PRD files on success
 // - Appending to progress.txt
@@ -8,6 +9,7 @@
 
 #![allow(dead_code)]
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::AtomicBool;
@@ -16,22 +18,46 @@ use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::sync::{watch, Mutex};
 
+use crate::agent::config::{AgentSettings, AgentsConfig, PermissionMode};
+use crate::agent::container::{container_name, ContainerRun};
+use crate::agent::{
+    AgentCapabilities, AgentCommandTemplate, AgentContainer, AgentSession, ApiAgentSpec,
+    MockAgent, MockAgentSpec, MockScript, Review, TemplateValues, REVIEW_EVIDENCE_KIND,
+};
 use crate::budget::{
     extract_or_estimate, BudgetStrategy, ParsedTokenUsage, PromptStrategy, SharedTokenBudget,
     TokenBudget, TokenBudgetConfig, TokenEstimator,
 };
 use crate::checkpoint::{Checkpoint, CheckpointManager, PauseReason, StoryCheckpoint};
 use crate::error::classification::{ErrorCategory, TimeoutReason};
+use crate::error::ErrorDetector;
 use crate::iteration::{
     context::{ErrorCategory as IterErrorCategory, IterationContext, IterationError},
     futility::{FutileRetryDetector, FutilityConfig, FutilityVerdict},
+    mutation::PromptMutation,
+};
+use crate::evidence::{EvidenceWriter, OutputCapture, OutputCaptureConfig};
+use crate::metrics::{
+    AgentInvocation, GateOutcome, InvocationStatus, MetricsCollector, RunMetricsCollector,
+    INVOCATION_EVIDENCE_KIND,
 };
-use crate::metrics::MetricsCollector;
 use crate::timeout::{HeartbeatEvent, HeartbeatMonitor, TimeoutConfig};
 use crate::ui::DisplayCallback;
 
 use crate::mcp::tools::load_prd::{PrdFile, PrdUserStory};
-use crate::quality::{GateResult, Profile, QualityGateChecker};
+use crate::parallel::rate_limit::LaunchRateLimiter;
+use crate::parallel::retry::{RetryBudgets, StoryRetryPolicy};
+use crate::prd::workspace::{story_prd, Workspace};
+use crate::prd::writeback::set_story_passes;
+use crate::prompts::{
+    BudgetVariables, FileContextBuilder, MergeVariables, PromptTemplates, PromptVariables,
+    ReconcileVariables, ReviewVariables,
+};
+use crate::quality::{
+    evaluate_criteria, CriteriaReport, FailureCategory, GateFailureDetail, GateResult, Profile,
+    QualityGateChecker, CRITERIA_EVIDENCE_KIND,
+};
+use crate::resources::{ResourceLimits, ScratchDir};
 
 /// Result of story execution
 #[derive(Debug)]
@@ -85,6 +111,12 @@ pub enum ExecutorError {
     Timeout(String),
     /// Token budget exceeded
     BudgetExceeded(String),
+    /// A per-worker resource limit was exceeded
+    ResourceLimitExceeded(String),
+    /// A prompt template failed to load or render
+    PromptError(String),
+    /// Agent settings could not be loaded
+    ConfigError(String),
 }
 
 impl std::fmt::Display for ExecutorError {
@@ -100,12 +132,82 @@ impl std::fmt::Display for ExecutorError {
             ExecutorError::IoError(msg) => write!(f, "IO error: {}", msg),
             ExecutorError::Timeout(msg) => write!(f, "Execution timed out: {}", msg),
             ExecutorError::BudgetExceeded(msg) => write!(f, "Token budget exceeded: {}", msg),
+            ExecutorError::ResourceLimitExceeded(msg) => {
+                write!(f, "Resource limit exceeded: {}", msg)
+            }
+            ExecutorError::PromptError(msg) => write!(f, "Prompt template error: {}", msg),
+            ExecutorError::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
         }
     }
 }
 
 impl std::error::Error for ExecutorError {}
 
+/// Diffs longer than this are cut before they go into a review prompt.
+const MAX_REVIEW_DIFF_BYTES: usize = 100_000;
+
+/// Step ID the reconciliation agent's runs are recorded under.
+pub const RECONCILIATION_STEP_ID: &str = "reconciliation";
+
+/// An agent CLI (or API agent) to run, and how.
+struct AgentRun<'a> {
+    /// Agent command
+    command: &'a str,
+    /// What the agent's CLI reported when probed
+    capabilities: Option<&'a AgentCapabilities>,
+    settings: &'a AgentSettings,
+    /// Model for API and local agents
+    model: Option<&'a str>,
+    /// Prefix of the run's spilled output logs, e.g. "agent"
+    role: &'a str,
+}
+
+impl AgentRun<'_> {
+    /// Label attributing the run to the agent, e.g. "codex (gpt-5-codex)".
+    fn label(&self) -> String {
+        match self.settings.model.as_deref().or(self.model) {
+            Some(model) => format!("{} ({})", self.command, model),
+            None => self.command.to_string(),
+        }
+    }
+}
+
+/// A rendered agent prompt.
+struct AgentPrompt {
+    text: String,
+    /// Strategy the token budget selected
+    strategy: PromptStrategy,
+    /// Whether context was left out to fit the budget or the file context cap
+    trimmed: bool,
+}
+
+/// The reviewer checking each iteration's changes before the gates.
+struct Reviewer {
+    command: String,
+    settings: AgentSettings,
+    require_approval: bool,
+}
+
+impl Reviewer {
+    /// Label attributing reviews to this agent, e.g. "codex (gpt-5-codex)".
+    fn label(&self) -> String {
+        match self.settings.model {
+            Some(ref model) => format!("{} ({})", self.command, model),
+            None => self.command.clone(),
+        }
+    }
+}
+
+/// A review, as recorded in the evidence log.
+#[derive(serde::Serialize)]
+struct ReviewEvidence<'a> {
+    step_id: &'a str,
+    iteration: u32,
+    require_approval: bool,
+    #[serde(flatten)]
+    review: &'a Review,
+}
+
 /// Result of running an agent, including token usage.
 struct AgentRunResult {
     /// Files that were changed
@@ -127,15 +229,33 @@ impl ExecutorError {
             ExecutorError::Cancelled => ErrorCategory::Fatal(FatalReason::InternalError),
             ExecutorError::StoryNotFound(_) => ErrorCategory::Fatal(FatalReason::ResourceNotFound),
             ExecutorError::PrdError(_) => ErrorCategory::Fatal(FatalReason::ConfigurationError),
+            ExecutorError::PromptError(_) => ErrorCategory::Fatal(FatalReason::ConfigurationError),
+            ExecutorError::ConfigError(_) => ErrorCategory::Fatal(FatalReason::ConfigurationError),
             ExecutorError::GitError(_) => ErrorCategory::Transient(TransientReason::ResourceLocked),
             ExecutorError::QualityGateFailed(_) => ErrorCategory::Fatal(FatalReason::InternalError),
-            ExecutorError::AgentError(_) => ErrorCategory::Transient(TransientReason::ServerError),
-            ExecutorError::IoError(_) => ErrorCategory::Transient(TransientReason::NetworkError),
+            ExecutorError::AgentError(msg) => detect_subcategory(msg)
+                .unwrap_or(ErrorCategory::Transient(TransientReason::ServerError)),
+            ExecutorError::IoError(msg) => detect_subcategory(msg)
+                .unwrap_or(ErrorCategory::Transient(TransientReason::NetworkError)),
             ExecutorError::BudgetExceeded(_) => ErrorCategory::Fatal(FatalReason::InternalError),
+            ExecutorError::ResourceLimitExceeded(_) => {
+                ErrorCategory::Fatal(FatalReason::InternalError)
+            }
         }
     }
 }
 
+/// Recognizes network, rate-limit, authentication and disk-full errors in an
+/// agent or IO error message, so they are retried their own way.
+fn detect_subcategory(message: &str) -> Option<ErrorCategory> {
+    static DETECTOR: std::sync::OnceLock<ErrorDetector> = std::sync::OnceLock::new();
+    DETECTOR
+        .get_or_init(ErrorDetector::default)
+        .classify_error(message)
+        .map(|error| error.category)
+        .filter(|category| category.subcategory().is_some())
+}
+
 /// Configuration for the story executor
 #[derive(Debug, Clone)]
 pub struct ExecutorConfig {
@@ -163,6 +283,39 @@ pub struct ExecutorConfig {
     pub metrics_collector: Option<MetricsCollector>,
     /// Token budget configuration for cost control
     pub budget_config: Option<TokenBudgetConfig>,
+    /// Evidence artifacts directory for gate outputs (e.g. coverage reports)
+    pub artifacts_dir: Option<PathBuf>,
+    /// Evidence writer that quality gate results are recorded to
+    pub evidence: Option<EvidenceWriter>,
+    /// Commit the work of every failed iteration, not just the final passing one
+    pub commit_iterations: bool,
+    /// Resource limits applied to agent and quality gate subprocesses
+    pub resource_limits: ResourceLimits,
+    /// Optional limiter on agent launches shared across parallel executions
+    pub launch_limiter: Option<LaunchRateLimiter>,
+    /// Caps on agent and gate output kept in memory per stream
+    pub output_capture: OutputCaptureConfig,
+    /// What the agent CLI reported when probed at startup; flags it does not
+    /// support are left out of the invocation
+    pub agent_capabilities: Option<AgentCapabilities>,
+    /// Container the agent CLI runs in (None = directly on the host)
+    pub agent_container: Option<AgentContainer>,
+    /// Token cap for the story's most relevant files included in the prompt
+    /// (None = no file context)
+    pub file_context_tokens: Option<u64>,
+    /// Model assigned to the story by the project's agent settings; overrides
+    /// the agent's configured model
+    pub model: Option<String>,
+    /// Run metrics that each agent invocation's telemetry is recorded to
+    pub run_metrics: Option<RunMetricsCollector>,
+    /// Whether a passing story is marked `passes: true` in the PRD
+    pub prd_writeback: bool,
+    /// Retry limits per error category for failed agent runs; without it
+    /// every failed run just uses up an iteration
+    pub retry_policy: Option<StoryRetryPolicy>,
+    /// Retry budgets the failed agent runs are spent from, shared with the
+    /// scheduler re-running failed stories
+    pub retry_budgets: RetryBudgets,
 }
 
 impl Default for ExecutorConfig {
@@ -180,6 +333,20 @@ impl Default for ExecutorConfig {
             futility_config: FutilityConfig::default(),
             metrics_collector: None,
             budget_config: None, // Disabled by default for backwards compatibility
+            artifacts_dir: None,
+            evidence: None,
+            commit_iterations: false,
+            resource_limits: ResourceLimits::default(),
+            launch_limiter: None,
+            output_capture: OutputCaptureConfig::default(),
+            agent_capabilities: None,
+            agent_container: None,
+            file_context_tokens: None,
+            model: None,
+            run_metrics: None,
+            prd_writeback: true,
+            retry_policy: None,
+            retry_budgets: RetryBudgets::default(),
         }
     }
 }
@@ -200,7 +367,7 @@ impl StoryExecutor {
     /// Create a new story executor with the given configuration
     pub fn new(config: ExecutorConfig) -> Self {
         // Attempt to create a checkpoint manager for the project root
-        let checkpoint_manager = CheckpointManager::new(&config.project_root).ok();
+        let checkpoint_manager = CheckpointManager::for_run(&config.project_root, &config.prd_path).ok();
         let token_budget = config
             .budget_config
             .clone()
@@ -379,6 +546,90 @@ impl StoryExecutor {
             .await
     }
 
+    /// Have the story's agent resolve the conflicts of a merge of the story's
+    /// `branch` left in progress in the project root.
+    ///
+    /// The agent gets the `merge` prompt template and only edits files;
+    /// checking and committing the resolution is left to the caller.
+    pub async fn resolve_merge_conflicts(
+        &self,
+        story_id: &str,
+        branch: &str,
+        files: &[String],
+    ) -> Result<(), ExecutorError> {
+        let prd = self.load_prd()?;
+        let story = self.find_story(&prd, story_id)?;
+        let templates = PromptTemplates::load(&self.config.project_root)
+            .map_err(|e| ExecutorError::PromptError(e.to_string()))?;
+        let agents_config = AgentsConfig::load(&self.config.project_root)
+            .map_err(|e| ExecutorError::ConfigError(e.to_string()))?;
+        let mut settings = agents_config.for_agent(&self.config.agent_command);
+        if let Some(ref model) = self.config.model {
+            settings.model = Some(model.clone());
+        }
+
+        let variables = MergeVariables::new(story, &prd.project, branch, files.to_vec());
+        let prompt = templates
+            .render_merge(&variables)
+            .map_err(|e| ExecutorError::PromptError(e.to_string()))?;
+        let agent = AgentRun {
+            command: &self.config.agent_command,
+            capabilities: self.config.agent_capabilities.as_ref(),
+            settings: &settings,
+            model: story.model.as_deref().or(self.config.model.as_deref()),
+            role: "merge",
+        };
+        let (outcome, invocation) = self.invoke_agent(&agent, story_id, &prompt, 1, None).await;
+        self.record_invocation(invocation);
+        outcome.map(|_| ())
+    }
+
+    /// Have the agent fix `issues` that reconciliation found in the project
+    /// root after `story_ids` ran in parallel.
+    ///
+    /// The agent gets the `reconcile` prompt template with `diff`, what the
+    /// stories committed to `files`, and only edits files; checking and
+    /// committing the fix is left to the caller.
+    pub async fn fix_reconciliation_issues(
+        &self,
+        story_ids: &[String],
+        issues: Vec<String>,
+        files: Vec<String>,
+        diff: String,
+    ) -> Result<(), ExecutorError> {
+        let prd = self.load_prd()?;
+        let stories: Vec<&PrdUserStory> = prd
+            .user_stories
+            .iter()
+            .filter(|story| story_ids.contains(&story.id))
+            .collect();
+        let templates = PromptTemplates::load(&self.config.project_root)
+            .map_err(|e| ExecutorError::PromptError(e.to_string()))?;
+        let agents_config = AgentsConfig::load(&self.config.project_root)
+            .map_err(|e| ExecutorError::ConfigError(e.to_string()))?;
+        let mut settings = agents_config.for_agent(&self.config.agent_command);
+        if let Some(ref model) = self.config.model {
+            settings.model = Some(model.clone());
+        }
+
+        let variables = ReconcileVariables::new(&prd.project, &stories, issues, files, diff);
+        let prompt = templates
+            .render_reconcile(&variables)
+            .map_err(|e| ExecutorError::PromptError(e.to_string()))?;
+        let agent = AgentRun {
+            command: &self.config.agent_command,
+            capabilities: self.config.agent_capabilities.as_ref(),
+            settings: &settings,
+            model: self.config.model.as_deref(),
+            role: "reconcile",
+        };
+        let (outcome, invocation) = self
+            .invoke_agent(&agent, RECONCILIATION_STEP_ID, &prompt, 1, None)
+            .await;
+        self.record_invocation(invocation);
+        outcome.map(|_| ())
+    }
+
     /// Execute a story with an existing iteration context.
     ///
     /// This is the internal method that handles both fresh starts and resumptions.
@@ -395,6 +646,48 @@ impl StoryExecutor {
         // Load the PRD and find the story
         let prd = self.load_prd()?;
         let story = self.find_story(&prd, story_id)?;
+        let templates = PromptTemplates::load(&self.config.project_root)
+            .map_err(|e| ExecutorError::PromptError(e.to_string()))?;
+        let agents_config = AgentsConfig::load(&self.config.project_root)
+            .map_err(|e| ExecutorError::ConfigError(e.to_string()))?;
+        let mut agent_settings = agents_config.for_agent(&self.config.agent_command);
+        if let Some(ref model) = self.config.model {
+            agent_settings.model = Some(model.clone());
+        }
+
+        // The reviewer only reads the changes, so it runs without the
+        // approval-skipping flags
+        let reviewer = agents_config.review().map(|review| {
+            let command = review
+                .agent
+                .clone()
+                .unwrap_or_else(|| self.config.agent_command.clone());
+            let mut settings = agents_config.for_agent(&command);
+            if review.model.is_some() {
+                settings.model = review.model.clone();
+            }
+            settings.permission_mode = Some(PermissionMode::Default);
+            Reviewer {
+                command,
+                settings,
+                require_approval: review.require_approval,
+            }
+        });
+
+        // Continue the agent's conversation across iterations where it can,
+        // so later iterations only send the new failures. Containers are
+        // removed after each run, taking the agent's session store with them.
+        let mut session = if self.config.agent_container.is_none()
+            && agent_settings.reuse_session.unwrap_or(true)
+        {
+            AgentSession::start(
+                &self.config.agent_command,
+                story_id,
+                self.config.agent_capabilities.as_ref(),
+            )
+        } else {
+            None
+        };
 
         // Update iteration context (may already be initialized if resuming)
         if iter_context.max_iterations == 0 {
@@ -415,6 +708,15 @@ impl StoryExecutor {
             collector.start_story(story_id, self.config.max_iterations);
         }
 
+        // Per-story scratch space for subprocesses, removed when the story finishes
+        let scratch = match self.config.resource_limits.scratch_quota_mb {
+            Some(quota_mb) => Some(
+                ScratchDir::create(story_id, quota_mb)
+                    .map_err(|e| ExecutorError::IoError(e.to_string()))?,
+            ),
+            None => None,
+        };
+
         let execution_start = std::time::Instant::now();
         let mut iterations_used = 0;
         let mut last_error: Option<String> = None;
@@ -441,15 +743,35 @@ impl StoryExecutor {
                 return Err(ExecutorError::Cancelled);
             }
 
-            // Build the prompt with iteration context if we have previous errors
-            let prompt = if iter_context.error_history.is_empty() {
-                self.build_agent_prompt(story, &prd)
-            } else {
-                self.build_agent_prompt_with_context(story, &prd, &iter_context)
-            };
+            // Build the prompt with iteration context if we have previous
+            // errors; a continued session already has the story
+            let continuation = session.as_ref().is_some_and(AgentSession::is_established);
+            let prompt =
+                self.build_agent_prompt(&templates, story, &prd, &iter_context, continuation)?;
+            let prompt_strategy = prompt.strategy;
+            let context_trimmed = prompt.trimmed;
+            let mut run_settings = agent_settings.clone();
+            if let Some(ref session) = session {
+                run_settings.flags.extend(session.args());
+            }
 
             // Run the agent
-            match self.run_agent(&prompt, iteration).await {
+            let agent = AgentRun {
+                command: &self.config.agent_command,
+                capabilities: self.config.agent_capabilities.as_ref(),
+                settings: &run_settings,
+                model: story.model.as_deref().or(self.config.model.as_deref()),
+                role: "agent",
+            };
+            let (outcome, mut invocation) = self
+                .invoke_agent(&agent, story_id, &prompt.text, iteration, scratch.as_ref())
+                .await;
+            invocation.prompt_strategy = prompt_strategy;
+            invocation.context_trimmed = context_trimmed;
+            if let Some(ref mut session) = session {
+                session.record_run(outcome.is_ok());
+            }
+            match outcome {
                 Ok(result) => {
                     files_changed = result.files_changed;
                     // Track token usage
@@ -458,14 +780,11 @@ impl StoryExecutor {
                     if usage.is_actual {
                         _any_actual_usage = true;
                     }
-                    // Estimate cost using default pricing (Claude Sonnet)
-                    if let (Some(input), Some(output)) = (usage.input_tokens, usage.output_tokens) {
-                        // Sonnet pricing: $0.003/1K input, $0.015/1K output
-                        total_cost_cents += (input as f64 / 1000.0) * 0.3
-                            + (output as f64 / 1000.0) * 1.5;
-                    }
+                    total_cost_cents += invocation.cost_cents.unwrap_or(0.0);
                 }
                 Err(ExecutorError::Timeout(msg)) => {
+                    self.record_invocation(invocation);
+
                     // Record timeout error in context
                     iter_context.record_error(IterationError::new(
                         iteration,
@@ -483,6 +802,7 @@ impl StoryExecutor {
                     return Err(ExecutorError::Timeout(msg));
                 }
                 Err(e) => {
+                    self.record_invocation(invocation);
                     let error_msg = e.to_string();
                     let category = IterErrorCategory::from_error_message(&error_msg, None);
 
@@ -496,6 +816,45 @@ impl StoryExecutor {
 
                     last_error = Some(error_msg);
 
+                    // Stop once the error's category has no retries left; without a
+                    // retry policy, errors no retry can fix still stop the story
+                    let error_category = e.classify();
+                    let remedy = error_category
+                        .subcategory()
+                        .and_then(|subcategory| subcategory.remedy());
+                    let (retry, backoff) = match self.config.retry_policy {
+                        Some(ref policy) => {
+                            let delay =
+                                self.config
+                                    .retry_budgets
+                                    .spend(story_id, policy, &error_category);
+                            (delay.is_some(), delay)
+                        }
+                        None => (remedy.is_none(), None),
+                    };
+                    if !retry {
+                        // Say what to do about it, keeping the original error
+                        let e = match remedy {
+                            Some(remedy) => {
+                                let message = match e {
+                                    ExecutorError::AgentError(msg)
+                                    | ExecutorError::IoError(msg) => msg,
+                                    other => other.to_string(),
+                                };
+                                ExecutorError::AgentError(format!("{}\n{}", remedy, message))
+                            }
+                            None => e,
+                        };
+                        if let Some(ref collector) = self.config.metrics_collector {
+                            collector.complete_story(
+                                false,
+                                execution_start.elapsed(),
+                                Some(e.to_string()),
+                            );
+                        }
+                        return Err(e);
+                    }
+
                     // Check for futility before continuing
                     if let Some(ref detector) = futility_detector {
                         let verdict = detector.analyze(&iter_context);
@@ -532,6 +891,9 @@ impl StoryExecutor {
                         }
                     }
 
+                    if let Some(delay) = backoff {
+                        tokio::time::sleep(delay).await;
+                    }
                     continue; // Try next iteration
                 }
             }
@@ -540,24 +902,93 @@ impl StoryExecutor {
             if cancel_receiver.has_changed().unwrap_or(false) && *cancel_receiver.borrow() {
                 return Err(ExecutorError::Cancelled);
             }
+            check_scratch_quota(scratch.as_ref())?;
+
+            // Have the reviewer check the changes against the acceptance
+            // criteria; a required approval it withholds fails the iteration
+            // before the gates run
+            if let Some(ref reviewer) = reviewer {
+                let review = match self
+                    .run_review(
+                        reviewer,
+                        &templates,
+                        story,
+                        &prd,
+                        iteration,
+                        &files_changed,
+                        scratch.as_ref(),
+                    )
+                    .await
+                {
+                    Ok(review) => Some(review),
+                    Err(e) if reviewer.require_approval => {
+                        self.record_invocation(invocation);
+                        return Err(e);
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: Review of {} failed: {}", story_id, e);
+                        None
+                    }
+                };
+                if let Some(review) = review.filter(|r| reviewer.require_approval && !r.approved())
+                {
+                    let failures = review
+                        .findings
+                        .iter()
+                        .map(|finding| GateFailureDetail::new(FailureCategory::Review, finding))
+                        .collect();
+                    iter_context.record_error(
+                        IterationError::new(
+                            iteration,
+                            IterErrorCategory::Other,
+                            format!("Reviewer '{}' requested changes", review.agent),
+                        )
+                        .with_gate("review")
+                        .with_files(files_changed.clone())
+                        .with_failures(failures),
+                    );
+                    if let Some(ref collector) = self.config.metrics_collector {
+                        collector.record_error(IterErrorCategory::Other);
+                    }
+                    last_error = Some(format!("Reviewer '{}' requested changes", review.agent));
+                    invocation.gate_outcome = Some(GateOutcome::Rejected);
+                    self.record_invocation(invocation);
+                    continue;
+                }
+            }
 
             // Run quality gates with timing
-            let gate_start = std::time::Instant::now();
-            let gate_results = self.run_quality_gates();
-            let gate_duration = gate_start.elapsed();
+            let (gate_results, gate_durations) = self.run_quality_gates(story, scratch.as_ref());
+            if let Some(ref callback) = self.display_callback {
+                for gate in &gate_results {
+                    callback.on_gate_result(story_id, iteration, gate);
+                }
+            }
+            check_scratch_quota(scratch.as_ref())?;
 
             // Record gate durations in metrics
             if let Some(ref collector) = self.config.metrics_collector {
-                for gate in &gate_results {
-                    collector.record_gate_duration(
-                        &gate.gate_name,
-                        gate_duration / gate_results.len() as u32,
-                    );
+                for (gate_name, duration) in &gate_durations {
+                    collector.record_gate_duration(gate_name, *duration);
                 }
             }
+            invocation.gate_durations = gate_durations;
+
+            self.record_criteria(story, iteration, &gate_results);
 
             last_gate_results = gate_results.clone();
             let all_passed = QualityGateChecker::all_passed(&gate_results);
+            invocation.gate_outcome = Some(if all_passed {
+                GateOutcome::Passed
+            } else {
+                GateOutcome::Failed
+            });
+            invocation.failed_gates = gate_results
+                .iter()
+                .filter(|gate| gate.is_blocking_failure())
+                .map(|gate| gate.gate_name.clone())
+                .collect();
+            self.record_invocation(invocation);
 
             if all_passed {
                 // Success! Create commit and update PRD
@@ -587,14 +1018,15 @@ impl StoryExecutor {
             }
 
             // Quality gates failed, record in iteration context
-            let failed_gates: Vec<&str> = gate_results
+            let failed_gates: Vec<&GateResult> = gate_results
                 .iter()
-                .filter(|g| !g.passed)
-                .map(|g| g.gate_name.as_str())
+                .filter(|g| g.is_blocking_failure())
                 .collect();
 
-            // Record each failed gate as an error
-            for gate_name in &failed_gates {
+            // Record each failed gate as an error, with its structured findings
+            // for the next prompt
+            for gate in &failed_gates {
+                let gate_name = gate.gate_name.as_str();
                 let category = IterErrorCategory::from_error_message("", Some(gate_name));
                 iter_context.record_error(
                     IterationError::new(
@@ -602,8 +1034,9 @@ impl StoryExecutor {
                         category,
                         format!("Gate '{}' failed", gate_name),
                     )
-                    .with_gate(*gate_name)
-                    .with_files(files_changed.clone()),
+                    .with_gate(gate_name)
+                    .with_files(files_changed.clone())
+                    .with_failures(gate.failures.clone()),
                 );
 
                 // Record in metrics
@@ -612,12 +1045,27 @@ impl StoryExecutor {
                 }
             }
 
-            last_error = Some(format!("Quality gates failed: {}", failed_gates.join(", ")));
+            let failed_names: Vec<&str> =
+                failed_gates.iter().map(|g| g.gate_name.as_str()).collect();
+            last_error = Some(format!("Quality gates failed: {}", failed_names.join(", ")));
+
+            // Keep the iteration's work on the story branch
+            if self.config.commit_iterations && !files_changed.is_empty() {
+                let message = format!("wip: {} - iteration {}", story.id, iteration);
+                if let Err(e) = self.commit_changes(&story.id, message).await {
+                    eprintln!("Warning: Failed to commit iteration {}: {}", iteration, e);
+                }
+            }
 
-            // Check for futility after gate failures
+            // Check for futility after gate failures. A failure that keeps
+            // recurring gets a mutated prompt before the story is given up on.
             if let Some(ref detector) = futility_detector {
                 let verdict = detector.analyze(&iter_context);
-                if !verdict.should_continue() {
+                let mutation_pending = !matches!(
+                    verdict,
+                    FutilityVerdict::Fatal { .. } | FutilityVerdict::NoProgress { .. }
+                ) && PromptMutation::for_context(&iter_context).is_some();
+                if !verdict.should_continue() && !mutation_pending {
                     // Check if this is a pause for guidance scenario
                     let needs_guidance =
                         matches!(verdict, FutilityVerdict::PauseForGuidance { .. });
@@ -705,7 +1153,13 @@ impl StoryExecutor {
         if !gate_results.is_empty() {
             summary.push_str("Quality Gate Results (Last Iteration):\n");
             for gate in gate_results {
-                let status = if gate.passed { "PASS" } else { "FAIL" };
+                let status = if gate.passed {
+                    "PASS"
+                } else if gate.is_warning() {
+                    "WARN"
+                } else {
+                    "FAIL"
+                };
                 summary.push_str(&format!("  - {}: {}\n", gate.gate_name, status));
                 if !gate.passed {
                     if let Some(ref details) = gate.details {
@@ -794,22 +1248,14 @@ impl StoryExecutor {
         summary
     }
 
-    /// Build an agent prompt that includes iteration context from previous failures.
-    fn build_agent_prompt_with_context(
-        &self,
-        story: &PrdUserStory,
-        prd: &PrdFile,
-        context: &IterationContext,
-    ) -> String {
-        let base_prompt = self.build_agent_prompt(story, prd);
-        // Use budget-aware context building if budget is enabled
-        let prompt_strategy = self.get_prompt_strategy();
-        let context_section = context.build_prompt_context_with_strategy(prompt_strategy);
-        format!("{}{}", base_prompt, context_section)
-    }
-
     /// Load the PRD file
     fn load_prd(&self) -> Result<PrdFile, ExecutorError> {
+        if Workspace::is_manifest(&self.config.prd_path) {
+            return Workspace::load(&self.config.prd_path)
+                .and_then(|workspace| workspace.merged())
+                .map_err(|e| ExecutorError::PrdError(e.to_string()));
+        }
+
         let content = std::fs::read_to_string(&self.config.prd_path)
             .map_err(|e| ExecutorError::PrdError(format!("Failed to read PRD: {}", e)))?;
 
@@ -829,37 +1275,108 @@ impl StoryExecutor {
             .ok_or_else(|| ExecutorError::StoryNotFound(story_id.to_string()))
     }
 
-    /// Build the agent prompt for implementing a story
-    fn build_agent_prompt(&self, story: &PrdUserStory, prd: &PrdFile) -> String {
-        let mut prompt = format!("# Implement User Story: {} - {}\n\n", story.id, story.title);
+    /// Build the agent prompt for implementing a story from the story
+    /// template, including context from previous failures if there are any.
+    /// A `continuation` of the agent's session gets the shorter continue
+    /// template instead.
+    fn build_agent_prompt(
+        &self,
+        templates: &PromptTemplates,
+        story: &PrdUserStory,
+        prd: &PrdFile,
+        context: &IterationContext,
+        continuation: bool,
+    ) -> Result<AgentPrompt, ExecutorError> {
+        // Use budget-aware context building if budget is enabled
+        let prompt_strategy = self.get_prompt_strategy();
+        let variables = PromptVariables::new(
+            story,
+            &prd.project,
+            &prd.branch_name,
+            context,
+            BudgetVariables::new(self.token_budget.as_ref(), prompt_strategy),
+            context.build_prompt_context_with_strategy(prompt_strategy),
+        );
+        let mut trimmed = matches!(
+            prompt_strategy,
+            PromptStrategy::Minimal | PromptStrategy::Critical
+        );
+        let prompt = if continuation {
+            templates.render_continuation(&variables)
+        } else {
+            let variables = match self.config.file_context_tokens {
+                Some(max_tokens) => {
+                    let files = FileContextBuilder::new(&self.config.project_root, max_tokens)
+                        .with_strategy(prompt_strategy)
+                        .build(story, context);
+                    trimmed |= !files.omitted.is_empty();
+                    variables.with_files(files)
+                }
+                None => variables,
+            };
+            templates.render_story(&variables)
+        };
+        Ok(AgentPrompt {
+            text: prompt.map_err(|e| ExecutorError::PromptError(e.to_string()))?,
+            strategy: prompt_strategy,
+            trimmed,
+        })
+    }
 
-        if !story.description.is_empty() {
-            prompt.push_str(&format!("## Description\n{}\n\n", story.description));
+    /// Run an agent, timing it and starting its telemetry record with the
+    /// exit status, token usage and estimated cost.
+    async fn invoke_agent(
+        &self,
+        agent: &AgentRun<'_>,
+        story_id: &str,
+        prompt: &str,
+        iteration: u32,
+        scratch: Option<&ScratchDir>,
+    ) -> (Result<AgentRunResult, ExecutorError>, AgentInvocation) {
+        let mut invocation = AgentInvocation::new(story_id, iteration, agent.role, agent.label());
+        let start = std::time::Instant::now();
+        let outcome = self
+            .run_agent(agent, story_id, prompt, iteration, scratch)
+            .await;
+        invocation.duration = start.elapsed();
+        match outcome {
+            Ok(ref result) => {
+                let usage = &result.token_usage;
+                invocation.input_tokens = usage.input_tokens;
+                invocation.output_tokens = usage.output_tokens;
+                invocation.tokens_estimated = !usage.is_actual;
+                invocation.cost_cents = estimate_cost_cents(agent.command, usage);
+            }
+            Err(ExecutorError::Timeout(_)) => invocation.status = InvocationStatus::TimedOut,
+            Err(_) => invocation.status = InvocationStatus::Failed,
         }
+        (outcome, invocation)
+    }
 
-        if !story.acceptance_criteria.is_empty() {
-            prompt.push_str("## Acceptance Criteria\n");
-            for (i, criterion) in story.acceptance_criteria.iter().enumerate() {
-                prompt.push_str(&format!("{}. {}\n", i + 1, criterion));
-            }
-            prompt.push('\n');
-        }
-
-        prompt.push_str(&format!(
-            "## Project Context\n\
-            - Project: {}\n\
-            - Branch: {}\n\
-            - Story Priority: {}\n\n\
-            ## Instructions\n\
-            1. Implement all acceptance criteria\n\
-            2. Ensure code compiles without errors (cargo check)\n\
-            3. Ensure no clippy warnings (cargo clippy -- -D warnings)\n\
-            4. Ensure proper formatting (cargo fmt)\n\
-            5. Keep changes focused and minimal\n",
-            prd.project, prd.branch_name, story.priority
-        ));
+    /// Record a finished agent run in the run metrics and the evidence log.
+    fn record_invocation(&self, invocation: AgentInvocation) {
+        if let Some(ref evidence) = self.config.evidence {
+            evidence.emit_record(INVOCATION_EVIDENCE_KIND, &invocation);
+        }
+        if let Some(ref run_metrics) = self.config.run_metrics {
+            run_metrics.record_invocation(invocation);
+        }
+    }
 
-        prompt
+    /// Record how each of the story's acceptance criteria fared against an
+    /// iteration's gate results in the evidence log.
+    fn record_criteria(&self, story: &PrdUserStory, iteration: u32, gate_results: &[GateResult]) {
+        let Some(ref evidence) = self.config.evidence else {
+            return;
+        };
+        if story.acceptance_criteria.is_empty() {
+            return;
+        }
+        let results = evaluate_criteria(&story.acceptance_criteria, gate_results);
+        evidence.emit_record(
+            CRITERIA_EVIDENCE_KIND,
+            &CriteriaReport::new(&story.id, iteration, &results),
+        );
     }
 
     /// Run the agent (Claude Code or Amp CLI) to implement the story
@@ -867,22 +1384,89 @@ impl StoryExecutor {
     /// This method integrates heartbeat monitoring to detect stalled agents.
     /// The heartbeat is updated whenever the agent produces output, and stall
     /// detection triggers a graceful timeout.
-    async fn run_agent(&self, prompt: &str, iteration: u32) -> Result<AgentRunResult, ExecutorError> {
-        let (program, args) = build_agent_invocation(
-            &self.config.agent_command,
-            prompt,
-            self.config.project_root.as_path(),
-        );
+    async fn run_agent(
+        &self,
+        agent: &AgentRun<'_>,
+        story_id: &str,
+        prompt: &str,
+        iteration: u32,
+        scratch: Option<&ScratchDir>,
+    ) -> Result<AgentRunResult, ExecutorError> {
+        let settings = agent.settings;
+        if let Some(spec) = MockAgentSpec::parse(agent.command) {
+            return self
+                .run_mock_agent(&spec, story_id, prompt, iteration, agent.role)
+                .await;
+        }
+        if let Some(spec) = ApiAgentSpec::parse(agent.command).map_err(ExecutorError::AgentError)? {
+            let spec = spec.with_model(agent.model);
+            return self
+                .run_api_agent(&spec, story_id, prompt, iteration, agent.role)
+                .await;
+        }
+
+        let template =
+            AgentCommandTemplate::parse(agent.command).map_err(ExecutorError::AgentError)?;
+
+        // Aider (and a template naming `{prompt_file}`) reads the prompt from
+        // a file, kept outside the project so it is never committed
+        let prompt_file = if is_aider_command(agent.command)
+            || template.as_ref().is_some_and(|t| t.uses_prompt_file())
+        {
+            let dir = scratch
+                .map(|scratch| scratch.path().to_path_buf())
+                .unwrap_or_else(std::env::temp_dir);
+            Some(PromptFile::write(&dir, story_id, iteration, prompt)?)
+        } else {
+            None
+        };
+        let (program, args) = match template {
+            Some(ref template) => {
+                let (program, mut args) = template.expand(&TemplateValues {
+                    prompt_file: prompt_file.as_ref().map(|file| file.path.as_path()),
+                    story_id,
+                    working_dir: &self.config.project_root,
+                    model: settings.model.as_deref().or(agent.model),
+                });
+                args.extend(settings.flags.iter().cloned());
+                if !template.uses_prompt_file() {
+                    args.push(prompt.to_string());
+                }
+                (program, args)
+            }
+            None => build_agent_invocation(
+                agent.command,
+                prompt,
+                self.config.project_root.as_path(),
+                prompt_file.as_ref().map(|file| file.path.as_path()),
+                agent.capabilities,
+                settings,
+            ),
+        };
         let codex_json = program.contains("codex") && args.iter().any(|arg| arg == "--json");
 
-        // Check if the agent is available (cross-platform)
-        if !is_program_in_path(&program) {
+        // Check if the agent is available (cross-platform). A containerized
+        // agent only needs the container runtime on the host.
+        if let Some(ref container) = self.config.agent_container {
+            if !is_program_in_path(&container.runtime) {
+                return Err(ExecutorError::AgentError(format!(
+                    "Container runtime '{}' for the agent not found in PATH",
+                    container.runtime
+                )));
+            }
+        } else if !is_program_in_path(&program) {
             return Err(ExecutorError::AgentError(format!(
-                "Agent '{}' not found in PATH. Install Claude Code CLI, Codex CLI, or Amp CLI.",
+                "Agent '{}' not found in PATH. Install Claude Code CLI, Codex CLI, Amp CLI, or Aider.",
                 program
             )));
         }
 
+        // Wait for a launch slot before starting the heartbeat, so time spent
+        // behind the rate limit is not mistaken for a stalled agent
+        if let Some(ref limiter) = self.config.launch_limiter {
+            limiter.acquire().await;
+        }
+
         // Create heartbeat monitor for stall detection
         let (heartbeat_monitor, mut heartbeat_receiver) =
             HeartbeatMonitor::new(self.config.timeout_config.clone());
@@ -890,16 +1474,55 @@ impl StoryExecutor {
         // Start heartbeat monitoring before agent execution
         heartbeat_monitor.start_monitoring().await;
 
-        // Spawn the agent process with piped stdout/stderr for streaming
-        let mut child = tokio::process::Command::new(&program)
-            .args(&args)
+        // Spawn the agent process with piped stdout/stderr for streaming,
+        // under the configured per-worker resource limits. The agent is killed
+        // if the execution is dropped (story cancelled or timed out).
+        let (mut command, _container_guard) = match self.config.agent_container {
+            Some(ref container) => {
+                let name = container_name(story_id, iteration);
+                let mut run = ContainerRun {
+                    name: &name,
+                    workdir: &self.config.project_root,
+                    shared: Vec::new(),
+                    env: Vec::new(),
+                };
+                if let Some(scratch) = scratch {
+                    run.shared.push(scratch.path());
+                    run.env
+                        .push(("TMPDIR".to_string(), scratch.path().display().to_string()));
+                }
+                run.env.extend(settings.env.clone());
+                if let Some(file) = prompt_file.as_ref().filter(|_| scratch.is_none()) {
+                    run.shared.push(&file.path);
+                }
+                let mut command = tokio::process::Command::new(&container.runtime);
+                command.args(container.run_args(
+                    &run,
+                    &program,
+                    &args,
+                    &self.config.resource_limits,
+                ));
+                (command, Some(container.guard(name)))
+            }
+            None => {
+                let (launch_program, launch_args) =
+                    self.config.resource_limits.wrap(&program, &args);
+                let mut command = tokio::process::Command::new(launch_program);
+                command.args(launch_args).envs(&settings.env);
+                if let Some(scratch) = scratch {
+                    command.env("TMPDIR", scratch.path());
+                }
+                (command, None)
+            }
+        };
+        command
             .current_dir(&self.config.project_root)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| {
-                ExecutorError::AgentError(format!("Failed to spawn {}: {}", program, e))
-            })?;
+            .kill_on_drop(true);
+        let mut child = command.spawn().map_err(|e| {
+            ExecutorError::AgentError(format!("Failed to spawn {}: {}", program, e))
+        })?;
 
         // Take ownership of stdout and stderr
         let stdout = child.stdout.take();
@@ -909,9 +1532,10 @@ impl StoryExecutor {
         let mut stdout_reader = stdout.map(|s| BufReader::new(s).lines());
         let mut stderr_reader = stderr.map(|s| BufReader::new(s).lines());
 
-        // Collect both stdout and stderr for error reporting
-        let mut stderr_output = String::new();
-        let mut stdout_output = String::new();
+        // Collect both stdout and stderr for error reporting, keeping only the
+        // most recent output in memory
+        let mut stderr_output = self.output_capture(story_id, iteration, agent.role, "stderr");
+        let mut stdout_output = self.output_capture(story_id, iteration, agent.role, "stdout");
 
         // Track if we received a stall detection
         let mut stall_detected = false;
@@ -929,15 +1553,17 @@ impl StoryExecutor {
                         Some(HeartbeatEvent::Warning { missed, elapsed_secs, remaining_secs }) => {
                             // Log warning about missed heartbeats with actionable info
                             eprintln!(
-                                "Warning: No agent output for {}s ({} heartbeat intervals). Stall detection in {}s unless activity resumes. (iteration {})",
-                                elapsed_secs, missed, remaining_secs, iteration
+                                "Warning: No agent output for {}s ({} heartbeat intervals). Stall detection in {}s unless activity resumes. (iteration {}){}",
+                                elapsed_secs, missed, remaining_secs, iteration,
+                                last_output_suffix(heartbeat_monitor.last_output().await)
                             );
                         }
                         Some(HeartbeatEvent::StallDetected { missed, elapsed_secs, threshold_secs }) => {
                             // Stall detected - trigger graceful timeout with clear explanation
                             eprintln!(
-                                "Agent stall detected: no output for {}s (exceeded {}s threshold, {} missed heartbeats). Terminating agent. (iteration {})",
-                                elapsed_secs, threshold_secs, missed, iteration
+                                "Agent stall detected: no output for {}s (exceeded {}s threshold, {} missed heartbeats). Terminating agent. (iteration {}){}",
+                                elapsed_secs, threshold_secs, missed, iteration,
+                                last_output_suffix(heartbeat_monitor.last_output().await)
                             );
                             stall_detected = true;
                             // Kill the child process gracefully
@@ -961,7 +1587,7 @@ impl StoryExecutor {
                     match line {
                         Ok(Some(text)) => {
                             // Activity detected - update heartbeat
-                            heartbeat_monitor.pulse().await;
+                            heartbeat_monitor.pulse_output(&text).await;
 
                             // Stream output to display callback if configured
                             if let Some(ref callback) = self.display_callback {
@@ -975,14 +1601,12 @@ impl StoryExecutor {
                                     } else {
                                         &mut stdout_output
                                     };
-                                    target.push_str(&parsed);
-                                    target.push('\n');
+                                    target.push_line(&parsed);
                                     continue;
                                 }
                             }
                             // Collect stdout for error diagnostics
-                            stdout_output.push_str(&text);
-                            stdout_output.push('\n');
+                            stdout_output.push_line(&text);
                         }
                         Ok(None) => {
                             // EOF on stdout
@@ -1005,7 +1629,7 @@ impl StoryExecutor {
                     match line {
                         Ok(Some(text)) => {
                             // Activity detected - update heartbeat
-                            heartbeat_monitor.pulse().await;
+                            heartbeat_monitor.pulse_output(&text).await;
 
                             // Stream output to display callback if configured
                             if let Some(ref callback) = self.display_callback {
@@ -1014,14 +1638,12 @@ impl StoryExecutor {
 
                             if codex_json {
                                 if let Some((parsed, _is_error)) = extract_codex_json_line(&text) {
-                                    stderr_output.push_str(&parsed);
-                                    stderr_output.push('\n');
+                                    stderr_output.push_line(&parsed);
                                     continue;
                                 }
                             }
                             // Collect stderr for error reporting
-                            stderr_output.push_str(&text);
-                            stderr_output.push('\n');
+                            stderr_output.push_line(&text);
                         }
                         Ok(None) => {
                             // EOF on stderr
@@ -1043,8 +1665,8 @@ impl StoryExecutor {
                             if !exit_status.success() {
                                 // Build comprehensive error message from both streams
                                 let error_details = self.build_agent_error_message(
-                                    &stdout_output,
-                                    &stderr_output,
+                                    &stdout_output.to_report(),
+                                    &stderr_output.to_report(),
                                     exit_status.code()
                                 );
                                 return Err(ExecutorError::AgentError(error_details));
@@ -1053,14 +1675,14 @@ impl StoryExecutor {
                             let files_changed = self.get_changed_files()?;
                             // Parse token usage from agent output
                             let token_usage = extract_or_estimate(
-                                &stdout_output,
+                                &stdout_output.contents(),
                                 prompt,
                                 &self.token_estimator,
                             );
                             return Ok(AgentRunResult {
                                 files_changed,
                                 token_usage,
-                                stdout: stdout_output,
+                                stdout: stdout_output.contents(),
                             });
                         }
                         Err(e) => {
@@ -1102,8 +1724,8 @@ impl StoryExecutor {
 
                         if !exit_status.success() {
                             let error_details = self.build_agent_error_message(
-                                &stdout_output,
-                                &stderr_output,
+                                &stdout_output.to_report(),
+                                &stderr_output.to_report(),
                                 exit_status.code(),
                             );
                             return Err(ExecutorError::AgentError(error_details));
@@ -1112,14 +1734,14 @@ impl StoryExecutor {
                         let files_changed = self.get_changed_files()?;
                         // Parse token usage from agent output
                         let token_usage = extract_or_estimate(
-                            &stdout_output,
+                            &stdout_output.contents(),
                             prompt,
                             &self.token_estimator,
                         );
                         return Ok(AgentRunResult {
                             files_changed,
                             token_usage,
-                            stdout: stdout_output,
+                            stdout: stdout_output.contents(),
                         });
                     }
                     Err(e) => {
@@ -1149,14 +1771,164 @@ impl StoryExecutor {
         // Get list of changed files from git
         let files_changed = self.get_changed_files()?;
         // Parse token usage from agent output
-        let token_usage = extract_or_estimate(&stdout_output, prompt, &self.token_estimator);
+        let token_usage =
+            extract_or_estimate(&stdout_output.contents(), prompt, &self.token_estimator);
         Ok(AgentRunResult {
             files_changed,
             token_usage,
-            stdout: stdout_output,
+            stdout: stdout_output.contents(),
         })
     }
 
+    /// Run the story against a provider API instead of an agent CLI.
+    ///
+    /// Token usage is exact, summed from the provider's responses.
+    #[cfg(feature = "api-agent")]
+    async fn run_api_agent(
+        &self,
+        spec: &ApiAgentSpec,
+        story_id: &str,
+        prompt: &str,
+        iteration: u32,
+        role: &str,
+    ) -> Result<AgentRunResult, ExecutorError> {
+        use crate::agent::{ApiAgent, ApiAgentConfig};
+
+        let agent = ApiAgentConfig::from_spec(spec)
+            .and_then(ApiAgent::new)
+            .map_err(|e| ExecutorError::AgentError(e.to_string()))?;
+        if let Some(ref limiter) = self.config.launch_limiter {
+            limiter.acquire().await;
+        }
+
+        let mut stdout_output = self.output_capture(story_id, iteration, role, "stdout");
+        let timeout_duration = self.config.timeout_config.agent_timeout;
+        let run = tokio::time::timeout(
+            timeout_duration,
+            agent.run(prompt, &self.config.project_root, |line| {
+                if let Some(ref callback) = self.display_callback {
+                    callback.on_agent_output(line, false);
+                }
+                stdout_output.push_line(line);
+            }),
+        )
+        .await
+        .map_err(|_| {
+            ExecutorError::Timeout(format!(
+                "Agent '{}' timed out after {:?} (iteration {})",
+                spec.command(),
+                timeout_duration,
+                iteration
+            ))
+        })?
+        .map_err(|e| ExecutorError::AgentError(e.to_string()))?;
+        stdout_output.finish();
+
+        Ok(AgentRunResult {
+            files_changed: self.get_changed_files()?,
+            token_usage: ParsedTokenUsage::actual(
+                run.input_tokens,
+                run.output_tokens,
+                spec.provider.name(),
+            ),
+            stdout: stdout_output.contents(),
+        })
+    }
+
+    /// Play the mock agent's script for one run.
+    async fn run_mock_agent(
+        &self,
+        spec: &MockAgentSpec,
+        story_id: &str,
+        prompt: &str,
+        iteration: u32,
+        role: &str,
+    ) -> Result<AgentRunResult, ExecutorError> {
+        let agent = MockScript::load(&self.config.project_root, spec)
+            .map(MockAgent::new)
+            .map_err(|e| ExecutorError::AgentError(e.to_string()))?;
+        if let Some(ref limiter) = self.config.launch_limiter {
+            limiter.acquire().await;
+        }
+
+        let mut stdout_output = self.output_capture(story_id, iteration, role, "stdout");
+        let timeout_duration = self.config.timeout_config.agent_timeout;
+        let run = tokio::time::timeout(
+            timeout_duration,
+            agent.run(
+                story_id,
+                iteration,
+                role,
+                &self.config.project_root,
+                |line| {
+                    if let Some(ref callback) = self.display_callback {
+                        callback.on_agent_output(line, false);
+                    }
+                    stdout_output.push_line(line);
+                },
+            ),
+        )
+        .await
+        .map_err(|_| {
+            ExecutorError::Timeout(format!(
+                "Agent 'mock' timed out after {:?} (iteration {})",
+                timeout_duration, iteration
+            ))
+        })?
+        .map_err(|e| ExecutorError::AgentError(e.to_string()))?;
+        stdout_output.finish();
+
+        let stdout = stdout_output.contents();
+        let token_usage = match (run.input_tokens, run.output_tokens) {
+            (Some(input), Some(output)) => ParsedTokenUsage::actual(input, output, "mock"),
+            _ => extract_or_estimate(&stdout, prompt, &self.token_estimator),
+        };
+        Ok(AgentRunResult {
+            files_changed: self.get_changed_files()?,
+            token_usage,
+            stdout,
+        })
+    }
+
+    /// Without the `api-agent` feature, API agents cannot run.
+    #[cfg(not(feature = "api-agent"))]
+    async fn run_api_agent(
+        &self,
+        spec: &ApiAgentSpec,
+        _story_id: &str,
+        _prompt: &str,
+        _iteration: u32,
+        _role: &str,
+    ) -> Result<AgentRunResult, ExecutorError> {
+        Err(ExecutorError::AgentError(format!(
+            "Agent '{}' needs Ralph built with the `api-agent` feature",
+            spec.command()
+        )))
+    }
+
+    /// Bounded capture for one stream of an iteration's agent (or reviewer)
+    /// output.
+    ///
+    /// Output beyond the configured caps is spilled to the story's evidence
+    /// artifacts, when an artifacts directory is configured.
+    fn output_capture(
+        &self,
+        story_id: &str,
+        iteration: u32,
+        role: &str,
+        stream: &str,
+    ) -> OutputCapture {
+        let capture = OutputCapture::new(self.config.output_capture);
+        match self.config.artifacts_dir {
+            Some(ref artifacts_dir) => capture.with_spill_path(
+                artifacts_dir
+                    .join(story_id)
+                    .join(format!("{}-iter{}-{}.log", role, iteration, stream)),
+            ),
+            None => capture,
+        }
+    }
+
     /// Build a comprehensive error message from agent output.
     ///
     /// Extracts the most relevant error information from stdout and stderr,
@@ -1235,6 +2007,89 @@ impl StoryExecutor {
         }
     }
 
+    /// Have the reviewer check an iteration's changes against the story's
+    /// acceptance criteria, recording the review as evidence.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_review(
+        &self,
+        reviewer: &Reviewer,
+        templates: &PromptTemplates,
+        story: &PrdUserStory,
+        prd: &PrdFile,
+        iteration: u32,
+        files_changed: &[String],
+        scratch: Option<&ScratchDir>,
+    ) -> Result<Review, ExecutorError> {
+        let variables = ReviewVariables::new(
+            story,
+            &prd.project,
+            iteration,
+            files_changed.to_vec(),
+            self.get_diff()?,
+        );
+        let prompt = templates
+            .render_review(&variables)
+            .map_err(|e| ExecutorError::PromptError(e.to_string()))?;
+        let capabilities = (reviewer.command == self.config.agent_command)
+            .then_some(self.config.agent_capabilities.as_ref())
+            .flatten();
+        let agent = AgentRun {
+            command: &reviewer.command,
+            capabilities,
+            settings: &reviewer.settings,
+            model: reviewer.settings.model.as_deref(),
+            role: "review",
+        };
+        let (outcome, invocation) = self
+            .invoke_agent(&agent, &story.id, &prompt, iteration, scratch)
+            .await;
+        self.record_invocation(invocation);
+        let result = outcome?;
+
+        let review = Review::parse(reviewer.label(), &result.stdout);
+        if let Some(ref evidence) = self.config.evidence {
+            evidence.emit_record(
+                REVIEW_EVIDENCE_KIND,
+                &ReviewEvidence {
+                    step_id: &story.id,
+                    iteration,
+                    require_approval: reviewer.require_approval,
+                    review: &review,
+                },
+            );
+        }
+        Ok(review)
+    }
+
+    /// The uncommitted changes as a unified diff against `HEAD`, with new
+    /// files included, cut to [`MAX_REVIEW_DIFF_BYTES`].
+    fn get_diff(&self) -> Result<String, ExecutorError> {
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&self.config.project_root)
+                .output()
+                .map_err(|e| ExecutorError::GitError(format!("Failed to run git diff: {}", e)))
+        };
+        let mut diff = String::from_utf8_lossy(&git(&["diff", "HEAD"])?.stdout).into_owned();
+        let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+        for file in String::from_utf8_lossy(&untracked.stdout).lines() {
+            // `--no-index` exits 1 when the files differ, which they always do
+            let output = git(&["diff", "--no-index", "--", "/dev/null", file])?;
+            diff.push_str(&String::from_utf8_lossy(&output.stdout));
+        }
+
+        if diff.len() > MAX_REVIEW_DIFF_BYTES {
+            let mut end = MAX_REVIEW_DIFF_BYTES;
+            while !diff.is_char_boundary(end) {
+                end -= 1;
+            }
+            diff.truncate(end);
+            diff.push_str("\n... (diff truncated)");
+        }
+        Ok(diff)
+    }
+
     /// Get the list of files changed according to git
     fn get_changed_files(&self) -> Result<Vec<String>, ExecutorError> {
         let output = Command::new("git")
@@ -1294,10 +2149,46 @@ impl StoryExecutor {
     }
 
     /// Run quality gates and return results
-    fn run_quality_gates(&self) -> Vec<GateResult> {
+    ///
+    /// Gate artifacts are written to a per-story subdirectory of the configured
+    /// artifacts directory. When the profile selects impacted tests only, a
+    /// passing iteration is confirmed with a full test run before it is accepted.
+    fn run_quality_gates(
+        &self,
+        story: &PrdUserStory,
+        scratch: Option<&ScratchDir>,
+    ) -> (Vec<GateResult>, BTreeMap<String, Duration>) {
         let profile = self.config.quality_profile.clone().unwrap_or_default();
-        let checker = QualityGateChecker::new(profile, &self.config.project_root);
-        checker.run_all()
+        let mut checker = QualityGateChecker::new(profile, &self.config.project_root)
+            .with_target_files(story.target_files.clone())
+            .with_output_capture(self.config.output_capture)
+            .with_resource_limits(
+                self.config.resource_limits.clone(),
+                scratch.map(|scratch| scratch.path().to_path_buf()),
+            );
+        if let Some(ref artifacts_dir) = self.config.artifacts_dir {
+            checker = checker.with_artifacts_dir(artifacts_dir.join(&story.id));
+        }
+        if let Some(ref evidence) = self.config.evidence {
+            checker = checker.with_evidence(evidence.clone(), &story.id);
+        }
+        let mut durations = BTreeMap::new();
+        let mut results = checker.run_all_gates_with_progress(|update| {
+            if let Some(duration) = update.duration {
+                durations.insert(update.gate_name, duration);
+            }
+        });
+
+        // Final gate pass: impacted tests passed, now run the full suite
+        if checker.uses_test_selection() && QualityGateChecker::all_passed(&results) {
+            if let Some(tests) = results.iter_mut().find(|r| r.gate_name == "tests") {
+                let start = std::time::Instant::now();
+                *tests = checker.run_gate(QualityGateChecker::check_tests_full);
+                *durations.entry("tests".to_string()).or_default() += start.elapsed();
+            }
+        }
+
+        (results, durations)
     }
 
     /// Create a git commit with the proper format
@@ -1308,8 +2199,19 @@ impl StoryExecutor {
     ///
     /// All git operations are wrapped with timeout from `ExecutorConfig.timeout_config.git_timeout`.
     async fn create_commit(&self, story: &PrdUserStory) -> Result<String, ExecutorError> {
+        // Create commit with proper message format: feat: [ID] - [Title]
+        let commit_message = format!("feat: {} - {}", story.id, story.title);
+        self.commit_changes(&story.id, commit_message).await
+    }
+
+    /// Stage all changes and commit them with `commit_message`, returning the commit hash.
+    async fn commit_changes(
+        &self,
+        story_id: &str,
+        commit_message: String,
+    ) -> Result<String, ExecutorError> {
         let git_timeout = self.config.timeout_config.git_timeout;
-        let story_id = story.id.clone();
+        let story_id = story_id.to_string();
 
         // Acquire git mutex if configured (for parallel execution), with timeout
         let _guard = if let Some(ref mutex) = self.config.git_mutex {
@@ -1368,13 +2270,20 @@ impl StoryExecutor {
             }
         }
 
-        // Create commit with proper message format: feat: [ID] - [Title]
-        let commit_message = format!("feat: {} - {}", story.id, story.title);
         let project_root = self.config.project_root.clone();
         let commit_result = tokio::time::timeout(git_timeout, async {
             tokio::task::spawn_blocking(move || {
+                // Nothing is staged when the agent already committed its
+                // changes; keep its commit instead of failing the story
+                let nothing_staged = Command::new("git")
+                    .args(["diff", "--cached", "--quiet"])
+                    .current_dir(&project_root)
+                    .status()?;
+                if nothing_staged.success() {
+                    return Ok(nothing_staged);
+                }
                 Command::new("git")
-                    .args(["commit", "-m", &commit_message])
+                    .args(["commit", "-q", "-m", &commit_message])
                     .current_dir(&project_root)
                     .status()
             })
@@ -1481,31 +2390,14 @@ impl StoryExecutor {
     }
 
     /// Update the PRD file to set passes: true for the story
-    fn update_prd_passes(&self, story_id: &str) -> Result<(), ExecutorError> {
-        // Read the PRD as raw JSON to preserve structure
-        let content = std::fs::read_to_string(&self.config.prd_path)
-            .map_err(|e| ExecutorError::PrdError(format!("Failed to read PRD: {}", e)))?;
-
-        let mut json: serde_json::Value = serde_json::from_str(&content)
-            .map_err(|e| ExecutorError::PrdError(format!("Failed to parse PRD: {}", e)))?;
-
-        // Find and update the story
-        if let Some(stories) = json.get_mut("userStories").and_then(|s| s.as_array_mut()) {
-            for story in stories {
-                if story.get("id").and_then(|id| id.as_str()) == Some(story_id) {
-                    story["passes"] = serde_json::Value::Bool(true);
-                    break;
-                }
-            }
+    pub(crate) fn update_prd_passes(&self, story_id: &str) -> Result<(), ExecutorError> {
+        if !self.config.prd_writeback {
+            return Ok(());
         }
-
-        // Write back with pretty formatting
-        let updated_content = serde_json::to_string_pretty(&json)
-            .map_err(|e| ExecutorError::PrdError(format!("Failed to serialize PRD: {}", e)))?;
-
-        std::fs::write(&self.config.prd_path, updated_content)
-            .map_err(|e| ExecutorError::PrdError(format!("Failed to write PRD: {}", e)))?;
-
+        // A workspace story is marked in its member PRD
+        let (prd_path, story_id) = story_prd(&self.config.prd_path, story_id);
+        set_story_passes(&prd_path, &story_id, true)
+            .map_err(|e| ExecutorError::PrdError(e.to_string()))?;
         Ok(())
     }
 
@@ -1560,6 +2452,34 @@ impl StoryExecutor {
 }
 
 /// Check if a program exists in PATH (cross-platform)
+/// Fail the story if its scratch directory has outgrown its quota.
+/// Estimated cost in cents of a run's token usage at default (Claude
+/// Sonnet) pricing; local models cost nothing.
+fn estimate_cost_cents(agent_command: &str, usage: &ParsedTokenUsage) -> Option<f64> {
+    let local = MockAgentSpec::parse(agent_command).is_some()
+        || ApiAgentSpec::parse(agent_command)
+            .ok()
+            .flatten()
+            .is_some_and(|spec| spec.provider.is_local());
+    match (usage.input_tokens, usage.output_tokens) {
+        _ if local => Some(0.0),
+        // Sonnet pricing: $0.003/1K input, $0.015/1K output
+        (Some(input), Some(output)) => {
+            Some((input as f64 / 1000.0) * 0.3 + (output as f64 / 1000.0) * 1.5)
+        }
+        _ => None,
+    }
+}
+
+fn check_scratch_quota(scratch: Option<&ScratchDir>) -> Result<(), ExecutorError> {
+    match scratch {
+        Some(scratch) => scratch
+            .check_quota()
+            .map_err(ExecutorError::ResourceLimitExceeded),
+        None => Ok(()),
+    }
+}
+
 fn is_program_in_path(program: &str) -> bool {
     #[cfg(target_os = "windows")]
     let check_cmd = "where";
@@ -1582,15 +2502,72 @@ pub fn is_agent_available(agent: &str) -> bool {
 
 /// Detect the best available agent CLI
 pub fn detect_agent() -> Option<String> {
-    // Prefer Claude Code, then Codex, then Amp
+    // Prefer Claude Code, then Codex, then Amp, then Aider
     if is_agent_available("claude") {
         Some("claude".to_string())
     } else if is_agent_available("codex") {
         Some("codex".to_string())
     } else if is_agent_available("amp") {
         Some("amp".to_string())
+    } else if is_agent_available("aider") {
+        Some("aider".to_string())
     } else {
-        None
+        // Without a CLI, call a provider API directly if a key is set
+        ApiAgentSpec::from_env().map(|spec| spec.command())
+    }
+}
+
+/// " Last output: ..." for heartbeat warnings, or nothing if the agent has
+/// printed nothing yet.
+fn last_output_suffix(last_output: Option<String>) -> String {
+    last_output
+        .map(|line| {
+            format!(
+                " Last output: {}",
+                line.chars().take(120).collect::<String>()
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `agent_command` runs Aider.
+fn is_aider_command(agent_command: &str) -> bool {
+    agent_command.contains("aider")
+}
+
+/// Prompt written to a file for agents that read it from disk, removed once
+/// the agent has run.
+struct PromptFile {
+    path: PathBuf,
+}
+
+impl PromptFile {
+    fn write(
+        dir: &Path,
+        story_id: &str,
+        iteration: u32,
+        prompt: &str,
+    ) -> Result<Self, ExecutorError> {
+        let path = dir.join(format!(
+            "ralph-prompt-{}-{}-iter{}.md",
+            std::process::id(),
+            story_id,
+            iteration
+        ));
+        std::fs::write(&path, prompt).map_err(|e| {
+            ExecutorError::IoError(format!(
+                "Failed to write prompt file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for PromptFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
     }
 }
 
@@ -1598,6 +2575,9 @@ fn build_agent_invocation(
     agent_command: &str,
     prompt: &str,
     project_root: &Path,
+    prompt_file: Option<&Path>,
+    capabilities: Option<&AgentCapabilities>,
+    settings: &AgentSettings,
 ) -> (String, Vec<String>) {
     fn env_var_truthy(name: &str) -> bool {
         std::env::var(name)
@@ -1605,34 +2585,60 @@ fn build_agent_invocation(
             .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
             .unwrap_or(false)
     }
+    // Without a probe result every flag is assumed to be supported
+    let supports = |flag: &str| capabilities.map_or(true, |caps| caps.supports(flag));
+    let flags = |flags: &[&str]| -> Vec<String> {
+        flags
+            .iter()
+            .filter(|flag| supports(flag))
+            .map(|flag| flag.to_string())
+            .collect()
+    };
+    // The configured model, else the first of the agent's model variables
+    let model = |env_vars: &[&str]| {
+        settings
+            .model
+            .clone()
+            .or_else(|| env_vars.iter().find_map(|name| std::env::var(name).ok()))
+            .filter(|_| supports("--model"))
+    };
+    let is_codex = agent_command == "codex" || agent_command.contains("codex");
+    let permission_mode = settings.permission_mode.unwrap_or(
+        if is_codex && env_var_truthy("RALPH_CODEX_DANGEROUS") {
+            PermissionMode::Bypass
+        } else {
+            PermissionMode::Auto
+        },
+    );
+    let unattended = permission_mode != PermissionMode::Default;
 
     if agent_command == "claude" || agent_command.contains("claude") {
         // Claude Code CLI - use --print for non-interactive mode
         // and --dangerously-skip-permissions to allow file changes
-        (
-            "claude".to_string(),
-            vec![
-                "--print".to_string(),
-                "--dangerously-skip-permissions".to_string(),
-                prompt.to_string(),
-            ],
-        )
-    } else if agent_command == "codex" || agent_command.contains("codex") {
-        let mut args = vec![
-            "exec".to_string(),
-            "--full-auto".to_string(),
-            "--json".to_string(),
-            prompt.to_string(),
-        ];
-
-        if env_var_truthy("RALPH_CODEX_DANGEROUS") {
-            args.insert(2, "--dangerously-bypass-approvals-and-sandbox".to_string());
+        let mut args = vec!["--print".to_string()];
+        if unattended {
+            args.extend(flags(&["--dangerously-skip-permissions"]));
         }
-
-        if let Ok(model) = std::env::var("CODEX_OSS_MODEL") {
+        if let Some(model) = model(&[]) {
             args.push("--model".to_string());
             args.push(model);
-        } else if let Ok(model) = std::env::var("CODEX_MODEL") {
+        }
+        args.extend(settings.flags.iter().cloned());
+        args.push(prompt.to_string());
+        ("claude".to_string(), args)
+    } else if is_codex {
+        let mut args = vec!["exec".to_string()];
+        if permission_mode == PermissionMode::Bypass {
+            args.extend(flags(&["--dangerously-bypass-approvals-and-sandbox"]));
+        }
+        if unattended {
+            args.extend(flags(&["--full-auto"]));
+        }
+        args.extend(flags(&["--json"]));
+        args.extend(settings.flags.iter().cloned());
+        args.push(prompt.to_string());
+
+        if let Some(model) = model(&["CODEX_OSS_MODEL", "CODEX_MODEL"]) {
             args.push("--model".to_string());
             args.push(model);
         }
@@ -1641,6 +2647,7 @@ fn build_agent_invocation(
         if std::env::var("CODEX_OSS")
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
             .unwrap_or(false)
+            && supports("--oss")
         {
             args.push("--oss".to_string());
             args.push("--local-provider".to_string());
@@ -1651,12 +2658,45 @@ fn build_agent_invocation(
         args_with_dir.extend(args);
         ("codex".to_string(), args_with_dir)
     } else if agent_command == "amp" || agent_command.contains("amp") {
-        (
-            "amp".to_string(),
-            vec!["--prompt".to_string(), prompt.to_string()],
-        )
+        let mut args = settings.flags.clone();
+        args.push("--prompt".to_string());
+        args.push(prompt.to_string());
+        ("amp".to_string(), args)
+    } else if is_aider_command(agent_command) {
+        // Ralph commits once the quality gates pass, so Aider must not commit
+        // each edit itself (or commit pending changes before editing)
+        let mut args = if unattended {
+            flags(&["--yes-always"])
+        } else {
+            Vec::new()
+        };
+        args.extend(flags(&[
+            "--no-auto-commits",
+            "--no-dirty-commits",
+            "--no-pretty",
+            "--no-stream",
+            "--no-check-update",
+        ]));
+        if let Some(model) = model(&["AIDER_MODEL"]) {
+            args.push("--model".to_string());
+            args.push(model);
+        }
+        args.extend(settings.flags.iter().cloned());
+        match prompt_file.filter(|_| supports("--message-file")) {
+            Some(path) => {
+                args.push("--message-file".to_string());
+                args.push(path.display().to_string());
+            }
+            None => {
+                args.push("--message".to_string());
+                args.push(prompt.to_string());
+            }
+        }
+        ("aider".to_string(), args)
     } else {
-        (agent_command.to_string(), vec![prompt.to_string()])
+        let mut args = settings.flags.clone();
+        args.push(prompt.to_string());
+        (agent_command.to_string(), args)
     }
 }
 
@@ -1806,7 +2846,12 @@ mod tests {
 
         let prd = executor.load_prd().unwrap();
         let story = executor.find_story(&prd, "US-001").unwrap();
-        let prompt = executor.build_agent_prompt(story, &prd);
+        let context = IterationContext::new("US-001", 3);
+        let prompt = executor
+            .build_agent_prompt(&PromptTemplates::builtin(), story, &prd, &context, false)
+            .unwrap();
+        assert!(!prompt.trimmed);
+        let prompt = prompt.text;
 
         assert!(prompt.contains("US-001"));
         assert!(prompt.contains("First story"));
@@ -1814,6 +2859,13 @@ mod tests {
         assert!(prompt.contains("AC2"));
         assert!(prompt.contains("TestProject"));
         assert!(prompt.contains("cargo check"));
+
+        let prompt = executor
+            .build_agent_prompt(&PromptTemplates::builtin(), story, &prd, &context, true)
+            .unwrap()
+            .text;
+        assert!(prompt.starts_with("# Continue User Story: US-001"));
+        assert!(!prompt.contains("AC1"));
     }
 
     #[test]
@@ -1827,6 +2879,36 @@ mod tests {
             .contains("Git error"));
     }
 
+    #[test]
+    fn test_executor_error_subcategories() {
+        use crate::error::{ErrorSubcategory, TransientReason};
+
+        let subcategory = |e: ExecutorError| e.classify().subcategory();
+        assert_eq!(
+            subcategory(ExecutorError::AgentError(
+                "Exit code: 1\nstderr:\nAPI Error: 401 Unauthorized".to_string()
+            )),
+            Some(ErrorSubcategory::Auth)
+        );
+        assert_eq!(
+            subcategory(ExecutorError::AgentError(
+                "429 Too Many Requests".to_string()
+            )),
+            Some(ErrorSubcategory::RateLimit)
+        );
+        assert_eq!(
+            subcategory(ExecutorError::IoError(
+                "No space left on device (os error 28)".to_string()
+            )),
+            Some(ErrorSubcategory::DiskFull)
+        );
+        // Anything else keeps the error's own category
+        assert_eq!(
+            ExecutorError::AgentError("Exit code: 1".to_string()).classify(),
+            ErrorCategory::Transient(TransientReason::ServerError)
+        );
+    }
+
     #[test]
     fn test_update_prd_passes() {
         let prd_file = create_test_prd();
@@ -1863,14 +2945,26 @@ mod tests {
         let agent = detect_agent();
         // Just verify it returns a valid option or None
         if let Some(a) = agent {
-            assert!(a == "claude" || a == "codex" || a == "amp");
+            assert!(
+                a == "claude"
+                    || a == "codex"
+                    || a == "amp"
+                    || a == "aider"
+                    || a.starts_with("api:")
+            );
         }
     }
 
     #[test]
     fn test_build_agent_invocation_codex_default() {
-        let (program, args) =
-            build_agent_invocation("codex", "test prompt", Path::new("/tmp/project"));
+        let (program, args) = build_agent_invocation(
+            "codex",
+            "test prompt",
+            Path::new("/tmp/project"),
+            None,
+            None,
+            &AgentSettings::default(),
+        );
 
         assert_eq!(program, "codex");
         assert_eq!(args[0], "-C");
@@ -1880,6 +2974,141 @@ mod tests {
         assert!(args.contains(&"test prompt".to_string()));
     }
 
+    #[test]
+    fn test_build_agent_invocation_aider() {
+        let (program, args) = build_agent_invocation(
+            "aider",
+            "test prompt",
+            Path::new("/tmp/project"),
+            Some(Path::new("/tmp/prompt.md")),
+            None,
+            &AgentSettings::default(),
+        );
+
+        assert_eq!(program, "aider");
+        assert!(args.contains(&"--no-auto-commits".to_string()));
+        assert!(args.contains(&"--yes-always".to_string()));
+        let file_index = args.iter().position(|arg| arg == "--message-file").unwrap();
+        assert_eq!(args[file_index + 1], "/tmp/prompt.md");
+        assert!(!args.contains(&"test prompt".to_string()));
+    }
+
+    #[test]
+    fn test_build_agent_invocation_drops_unsupported_flags() {
+        // An older Aider without --message-file or --no-check-update
+        let capabilities = AgentCapabilities {
+            program: "aider".to_string(),
+            version: Some("aider 0.40.0".to_string()),
+            flags: Some(vec![
+                "--yes-always".to_string(),
+                "--no-auto-commits".to_string(),
+            ]),
+        };
+        let (_, args) = build_agent_invocation(
+            "aider",
+            "test prompt",
+            Path::new("/tmp/project"),
+            Some(Path::new("/tmp/prompt.md")),
+            Some(&capabilities),
+            &AgentSettings::default(),
+        );
+        assert_eq!(
+            args,
+            vec![
+                "--yes-always",
+                "--no-auto-commits",
+                "--message",
+                "test prompt"
+            ]
+        );
+
+        // A Codex without --json falls back to plain output
+        let capabilities = AgentCapabilities {
+            program: "codex".to_string(),
+            version: None,
+            flags: Some(vec!["--full-auto".to_string()]),
+        };
+        let (_, args) = build_agent_invocation(
+            "codex",
+            "test prompt",
+            Path::new("/tmp/project"),
+            None,
+            Some(&capabilities),
+            &AgentSettings::default(),
+        );
+        assert!(args.contains(&"--full-auto".to_string()));
+        assert!(!args.contains(&"--json".to_string()));
+    }
+
+    #[test]
+    fn test_build_agent_invocation_with_settings() {
+        let settings = AgentSettings {
+            model: Some("claude-opus-4-1".to_string()),
+            permission_mode: Some(PermissionMode::Default),
+            flags: vec!["--max-turns".to_string(), "40".to_string()],
+            ..Default::default()
+        };
+        let (_, args) = build_agent_invocation(
+            "claude",
+            "test prompt",
+            Path::new("/tmp/project"),
+            None,
+            None,
+            &settings,
+        );
+        assert_eq!(
+            args,
+            vec![
+                "--print",
+                "--model",
+                "claude-opus-4-1",
+                "--max-turns",
+                "40",
+                "test prompt"
+            ]
+        );
+
+        let settings = AgentSettings {
+            permission_mode: Some(PermissionMode::Bypass),
+            ..Default::default()
+        };
+        let (_, args) = build_agent_invocation(
+            "codex",
+            "test prompt",
+            Path::new("/tmp/project"),
+            None,
+            None,
+            &settings,
+        );
+        assert!(args.contains(&"--dangerously-bypass-approvals-and-sandbox".to_string()));
+        assert!(args.contains(&"--full-auto".to_string()));
+
+        let settings = AgentSettings {
+            flags: vec!["--fast".to_string()],
+            ..Default::default()
+        };
+        let (program, args) = build_agent_invocation(
+            "./my-agent.sh",
+            "test prompt",
+            Path::new("/tmp/project"),
+            None,
+            None,
+            &settings,
+        );
+        assert_eq!(program, "./my-agent.sh");
+        assert_eq!(args, vec!["--fast", "test prompt"]);
+    }
+
+    #[test]
+    fn test_prompt_file_removed_on_drop() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = PromptFile::write(dir.path(), "US-001", 2, "do the thing").unwrap();
+        let path = file.path.clone();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "do the thing");
+        drop(file);
+        assert!(!path.exists());
+    }
+
     #[test]
     fn test_extract_codex_json_line_text() {
         let line = r#"{"type":"message","data":{"content":[{"type":"text","text":"Hello"}]}}"#;
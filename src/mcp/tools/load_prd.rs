@@ -1,9 +1,11 @@
 // load_prd MCP tool implementation
 // This tool loads a PRD file into the Ralph MCP server
 
+use crate::prd::workspace::Workspace;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::ops::Range;
 use std::path::Path;
 
 /// Request parameters for the load_prd tool.
@@ -113,7 +115,7 @@ impl Default for ParallelConfig {
 }
 
 /// User story structure for validation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct PrdUserStory {
     /// Story ID (e.g., "US-001")
     pub id: String,
@@ -122,9 +124,9 @@ pub struct PrdUserStory {
     /// Story description
     #[serde(default)]
     pub description: String,
-    /// Acceptance criteria
+    /// Acceptance criteria, as plain text or with how each is verified
     #[serde(rename = "acceptanceCriteria", default)]
-    pub acceptance_criteria: Vec<String>,
+    pub acceptance_criteria: Vec<AcceptanceCriterion>,
     /// Priority (lower is higher priority)
     pub priority: u32,
     /// Whether the story passes
@@ -135,6 +137,166 @@ pub struct PrdUserStory {
     /// Files that this story will modify (for conflict detection)
     #[serde(rename = "targetFiles", default)]
     pub target_files: Vec<String>,
+    /// Files that this story only reads; shared with other readers, exclusive against writers
+    #[serde(rename = "readFiles", default)]
+    pub read_files: Vec<String>,
+    /// Whether the story is resource-heavy (held back when memory headroom is low)
+    #[serde(default)]
+    pub heavy: bool,
+    /// Agent to run this story with instead of the run's agent or an
+    /// `[[assign]]` rule's (e.g. "codex" or "api:ollama:qwen2.5-coder:7b")
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Model for API and local agents to use on this story instead of the
+    /// agent's default (e.g. a small local model for lint fixes)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Epic the story belongs to; progress is reported per epic
+    #[serde(default)]
+    pub epic: Option<String>,
+    /// Conditions outside the PRD that must hold before the story runs
+    #[serde(default)]
+    pub prerequisites: Vec<Prerequisite>,
+    /// Expected size of the story, compared with what it took at run end
+    #[serde(default)]
+    pub estimate: Option<StoryEstimate>,
+}
+
+/// How big a story is expected to be. Any of the fields may be given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct StoryEstimate {
+    /// Story points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<u32>,
+    /// Tokens the story is expected to use across all its iterations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+    /// Agent iterations the story is expected to take
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+}
+
+/// An external condition a story needs, checked before it is dispatched.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Prerequisite {
+    /// A shell command that must exit successfully, e.g. `docker info`
+    Command { command: String },
+    /// An environment variable that must be set and not empty
+    Env { name: String },
+    /// A TCP service, as `host:port`, that must accept connections
+    Service { address: String },
+}
+
+/// How an acceptance criterion is verified.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Verification {
+    /// A test that must pass, by (part of) its name
+    Test { name: String },
+    /// A quality gate that must pass, e.g. "lint"
+    Gate { gate: String },
+    /// Checked by a person; ralph cannot verify it
+    #[default]
+    Manual,
+}
+
+/// One acceptance criterion of a story.
+///
+/// A PRD may give a criterion as plain text, which is verified manually, or
+/// as `{"id": "AC-1", "description": "...", "verify": {"type": "test",
+/// "name": "login_renders"}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "RawCriterion")]
+pub struct AcceptanceCriterion {
+    /// Criterion ID; see [`AcceptanceCriterion::label`]
+    pub id: Option<String>,
+    pub description: String,
+    pub verify: Verification,
+}
+
+impl AcceptanceCriterion {
+    /// A plain-text criterion, verified manually.
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            description: description.into(),
+            verify: Verification::Manual,
+        }
+    }
+
+    /// The criterion's ID, or `AC-<n>` for the `index`th criterion without one.
+    pub fn label(&self, index: usize) -> String {
+        self.id
+            .clone()
+            .unwrap_or_else(|| format!("AC-{}", index + 1))
+    }
+}
+
+impl From<&str> for AcceptanceCriterion {
+    fn from(description: &str) -> Self {
+        Self::new(description)
+    }
+}
+
+/// Acceptance criterion as written in a PRD.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawCriterion {
+    Text(String),
+    Structured {
+        #[serde(default)]
+        id: Option<String>,
+        description: String,
+        #[serde(default)]
+        verify: Verification,
+    },
+}
+
+impl From<RawCriterion> for AcceptanceCriterion {
+    fn from(raw: RawCriterion) -> Self {
+        match raw {
+            RawCriterion::Text(description) => Self::new(description),
+            RawCriterion::Structured {
+                id,
+                description,
+                verify,
+            } => Self {
+                id,
+                description,
+                verify,
+            },
+        }
+    }
+}
+
+/// JSON Schema a PRD file must satisfy.
+pub const PRD_SCHEMA: &str = include_str!("prd.schema.json");
+
+/// One way in which a PRD violates the schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrdProblem {
+    /// JSON pointer to the offending value ("" for the document itself)
+    pub pointer: String,
+    /// 1-based line of the offending value, if it could be located
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+impl std::fmt::Display for PrdProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}: {}", pointer, self.message)
+    }
 }
 
 /// Validation error types for PRD files.
@@ -146,8 +308,10 @@ pub enum PrdValidationError {
     ReadError(String),
     /// JSON parsing failed
     ParseError(String),
-    /// PRD structure is invalid
-    StructureError(String),
+    /// PRD does not satisfy the schema
+    Invalid(Vec<PrdProblem>),
+    /// Workspace manifest or one of its members is invalid
+    Workspace(String),
 }
 
 impl std::fmt::Display for PrdValidationError {
@@ -162,9 +326,14 @@ impl std::fmt::Display for PrdValidationError {
             PrdValidationError::ParseError(msg) => {
                 write!(f, "Failed to parse PRD JSON: {}", msg)
             }
-            PrdValidationError::StructureError(msg) => {
-                write!(f, "Invalid PRD structure: {}", msg)
+            PrdValidationError::Invalid(problems) => {
+                write!(f, "Invalid PRD structure:")?;
+                for problem in problems {
+                    write!(f, "\n  {}", problem)?;
+                }
+                Ok(())
             }
+            PrdValidationError::Workspace(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -184,59 +353,224 @@ pub fn validate_prd(path: &Path) -> Result<PrdFile, PrdValidationError> {
         return Err(PrdValidationError::FileNotFound(path.display().to_string()));
     }
 
+    // A workspace manifest stands for its members' stories, merged
+    if Workspace::is_manifest(path) {
+        return Workspace::load(path)
+            .and_then(|workspace| workspace.merged())
+            .map_err(|e| PrdValidationError::Workspace(e.to_string()));
+    }
+
     // Read file content
     let content =
         fs::read_to_string(path).map_err(|e| PrdValidationError::ReadError(e.to_string()))?;
 
-    // Parse JSON
-    let prd: PrdFile = serde_json::from_str(&content)
-        .map_err(|e| PrdValidationError::ParseError(e.to_string()))?;
+    validate_prd_content(&content)
+}
+
+/// Validate PRD JSON against [`PRD_SCHEMA`] and return parsed content.
+///
+/// Every schema violation is reported at once, each with its JSON pointer
+/// and line number.
+pub fn validate_prd_content(content: &str) -> Result<PrdFile, PrdValidationError> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| PrdValidationError::ParseError(e.to_string()))?;
+
+    let problems = schema_problems(content, &value);
+    if !problems.is_empty() {
+        return Err(PrdValidationError::Invalid(problems));
+    }
 
-    // Validate structure
-    validate_prd_structure(&prd)?;
+    serde_json::from_value(value).map_err(|e| PrdValidationError::ParseError(e.to_string()))
+}
 
-    Ok(prd)
+/// The compiled PRD schema.
+fn prd_validator() -> &'static jsonschema::Validator {
+    static VALIDATOR: std::sync::OnceLock<jsonschema::Validator> = std::sync::OnceLock::new();
+    VALIDATOR.get_or_init(|| {
+        let schema = serde_json::from_str(PRD_SCHEMA).expect("PRD schema is valid JSON");
+        jsonschema::validator_for(&schema).expect("PRD schema is a valid JSON Schema")
+    })
 }
 
-/// Validate the structure of a parsed PRD.
-fn validate_prd_structure(prd: &PrdFile) -> Result<(), PrdValidationError> {
-    // Check project name is not empty
-    if prd.project.trim().is_empty() {
-        return Err(PrdValidationError::StructureError(
-            "Project name is empty".to_string(),
-        ));
+/// Schema violations of `value`, parsed from `content`, in document order.
+fn schema_problems(content: &str, value: &serde_json::Value) -> Vec<PrdProblem> {
+    use jsonschema::error::ValidationErrorKind;
+
+    let mut problems: Vec<PrdProblem> = prd_validator()
+        .iter_errors(value)
+        .map(|error| {
+            let pointer = error.instance_path.as_str().to_string();
+            let message = match &error.kind {
+                ValidationErrorKind::Pattern { pattern } if pattern == "\\S" => {
+                    "must not be blank".to_string()
+                }
+                ValidationErrorKind::MinItems { limit } => {
+                    format!("must have at least {} item(s)", limit)
+                }
+                _ => error.to_string(),
+            };
+            PrdProblem {
+                line: pointer_line(content, &pointer),
+                pointer,
+                message,
+            }
+        })
+        .collect();
+    problems.sort_by_key(|problem| problem.line.unwrap_or(usize::MAX));
+    problems
+}
+
+/// 1-based line on which the value at JSON `pointer` starts in `content`.
+pub fn pointer_line(content: &str, pointer: &str) -> Option<usize> {
+    let scanner = JsonScanner::seek(content, pointer)?;
+    Some(content[..scanner.pos].matches('\n').count() + 1)
+}
+
+/// Byte range of the value at JSON `pointer` in `content`.
+pub fn pointer_span(content: &str, pointer: &str) -> Option<Range<usize>> {
+    let mut scanner = JsonScanner::seek(content, pointer)?;
+    let start = scanner.pos;
+    scanner.skip_value()?;
+    Some(start..scanner.pos)
+}
+
+/// Minimal scanner locating values in JSON text that is known to be valid.
+struct JsonScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> JsonScanner<'a> {
+    /// A scanner at the start of the value at JSON `pointer` in `content`.
+    fn seek(content: &'a str, pointer: &str) -> Option<Self> {
+        let tokens: Vec<String> = if pointer.is_empty() {
+            Vec::new()
+        } else {
+            pointer
+                .strip_prefix('/')?
+                .split('/')
+                .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                .collect()
+        };
+        let mut scanner = JsonScanner {
+            bytes: content.as_bytes(),
+            pos: 0,
+        };
+        scanner.skip_whitespace();
+        for token in &tokens {
+            scanner.enter(token)?;
+        }
+        Some(scanner)
     }
 
-    // Check branch name is not empty
-    if prd.branch_name.trim().is_empty() {
-        return Err(PrdValidationError::StructureError(
-            "Branch name is empty".to_string(),
-        ));
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
     }
 
-    // Check there's at least one user story
-    if prd.user_stories.is_empty() {
-        return Err(PrdValidationError::StructureError(
-            "No user stories found".to_string(),
-        ));
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
     }
 
-    // Validate each user story
-    for story in &prd.user_stories {
-        if story.id.trim().is_empty() {
-            return Err(PrdValidationError::StructureError(
-                "User story has empty ID".to_string(),
-            ));
+    /// Move from the start of a container to the start of its member `token`.
+    fn enter(&mut self, token: &str) -> Option<()> {
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    if self.peek()? != b':' {
+                        return None;
+                    }
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if key == token {
+                        return Some(());
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    if self.peek()? != b',' {
+                        return None;
+                    }
+                    self.pos += 1;
+                }
+            }
+            b'[' => {
+                let index: usize = token.parse().ok()?;
+                self.pos += 1;
+                for _ in 0..index {
+                    self.skip_whitespace();
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    if self.peek()? != b',' {
+                        return None;
+                    }
+                    self.pos += 1;
+                }
+                self.skip_whitespace();
+                Some(())
+            }
+            _ => None,
         }
-        if story.title.trim().is_empty() {
-            return Err(PrdValidationError::StructureError(format!(
-                "User story {} has empty title",
-                story.id
-            )));
+    }
+
+    /// Read a string, returning it unescaped.
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => break,
+                _ => self.pos += 1,
+            }
         }
+        self.pos += 1;
+        let raw = std::str::from_utf8(&self.bytes[start..self.pos]).ok()?;
+        serde_json::from_str(raw).ok()
     }
 
-    Ok(())
+    fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => self.string().map(|_| ()),
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return Some(());
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
 }
 
 /// Create a success response for load_prd.
@@ -351,10 +685,11 @@ mod tests {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            PrdValidationError::StructureError(msg) => {
-                assert!(msg.contains("Project name is empty"));
+            PrdValidationError::Invalid(problems) => {
+                assert_eq!(problems.len(), 1);
+                assert_eq!(problems[0].pointer, "/project");
             }
-            _ => panic!("Expected StructureError error"),
+            _ => panic!("Expected Invalid error"),
         }
     }
 
@@ -372,10 +707,11 @@ mod tests {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            PrdValidationError::StructureError(msg) => {
-                assert!(msg.contains("Branch name is empty"));
+            PrdValidationError::Invalid(problems) => {
+                assert_eq!(problems.len(), 1);
+                assert_eq!(problems[0].pointer, "/branchName");
             }
-            _ => panic!("Expected StructureError error"),
+            _ => panic!("Expected Invalid error"),
         }
     }
 
@@ -393,10 +729,11 @@ mod tests {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            PrdValidationError::StructureError(msg) => {
-                assert!(msg.contains("No user stories found"));
+            PrdValidationError::Invalid(problems) => {
+                assert_eq!(problems.len(), 1);
+                assert_eq!(problems[0].pointer, "/userStories");
             }
-            _ => panic!("Expected StructureError error"),
+            _ => panic!("Expected Invalid error"),
         }
     }
 
@@ -414,10 +751,11 @@ mod tests {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            PrdValidationError::StructureError(msg) => {
-                assert!(msg.contains("empty ID"));
+            PrdValidationError::Invalid(problems) => {
+                assert_eq!(problems.len(), 1);
+                assert_eq!(problems[0].pointer, "/userStories/0/id");
             }
-            _ => panic!("Expected StructureError error"),
+            _ => panic!("Expected Invalid error"),
         }
     }
 
@@ -435,10 +773,11 @@ mod tests {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            PrdValidationError::StructureError(msg) => {
-                assert!(msg.contains("empty title"));
+            PrdValidationError::Invalid(problems) => {
+                assert_eq!(problems.len(), 1);
+                assert_eq!(problems[0].pointer, "/userStories/0/title");
             }
-            _ => panic!("Expected StructureError error"),
+            _ => panic!("Expected Invalid error"),
         }
     }
 
@@ -516,8 +855,87 @@ mod tests {
             "Failed to parse PRD JSON: Unexpected token"
         );
 
-        let error = PrdValidationError::StructureError("Missing field".to_string());
-        assert_eq!(error.to_string(), "Invalid PRD structure: Missing field");
+        let error = PrdValidationError::Invalid(vec![PrdProblem {
+            pointer: "/userStories/0/title".to_string(),
+            line: Some(7),
+            message: "must not be blank".to_string(),
+        }]);
+        assert_eq!(
+            error.to_string(),
+            "Invalid PRD structure:\n  line 7: /userStories/0/title: must not be blank"
+        );
+    }
+
+    #[test]
+    fn test_structured_acceptance_criteria() {
+        let content = r#"{
+  "project": "Demo",
+  "branchName": "demo",
+  "userStories": [{
+    "id": "US-001", "title": "Login", "priority": 1, "passes": false,
+    "acceptanceCriteria": [
+      "Looks right",
+      {"id": "AC-login", "description": "Renders", "verify": {"type": "test", "name": "login_renders"}},
+      {"description": "Lint clean", "verify": {"type": "gate", "gate": "lint"}}
+    ]
+  }]
+}"#;
+        let prd = validate_prd_content(content).unwrap();
+        let criteria = &prd.user_stories[0].acceptance_criteria;
+        assert_eq!(criteria[0], AcceptanceCriterion::new("Looks right"));
+        assert_eq!(criteria[1].label(1), "AC-login");
+        assert_eq!(
+            criteria[1].verify,
+            Verification::Test {
+                name: "login_renders".to_string()
+            }
+        );
+        assert_eq!(criteria[2].label(2), "AC-3");
+
+        let missing_gate = content.replace(r#""gate": "lint""#, r#""name": "lint""#);
+        match validate_prd_content(&missing_gate) {
+            Err(PrdValidationError::Invalid(problems)) => {
+                assert_eq!(problems[0].pointer, "/userStories/0/acceptanceCriteria/2");
+            }
+            other => panic!("Expected Invalid error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_prd_reports_all_problems() {
+        let content = r#"{
+  "project": "Demo",
+  "userStories": [
+    {"id": "US-001", "title": "First", "priority": 1, "passes": false},
+    {
+      "id": "US-002",
+      "title": "Second",
+      "priority": -1,
+      "passes": "no",
+      "dependsOn": ["US-001", 3]
+    }
+  ],
+  "parallel": {"conflictStrategy": "by_file"}
+}"#;
+        let problems = match validate_prd_content(content) {
+            Err(PrdValidationError::Invalid(problems)) => problems,
+            other => panic!("Expected Invalid error, got {:?}", other),
+        };
+        let found: Vec<(&str, Option<usize>)> = problems
+            .iter()
+            .map(|problem| (problem.pointer.as_str(), problem.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("", Some(1)),
+                ("/userStories/1/priority", Some(8)),
+                ("/userStories/1/passes", Some(9)),
+                ("/userStories/1/dependsOn/1", Some(10)),
+                ("/parallel/conflictStrategy", Some(13)),
+            ]
+        );
+        assert!(problems[0].message.contains("branchName"));
     }
 
     #[test]
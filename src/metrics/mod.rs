@@ -3,14 +3,19 @@
 //! This module provides infrastructure for collecting and analyzing
 //! execution metrics across story executions, iterations, and quality gates.
 
+pub mod timeline;
+
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 
+use crate::agent::AgentCapabilities;
+use crate::budget::PromptStrategy;
 use crate::iteration::context::ErrorCategory;
+use crate::mcp::tools::load_prd::{PrdUserStory, StoryEstimate};
 
 /// Metrics for a single story execution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,13 +109,51 @@ pub struct ExecutionMetrics {
     pub first_time_success_rate: f64,
 }
 
+/// Scheduling contention a parallel story ran into before it was dispatched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContentionMetrics {
+    /// Time spent in the pending queue before dispatch
+    #[serde(default)]
+    pub queue_wait: Duration,
+    /// Time spent queued while every worker slot was taken
+    #[serde(default)]
+    pub semaphore_wait: Duration,
+    /// Dispatch attempts that found the story's files locked by another story
+    #[serde(default)]
+    pub lock_failures: u32,
+    /// Scheduling cycles the story was deferred for a conflicting story
+    #[serde(default)]
+    pub conflict_deferrals: u32,
+}
+
+impl ContentionMetrics {
+    fn add(&mut self, other: &ContentionMetrics) {
+        self.queue_wait += other.queue_wait;
+        self.semaphore_wait += other.semaphore_wait;
+        self.lock_failures += other.lock_failures;
+        self.conflict_deferrals += other.conflict_deferrals;
+    }
+}
+
 /// Metrics for a single step within a run.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StepMetrics {
     /// Step identifier (typically the story ID)
     pub step_id: String,
     /// Number of attempts made for this step
     pub attempts: u32,
+    /// Number of times the step was re-run after a retryable error
+    #[serde(default)]
+    pub retries: u32,
+    /// Retries the step had left per error category when it finished
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub retry_budget: BTreeMap<String, u32>,
+    /// Time spent waiting for the agent launch rate limit
+    #[serde(default)]
+    pub launch_wait: Duration,
+    /// Scheduling contention before the step was dispatched
+    #[serde(default)]
+    pub contention: ContentionMetrics,
     /// Step duration
     pub duration: Duration,
     /// Whether the step succeeded
@@ -121,6 +164,15 @@ pub struct StepMetrics {
     pub completed_at: std::time::SystemTime,
     /// Error message if step failed
     pub error: Option<String>,
+    /// Agent (and model) the step ran with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Epic of the step's story
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epic: Option<String>,
+    /// Estimate the PRD gave for the step's story
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<StoryEstimate>,
 }
 
 impl StepMetrics {
@@ -129,17 +181,302 @@ impl StepMetrics {
         Self {
             step_id: step_id.into(),
             attempts: 0,
+            retries: 0,
+            retry_budget: BTreeMap::new(),
+            launch_wait: Duration::ZERO,
+            contention: ContentionMetrics::default(),
             duration: Duration::ZERO,
             success: false,
             started_at: now,
             completed_at: now,
             error: None,
+            agent: None,
+            epic: None,
+            estimate: None,
         }
     }
 }
 
+/// Results of the steps run with one agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentMetrics {
+    /// Steps run with the agent
+    pub steps: u32,
+    /// Steps that succeeded
+    pub completed: u32,
+    /// Steps that failed
+    pub failures: u32,
+    /// Attempts across the steps
+    pub attempts: u32,
+    /// Total duration of the steps
+    pub duration: Duration,
+}
+
+/// Progress of the stories in one epic.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EpicMetrics {
+    /// Stories in the epic
+    pub stories: u32,
+    /// Stories that passed, in this run or before it
+    pub completed: u32,
+    /// Stories that ran and failed
+    pub failures: u32,
+    /// Total duration of the epic's steps
+    pub duration: Duration,
+    /// Cost of the agent runs for the epic's stories, in cents
+    pub cost_cents: f64,
+}
+
+/// What a story with an estimate actually took.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EstimateMetrics {
+    /// Estimate from the PRD
+    pub estimate: StoryEstimate,
+    /// Whether the story passed
+    pub success: bool,
+    /// Agent iterations run
+    pub iterations: u32,
+    /// Tokens used, if any agent run reported or estimated them
+    pub tokens: Option<u64>,
+    /// Duration of the story's step
+    pub duration: Duration,
+}
+
+/// Evidence record kind for reconciliation reports.
+pub const RECONCILIATION_EVIDENCE_KIND: &str = "reconciliation_report";
+
+/// What was done about the issues a reconciliation found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationAction {
+    /// Nothing was found, or nothing could be done
+    #[default]
+    None,
+    /// The reconciliation agent fixed the issues in the integrated tree
+    AgentFix,
+    /// The affected stories were re-run one at a time
+    SequentialRetry,
+    /// Sequential fallback is disabled, so the run failed
+    FallbackDisabled,
+}
+
+/// What reconciling a set of finished parallel stories found and cost.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    /// Stories finished since the previous reconciliation
+    pub stories: Vec<String>,
+    /// Issues found in the integrated tree
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<String>,
+    /// Files the issues were found in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Stories the issues were traced to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affected_stories: Vec<String>,
+    /// Conflicting story branches merged by a resolution strategy, keyed by
+    /// story, with the strategy that merged them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub merge_resolutions: BTreeMap<String, String>,
+    /// Whether the reconciliation agent's fix left no issues but unmerged
+    /// story branches, if the agent ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_fixed: Option<bool>,
+    #[serde(default)]
+    pub action: ReconciliationAction,
+    /// Whether each story re-run sequentially passed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub retries: BTreeMap<String, bool>,
+    /// Agent iterations the sequential re-runs took
+    #[serde(default)]
+    pub retry_iterations: u32,
+    /// Issues left once everything was tried
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remaining_issues: Vec<String>,
+    /// Time spent reconciling, re-runs included
+    pub duration: Duration,
+}
+
+impl ReconciliationReport {
+    /// Whether the tree was left without issues.
+    pub fn is_clean(&self) -> bool {
+        self.remaining_issues.is_empty()
+    }
+}
+
+/// Evidence record kind for agent invocation telemetry.
+pub const INVOCATION_EVIDENCE_KIND: &str = "agent_invocation";
+
+/// How an agent invocation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvocationStatus {
+    /// The agent exited successfully
+    Succeeded,
+    /// The agent failed to start or exited with an error
+    Failed,
+    /// The agent timed out or stalled
+    TimedOut,
+}
+
+/// What became of the changes an agent invocation made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GateOutcome {
+    /// Every blocking quality gate passed
+    Passed,
+    /// A blocking quality gate failed
+    Failed,
+    /// The reviewer withheld a required approval, so no gates ran
+    Rejected,
+}
+
+/// Telemetry for one run of an agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentInvocation {
+    /// Step (story) the agent ran for
+    pub step_id: String,
+    /// Iteration of the step
+    pub iteration: u32,
+    /// `agent` for an implementation run, `review` for a reviewer pass
+    pub role: String,
+    /// Agent (and model) that ran
+    pub agent: String,
+    /// Timestamp when the agent was started
+    pub started_at: std::time::SystemTime,
+    /// Wall time of the run, including any wait for a launch slot
+    pub duration: Duration,
+    /// How the run ended
+    pub status: InvocationStatus,
+    /// Input tokens used, if known
+    #[serde(default)]
+    pub input_tokens: Option<u64>,
+    /// Output tokens used, if known
+    #[serde(default)]
+    pub output_tokens: Option<u64>,
+    /// Whether the token counts are estimates rather than reported usage
+    #[serde(default)]
+    pub tokens_estimated: bool,
+    /// Estimated cost of the run in cents
+    #[serde(default)]
+    pub cost_cents: Option<f64>,
+    /// Prompt strategy the budget selected for the prompt
+    pub prompt_strategy: PromptStrategy,
+    /// Whether context was left out of the prompt to fit the budget or the
+    /// file context cap
+    #[serde(default)]
+    pub context_trimmed: bool,
+    /// Result of the review and gates that followed, if they ran
+    #[serde(default)]
+    pub gate_outcome: Option<GateOutcome>,
+    /// Blocking gates that failed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_gates: Vec<String>,
+    /// How long each gate that followed took
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub gate_durations: BTreeMap<String, Duration>,
+}
+
+impl AgentInvocation {
+    /// Start recording a run of `agent` for `step_id`.
+    pub fn new(
+        step_id: impl Into<String>,
+        iteration: u32,
+        role: impl Into<String>,
+        agent: impl Into<String>,
+    ) -> Self {
+        Self {
+            step_id: step_id.into(),
+            iteration,
+            role: role.into(),
+            agent: agent.into(),
+            started_at: std::time::SystemTime::now(),
+            duration: Duration::ZERO,
+            status: InvocationStatus::Succeeded,
+            input_tokens: None,
+            output_tokens: None,
+            tokens_estimated: false,
+            cost_cents: None,
+            prompt_strategy: PromptStrategy::default(),
+            context_trimmed: false,
+            gate_outcome: None,
+            failed_gates: Vec::new(),
+            gate_durations: BTreeMap::new(),
+        }
+    }
+
+    /// Total tokens used, if known.
+    pub fn total_tokens(&self) -> Option<u64> {
+        match (self.input_tokens, self.output_tokens) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+        }
+    }
+}
+
+/// Totals over a set of agent invocations, for comparing groups of them
+/// (e.g. runs with and without trimmed context).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InvocationStats {
+    /// Invocations counted
+    pub invocations: u32,
+    /// Invocations that failed or timed out
+    pub agent_failures: u32,
+    /// Invocations whose changes passed the gates
+    pub gates_passed: u32,
+    /// Invocations whose changes failed the gates or review
+    pub gates_failed: u32,
+    /// Total wall time
+    pub duration: Duration,
+    /// Total known tokens
+    pub tokens: u64,
+    /// Total estimated cost in cents
+    pub cost_cents: f64,
+}
+
+impl InvocationStats {
+    /// Sum up `invocations`.
+    pub fn from_invocations<'a>(
+        invocations: impl IntoIterator<Item = &'a AgentInvocation>,
+    ) -> Self {
+        let mut stats = Self::default();
+        for invocation in invocations {
+            stats.invocations += 1;
+            if invocation.status != InvocationStatus::Succeeded {
+                stats.agent_failures += 1;
+            }
+            match invocation.gate_outcome {
+                Some(GateOutcome::Passed) => stats.gates_passed += 1,
+                Some(GateOutcome::Failed | GateOutcome::Rejected) => stats.gates_failed += 1,
+                None => {}
+            }
+            stats.duration += invocation.duration;
+            stats.tokens += invocation.total_tokens().unwrap_or(0);
+            stats.cost_cents += invocation.cost_cents.unwrap_or(0.0);
+        }
+        stats
+    }
+
+    /// Share of invocations that did not produce passing changes: the agent
+    /// failed, or the review or gates rejected its work.
+    pub fn failure_rate(&self) -> f64 {
+        if self.invocations == 0 {
+            return 0.0;
+        }
+        (self.agent_failures + self.gates_failed) as f64 / self.invocations as f64
+    }
+
+    /// Mean wall time per invocation.
+    pub fn mean_duration(&self) -> Duration {
+        if self.invocations == 0 {
+            return Duration::ZERO;
+        }
+        self.duration / self.invocations
+    }
+}
+
 /// Aggregated metrics for a single run.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RunMetrics {
     /// Unique run identifier
     pub run_id: String,
@@ -161,8 +498,36 @@ pub struct RunMetrics {
     pub failures: u32,
     /// Total retry count across steps
     pub retries: u32,
+    /// Total time steps spent waiting for the agent launch rate limit
+    #[serde(default)]
+    pub launch_wait: Duration,
+    /// Scheduling contention summed across all stories, including ones that
+    /// never ran
+    #[serde(default)]
+    pub contention: ContentionMetrics,
     /// Percentage of steps with evidence recorded
     pub completeness_percent: f64,
+    /// Steps with evidence recorded
+    #[serde(default)]
+    pub evidence_steps: Vec<String>,
+    /// Version and supported flags of the agent CLI, probed at startup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<AgentCapabilities>,
+    /// Results by the agent (and model) the steps ran with
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, AgentMetrics>,
+    /// Progress by the epic the stories belong to
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub epics: BTreeMap<String, EpicMetrics>,
+    /// Actual against estimated size of the stories that ran with an estimate
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub estimates: BTreeMap<String, EstimateMetrics>,
+    /// Every agent run, in the order they finished
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invocations: Vec<AgentInvocation>,
+    /// Every reconciliation of parallel stories, in the order they ran
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reconciliations: Vec<ReconciliationReport>,
     /// Per-step durations keyed by step ID
     pub step_durations: HashMap<String, Duration>,
     /// Detailed step metrics
@@ -176,7 +541,18 @@ struct RunMetricsState {
     started_instant: Instant,
     expected_steps: usize,
     steps: HashMap<String, StepMetrics>,
+    /// Kept apart from `steps` so contention alone does not count a story as attempted
+    contention: HashMap<String, ContentionMetrics>,
     evidence_steps: HashSet<String>,
+    agent: Option<AgentCapabilities>,
+    invocations: Vec<AgentInvocation>,
+    reconciliations: Vec<ReconciliationReport>,
+    /// Epic of each story that has one
+    story_epics: HashMap<String, String>,
+    /// Estimate of each story that has one
+    story_estimates: HashMap<String, StoryEstimate>,
+    /// Stories that passed before the run
+    passed_before: HashSet<String>,
 }
 
 /// Thread-safe run metrics collector.
@@ -196,11 +572,74 @@ impl RunMetricsCollector {
                 started_instant: Instant::now(),
                 expected_steps,
                 steps: HashMap::new(),
+                contention: HashMap::new(),
                 evidence_steps: HashSet::new(),
+                agent: None,
+                invocations: Vec::new(),
+                reconciliations: Vec::new(),
+                story_epics: HashMap::new(),
+                story_estimates: HashMap::new(),
+                passed_before: HashSet::new(),
             })),
         }
     }
 
+    /// Continue collecting into the run captured by `snapshot`.
+    ///
+    /// Used when a checkpointed run is resumed, so the resumed run keeps the
+    /// same run ID and its totals include the steps run before the pause.
+    /// Contention of stories that never started is not carried over.
+    pub fn resume(snapshot: RunMetrics) -> Self {
+        let contention = snapshot
+            .steps
+            .iter()
+            .map(|step| (step.step_id.clone(), step.contention.clone()))
+            .collect();
+        let story_epics = snapshot
+            .steps
+            .iter()
+            .filter_map(|step| Some((step.step_id.clone(), step.epic.clone()?)))
+            .collect();
+        let story_estimates = snapshot
+            .steps
+            .iter()
+            .filter_map(|step| Some((step.step_id.clone(), step.estimate?)))
+            .collect();
+        let steps = snapshot
+            .steps
+            .into_iter()
+            .map(|step| (step.step_id.clone(), step))
+            .collect();
+        // Count the time run before the pause towards the run duration
+        let now = Instant::now();
+        let started_instant = now.checked_sub(snapshot.run_duration).unwrap_or(now);
+        Self {
+            inner: Arc::new(Mutex::new(RunMetricsState {
+                run_id: snapshot.run_id,
+                started_at: snapshot.started_at,
+                started_instant,
+                expected_steps: snapshot.expected_steps as usize,
+                steps,
+                contention,
+                evidence_steps: snapshot.evidence_steps.into_iter().collect(),
+                agent: snapshot.agent,
+                invocations: snapshot.invocations,
+                reconciliations: snapshot.reconciliations,
+                story_epics,
+                story_estimates,
+                passed_before: HashSet::new(),
+            })),
+        }
+    }
+
+    /// ID of the run being collected.
+    pub fn run_id(&self) -> String {
+        self.inner
+            .lock()
+            .map(|state| state.run_id.clone())
+            .unwrap_or_else(|_| "run-unknown".to_string())
+    }
+
     /// Generate a run ID using timestamp and process ID.
     pub fn generate_run_id() -> String {
         let millis = std::time::SystemTime::now()
@@ -229,6 +668,131 @@ impl RunMetricsCollector {
         }
     }
 
+    /// Record that a step is being re-run after a retryable error.
+    pub fn record_retry(&self, step_id: &str) {
+        if let Ok(mut state) = self.inner.lock() {
+            state
+                .steps
+                .entry(step_id.to_string())
+                .or_insert_with(|| StepMetrics::new(step_id))
+                .retries += 1;
+        }
+    }
+
+    /// Record the retries a step has left per error category.
+    pub fn record_retry_budget(&self, step_id: &str, remaining: BTreeMap<String, u32>) {
+        if let Ok(mut state) = self.inner.lock() {
+            state
+                .steps
+                .entry(step_id.to_string())
+                .or_insert_with(|| StepMetrics::new(step_id))
+                .retry_budget = remaining;
+        }
+    }
+
+    /// Record time a step spent waiting for the agent launch rate limit.
+    pub fn record_launch_wait(&self, step_id: &str, wait: Duration) {
+        if let Ok(mut state) = self.inner.lock() {
+            state
+                .steps
+                .entry(step_id.to_string())
+                .or_insert_with(|| StepMetrics::new(step_id))
+                .launch_wait = wait;
+        }
+    }
+
+    /// Record time a story spent in the pending queue before dispatch.
+    pub fn record_queue_wait(&self, step_id: &str, wait: Duration) {
+        self.update_contention(step_id, |contention| contention.queue_wait += wait);
+    }
+
+    /// Record time a queued story waited while every worker slot was taken.
+    pub fn record_semaphore_wait(&self, step_id: &str, wait: Duration) {
+        self.update_contention(step_id, |contention| contention.semaphore_wait += wait);
+    }
+
+    /// Record a dispatch attempt that found the story's files locked.
+    pub fn record_lock_failure(&self, step_id: &str) {
+        self.update_contention(step_id, |contention| contention.lock_failures += 1);
+    }
+
+    /// Record a scheduling cycle in which the story was deferred for a
+    /// conflicting story.
+    pub fn record_conflict_deferral(&self, step_id: &str) {
+        self.update_contention(step_id, |contention| contention.conflict_deferrals += 1);
+    }
+
+    fn update_contention(&self, step_id: &str, update: impl FnOnce(&mut ContentionMetrics)) {
+        if let Ok(mut state) = self.inner.lock() {
+            update(state.contention.entry(step_id.to_string()).or_default());
+        }
+    }
+
+    /// Record what the agent CLI reported when probed.
+    pub fn set_agent_capabilities(&self, capabilities: AgentCapabilities) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.agent = Some(capabilities);
+        }
+    }
+
+    /// What the agent CLI reported when probed, if it was.
+    pub fn agent_capabilities(&self) -> Option<AgentCapabilities> {
+        self.inner.lock().ok().and_then(|state| state.agent.clone())
+    }
+
+    /// Record the agent (and model) a step runs with.
+    pub fn record_step_agent(&self, step_id: &str, agent: impl Into<String>) {
+        if let Ok(mut state) = self.inner.lock() {
+            state
+                .steps
+                .entry(step_id.to_string())
+                .or_insert_with(|| StepMetrics::new(step_id))
+                .agent = Some(agent.into());
+        }
+    }
+
+    /// Record the epic and estimate of each story in the run, so per-epic
+    /// progress counts stories that do not run. Stories that already pass
+    /// count as done.
+    pub fn record_stories(&self, stories: &[PrdUserStory]) {
+        if let Ok(mut state) = self.inner.lock() {
+            for story in stories {
+                if let Some(estimate) = story.estimate {
+                    state.story_estimates.insert(story.id.clone(), estimate);
+                }
+                let Some(ref epic) = story.epic else {
+                    continue;
+                };
+                state.story_epics.insert(story.id.clone(), epic.clone());
+                if story.passes {
+                    state.passed_before.insert(story.id.clone());
+                }
+            }
+        }
+    }
+
+    /// Record a finished agent run.
+    pub fn record_invocation(&self, invocation: AgentInvocation) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.invocations.push(invocation);
+        }
+    }
+
+    /// Totals over the agent runs recorded so far.
+    pub fn invocation_stats(&self) -> InvocationStats {
+        self.inner
+            .lock()
+            .map(|state| InvocationStats::from_invocations(&state.invocations))
+            .unwrap_or_default()
+    }
+
+    /// Record a finished reconciliation.
+    pub fn record_reconciliation(&self, report: ReconciliationReport) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.reconciliations.push(report);
+        }
+    }
+
     /// Record that evidence was captured for a step.
     pub fn record_evidence_step(&self, step_id: impl Into<String>) {
         if let Ok(mut state) = self.inner.lock() {
@@ -258,6 +822,17 @@ impl RunMetricsCollector {
         }
     }
 
+    /// Record that a failed step succeeded after all, e.g. once its
+    /// conflicting merge was resolved, keeping its attempts and duration.
+    pub fn succeed_step(&self, step_id: &str) {
+        if let Ok(mut state) = self.inner.lock() {
+            if let Some(entry) = state.steps.get_mut(step_id) {
+                entry.success = true;
+                entry.error = None;
+            }
+        }
+    }
+
     /// Build a run metrics snapshot.
     pub fn finish(&self) -> RunMetrics {
         if let Ok(state) = self.inner.lock() {
@@ -269,8 +844,13 @@ impl RunMetricsCollector {
             let retries = state
                 .steps
                 .values()
-                .map(|step| step.attempts.saturating_sub(1))
+                .map(|step| step.attempts.saturating_sub(1) + step.retries)
                 .sum();
+            let launch_wait = state.steps.values().map(|step| step.launch_wait).sum();
+            let mut contention = ContentionMetrics::default();
+            for step_contention in state.contention.values() {
+                contention.add(step_contention);
+            }
             let evidence_steps = state.evidence_steps.len() as f64;
             let completeness_percent = if state.expected_steps == 0 {
                 100.0
@@ -282,7 +862,91 @@ impl RunMetricsCollector {
                 .iter()
                 .map(|(id, step)| (id.clone(), step.duration))
                 .collect();
-            let steps = state.steps.values().cloned().collect();
+            let steps = state
+                .steps
+                .values()
+                .map(|step| {
+                    let mut step = step.clone();
+                    if let Some(step_contention) = state.contention.get(&step.step_id) {
+                        step.contention = step_contention.clone();
+                    }
+                    if let Some(epic) = state.story_epics.get(&step.step_id) {
+                        step.epic = Some(epic.clone());
+                    }
+                    if let Some(estimate) = state.story_estimates.get(&step.step_id) {
+                        step.estimate = Some(*estimate);
+                    }
+                    step
+                })
+                .collect();
+            let mut evidence_steps: Vec<String> = state.evidence_steps.iter().cloned().collect();
+            evidence_steps.sort();
+            let mut agents: BTreeMap<String, AgentMetrics> = BTreeMap::new();
+            for step in state.steps.values() {
+                let Some(ref agent) = step.agent else {
+                    continue;
+                };
+                let entry = agents.entry(agent.clone()).or_default();
+                entry.steps += 1;
+                if step.success {
+                    entry.completed += 1;
+                } else {
+                    entry.failures += 1;
+                }
+                entry.attempts += step.attempts;
+                entry.duration += step.duration;
+            }
+            let mut epics: BTreeMap<String, EpicMetrics> = BTreeMap::new();
+            for (story_id, epic) in &state.story_epics {
+                let entry = epics.entry(epic.clone()).or_default();
+                entry.stories += 1;
+                let step = state.steps.get(story_id);
+                if state.passed_before.contains(story_id) || step.is_some_and(|s| s.success) {
+                    entry.completed += 1;
+                } else if step.is_some() {
+                    entry.failures += 1;
+                }
+                entry.duration += step.map(|s| s.duration).unwrap_or_default();
+                entry.cost_cents += state
+                    .invocations
+                    .iter()
+                    .filter(|invocation| &invocation.step_id == story_id)
+                    .filter_map(|invocation| invocation.cost_cents)
+                    .sum::<f64>();
+            }
+            let mut estimates: BTreeMap<String, EstimateMetrics> = BTreeMap::new();
+            for (story_id, estimate) in &state.story_estimates {
+                let Some(step) = state.steps.get(story_id) else {
+                    continue;
+                };
+                let runs: Vec<&AgentInvocation> = state
+                    .invocations
+                    .iter()
+                    .filter(|invocation| &invocation.step_id == story_id)
+                    .collect();
+                let iterations = runs
+                    .iter()
+                    .filter(|invocation| invocation.role == "agent")
+                    .count() as u32;
+                let tokens = runs
+                    .iter()
+                    .filter_map(|invocation| invocation.total_tokens())
+                    .reduce(|a, b| a + b);
+                estimates.insert(
+                    story_id.clone(),
+                    EstimateMetrics {
+                        estimate: *estimate,
+                        success: step.success,
+                        iterations: if iterations > 0 {
+                            iterations
+                        } else {
+                            step.attempts
+                        },
+                        tokens,
+                        duration: step.duration,
+                    },
+                );
+            }
 
             RunMetrics {
                 run_id: state.run_id.clone(),
@@ -295,7 +959,16 @@ impl RunMetricsCollector {
                 steps_completed,
                 failures,
                 retries,
+                launch_wait,
+                contention,
                 completeness_percent,
+                evidence_steps,
+                agent: state.agent.clone(),
+                agents,
+                epics,
+                estimates,
+                invocations: state.invocations.clone(),
+                reconciliations: state.reconciliations.clone(),
                 step_durations,
                 steps,
             }
@@ -311,7 +984,16 @@ impl RunMetricsCollector {
                 steps_completed: 0,
                 failures: 0,
                 retries: 0,
+                launch_wait: Duration::ZERO,
+                contention: ContentionMetrics::default(),
                 completeness_percent: 0.0,
+                evidence_steps: Vec::new(),
+                agent: None,
+                agents: BTreeMap::new(),
+                epics: BTreeMap::new(),
+                estimates: BTreeMap::new(),
+                invocations: Vec::new(),
+                reconciliations: Vec::new(),
                 step_durations: HashMap::new(),
                 steps: Vec::new(),
             }
@@ -361,6 +1043,23 @@ impl RunMetricsStore {
             Err(err) => Err(err),
         }
     }
+
+    /// Load every stored run, skipping files that cannot be parsed.
+    pub fn load_all(&self) -> io::Result<Vec<RunMetrics>> {
+        let mut runs = Vec::new();
+        for entry in std::fs::read_dir(&self.runs_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            if let Ok(contents) = std::fs::read_to_string(&path) {
+                if let Ok(metrics) = serde_json::from_str(&contents) {
+                    runs.push(metrics);
+                }
+            }
+        }
+        Ok(runs)
+    }
 }
 
 impl ExecutionMetrics {
@@ -722,6 +1421,78 @@ pub fn format_metrics(metrics: &ExecutionMetrics) -> String {
     output
 }
 
+/// Format per-epic progress for the end-of-run summary.
+pub fn format_epic_progress(epics: &BTreeMap<String, EpicMetrics>) -> String {
+    let width = epics
+        .keys()
+        .map(|epic| epic.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut output = String::from("Epic progress:\n");
+    for (epic, progress) in epics {
+        output.push_str(&format!(
+            "  {:<width$}  {}/{} done",
+            epic,
+            progress.completed,
+            progress.stories,
+            width = width
+        ));
+        if progress.failures > 0 {
+            output.push_str(&format!(", {} failed", progress.failures));
+        }
+        output.push_str(&format!(", {:.1}s", progress.duration.as_secs_f64()));
+        if progress.cost_cents > 0.0 {
+            output.push_str(&format!(", ${:.2}", progress.cost_cents / 100.0));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Format actual against estimated size of the stories that ran with an
+/// estimate, one line per story.
+pub fn format_estimate_report(estimates: &BTreeMap<String, EstimateMetrics>) -> String {
+    let width = estimates
+        .keys()
+        .map(|id| id.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut output = String::from("Actual vs estimate:\n");
+    for (story_id, actual) in estimates {
+        let mut parts = Vec::new();
+        if let Some(iterations) = actual.estimate.iterations {
+            parts.push(format!(
+                "iterations {} (est. {})",
+                actual.iterations, iterations
+            ));
+        }
+        if let Some(tokens) = actual.estimate.tokens {
+            match actual.tokens {
+                Some(used) => parts.push(format!("tokens {} (est. {})", used, tokens)),
+                None => parts.push(format!("tokens unknown (est. {})", tokens)),
+            }
+        }
+        match actual.estimate.points {
+            Some(points) => parts.push(format!(
+                "{} points in {:.1}s",
+                points,
+                actual.duration.as_secs_f64()
+            )),
+            None => parts.push(format!("{:.1}s", actual.duration.as_secs_f64())),
+        }
+        if !actual.success {
+            parts.push("failed".to_string());
+        }
+        output.push_str(&format!(
+            "  {:<width$}  {}\n",
+            story_id,
+            parts.join(", "),
+            width = width
+        ));
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -902,6 +1673,320 @@ mod tests {
         assert_eq!(metrics.total_stories, 1);
     }
 
+    #[test]
+    fn test_run_metrics_counts_story_retries() {
+        let collector = RunMetricsCollector::new("run-test", 1);
+
+        collector.start_step("US-001");
+        collector.record_retry("US-001");
+        collector.record_retry("US-001");
+        collector.record_retry_budget("US-001", BTreeMap::from([("transient".to_string(), 0)]));
+        collector.complete_step("US-001", true, 3, Duration::from_secs(5), None);
+
+        let metrics = collector.finish();
+        assert_eq!(metrics.steps[0].retries, 2);
+        assert_eq!(metrics.steps[0].retry_budget["transient"], 0);
+        // Two extra iterations plus two story retries
+        assert_eq!(metrics.retries, 4);
+    }
+
+    #[test]
+    fn test_run_metrics_sums_launch_wait() {
+        let collector = RunMetricsCollector::new("run-test", 2);
+
+        collector.start_step("US-001");
+        collector.record_launch_wait("US-001", Duration::from_secs(3));
+        collector.start_step("US-002");
+        collector.record_launch_wait("US-002", Duration::from_secs(4));
+
+        let metrics = collector.finish();
+        assert_eq!(metrics.launch_wait, Duration::from_secs(7));
+
+        // Metrics saved before launch waits were recorded still load
+        let mut json = serde_json::to_value(&metrics).unwrap();
+        json.as_object_mut().unwrap().remove("launch_wait");
+        let loaded: RunMetrics = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.launch_wait, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_run_metrics_records_contention() {
+        let collector = RunMetricsCollector::new("run-test", 2);
+
+        collector.record_conflict_deferral("US-001");
+        collector.record_conflict_deferral("US-001");
+        collector.record_lock_failure("US-001");
+        collector.record_queue_wait("US-001", Duration::from_secs(5));
+        collector.record_semaphore_wait("US-001", Duration::from_secs(2));
+        collector.start_step("US-001");
+        collector.complete_step("US-001", true, 1, Duration::from_secs(30), None);
+        // Never dispatched: counted in the run total but not as an attempted step
+        collector.record_conflict_deferral("US-002");
+
+        let metrics = collector.finish();
+        assert_eq!(metrics.steps_attempted, 1);
+        assert_eq!(
+            metrics.steps[0].contention,
+            ContentionMetrics {
+                queue_wait: Duration::from_secs(5),
+                semaphore_wait: Duration::from_secs(2),
+                lock_failures: 1,
+                conflict_deferrals: 2,
+            }
+        );
+        assert_eq!(metrics.contention.conflict_deferrals, 3);
+        assert_eq!(metrics.contention.queue_wait, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_resumed_collector_continues_run() {
+        let collector = RunMetricsCollector::new("run-test", 2);
+        collector.start_step("US-001");
+        collector.record_evidence_step("US-001");
+        collector.complete_step("US-001", true, 2, Duration::from_secs(5), None);
+        let snapshot = collector.finish();
+
+        let resumed = RunMetricsCollector::resume(snapshot.clone());
+        assert_eq!(resumed.run_id(), "run-test");
+        resumed.start_step("US-002");
+        resumed.record_evidence_step("US-002");
+        resumed.complete_step("US-002", true, 1, Duration::from_secs(3), None);
+
+        let metrics = resumed.finish();
+        assert_eq!(metrics.run_id, "run-test");
+        assert_eq!(metrics.started_at, snapshot.started_at);
+        assert_eq!(metrics.steps_completed, 2);
+        assert_eq!(metrics.retries, 1);
+        assert_eq!(metrics.completeness_percent, 100.0);
+        assert!(metrics.run_duration >= snapshot.run_duration);
+    }
+
+    #[test]
+    fn test_agent_capabilities_recorded() {
+        let collector = RunMetricsCollector::new("run-test", 1);
+        assert_eq!(collector.finish().agent, None);
+        let capabilities = AgentCapabilities {
+            program: "claude".to_string(),
+            version: Some("1.0.0 (Claude Code)".to_string()),
+            flags: Some(vec!["--print".to_string()]),
+        };
+        collector.set_agent_capabilities(capabilities.clone());
+
+        let metrics = collector.finish();
+        assert_eq!(metrics.agent.as_ref(), Some(&capabilities));
+        let json = serde_json::to_string(&metrics).unwrap();
+        let restored: RunMetrics = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.agent, Some(capabilities));
+    }
+
+    #[test]
+    fn test_run_metrics_per_agent() {
+        let collector = RunMetricsCollector::new("run-test", 3);
+        for (story, agent, success) in [
+            ("DOC-1", "claude (claude-haiku-4-5)", true),
+            ("DOC-2", "claude (claude-haiku-4-5)", false),
+            ("CORE-1", "codex", true),
+        ] {
+            collector.start_step(story);
+            collector.record_step_agent(story, agent);
+            collector.complete_step(story, success, 2, Duration::from_secs(10), None);
+        }
+
+        let metrics = collector.finish();
+        assert_eq!(
+            metrics.agents["claude (claude-haiku-4-5)"],
+            AgentMetrics {
+                steps: 2,
+                completed: 1,
+                failures: 1,
+                attempts: 4,
+                duration: Duration::from_secs(20),
+            }
+        );
+        assert_eq!(metrics.agents["codex"].completed, 1);
+
+        let mut json = serde_json::to_value(&metrics).unwrap();
+        json.as_object_mut().unwrap().remove("agents");
+        let loaded: RunMetrics = serde_json::from_value(json).unwrap();
+        assert!(loaded.agents.is_empty());
+    }
+
+    #[test]
+    fn test_run_metrics_per_epic() {
+        let stories: Vec<PrdUserStory> = serde_json::from_str(
+            r#"[
+                {"id": "US-1", "title": "Cart", "priority": 1, "passes": true, "epic": "checkout"},
+                {"id": "US-2", "title": "Pay", "priority": 2, "passes": false, "epic": "checkout"},
+                {"id": "US-3", "title": "Refund", "priority": 3, "passes": false, "epic": "checkout"},
+                {"id": "US-4", "title": "Login", "priority": 4, "passes": false, "epic": "auth"},
+                {"id": "US-5", "title": "Docs", "priority": 5, "passes": false}
+            ]"#,
+        )
+        .unwrap();
+        let collector = RunMetricsCollector::new("run-test", 4);
+        collector.record_stories(&stories);
+        for (story, success) in [("US-2", true), ("US-4", false), ("US-5", true)] {
+            collector.start_step(story);
+            let mut invocation = AgentInvocation::new(story, 1, "agent", "claude");
+            invocation.cost_cents = Some(12.5);
+            collector.record_invocation(invocation);
+            collector.complete_step(story, success, 1, Duration::from_secs(30), None);
+        }
+
+        let metrics = collector.finish();
+        assert_eq!(
+            metrics.epics["checkout"],
+            EpicMetrics {
+                stories: 3,
+                completed: 2,
+                failures: 0,
+                duration: Duration::from_secs(30),
+                cost_cents: 12.5,
+            }
+        );
+        assert_eq!(
+            (
+                metrics.epics["auth"].completed,
+                metrics.epics["auth"].failures
+            ),
+            (0, 1)
+        );
+        assert_eq!(metrics.epics.len(), 2);
+        let step = metrics.steps.iter().find(|s| s.step_id == "US-2").unwrap();
+        assert_eq!(step.epic.as_deref(), Some("checkout"));
+
+        let summary = format_epic_progress(&metrics.epics);
+        assert!(summary.contains("  auth      0/1 done, 1 failed, 30.0s, $0.12\n"));
+        assert!(summary.contains("  checkout  2/3 done, 30.0s, $0.12\n"));
+
+        // A resumed run keeps the epics of the steps run before the pause
+        let resumed = RunMetricsCollector::resume(metrics).finish();
+        assert_eq!(resumed.epics["auth"].failures, 1);
+    }
+
+    #[test]
+    fn test_run_metrics_actual_vs_estimate() {
+        let stories: Vec<PrdUserStory> = serde_json::from_str(
+            r#"[
+                {"id": "US-1", "title": "Cart", "priority": 1, "passes": false,
+                 "estimate": {"points": 3, "tokens": 40000, "iterations": 1}},
+                {"id": "US-2", "title": "Pay", "priority": 2, "passes": false,
+                 "estimate": {"iterations": 2}},
+                {"id": "US-3", "title": "Refund", "priority": 3, "passes": false,
+                 "estimate": {"points": 5}},
+                {"id": "US-4", "title": "Docs", "priority": 4, "passes": false}
+            ]"#,
+        )
+        .unwrap();
+        let collector = RunMetricsCollector::new("run-test", 4);
+        collector.record_stories(&stories);
+        for (story, iterations, success) in
+            [("US-1", 2, true), ("US-2", 1, false), ("US-4", 1, true)]
+        {
+            collector.start_step(story);
+            for iteration in 1..=iterations {
+                let mut invocation = AgentInvocation::new(story, iteration, "agent", "claude");
+                invocation.input_tokens = Some(15_000);
+                invocation.output_tokens = Some(5_000);
+                collector.record_invocation(invocation);
+            }
+            collector.complete_step(story, success, iterations, Duration::from_secs(45), None);
+        }
+
+        let metrics = collector.finish();
+        assert_eq!(
+            metrics.estimates.len(),
+            2,
+            "only stories that ran with an estimate"
+        );
+        assert_eq!(
+            metrics.estimates["US-1"],
+            EstimateMetrics {
+                estimate: stories[0].estimate.unwrap(),
+                success: true,
+                iterations: 2,
+                tokens: Some(40_000),
+                duration: Duration::from_secs(45),
+            }
+        );
+
+        let report = format_estimate_report(&metrics.estimates);
+        assert!(report.contains(
+            "  US-1  iterations 2 (est. 1), tokens 40000 (est. 40000), 3 points in 45.0s\n"
+        ));
+        assert!(report.contains("  US-2  iterations 1 (est. 2), 45.0s, failed\n"));
+
+        // A resumed run keeps the estimates of the steps run before the pause
+        let resumed = RunMetricsCollector::resume(metrics).finish();
+        assert_eq!(resumed.estimates["US-2"].estimate.iterations, Some(2));
+    }
+
+    #[test]
+    fn test_invocation_telemetry() {
+        let collector = RunMetricsCollector::new("run-test", 1);
+        for (iteration, trimmed, outcome) in [
+            (1, false, Some(GateOutcome::Failed)),
+            (2, true, Some(GateOutcome::Failed)),
+            (3, true, None),
+            (4, false, Some(GateOutcome::Passed)),
+        ] {
+            let mut invocation = AgentInvocation::new("US-1", iteration, "agent", "claude");
+            invocation.duration = Duration::from_secs(10);
+            invocation.input_tokens = Some(1_000);
+            invocation.output_tokens = Some(200);
+            invocation.cost_cents = Some(0.6);
+            invocation.context_trimmed = trimmed;
+            invocation.gate_outcome = outcome;
+            if outcome.is_none() {
+                invocation.status = InvocationStatus::TimedOut;
+            }
+            collector.record_invocation(invocation);
+        }
+
+        let metrics = RunMetricsCollector::resume(collector.finish()).finish();
+        assert_eq!(metrics.invocations.len(), 4);
+        let trimmed = InvocationStats::from_invocations(
+            metrics.invocations.iter().filter(|i| i.context_trimmed),
+        );
+        let full = InvocationStats::from_invocations(
+            metrics.invocations.iter().filter(|i| !i.context_trimmed),
+        );
+        assert_eq!(trimmed.failure_rate(), 1.0);
+        assert_eq!(full.failure_rate(), 0.5);
+        assert_eq!(full.gates_passed, 1);
+        assert_eq!(full.tokens, 2_400);
+        assert_eq!(trimmed.agent_failures, 1);
+        assert_eq!(trimmed.mean_duration(), Duration::from_secs(10));
+
+        let json = serde_json::to_string(&metrics.invocations[0]).unwrap();
+        assert!(json.contains("\"status\":\"succeeded\""));
+        assert!(json.contains("\"gate_outcome\":\"failed\""));
+    }
+
+    #[test]
+    fn test_reconciliation_reports() {
+        let collector = RunMetricsCollector::new("run-test", 2);
+        collector.record_reconciliation(ReconciliationReport {
+            stories: vec!["US-1".to_string(), "US-2".to_string()],
+            issues: vec!["Cart defined more than once: src/cart.rs:3, src/lib.rs:9".to_string()],
+            files: vec!["src/cart.rs".to_string(), "src/lib.rs".to_string()],
+            affected_stories: vec!["US-2".to_string()],
+            action: ReconciliationAction::SequentialRetry,
+            retries: BTreeMap::from([("US-2".to_string(), true)]),
+            retry_iterations: 2,
+            duration: Duration::from_secs(30),
+            ..Default::default()
+        });
+
+        let metrics = RunMetricsCollector::resume(collector.finish()).finish();
+        assert_eq!(metrics.reconciliations.len(), 1);
+        assert!(metrics.reconciliations[0].is_clean());
+        let json = serde_json::to_string(&metrics).unwrap();
+        assert!(json.contains("\"action\":\"sequential_retry\""));
+        let restored: RunMetrics = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.reconciliations, metrics.reconciliations);
+    }
+
     #[test]
     fn test_format_metrics() {
         let mut metrics = ExecutionMetrics::default();
@@ -0,0 +1,569 @@
+//! Timeline of a run (`ralph timeline`).
+//!
+//! Lays the stories of a recorded run out on a shared time axis: when each
+//! story sat in the pending queue, when it waited on another story's file
+//! locks, and when it ran. Periods in which no story ran at all are marked
+//! as gaps, so it is easy to see where a parallel run stopped being parallel.
+//!
+//! Queue and run periods come from the run's metrics; lock waits come from
+//! its scheduler trace, when one was written.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime};
+
+use crate::metrics::RunMetrics;
+use crate::parallel::trace::{SchedulerDecision, TraceEntry};
+
+/// Periods without a running story shorter than this are not reported as gaps.
+const MIN_GAP: Duration = Duration::from_secs(1);
+
+/// Width of an SVG timeline's chart area in pixels.
+const SVG_CHART_WIDTH: usize = 800;
+/// Height of an SVG timeline row in pixels.
+const SVG_ROW_HEIGHT: usize = 22;
+
+/// A period of a run, as offsets from its start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl Span {
+    /// Length of the period.
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// One story on the timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineRow {
+    /// Story ID
+    pub step_id: String,
+    /// Time in the pending queue before the story was dispatched
+    pub queued: Option<Span>,
+    /// Periods spent blocked on other stories' file locks
+    pub lock_waits: Vec<Span>,
+    /// Time the story ran
+    pub running: Span,
+    /// Whether the story succeeded
+    pub success: bool,
+}
+
+/// The stories of a run laid out on a shared time axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    /// Run ID
+    pub run_id: String,
+    /// Length of the time axis
+    pub duration: Duration,
+    /// Stories in the order they started running
+    pub rows: Vec<TimelineRow>,
+    /// Periods in which no story was running
+    pub gaps: Vec<Span>,
+}
+
+impl Timeline {
+    /// Builds the timeline of `metrics`, taking lock waits from the run's
+    /// scheduler `trace` (empty if none was written).
+    pub fn build(metrics: &RunMetrics, trace: &[TraceEntry]) -> Self {
+        let offset = |time: SystemTime| {
+            time.duration_since(metrics.started_at)
+                .unwrap_or(Duration::ZERO)
+        };
+        let lock_waits = lock_wait_spans(trace, &offset);
+
+        let mut rows: Vec<TimelineRow> = metrics
+            .steps
+            .iter()
+            .map(|step| {
+                let start = offset(step.started_at);
+                let end = offset(step.completed_at).max(start);
+                let queue_wait = step.contention.queue_wait;
+                let queued = (!queue_wait.is_zero()).then(|| Span {
+                    start: start.saturating_sub(queue_wait),
+                    end: start,
+                });
+                TimelineRow {
+                    step_id: step.step_id.clone(),
+                    queued,
+                    lock_waits: lock_waits.get(&step.step_id).cloned().unwrap_or_default(),
+                    running: Span { start, end },
+                    success: step.success,
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            a.running
+                .start
+                .cmp(&b.running.start)
+                .then_with(|| a.step_id.cmp(&b.step_id))
+        });
+
+        let duration = rows
+            .iter()
+            .flat_map(|row| {
+                std::iter::once(row.running.end).chain(row.lock_waits.iter().map(|span| span.end))
+            })
+            .fold(metrics.run_duration, Duration::max);
+        let gaps = idle_spans(&rows, duration);
+
+        Self {
+            run_id: metrics.run_id.clone(),
+            duration,
+            rows,
+            gaps,
+        }
+    }
+
+    /// Most stories running at the same time.
+    pub fn peak_concurrency(&self) -> usize {
+        let mut edges: Vec<(Duration, i32)> = self
+            .rows
+            .iter()
+            .filter(|row| !row.running.duration().is_zero())
+            .flat_map(|row| [(row.running.start, 1), (row.running.end, -1)])
+            .collect();
+        // Ends sort before starts at the same instant
+        edges.sort();
+        let mut running = 0;
+        let mut peak = 0;
+        for (_, change) in edges {
+            running += change;
+            peak = peak.max(running);
+        }
+        peak as usize
+    }
+
+    /// Average number of stories running over the run.
+    pub fn average_concurrency(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 0.0;
+        }
+        let busy: Duration = self.rows.iter().map(|row| row.running.duration()).sum();
+        busy.as_secs_f64() / self.duration.as_secs_f64()
+    }
+
+    /// Total time no story was running.
+    pub fn idle(&self) -> Duration {
+        self.gaps.iter().map(Span::duration).sum()
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "Run {}: {:.1}s, peak {} concurrent, average {:.1}, idle {:.1}s in {} gap{}",
+            self.run_id,
+            self.duration.as_secs_f64(),
+            self.peak_concurrency(),
+            self.average_concurrency(),
+            self.idle().as_secs_f64(),
+            self.gaps.len(),
+            if self.gaps.len() == 1 { "" } else { "s" }
+        )
+    }
+
+    /// Columns a span covers in a chart `width` columns wide.
+    fn columns(&self, span: &Span, width: usize) -> std::ops::Range<usize> {
+        if self.duration.is_zero() || width == 0 {
+            return 0..0;
+        }
+        let total = self.duration.as_secs_f64();
+        let column = |offset: Duration| offset.as_secs_f64() / total * width as f64;
+        let start = (column(span.start).floor() as usize).min(width - 1);
+        let end = (column(span.end).ceil() as usize).clamp(start + 1, width);
+        start..end
+    }
+
+    /// Renders the timeline as text, with a chart `width` columns wide.
+    pub fn render_text(&self, width: usize) -> String {
+        let width = width.max(10);
+        let label_width = self
+            .rows
+            .iter()
+            .map(|row| row.step_id.chars().count())
+            .chain(std::iter::once("idle".len()))
+            .max()
+            .unwrap_or(0);
+
+        let mut output = format!("{}\n", self.summary());
+        for row in &self.rows {
+            let mut cells = vec![' '; width];
+            if let Some(ref span) = row.queued {
+                cells[self.columns(span, width)].fill('░');
+            }
+            for span in &row.lock_waits {
+                cells[self.columns(span, width)].fill('▒');
+            }
+            let bar = if row.success { '█' } else { '▓' };
+            cells[self.columns(&row.running, width)].fill(bar);
+            let _ = writeln!(
+                output,
+                "{:<label_width$} |{}| {:.1}s{}",
+                row.step_id,
+                cells.into_iter().collect::<String>(),
+                row.running.duration().as_secs_f64(),
+                if row.success { "" } else { " failed" },
+                label_width = label_width
+            );
+        }
+
+        let mut idle = vec![' '; width];
+        for gap in &self.gaps {
+            idle[self.columns(gap, width)].fill('·');
+        }
+        let _ = writeln!(
+            output,
+            "{:<label_width$} |{}|",
+            "idle",
+            idle.into_iter().collect::<String>(),
+            label_width = label_width
+        );
+        let end = format!("{:.1}s", self.duration.as_secs_f64());
+        let _ = writeln!(
+            output,
+            "{:<label_width$}  0s{:>pad$}",
+            "",
+            end,
+            label_width = label_width,
+            pad = width.saturating_sub(2)
+        );
+        output.push_str(
+            "█ running  ▓ failed  ░ queued  ▒ waiting on file locks  · no story running\n",
+        );
+        output
+    }
+
+    /// Renders the timeline as an SVG image.
+    pub fn render_svg(&self) -> String {
+        let label_width = self
+            .rows
+            .iter()
+            .map(|row| row.step_id.chars().count())
+            .max()
+            .unwrap_or(0)
+            * 8
+            + 16;
+        let chart_top = 30;
+        let chart_height = self.rows.len().max(1) * SVG_ROW_HEIGHT;
+        let width = label_width + SVG_CHART_WIDTH + 20;
+        let height = chart_top + chart_height + 60;
+        let x = |offset: Duration| {
+            if self.duration.is_zero() {
+                label_width as f64
+            } else {
+                label_width as f64
+                    + offset.as_secs_f64() / self.duration.as_secs_f64() * SVG_CHART_WIDTH as f64
+            }
+        };
+        let rect = |output: &mut String,
+                    span: &Span,
+                    y: usize,
+                    height: usize,
+                    class: &str,
+                    title: &str| {
+            let _ = writeln!(
+                output,
+                r#"  <rect class="{}" x="{:.1}" y="{}" width="{:.1}" height="{}"><title>{}</title></rect>"#,
+                class,
+                x(span.start),
+                y,
+                (x(span.end) - x(span.start)).max(1.0),
+                height,
+                escape_xml(title)
+            );
+        };
+
+        let mut output = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="12">"#,
+            width, height
+        );
+        output.push('\n');
+        output.push_str(
+            "  <style>.running{fill:#43a047}.failed{fill:#e53935}.queued{fill:#cfd8dc}\
+             .lock{fill:#fb8c00}.gap{fill:#e53935;fill-opacity:0.12}</style>\n",
+        );
+        let _ = writeln!(
+            output,
+            r#"  <text x="4" y="18">{}</text>"#,
+            escape_xml(&self.summary())
+        );
+        for gap in &self.gaps {
+            let title = format!("no story running for {:.1}s", gap.duration().as_secs_f64());
+            rect(&mut output, gap, chart_top, chart_height, "gap", &title);
+        }
+        for (index, row) in self.rows.iter().enumerate() {
+            let y = chart_top + index * SVG_ROW_HEIGHT;
+            let _ = writeln!(
+                output,
+                r#"  <text x="4" y="{}">{}</text>"#,
+                y + SVG_ROW_HEIGHT - 7,
+                escape_xml(&row.step_id)
+            );
+            let bar_y = y + 3;
+            let bar_height = SVG_ROW_HEIGHT - 6;
+            if let Some(ref span) = row.queued {
+                let title = format!(
+                    "{} queued for {:.1}s",
+                    row.step_id,
+                    span.duration().as_secs_f64()
+                );
+                rect(&mut output, span, bar_y, bar_height, "queued", &title);
+            }
+            for span in &row.lock_waits {
+                let title = format!(
+                    "{} waited {:.1}s on file locks",
+                    row.step_id,
+                    span.duration().as_secs_f64()
+                );
+                rect(&mut output, span, bar_y, bar_height, "lock", &title);
+            }
+            let (class, outcome) = if row.success {
+                ("running", "ran")
+            } else {
+                ("failed", "failed after")
+            };
+            let title = format!(
+                "{} {} {:.1}s",
+                row.step_id,
+                outcome,
+                row.running.duration().as_secs_f64()
+            );
+            rect(&mut output, &row.running, bar_y, bar_height, class, &title);
+        }
+
+        let axis_y = chart_top + chart_height;
+        let _ = writeln!(
+            output,
+            r##"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#90a4ae"/>"##,
+            label_width,
+            axis_y,
+            label_width + SVG_CHART_WIDTH,
+            axis_y
+        );
+        for tick in 0..=4 {
+            let offset = self.duration.mul_f64(tick as f64 / 4.0);
+            let anchor = match tick {
+                0 => "start",
+                4 => "end",
+                _ => "middle",
+            };
+            let _ = writeln!(
+                output,
+                r#"  <text x="{:.1}" y="{}" text-anchor="{}">{:.1}s</text>"#,
+                x(offset),
+                axis_y + 16,
+                anchor,
+                offset.as_secs_f64()
+            );
+        }
+        let legend_y = axis_y + 40;
+        let mut legend_x = label_width;
+        for (class, label) in [
+            ("running", "running"),
+            ("failed", "failed"),
+            ("queued", "queued"),
+            ("lock", "waiting on file locks"),
+            ("gap", "no story running"),
+        ] {
+            let _ = writeln!(
+                output,
+                r#"  <rect class="{}" x="{}" y="{}" width="12" height="12"/><text x="{}" y="{}">{}</text>"#,
+                class,
+                legend_x,
+                legend_y - 10,
+                legend_x + 16,
+                legend_y,
+                label
+            );
+            legend_x += 16 + label.len() * 8 + 16;
+        }
+        output.push_str("</svg>\n");
+        output
+    }
+}
+
+/// Periods each story spent blocked on file locks: from the first
+/// `lock_blocked` decision until it was dispatched or gave up.
+fn lock_wait_spans(
+    trace: &[TraceEntry],
+    offset: &impl Fn(SystemTime) -> Duration,
+) -> HashMap<String, Vec<Span>> {
+    let mut open: HashMap<&str, Duration> = HashMap::new();
+    let mut spans: HashMap<String, Vec<Span>> = HashMap::new();
+    for entry in trace {
+        let Some(ref story_id) = entry.story_id else {
+            continue;
+        };
+        let at = offset(SystemTime::from(entry.timestamp));
+        match entry.decision {
+            SchedulerDecision::LockBlocked { .. } => {
+                open.entry(story_id).or_insert(at);
+            }
+            SchedulerDecision::Dispatched { .. } | SchedulerDecision::LockWaitFailed { .. } => {
+                if let Some(start) = open.remove(story_id.as_str()) {
+                    spans
+                        .entry(story_id.clone())
+                        .or_default()
+                        .push(Span { start, end: at });
+                }
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Periods of at least [`MIN_GAP`] within `duration` in which no row was running.
+fn idle_spans(rows: &[TimelineRow], duration: Duration) -> Vec<Span> {
+    let mut running: Vec<Span> = rows.iter().map(|row| row.running).collect();
+    running.sort_by_key(|span| span.start);
+    let mut gaps = Vec::new();
+    let mut covered = Duration::ZERO;
+    for span in running.into_iter().chain(std::iter::once(Span {
+        start: duration,
+        end: duration,
+    })) {
+        if span.start > covered && span.start - covered >= MIN_GAP {
+            gaps.push(Span {
+                start: covered,
+                end: span.start,
+            });
+        }
+        covered = covered.max(span.end);
+    }
+    gaps
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{RunMetricsCollector, StepMetrics};
+    use chrono::{DateTime, Utc};
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    /// A run of 30s: US-001 runs 0-10s, US-002 waits 4s on US-001's locks
+    /// and runs 10-20s and fails, and US-003 runs 25-30s after a gap.
+    fn sample() -> (RunMetrics, Vec<TraceEntry>) {
+        let mut metrics = RunMetricsCollector::new("run-1", 3).finish();
+        let start = metrics.started_at;
+        metrics.run_duration = secs(30);
+        for (id, from, to, success) in [
+            ("US-001", 0, 10, true),
+            ("US-002", 10, 20, false),
+            ("US-003", 25, 30, true),
+        ] {
+            let mut step = StepMetrics::new(id);
+            step.started_at = start + secs(from);
+            step.completed_at = start + secs(to);
+            step.success = success;
+            metrics.steps.push(step);
+        }
+        metrics.steps[1].contention.queue_wait = secs(8);
+
+        let entry = |at: u64, decision: SchedulerDecision| TraceEntry {
+            timestamp: DateTime::<Utc>::from(start + secs(at)),
+            cycle: at,
+            story_id: Some("US-002".to_string()),
+            decision,
+        };
+        let blocked = || SchedulerDecision::LockBlocked {
+            file: "src/lib.rs".to_string(),
+            holder: "US-001".to_string(),
+            escalation: None,
+        };
+        let trace = vec![
+            entry(6, blocked()),
+            entry(8, blocked()),
+            entry(
+                10,
+                SchedulerDecision::Dispatched {
+                    in_flight: 1,
+                    waited_ms: 8_000,
+                },
+            ),
+        ];
+        (metrics, trace)
+    }
+
+    #[test]
+    fn test_timeline_from_metrics_and_trace() {
+        let (metrics, trace) = sample();
+        let timeline = Timeline::build(&metrics, &trace);
+
+        assert_eq!(timeline.duration, secs(30));
+        let ids: Vec<&str> = timeline
+            .rows
+            .iter()
+            .map(|row| row.step_id.as_str())
+            .collect();
+        assert_eq!(ids, ["US-001", "US-002", "US-003"]);
+        let second = &timeline.rows[1];
+        assert_eq!(
+            second.queued,
+            Some(Span {
+                start: secs(2),
+                end: secs(10)
+            })
+        );
+        assert_eq!(
+            second.lock_waits,
+            [Span {
+                start: secs(6),
+                end: secs(10)
+            }]
+        );
+        assert!(!second.success);
+        assert_eq!(
+            timeline.gaps,
+            [Span {
+                start: secs(20),
+                end: secs(25)
+            }]
+        );
+        assert_eq!(timeline.peak_concurrency(), 1);
+        assert!((timeline.average_concurrency() - 25.0 / 30.0).abs() < 1e-9);
+
+        // Without a trace there are no lock waits
+        let timeline = Timeline::build(&metrics, &[]);
+        assert!(timeline.rows[1].lock_waits.is_empty());
+    }
+
+    #[test]
+    fn test_timeline_renders_text_and_svg() {
+        let (metrics, trace) = sample();
+        let timeline = Timeline::build(&metrics, &trace);
+
+        let text = timeline.render_text(30);
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("Run run-1: 30.0s, peak 1 concurrent"));
+        assert!(lines[0].ends_with("idle 5.0s in 1 gap"));
+        assert_eq!(lines[1], format!("US-001 |{:<30}| 10.0s", "█".repeat(10)));
+        assert_eq!(
+            lines[2],
+            format!("US-002 |  ░░░░▒▒▒▒{:<20}| 10.0s failed", "▓".repeat(10))
+        );
+        assert_eq!(
+            lines[4],
+            format!("idle   |{:<30}|", format!("{:>25}", "·".repeat(5)))
+        );
+
+        let svg = timeline.render_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("<title>US-002 waited 4.0s on file locks</title>"));
+        assert!(svg.contains("<title>US-002 failed after 10.0s</title>"));
+        assert!(svg.contains("<title>no story running for 5.0s</title>"));
+    }
+}
@@ -0,0 +1,79 @@
+//! Terminal bell rung when a run fails (`--bell`, `RALPH_BELL`).
+
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+/// How long a visual bell keeps the screen inverted.
+const VISUAL_BELL_DURATION: Duration = Duration::from_millis(150);
+
+/// How the terminal is alerted when a run fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BellMode {
+    /// No alert.
+    #[default]
+    Off,
+    /// The BEL character, which the terminal plays as a sound.
+    Audible,
+    /// Briefly inverts the screen.
+    Visual,
+    /// Both a sound and a flash.
+    Both,
+}
+
+impl BellMode {
+    const ALL: [Self; 4] = [Self::Off, Self::Audible, Self::Visual, Self::Both];
+
+    /// Label used on the command line and in `RALPH_BELL`.
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Audible => "audible",
+            Self::Visual => "visual",
+            Self::Both => "both",
+        }
+    }
+
+    /// Rings the bell on stderr, if it is a terminal.
+    pub fn ring(self) {
+        let mut stderr = io::stderr();
+        if self == Self::Off || !stderr.is_terminal() {
+            return;
+        }
+        if matches!(self, Self::Audible | Self::Both) {
+            let _ = stderr.write_all(b"\x07");
+        }
+        if matches!(self, Self::Visual | Self::Both) {
+            // Reverse video on, then off again.
+            let _ = stderr.write_all(b"\x1b[?5h");
+            let _ = stderr.flush();
+            thread::sleep(VISUAL_BELL_DURATION);
+            let _ = stderr.write_all(b"\x1b[?5l");
+        }
+        let _ = stderr.flush();
+    }
+}
+
+impl fmt::Display for BellMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
+impl FromStr for BellMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_label() == value.trim())
+            .ok_or_else(|| {
+                format!(
+                    "unknown bell mode '{}' (expected one of: off, audible, visual, both)",
+                    value.trim()
+                )
+            })
+    }
+}
@@ -4,7 +4,9 @@
 //! actions and status changes to users. Each notification variant includes
 //! relevant context data for display purposes.
 
+pub mod bell;
 mod renderer;
+pub mod webhook;
 
 pub use renderer::NotificationRenderer;
 
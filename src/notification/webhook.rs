@@ -0,0 +1,573 @@
+//! Webhook notifications for unattended runs.
+//!
+//! `.ralph/webhooks.toml` lists the webhooks a run reports to, so a run left
+//! alone posts into a team's Slack or Discord channel:
+//!
+//! ```toml
+//! [[webhook]]
+//! url_env = "SLACK_WEBHOOK_URL"   # or `url = "https://hooks.slack.com/..."`
+//! format = "slack"
+//!
+//! [[webhook]]
+//! url = "https://discord.com/api/webhooks/..."
+//! format = "discord"
+//! events = ["story_failed", "circuit_breaker_triggered", "run_finished"]
+//! ```
+//!
+//! Webhooks fire on run start, story failure, circuit breaker trips and run
+//! completion; `events` limits a webhook to some of them. A story that fails
+//! again after being re-run is reported once. Slack and Discord
+//! get a message; the `json` format posts the [`WebhookEvent`] itself with the
+//! message as `text`. Deliveries are best effort: a failed post is reported
+//! as a warning and never fails the run.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::runner::RunResult;
+use crate::ui::parallel_events::ParallelUIEvent;
+
+/// File, relative to the project root, listing the webhooks.
+pub const WEBHOOKS_CONFIG_FILE: &str = ".ralph/webhooks.toml";
+
+/// How long a single delivery may take.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Capacity of the channel [`WebhookNotifier::tap`] reads events from.
+const EVENT_CHANNEL_CAPACITY: usize = 100;
+
+/// Errors from loading the webhook settings.
+#[derive(Debug, Error)]
+pub enum WebhookConfigError {
+    /// The settings file could not be read
+    #[error("Failed to read {path}: {message}")]
+    Read { path: PathBuf, message: String },
+    /// The settings file is not valid
+    #[error("Invalid webhook settings in {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+}
+
+/// Payload shape a webhook expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{"text": ...}` for Slack incoming webhooks
+    Slack,
+    /// `{"content": ...}` for Discord webhooks
+    Discord,
+    /// The event as JSON, with the message as `text`
+    #[default]
+    Json,
+}
+
+/// Events a webhook can be limited to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    RunStarted,
+    StoryFailed,
+    CircuitBreakerTriggered,
+    RunFinished,
+}
+
+/// One `[[webhook]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSettings {
+    /// URL posted to
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Environment variable holding the URL, to keep it out of the repository
+    #[serde(default)]
+    pub url_env: Option<String>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Events posted; empty posts all of them
+    #[serde(default)]
+    pub events: Vec<WebhookEventKind>,
+}
+
+impl WebhookSettings {
+    /// The URL to post to, if it is set.
+    fn resolve_url(&self) -> Option<String> {
+        match self.url_env {
+            Some(ref var) => std::env::var(var).ok().filter(|url| !url.is_empty()),
+            None => self.url.clone(),
+        }
+    }
+
+    fn wants(&self, kind: WebhookEventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+/// The webhook settings of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhooksConfig {
+    #[serde(default, rename = "webhook")]
+    pub webhooks: Vec<WebhookSettings>,
+}
+
+impl WebhooksConfig {
+    /// Load `.ralph/webhooks.toml` under `project_root`; a missing file
+    /// configures no webhooks.
+    pub fn load(project_root: &Path) -> Result<Self, WebhookConfigError> {
+        let path = project_root.join(WEBHOOKS_CONFIG_FILE);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(WebhookConfigError::Read {
+                    path,
+                    message: e.to_string(),
+                })
+            }
+        };
+        toml::from_str(&content).map_err(|e| WebhookConfigError::Parse {
+            path,
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Something worth telling a team channel about.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    RunStarted {
+        project: String,
+        run_id: String,
+        total_stories: usize,
+        parallel: bool,
+    },
+    StoryFailed {
+        project: String,
+        story_id: String,
+        error: String,
+    },
+    CircuitBreakerTriggered {
+        project: String,
+        failures: u32,
+        threshold: u32,
+    },
+    RunFinished {
+        project: String,
+        run_id: String,
+        all_passed: bool,
+        stories_passed: usize,
+        total_stories: usize,
+        duration_ms: u64,
+        cost_cents: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+impl WebhookEvent {
+    /// The event reporting `result` of run `run_id`.
+    pub fn run_finished(
+        project: impl Into<String>,
+        run_id: impl Into<String>,
+        result: &RunResult,
+        duration: Duration,
+        cost_cents: f64,
+    ) -> Self {
+        Self::RunFinished {
+            project: project.into(),
+            run_id: run_id.into(),
+            all_passed: result.all_passed,
+            stories_passed: result.stories_passed,
+            total_stories: result.total_stories,
+            duration_ms: duration.as_millis() as u64,
+            cost_cents,
+            error: result.error.clone(),
+        }
+    }
+
+    pub fn kind(&self) -> WebhookEventKind {
+        match self {
+            Self::RunStarted { .. } => WebhookEventKind::RunStarted,
+            Self::StoryFailed { .. } => WebhookEventKind::StoryFailed,
+            Self::CircuitBreakerTriggered { .. } => WebhookEventKind::CircuitBreakerTriggered,
+            Self::RunFinished { .. } => WebhookEventKind::RunFinished,
+        }
+    }
+
+    /// One-line message for a chat channel.
+    pub fn message(&self) -> String {
+        match self {
+            Self::RunStarted {
+                project,
+                total_stories,
+                parallel,
+                ..
+            } => format!(
+                "Ralph started {} ({} stories{})",
+                project,
+                total_stories,
+                if *parallel { ", parallel" } else { "" }
+            ),
+            Self::StoryFailed {
+                project,
+                story_id,
+                error,
+            } => format!(
+                "{}: story {} failed: {}",
+                project,
+                story_id,
+                error.lines().next().unwrap_or_default()
+            ),
+            Self::CircuitBreakerTriggered {
+                project,
+                failures,
+                threshold,
+            } => format!(
+                "{}: circuit breaker tripped after {} failures (threshold {}); run paused",
+                project, failures, threshold
+            ),
+            Self::RunFinished {
+                project,
+                all_passed,
+                stories_passed,
+                total_stories,
+                duration_ms,
+                cost_cents,
+                error,
+                ..
+            } => {
+                let mut message = format!(
+                    "Ralph {} {}: {}/{} stories passed in {}, ${:.2}",
+                    if *all_passed { "finished" } else { "stopped" },
+                    project,
+                    stories_passed,
+                    total_stories,
+                    format_duration(*duration_ms),
+                    cost_cents / 100.0
+                );
+                if let Some(error) = error.as_deref().and_then(|error| error.lines().next()) {
+                    message.push_str(&format!(" ({})", error));
+                }
+                message
+            }
+        }
+    }
+
+    /// Request body for a webhook of `format`.
+    pub fn payload(&self, format: WebhookFormat) -> serde_json::Value {
+        match format {
+            WebhookFormat::Slack => serde_json::json!({ "text": self.message() }),
+            WebhookFormat::Discord => serde_json::json!({ "content": self.message() }),
+            WebhookFormat::Json => {
+                let mut payload = serde_json::to_value(self).unwrap_or_default();
+                if let Some(object) = payload.as_object_mut() {
+                    object.insert("text".to_string(), self.message().into());
+                }
+                payload
+            }
+        }
+    }
+}
+
+fn format_duration(duration_ms: u64) -> String {
+    let seconds = duration_ms / 1000;
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+/// Posts events to the configured webhooks.
+///
+/// Cloning is cheap; clones share the deliveries still in flight.
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    project: String,
+    webhooks: Arc<Vec<(String, WebhookSettings)>>,
+    client: reqwest::Client,
+    deliveries: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// Stories whose failure was reported
+    failed_stories: Arc<Mutex<HashSet<String>>>,
+}
+
+impl WebhookNotifier {
+    /// A notifier for the webhooks in `config` that have a URL, or `None`
+    /// if there are none.
+    pub fn new(project: impl Into<String>, config: WebhooksConfig) -> Option<Self> {
+        let webhooks: Vec<_> = config
+            .webhooks
+            .into_iter()
+            .filter_map(|settings| match settings.resolve_url() {
+                Some(url) => Some((url, settings)),
+                None => {
+                    eprintln!(
+                        "Warning: Skipping webhook without a URL{}",
+                        settings
+                            .url_env
+                            .as_deref()
+                            .map(|var| format!(" (${} is not set)", var))
+                            .unwrap_or_default()
+                    );
+                    None
+                }
+            })
+            .collect();
+        if webhooks.is_empty() {
+            return None;
+        }
+        Some(Self {
+            project: project.into(),
+            webhooks: Arc::new(webhooks),
+            client: reqwest::Client::new(),
+            deliveries: Arc::new(Mutex::new(Vec::new())),
+            failed_stories: Arc::new(Mutex::new(HashSet::new())),
+        })
+    }
+
+    /// Project name the events report.
+    pub fn project(&self) -> &str {
+        &self.project
+    }
+
+    /// Post `event` to every webhook that wants it, in the background.
+    pub fn notify(&self, event: WebhookEvent) {
+        if let WebhookEvent::StoryFailed { ref story_id, .. } = event {
+            let mut failed_stories = self
+                .failed_stories
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if !failed_stories.insert(story_id.clone()) {
+                return;
+            }
+        }
+        let kind = event.kind();
+        let mut deliveries = self
+            .deliveries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        deliveries.retain(|delivery| !delivery.is_finished());
+        for (url, settings) in self.webhooks.iter().filter(|(_, s)| s.wants(kind)) {
+            let request = self
+                .client
+                .post(url)
+                .timeout(DELIVERY_TIMEOUT)
+                .json(&event.payload(settings.format));
+            deliveries.push(tokio::spawn(async move {
+                match request.send().await.and_then(|r| r.error_for_status()) {
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Warning: Webhook delivery failed: {}", e),
+                }
+            }));
+        }
+    }
+
+    /// Wait for the deliveries still in flight.
+    pub async fn flush(&self) {
+        let deliveries = std::mem::take(
+            &mut *self
+                .deliveries
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        for delivery in deliveries {
+            let _ = delivery.await;
+        }
+    }
+
+    /// A sender that notifies about the failed stories and circuit breaker
+    /// trips among the UI events sent to it, then passes every event on to
+    /// `forward`.
+    pub fn tap(
+        &self,
+        forward: Option<mpsc::Sender<ParallelUIEvent>>,
+    ) -> mpsc::Sender<ParallelUIEvent> {
+        let (sender, mut receiver) = mpsc::channel::<ParallelUIEvent>(EVENT_CHANNEL_CAPACITY);
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                match event {
+                    ParallelUIEvent::StoryFailed {
+                        ref story_id,
+                        ref error,
+                        ..
+                    } => notifier.notify(WebhookEvent::StoryFailed {
+                        project: notifier.project.clone(),
+                        story_id: story_id.clone(),
+                        error: error.clone(),
+                    }),
+                    ParallelUIEvent::CircuitBreakerTriggered {
+                        failures,
+                        threshold,
+                    } => notifier.notify(WebhookEvent::CircuitBreakerTriggered {
+                        project: notifier.project.clone(),
+                        failures,
+                        threshold,
+                    }),
+                    _ => {}
+                }
+                if let Some(ref forward) = forward {
+                    let _ = forward.try_send(event);
+                }
+            }
+        });
+        sender
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::RunOutcome;
+    use axum::extract::State;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    #[test]
+    fn test_load_webhooks_config() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            WebhooksConfig::load(dir.path()).unwrap(),
+            WebhooksConfig::default()
+        );
+
+        std::fs::create_dir_all(dir.path().join(".ralph")).unwrap();
+        std::fs::write(
+            dir.path().join(WEBHOOKS_CONFIG_FILE),
+            r#"
+                [[webhook]]
+                url = "https://hooks.slack.com/services/T/B/X"
+                format = "slack"
+
+                [[webhook]]
+                url_env = "RALPH_TEST_UNSET_WEBHOOK_URL"
+                events = ["run_finished"]
+            "#,
+        )
+        .unwrap();
+        let config = WebhooksConfig::load(dir.path()).unwrap();
+        assert_eq!(config.webhooks.len(), 2);
+        assert_eq!(config.webhooks[0].format, WebhookFormat::Slack);
+        assert!(config.webhooks[0].wants(WebhookEventKind::StoryFailed));
+        assert!(!config.webhooks[1].wants(WebhookEventKind::StoryFailed));
+        assert_eq!(config.webhooks[1].resolve_url(), None);
+
+        std::fs::write(
+            dir.path().join(WEBHOOKS_CONFIG_FILE),
+            "[[webhook]]\nformat = \"teams\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            WebhooksConfig::load(dir.path()),
+            Err(WebhookConfigError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_webhook_payloads() {
+        let result = RunResult {
+            all_passed: false,
+            stories_passed: 3,
+            total_stories: 4,
+            total_iterations: 9,
+            error: Some("US-004 failed".to_string()),
+            outcome: RunOutcome::GateFailure,
+        };
+        let event =
+            WebhookEvent::run_finished("shop", "run-1", &result, Duration::from_secs(754), 123.0);
+        let message = "Ralph stopped shop: 3/4 stories passed in 12m 34s, $1.23 (US-004 failed)";
+        assert_eq!(event.message(), message);
+        assert_eq!(
+            event.payload(WebhookFormat::Slack),
+            serde_json::json!({ "text": message })
+        );
+        assert_eq!(
+            event.payload(WebhookFormat::Discord),
+            serde_json::json!({ "content": message })
+        );
+        let json = event.payload(WebhookFormat::Json);
+        assert_eq!(json["event"], "run_finished");
+        assert_eq!(json["stories_passed"], 3);
+        assert_eq!(json["cost_cents"], 123.0);
+        assert_eq!(json["text"], message);
+    }
+
+    #[tokio::test]
+    async fn test_notifier_posts_tapped_events() {
+        let received = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(received): State<Arc<Mutex<Vec<serde_json::Value>>>>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        received.lock().unwrap().push(body);
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let config = WebhooksConfig {
+            webhooks: vec![WebhookSettings {
+                url: Some(format!("http://{}/hook", addr)),
+                url_env: None,
+                format: WebhookFormat::Json,
+                events: vec![WebhookEventKind::StoryFailed],
+            }],
+        };
+        let notifier = WebhookNotifier::new("shop", config).unwrap();
+        let (forward, mut forwarded) = mpsc::channel(8);
+        let tap = notifier.tap(Some(forward));
+        tap.send(ParallelUIEvent::StoryFailed {
+            story_id: "US-002".to_string(),
+            error: "tests failed\n\nLast Error: ...".to_string(),
+            iteration: 3,
+        })
+        .await
+        .unwrap();
+        // Re-run and failed again
+        tap.send(ParallelUIEvent::StoryFailed {
+            story_id: "US-002".to_string(),
+            error: "tests failed".to_string(),
+            iteration: 1,
+        })
+        .await
+        .unwrap();
+        tap.send(ParallelUIEvent::RunPaused).await.unwrap();
+        for _ in 0..2 {
+            assert!(matches!(
+                forwarded.recv().await,
+                Some(ParallelUIEvent::StoryFailed { .. })
+            ));
+        }
+        assert_eq!(forwarded.recv().await, Some(ParallelUIEvent::RunPaused));
+
+        // Not one of the webhook's events
+        notifier.notify(WebhookEvent::CircuitBreakerTriggered {
+            project: "shop".to_string(),
+            failures: 5,
+            threshold: 5,
+        });
+        notifier.flush().await;
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["event"], "story_failed");
+        assert_eq!(received[0]["story_id"], "US-002");
+        assert_eq!(
+            received[0]["text"],
+            "shop: story US-002 failed: tests failed"
+        );
+        server.abort();
+    }
+}
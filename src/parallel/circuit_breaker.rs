@@ -0,0 +1,235 @@
+//! Circuit breaker for parallel execution
+//!
+//! Counts non-transient story failures across the run and stops it once too
+//! many have occurred. Failures are grouped by category, and a category can
+//! have its own threshold: e.g. stop after 3 fatal errors but tolerate 10
+//! quality-gate failures. Categories without their own threshold share the
+//! run-wide one.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::classification::ErrorCategory;
+
+/// Category of a story failure counted by the circuit breaker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FailureCategory {
+    /// The agent ran but the quality gates failed.
+    QualityGate,
+    /// An unrecoverable error (authentication, configuration, ...).
+    Fatal,
+    /// An API rate limit or quota was exhausted.
+    UsageLimit,
+    /// The agent or the whole story timed out.
+    Timeout,
+}
+
+impl FailureCategory {
+    /// All categories, in display order.
+    pub const ALL: [FailureCategory; 4] = [
+        FailureCategory::QualityGate,
+        FailureCategory::Fatal,
+        FailureCategory::UsageLimit,
+        FailureCategory::Timeout,
+    ];
+
+    /// Category of a classified error, or `None` for transient errors, which
+    /// are never counted.
+    pub fn from_error_category(category: &ErrorCategory) -> Option<Self> {
+        match category {
+            ErrorCategory::Transient(_) => None,
+            ErrorCategory::UsageLimit(_) => Some(Self::UsageLimit),
+            ErrorCategory::Fatal(_) => Some(Self::Fatal),
+            ErrorCategory::Timeout(_) => Some(Self::Timeout),
+        }
+    }
+
+    /// Stable label used in CLI settings and checkpoints.
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            Self::QualityGate => "quality_gate",
+            Self::Fatal => "fatal",
+            Self::UsageLimit => "usage_limit",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
+impl fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
+impl FromStr for FailureCategory {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.as_label() == value.trim())
+            .ok_or_else(|| {
+                let labels: Vec<&str> = Self::ALL.iter().map(|c| c.as_label()).collect();
+                format!(
+                    "unknown failure category '{}' (expected one of: {})",
+                    value.trim(),
+                    labels.join(", ")
+                )
+            })
+    }
+}
+
+/// Parses per-category thresholds such as `fatal=3,quality_gate=10`.
+pub fn parse_category_thresholds(spec: &str) -> Result<BTreeMap<FailureCategory, u32>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (category, count) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected CATEGORY=COUNT, got '{}'", entry))?;
+            let count = count
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("invalid failure count in '{}'", entry))?;
+            Ok((category.parse()?, count))
+        })
+        .collect()
+}
+
+/// Why the circuit breaker tripped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerTrip {
+    /// Category of the failure that tripped the breaker
+    pub category: FailureCategory,
+    /// Failures counted against the threshold that was reached
+    pub failures: u32,
+    /// The threshold that was reached
+    pub threshold: u32,
+}
+
+/// Counts failures per category and decides when to stop the run.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    /// Threshold shared by categories without their own
+    threshold: u32,
+    /// Per-category thresholds
+    category_thresholds: BTreeMap<FailureCategory, u32>,
+    /// Failures recorded per category
+    counts: BTreeMap<FailureCategory, u32>,
+}
+
+impl CircuitBreaker {
+    /// Creates a breaker that trips after `threshold` failures, counting each
+    /// category in `category_thresholds` separately against its own threshold.
+    pub fn new(threshold: u32, category_thresholds: BTreeMap<FailureCategory, u32>) -> Self {
+        Self {
+            threshold,
+            category_thresholds,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Records a failure and returns the trip if a threshold was reached.
+    pub fn record(&mut self, category: FailureCategory) -> Option<CircuitBreakerTrip> {
+        *self.counts.entry(category).or_default() += 1;
+        let (failures, threshold) = self.status(category);
+        (failures >= threshold).then_some(CircuitBreakerTrip {
+            category,
+            failures,
+            threshold,
+        })
+    }
+
+    /// Failures counted against the threshold that applies to `category`, and
+    /// that threshold.
+    pub fn status(&self, category: FailureCategory) -> (u32, u32) {
+        match self.category_thresholds.get(&category) {
+            Some(&threshold) => (self.count(category), threshold),
+            None => {
+                let shared = self
+                    .counts
+                    .iter()
+                    .filter(|(category, _)| !self.category_thresholds.contains_key(category))
+                    .map(|(_, count)| count)
+                    .sum();
+                (shared, self.threshold)
+            }
+        }
+    }
+
+    /// Failures recorded in `category`.
+    pub fn count(&self, category: FailureCategory) -> u32 {
+        self.counts.get(&category).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::classification::{FatalReason, TransientReason};
+
+    #[test]
+    fn test_shared_threshold_counts_all_categories() {
+        let mut breaker = CircuitBreaker::new(3, BTreeMap::new());
+        assert_eq!(breaker.record(FailureCategory::QualityGate), None);
+        assert_eq!(breaker.record(FailureCategory::Timeout), None);
+        assert_eq!(
+            breaker.record(FailureCategory::Fatal),
+            Some(CircuitBreakerTrip {
+                category: FailureCategory::Fatal,
+                failures: 3,
+                threshold: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn test_category_thresholds_count_separately() {
+        let thresholds = parse_category_thresholds("fatal=2, quality_gate=10").unwrap();
+        let mut breaker = CircuitBreaker::new(3, thresholds);
+
+        for _ in 0..9 {
+            assert_eq!(breaker.record(FailureCategory::QualityGate), None);
+        }
+        // Quality-gate failures do not count toward the shared threshold
+        assert_eq!(breaker.record(FailureCategory::Timeout), None);
+        assert_eq!(breaker.status(FailureCategory::Timeout), (1, 3));
+
+        assert_eq!(breaker.record(FailureCategory::Fatal), None);
+        let trip = breaker.record(FailureCategory::Fatal).unwrap();
+        assert_eq!(trip.category, FailureCategory::Fatal);
+        assert_eq!((trip.failures, trip.threshold), (2, 2));
+    }
+
+    #[test]
+    fn test_parse_category_thresholds_errors() {
+        assert!(parse_category_thresholds("").unwrap().is_empty());
+        assert!(parse_category_thresholds("fatal")
+            .unwrap_err()
+            .contains("CATEGORY=COUNT"));
+        assert!(parse_category_thresholds("fatal=many")
+            .unwrap_err()
+            .contains("invalid failure count"));
+        assert!(parse_category_thresholds("network=2")
+            .unwrap_err()
+            .contains("unknown failure category 'network'"));
+    }
+
+    #[test]
+    fn test_transient_errors_are_not_counted() {
+        assert_eq!(
+            FailureCategory::from_error_category(&ErrorCategory::Transient(
+                TransientReason::NetworkError
+            )),
+            None
+        );
+        assert_eq!(
+            FailureCategory::from_error_category(&ErrorCategory::Fatal(
+                FatalReason::AuthenticationFailed
+            )),
+            Some(FailureCategory::Fatal)
+        );
+    }
+}
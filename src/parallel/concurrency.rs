@@ -0,0 +1,397 @@
+//! Adaptive concurrency control for parallel execution
+//!
+//! The controller watches story outcomes and system load and raises or lowers
+//! the effective concurrency between a floor and the configured maximum:
+//! throttling halves it, high load or a high failure rate steps it down, and
+//! a run of healthy outcomes steps it back up.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+
+use crate::error::classification::{ErrorCategory, UsageLimitReason};
+
+/// Outcome of a finished story, as seen by the concurrency controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoryOutcome {
+    /// The story passed.
+    Succeeded,
+    /// The story failed for a reason unrelated to API throttling.
+    Failed,
+    /// The agent API rejected requests due to rate or concurrency limits.
+    Throttled,
+}
+
+impl StoryOutcome {
+    /// Classify a failed story's error category.
+    pub fn from_error_category(category: &ErrorCategory) -> Self {
+        match category {
+            ErrorCategory::UsageLimit(
+                UsageLimitReason::RateLimited | UsageLimitReason::ConcurrencyLimit,
+            ) => StoryOutcome::Throttled,
+            _ => StoryOutcome::Failed,
+        }
+    }
+}
+
+/// Thresholds for the adaptive concurrency controller.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveConcurrencyConfig {
+    /// Lowest concurrency the controller will scale down to.
+    pub min_concurrency: u32,
+    /// Highest concurrency the controller will scale up to.
+    pub max_concurrency: u32,
+    /// Number of recent outcomes used to compute the failure rate.
+    pub window: usize,
+    /// Failure rate (0.0-1.0) above which concurrency is lowered.
+    pub max_failure_rate: f64,
+    /// 1-minute load average per CPU above which concurrency is lowered.
+    pub max_load_per_cpu: f64,
+    /// Outcomes that must be observed between two adjustments.
+    pub cooldown: usize,
+}
+
+impl Default for AdaptiveConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            min_concurrency: 1,
+            max_concurrency: 3,
+            window: 10,
+            max_failure_rate: 0.5,
+            max_load_per_cpu: 1.5,
+            cooldown: 2,
+        }
+    }
+}
+
+/// A change to the effective concurrency.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConcurrencyAdjustment {
+    /// Concurrency before the adjustment.
+    pub previous: u32,
+    /// Concurrency after the adjustment.
+    pub current: u32,
+    /// Why the adjustment was made.
+    pub reason: String,
+}
+
+/// Decides the effective concurrency from recent outcomes and system load.
+#[derive(Debug)]
+pub struct ConcurrencyController {
+    /// Controller thresholds
+    config: AdaptiveConcurrencyConfig,
+    /// Current effective concurrency
+    current: u32,
+    /// Most recent outcomes, oldest first (bounded by `config.window`)
+    outcomes: VecDeque<StoryOutcome>,
+    /// Outcomes observed since the last adjustment
+    since_adjustment: usize,
+}
+
+impl ConcurrencyController {
+    /// Creates a controller starting at the configured maximum.
+    pub fn new(config: AdaptiveConcurrencyConfig) -> Self {
+        let current = config.max_concurrency.max(config.min_concurrency);
+        Self {
+            config,
+            current,
+            outcomes: VecDeque::new(),
+            since_adjustment: 0,
+        }
+    }
+
+    /// Current effective concurrency.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Highest concurrency the controller scales up to.
+    pub fn max_concurrency(&self) -> u32 {
+        self.config.max_concurrency
+    }
+
+    /// Changes the highest concurrency, e.g. at the user's request, moving the
+    /// current concurrency by as much as the maximum moved.
+    pub fn set_max_concurrency(&mut self, max_concurrency: u32) -> Option<ConcurrencyAdjustment> {
+        let max_concurrency = max_concurrency.max(self.config.min_concurrency);
+        let previous_max = self.config.max_concurrency;
+        self.config.max_concurrency = max_concurrency;
+
+        let target = if max_concurrency > previous_max {
+            self.current + (max_concurrency - previous_max)
+        } else {
+            self.current
+        }
+        .min(max_concurrency);
+        if target == self.current {
+            return None;
+        }
+
+        let adjustment = ConcurrencyAdjustment {
+            previous: self.current,
+            current: target,
+            reason: format!("maximum set to {}", max_concurrency),
+        };
+        self.current = target;
+        self.since_adjustment = 0;
+        Some(adjustment)
+    }
+
+    /// Records the outcome of a finished story.
+    pub fn record(&mut self, outcome: StoryOutcome) {
+        self.outcomes.push_back(outcome);
+        while self.outcomes.len() > self.config.window.max(1) {
+            self.outcomes.pop_front();
+        }
+        self.since_adjustment += 1;
+    }
+
+    /// Evaluates recent outcomes and load, returning an adjustment if one is due.
+    ///
+    /// `load_per_cpu` is the 1-minute load average divided by the CPU count,
+    /// or `None` when it cannot be measured.
+    pub fn evaluate(&mut self, load_per_cpu: Option<f64>) -> Option<ConcurrencyAdjustment> {
+        if self.since_adjustment < self.config.cooldown.max(1) {
+            return None;
+        }
+
+        let recent: Vec<StoryOutcome> = self
+            .outcomes
+            .iter()
+            .rev()
+            .take(self.since_adjustment)
+            .copied()
+            .collect();
+        let failures = self
+            .outcomes
+            .iter()
+            .filter(|outcome| **outcome != StoryOutcome::Succeeded)
+            .count();
+        let failure_rate = failures as f64 / self.outcomes.len().max(1) as f64;
+        let overloaded = load_per_cpu.filter(|load| *load > self.config.max_load_per_cpu);
+
+        let (target, reason) = if recent.contains(&StoryOutcome::Throttled) {
+            (
+                self.current / 2,
+                "agent API throttling detected".to_string(),
+            )
+        } else if let Some(load) = overloaded {
+            (
+                self.current.saturating_sub(1),
+                format!("system load {:.2} per CPU", load),
+            )
+        } else if failure_rate > self.config.max_failure_rate {
+            (
+                self.current.saturating_sub(1),
+                format!("failure rate {:.0}%", failure_rate * 100.0),
+            )
+        } else if recent
+            .iter()
+            .all(|outcome| *outcome == StoryOutcome::Succeeded)
+        {
+            (
+                self.current.saturating_add(1),
+                "recent stories healthy".to_string(),
+            )
+        } else {
+            return None;
+        };
+
+        let target = target.clamp(self.config.min_concurrency, self.config.max_concurrency);
+        if target == self.current {
+            return None;
+        }
+
+        let adjustment = ConcurrencyAdjustment {
+            previous: self.current,
+            current: target,
+            reason,
+        };
+        self.current = target;
+        self.since_adjustment = 0;
+        Some(adjustment)
+    }
+}
+
+/// The 1-minute load average divided by the number of CPUs.
+///
+/// Returns `None` on platforms without `/proc/loadavg`.
+pub fn system_load_per_cpu() -> Option<f64> {
+    let loadavg = std::fs::read_to_string("/proc/loadavg").ok()?;
+    let load: f64 = loadavg.split_whitespace().next()?.parse().ok()?;
+    let cpus = std::thread::available_parallelism().ok()?.get();
+    Some(load / cpus as f64)
+}
+
+/// Resizes a semaphore from `previous` to `current` permits.
+///
+/// Growing adds permits immediately. Shrinking forgets free permits right away
+/// and retires the rest as running stories release them.
+pub fn resize_semaphore(semaphore: &Arc<Semaphore>, previous: u32, current: u32) {
+    if current > previous {
+        semaphore.add_permits((current - previous) as usize);
+    } else if current < previous {
+        let excess = (previous - current) as usize;
+        let remaining = excess - semaphore.forget_permits(excess);
+        if remaining > 0 {
+            let semaphore = semaphore.clone();
+            tokio::spawn(async move {
+                if let Ok(permits) = semaphore.acquire_many_owned(remaining as u32).await {
+                    permits.forget();
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::classification::TransientReason;
+
+    fn controller(max: u32) -> ConcurrencyController {
+        ConcurrencyController::new(AdaptiveConcurrencyConfig {
+            max_concurrency: max,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_outcome_from_error_category() {
+        assert_eq!(
+            StoryOutcome::from_error_category(&ErrorCategory::UsageLimit(
+                UsageLimitReason::RateLimited
+            )),
+            StoryOutcome::Throttled
+        );
+        assert_eq!(
+            StoryOutcome::from_error_category(&ErrorCategory::Transient(
+                TransientReason::NetworkError
+            )),
+            StoryOutcome::Failed
+        );
+    }
+
+    #[test]
+    fn test_throttling_halves_concurrency() {
+        let mut controller = controller(8);
+        controller.record(StoryOutcome::Succeeded);
+        controller.record(StoryOutcome::Throttled);
+
+        let adjustment = controller.evaluate(None).unwrap();
+        assert_eq!(adjustment.previous, 8);
+        assert_eq!(adjustment.current, 4);
+        assert!(adjustment.reason.contains("throttling"));
+        assert_eq!(controller.current(), 4);
+    }
+
+    #[test]
+    fn test_cooldown_delays_adjustment() {
+        let mut controller = controller(4);
+        controller.record(StoryOutcome::Throttled);
+        assert!(controller.evaluate(None).is_none());
+
+        controller.record(StoryOutcome::Failed);
+        assert_eq!(controller.evaluate(None).unwrap().current, 2);
+        // Outcomes before the adjustment no longer count toward the next one
+        assert!(controller.evaluate(None).is_none());
+    }
+
+    #[test]
+    fn test_high_load_lowers_concurrency() {
+        let mut controller = controller(4);
+        controller.record(StoryOutcome::Succeeded);
+        controller.record(StoryOutcome::Succeeded);
+
+        let adjustment = controller.evaluate(Some(2.5)).unwrap();
+        assert_eq!(adjustment.current, 3);
+        assert!(adjustment.reason.contains("system load 2.50"));
+    }
+
+    #[test]
+    fn test_failure_rate_lowers_concurrency() {
+        let mut controller = controller(4);
+        controller.record(StoryOutcome::Failed);
+        controller.record(StoryOutcome::Failed);
+        controller.record(StoryOutcome::Succeeded);
+
+        let adjustment = controller.evaluate(Some(0.5)).unwrap();
+        assert_eq!(adjustment.current, 3);
+        assert!(adjustment.reason.contains("failure rate 67%"));
+    }
+
+    #[test]
+    fn test_healthy_outcomes_raise_concurrency_up_to_max() {
+        let mut controller = controller(4);
+        controller.record(StoryOutcome::Throttled);
+        controller.record(StoryOutcome::Throttled);
+        assert_eq!(controller.evaluate(None).unwrap().current, 2);
+
+        for _ in 0..8 {
+            controller.record(StoryOutcome::Succeeded);
+        }
+        assert_eq!(controller.evaluate(Some(0.2)).unwrap().current, 3);
+
+        controller.record(StoryOutcome::Succeeded);
+        controller.record(StoryOutcome::Succeeded);
+        assert_eq!(controller.evaluate(None).unwrap().current, 4);
+
+        controller.record(StoryOutcome::Succeeded);
+        controller.record(StoryOutcome::Succeeded);
+        assert!(controller.evaluate(None).is_none());
+    }
+
+    #[test]
+    fn test_never_drops_below_min() {
+        let mut controller = ConcurrencyController::new(AdaptiveConcurrencyConfig {
+            min_concurrency: 2,
+            max_concurrency: 3,
+            ..Default::default()
+        });
+        controller.record(StoryOutcome::Throttled);
+        controller.record(StoryOutcome::Throttled);
+        assert_eq!(controller.evaluate(None).unwrap().current, 2);
+
+        controller.record(StoryOutcome::Throttled);
+        controller.record(StoryOutcome::Throttled);
+        assert!(controller.evaluate(None).is_none());
+    }
+
+    #[test]
+    fn test_set_max_concurrency() {
+        let mut controller = controller(4);
+        controller.record(StoryOutcome::Throttled);
+        controller.record(StoryOutcome::Throttled);
+        assert_eq!(controller.evaluate(None).unwrap().current, 2);
+
+        // Raising the maximum raises the current concurrency by as much
+        let adjustment = controller.set_max_concurrency(5).unwrap();
+        assert_eq!((adjustment.previous, adjustment.current), (2, 3));
+        assert_eq!(controller.max_concurrency(), 5);
+
+        // Lowering it only matters once it drops below the current concurrency
+        assert!(controller.set_max_concurrency(3).is_none());
+        assert_eq!(controller.set_max_concurrency(2).unwrap().current, 2);
+        // ... and never below the minimum
+        assert_eq!(controller.set_max_concurrency(0).unwrap().current, 1);
+        assert_eq!(controller.max_concurrency(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resize_semaphore() {
+        let semaphore = Arc::new(Semaphore::new(4));
+        resize_semaphore(&semaphore, 4, 2);
+        assert_eq!(semaphore.available_permits(), 2);
+
+        resize_semaphore(&semaphore, 2, 5);
+        assert_eq!(semaphore.available_permits(), 5);
+
+        // Permits held by running stories are retired when released
+        let held = semaphore.clone().acquire_many_owned(5).await.unwrap();
+        resize_semaphore(&semaphore, 5, 3);
+        drop(held);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert_eq!(semaphore.available_permits(), 3);
+    }
+}
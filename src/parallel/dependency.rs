@@ -1,10 +1,11 @@
 //! Dependency graph construction and analysis
 
 use crate::mcp::tools::load_prd::PrdUserStory;
-use crate::parallel::inference::infer_from_files;
+use crate::parallel::inference::{infer_from_files, patterns_match};
 use petgraph::algo::{is_cyclic_directed, toposort};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
+use petgraph::Direction;
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -14,6 +15,17 @@ pub enum DependencyError {
     /// A circular dependency was detected in the graph.
     #[error("Cycle detected involving stories: {}", .0.join(", "))]
     CycleDetected(Vec<String>),
+    /// A story filter named a story that is not in the PRD.
+    #[error("Unknown story in filter: {0}")]
+    UnknownStory(String),
+    /// A selected story depends on a story that was filtered out and has not passed.
+    #[error("Story {story} depends on {dependency}, which is excluded and not passing")]
+    ExcludedDependency {
+        /// The selected story
+        story: String,
+        /// The excluded dependency
+        dependency: String,
+    },
 }
 
 /// Represents a story node in the dependency graph.
@@ -30,6 +42,40 @@ pub struct StoryNode {
     pub depends_on: Vec<String>,
     /// Files that this story will modify (for conflict detection)
     pub target_files: Vec<String>,
+    /// Files that this story only reads (may be shared with other readers)
+    pub read_files: Vec<String>,
+    /// Whether the story is resource-heavy
+    pub heavy: bool,
+    /// Agent the PRD sets for the story, overriding the run's assignment
+    pub agent: Option<String>,
+    /// Model the PRD sets for the story
+    pub model: Option<String>,
+}
+
+impl StoryNode {
+    /// Files that keep this story and `other` from running at the same time.
+    ///
+    /// A file conflicts when either story writes it and the other reads or
+    /// writes it; files both stories only read do not conflict. Entries are
+    /// compared as glob patterns, so `src/api/*.rs` conflicts with `src/api/users.rs`.
+    pub fn conflicting_files(&self, other: &StoryNode) -> Vec<String> {
+        let matches_any =
+            |file: &str, patterns: &[String]| patterns.iter().any(|p| patterns_match(file, p));
+        let mut files: Vec<String> = self
+            .target_files
+            .iter()
+            .filter(|file| {
+                matches_any(file, &other.target_files) || matches_any(file, &other.read_files)
+            })
+            .cloned()
+            .collect();
+        for file in &self.read_files {
+            if matches_any(file, &other.target_files) && !files.contains(file) {
+                files.push(file.clone());
+            }
+        }
+        files
+    }
 }
 
 impl From<&PrdUserStory> for StoryNode {
@@ -40,6 +86,10 @@ impl From<&PrdUserStory> for StoryNode {
             passes: story.passes,
             depends_on: story.depends_on.clone(),
             target_files: story.target_files.clone(),
+            read_files: story.read_files.clone(),
+            heavy: story.heavy,
+            agent: story.agent.clone(),
+            model: story.model.clone(),
         }
     }
 }
@@ -99,6 +149,62 @@ impl DependencyGraph {
         DependencyGraph { graph, id_to_node }
     }
 
+    /// Returns a graph containing only the selected stories.
+    ///
+    /// `only` limits the graph to the listed stories (every story when empty)
+    /// and `skip` removes stories from it. Each selected story's dependencies
+    /// must be selected too or already passing; passing dependencies that were
+    /// filtered out are dropped from the story's `depends_on`.
+    pub fn select(&self, only: &[String], skip: &[String]) -> Result<Self, DependencyError> {
+        if let Some(unknown) = only
+            .iter()
+            .chain(skip)
+            .find(|id| !self.id_to_node.contains_key(*id))
+        {
+            return Err(DependencyError::UnknownStory(unknown.clone()));
+        }
+
+        let selected = |id: &String| (only.is_empty() || only.contains(id)) && !skip.contains(id);
+        let mut nodes = Vec::new();
+        for idx in self.graph.node_indices() {
+            let node = &self.graph[idx];
+            if !selected(&node.id) {
+                continue;
+            }
+            let mut node = node.clone();
+            // Dependencies missing from the PRD are left as they are
+            let excluded = |dep: &String| self.id_to_node.contains_key(dep) && !selected(dep);
+            if let Some(dep) = node
+                .depends_on
+                .iter()
+                .find(|dep| excluded(dep) && self.get_story(dep).is_some_and(|dep| !dep.passes))
+            {
+                return Err(DependencyError::ExcludedDependency {
+                    story: node.id.clone(),
+                    dependency: dep.clone(),
+                });
+            }
+            node.depends_on.retain(|dep| !excluded(dep));
+            nodes.push(node);
+        }
+
+        let mut graph = DiGraph::new();
+        let mut id_to_node = HashMap::new();
+        for node in nodes {
+            let id = node.id.clone();
+            id_to_node.insert(id, graph.add_node(node));
+        }
+        for dependent_idx in graph.node_indices() {
+            for dep_id in graph[dependent_idx].depends_on.clone() {
+                if let Some(&dependency_idx) = id_to_node.get(&dep_id) {
+                    graph.add_edge(dependent_idx, dependency_idx, ());
+                }
+            }
+        }
+
+        Ok(DependencyGraph { graph, id_to_node })
+    }
+
     /// Returns a reference to the underlying graph.
     pub fn graph(&self) -> &DiGraph<StoryNode, ()> {
         &self.graph
@@ -217,6 +323,35 @@ impl DependencyGraph {
             .collect()
     }
 
+    /// Returns the IDs of all stories that depend on `id`, directly or transitively.
+    ///
+    /// The result is sorted and does not include `id` itself.
+    pub fn transitive_dependents(&self, id: &str) -> Vec<String> {
+        let Some(&start) = self.id_to_node.get(id) else {
+            return Vec::new();
+        };
+
+        // Edges point from dependent to dependency, so walk them backwards
+        let mut seen = vec![start];
+        let mut stack = vec![start];
+        while let Some(idx) = stack.pop() {
+            for dependent in self.graph.neighbors_directed(idx, Direction::Incoming) {
+                if !seen.contains(&dependent) {
+                    seen.push(dependent);
+                    stack.push(dependent);
+                }
+            }
+        }
+
+        let mut dependents: Vec<String> = seen
+            .into_iter()
+            .skip(1)
+            .map(|idx| self.graph[idx].id.clone())
+            .collect();
+        dependents.sort();
+        dependents
+    }
+
     /// Finds all story IDs that participate in cycles.
     ///
     /// Uses a simple approach: a node is in a cycle if it can reach itself
@@ -304,6 +439,13 @@ mod tests {
             passes: false,
             depends_on: depends_on.into_iter().map(String::from).collect(),
             target_files: vec![],
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
+            epic: None,
+            prerequisites: Vec::new(),
+            estimate: None,
         }
     }
 
@@ -552,9 +694,36 @@ mod tests {
             passes,
             depends_on: depends_on.into_iter().map(String::from).collect(),
             target_files: vec![],
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
+            epic: None,
+            prerequisites: Vec::new(),
+            estimate: None,
         }
     }
 
+    #[test]
+    fn test_transitive_dependents() {
+        let stories = vec![
+            make_story("US-001", vec![]),
+            make_story("US-002", vec!["US-001"]),
+            make_story("US-003", vec!["US-002"]),
+            make_story("US-004", vec!["US-001", "US-003"]),
+            make_story("US-005", vec![]),
+        ];
+        let graph = DependencyGraph::from_stories(&stories);
+
+        assert_eq!(
+            graph.transitive_dependents("US-001"),
+            vec!["US-002", "US-003", "US-004"]
+        );
+        assert_eq!(graph.transitive_dependents("US-003"), vec!["US-004"]);
+        assert!(graph.transitive_dependents("US-005").is_empty());
+        assert!(graph.transitive_dependents("US-404").is_empty());
+    }
+
     #[test]
     fn test_get_ready_stories_partially_completed() {
         use std::collections::HashSet;
@@ -650,6 +819,13 @@ mod tests {
             passes: false,
             depends_on: depends_on.into_iter().map(String::from).collect(),
             target_files: target_files.into_iter().map(String::from).collect(),
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
+            epic: None,
+            prerequisites: Vec::new(),
+            estimate: None,
         }
     }
 
@@ -732,4 +908,42 @@ mod tests {
             "Should still have only one edge (no duplicate)"
         );
     }
+
+    #[test]
+    fn test_select_prunes_graph_and_checks_dependencies() {
+        let mut passed = make_story("US-001", vec![]);
+        passed.passes = true;
+        let stories = vec![
+            passed,
+            make_story("US-002", vec!["US-001"]),
+            make_story("US-003", vec!["US-002"]),
+            make_story("US-004", vec![]),
+        ];
+        let graph = DependencyGraph::from_stories(&stories);
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        // A passing dependency may be filtered out and no longer gates the story
+        let selected = graph.select(&ids(&["US-002", "US-003"]), &[]).unwrap();
+        assert_eq!(selected.node_count(), 2);
+        assert_eq!(selected.edge_count(), 1);
+        assert!(selected.get_story("US-002").unwrap().depends_on.is_empty());
+        assert!(selected.get_story("US-001").is_none());
+
+        let selected = graph.select(&[], &ids(&["US-003"])).unwrap();
+        assert_eq!(selected.node_count(), 3);
+        assert!(selected.get_story("US-003").is_none());
+
+        // A failing dependency must be selected too
+        assert_eq!(
+            graph.select(&ids(&["US-003"]), &[]).err(),
+            Some(DependencyError::ExcludedDependency {
+                story: "US-003".to_string(),
+                dependency: "US-002".to_string(),
+            })
+        );
+        assert_eq!(
+            graph.select(&[], &ids(&["US-009"])).err(),
+            Some(DependencyError::UnknownStory("US-009".to_string()))
+        );
+    }
 }
@@ -0,0 +1,119 @@
+//! Fair dispatch ordering for the parallel queue
+//!
+//! Stories that cannot start when they reach the front of the queue (their
+//! files are locked, or memory is low) used to be pushed to the back, so under
+//! contention a story could be overtaken indefinitely. Each story instead gets
+//! a ticket when it is first queued and keeps it until it is dispatched;
+//! requeued stories go back to their ticket's place, so stories are offered
+//! worker slots in the order they became ready.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::parallel::dependency::StoryNode;
+
+/// Tickets recording the order stories became ready.
+#[derive(Debug, Default)]
+pub struct ReadyOrder {
+    /// Ticket handed to the next newly ready story
+    next: u64,
+    /// Tickets of stories waiting to be dispatched
+    tickets: HashMap<String, u64>,
+}
+
+impl ReadyOrder {
+    /// Creates an empty ordering.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the story's ticket, handing out the next one if it has none.
+    pub fn ticket(&mut self, story_id: &str) -> u64 {
+        if let Some(&ticket) = self.tickets.get(story_id) {
+            return ticket;
+        }
+        let ticket = self.next;
+        self.next += 1;
+        self.tickets.insert(story_id.to_string(), ticket);
+        ticket
+    }
+
+    /// Forgets the story's ticket once it is dispatched or leaves the queue
+    /// for good; if it becomes ready again it queues at the back.
+    pub fn release(&mut self, story_id: &str) {
+        self.tickets.remove(story_id);
+    }
+
+    /// Inserts `story` ahead of every queued story that became ready after it.
+    ///
+    /// A story without a ticket gets the next one and so goes to the back.
+    pub fn enqueue(&mut self, queue: &mut VecDeque<StoryNode>, story: StoryNode) {
+        let ticket = self.ticket(&story.id);
+        let index = queue
+            .iter()
+            .position(|queued| self.tickets.get(&queued.id).map_or(true, |t| *t > ticket))
+            .unwrap_or(queue.len());
+        queue.insert(index, story);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story(id: &str) -> StoryNode {
+        StoryNode {
+            id: id.to_string(),
+            priority: 1,
+            passes: false,
+            depends_on: Vec::new(),
+            target_files: Vec::new(),
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
+        }
+    }
+
+    fn ids(queue: &VecDeque<StoryNode>) -> Vec<&str> {
+        queue.iter().map(|story| story.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_new_stories_queue_in_ready_order() {
+        let mut order = ReadyOrder::new();
+        let mut queue = VecDeque::new();
+        for id in ["US-001", "US-002", "US-003"] {
+            order.enqueue(&mut queue, story(id));
+        }
+        assert_eq!(ids(&queue), ["US-001", "US-002", "US-003"]);
+    }
+
+    #[test]
+    fn test_requeued_story_keeps_its_place() {
+        let mut order = ReadyOrder::new();
+        let mut queue = VecDeque::new();
+        for id in ["US-001", "US-002", "US-003"] {
+            order.enqueue(&mut queue, story(id));
+        }
+
+        // US-001 finds its files locked and goes back into the queue
+        let blocked = queue.pop_front().unwrap();
+        order.enqueue(&mut queue, story("US-004"));
+        order.enqueue(&mut queue, blocked);
+        assert_eq!(ids(&queue), ["US-001", "US-002", "US-003", "US-004"]);
+    }
+
+    #[test]
+    fn test_released_story_queues_at_the_back() {
+        let mut order = ReadyOrder::new();
+        let mut queue = VecDeque::new();
+        for id in ["US-001", "US-002"] {
+            order.enqueue(&mut queue, story(id));
+        }
+
+        let dispatched = queue.pop_front().unwrap();
+        order.release(&dispatched.id);
+        order.enqueue(&mut queue, dispatched);
+        assert_eq!(ids(&queue), ["US-002", "US-001"]);
+    }
+}
@@ -73,7 +73,7 @@ fn patterns_overlap(patterns_a: &[String], patterns_b: &[String]) -> bool {
 /// - Pattern A (as a glob) matches pattern B (as a literal path)
 /// - Pattern B (as a glob) matches pattern A (as a literal path)
 /// - Both patterns share a common prefix that could lead to matching files
-fn patterns_match(pattern_a: &str, pattern_b: &str) -> bool {
+pub(crate) fn patterns_match(pattern_a: &str, pattern_b: &str) -> bool {
     // Identical patterns always match
     if pattern_a == pattern_b {
         return true;
@@ -178,6 +178,10 @@ mod tests {
             passes: false,
             depends_on: vec![],
             target_files: target_files.into_iter().map(String::from).collect(),
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
         }
     }
 
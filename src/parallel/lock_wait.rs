@@ -0,0 +1,263 @@
+//! Lock-wait tracking for parallel execution
+//!
+//! A story whose target files are locked by another story is requeued until
+//! the locks are free. If the holder hangs, or other stories keep winning the
+//! lock, the waiter could be requeued forever. This module tracks how long and
+//! how often each story has been blocked and escalates: first a warning naming
+//! the holder, then moving the story to the front of the queue, and finally
+//! failing it with a diagnostic.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Thresholds at which a story blocked on file locks is escalated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockWaitPolicy {
+    /// Wait after which a warning naming the lock holder is logged.
+    pub warn_after: Duration,
+    /// Wait after which the story moves to the front of the queue, so it gets
+    /// the lock before other waiters once the holder releases it.
+    pub reprioritize_after: Duration,
+    /// Wait after which the story fails. `None` waits indefinitely.
+    pub fail_after: Option<Duration>,
+    /// Failed lock attempts after which the story fails, regardless of how
+    /// long it has waited. `None` disables the limit.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for LockWaitPolicy {
+    fn default() -> Self {
+        Self {
+            warn_after: Duration::from_secs(120),
+            reprioritize_after: Duration::from_secs(300),
+            // Longer than the default story timeout, so a hung holder is
+            // normally timed out before its waiters give up
+            fail_after: Some(Duration::from_secs(3600)),
+            max_attempts: None,
+        }
+    }
+}
+
+/// What to do with a story that failed to acquire its locks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LockWaitAction {
+    /// Requeue the story as usual.
+    Wait,
+    /// Requeue the story and log the diagnostic.
+    Warn(String),
+    /// Requeue the story at the front of the queue and log the diagnostic.
+    Reprioritize(String),
+    /// Fail the story with the diagnostic.
+    Fail(String),
+}
+
+/// A story currently blocked on file locks.
+#[derive(Clone, Debug)]
+struct LockWait {
+    /// When the story first failed to acquire its locks
+    since: Instant,
+    /// Number of failed lock attempts
+    attempts: u32,
+    /// Whether the warning was already issued
+    warned: bool,
+    /// Whether the story was already moved to the front of the queue
+    reprioritized: bool,
+}
+
+/// Tracks stories waiting on file locks and decides when to escalate.
+#[derive(Clone, Debug, Default)]
+pub struct LockWaitTracker {
+    policy: LockWaitPolicy,
+    waits: HashMap<String, LockWait>,
+}
+
+impl LockWaitTracker {
+    /// Creates a tracker using `policy`.
+    pub fn new(policy: LockWaitPolicy) -> Self {
+        Self {
+            policy,
+            waits: HashMap::new(),
+        }
+    }
+
+    /// Records a failed lock attempt by `story_id` and returns the action to take.
+    ///
+    /// `holder` is the story holding `file`, the first lock the waiter needs.
+    pub fn record_blocked(
+        &mut self,
+        story_id: &str,
+        file: &Path,
+        holder: &str,
+        now: Instant,
+    ) -> LockWaitAction {
+        let wait = self
+            .waits
+            .entry(story_id.to_string())
+            .or_insert_with(|| LockWait {
+                since: now,
+                attempts: 0,
+                warned: false,
+                reprioritized: false,
+            });
+        wait.attempts += 1;
+        let waited = now.saturating_duration_since(wait.since);
+        let diagnostic = format!(
+            "{} has waited {}s ({} attempts) for {} locked by {}",
+            story_id,
+            waited.as_secs(),
+            wait.attempts,
+            file.display(),
+            holder
+        );
+
+        let timed_out = self.policy.fail_after.is_some_and(|limit| waited >= limit);
+        let over_attempts = self
+            .policy
+            .max_attempts
+            .is_some_and(|limit| wait.attempts >= limit);
+        if timed_out || over_attempts {
+            self.waits.remove(story_id);
+            return LockWaitAction::Fail(diagnostic);
+        }
+        if !wait.reprioritized && waited >= self.policy.reprioritize_after {
+            wait.reprioritized = true;
+            wait.warned = true;
+            return LockWaitAction::Reprioritize(diagnostic);
+        }
+        if !wait.warned && waited >= self.policy.warn_after {
+            wait.warned = true;
+            return LockWaitAction::Warn(diagnostic);
+        }
+        LockWaitAction::Wait
+    }
+
+    /// Stops tracking a story, e.g. once it acquired its locks or was cancelled.
+    pub fn clear(&mut self, story_id: &str) {
+        self.waits.remove(story_id);
+    }
+
+    /// Whether any story is waiting on locks.
+    pub fn is_empty(&self) -> bool {
+        self.waits.is_empty()
+    }
+
+    /// The next instant at which a waiting story reaches an escalation threshold.
+    ///
+    /// The scheduler wakes up at this point to re-check waiting stories even if
+    /// no in-flight story has finished.
+    pub fn next_escalation(&self) -> Option<Instant> {
+        self.waits
+            .values()
+            .filter_map(|wait| {
+                let threshold = if !wait.warned {
+                    Some(self.policy.warn_after)
+                } else if !wait.reprioritized {
+                    Some(self.policy.reprioritize_after)
+                } else {
+                    self.policy.fail_after
+                };
+                threshold.map(|threshold| wait.since + threshold)
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn policy() -> LockWaitPolicy {
+        LockWaitPolicy {
+            warn_after: Duration::from_secs(10),
+            reprioritize_after: Duration::from_secs(20),
+            fail_after: Some(Duration::from_secs(30)),
+            max_attempts: None,
+        }
+    }
+
+    #[test]
+    fn test_escalates_warn_reprioritize_fail() {
+        let mut tracker = LockWaitTracker::new(policy());
+        let file = PathBuf::from("src/lib.rs");
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(
+            tracker.record_blocked("US-002", &file, "US-001", start),
+            LockWaitAction::Wait
+        );
+        assert_eq!(tracker.next_escalation(), Some(at(10)));
+
+        let LockWaitAction::Warn(message) =
+            tracker.record_blocked("US-002", &file, "US-001", at(12))
+        else {
+            panic!("expected a warning");
+        };
+        assert!(message.contains("src/lib.rs locked by US-001"));
+        // The warning is issued once
+        assert_eq!(
+            tracker.record_blocked("US-002", &file, "US-001", at(15)),
+            LockWaitAction::Wait
+        );
+
+        assert!(matches!(
+            tracker.record_blocked("US-002", &file, "US-001", at(20)),
+            LockWaitAction::Reprioritize(_)
+        ));
+        assert_eq!(tracker.next_escalation(), Some(at(30)));
+
+        let LockWaitAction::Fail(message) =
+            tracker.record_blocked("US-002", &file, "US-001", at(31))
+        else {
+            panic!("expected a failure");
+        };
+        assert_eq!(
+            message,
+            "US-002 has waited 31s (5 attempts) for src/lib.rs locked by US-001"
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_max_attempts_fails_livelocked_story() {
+        let mut tracker = LockWaitTracker::new(LockWaitPolicy {
+            max_attempts: Some(3),
+            ..policy()
+        });
+        let file = PathBuf::from("src/lib.rs");
+        let now = Instant::now();
+
+        assert_eq!(
+            tracker.record_blocked("US-002", &file, "US-001", now),
+            LockWaitAction::Wait
+        );
+        assert_eq!(
+            tracker.record_blocked("US-002", &file, "US-003", now),
+            LockWaitAction::Wait
+        );
+        assert!(matches!(
+            tracker.record_blocked("US-002", &file, "US-003", now),
+            LockWaitAction::Fail(_)
+        ));
+    }
+
+    #[test]
+    fn test_clear_resets_wait() {
+        let mut tracker = LockWaitTracker::new(policy());
+        let file = PathBuf::from("src/lib.rs");
+        let start = Instant::now();
+
+        tracker.record_blocked("US-002", &file, "US-001", start);
+        tracker.clear("US-002");
+        assert!(tracker.is_empty());
+        assert_eq!(tracker.next_escalation(), None);
+
+        // A new wait starts from scratch
+        assert_eq!(
+            tracker.record_blocked("US-002", &file, "US-001", start + Duration::from_secs(25)),
+            LockWaitAction::Wait
+        );
+    }
+}
@@ -3,11 +3,23 @@
 //! This module provides infrastructure for parallel story execution,
 //! including dependency analysis, scheduling, conflict detection, and reconciliation.
 
+pub mod circuit_breaker;
+pub mod concurrency;
 pub mod conflict;
 pub mod dependency;
+pub mod fair_queue;
 pub mod inference;
+pub mod lock_wait;
+pub mod planner;
+pub mod rate_limit;
 pub mod reconcile;
+pub mod replan;
+pub mod resolution;
+pub mod remote;
+pub mod retry;
 pub mod scheduler;
+pub mod trace;
+pub mod worktree;
 
 // Re-export UI events for external use
 pub use crate::ui::parallel_events::{ParallelUIEvent, StoryDisplayInfo, StoryStatus};
@@ -0,0 +1,626 @@
+//! Dry-run execution planning for parallel runs
+//!
+//! The planner replays the scheduler's decisions without invoking an agent: it
+//! builds the dependency graph, applies the same pre-execution conflict filter
+//! and concurrency limit, and projects the batches stories would run in along
+//! with the lock conflicts that defer them, an estimated duration and the
+//! tokens the stories' estimates forecast.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::budget::TokenForecast;
+use crate::mcp::tools::load_prd::{PrdUserStory, StoryEstimate};
+use crate::metrics::{InvocationStatus, RunMetrics};
+use crate::parallel::dependency::{DependencyError, DependencyGraph};
+use crate::parallel::scheduler::{filter_conflicting_stories, ParallelRunnerConfig};
+
+/// Estimate used for stories without any recorded history.
+const DEFAULT_STORY_ESTIMATE: Duration = Duration::from_secs(600);
+
+/// Per-story duration estimates derived from previous runs.
+#[derive(Clone, Debug, Default)]
+pub struct DurationEstimates {
+    /// Average recorded duration per story ID
+    per_story: HashMap<String, Duration>,
+    /// Average duration per story point of stories estimated in points
+    per_point: Option<Duration>,
+    /// Average duration per attempt
+    per_iteration: Option<Duration>,
+    /// Estimate for stories without history
+    fallback: Option<Duration>,
+}
+
+/// What a story's duration estimate is based on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EstimateBasis {
+    /// The story's own recorded durations
+    History,
+    /// The story's `estimate`, scaled by what points or iterations took before
+    Estimate,
+    /// The mean of all recorded steps, or a default without history
+    Fallback,
+}
+
+impl DurationEstimates {
+    /// Builds estimates from stored run metrics.
+    ///
+    /// Each story's estimate is the mean of its recorded step durations. A
+    /// story never seen before is scaled from its `estimate` by the mean time
+    /// per point or per iteration, and otherwise uses the mean across all
+    /// recorded steps.
+    pub fn from_history(runs: &[RunMetrics]) -> Self {
+        let mut samples: HashMap<String, Vec<Duration>> = HashMap::new();
+        let mut per_point = Vec::new();
+        let mut per_iteration = Vec::new();
+        for step in runs.iter().flat_map(|run| run.steps.iter()) {
+            if !step.duration.is_zero() {
+                samples
+                    .entry(step.step_id.clone())
+                    .or_default()
+                    .push(step.duration);
+                if let Some(points) = step.estimate.and_then(|e| e.points).filter(|p| *p > 0) {
+                    per_point.push(step.duration / points);
+                }
+                if step.attempts > 0 {
+                    per_iteration.push(step.duration / step.attempts);
+                }
+            }
+        }
+
+        let all: Vec<Duration> = samples.values().flatten().copied().collect();
+        let per_story = samples
+            .into_iter()
+            .map(|(id, durations)| (id, mean(&durations)))
+            .collect();
+        Self {
+            per_story,
+            per_point: (!per_point.is_empty()).then(|| mean(&per_point)),
+            per_iteration: (!per_iteration.is_empty()).then(|| mean(&per_iteration)),
+            fallback: (!all.is_empty()).then(|| mean(&all)),
+        }
+    }
+
+    /// Estimated duration of a story with the given `estimate`, and what it
+    /// is based on.
+    pub fn estimate(
+        &self,
+        story_id: &str,
+        estimate: Option<&StoryEstimate>,
+    ) -> (Duration, EstimateBasis) {
+        if let Some(duration) = self.per_story.get(story_id) {
+            return (*duration, EstimateBasis::History);
+        }
+        let scaled = estimate.and_then(|estimate| {
+            let by_points = self.per_point.zip(estimate.points);
+            by_points
+                .or(self.per_iteration.zip(estimate.iterations))
+                .map(|(per_unit, units)| per_unit * units)
+        });
+        match scaled {
+            Some(duration) => (duration, EstimateBasis::Estimate),
+            None => (
+                self.fallback.unwrap_or(DEFAULT_STORY_ESTIMATE),
+                EstimateBasis::Fallback,
+            ),
+        }
+    }
+}
+
+/// Iteration durations and counts derived from previous runs, from which the
+/// progress display estimates how long running stories have left.
+#[derive(Clone, Debug, Default)]
+pub struct IterationEstimates {
+    /// Median agent run per agent label
+    per_agent: HashMap<String, Duration>,
+    /// Median agent run across all agents
+    any_agent: Option<Duration>,
+    /// Median duration per quality gate
+    per_gate: BTreeMap<String, Duration>,
+    /// Median iterations a story took to pass, per story ID
+    per_story: HashMap<String, u32>,
+    /// Median iterations passing stories took
+    iterations: Option<u32>,
+}
+
+impl IterationEstimates {
+    /// Builds estimates from stored run metrics.
+    ///
+    /// An iteration is estimated as the median run of the story's agent (or
+    /// of any agent, for one never seen before) plus the median duration of
+    /// each quality gate. The iterations a story needs are the median it took
+    /// to pass before, or the median across all passing stories.
+    pub fn from_history(runs: &[RunMetrics]) -> Self {
+        let mut per_agent: HashMap<String, Vec<Duration>> = HashMap::new();
+        let mut per_gate: BTreeMap<String, Vec<Duration>> = BTreeMap::new();
+        for invocation in runs.iter().flat_map(|run| run.invocations.iter()) {
+            if invocation.role != "agent" || invocation.status != InvocationStatus::Succeeded {
+                continue;
+            }
+            per_agent
+                .entry(invocation.agent.clone())
+                .or_default()
+                .push(invocation.duration);
+            for (gate, duration) in &invocation.gate_durations {
+                per_gate.entry(gate.clone()).or_default().push(*duration);
+            }
+        }
+        let mut per_story: HashMap<String, Vec<u32>> = HashMap::new();
+        for step in runs.iter().flat_map(|run| run.steps.iter()) {
+            if step.success && step.attempts > 0 {
+                per_story
+                    .entry(step.step_id.clone())
+                    .or_default()
+                    .push(step.attempts);
+            }
+        }
+
+        let any_agent: Vec<Duration> = per_agent.values().flatten().copied().collect();
+        let iterations: Vec<u32> = per_story.values().flatten().copied().collect();
+        Self {
+            per_agent: per_agent
+                .into_iter()
+                .map(|(agent, durations)| (agent, median(durations)))
+                .collect(),
+            any_agent: (!any_agent.is_empty()).then(|| median(any_agent)),
+            per_gate: per_gate
+                .into_iter()
+                .map(|(gate, durations)| (gate, median(durations)))
+                .collect(),
+            per_story: per_story
+                .into_iter()
+                .map(|(id, attempts)| (id, median(attempts)))
+                .collect(),
+            iterations: (!iterations.is_empty()).then(|| median(iterations)),
+        }
+    }
+
+    /// Whether no agent runs were recorded to estimate from.
+    pub fn is_empty(&self) -> bool {
+        self.any_agent.is_none()
+    }
+
+    /// Estimated duration of one iteration run by `agent`, its gates
+    /// included; `None` without history.
+    pub fn iteration(&self, agent: &str) -> Option<Duration> {
+        let run = self.per_agent.get(agent).copied().or(self.any_agent)?;
+        Some(run + self.per_gate.values().sum::<Duration>())
+    }
+
+    /// Iterations `story_id` is expected to take to pass.
+    pub fn iterations(&self, story_id: &str) -> u32 {
+        self.per_story
+            .get(story_id)
+            .copied()
+            .or(self.iterations)
+            .unwrap_or(1)
+    }
+}
+
+/// A story scheduled into a projected batch.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PlannedStory {
+    /// Story ID
+    pub id: String,
+    /// Story title
+    pub title: String,
+    /// Story priority (lower runs first)
+    pub priority: u32,
+    /// Estimated duration in seconds
+    pub estimated_secs: u64,
+    /// Whether the estimate comes from this story's own run history
+    pub from_history: bool,
+    /// Whether the estimate is scaled from the story's `estimate`
+    pub from_estimate: bool,
+    /// Tokens the story's estimate forecasts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_tokens: Option<u64>,
+}
+
+/// A story held back from a batch because it shares target files with another.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PlannedConflict {
+    /// The deferred (lower-priority) story
+    pub deferred: String,
+    /// The story holding the lock
+    pub blocking: String,
+    /// Files one story writes and the other reads or writes
+    pub files: Vec<String>,
+}
+
+/// A group of stories projected to run concurrently.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PlannedBatch {
+    /// 1-based batch number
+    pub number: usize,
+    /// Stories in this batch
+    pub stories: Vec<PlannedStory>,
+    /// Stories deferred out of this batch by lock conflicts
+    pub conflicts: Vec<PlannedConflict>,
+    /// Ready stories left for a later batch by the concurrency limit
+    pub over_limit: Vec<String>,
+    /// Estimated batch duration (its slowest story) in seconds
+    pub estimated_secs: u64,
+    /// Tokens the estimates of the batch's stories forecast
+    pub estimated_tokens: u64,
+}
+
+/// The projected execution of a PRD.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExecutionPlan {
+    /// Concurrency limit the plan was built with
+    pub max_concurrency: u32,
+    /// Stories that already pass and will be skipped
+    pub already_passing: Vec<String>,
+    /// Projected batches in execution order
+    pub batches: Vec<PlannedBatch>,
+    /// Stories that can never become ready (e.g., unknown dependencies)
+    pub unreachable: Vec<String>,
+    /// Estimated total duration in seconds
+    pub estimated_secs: u64,
+    /// Tokens the stories' estimates forecast
+    pub tokens: TokenForecast,
+}
+
+impl ExecutionPlan {
+    /// Projects how a parallel run would schedule `stories` under `config`.
+    ///
+    /// Each batch assumes all of its stories finish before the next batch is
+    /// dispatched, so the estimate is an upper bound for continuous dispatch.
+    pub fn build(
+        stories: &[PrdUserStory],
+        config: &ParallelRunnerConfig,
+        estimates: &DurationEstimates,
+        tokens: TokenForecast,
+    ) -> Result<Self, DependencyError> {
+        let mut graph = DependencyGraph::from_stories(stories);
+        if config.infer_dependencies {
+            graph.infer_dependencies();
+        }
+        graph.validate()?;
+
+        let by_id: HashMap<&str, &PrdUserStory> = stories
+            .iter()
+            .map(|story| (story.id.as_str(), story))
+            .collect();
+        let already_passing: Vec<String> = stories
+            .iter()
+            .filter(|story| story.passes)
+            .map(|story| story.id.clone())
+            .collect();
+        let limit = (config.max_concurrency.max(1) as usize).min(stories.len().max(1));
+
+        // Passing stories satisfy dependencies just as they do in a real run
+        let mut completed: HashSet<String> = already_passing.iter().cloned().collect();
+        let mut batches = Vec::new();
+        loop {
+            let ready: Vec<_> = graph
+                .get_ready_stories(&completed)
+                .into_iter()
+                .cloned()
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+
+            let (runnable, deferred) = filter_conflicting_stories(ready);
+            let conflicts = deferred
+                .into_iter()
+                .map(|(deferred, blocking)| {
+                    let files = shared_files(&graph, &deferred, &blocking);
+                    PlannedConflict {
+                        deferred,
+                        blocking,
+                        files,
+                    }
+                })
+                .collect();
+
+            let over_limit = runnable
+                .iter()
+                .skip(limit)
+                .map(|story| story.id.clone())
+                .collect();
+            let planned: Vec<PlannedStory> = runnable
+                .iter()
+                .take(limit)
+                .map(|story| {
+                    let prd_story = by_id.get(story.id.as_str());
+                    let (estimate, basis) = estimates.estimate(
+                        &story.id,
+                        prd_story.and_then(|story| story.estimate.as_ref()),
+                    );
+                    PlannedStory {
+                        id: story.id.clone(),
+                        title: prd_story.map(|s| s.title.clone()).unwrap_or_default(),
+                        priority: story.priority,
+                        estimated_secs: estimate.as_secs(),
+                        from_history: basis == EstimateBasis::History,
+                        from_estimate: basis == EstimateBasis::Estimate,
+                        estimated_tokens: tokens.per_story.get(&story.id).copied(),
+                    }
+                })
+                .collect();
+
+            completed.extend(planned.iter().map(|story| story.id.clone()));
+            batches.push(PlannedBatch {
+                number: batches.len() + 1,
+                estimated_secs: planned
+                    .iter()
+                    .map(|story| story.estimated_secs)
+                    .max()
+                    .unwrap_or(0),
+                estimated_tokens: planned.iter().filter_map(|s| s.estimated_tokens).sum(),
+                stories: planned,
+                conflicts,
+                over_limit,
+            });
+        }
+
+        let unreachable = stories
+            .iter()
+            .filter(|story| !story.passes && !completed.contains(&story.id))
+            .map(|story| story.id.clone())
+            .collect();
+
+        Ok(Self {
+            max_concurrency: config.max_concurrency,
+            already_passing,
+            estimated_secs: batches.iter().map(|batch| batch.estimated_secs).sum(),
+            batches,
+            unreachable,
+            tokens,
+        })
+    }
+
+    /// Number of stories the plan would run.
+    pub fn planned_stories(&self) -> usize {
+        self.batches.iter().map(|batch| batch.stories.len()).sum()
+    }
+}
+
+/// Files one story writes and the other reads or writes.
+fn shared_files(graph: &DependencyGraph, a: &str, b: &str) -> Vec<String> {
+    match (graph.get_story(a), graph.get_story(b)) {
+        (Some(a), Some(b)) => a.conflicting_files(b),
+        _ => Vec::new(),
+    }
+}
+
+fn mean(durations: &[Duration]) -> Duration {
+    durations.iter().sum::<Duration>() / durations.len().max(1) as u32
+}
+
+/// Median of a non-empty list of samples, the lower one of an even count.
+fn median<T: Ord + Copy>(mut samples: Vec<T>) -> T {
+    samples.sort_unstable();
+    samples[(samples.len() - 1) / 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{AgentInvocation, RunMetricsCollector};
+
+    fn story(id: &str, priority: u32, depends_on: &[&str], files: &[&str]) -> PrdUserStory {
+        PrdUserStory {
+            id: id.to_string(),
+            title: format!("Story {}", id),
+            description: String::new(),
+            acceptance_criteria: Vec::new(),
+            priority,
+            passes: false,
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            target_files: files.iter().map(|s| s.to_string()).collect(),
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
+            epic: None,
+            prerequisites: Vec::new(),
+            estimate: None,
+        }
+    }
+
+    fn config(max_concurrency: u32) -> ParallelRunnerConfig {
+        ParallelRunnerConfig {
+            max_concurrency,
+            infer_dependencies: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_follows_dependencies_and_concurrency() {
+        let stories = vec![
+            story("US-001", 1, &[], &["a.rs"]),
+            story("US-002", 2, &[], &["b.rs"]),
+            story("US-003", 3, &[], &["c.rs"]),
+            story("US-004", 4, &["US-001"], &["d.rs"]),
+        ];
+        let plan = ExecutionPlan::build(
+            &stories,
+            &config(2),
+            &DurationEstimates::default(),
+            TokenForecast::default(),
+        )
+        .unwrap();
+
+        let ids: Vec<Vec<&str>> = plan
+            .batches
+            .iter()
+            .map(|batch| batch.stories.iter().map(|s| s.id.as_str()).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![vec!["US-001", "US-002"], vec!["US-003", "US-004"]]
+        );
+        assert_eq!(plan.batches[0].over_limit, vec!["US-003".to_string()]);
+        assert_eq!(plan.planned_stories(), 4);
+        assert_eq!(plan.estimated_secs, 2 * DEFAULT_STORY_ESTIMATE.as_secs());
+    }
+
+    #[test]
+    fn test_plan_reports_lock_conflicts() {
+        let stories = vec![
+            story("US-001", 1, &[], &["src/lib.rs", "a.rs"]),
+            story("US-002", 2, &[], &["src/lib.rs"]),
+        ];
+        let plan = ExecutionPlan::build(
+            &stories,
+            &config(4),
+            &DurationEstimates::default(),
+            TokenForecast::default(),
+        )
+        .unwrap();
+
+        assert_eq!(plan.batches.len(), 2);
+        assert_eq!(
+            plan.batches[0].conflicts,
+            vec![PlannedConflict {
+                deferred: "US-002".to_string(),
+                blocking: "US-001".to_string(),
+                files: vec!["src/lib.rs".to_string()],
+            }]
+        );
+        assert_eq!(plan.batches[1].stories[0].id, "US-002");
+    }
+
+    #[test]
+    fn test_plan_skips_passing_and_flags_unreachable() {
+        let mut done = story("US-001", 1, &[], &[]);
+        done.passes = true;
+        let stories = vec![done, story("US-002", 2, &["US-404"], &[])];
+        let plan = ExecutionPlan::build(
+            &stories,
+            &config(2),
+            &DurationEstimates::default(),
+            TokenForecast::default(),
+        )
+        .unwrap();
+
+        assert_eq!(plan.already_passing, vec!["US-001".to_string()]);
+        assert!(plan.batches.is_empty());
+        assert_eq!(plan.unreachable, vec!["US-002".to_string()]);
+    }
+
+    #[test]
+    fn test_estimates_from_history() {
+        let collector = RunMetricsCollector::new("run-1", 2);
+        let mut estimated = story("US-002", 2, &[], &[]);
+        estimated.estimate = Some(StoryEstimate {
+            points: Some(3),
+            ..Default::default()
+        });
+        collector.record_stories(&[estimated]);
+        collector.complete_step("US-001", true, 1, Duration::from_secs(60), None);
+        collector.complete_step("US-002", true, 1, Duration::from_secs(180), None);
+        let estimates = DurationEstimates::from_history(&[collector.finish()]);
+
+        assert_eq!(
+            estimates.estimate("US-001", None),
+            (Duration::from_secs(60), EstimateBasis::History)
+        );
+        // Unknown stories fall back to the mean across all recorded steps
+        assert_eq!(
+            estimates.estimate("US-003", None),
+            (Duration::from_secs(120), EstimateBasis::Fallback)
+        );
+        assert_eq!(
+            DurationEstimates::default().estimate("US-003", None),
+            (DEFAULT_STORY_ESTIMATE, EstimateBasis::Fallback)
+        );
+
+        // Estimated stories are scaled by the time per point, else per iteration
+        let points = StoryEstimate {
+            points: Some(5),
+            iterations: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            estimates.estimate("US-003", Some(&points)),
+            (Duration::from_secs(300), EstimateBasis::Estimate)
+        );
+        let iterations = StoryEstimate {
+            iterations: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            estimates.estimate("US-003", Some(&iterations)),
+            (Duration::from_secs(240), EstimateBasis::Estimate)
+        );
+        assert_eq!(
+            DurationEstimates::default().estimate("US-003", Some(&points)),
+            (DEFAULT_STORY_ESTIMATE, EstimateBasis::Fallback)
+        );
+    }
+
+    #[test]
+    fn test_iteration_estimates_from_history() {
+        let collector = RunMetricsCollector::new("run-1", 2);
+        let invocation = |agent: &str, secs: u64, gate_secs: u64| {
+            let mut invocation = AgentInvocation::new("US-001", 1, "agent", agent);
+            invocation.duration = Duration::from_secs(secs);
+            invocation
+                .gate_durations
+                .insert("tests".to_string(), Duration::from_secs(gate_secs));
+            invocation
+        };
+        collector.record_invocation(invocation("claude", 60, 10));
+        collector.record_invocation(invocation("claude", 90, 30));
+        collector.record_invocation(invocation("claude", 300, 20));
+        collector.record_invocation(invocation("codex", 30, 20));
+        let mut failed = invocation("codex", 600, 20);
+        failed.status = InvocationStatus::Failed;
+        collector.record_invocation(failed);
+        collector.complete_step("US-001", true, 3, Duration::from_secs(480), None);
+        collector.complete_step("US-002", true, 1, Duration::from_secs(60), None);
+        let estimates = IterationEstimates::from_history(&[collector.finish()]);
+
+        // The agent's median run plus the median of each gate
+        assert_eq!(
+            estimates.iteration("claude"),
+            Some(Duration::from_secs(110))
+        );
+        assert_eq!(estimates.iteration("codex"), Some(Duration::from_secs(50)));
+        // Unknown agents use the median across all agents
+        assert_eq!(estimates.iteration("gemini"), Some(Duration::from_secs(80)));
+        assert_eq!(estimates.iterations("US-001"), 3);
+        assert_eq!(estimates.iterations("US-003"), 1);
+
+        assert!(IterationEstimates::default().is_empty());
+        assert_eq!(IterationEstimates::default().iteration("claude"), None);
+        assert_eq!(IterationEstimates::default().iterations("US-001"), 1);
+    }
+
+    #[test]
+    fn test_plan_forecasts_tokens() {
+        let mut first = story("US-001", 1, &[], &[]);
+        first.estimate = Some(StoryEstimate {
+            tokens: Some(40_000),
+            ..Default::default()
+        });
+        let mut second = story("US-002", 2, &[], &[]);
+        second.estimate = Some(StoryEstimate {
+            tokens: Some(25_000),
+            ..Default::default()
+        });
+        let stories = vec![first, second, story("US-003", 3, &["US-001"], &[])];
+        let forecast = TokenForecast::new(&stories, &[]);
+        let plan = ExecutionPlan::build(
+            &stories,
+            &config(4),
+            &DurationEstimates::default(),
+            forecast,
+        )
+        .unwrap();
+
+        assert_eq!(plan.batches[0].estimated_tokens, 65_000);
+        assert_eq!(plan.batches[0].stories[0].estimated_tokens, Some(40_000));
+        assert_eq!(plan.batches[1].stories[0].estimated_tokens, None);
+        assert_eq!(plan.tokens.total, 65_000);
+        assert_eq!(plan.tokens.unforecast, vec!["US-003".to_string()]);
+    }
+}
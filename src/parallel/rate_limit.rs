@@ -0,0 +1,151 @@
+//! Agent launch rate limiting for parallel execution
+//!
+//! Every story iteration launches an agent process, and a burst of parallel
+//! stories can launch enough of them at once to trip the provider's rate
+//! limits. A token bucket shared by all workers spaces launches out to a
+//! configured number per minute; each story's handle tracks how long it waited
+//! so the delay can be attributed in run metrics.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Token bucket holding up to one minute's worth of launches.
+#[derive(Debug)]
+struct TokenBucket {
+    /// Maximum number of launches that can start back to back
+    capacity: f64,
+    /// Available launches; negative when launches are already reserved
+    tokens: f64,
+    /// Launches regained per second
+    refill_per_sec: f64,
+    /// When `tokens` was last refilled
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(launches_per_minute: u32, now: Instant) -> Self {
+        let capacity = f64::from(launches_per_minute.max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            updated: now,
+        }
+    }
+
+    /// Reserves a launch and returns how long the caller must wait before it.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        if let Some(elapsed) = now.checked_duration_since(self.updated) {
+            self.tokens =
+                (self.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+            self.updated = now;
+        }
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.refill_per_sec)
+        }
+    }
+}
+
+/// Limits agent process launches across all parallel workers.
+///
+/// Clones share the bucket and the wait counter; use [`Self::handle`] to get a
+/// handle with its own counter for a single story.
+#[derive(Clone, Debug)]
+pub struct LaunchRateLimiter {
+    bucket: Arc<Mutex<TokenBucket>>,
+    /// Total time this handle waited for launches, in milliseconds
+    waited_ms: Arc<AtomicU64>,
+}
+
+impl LaunchRateLimiter {
+    /// Creates a limiter allowing `launches` agent launches per minute.
+    ///
+    /// Up to `launches` agents may start back to back after an idle minute;
+    /// further launches are spaced evenly.
+    pub fn per_minute(launches: u32) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(TokenBucket::new(launches, Instant::now()))),
+            waited_ms: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// A handle sharing this limiter's bucket with its own wait counter.
+    pub fn handle(&self) -> Self {
+        Self {
+            bucket: self.bucket.clone(),
+            waited_ms: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Waits until an agent may be launched and returns how long that took.
+    ///
+    /// The launch is reserved before waiting, so concurrent callers are served
+    /// in the order they asked. A caller dropped while waiting forfeits its slot.
+    pub async fn acquire(&self) -> Duration {
+        let wait = match self.bucket.lock() {
+            Ok(mut bucket) => bucket.reserve(Instant::now()),
+            Err(_) => Duration::ZERO,
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+            self.waited_ms
+                .fetch_add(wait.as_millis() as u64, Ordering::Relaxed);
+        }
+        wait
+    }
+
+    /// Total time this handle has waited for launches.
+    pub fn waited(&self) -> Duration {
+        Duration::from_millis(self.waited_ms.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_spaces_launches_after_burst() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut bucket = TokenBucket::new(2, start);
+        let mut reserve = |now| bucket.reserve(now).as_secs_f64().round() as u64;
+
+        // A full minute's worth of launches may start immediately
+        assert_eq!(reserve(start), 0);
+        assert_eq!(reserve(start), 0);
+        // Later ones queue up one refill interval apart
+        assert_eq!(reserve(start), 30);
+        assert_eq!(reserve(start), 60);
+
+        // Two reserved launches are paid off after a minute
+        assert_eq!(reserve(at(60)), 30);
+        // Idle time refills the bucket up to its capacity only
+        assert_eq!(reserve(at(600)), 0);
+        assert_eq!(reserve(at(600)), 0);
+        assert_eq!(reserve(at(600)), 30);
+    }
+
+    #[tokio::test]
+    async fn test_handles_track_their_own_wait() {
+        // 10 launches per second, so a launch past the burst waits up to 100ms
+        let limiter = LaunchRateLimiter::per_minute(600);
+        let first = limiter.handle();
+        let second = limiter.handle();
+
+        for _ in 0..600 {
+            assert_eq!(first.acquire().await, Duration::ZERO);
+        }
+        let wait = second.acquire().await;
+        assert!(wait <= Duration::from_millis(100));
+        assert_eq!(first.waited(), Duration::ZERO);
+        assert_eq!(
+            second.waited(),
+            Duration::from_millis(wait.as_millis() as u64)
+        );
+    }
+}
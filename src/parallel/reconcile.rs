@@ -1,11 +1,29 @@
 //! Reconciliation of parallel execution results
 //!
 //! This module handles detection and reporting of issues that may arise from parallel
-//! execution of stories, including git conflicts, type mismatches, and duplicate imports.
+//! execution of stories, including git conflicts, compile errors, duplicate
+//! definitions, and code gated on features the package does not declare.
+//!
+//! For Rust projects the whole workspace is checked with
+//! `cargo check --workspace --message-format=json`, so every issue carries the
+//! file (and line) it was found in and can be traced to the stories that
+//! target that file.
 
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use serde_json::Value;
+
+/// Error codes for names defined more than once: items, imports, extern
+/// crates, impl members, struct fields and trait impls.
+const DUPLICATE_DEFINITION_CODES: &[&str] = &[
+    "E0119", "E0124", "E0201", "E0252", "E0254", "E0255", "E0259", "E0260", "E0428", "E0592",
+];
+
+/// Story diffs longer than this are cut before they go into a prompt.
+const MAX_STORY_DIFF_BYTES: usize = 100_000;
+
 /// Issues that can be detected during reconciliation
 #[allow(dead_code)]
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -24,6 +42,198 @@ pub enum ReconciliationIssue {
     },
     /// Duplicate import detected
     ImportDuplicate,
+    /// A compile error reported by `cargo check`
+    CompileError {
+        /// File the error points at, or "unknown"
+        file: String,
+        /// Line the error points at, if known
+        line: Option<u32>,
+        /// Compiler error code (e.g. "E0425"), if any
+        code: Option<String>,
+        /// Error message from the compiler
+        message: String,
+    },
+    /// A name defined more than once, as when two stories add the same item
+    /// or import to a module
+    DuplicateDefinition {
+        /// The name defined more than once
+        symbol: String,
+        /// Every definition, as `file:line`
+        locations: Vec<String>,
+    },
+    /// Code gated on a Cargo feature its package does not declare
+    FeatureDrift {
+        /// The undeclared feature
+        feature: String,
+        /// File with the `cfg(feature = ...)` condition
+        file: String,
+        /// Line of the condition
+        line: u32,
+    },
+    /// A story branch could not be merged into the integration branch
+    MergeConflict {
+        /// Story whose branch failed to merge
+        story_id: String,
+        /// The story branch that was left unmerged
+        branch: String,
+        /// Files that conflicted during the merge
+        affected_files: Vec<String>,
+    },
+}
+
+impl ReconciliationIssue {
+    /// Files the issue was found in, so it can be traced to the stories
+    /// that target them
+    pub fn files(&self) -> Vec<String> {
+        match self {
+            ReconciliationIssue::GitConflict { affected_files }
+            | ReconciliationIssue::MergeConflict { affected_files, .. } => affected_files.clone(),
+            ReconciliationIssue::TypeMismatch { file, .. }
+            | ReconciliationIssue::CompileError { file, .. } => {
+                if file == "unknown" {
+                    Vec::new()
+                } else {
+                    vec![file.clone()]
+                }
+            }
+            ReconciliationIssue::DuplicateDefinition { locations, .. } => locations
+                .iter()
+                .map(|location| {
+                    location
+                        .rsplit_once(':')
+                        .map_or(location.clone(), |(file, _)| file.to_string())
+                })
+                .collect(),
+            ReconciliationIssue::FeatureDrift { file, .. } => vec![file.clone()],
+            ReconciliationIssue::ImportDuplicate => Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for ReconciliationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReconciliationIssue::GitConflict { affected_files } => {
+                write!(f, "git conflict in: {}", affected_files.join(", "))
+            }
+            ReconciliationIssue::TypeMismatch { file, error } => {
+                write!(f, "type error in {}: {}", file, error)
+            }
+            ReconciliationIssue::ImportDuplicate => write!(f, "duplicate import detected"),
+            ReconciliationIssue::CompileError {
+                file,
+                line,
+                message,
+                ..
+            } => match line {
+                Some(line) => write!(f, "compile error in {}:{}: {}", file, line, message),
+                None => write!(f, "compile error in {}: {}", file, message),
+            },
+            ReconciliationIssue::DuplicateDefinition { symbol, locations } => {
+                write!(
+                    f,
+                    "{} defined more than once: {}",
+                    symbol,
+                    locations.join(", ")
+                )
+            }
+            ReconciliationIssue::FeatureDrift {
+                feature,
+                file,
+                line,
+            } => write!(
+                f,
+                "undeclared feature {} used in {}:{}",
+                feature, file, line
+            ),
+            ReconciliationIssue::MergeConflict {
+                story_id,
+                affected_files,
+                ..
+            } => write!(
+                f,
+                "merge conflict for {} in: {}",
+                story_id,
+                affected_files.join(", ")
+            ),
+        }
+    }
+}
+
+/// Subject prefix of the commits holding a story's changes, which are
+/// committed as `feat: <story> - <title>`.
+fn story_commit_grep(story_id: &str) -> String {
+    format!("feat: {} - ", story_id)
+}
+
+/// Files `story_id`'s commits changed, sorted; empty when the story has no
+/// commits to go by.
+pub fn story_files(project_root: &Path, story_id: &str) -> Vec<String> {
+    let grep = story_commit_grep(story_id);
+    let output = Command::new("git")
+        .args([
+            "log",
+            "--name-only",
+            "--format=",
+            "--fixed-strings",
+            "--grep",
+            grep.as_str(),
+        ])
+        .current_dir(project_root)
+        .output();
+    let Ok(output) = output else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// What `story_ids` committed to `files` (to any file when `files` is
+/// empty), as a unified diff cut to [`MAX_STORY_DIFF_BYTES`].
+pub fn story_diffs(project_root: &Path, story_ids: &[String], files: &[String]) -> String {
+    let mut diff = String::new();
+    for story_id in story_ids {
+        let grep = story_commit_grep(story_id);
+        let mut args = vec![
+            "log",
+            "-p",
+            "--format=# %s",
+            "--fixed-strings",
+            "--grep",
+            grep.as_str(),
+            "--",
+        ];
+        args.extend(files.iter().map(String::as_str));
+        let Ok(output) = Command::new("git")
+            .args(&args)
+            .current_dir(project_root)
+            .output()
+        else {
+            continue;
+        };
+        if output.status.success() {
+            diff.push_str(&String::from_utf8_lossy(&output.stdout));
+        }
+    }
+
+    if diff.len() > MAX_STORY_DIFF_BYTES {
+        let mut end = MAX_STORY_DIFF_BYTES;
+        while !diff.is_char_boundary(end) {
+            end -= 1;
+        }
+        diff.truncate(end);
+        diff.push_str("\n... (diff truncated)");
+    }
+    diff
 }
 
 /// Result of reconciliation analysis
@@ -44,6 +254,8 @@ pub enum ReconciliationResult {
 pub struct ReconciliationEngine {
     /// Root directory of the project
     project_root: PathBuf,
+    /// Story branch merge failures reported by the scheduler
+    merge_conflicts: Vec<ReconciliationIssue>,
 }
 
 #[allow(dead_code)]
@@ -53,7 +265,20 @@ impl ReconciliationEngine {
     /// # Arguments
     /// * `project_root` - The root directory of the project to reconcile
     pub fn new(project_root: PathBuf) -> Self {
-        Self { project_root }
+        Self {
+            project_root,
+            merge_conflicts: Vec::new(),
+        }
+    }
+
+    /// Includes story branch merge failures in the reconciliation result
+    ///
+    /// # Arguments
+    /// * `merge_conflicts` - `ReconciliationIssue::MergeConflict` issues recorded while
+    ///   merging story branches into the integration branch
+    pub fn with_merge_conflicts(mut self, merge_conflicts: Vec<ReconciliationIssue>) -> Self {
+        self.merge_conflicts = merge_conflicts;
+        self
     }
 
     /// Checks for git merge conflicts in the project
@@ -109,15 +334,19 @@ impl ReconciliationEngine {
         }
     }
 
-    /// Checks for type errors in the project by running `cargo check`
+    /// Checks the whole workspace for compile errors by running `cargo check`
     ///
-    /// Runs `cargo check` and parses the output for error messages.
-    /// Returns a vector of `ReconciliationIssue::TypeMismatch` for each error found.
+    /// Runs `cargo check --workspace --message-format=json` and turns the
+    /// compiler's diagnostics into issues: `DuplicateDefinition` for names
+    /// defined more than once, `FeatureDrift` for `cfg(feature = ...)`
+    /// conditions on undeclared features, and `CompileError` for every other
+    /// error. When cargo fails before compiling (e.g. a broken manifest), its
+    /// error lines are reported as `CompileError`s without a line.
     ///
     /// # Returns
-    /// A vector of `ReconciliationIssue` containing any detected type errors.
-    /// Returns an empty vector if no type errors are detected or if this is not a Rust project.
-    pub fn check_type_errors(&self) -> Vec<ReconciliationIssue> {
+    /// A vector of `ReconciliationIssue` containing any detected errors.
+    /// Returns an empty vector if no errors are detected or if this is not a Rust project.
+    pub fn check_compile_errors(&self) -> Vec<ReconciliationIssue> {
         // Check if this is a Rust project by looking for Cargo.toml
         let cargo_toml = self.project_root.join("Cargo.toml");
         if !cargo_toml.exists() {
@@ -125,7 +354,7 @@ impl ReconciliationEngine {
         }
 
         let output = Command::new("cargo")
-            .args(["check", "--message-format=short"])
+            .args(["check", "--workspace", "--message-format=json"])
             .current_dir(&self.project_root)
             .output();
 
@@ -134,42 +363,130 @@ impl ReconciliationEngine {
             Err(_) => return Vec::new(),
         };
 
-        // cargo check returns non-zero exit status when there are errors
-        // We need to parse stderr for error messages
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut issues = Self::parse_cargo_messages(&String::from_utf8_lossy(&output.stdout));
+        if output.status.success() || !issues.is_empty() {
+            return issues;
+        }
 
-        // Parse error messages in the short format: "file:line:col: error: message"
-        let mut issues = Vec::new();
+        // Cargo failed without compiler diagnostics; report its own errors
+        let stderr = String::from_utf8_lossy(&output.stderr);
         for line in stderr.lines() {
-            // Match lines that look like error messages
-            // Format: "src/file.rs:10:5: error[E0001]: some error message"
-            // Or: "error[E0001]: some error message"
-            // Or: "error: some error message"
             if line.starts_with("error") || line.contains(": error") {
-                let (file, error) = Self::parse_error_line(line);
-                issues.push(ReconciliationIssue::TypeMismatch { file, error });
+                let (file, message) = Self::parse_error_line(line);
+                issues.push(ReconciliationIssue::CompileError {
+                    file,
+                    line: None,
+                    code: None,
+                    message,
+                });
             }
         }
+        issues
+    }
 
+    /// Turns the JSON messages of `cargo check --message-format=json` into
+    /// issues, once each even when several targets report the same diagnostic
+    fn parse_cargo_messages(stdout: &str) -> Vec<ReconciliationIssue> {
+        let mut issues = Vec::new();
+        let mut seen = HashSet::new();
+        for line in stdout.lines() {
+            let Ok(message) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            if message["reason"] != "compiler-message" {
+                continue;
+            }
+            if let Some(issue) = Self::diagnostic_issue(&message["message"]) {
+                if seen.insert(format!("{:?}", issue)) {
+                    issues.push(issue);
+                }
+            }
+        }
         issues
     }
 
+    /// The issue a compiler diagnostic reports, if it is one
+    fn diagnostic_issue(diagnostic: &Value) -> Option<ReconciliationIssue> {
+        let level = diagnostic["level"].as_str()?;
+        let text = diagnostic["message"].as_str()?;
+        let code = diagnostic["code"]["code"].as_str();
+        let spans = diagnostic["spans"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let location = |span: &Value| -> Option<(String, u32)> {
+            Some((
+                span["file_name"].as_str()?.to_string(),
+                span["line_start"].as_u64()? as u32,
+            ))
+        };
+        let primary = spans
+            .iter()
+            .find(|span| span["is_primary"] == true)
+            .and_then(location);
+
+        if code == Some("unexpected_cfgs") {
+            let for_feature = diagnostic["children"].as_array().is_some_and(|children| {
+                children.iter().any(|child| {
+                    child["message"]
+                        .as_str()
+                        .is_some_and(|message| message.contains("for `feature`"))
+                })
+            });
+            let (file, line) = primary?;
+            return for_feature.then(|| ReconciliationIssue::FeatureDrift {
+                feature: quoted_names(text).pop().unwrap_or_default(),
+                file,
+                line,
+            });
+        }
+        if level != "error" || text.starts_with("aborting due to") {
+            return None;
+        }
+        if code.is_some_and(|code| DUPLICATE_DEFINITION_CODES.contains(&code)) {
+            let mut locations: Vec<String> = spans
+                .iter()
+                .filter_map(location)
+                .map(|(file, line)| format!("{}:{}", file, line))
+                .collect();
+            locations.sort();
+            locations.dedup();
+            return Some(ReconciliationIssue::DuplicateDefinition {
+                symbol: quoted_names(text)
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| text.to_string()),
+                locations,
+            });
+        }
+        let (file, line) = match primary {
+            Some((file, line)) => (file, Some(line)),
+            None => ("unknown".to_string(), None),
+        };
+        Some(ReconciliationIssue::CompileError {
+            file,
+            line,
+            code: code.map(str::to_string),
+            message: text.to_string(),
+        })
+    }
+
     /// Runs full reconciliation checking for all known issue types
     ///
-    /// This method combines all individual checks (git conflicts, type errors)
+    /// This method combines all individual checks (git conflicts, compile errors)
     /// and returns a comprehensive `ReconciliationResult`.
     ///
     /// # Returns
     /// `ReconciliationResult::Clean` if no issues are found, or
     /// `ReconciliationResult::IssuesFound` with a list of all detected issues.
     pub fn reconcile(&self) -> ReconciliationResult {
-        let mut all_issues = Vec::new();
+        let mut all_issues = self.merge_conflicts.clone();
 
         // Check for git conflicts
         all_issues.extend(self.check_git_conflicts());
 
-        // Check for type errors
-        all_issues.extend(self.check_type_errors());
+        // Check the workspace for compile errors
+        all_issues.extend(self.check_compile_errors());
 
         if all_issues.is_empty() {
             ReconciliationResult::Clean
@@ -207,6 +524,16 @@ impl ReconciliationEngine {
     }
 }
 
+/// The `quoted` names in a compiler message, in order.
+fn quoted_names(message: &str) -> Vec<String> {
+    message
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,28 +581,28 @@ mod tests {
     }
 
     #[test]
-    fn test_check_type_errors_valid_project() {
+    fn test_check_compile_errors_valid_project() {
         // Use current directory which is a valid Rust project
         let cwd = env::current_dir().expect("Failed to get current directory");
         let engine = ReconciliationEngine::new(cwd);
-        let issues = engine.check_type_errors();
+        let issues = engine.check_compile_errors();
         // A valid project should have no type errors
         assert!(issues.is_empty());
     }
 
     #[test]
-    fn test_check_type_errors_non_rust_project() {
+    fn test_check_compile_errors_non_rust_project() {
         // Use a directory without Cargo.toml
         let engine = ReconciliationEngine::new(PathBuf::from("/tmp"));
-        let issues = engine.check_type_errors();
+        let issues = engine.check_compile_errors();
         // Should return empty for non-Rust projects
         assert!(issues.is_empty());
     }
 
     #[test]
-    fn test_check_type_errors_invalid_directory() {
+    fn test_check_compile_errors_invalid_directory() {
         let engine = ReconciliationEngine::new(PathBuf::from("/nonexistent/path"));
-        let issues = engine.check_type_errors();
+        let issues = engine.check_compile_errors();
         // Should return empty on error
         assert!(issues.is_empty());
     }
@@ -304,6 +631,80 @@ mod tests {
         assert_eq!(error, "error: aborting due to 2 previous errors");
     }
 
+    #[test]
+    fn test_parse_cargo_messages() {
+        let diagnostic = |level: &str, code: &str, message: &str, spans: &str, children: &str| {
+            format!(
+                r#"{{"reason":"compiler-message","target":{{"name":"app"}},"message":{{"level":"{}","code":{},"message":"{}","spans":[{}],"children":[{}]}}}}"#,
+                level, code, message, spans, children
+            )
+        };
+        let span = |file: &str, line: u32, primary: bool| {
+            format!(
+                r#"{{"file_name":"{}","line_start":{},"is_primary":{}}}"#,
+                file, line, primary
+            )
+        };
+        let duplicate = diagnostic(
+            "error",
+            r#"{"code":"E0428"}"#,
+            "the name `parse` is defined multiple times",
+            &[span("src/lib.rs", 9, true), span("src/lib.rs", 2, false)].join(","),
+            "",
+        );
+        let stdout = [
+            r#"{"reason":"compiler-artifact","target":{"name":"dep"}}"#.to_string(),
+            duplicate.clone(),
+            // The same diagnostic reported again for another target
+            duplicate,
+            diagnostic(
+                "warning",
+                r#"{"code":"unexpected_cfgs"}"#,
+                "unexpected `cfg` condition value: `fancy`",
+                &span("crates/core/src/fmt.rs", 5, true),
+                r#"{"level":"note","message":"no expected values for `feature`"}"#,
+            ),
+            diagnostic(
+                "warning",
+                r#"{"code":"unused_imports"}"#,
+                "unused import: `std::fmt`",
+                &span("src/lib.rs", 1, true),
+                "",
+            ),
+            diagnostic(
+                "error",
+                r#"{"code":"E0308"}"#,
+                "mismatched types",
+                &span("src/api.rs", 14, true),
+                "",
+            ),
+            diagnostic("error", "null", "aborting due to 2 previous errors", "", ""),
+            r#"{"reason":"build-finished","success":false}"#.to_string(),
+        ]
+        .join("\n");
+
+        assert_eq!(
+            ReconciliationEngine::parse_cargo_messages(&stdout),
+            vec![
+                ReconciliationIssue::DuplicateDefinition {
+                    symbol: "parse".to_string(),
+                    locations: vec!["src/lib.rs:2".to_string(), "src/lib.rs:9".to_string()],
+                },
+                ReconciliationIssue::FeatureDrift {
+                    feature: "fancy".to_string(),
+                    file: "crates/core/src/fmt.rs".to_string(),
+                    line: 5,
+                },
+                ReconciliationIssue::CompileError {
+                    file: "src/api.rs".to_string(),
+                    line: Some(14),
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_type_mismatch_issue_contains_details() {
         let issue = ReconciliationIssue::TypeMismatch {
@@ -318,6 +719,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_issue_files_and_description() {
+        let duplicate = ReconciliationIssue::DuplicateDefinition {
+            symbol: "Cart".to_string(),
+            locations: vec!["src/cart.rs:3".to_string(), "src/lib.rs:12".to_string()],
+        };
+        assert_eq!(duplicate.files(), vec!["src/cart.rs", "src/lib.rs"]);
+        assert_eq!(
+            duplicate.to_string(),
+            "Cart defined more than once: src/cart.rs:3, src/lib.rs:12"
+        );
+
+        let compile = ReconciliationIssue::CompileError {
+            file: "unknown".to_string(),
+            line: None,
+            code: None,
+            message: "linking failed".to_string(),
+        };
+        assert!(compile.files().is_empty());
+        assert_eq!(
+            compile.to_string(),
+            "compile error in unknown: linking failed"
+        );
+        assert!(ReconciliationIssue::ImportDuplicate.files().is_empty());
+    }
+
     #[test]
     fn test_reconcile_clean_project() {
         // Use current directory which should be a clean project
@@ -328,6 +755,55 @@ mod tests {
         assert_eq!(result, ReconciliationResult::Clean);
     }
 
+    #[test]
+    fn test_reconcile_includes_merge_conflicts() {
+        let conflict = ReconciliationIssue::MergeConflict {
+            story_id: "US-001".to_string(),
+            branch: "ralph/US-001".to_string(),
+            affected_files: vec!["src/lib.rs".to_string()],
+        };
+        let engine = ReconciliationEngine::new(PathBuf::from("/tmp"))
+            .with_merge_conflicts(vec![conflict.clone()]);
+        let result = engine.reconcile();
+        assert_eq!(result, ReconciliationResult::IssuesFound(vec![conflict]));
+    }
+
+    #[test]
+    fn test_story_diffs() {
+        use crate::parallel::worktree::git;
+
+        let repo = tempfile::TempDir::new().unwrap();
+        let root = repo.path();
+        git(root, &["init", "-q"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        for (story, file) in [
+            ("US-001", "cart.rs"),
+            ("US-002", "pay.rs"),
+            ("US-010", "cart.rs"),
+        ] {
+            std::fs::write(
+                root.join(file),
+                format!("fn {}() {{}}\n", story.replace('-', "_")),
+            )
+            .unwrap();
+            git(root, &["add", "-A"]).unwrap();
+            let message = format!("feat: {} - Story", story);
+            git(root, &["commit", "-q", "-m", &message]).unwrap();
+        }
+
+        let diff = story_diffs(root, &["US-001".to_string(), "US-002".to_string()], &[]);
+        assert!(diff.contains("# feat: US-001 - Story"));
+        assert!(diff.contains("+fn US_002() {}"));
+        assert!(!diff.contains("US_010"));
+
+        let diff = story_diffs(root, &["US-002".to_string()], &["cart.rs".to_string()]);
+        assert!(diff.is_empty());
+
+        assert_eq!(story_files(root, "US-010"), vec!["cart.rs"]);
+        assert!(story_files(root, "US-003").is_empty());
+    }
+
     #[test]
     fn test_reconcile_non_rust_project() {
         // Use /tmp which has no Cargo.toml
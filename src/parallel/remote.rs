@@ -0,0 +1,1097 @@
+//! Distributed story execution on remote workers
+//!
+//! A coordinator run (`ralph --parallel --remote-workers ...`) hands stories to
+//! `ralph worker` processes on other machines over HTTP+JSON:
+//!
+//! - `GET /v1/health` reports the worker's capacity and running stories
+//! - `POST /v1/stories` accepts a [`StoryAssignment`] and starts it
+//! - `GET /v1/stories/{id}` reports progress and, once finished, the [`StoryReport`]
+//! - `DELETE /v1/stories/{id}` cancels a story
+//!
+//! Before dispatching a story the coordinator pushes its `HEAD` to a run
+//! branch. The worker clones that branch, runs the agent and quality gates, and
+//! pushes the story's commits to a story branch, which the coordinator merges
+//! back. Workers only see committed work.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use axum::extract::{Path as UrlPath, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use tokio::sync::watch;
+
+use crate::mcp::tools::executor::{ExecutionResult, ExecutorConfig, ExecutorError, StoryExecutor};
+use crate::parallel::worktree::{git, sanitize_name, WorktreeError};
+
+/// Prefix for the branches a coordinator run pushes for its workers.
+const BRANCH_PREFIX: &str = "ralph/remote";
+
+/// Consecutive failed status requests before an assignment is given up on.
+const MAX_POLL_FAILURES: u32 = 5;
+
+/// A story handed to a remote worker.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoryAssignment {
+    /// ID of the story to implement
+    pub story_id: String,
+    /// Git URL the worker clones from and pushes to
+    pub repo_url: String,
+    /// Branch holding the coordinator's committed work
+    pub base_branch: String,
+    /// Branch the worker pushes the story's commits to
+    pub story_branch: String,
+    /// Contents of the coordinator's PRD file
+    pub prd: String,
+    /// Maximum agent iterations for the story
+    pub max_iterations: u32,
+}
+
+impl StoryAssignment {
+    /// Checks the repository URL and branch names before they reach git,
+    /// which would read a value starting with `-` as an option.
+    async fn validate(&self, dir: &Path) -> Result<(), String> {
+        if self.repo_url.starts_with('-') {
+            return Err(format!("Invalid repository URL '{}'", self.repo_url));
+        }
+        for branch in [&self.base_branch, &self.story_branch] {
+            let valid = !branch.starts_with('-')
+                && git_blocking(dir, &["check-ref-format", "--branch", branch])
+                    .await
+                    .is_ok();
+            if !valid {
+                return Err(format!("Invalid branch name '{}'", branch));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of a story run on a remote worker.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoryReport {
+    /// Whether the story passed its quality gates and was pushed
+    pub success: bool,
+    /// Agent iterations used
+    pub iterations: u32,
+    /// Why the story failed
+    pub error: Option<String>,
+    /// Commit created for the story
+    pub commit: Option<String>,
+    /// Files the story changed
+    pub files_changed: Vec<String>,
+}
+
+/// Progress of an assignment on a worker.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum AssignmentStatus {
+    /// The worker is cloning the repository or running the story.
+    Running {
+        /// Current agent iteration (0 while cloning)
+        iteration: u32,
+        /// Maximum agent iterations
+        max_iterations: u32,
+    },
+    /// The story finished.
+    Finished {
+        /// The outcome
+        report: StoryReport,
+    },
+}
+
+/// Response to an accepted assignment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssignmentAccepted {
+    /// ID to poll the assignment's status with
+    pub assignment_id: String,
+}
+
+/// Worker load, as reported by `GET /v1/health`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerHealth {
+    /// Stories the worker runs at once
+    pub capacity: usize,
+    /// Stories currently running
+    pub running: usize,
+}
+
+/// Errors from dispatching a story to a remote worker.
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    /// The worker could not be reached or sent an unexpected response.
+    #[error("Remote worker {url}: {message}")]
+    Http {
+        /// Base URL of the worker
+        url: String,
+        /// What went wrong
+        message: String,
+    },
+    /// The worker no longer knows the assignment (e.g., it restarted).
+    #[error("Remote worker {url} lost assignment {assignment_id}")]
+    AssignmentLost {
+        /// Base URL of the worker
+        url: String,
+        /// The lost assignment
+        assignment_id: String,
+    },
+    /// A git command on the coordinator failed.
+    #[error(transparent)]
+    Git(#[from] WorktreeError),
+    /// The story was cancelled before it finished.
+    #[error("Remote story was cancelled")]
+    Cancelled,
+}
+
+impl From<RemoteError> for ExecutorError {
+    fn from(err: RemoteError) -> Self {
+        match err {
+            RemoteError::Cancelled => ExecutorError::Cancelled,
+            RemoteError::Git(err) => ExecutorError::GitError(err.to_string()),
+            other => ExecutorError::AgentError(other.to_string()),
+        }
+    }
+}
+
+/// Settings for a `ralph worker` process.
+#[derive(Clone, Debug)]
+pub struct WorkerConfig {
+    /// Address to bind to (e.g., "0.0.0.0" or "127.0.0.1")
+    pub bind_address: String,
+    /// Port to listen on
+    pub port: u16,
+    /// Stories run at once
+    pub capacity: usize,
+    /// Agent command used for every story
+    pub agent_command: String,
+    /// Directory stories are cloned into
+    pub work_dir: PathBuf,
+    /// Token coordinators must send as a bearer token
+    pub token: Option<String>,
+}
+
+impl WorkerConfig {
+    /// Creates a worker running stories with `agent_command`, one at a time,
+    /// on 127.0.0.1:7870.
+    pub fn new(agent_command: impl Into<String>) -> Self {
+        Self {
+            bind_address: "127.0.0.1".to_string(),
+            port: 7870,
+            capacity: 1,
+            agent_command: agent_command.into(),
+            work_dir: std::env::temp_dir().join("ralph-worker"),
+            token: None,
+        }
+    }
+
+    /// Set the bind address
+    pub fn with_bind_address(mut self, address: impl Into<String>) -> Self {
+        self.bind_address = address.into();
+        self
+    }
+
+    /// Set the port
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Set how many stories run at once
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Set the directory stories are cloned into
+    pub fn with_work_dir(mut self, work_dir: impl Into<PathBuf>) -> Self {
+        self.work_dir = work_dir.into();
+        self
+    }
+
+    /// Require coordinators to send `token`
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Get the full bind address (ip:port)
+    pub fn socket_addr(&self) -> String {
+        format!("{}:{}", self.bind_address, self.port)
+    }
+}
+
+/// An assignment known to the worker.
+#[derive(Debug)]
+struct Assignment {
+    status: AssignmentStatus,
+    cancel: watch::Sender<bool>,
+}
+
+/// Shared state of the worker server.
+#[derive(Clone, Debug)]
+struct WorkerState {
+    config: Arc<WorkerConfig>,
+    assignments: Arc<Mutex<HashMap<String, Assignment>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl WorkerState {
+    fn assignments(&self) -> MutexGuard<'_, HashMap<String, Assignment>> {
+        self.assignments
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set_status(&self, assignment_id: &str, status: AssignmentStatus) {
+        if let Some(assignment) = self.assignments().get_mut(assignment_id) {
+            assignment.status = status;
+        }
+    }
+
+    /// Whether the request carries the configured token, if any.
+    ///
+    /// The token is compared in constant time so it cannot be guessed byte by
+    /// byte from response times.
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(ref token) = self.config.token else {
+            return true;
+        };
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|sent| bool::from(sent.as_bytes().ct_eq(token.as_bytes())))
+    }
+}
+
+fn unauthorized() -> Response {
+    (StatusCode::UNAUTHORIZED, "invalid worker token").into_response()
+}
+
+fn running_count(assignments: &HashMap<String, Assignment>) -> usize {
+    assignments
+        .values()
+        .filter(|assignment| matches!(assignment.status, AssignmentStatus::Running { .. }))
+        .count()
+}
+
+/// Creates the worker router with all routes.
+pub fn worker_router(config: WorkerConfig) -> Router {
+    let state = WorkerState {
+        config: Arc::new(config),
+        assignments: Arc::new(Mutex::new(HashMap::new())),
+        next_id: Arc::new(AtomicU64::new(1)),
+    };
+    Router::new()
+        .route("/v1/health", get(health_handler))
+        .route("/v1/stories", post(submit_handler))
+        .route(
+            "/v1/stories/{id}",
+            get(status_handler).delete(cancel_handler),
+        )
+        .with_state(state)
+}
+
+/// Serves the worker API until the process is stopped.
+///
+/// A worker runs an agent on whatever repository an assignment names, so
+/// one reachable from other machines refuses to start without a token.
+pub async fn serve_worker(config: WorkerConfig) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(config.socket_addr()).await?;
+    if config.token.is_none() && !listener.local_addr()?.ip().is_loopback() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Refusing to listen on {} without a token; set RALPH_WORKER_TOKEN",
+                config.socket_addr()
+            ),
+        ));
+    }
+    axum::serve(listener, worker_router(config)).await
+}
+
+/// GET /v1/health
+async fn health_handler(State(state): State<WorkerState>, headers: HeaderMap) -> Response {
+    if !state.authorized(&headers) {
+        return unauthorized();
+    }
+    let running = running_count(&state.assignments());
+    Json(WorkerHealth {
+        capacity: state.config.capacity,
+        running,
+    })
+    .into_response()
+}
+
+/// POST /v1/stories
+///
+/// Starts the story and returns 202 with its assignment ID, or 503 when the
+/// worker is already running as many stories as it can.
+async fn submit_handler(
+    State(state): State<WorkerState>,
+    headers: HeaderMap,
+    Json(assignment): Json<StoryAssignment>,
+) -> Response {
+    if !state.authorized(&headers) {
+        return unauthorized();
+    }
+    let (cancel_tx, cancel_rx) = watch::channel(false);
+    let assignment_id = {
+        let mut assignments = state.assignments();
+        if running_count(&assignments) >= state.config.capacity {
+            return (StatusCode::SERVICE_UNAVAILABLE, "worker is at capacity").into_response();
+        }
+        let assignment_id = format!(
+            "{}-{}",
+            sanitize_name(&assignment.story_id),
+            state.next_id.fetch_add(1, Ordering::Relaxed)
+        );
+        assignments.insert(
+            assignment_id.clone(),
+            Assignment {
+                status: AssignmentStatus::Running {
+                    iteration: 0,
+                    max_iterations: assignment.max_iterations,
+                },
+                cancel: cancel_tx,
+            },
+        );
+        assignment_id
+    };
+
+    let task_state = state.clone();
+    let task_id = assignment_id.clone();
+    tokio::spawn(async move {
+        let report = run_assignment(&task_state, &task_id, &assignment, cancel_rx)
+            .await
+            .unwrap_or_else(|error| StoryReport {
+                error: Some(error),
+                ..Default::default()
+            });
+        task_state.set_status(&task_id, AssignmentStatus::Finished { report });
+    });
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AssignmentAccepted { assignment_id }),
+    )
+        .into_response()
+}
+
+/// GET /v1/stories/{id}
+///
+/// A finished assignment is forgotten once its report has been returned.
+async fn status_handler(
+    State(state): State<WorkerState>,
+    headers: HeaderMap,
+    UrlPath(assignment_id): UrlPath<String>,
+) -> Response {
+    if !state.authorized(&headers) {
+        return unauthorized();
+    }
+    let mut assignments = state.assignments();
+    let Some(assignment) = assignments.get(&assignment_id) else {
+        return (StatusCode::NOT_FOUND, "unknown assignment").into_response();
+    };
+    let status = assignment.status.clone();
+    if matches!(status, AssignmentStatus::Finished { .. }) {
+        assignments.remove(&assignment_id);
+    }
+    Json(status).into_response()
+}
+
+/// DELETE /v1/stories/{id}
+async fn cancel_handler(
+    State(state): State<WorkerState>,
+    headers: HeaderMap,
+    UrlPath(assignment_id): UrlPath<String>,
+) -> Response {
+    if !state.authorized(&headers) {
+        return unauthorized();
+    }
+    match state.assignments().get(&assignment_id) {
+        Some(assignment) => {
+            let _ = assignment.cancel.send(true);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => (StatusCode::NOT_FOUND, "unknown assignment").into_response(),
+    }
+}
+
+/// Clones the base branch, runs the story and pushes its commits.
+///
+/// Returns an error message if the story could not be run at all.
+async fn run_assignment(
+    state: &WorkerState,
+    assignment_id: &str,
+    assignment: &StoryAssignment,
+    cancel_rx: watch::Receiver<bool>,
+) -> Result<StoryReport, String> {
+    let job_dir = state.config.work_dir.join(assignment_id);
+    let checkout = job_dir.join("repo");
+    let _ = std::fs::remove_dir_all(&job_dir);
+    std::fs::create_dir_all(&job_dir)
+        .map_err(|e| format!("Failed to create {}: {}", job_dir.display(), e))?;
+
+    let result = async {
+        assignment.validate(&job_dir).await?;
+        git_blocking(
+            &job_dir,
+            &[
+                "clone",
+                "--quiet",
+                "--single-branch",
+                "--branch",
+                &assignment.base_branch,
+                "--",
+                &assignment.repo_url,
+                "repo",
+            ],
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        git_blocking(
+            &checkout,
+            &["checkout", "--quiet", "-b", &assignment.story_branch],
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        let prd_path = job_dir.join("prd.json");
+        std::fs::write(&prd_path, &assignment.prd)
+            .map_err(|e| format!("Failed to write PRD: {}", e))?;
+
+        let executor = StoryExecutor::new(ExecutorConfig {
+            prd_path,
+            project_root: checkout.clone(),
+            progress_path: job_dir.join("progress.txt"),
+            agent_command: state.config.agent_command.clone(),
+            max_iterations: assignment.max_iterations,
+            ..Default::default()
+        });
+        let exec_result = executor
+            .execute_story(
+                &assignment.story_id,
+                cancel_rx,
+                |iteration, max_iterations| {
+                    state.set_status(
+                        assignment_id,
+                        AssignmentStatus::Running {
+                            iteration,
+                            max_iterations,
+                        },
+                    );
+                },
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut report = StoryReport {
+            success: exec_result.success,
+            iterations: exec_result.iterations_used,
+            error: exec_result.error,
+            commit: exec_result.commit_hash,
+            files_changed: exec_result.files_changed,
+        };
+        if report.success {
+            let refspec = format!("HEAD:refs/heads/{}", assignment.story_branch);
+            if let Err(err) = git_blocking(
+                &checkout,
+                &["push", "--quiet", "--force", "origin", &refspec],
+            )
+            .await
+            {
+                report.success = false;
+                report.error = Some(err.to_string());
+            }
+        }
+        Ok(report)
+    }
+    .await;
+
+    let _ = std::fs::remove_dir_all(&job_dir);
+    result
+}
+
+/// Runs `git` on the blocking pool, so clones, pushes and fetches do not
+/// stall the runtime's worker threads while they wait on the network.
+async fn git_blocking(dir: &Path, args: &[&str]) -> Result<String, WorktreeError> {
+    let dir = dir.to_path_buf();
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let command = args.first().cloned().unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        git(&dir, &args)
+    })
+    .await
+    .unwrap_or_else(|e| {
+        Err(WorktreeError::Git {
+            command,
+            message: format!("git task failed: {}", e),
+        })
+    })
+}
+
+/// Dispatches stories to remote workers and merges their results.
+#[derive(Debug)]
+pub struct RemoteWorkerPool {
+    /// Base URLs of the workers
+    workers: Vec<String>,
+    /// Git remote (name or URL) workers clone from and push to
+    remote: String,
+    /// Token sent to workers as a bearer token
+    token: Option<String>,
+    /// Interval between status requests (and retries while all workers are busy)
+    poll_interval: Duration,
+    /// Worker the next dispatch tries first
+    next_worker: AtomicUsize,
+    client: reqwest::Client,
+}
+
+impl RemoteWorkerPool {
+    /// Creates a pool dispatching to the workers at `workers`, sharing work
+    /// through the `origin` remote.
+    pub fn new(workers: Vec<String>) -> Self {
+        Self {
+            workers: workers
+                .into_iter()
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty())
+                .collect(),
+            remote: "origin".to_string(),
+            token: None,
+            poll_interval: Duration::from_secs(2),
+            next_worker: AtomicUsize::new(0),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Set the git remote (name or URL) workers clone from and push to
+    pub fn with_remote(mut self, remote: impl Into<String>) -> Self {
+        self.remote = remote.into();
+        self
+    }
+
+    /// Send `token` to workers as a bearer token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Set the interval between status requests
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Base URLs of the workers.
+    pub fn workers(&self) -> &[String] {
+        &self.workers
+    }
+
+    /// Runs a story on a worker and merges its commits into `repo_root`.
+    ///
+    /// `git_mutex` serializes the coordinator's pushes and merges with other
+    /// git operations. A merge conflict fails the story, leaving `repo_root`
+    /// untouched.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn run_story<F>(
+        &self,
+        repo_root: &Path,
+        git_mutex: &tokio::sync::Mutex<()>,
+        run_id: &str,
+        story_id: &str,
+        prd: String,
+        max_iterations: u32,
+        cancel_rx: watch::Receiver<bool>,
+        on_iteration: F,
+    ) -> Result<ExecutionResult, RemoteError>
+    where
+        F: FnMut(u32, u32),
+    {
+        let repo_url = git_blocking(repo_root, &["remote", "get-url", &self.remote])
+            .await
+            .map(|url| url.trim().to_string())
+            .unwrap_or_else(|_| self.remote.clone());
+        let base_branch = format!("{}/{}/base", BRANCH_PREFIX, run_id);
+        let story_branch = format!("{}/{}/{}", BRANCH_PREFIX, run_id, sanitize_name(story_id));
+        {
+            let _git_guard = git_mutex.lock().await;
+            let refspec = format!("HEAD:refs/heads/{}", base_branch);
+            git_blocking(
+                repo_root,
+                &["push", "--quiet", "--force", &self.remote, &refspec],
+            )
+            .await?;
+        }
+
+        let assignment = StoryAssignment {
+            story_id: story_id.to_string(),
+            repo_url,
+            base_branch,
+            story_branch: story_branch.clone(),
+            prd,
+            max_iterations,
+        };
+        let mut report = self.dispatch(&assignment, cancel_rx, on_iteration).await?;
+
+        if report.success {
+            let _git_guard = git_mutex.lock().await;
+            if let Err(err) = self
+                .merge_story_branch(repo_root, story_id, &story_branch)
+                .await
+            {
+                report.success = false;
+                report.error = Some(err.to_string());
+            }
+        }
+
+        Ok(ExecutionResult {
+            success: report.success,
+            commit_hash: report.commit,
+            error: report.error,
+            iterations_used: report.iterations,
+            gate_results: Vec::new(),
+            files_changed: report.files_changed,
+            futility_verdict: None,
+            iteration_context: None,
+            needs_guidance: false,
+            tokens_used: None,
+            estimated_cost_cents: None,
+            budget_exceeded: false,
+        })
+    }
+
+    /// Fetches a story branch and merges it, aborting the merge on conflict.
+    ///
+    /// The remote branch is deleted once merged.
+    async fn merge_story_branch(
+        &self,
+        repo_root: &Path,
+        story_id: &str,
+        story_branch: &str,
+    ) -> Result<(), WorktreeError> {
+        git_blocking(repo_root, &["fetch", "--quiet", &self.remote, story_branch]).await?;
+        let message = format!("Merge story {}", story_id);
+        if let Err(err) = git_blocking(
+            repo_root,
+            &["merge", "--no-edit", "-m", &message, "FETCH_HEAD"],
+        )
+        .await
+        {
+            let files = git_blocking(repo_root, &["diff", "--name-only", "--diff-filter=U"])
+                .await
+                .map(|out| out.lines().map(str::to_string).collect::<Vec<_>>())
+                .unwrap_or_default();
+            if files.is_empty() {
+                return Err(err);
+            }
+            let _ = git_blocking(repo_root, &["merge", "--abort"]).await;
+            return Err(WorktreeError::MergeConflict {
+                branch: story_branch.to_string(),
+                files,
+            });
+        }
+        let _ = git_blocking(
+            repo_root,
+            &["push", "--quiet", &self.remote, "--delete", story_branch],
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Hands the assignment to the first worker with a free slot and waits for
+    /// its report.
+    ///
+    /// While every worker is busy the dispatch is retried each poll interval.
+    /// Dropping the returned future cancels the story on the worker.
+    pub async fn dispatch<F>(
+        &self,
+        assignment: &StoryAssignment,
+        mut cancel_rx: watch::Receiver<bool>,
+        mut on_iteration: F,
+    ) -> Result<StoryReport, RemoteError>
+    where
+        F: FnMut(u32, u32),
+    {
+        let (url, assignment_id) = loop {
+            if let Some(accepted) = self.submit(assignment).await? {
+                break accepted;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.poll_interval) => {}
+                _ = cancel_rx.wait_for(|cancelled| *cancelled) => {
+                    return Err(RemoteError::Cancelled);
+                }
+            }
+        };
+
+        let mut guard = CancelOnDrop {
+            client: self.client.clone(),
+            url: format!("{}/v1/stories/{}", url, assignment_id),
+            token: self.token.clone(),
+            armed: true,
+        };
+        let mut last_iteration = 0;
+        let mut failures = 0;
+        loop {
+            let cancelled = tokio::select! {
+                _ = tokio::time::sleep(self.poll_interval) => false,
+                _ = cancel_rx.wait_for(|cancelled| *cancelled) => true,
+            };
+            if cancelled {
+                let _ = self.request(self.client.delete(&guard.url)).send().await;
+                guard.armed = false;
+                return Err(RemoteError::Cancelled);
+            }
+
+            let response = match self.request(self.client.get(&guard.url)).send().await {
+                Ok(response) => response,
+                Err(err) => {
+                    failures += 1;
+                    if failures >= MAX_POLL_FAILURES {
+                        guard.armed = false;
+                        return Err(RemoteError::Http {
+                            url,
+                            message: err.to_string(),
+                        });
+                    }
+                    continue;
+                }
+            };
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                guard.armed = false;
+                return Err(RemoteError::AssignmentLost { url, assignment_id });
+            }
+            let status = match check_status(&url, response).await {
+                Ok(response) => response.json::<AssignmentStatus>().await,
+                Err(err) => {
+                    guard.armed = false;
+                    return Err(err);
+                }
+            };
+            match status {
+                Ok(AssignmentStatus::Running {
+                    iteration,
+                    max_iterations,
+                }) => {
+                    failures = 0;
+                    if iteration != last_iteration {
+                        last_iteration = iteration;
+                        on_iteration(iteration, max_iterations);
+                    }
+                }
+                Ok(AssignmentStatus::Finished { report }) => {
+                    guard.armed = false;
+                    return Ok(report);
+                }
+                Err(err) => {
+                    guard.armed = false;
+                    return Err(RemoteError::Http {
+                        url,
+                        message: format!("invalid status response: {}", err),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Offers the assignment to each worker in turn, starting after the one
+    /// used last. Returns `None` if every worker is busy or unreachable.
+    ///
+    /// Fails only if every worker rejected the assignment outright.
+    async fn submit(
+        &self,
+        assignment: &StoryAssignment,
+    ) -> Result<Option<(String, String)>, RemoteError> {
+        let start = self.next_worker.fetch_add(1, Ordering::Relaxed);
+        let mut last_rejection = None;
+        let mut busy = false;
+        for offset in 0..self.workers.len() {
+            let url = &self.workers[(start + offset) % self.workers.len()];
+            let request = self
+                .request(self.client.post(format!("{}/v1/stories", url)))
+                .json(assignment);
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(_) => {
+                    // Unreachable workers may come back; keep waiting for them
+                    busy = true;
+                    continue;
+                }
+            };
+            if response.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+                busy = true;
+                continue;
+            }
+            match check_status(url, response).await {
+                Ok(response) => match response.json::<AssignmentAccepted>().await {
+                    Ok(accepted) => return Ok(Some((url.clone(), accepted.assignment_id))),
+                    Err(err) => {
+                        last_rejection = Some(RemoteError::Http {
+                            url: url.clone(),
+                            message: format!("invalid response: {}", err),
+                        })
+                    }
+                },
+                Err(err) => last_rejection = Some(err),
+            }
+        }
+        match last_rejection {
+            Some(err) if !busy => Err(err),
+            _ => Ok(None),
+        }
+    }
+
+    fn request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.token {
+            Some(ref token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+/// Turns an unsuccessful response into an error carrying the worker's message.
+async fn check_status(
+    url: &str,
+    response: reqwest::Response,
+) -> Result<reqwest::Response, RemoteError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(RemoteError::Http {
+        url: url.to_string(),
+        message: format!("HTTP {}: {}", status, body.trim()),
+    })
+}
+
+/// Cancels an assignment on its worker if the dispatch is dropped before it
+/// finished (e.g., when the story times out).
+struct CancelOnDrop {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+    armed: bool,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let mut request = self.client.delete(&self.url);
+        if let Some(ref token) = self.token {
+            request = request.bearer_auth(token);
+        }
+        runtime.spawn(async move {
+            let _ = request.send().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn assignment(repo_url: &str) -> StoryAssignment {
+        StoryAssignment {
+            story_id: "US-001".to_string(),
+            repo_url: repo_url.to_string(),
+            base_branch: "ralph/remote/run-1/base".to_string(),
+            story_branch: "ralph/remote/run-1/US-001".to_string(),
+            prd: "{}".to_string(),
+            max_iterations: 3,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_worker_requires_token() {
+        let app = worker_router(WorkerConfig::new("true").with_token("secret"));
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/health")
+                    .header(header::AUTHORIZATION, "Bearer secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        let health: WorkerHealth = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            health,
+            WorkerHealth {
+                capacity: 1,
+                running: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_public_worker_requires_token() {
+        let config = WorkerConfig::new("true")
+            .with_bind_address("0.0.0.0")
+            .with_port(0);
+        let err = serve_worker(config).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        let app = worker_router(WorkerConfig::new("true").with_token("secret"));
+        for sent in ["Bearer secreT", "Bearer secret2", "Bearer ", "secret"] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/v1/health")
+                        .header(header::AUTHORIZATION, sent)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{}", sent);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_assignment_is_not_found() {
+        let app = worker_router(WorkerConfig::new("true"));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/stories/US-001-7")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_status_wire_format() {
+        let status = AssignmentStatus::Running {
+            iteration: 2,
+            max_iterations: 5,
+        };
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(
+            json,
+            r#"{"state":"running","iteration":2,"max_iterations":5}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<AssignmentStatus>(&json).unwrap(),
+            status
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pool_reports_failed_clone() {
+        let work_dir = tempfile::TempDir::new().unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let config = WorkerConfig::new("true")
+            .with_work_dir(work_dir.path())
+            .with_token("secret");
+        tokio::spawn(async move { axum::serve(listener, worker_router(config)).await });
+
+        let pool = RemoteWorkerPool::new(vec![format!("{}/", url)])
+            .with_token("secret")
+            .with_poll_interval(Duration::from_millis(20));
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let missing_repo = work_dir.path().join("missing.git");
+        let report = pool
+            .dispatch(
+                &assignment(&missing_repo.to_string_lossy()),
+                cancel_rx,
+                |_, _| {},
+            )
+            .await
+            .unwrap();
+
+        assert!(!report.success);
+        assert!(report.error.unwrap().contains("git clone"));
+        // The clone directory is cleaned up
+        assert_eq!(std::fs::read_dir(work_dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_worker_rejects_option_like_assignment() {
+        let work_dir = tempfile::TempDir::new().unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let config = WorkerConfig::new("true").with_work_dir(work_dir.path());
+        tokio::spawn(async move { axum::serve(listener, worker_router(config)).await });
+        let pool = RemoteWorkerPool::new(vec![url]).with_poll_interval(Duration::from_millis(20));
+        let marker = work_dir.path().join("injected");
+        let injected = format!("--upload-pack=touch {}", marker.display());
+
+        let mut bad_url = assignment(&injected);
+        bad_url.base_branch = "main".to_string();
+        let mut bad_branch = assignment("unused");
+        bad_branch.base_branch = injected.clone();
+        let mut bad_story_branch = assignment("unused");
+        bad_story_branch.story_branch = "ralph/a..b".to_string();
+        for (assignment, error) in [
+            (bad_url, "Invalid repository URL"),
+            (bad_branch, "Invalid branch name"),
+            (bad_story_branch, "Invalid branch name"),
+        ] {
+            let (_cancel_tx, cancel_rx) = watch::channel(false);
+            let report = pool
+                .dispatch(&assignment, cancel_rx, |_, _| {})
+                .await
+                .unwrap();
+            assert!(!report.success);
+            assert!(report.error.unwrap().contains(error));
+        }
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_pool_fails_when_workers_reject_assignment() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let config = WorkerConfig::new("true").with_token("secret");
+        tokio::spawn(async move { axum::serve(listener, worker_router(config)).await });
+
+        let pool = RemoteWorkerPool::new(vec![url]);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let err = pool
+            .dispatch(&assignment("unused"), cancel_rx, |_, _| {})
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("401"));
+    }
+}
@@ -0,0 +1,207 @@
+//! Re-planning a parallel run when its PRD changes.
+//!
+//! With `--watch-prd` the scheduler polls the PRD, and for a workspace its
+//! member PRDs, between dispatch cycles. When the stories change, the new PRD
+//! is validated and the dependency graph rebuilt, so stories added or edited
+//! during a run are scheduled without restarting it.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::mcp::tools::load_prd::{PrdFile, PrdUserStory};
+use crate::prd::lint::{lint_prd, LintFinding, LintOptions, LintSeverity};
+use crate::prd::workspace::Workspace;
+
+/// How often the watched files are read.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Detects changes to a PRD file and, for a workspace, its members.
+#[derive(Debug)]
+pub struct PrdWatcher {
+    prd_path: PathBuf,
+    files: Vec<PathBuf>,
+    /// Contents last seen, `None` for files that could not be read
+    contents: Vec<Option<String>>,
+    last_poll: Instant,
+}
+
+impl PrdWatcher {
+    pub fn new(prd_path: &Path) -> Self {
+        let files = watched_files(prd_path);
+        let contents = read_all(&files);
+        Self {
+            prd_path: prd_path.to_path_buf(),
+            files,
+            contents,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Whether a watched file changed since the last call. Files are read at
+    /// most once per [`WATCH_INTERVAL`].
+    pub fn changed(&mut self) -> bool {
+        if self.last_poll.elapsed() < WATCH_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        let contents = read_all(&self.files);
+        if contents == self.contents {
+            return false;
+        }
+        // Members may have been added to or removed from a workspace
+        self.files = watched_files(&self.prd_path);
+        self.contents = read_all(&self.files);
+        true
+    }
+}
+
+fn watched_files(prd_path: &Path) -> Vec<PathBuf> {
+    let mut files = vec![prd_path.to_path_buf()];
+    if Workspace::is_manifest(prd_path) {
+        if let Ok(workspace) = Workspace::load(prd_path) {
+            files.extend(
+                workspace
+                    .member_names()
+                    .iter()
+                    .filter_map(|name| workspace.member_prd(name)),
+            );
+        }
+    }
+    files
+}
+
+fn read_all(files: &[PathBuf]) -> Vec<Option<String>> {
+    files
+        .iter()
+        .map(|file| std::fs::read_to_string(file).ok())
+        .collect()
+}
+
+/// Stories that differ between two versions of a PRD.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PrdChanges {
+    pub added: Vec<String>,
+    pub edited: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl PrdChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.edited.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Compare the stories of two PRD versions. A story whose only change is its
+/// `passes` flag is not edited: the run sets that flag itself.
+pub fn diff_stories(old: &[PrdUserStory], new: &[PrdUserStory]) -> PrdChanges {
+    let find = |stories: &[PrdUserStory], id: &str| -> Option<PrdUserStory> {
+        stories.iter().find(|story| story.id == id).cloned()
+    };
+    let mut changes = PrdChanges::default();
+    for story in new {
+        match find(old, &story.id) {
+            None => changes.added.push(story.id.clone()),
+            Some(previous) => {
+                let unchanged = PrdUserStory {
+                    passes: previous.passes,
+                    ..story.clone()
+                } == previous;
+                if !unchanged {
+                    changes.edited.push(story.id.clone());
+                }
+            }
+        }
+    }
+    changes.removed = old
+        .iter()
+        .filter(|story| find(new, &story.id).is_none())
+        .map(|story| story.id.clone())
+        .collect();
+    changes
+}
+
+/// Lint errors `new` has that `old` did not, so a change is rejected only for
+/// problems it introduced.
+pub fn introduced_errors(old: &PrdFile, new: &PrdFile) -> Vec<LintFinding> {
+    let errors = |prd: &PrdFile| -> Vec<LintFinding> {
+        lint_prd("", prd, &LintOptions::default())
+            .into_iter()
+            .filter(|finding| finding.severity == LintSeverity::Error)
+            .collect()
+    };
+    let existing = errors(old);
+    errors(new)
+        .into_iter()
+        .filter(|finding| {
+            !existing.iter().any(|old| {
+                old.rule == finding.rule
+                    && old.story == finding.story
+                    && old.message == finding.message
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prd(stories: &str) -> PrdFile {
+        serde_json::from_str(&format!(
+            r#"{{"project": "p", "branchName": "b", "userStories": [{}]}}"#,
+            stories
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff_stories() {
+        let old = prd(r#"
+            {"id": "US-1", "title": "One", "priority": 1, "passes": false},
+            {"id": "US-2", "title": "Two", "priority": 2, "passes": false},
+            {"id": "US-3", "title": "Three", "priority": 3, "passes": false}
+        "#);
+        let new = prd(r#"
+            {"id": "US-1", "title": "One", "priority": 1, "passes": true},
+            {"id": "US-2", "title": "Two, revised", "priority": 2, "passes": false},
+            {"id": "US-4", "title": "Four", "priority": 4, "passes": false, "dependsOn": ["US-9"]}
+        "#);
+
+        let changes = diff_stories(&old.user_stories, &new.user_stories);
+        assert_eq!(
+            changes,
+            PrdChanges {
+                added: vec!["US-4".to_string()],
+                edited: vec!["US-2".to_string()],
+                removed: vec!["US-3".to_string()],
+            }
+        );
+        assert!(diff_stories(&old.user_stories, &old.user_stories).is_empty());
+
+        let errors = introduced_errors(&old, &new);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].story, "US-4");
+        assert!(introduced_errors(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_watcher_detects_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("prd.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        let mut watcher = PrdWatcher::new(&path);
+        watcher.last_poll -= WATCH_INTERVAL;
+        assert!(!watcher.changed());
+
+        std::fs::write(&path, r#"{"project": "p"}"#).unwrap();
+        assert!(!watcher.changed(), "polled again too soon");
+        watcher.last_poll -= WATCH_INTERVAL;
+        assert!(watcher.changed());
+        watcher.last_poll -= WATCH_INTERVAL;
+        assert!(!watcher.changed());
+    }
+}
@@ -0,0 +1,162 @@
+//! Resolving story branches that conflict when merged back.
+//!
+//! In branch isolation a story whose branch conflicts with the integration
+//! branch keeps its branch for reconciliation. Before the story is re-run
+//! sequentially, the configured strategies are tried in order:
+//!
+//! - `priority` settles conflicting hunks in favour of the story branch when
+//!   the story outranks the stories already merged into the conflicted files,
+//!   and in favour of the integration branch otherwise
+//! - `rerere` resolves conflicts the way git rerere recorded them being
+//!   resolved before
+//! - `agent` has the story's agent edit the conflicted files
+//!
+//! The first strategy that leaves no conflicts is committed; every attempt is
+//! recorded in evidence as a `merge_resolution` event.
+
+use serde::Serialize;
+
+/// Evidence record kind for merge conflict resolutions.
+pub const MERGE_RESOLUTION_EVIDENCE_KIND: &str = "merge_resolution";
+
+/// A way of resolving a story branch's merge conflicts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeResolution {
+    /// Favour the side of the higher-priority story.
+    Priority,
+    /// Replay resolutions git rerere recorded.
+    Rerere,
+    /// Have the story's agent resolve the conflicts.
+    Agent,
+}
+
+impl MergeResolution {
+    pub fn from_label(label: &str) -> Result<Self, String> {
+        match label {
+            "priority" => Ok(MergeResolution::Priority),
+            "rerere" => Ok(MergeResolution::Rerere),
+            "agent" => Ok(MergeResolution::Agent),
+            other => Err(format!(
+                "unknown merge resolution '{}' (expected one of: none, priority, rerere, agent)",
+                other
+            )),
+        }
+    }
+
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            MergeResolution::Priority => "priority",
+            MergeResolution::Rerere => "rerere",
+            MergeResolution::Agent => "agent",
+        }
+    }
+}
+
+/// Parse a comma-separated list of strategies, tried in the order given;
+/// `none` (or an empty list) tries none.
+pub fn parse_resolutions(spec: &str) -> Result<Vec<MergeResolution>, String> {
+    let mut resolutions = Vec::new();
+    for label in spec.split(',').map(str::trim) {
+        if label.is_empty() || label == "none" {
+            continue;
+        }
+        let resolution = MergeResolution::from_label(label)?;
+        if !resolutions.contains(&resolution) {
+            resolutions.push(resolution);
+        }
+    }
+    Ok(resolutions)
+}
+
+/// Side whose changes win conflicting hunks under the `priority` strategy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeSide {
+    /// The integration branch, holding the stories merged before
+    Ours,
+    /// The conflicting story's branch
+    Theirs,
+}
+
+impl MergeSide {
+    /// Side favouring a story of `priority` over stories of `rival_priorities`
+    /// (lower values = higher priority). Ties, and conflicts with no rival
+    /// story, keep what is already merged.
+    pub fn by_priority(priority: u32, rival_priorities: impl IntoIterator<Item = u32>) -> Self {
+        match rival_priorities.into_iter().min() {
+            Some(rival) if priority < rival => MergeSide::Theirs,
+            _ => MergeSide::Ours,
+        }
+    }
+
+    /// The `git merge` option selecting this side.
+    pub fn merge_option(&self) -> &'static str {
+        match self {
+            MergeSide::Ours => "-Xours",
+            MergeSide::Theirs => "-Xtheirs",
+        }
+    }
+
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            MergeSide::Ours => "ours",
+            MergeSide::Theirs => "theirs",
+        }
+    }
+}
+
+/// One strategy tried on a conflicting story branch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ResolutionAttempt {
+    pub strategy: &'static str,
+    /// Side favoured by the `priority` strategy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<&'static str>,
+    pub resolved: bool,
+    /// Why the strategy did not resolve the conflicts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How a story branch's merge conflicts were handled, as recorded in evidence.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MergeResolutionRecord {
+    pub story_id: String,
+    pub branch: String,
+    /// Files that conflicted
+    pub files: Vec<String>,
+    /// Strategy that resolved the conflicts; `None` leaves the story to be
+    /// re-run sequentially
+    pub resolution: Option<&'static str>,
+    pub attempts: Vec<ResolutionAttempt>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolutions() {
+        assert_eq!(
+            parse_resolutions("rerere, priority,agent,rerere").unwrap(),
+            vec![
+                MergeResolution::Rerere,
+                MergeResolution::Priority,
+                MergeResolution::Agent
+            ]
+        );
+        assert!(parse_resolutions("none").unwrap().is_empty());
+        assert!(parse_resolutions("").unwrap().is_empty());
+        assert!(parse_resolutions("theirs")
+            .unwrap_err()
+            .contains("unknown merge resolution 'theirs'"));
+    }
+
+    #[test]
+    fn test_side_by_priority() {
+        assert_eq!(MergeSide::by_priority(1, [2, 3]), MergeSide::Theirs);
+        assert_eq!(MergeSide::by_priority(2, [1, 3]), MergeSide::Ours);
+        assert_eq!(MergeSide::by_priority(2, [2]), MergeSide::Ours);
+        assert_eq!(MergeSide::by_priority(5, []), MergeSide::Ours);
+        assert_eq!(MergeSide::Theirs.merge_option(), "-Xtheirs");
+    }
+}
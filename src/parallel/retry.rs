@@ -0,0 +1,366 @@
+//! Per-story retry policy for parallel execution
+//!
+//! When a story fails with an error whose category allows it, the story is
+//! re-run in place after an exponential backoff with jitter. Each story gets
+//! its own retry budget per error category, spent both by the executor when an
+//! agent run fails and by the scheduler when it re-runs the whole story.
+//!
+//! Errors with a subcategory are retried by its strategy instead: network
+//! errors quickly, rate limits after a long backoff, and authentication and
+//! disk-full errors not at all.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::classification::ErrorCategory;
+use crate::evidence::error_category_label;
+use crate::pause::RetryStrategy;
+
+/// Labels retry budgets can be set for: the error categories, then the
+/// subcategories.
+pub const RETRY_LABELS: [&str; 8] = [
+    "transient",
+    "usage_limit",
+    "fatal",
+    "timeout",
+    "network",
+    "rate_limit",
+    "auth",
+    "disk_full",
+];
+
+/// Retry limits and backoff for failed stories, keyed by error category.
+#[derive(Clone, Debug)]
+pub struct StoryRetryPolicy {
+    /// Strategy for each error category label ("transient", "usage_limit",
+    /// "fatal", "timeout") or subcategory label ("network", "rate_limit",
+    /// "auth", "disk_full"). `max_attempts` is the number of retries allowed
+    /// for one story; categories without an entry are never retried.
+    pub categories: HashMap<String, RetryStrategy>,
+}
+
+impl Default for StoryRetryPolicy {
+    fn default() -> Self {
+        let mut categories = HashMap::new();
+        categories.insert(
+            "transient".to_string(),
+            RetryStrategy::new(Duration::from_secs(5), Duration::from_secs(60), 2, 20),
+        );
+        categories.insert(
+            "timeout".to_string(),
+            RetryStrategy::new(Duration::from_secs(10), Duration::from_secs(60), 1, 20),
+        );
+        categories.insert(
+            "network".to_string(),
+            RetryStrategy::new(Duration::from_secs(2), Duration::from_secs(30), 3, 20),
+        );
+        categories.insert(
+            "rate_limit".to_string(),
+            RetryStrategy::new(Duration::from_secs(60), Duration::from_secs(900), 3, 20),
+        );
+        // Retrying cannot fix these; the story fails with what to do instead
+        for label in ["auth", "disk_full"] {
+            categories.insert(
+                label.to_string(),
+                RetryStrategy::new(Duration::from_secs(5), Duration::from_secs(60), 0, 20),
+            );
+        }
+        Self { categories }
+    }
+}
+
+impl StoryRetryPolicy {
+    /// A policy that never retries.
+    pub fn disabled() -> Self {
+        Self {
+            categories: HashMap::new(),
+        }
+    }
+
+    /// Sets the strategy for an error category label.
+    pub fn with_category(mut self, label: impl Into<String>, strategy: RetryStrategy) -> Self {
+        self.categories.insert(label.into(), strategy);
+        self
+    }
+
+    /// Sets the retry limit for one error category label, adding it with the
+    /// default backoff if the policy did not retry it.
+    pub fn with_category_retries(mut self, label: impl Into<String>, max_retries: u32) -> Self {
+        self.categories
+            .entry(label.into())
+            .or_insert_with(|| {
+                RetryStrategy::new(Duration::from_secs(5), Duration::from_secs(60), 0, 20)
+            })
+            .max_attempts = max_retries;
+        self
+    }
+
+    /// Sets the retry limit for every configured category that is retried;
+    /// categories that are never retried, such as `auth`, stay that way.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        for strategy in self.categories.values_mut() {
+            if strategy.max_attempts > 0 {
+                strategy.max_attempts = max_retries;
+            }
+        }
+        self
+    }
+
+    /// Label of the strategy and budget used for `category`: its
+    /// subcategory's, if the policy has one, else the category's.
+    pub fn label(&self, category: &ErrorCategory) -> &'static str {
+        category
+            .subcategory()
+            .map(|subcategory| subcategory.as_label())
+            .filter(|label| self.categories.contains_key(*label))
+            .unwrap_or_else(|| error_category_label(category))
+    }
+
+    /// Maximum retries for a story failing with `category`.
+    pub fn max_retries(&self, category: &ErrorCategory) -> u32 {
+        self.categories
+            .get(self.label(category))
+            .map(|strategy| strategy.max_attempts)
+            .unwrap_or(0)
+    }
+
+    /// Returns the delay before the next retry, or `None` if the story has
+    /// used up its retries for this category.
+    ///
+    /// `retries` is the number of retries the story has already made.
+    pub fn retry_delay(&self, retries: u32, category: &ErrorCategory) -> Option<Duration> {
+        let strategy = self.categories.get(self.label(category))?;
+        if retries >= strategy.max_attempts {
+            return None;
+        }
+        Some(strategy.calculate_delay(retries + 1))
+    }
+
+    /// Spends a retry of `budget` on an error of `category`, returning the
+    /// delay before the retry, or `None` if the category's retries are used up.
+    pub fn spend(&self, budget: &mut RetryBudget, category: &ErrorCategory) -> Option<Duration> {
+        let label = self.label(category);
+        let delay = self.retry_delay(budget.used(label), category)?;
+        *budget.used.entry(label.to_string()).or_insert(0) += 1;
+        Some(delay)
+    }
+
+    /// Retries `budget` has left per configured category label.
+    pub fn remaining(&self, budget: &RetryBudget) -> BTreeMap<String, u32> {
+        self.categories
+            .iter()
+            .map(|(label, strategy)| {
+                let left = strategy.max_attempts.saturating_sub(budget.used(label));
+                (label.clone(), left)
+            })
+            .collect()
+    }
+}
+
+/// Retries a story has made, per error category label.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RetryBudget {
+    used: BTreeMap<String, u32>,
+}
+
+impl RetryBudget {
+    /// Retries made for errors of category `label`.
+    pub fn used(&self, label: &str) -> u32 {
+        self.used.get(label).copied().unwrap_or(0)
+    }
+
+    /// Whether no retries were made.
+    pub fn is_empty(&self) -> bool {
+        self.used.values().all(|&used| used == 0)
+    }
+}
+
+/// Retry budgets of a run's stories, shared by the scheduler and the
+/// executors running the stories.
+#[derive(Clone, Debug, Default)]
+pub struct RetryBudgets {
+    stories: Arc<Mutex<HashMap<String, RetryBudget>>>,
+}
+
+impl RetryBudgets {
+    /// Spends a retry of `story_id`'s budget under `policy`; see
+    /// [`StoryRetryPolicy::spend`].
+    pub fn spend(
+        &self,
+        story_id: &str,
+        policy: &StoryRetryPolicy,
+        category: &ErrorCategory,
+    ) -> Option<Duration> {
+        let mut stories = self.stories.lock().ok()?;
+        policy.spend(stories.entry(story_id.to_string()).or_default(), category)
+    }
+
+    /// Retries `story_id` has made.
+    pub fn get(&self, story_id: &str) -> RetryBudget {
+        self.stories
+            .lock()
+            .ok()
+            .and_then(|stories| stories.get(story_id).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Budgets of the stories `selected` accepts that made any retries.
+    pub fn snapshot(&self, selected: impl Fn(&str) -> bool) -> BTreeMap<String, RetryBudget> {
+        let Ok(stories) = self.stories.lock() else {
+            return BTreeMap::new();
+        };
+        stories
+            .iter()
+            .filter(|(story_id, budget)| selected(story_id) && !budget.is_empty())
+            .map(|(story_id, budget)| (story_id.clone(), budget.clone()))
+            .collect()
+    }
+
+    /// Continues the budgets of a checkpointed run.
+    pub fn restore(&self, budgets: &BTreeMap<String, RetryBudget>) {
+        if let Ok(mut stories) = self.stories.lock() {
+            stories.extend(
+                budgets
+                    .iter()
+                    .map(|(id, budget)| (id.clone(), budget.clone())),
+            );
+        }
+    }
+}
+
+/// Parses per-category retry limits such as `transient=5,auth=1`.
+pub fn parse_retry_budgets(spec: &str) -> Result<BTreeMap<String, u32>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (label, count) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected CATEGORY=COUNT, got '{}'", entry))?;
+            let label = label.trim();
+            if !RETRY_LABELS.contains(&label) {
+                return Err(format!(
+                    "unknown error category '{}' (expected one of: {})",
+                    label,
+                    RETRY_LABELS.join(", ")
+                ));
+            }
+            let count = count
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("invalid retry count in '{}'", entry))?;
+            Ok((label.to_string(), count))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::classification::{
+        FatalReason, TimeoutReason, TransientReason, UsageLimitReason,
+    };
+
+    #[test]
+    fn test_default_policy_retries_transient_errors() {
+        let policy = StoryRetryPolicy::default();
+        let category = ErrorCategory::Transient(TransientReason::ServerError);
+
+        assert_eq!(policy.max_retries(&category), 2);
+        let first = policy.retry_delay(0, &category).unwrap();
+        let second = policy.retry_delay(1, &category).unwrap();
+        assert!(second > first);
+        assert!(policy.retry_delay(2, &category).is_none());
+    }
+
+    #[test]
+    fn test_fatal_errors_are_not_retried() {
+        let policy = StoryRetryPolicy::default();
+        let category = ErrorCategory::Fatal(FatalReason::AuthenticationFailed);
+        assert_eq!(policy.max_retries(&category), 0);
+        assert!(policy.retry_delay(0, &category).is_none());
+    }
+
+    #[test]
+    fn test_category_override_and_retry_limit() {
+        let policy = StoryRetryPolicy::disabled().with_category(
+            "timeout",
+            RetryStrategy::new(Duration::from_secs(1), Duration::from_secs(4), 5, 0),
+        );
+        let category = ErrorCategory::Timeout(TimeoutReason::ProcessTimeout);
+        assert_eq!(
+            policy.retry_delay(0, &category),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.retry_delay(3, &category),
+            Some(Duration::from_secs(4))
+        );
+
+        let limited = policy.with_max_retries(1);
+        assert_eq!(limited.max_retries(&category), 1);
+        assert!(limited.retry_delay(1, &category).is_none());
+        // Categories without a strategy stay unretried
+        let fatal = ErrorCategory::Fatal(FatalReason::InternalError);
+        assert_eq!(limited.max_retries(&fatal), 0);
+    }
+
+    #[test]
+    fn test_budget_is_spent_per_category() {
+        let policy = parse_retry_budgets("transient=1, fatal=1")
+            .unwrap()
+            .into_iter()
+            .fold(StoryRetryPolicy::default(), |policy, (label, count)| {
+                policy.with_category_retries(label, count)
+            });
+        let transient = ErrorCategory::Transient(TransientReason::ServerError);
+        let fatal = ErrorCategory::Fatal(FatalReason::InternalError);
+
+        let mut budget = RetryBudget::default();
+        assert!(policy.spend(&mut budget, &transient).is_some());
+        assert!(policy.spend(&mut budget, &transient).is_none());
+        // Transient retries do not use up the fatal budget
+        assert!(policy.spend(&mut budget, &fatal).is_some());
+        assert!(policy.spend(&mut budget, &fatal).is_none());
+        assert_eq!(budget.used("transient"), 1);
+        assert_eq!(policy.remaining(&budget)["timeout"], 1);
+        assert_eq!(policy.remaining(&budget)["fatal"], 0);
+
+        assert!(parse_retry_budgets("dns=3").is_err());
+        assert!(parse_retry_budgets("fatal").is_err());
+    }
+
+    #[test]
+    fn test_subcategories_have_their_own_backoff() {
+        let policy = StoryRetryPolicy::default();
+        let network = ErrorCategory::Transient(TransientReason::ConnectionReset);
+        let server = ErrorCategory::Transient(TransientReason::ServerError);
+        let rate_limit = ErrorCategory::UsageLimit(UsageLimitReason::RateLimited);
+        let auth = ErrorCategory::Fatal(FatalReason::AuthenticationFailed);
+
+        assert_eq!(policy.label(&network), "network");
+        assert_eq!(policy.max_retries(&network), 3);
+        // Rate limits back off far longer than other transient errors
+        assert!(policy.retry_delay(0, &rate_limit).unwrap() > Duration::from_secs(45));
+        assert!(policy.retry_delay(0, &network).unwrap() < policy.retry_delay(0, &server).unwrap());
+        // Usage limits other than rate limits keep their category's strategy
+        let quota = ErrorCategory::UsageLimit(UsageLimitReason::QuotaExhausted);
+        assert_eq!(policy.label(&quota), "usage_limit");
+
+        // Authentication errors fail at once, even with more retries overall
+        let policy = policy.with_max_retries(5);
+        assert_eq!(policy.max_retries(&auth), 0);
+        assert_eq!(policy.max_retries(&network), 5);
+        let policy = policy.with_category_retries("auth", 1);
+        assert_eq!(policy.max_retries(&auth), 1);
+
+        // Without a subcategory strategy the category's is used
+        let policy = StoryRetryPolicy::disabled().with_category_retries("fatal", 2);
+        assert_eq!(policy.label(&auth), "fatal");
+        assert_eq!(policy.max_retries(&auth), 2);
+    }
+}
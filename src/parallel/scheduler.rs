@@ -1,24 +1,74 @@
 //! Parallel execution scheduler
 
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::io::IsTerminal;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use serde::Serialize;
 use tokio::sync::{mpsc, watch, Mutex, RwLock, Semaphore};
+use tokio::task::JoinSet;
 
-use crate::checkpoint::{Checkpoint, CheckpointManager, PauseReason, StoryCheckpoint};
-use crate::error::classification::ErrorCategory;
+use crate::agent::{AgentRoster, StoryAgent};
+use crate::checkpoint::{
+    Checkpoint, CheckpointManager, ParallelCheckpoint, PauseReason, RunFingerprint, StoryCheckpoint,
+};
 use crate::evidence::{error_category_label, generate_run_id, EvidenceWriter};
-use crate::mcp::tools::executor::{detect_agent, ExecutorConfig, StoryExecutor};
-use crate::mcp::tools::load_prd::{validate_prd, PrdFile};
-use crate::metrics::{RunMetricsCollector, RunMetricsStore};
+use crate::mcp::tools::executor::{
+    detect_agent, ExecutionResult, ExecutorConfig, ExecutorError, StoryExecutor,
+    RECONCILIATION_STEP_ID,
+};
+use crate::mcp::tools::load_prd::{validate_prd, PrdFile, PrdUserStory};
+use crate::metrics::{
+    ReconciliationAction, ReconciliationReport, RunMetrics, RunMetricsCollector, RunMetricsStore,
+    RECONCILIATION_EVIDENCE_KIND,
+};
+use crate::notification::webhook::WebhookNotifier;
+use crate::parallel::circuit_breaker::{CircuitBreaker, FailureCategory};
+use crate::parallel::concurrency::{
+    resize_semaphore, system_load_per_cpu, AdaptiveConcurrencyConfig, ConcurrencyAdjustment,
+    ConcurrencyController, StoryOutcome,
+};
 use crate::parallel::dependency::{DependencyGraph, StoryNode};
-use crate::parallel::reconcile::{ReconciliationEngine, ReconciliationIssue, ReconciliationResult};
-use crate::runner::{RunResult, RunnerConfig};
+use crate::parallel::fair_queue::ReadyOrder;
+use crate::parallel::inference::patterns_match;
+use crate::parallel::lock_wait::{LockWaitAction, LockWaitPolicy, LockWaitTracker};
+use crate::parallel::planner::IterationEstimates;
+use crate::parallel::rate_limit::LaunchRateLimiter;
+use crate::parallel::reconcile::{
+    story_diffs, story_files, ReconciliationEngine, ReconciliationIssue, ReconciliationResult,
+};
+use crate::parallel::remote::RemoteWorkerPool;
+use crate::parallel::replan::{diff_stories, introduced_errors, PrdChanges, PrdWatcher};
+use crate::parallel::resolution::{
+    MergeResolution, MergeResolutionRecord, MergeSide, ResolutionAttempt,
+    MERGE_RESOLUTION_EVIDENCE_KIND,
+};
+use crate::parallel::retry::{RetryBudgets, StoryRetryPolicy};
+use crate::parallel::trace::{SchedulerDecision, SchedulerTrace};
+use crate::parallel::worktree::{git, StoryWorktree, WorktreeError, WorktreeManager};
+use crate::pause::{PauseController, PauseState, StopFile, STOP_FILE};
+use crate::prd::prerequisites::check_prerequisites;
+use crate::prd::workspace::{story_prd, Workspace};
+use crate::prd::writeback::set_story_passes;
+use crate::resources::{available_memory_mb, ResourceLimits};
+use crate::runner::{run_final_verification, RunOutcome, RunResult, RunnerConfig};
 use crate::timeout::TimeoutConfig;
+use crate::ui::github_actions::GithubActions;
 use crate::ui::parallel_display::ParallelRunnerDisplay;
-use crate::ui::parallel_events::{ParallelUIEvent, StoryDisplayInfo};
+use crate::ui::parallel_events::{ParallelUIEvent, StoryDisplayInfo, StoryOutputForwarder};
+use crate::ui::run_log::RunLog;
+use crate::ui::run_report::RunReport;
+use crate::ui::{DisplayCallback, KeyboardListener, RunControl, ToggleState};
+
+/// Output of a dispatched story task: (story_id, success, iterations, failure), where
+/// `failure` is the category of a failure counted by the circuit breaker.
+type StoryTaskOutput = (String, bool, u32, Option<FailureCategory>);
+
+/// Reason recorded for stories cancelled from the UI.
+const STORY_CANCELLED: &str = "Cancelled by user";
 
 /// Strategy for detecting conflicts between parallel story executions.
 #[allow(dead_code)]
@@ -44,6 +94,9 @@ pub enum QueuePolicy {
     Reject,
     /// Drop the oldest queued story to make room.
     DropOldest,
+    /// Evict the lowest-priority queued story to make room for a higher-priority
+    /// one. The evicted story is not failed; it becomes ready again next cycle.
+    EvictLowestPriority,
 }
 
 impl QueuePolicy {
@@ -52,6 +105,74 @@ impl QueuePolicy {
             QueuePolicy::Block => "block",
             QueuePolicy::Reject => "reject",
             QueuePolicy::DropOldest => "drop_oldest",
+            QueuePolicy::EvictLowestPriority => "evict_lowest_priority",
+        }
+    }
+}
+
+/// Where parallel stories execute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum IsolationMode {
+    /// All stories share the main working directory, guarded by file locks.
+    #[default]
+    Shared,
+    /// Each story runs in its own git worktree and is merged back on success.
+    Worktree,
+    /// Like `Worktree`, but every iteration is committed to the story branch and
+    /// merge failures are routed into reconciliation instead of discarded.
+    Branch,
+}
+
+impl IsolationMode {
+    /// Parse an isolation mode name; unknown values fall back to `Shared`.
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "worktree" => IsolationMode::Worktree,
+            "branch" => IsolationMode::Branch,
+            _ => IsolationMode::Shared,
+        }
+    }
+}
+
+/// When reconciliation passes run during a parallel run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReconcileMode {
+    /// Reconcile whenever no stories are in flight.
+    #[default]
+    Idle,
+    /// Like `Idle`, but also pause dispatch after this many finished stories so
+    /// a pass can run.
+    Every(usize),
+    /// Reconcile once, after every story has finished.
+    AtEnd,
+    /// Reconcile when no stories are in flight, but only if the stories finished
+    /// since the last pass touch files in a shared parent directory.
+    SharedDirectories,
+}
+
+impl ReconcileMode {
+    /// Parses a mode name; `every` takes the story count from `every`, and
+    /// `idle` with a non-zero `every` means `Every(every)`.
+    pub fn from_label(label: &str, every: usize) -> Result<Self, String> {
+        match label {
+            "idle" if every > 0 => Ok(ReconcileMode::Every(every)),
+            "idle" => Ok(ReconcileMode::Idle),
+            "every" => Ok(ReconcileMode::Every(every.max(1))),
+            "end" => Ok(ReconcileMode::AtEnd),
+            "shared_dirs" => Ok(ReconcileMode::SharedDirectories),
+            other => Err(format!(
+                "unknown reconcile mode '{}' (expected one of: idle, every, end, shared_dirs)",
+                other
+            )),
+        }
+    }
+
+    fn as_label(&self) -> &'static str {
+        match self {
+            ReconcileMode::Idle => "idle",
+            ReconcileMode::Every(_) => "every",
+            ReconcileMode::AtEnd => "end",
+            ReconcileMode::SharedDirectories => "shared_dirs",
         }
     }
 }
@@ -76,13 +197,74 @@ pub struct ParallelRunnerConfig {
     pub conflict_strategy: ConflictStrategy,
     /// Timeout configuration for execution limits.
     pub timeout_config: TimeoutConfig,
-    /// Timeout for an entire batch of parallel executions.
-    /// If a batch takes longer than this, remaining tasks are cancelled.
+    /// Timeout for a single dispatched story.
+    /// If a story takes longer than this, its task is cancelled and it is marked failed.
     /// Default: 30 minutes.
-    pub batch_timeout: Duration,
+    pub story_timeout: Duration,
+    /// When reconciliation passes run. Default: whenever no stories are in flight.
+    pub reconcile_mode: ReconcileMode,
+    /// Strategies tried, in order, on story branches that conflicted on merge
+    /// before their stories are re-run sequentially. Default: none.
+    pub merge_resolution: Vec<MergeResolution>,
+    /// Whether an agent tries to fix the issues reconciliation finds before
+    /// the affected stories are re-run sequentially. Default: false.
+    pub reconcile_agent: bool,
     /// Number of consecutive failures before circuit breaker triggers.
     /// Default: 5.
     pub circuit_breaker_threshold: u32,
+    /// Failure categories counted against their own threshold instead of
+    /// `circuit_breaker_threshold`. Default: none.
+    pub circuit_breaker_categories: BTreeMap<FailureCategory, u32>,
+    /// Whether stories share the working directory or get their own worktree.
+    pub isolation: IsolationMode,
+    /// Whether to scale concurrency between `min_concurrency` and `max_concurrency`
+    /// based on system load, API throttling and failure rate.
+    pub adaptive_concurrency: bool,
+    /// Lowest concurrency adaptive scaling may reduce to.
+    pub min_concurrency: u32,
+    /// Retry limits and backoff for stories that fail with a retryable error.
+    pub retry_policy: StoryRetryPolicy,
+    /// Resource limits applied to each worker's agent and gate subprocesses.
+    pub worker_limits: ResourceLimits,
+    /// Free memory (MB) required before another heavy story is dispatched while
+    /// other stories are running. `None` disables the check.
+    pub min_memory_headroom_mb: Option<u64>,
+    /// When a story blocked on file locks is warned about, moved to the front
+    /// of the queue, and failed.
+    pub lock_wait: LockWaitPolicy,
+    /// Agent process launches allowed per minute across all workers. `None`
+    /// launches agents without delay.
+    pub agent_launches_per_minute: Option<u32>,
+    /// Base URLs of `ralph worker` processes stories are dispatched to.
+    /// Empty runs every story locally. Default: empty.
+    pub remote_workers: Vec<String>,
+    /// Git remote (name or URL) shared with remote workers. Default: "origin".
+    pub remote_repo: String,
+    /// Token sent to remote workers. Default: none.
+    pub remote_token: Option<String>,
+    /// Stories to run; empty runs every story. Default: empty.
+    pub only_stories: Vec<String>,
+    /// Stories left out of the run. Default: empty.
+    pub skip_stories: Vec<String>,
+    /// Whether to re-plan when the PRD changes during the run, scheduling
+    /// added and edited stories. Default: false.
+    pub watch_prd: bool,
+    /// Address the live web dashboard is served on (needs the `dashboard`
+    /// feature). Default: none.
+    pub dashboard: Option<SocketAddr>,
+}
+
+impl ParallelRunnerConfig {
+    /// Whether `--only`/`--skip` limit the run to some stories.
+    pub fn filters_stories(&self) -> bool {
+        !self.only_stories.is_empty() || !self.skip_stories.is_empty()
+    }
+
+    /// Whether the story filters let `story_id` run.
+    pub fn selects(&self, story_id: &str) -> bool {
+        (self.only_stories.is_empty() || self.only_stories.iter().any(|id| id == story_id))
+            && !self.skip_stories.iter().any(|id| id == story_id)
+    }
 }
 
 impl Default for ParallelRunnerConfig {
@@ -96,8 +278,27 @@ impl Default for ParallelRunnerConfig {
             fallback_to_sequential: true,
             conflict_strategy: ConflictStrategy::default(),
             timeout_config: TimeoutConfig::default(),
-            batch_timeout: Duration::from_secs(1800), // 30 minutes
+            story_timeout: Duration::from_secs(1800), // 30 minutes
+            reconcile_mode: ReconcileMode::Idle,
+            merge_resolution: Vec::new(),
+            reconcile_agent: false,
             circuit_breaker_threshold: 5,
+            circuit_breaker_categories: BTreeMap::new(),
+            isolation: IsolationMode::default(),
+            adaptive_concurrency: false,
+            min_concurrency: 1,
+            retry_policy: StoryRetryPolicy::default(),
+            worker_limits: ResourceLimits::default(),
+            min_memory_headroom_mb: None,
+            lock_wait: LockWaitPolicy::default(),
+            agent_launches_per_minute: None,
+            remote_workers: Vec::new(),
+            remote_repo: "origin".to_string(),
+            remote_token: None,
+            only_stories: Vec::new(),
+            skip_stories: Vec::new(),
+            watch_prd: false,
+            dashboard: None,
         }
     }
 }
@@ -118,6 +319,17 @@ pub struct ParallelExecutionState {
     pub failed: HashMap<String, String>,
     /// Files currently locked by stories, mapped from file path to story ID.
     pub locked_files: HashMap<PathBuf, String>,
+    /// Files currently read-locked, mapped from file path to the reading story IDs.
+    pub read_locks: HashMap<PathBuf, HashSet<String>>,
+    /// Story branches that failed to merge, awaiting reconciliation.
+    pub merge_conflicts: Vec<ReconciliationIssue>,
+    /// Stories cancelled by the user; they are not run again.
+    pub skipped: HashSet<String>,
+    /// Stories that cannot run because a story they depend on failed or was
+    /// skipped, mapped to that story.
+    pub blocked: HashMap<String, String>,
+    /// Stories whose external prerequisites are not met, mapped to the reason.
+    pub unmet_prerequisites: HashMap<String, String>,
 }
 
 impl ParallelExecutionState {
@@ -132,22 +344,43 @@ impl ParallelExecutionState {
     /// * `story_id` - The ID of the story requesting the locks
     /// * `target_files` - List of file patterns that the story will modify
     pub fn acquire_locks(&mut self, story_id: &str, target_files: &[String]) -> bool {
+        self.acquire_read_write_locks(story_id, target_files, &[])
+    }
+
+    /// Attempts to acquire write locks on `write_files` and shared read locks on
+    /// `read_files` for a story.
+    ///
+    /// A write lock fails if another story reads or writes the file; a read lock
+    /// fails only if another story writes it. Like [`Self::acquire_locks`],
+    /// either every lock is acquired or none is.
+    pub fn acquire_read_write_locks(
+        &mut self,
+        story_id: &str,
+        write_files: &[String],
+        read_files: &[String],
+    ) -> bool {
         // First, check if any file is already locked by another story
-        for file_pattern in target_files {
-            let path = PathBuf::from(file_pattern);
-            if let Some(locking_story) = self.locked_files.get(&path) {
-                if locking_story != story_id {
-                    // File is locked by another story
-                    return false;
-                }
-            }
+        if self
+            .lock_holder(story_id, write_files, read_files)
+            .is_some()
+        {
+            return false;
         }
 
         // All files are available, acquire all locks
-        for file_pattern in target_files {
+        for file_pattern in write_files {
             let path = PathBuf::from(file_pattern);
             self.locked_files.insert(path, story_id.to_string());
         }
+        for file_pattern in read_files {
+            // A file the story also writes is covered by the write lock
+            if !write_files.contains(file_pattern) {
+                self.read_locks
+                    .entry(PathBuf::from(file_pattern))
+                    .or_default()
+                    .insert(story_id.to_string());
+            }
+        }
 
         true
     }
@@ -162,6 +395,112 @@ impl ParallelExecutionState {
     pub fn release_locks(&mut self, story_id: &str) {
         self.locked_files
             .retain(|_path, locking_story| locking_story != story_id);
+        self.read_locks.retain(|_path, readers| {
+            readers.remove(story_id);
+            !readers.is_empty()
+        });
+    }
+
+    /// Returns the first file keeping a story from acquiring its locks, with the
+    /// ID of the story holding it.
+    ///
+    /// Locks are compared as glob patterns, so a lock on `src/api/*.rs` blocks
+    /// `src/api/users.rs` and vice versa.
+    pub fn lock_holder(
+        &self,
+        story_id: &str,
+        write_files: &[String],
+        read_files: &[String],
+    ) -> Option<(PathBuf, String)> {
+        let writer = |file_pattern: &str| {
+            self.locked_files
+                .iter()
+                .filter(|(path, locking_story)| {
+                    *locking_story != story_id
+                        && patterns_match(file_pattern, &path.to_string_lossy())
+                })
+                .map(|(_, locking_story)| locking_story)
+                .min()
+                .cloned()
+        };
+        let reader = |file_pattern: &str| {
+            self.read_locks
+                .iter()
+                .filter(|(path, _)| patterns_match(file_pattern, &path.to_string_lossy()))
+                .flat_map(|(_, readers)| readers)
+                .filter(|reading_story| *reading_story != story_id)
+                .min()
+                .cloned()
+        };
+
+        let blocked_write = write_files.iter().find_map(|file_pattern| {
+            let holder = writer(file_pattern).or_else(|| reader(file_pattern))?;
+            Some((PathBuf::from(file_pattern), holder))
+        });
+        blocked_write.or_else(|| {
+            read_files.iter().find_map(|file_pattern| {
+                let holder = writer(file_pattern)?;
+                Some((PathBuf::from(file_pattern), holder))
+            })
+        })
+    }
+
+    /// Snapshots the run for a checkpoint.
+    ///
+    /// `queued` are stories waiting to be dispatched; together with the stories
+    /// in flight they are recorded as pending and run again on resume.
+    pub fn to_checkpoint(&self, queued: impl IntoIterator<Item = String>) -> ParallelCheckpoint {
+        let sorted = |ids: &HashSet<String>| {
+            let mut ids: Vec<String> = ids.iter().cloned().collect();
+            ids.sort();
+            ids
+        };
+        let mut pending: Vec<String> = self.in_flight.iter().cloned().chain(queued).collect();
+        pending.sort();
+        pending.dedup();
+        ParallelCheckpoint {
+            completed: sorted(&self.completed),
+            failed: self
+                .failed
+                .iter()
+                .map(|(id, error)| (id.clone(), error.clone()))
+                .collect(),
+            skipped: sorted(&self.skipped),
+            pending,
+            iterations: BTreeMap::new(),
+            retry_budgets: BTreeMap::new(),
+        }
+    }
+
+    /// The iteration each in-flight story has reached, from the latest
+    /// iteration reported by every dispatched story.
+    pub fn in_flight_iterations(&self, progress: &HashMap<String, u32>) -> BTreeMap<String, u32> {
+        progress
+            .iter()
+            .filter(|(story_id, _)| self.in_flight.contains(*story_id))
+            .map(|(story_id, iteration)| (story_id.clone(), *iteration))
+            .collect()
+    }
+
+    /// Restores completed and skipped stories from a checkpoint of an earlier run.
+    ///
+    /// Stories no longer in the graph are ignored. Failed and pending stories
+    /// are not restored, so they run again. Returns the IDs newly marked completed.
+    pub fn restore_checkpoint(
+        &mut self,
+        checkpoint: &ParallelCheckpoint,
+        graph: &DependencyGraph,
+    ) -> Vec<String> {
+        let known = |id: &&String| graph.get_story(id).is_some();
+        self.skipped
+            .extend(checkpoint.skipped.iter().filter(known).cloned());
+        checkpoint
+            .completed
+            .iter()
+            .filter(known)
+            .filter(|id| self.completed.insert(id.to_string()))
+            .cloned()
+            .collect()
     }
 }
 
@@ -177,11 +516,8 @@ fn detect_preexecution_conflicts(stories: &[StoryNode]) -> Vec<(String, String)>
             let story_a = &stories[i];
             let story_b = &stories[j];
 
-            // Check for overlapping target_files
-            let files_a: HashSet<&String> = story_a.target_files.iter().collect();
-            let files_b: HashSet<&String> = story_b.target_files.iter().collect();
-
-            if !files_a.is_disjoint(&files_b) {
+            // Check for files one story writes and the other reads or writes
+            if !story_a.conflicting_files(story_b).is_empty() {
                 // There is an overlap - determine which is lower priority
                 // Lower priority number = higher priority
                 if story_a.priority > story_b.priority {
@@ -213,7 +549,9 @@ fn detect_preexecution_conflicts(stories: &[StoryNode]) -> Vec<(String, String)>
 /// is deferred to a subsequent batch.
 ///
 /// Returns a tuple of (stories to run this batch, deferred story IDs for logging).
-fn filter_conflicting_stories(stories: Vec<StoryNode>) -> (Vec<StoryNode>, Vec<(String, String)>) {
+pub(crate) fn filter_conflicting_stories(
+    stories: Vec<StoryNode>,
+) -> (Vec<StoryNode>, Vec<(String, String)>) {
     if stories.is_empty() {
         return (stories, Vec::new());
     }
@@ -239,6 +577,29 @@ fn filter_conflicting_stories(stories: Vec<StoryNode>) -> (Vec<StoryNode>, Vec<(
     (filtered, conflicts)
 }
 
+/// Whether available memory is below the required headroom.
+///
+/// Unknown availability (no `/proc/meminfo`) never holds stories back.
+fn below_memory_headroom(min_headroom_mb: Option<u64>, available_mb: Option<u64>) -> bool {
+    matches!((min_headroom_mb, available_mb), (Some(min), Some(available)) if available < min)
+}
+
+/// Removes the lowest-priority story from the queue if it has lower priority
+/// than a story with `priority` (higher numbers run later).
+///
+/// Among stories of equal priority the most recently queued one is evicted.
+fn evict_lowest_priority(queue: &mut VecDeque<StoryNode>, priority: u32) -> Option<StoryNode> {
+    let (index, lowest) = queue
+        .iter()
+        .enumerate()
+        .max_by_key(|(index, story)| (story.priority, *index))?;
+    if lowest.priority > priority {
+        queue.remove(index)
+    } else {
+        None
+    }
+}
+
 /// The main parallel runner that executes multiple stories concurrently.
 ///
 /// This struct manages parallel story execution with concurrency limiting
@@ -255,10 +616,36 @@ pub struct ParallelRunner {
     execution_state: Arc<RwLock<ParallelExecutionState>>,
     /// Mutex to serialize git operations across parallel stories.
     git_mutex: Arc<Mutex<()>>,
-    /// Optional channel sender for UI events during parallel execution.
+    /// Receives the UI events instead of the terminal UI, if set.
     ui_tx: Option<mpsc::Sender<ParallelUIEvent>>,
     /// Optional checkpoint manager for circuit breaker persistence.
     checkpoint_manager: Option<CheckpointManager>,
+    /// Sender for control events (e.g., story cancellation) from the UI.
+    control_tx: mpsc::Sender<ParallelUIEvent>,
+    /// Receiver for control events, taken by `run`.
+    control_rx: Mutex<Option<mpsc::Receiver<ParallelUIEvent>>>,
+    /// Pauses dispatch of new stories (keyboard or `SIGUSR1`).
+    pause_controller: PauseController,
+    /// Checkpointed state of an earlier run to continue from.
+    resume_state: Option<ParallelCheckpoint>,
+    /// Spaces out agent launches across workers, if configured.
+    launch_limiter: Option<LaunchRateLimiter>,
+    /// Remote workers stories are dispatched to, if configured.
+    remote_pool: Option<Arc<RemoteWorkerPool>>,
+    /// Latest iteration reported by each dispatched story, for checkpoints.
+    story_iterations: Arc<std::sync::Mutex<HashMap<String, u32>>>,
+    /// Retries each story has made per error category.
+    retry_budgets: RetryBudgets,
+    /// Metrics of this run, saved with checkpoints.
+    run_metrics: RunMetricsCollector,
+    /// Webhooks told about failed stories and circuit breaker trips.
+    webhooks: Option<WebhookNotifier>,
+    /// Log file the run's events and output are mirrored to.
+    run_log: Option<RunLog>,
+    /// GitHub Actions reporting of failed gates.
+    github_actions: Option<GithubActions>,
+    /// Final report collecting the stories' failed gates.
+    run_report: Option<RunReport>,
 }
 
 #[allow(dead_code)]
@@ -272,12 +659,13 @@ impl ParallelRunner {
         let semaphore = Arc::new(Semaphore::new(config.max_concurrency as usize));
         let execution_state = Arc::new(RwLock::new(ParallelExecutionState::default()));
         let git_mutex = Arc::new(Mutex::new(()));
+        let (control_tx, control_rx) = mpsc::channel(16);
 
         // Initialize checkpoint manager if checkpointing is enabled
         let checkpoint_manager = if base_config.no_checkpoint {
             None
         } else {
-            match CheckpointManager::new(&base_config.working_dir) {
+            match CheckpointManager::for_run(&base_config.working_dir, &base_config.prd_path) {
                 Ok(manager) => Some(manager),
                 Err(e) => {
                     eprintln!("Warning: Failed to initialize checkpoint manager: {}", e);
@@ -286,6 +674,18 @@ impl ParallelRunner {
             }
         };
 
+        let launch_limiter = config
+            .agent_launches_per_minute
+            .map(LaunchRateLimiter::per_minute);
+        let remote_pool = (!config.remote_workers.is_empty()).then(|| {
+            let pool = RemoteWorkerPool::new(config.remote_workers.clone())
+                .with_remote(config.remote_repo.clone());
+            Arc::new(match config.remote_token {
+                Some(ref token) => pool.with_token(token.clone()),
+                None => pool,
+            })
+        });
+
         Self {
             config,
             base_config,
@@ -294,9 +694,91 @@ impl ParallelRunner {
             git_mutex,
             ui_tx: None,
             checkpoint_manager,
+            control_tx,
+            control_rx: Mutex::new(Some(control_rx)),
+            pause_controller: PauseController::new(),
+            resume_state: None,
+            launch_limiter,
+            remote_pool,
+            story_iterations: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            retry_budgets: RetryBudgets::default(),
+            run_metrics: RunMetricsCollector::new(generate_run_id(), 0),
+            webhooks: None,
+            run_log: None,
+            github_actions: None,
+            run_report: None,
         }
     }
 
+    /// Continue from the checkpointed state of an earlier run.
+    ///
+    /// Stories the earlier run completed are not run again; failed and
+    /// interrupted stories are retried.
+    pub fn with_resume_state(mut self, resume_state: ParallelCheckpoint) -> Self {
+        self.retry_budgets.restore(&resume_state.retry_budgets);
+        self.resume_state = Some(resume_state);
+        self
+    }
+
+    /// Continue the metrics of the checkpointed run under its run ID instead
+    /// of starting a new run.
+    pub fn with_resume_metrics(mut self, metrics: RunMetrics) -> Self {
+        self.run_metrics = RunMetricsCollector::resume(metrics);
+        self
+    }
+
+    /// Send the run's UI events to `sender` instead of rendering them in the
+    /// terminal.
+    pub fn with_ui_sender(mut self, sender: mpsc::Sender<ParallelUIEvent>) -> Self {
+        self.ui_tx = Some(sender);
+        self
+    }
+
+    /// Tell `webhooks` about failed stories and circuit breaker trips.
+    pub fn with_webhooks(mut self, webhooks: WebhookNotifier) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Mirror the run's events and full agent and gate output to `run_log`.
+    pub fn with_run_log(mut self, run_log: RunLog) -> Self {
+        self.run_log = Some(run_log);
+        self
+    }
+
+    /// Report failed quality gates to GitHub Actions as annotations.
+    pub fn with_github_actions(mut self, github_actions: GithubActions) -> Self {
+        self.github_actions = Some(github_actions);
+        self
+    }
+
+    /// Collect the stories' failed gates for the final report.
+    pub fn with_run_report(mut self, run_report: RunReport) -> Self {
+        self.run_report = Some(run_report);
+        self
+    }
+
+    /// Returns the collector of the run's metrics.
+    pub fn run_metrics(&self) -> RunMetricsCollector {
+        self.run_metrics.clone()
+    }
+
+    /// Returns the controller that pauses and resumes dispatch.
+    ///
+    /// A requested pause stops new stories from starting; once in-flight
+    /// stories finish a checkpoint is written and the run waits to resume.
+    pub fn pause_controller(&self) -> PauseController {
+        self.pause_controller.clone()
+    }
+
+    /// Returns a sender for UI control events to this runner.
+    ///
+    /// Sending `ParallelUIEvent::CancelStoryRequested` stops that story and
+    /// marks it skipped while the rest of the run continues.
+    pub fn control_sender(&self) -> mpsc::Sender<ParallelUIEvent> {
+        self.control_tx.clone()
+    }
+
     /// Run all stories in parallel until all pass or an error occurs.
     ///
     /// This method implements the main parallel execution loop:
@@ -306,8 +788,8 @@ impl ParallelRunner {
     /// 4. Waits for any task to complete and updates state
     /// 5. Repeats until all stories pass or cannot make progress
     pub async fn run(&self) -> RunResult {
-        let run_id = generate_run_id();
-        let run_metrics = RunMetricsCollector::new(run_id.clone(), 0);
+        let run_metrics = self.run_metrics.clone();
+        let run_id = run_metrics.run_id();
         let metrics_store = match RunMetricsStore::new(&self.base_config.working_dir) {
             Ok(store) => Some(store),
             Err(err) => {
@@ -335,9 +817,34 @@ impl ParallelRunner {
                 None
             }
         };
+        record_reconciliation(&evidence, self.config.reconcile_mode, "configured", &[]).await;
+        let mut trace = match SchedulerTrace::create(&self.base_config.working_dir, &run_id) {
+            Ok(trace) => trace,
+            Err(err) => {
+                eprintln!("Warning: Failed to create scheduler trace: {}", err);
+                SchedulerTrace::disabled()
+            }
+        };
+        let artifacts_dir = evidence_artifacts_dir(&evidence).await;
+        let gate_evidence = evidence_writer(&evidence).await;
+        let worktrees = match self.config.isolation {
+            IsolationMode::Shared => None,
+            IsolationMode::Worktree | IsolationMode::Branch => {
+                match WorktreeManager::new(&self.base_config.working_dir) {
+                    Ok(manager) => Some(manager.with_run_id(&run_id)),
+                    Err(err) => {
+                        eprintln!(
+                        "Warning: Worktree isolation unavailable, using shared working directory: {}",
+                        err
+                    );
+                        None
+                    }
+                }
+            }
+        };
 
         // Load and validate PRD
-        let prd = match self.load_prd() {
+        let mut prd = match self.load_prd() {
             Ok(prd) => prd,
             Err(e) => {
                 emit_run_complete(
@@ -354,14 +861,40 @@ impl ParallelRunner {
                     total_stories: 0,
                     total_iterations: 0,
                     error: Some(format!("Failed to load PRD: {}", e)),
+                    outcome: RunOutcome::Error,
                 };
             }
         };
 
-        let total_stories = prd.user_stories.len();
+        self.mark_resumed_passes(&mut prd);
 
-        // Build dependency graph
+        // Build dependency graph, limited to the selected stories
         let mut graph = DependencyGraph::from_stories(&prd.user_stories);
+        if self.config.filters_stories() {
+            graph = match graph.select(&self.config.only_stories, &self.config.skip_stories) {
+                Ok(selected) => selected,
+                Err(e) => {
+                    emit_run_complete(
+                        &evidence,
+                        "failed",
+                        Some("fatal".to_string()),
+                        Some(format!("Invalid story filter: {}", e)),
+                    )
+                    .await;
+                    save_metrics(&run_metrics);
+                    return RunResult {
+                        all_passed: false,
+                        stories_passed: 0,
+                        total_stories: prd.user_stories.len(),
+                        total_iterations: 0,
+                        error: Some(format!("Invalid story filter: {}", e)),
+                        outcome: RunOutcome::Error,
+                    };
+                }
+            };
+            prd.user_stories.retain(|story| graph.get_story(&story.id).is_some());
+        }
+        let mut total_stories = prd.user_stories.len();
 
         // Optionally infer dependencies from file patterns
         if self.config.infer_dependencies {
@@ -384,24 +917,48 @@ impl ParallelRunner {
                 total_stories,
                 total_iterations: 0,
                 error: Some(format!("Invalid dependency graph: {}", e)),
+                outcome: RunOutcome::Error,
             };
         }
 
         // Count already passing stories
-        let initially_passing: HashSet<String> = prd
+        let mut initially_passing: HashSet<String> = prd
             .user_stories
             .iter()
             .filter(|s| s.passes)
             .map(|s| s.id.clone())
             .collect();
-        let expected_steps = total_stories.saturating_sub(initially_passing.len());
-        run_metrics.set_expected_steps(expected_steps);
 
-        // Initialize completed set with already passing stories
+        // Initialize completed set with already passing stories, plus the
+        // stories a resumed run had already completed
         {
             let mut state = self.execution_state.write().await;
             state.completed = initially_passing.clone();
+            if let Some(ref checkpoint) = self.resume_state {
+                let restored = state.restore_checkpoint(checkpoint, &graph);
+                if !self.base_config.display_options.quiet {
+                    println!(
+                        "Resuming parallel run: {} completed stories will not run again, \
+                         {} failed and {} interrupted stories will be retried",
+                        restored.len(),
+                        checkpoint
+                            .failed
+                            .keys()
+                            .filter(|id| graph.get_story(id).is_some())
+                            .count(),
+                        checkpoint
+                            .pending
+                            .iter()
+                            .filter(|id| graph.get_story(id).is_some())
+                            .count()
+                    );
+                }
+                initially_passing.extend(restored);
+            }
         }
+        let expected_steps = total_stories.saturating_sub(initially_passing.len());
+        run_metrics.set_expected_steps(expected_steps);
+        record_stories(&prd, &graph, &run_metrics, &evidence).await;
 
         // Check if all stories already pass - no agent needed in this case
         if initially_passing.len() == total_stories {
@@ -413,6 +970,9 @@ impl ParallelRunner {
                 );
                 display.display_completion(total_stories, total_stories, 0);
             }
+            if self.resume_state.is_some() {
+                self.complete_checkpoint().await;
+            }
             emit_run_complete(&evidence, "success", None, None).await;
             save_metrics(&run_metrics);
             return RunResult {
@@ -421,6 +981,7 @@ impl ParallelRunner {
                 total_stories,
                 total_iterations: 0,
                 error: None,
+                outcome: RunOutcome::Passed,
             };
         }
 
@@ -433,7 +994,7 @@ impl ParallelRunner {
                     "failed",
                     Some("fatal".to_string()),
                     Some(
-                        "No agent found. Install Claude Code CLI, Codex CLI, or Amp CLI."
+                        "No agent found. Install Claude Code CLI, Codex CLI, Amp CLI, or Aider."
                             .to_string(),
                     ),
                 )
@@ -445,18 +1006,57 @@ impl ParallelRunner {
                     total_stories,
                     total_iterations: 0,
                     error: Some(
-                        "No agent found. Install Claude Code CLI, Codex CLI, or Amp CLI."
+                        "No agent found. Install Claude Code CLI, Codex CLI, Amp CLI, or Aider."
                             .to_string(),
                     ),
+                    outcome: RunOutcome::Error,
+                };
+            }
+        };
+
+        // Probe the agent CLIs now, including those assigned to particular
+        // stories, so a missing agent or unsupported flags surface before any
+        // story starts. A containerized agent is not installed on the host to
+        // probe.
+        let probe = AgentRoster::load(
+            &self.base_config.working_dir,
+            &agent,
+            &prd.user_stories,
+            self.base_config.agent_container.is_some(),
+        )
+        .await;
+        let agents = match probe {
+            Ok(agents) => agents,
+            Err(e) => {
+                emit_run_complete(
+                    &evidence,
+                    "failed",
+                    Some("fatal".to_string()),
+                    Some(e.to_string()),
+                )
+                .await;
+                save_metrics(&run_metrics);
+                return RunResult {
+                    all_passed: false,
+                    stories_passed: initially_passing.len(),
+                    total_stories,
+                    total_iterations: 0,
+                    error: Some(e.to_string()),
+                    outcome: RunOutcome::Error,
                 };
             }
         };
+        if let Some(capabilities) = agents.default_capabilities() {
+            run_metrics.set_agent_capabilities(capabilities.clone());
+        }
 
         let mut total_iterations: u32 = 0;
 
         // Check if UI should be enabled based on display options
-        // Skip UI rendering when quiet mode is set or UI mode is disabled
-        let should_enable_ui = !self.base_config.display_options.quiet
+        // Skip UI rendering when quiet mode is set, UI mode is disabled or the
+        // events go to another consumer
+        let should_enable_ui = self.ui_tx.is_none()
+            && !self.base_config.display_options.quiet
             && self.base_config.display_options.should_enable_rich_ui();
 
         // Create UI channel and spawn event handler if UI is enabled
@@ -481,11 +1081,46 @@ impl ParallelRunner {
                 })
                 .collect();
             display.init_stories(&story_infos);
+            // Estimate the stories' ETAs from previous runs
+            let history = metrics_store
+                .as_ref()
+                .and_then(|store| store.load_all().ok())
+                .unwrap_or_default();
+            let estimates = IterationEstimates::from_history(&history);
+            for story in &prd.user_stories {
+                let agent = graph
+                    .get_story(&story.id)
+                    .map(|node| agents.for_story(node).label());
+                if let Some(iteration) = agent.and_then(|agent| estimates.iteration(&agent)) {
+                    display.set_story_estimate(
+                        &story.id,
+                        iteration,
+                        estimates.iterations(&story.id),
+                    );
+                }
+            }
+            let titles: HashMap<String, String> = story_infos
+                .into_iter()
+                .map(|info| (info.id, info.title))
+                .collect();
 
             // Spawn event handling task
             Some(tokio::spawn(async move {
                 let mut rx = ui_rx;
-                while let Some(event) = rx.recv().await {
+                // ETAs count down between events
+                let mut eta_tick = tokio::time::interval(Duration::from_secs(1));
+                loop {
+                    let event = tokio::select! {
+                        event = rx.recv() => match event {
+                            Some(event) => event,
+                            None => break,
+                        },
+                        _ = eta_tick.tick() => {
+                            display.refresh_progress();
+                            continue;
+                        }
+                    };
+                    display.update_details(&event);
                     match &event {
                         ParallelUIEvent::StoryStarted {
                             story,
@@ -544,6 +1179,21 @@ impl ParallelRunner {
                         ParallelUIEvent::SequentialRetryStarted { story_id, reason } => {
                             display.story_sequential_retry(story_id, story_id, reason);
                         }
+                        ParallelUIEvent::StorySkipped { story_id, reason } => {
+                            display.story_skipped(story_id, story_id, reason);
+                        }
+                        ParallelUIEvent::StoryBlocked {
+                            story_id,
+                            blocked_by,
+                        } => {
+                            display.story_blocked(story_id, story_id, blocked_by);
+                        }
+                        ParallelUIEvent::PrerequisiteUnmet { story_id, reason } => {
+                            display.story_prerequisite_unmet(story_id, story_id, reason);
+                        }
+                        ParallelUIEvent::AgentOutput { story_id, line, .. } => {
+                            display.story_output(story_id, line);
+                        }
                         ParallelUIEvent::GateUpdate { .. }
                         | ParallelUIEvent::ReconciliationStatus { .. } => {
                             // These events don't have direct display methods yet
@@ -562,12 +1212,44 @@ impl ParallelRunner {
                             // Display clear notification when circuit breaker triggers
                             display.display_circuit_breaker_triggered(*failures, *threshold);
                         }
+                        ParallelUIEvent::ConcurrencyAdjusted {
+                            previous,
+                            current,
+                            reason,
+                        } => {
+                            display.display_concurrency_adjusted(*previous, *current, reason);
+                        }
+                        ParallelUIEvent::RunPausing { in_flight } => {
+                            display.display_pausing(*in_flight)
+                        }
+                        ParallelUIEvent::RunPaused => display.display_paused(),
+                        ParallelUIEvent::RunResumed => display.display_resumed(),
+                        ParallelUIEvent::StorySelected { story_id } => {
+                            let title = titles.get(story_id).map_or("", String::as_str);
+                            display.story_selected(story_id, title);
+                        }
+                        ParallelUIEvent::StoryVerbosityChanged { story_id, verbose } => {
+                            display.set_story_verbose(story_id, *verbose);
+                        }
+                        ParallelUIEvent::StoryDetailsToggled { story_id } => {
+                            display.toggle_details(story_id);
+                        }
                         ParallelUIEvent::KeyboardToggle { .. }
+                        | ParallelUIEvent::CancelStoryRequested { .. }
+                        | ParallelUIEvent::TogglePauseRequested
+                        | ParallelUIEvent::ConcurrencyChangeRequested { .. }
+                        | ParallelUIEvent::VerboseToggleRequested { .. }
                         | ParallelUIEvent::GracefulQuitRequested
                         | ParallelUIEvent::ImmediateInterrupt => {
                             // Keyboard events are handled separately by the keyboard listener
                         }
                     }
+                    // A story that stopped running no longer shows its output
+                    if event.is_terminal() {
+                        if let Some(story_id) = event.story_id() {
+                            display.close_output_panel(story_id);
+                        }
+                    }
                 }
             }))
         } else {
@@ -576,16 +1258,85 @@ impl ParallelRunner {
             None
         };
 
+        // Listen for run control keys (pause, story selection, skip and cancel,
+        // concurrency, verbose output, Ctrl+C) while the UI is shown
+        let _keyboard = if should_enable_ui && std::io::stdin().is_terminal() {
+            let control = RunControl::new(
+                prd.user_stories.iter().map(|s| s.id.clone()).collect(),
+                self.control_tx.clone(),
+            );
+            Some(
+                KeyboardListener::new(Arc::new(ToggleState::default()))
+                    .with_run_control(control)
+                    .start(),
+            )
+        } else {
+            None
+        };
+        let mut control_rx = self.control_rx.lock().await.take();
+        // SIGUSR1 toggles pause, e.g. `kill -USR1 <pid>` from another terminal
+        #[cfg(unix)]
+        let _pause_signal = crate::pause::spawn_signal_toggle(self.pause_controller.clone())
+            .ok()
+            .map(AbortOnDrop);
+
         // Store sender for use in spawned tasks (only if UI enabled)
         let ui_sender: Option<mpsc::Sender<ParallelUIEvent>> = if should_enable_ui {
             Some(ui_tx)
         } else {
             drop(ui_tx);
-            None
+            self.ui_tx.clone()
+        };
+
+        // The dashboard sees every event first and passes it on to the terminal UI
+        #[cfg(feature = "dashboard")]
+        let _dashboard = match self.config.dashboard {
+            Some(addr) => {
+                let token_budget = self
+                    .base_config
+                    .budget_config
+                    .as_ref()
+                    .map(|budget| budget.total_budget)
+                    .filter(|&total| total > 0);
+                match crate::ui::dashboard::Dashboard::start(
+                    addr,
+                    &prd.user_stories,
+                    Some(self.run_metrics.clone()),
+                    token_budget,
+                    ui_sender.clone(),
+                )
+                .await
+                {
+                    Ok(dashboard) => {
+                        if !self.base_config.display_options.quiet {
+                            println!("Dashboard: http://{}", dashboard.addr());
+                        }
+                        Some(dashboard)
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: could not serve the dashboard on {}: {}", addr, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        #[cfg(feature = "dashboard")]
+        let ui_sender = _dashboard
+            .as_ref()
+            .map(|dashboard| dashboard.sender())
+            .or(ui_sender);
+        let ui_sender = match self.webhooks {
+            Some(ref webhooks) => Some(webhooks.tap(ui_sender)),
+            None => ui_sender,
+        };
+        let ui_sender = match self.run_log {
+            Some(ref run_log) => Some(run_log.tap(ui_sender)),
+            None => ui_sender,
         };
 
         // Build story info lookup for event creation
-        let story_info_map: HashMap<String, StoryDisplayInfo> = prd
+        let mut story_info_map: HashMap<String, StoryDisplayInfo> = prd
             .user_stories
             .iter()
             .map(|s| {
@@ -597,14 +1348,35 @@ impl ParallelRunner {
             .collect();
 
         // Circuit breaker: track cumulative failures across batches
-        let mut cumulative_failures: u32 = 0;
-        let circuit_breaker_threshold = self.config.circuit_breaker_threshold;
+        let mut circuit_breaker = CircuitBreaker::new(
+            self.config.circuit_breaker_threshold,
+            self.config.circuit_breaker_categories.clone(),
+        );
+        // Category of each story's latest failure, for the run's outcome
+        let mut failure_categories: HashMap<String, FailureCategory> = HashMap::new();
+
+        // Adaptive concurrency: resize the semaphore as story outcomes come in
+        let concurrency = if self.config.adaptive_concurrency {
+            Some(Arc::new(Mutex::new(ConcurrencyController::new(
+                AdaptiveConcurrencyConfig {
+                    min_concurrency: self.config.min_concurrency,
+                    max_concurrency: self.config.max_concurrency,
+                    ..Default::default()
+                },
+            ))))
+        } else {
+            None
+        };
+        // Concurrency without the adaptive controller, changed with the +/- keys
+        let mut concurrency_limit = self.config.max_concurrency;
+        // Stories whose agent output the user asked to see in full
+        let mut verbose_stories: HashSet<String> = HashSet::new();
 
         // Send initial circuit breaker status
         if let Some(ref sender) = ui_sender {
             let _ = sender.try_send(ParallelUIEvent::CircuitBreakerStatus {
-                current_failures: cumulative_failures,
-                threshold: circuit_breaker_threshold,
+                current_failures: 0,
+                threshold: self.config.circuit_breaker_threshold,
             });
         }
 
@@ -615,68 +1387,399 @@ impl ParallelRunner {
         // Main execution loop
         let mut pending_queue: VecDeque<StoryNode> = VecDeque::new();
         let mut queued_ids: HashSet<String> = HashSet::new();
+        // Order stories became ready in; requeued stories keep their place in it
+        let mut ready_order = ReadyOrder::new();
+        // When each queued story was first queued, for its queue wait
+        let mut queued_at: HashMap<String, Instant> = HashMap::new();
+        // Set when a cycle ended with stories queued and every worker slot taken
+        let mut slots_taken_since: Option<Instant> = None;
         let mut last_queue_size: Option<usize> = None;
+        // Stories run as individual tasks; new work is dispatched as each one finishes
+        let mut tasks: JoinSet<(String, StoryTaskOutput)> = JoinSet::new();
+        // Story each task runs and when it was spawned, so a panicked task can be recorded
+        let mut task_stories: HashMap<tokio::task::Id, (String, Instant)> = HashMap::new();
+        // Stories finished since the last reconciliation pass
+        let mut finished_since_reconcile: Vec<String> = Vec::new();
+        // Whether every story has finished and the end-of-run pass is due
+        let mut reconcile_at_end = false;
+        // Per-story cancel channels for stories in flight
+        let mut story_cancels: HashMap<String, watch::Sender<bool>> = HashMap::new();
+        // Stories requeued because their target files are locked
+        let mut lock_waits = LockWaitTracker::new(self.config.lock_wait.clone());
+        // Stories that gave up waiting for locks; unlike other failures they are not re-run
+        let mut lock_wait_failed: HashSet<String> = HashSet::new();
+        // Whether the run is currently paused (dispatch stopped, nothing in flight)
+        let mut paused = false;
+        // Outstanding dependencies last traced per story, so unchanged waits are not repeated
+        let mut traced_dependency_waits: HashMap<String, Vec<String>> = HashMap::new();
+        // Stories whose external prerequisites were checked and met
+        let mut prerequisites_met: HashSet<String> = HashSet::new();
+        // When a checkpoint was last autosaved, and whether one has been
+        let mut last_autosave = Instant::now();
+        let mut autosaved = false;
+        let stop_file = StopFile::new(&self.base_config.working_dir);
+        // Polls the PRD for stories added or edited during the run
+        let mut prd_watcher = self
+            .config
+            .watch_prd
+            .then(|| PrdWatcher::new(&self.base_config.prd_path));
         loop {
-            // Get current state snapshot
-            let state = self.execution_state.read().await;
-            let completed = state.completed.clone();
-            let in_flight = state.in_flight.clone();
-            drop(state);
-
-            // Get stories ready to execute (dependencies satisfied, not completed, not in flight)
-            // Keep the full StoryNode so we have access to target_files for locking
-            let ready_stories: Vec<_> = graph
-                .get_ready_stories(&completed)
-                .into_iter()
-                .filter(|s| !in_flight.contains(&s.id) && !queued_ids.contains(&s.id))
-                .cloned()
-                .collect();
+            trace.next_cycle();
+
+            // Re-plan when the PRD changed: new stories join the graph and
+            // queued stories pick up their edits
+            if prd_watcher.as_mut().is_some_and(PrdWatcher::changed) {
+                let in_flight = self.execution_state.read().await.in_flight.clone();
+                match self.replan(&prd, &in_flight) {
+                    Ok(Some((new_prd, new_graph, changes))) => {
+                        {
+                            let mut state = self.execution_state.write().await;
+                            for id in &changes.removed {
+                                state.completed.remove(id);
+                            }
+                            // An edit may fix a story's prerequisites
+                            for id in changes.edited.iter().chain(&changes.removed) {
+                                state.unmet_prerequisites.remove(id);
+                            }
+                            for story in &new_prd.user_stories {
+                                if story.passes && changes.added.contains(&story.id) {
+                                    state.completed.insert(story.id.clone());
+                                }
+                            }
+                        }
+                        for id in changes.edited.iter().chain(&changes.removed) {
+                            prerequisites_met.remove(id);
+                        }
+                        for id in &changes.removed {
+                            queued_ids.remove(id);
+                            queued_at.remove(id);
+                            ready_order.release(id);
+                            lock_waits.clear(id);
+                        }
+                        pending_queue.retain(|story| new_graph.get_story(&story.id).is_some());
+                        for story in pending_queue.iter_mut() {
+                            if let Some(node) = new_graph.get_story(&story.id) {
+                                *story = node.clone();
+                            }
+                        }
+                        for story in &new_prd.user_stories {
+                            story_info_map.insert(
+                                story.id.clone(),
+                                StoryDisplayInfo::new(&story.id, &story.title, story.priority),
+                            );
+                        }
+                        total_stories = new_prd.user_stories.len();
+                        graph = new_graph;
+                        prd = new_prd;
+                        record_stories(&prd, &graph, &run_metrics, &evidence).await;
 
-            // Pre-execution conflict detection: filter out lower-priority stories
-            // that have overlapping target_files with higher-priority stories
-            let (ready_stories, conflicts) = filter_conflicting_stories(ready_stories);
-            let ready_empty = ready_stories.is_empty();
+                        if !self.base_config.display_options.quiet {
+                            println!(
+                                "PRD changed: {} added, {} edited, {} removed",
+                                changes.added.len(),
+                                changes.edited.len(),
+                                changes.removed.len()
+                            );
+                        }
+                        record_prd_change(&evidence, "applied", &changes, None).await;
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        eprintln!("Warning: Ignoring PRD change: {}", error);
+                        record_prd_change(
+                            &evidence,
+                            "rejected",
+                            &PrdChanges::default(),
+                            Some(&error),
+                        )
+                        .await;
+                    }
+                }
+            }
 
-            // Send ConflictDeferred events when stories are deferred due to conflicts
-            for (deferred_id, higher_priority_id) in &conflicts {
-                if let Some(ref sender) = ui_sender {
-                    // Find conflicting files between the two stories
-                    let deferred_story = graph.get_story(deferred_id);
-                    let blocking_story = graph.get_story(higher_priority_id);
-                    let conflicting_files: Vec<PathBuf> = if let (Some(deferred), Some(blocking)) =
-                        (deferred_story, blocking_story)
-                    {
-                        let deferred_files: HashSet<&String> =
-                            deferred.target_files.iter().collect();
-                        blocking
-                            .target_files
-                            .iter()
-                            .filter(|f| deferred_files.contains(f))
-                            .map(PathBuf::from)
-                            .collect()
-                    } else {
-                        Vec::new()
-                    };
+            // The stop file halts the run: in-flight agents are cancelled and
+            // the run is checkpointed, with those stories to be retried
+            if stop_file.is_requested() {
+                let reason = stop_file.reason();
+                let _ = cancel_tx.send(true);
+                let queued = pending_queue.iter().map(|story| story.id.clone()).collect();
+                self.save_checkpoint(
+                    None,
+                    PauseReason::StopRequested {
+                        reason: reason.clone(),
+                    },
+                    queued,
+                )
+                .await;
+                // Dropping the story tasks kills their agents
+                tasks.shutdown().await;
+                if let Err(e) = stop_file.clear() {
+                    eprintln!(
+                        "Warning: Failed to remove {}: {}",
+                        stop_file.path().display(),
+                        e
+                    );
+                }
 
-                    let event = ParallelUIEvent::ConflictDeferred {
-                        story_id: deferred_id.clone(),
-                        blocking_story_id: higher_priority_id.clone(),
-                        conflicting_files,
-                    };
-                    let _ = sender.try_send(event);
+                let message = match reason {
+                    Some(reason) => format!("Stopped by {}: {}", STOP_FILE, reason),
+                    None => format!("Stopped by {}", STOP_FILE),
+                };
+                // Another consumer of the events gets the message with the result
+                if self.ui_tx.is_none() {
+                    println!();
+                    println!("{}", message);
+                    println!("Execution stopped. Resume with: ralph --resume");
+                    println!();
                 }
-            }
 
-            // Enqueue ready stories with backpressure handling
-            let mut blocked_on_queue = false;
-            for story in ready_stories {
-                if pending_queue.len() >= self.config.queue_capacity {
-                    match self.config.queue_policy {
-                        QueuePolicy::Block => {
-                            blocked_on_queue = true;
-                            break;
+                emit_run_complete(
+                    &evidence,
+                    "failed",
+                    Some("stopped".to_string()),
+                    Some(message.clone()),
+                )
+                .await;
+                save_metrics(&run_metrics);
+                let state = self.execution_state.read().await;
+                return RunResult {
+                    all_passed: false,
+                    stories_passed: state.completed.len(),
+                    total_stories,
+                    total_iterations,
+                    error: Some(message),
+                    outcome: RunOutcome::Interrupted,
+                };
+            }
+
+            // Reconcile once in-flight work has drained, as often as the mode allows
+            let reconcile_due = tasks.is_empty()
+                && !finished_since_reconcile.is_empty()
+                && (self.config.reconcile_mode != ReconcileMode::AtEnd || reconcile_at_end);
+            if reconcile_due {
+                let finished_story_ids = std::mem::take(&mut finished_since_reconcile);
+                let skip = self.config.reconcile_mode == ReconcileMode::SharedDirectories
+                    && !share_parent_directory(&graph, &finished_story_ids);
+                record_reconciliation(
+                    &evidence,
+                    self.config.reconcile_mode,
+                    if skip { "skipped" } else { "ran" },
+                    &finished_story_ids,
+                )
+                .await;
+                if !skip {
+                    let reconciliation_result = self
+                        .run_reconciliation(
+                            &finished_story_ids,
+                            &graph,
+                            &agents,
+                            &mut total_iterations,
+                            &evidence,
+                            &run_metrics,
+                            &ui_sender,
+                            &story_info_map,
+                        )
+                        .await;
+
+                    // If reconciliation failed and we couldn't recover, return error
+                    if let Some(error) = reconciliation_result {
+                        let state = self.execution_state.read().await;
+                        emit_run_complete(
+                            &evidence,
+                            "failed",
+                            Some("reconciliation_failed".to_string()),
+                            Some(error.clone()),
+                        )
+                        .await;
+                        save_metrics(&run_metrics);
+                        return RunResult {
+                            all_passed: false,
+                            stories_passed: state.completed.len(),
+                            total_stories,
+                            total_iterations,
+                            error: Some(error),
+                            outcome: RunOutcome::Error,
+                        };
+                    }
+                }
+            }
+
+            // Mark stories whose dependencies failed or were skipped as blocked
+            let newly_blocked: Vec<(String, String)> = {
+                let mut state = self.execution_state.write().await;
+                let blocked = blocked_by_failures(&graph, &state);
+                let newly_blocked = blocked
+                    .iter()
+                    .filter(|(story_id, blocked_by)| {
+                        state.blocked.get(*story_id) != Some(blocked_by)
+                    })
+                    .map(|(story_id, blocked_by)| (story_id.clone(), blocked_by.clone()))
+                    .collect();
+                state.blocked = blocked;
+                newly_blocked
+            };
+            for (story_id, blocked_by) in newly_blocked {
+                trace.record(
+                    Some(&story_id),
+                    SchedulerDecision::BlockedByFailure {
+                        blocked_by: blocked_by.clone(),
+                    },
+                );
+                if let Some(ref sender) = ui_sender {
+                    let _ = sender.try_send(ParallelUIEvent::StoryBlocked {
+                        story_id,
+                        blocked_by,
+                    });
+                }
+            }
+
+            // Get current state snapshot
+            let state = self.execution_state.read().await;
+            let completed = state.completed.clone();
+            let in_flight = state.in_flight.clone();
+            let skipped = state.skipped.clone();
+            let mut unmet_prerequisites = state.unmet_prerequisites.clone();
+            drop(state);
+
+            // Get stories ready to execute (dependencies satisfied, not completed, not in flight)
+            // Keep the full StoryNode so we have access to target_files for locking
+            let ready_stories: Vec<_> = graph
+                .get_ready_stories(&completed)
+                .into_iter()
+                .filter(|s| {
+                    !in_flight.contains(&s.id)
+                        && !queued_ids.contains(&s.id)
+                        && !skipped.contains(&s.id)
+                        && !lock_wait_failed.contains(&s.id)
+                        && !unmet_prerequisites.contains_key(&s.id)
+                })
+                .cloned()
+                .collect();
+
+            // Check external prerequisites before a story is first queued; a
+            // story whose prerequisites are not met is blocked, not dispatched
+            for story in &ready_stories {
+                if prerequisites_met.contains(&story.id) {
+                    continue;
+                }
+                let prerequisites = prd
+                    .user_stories
+                    .iter()
+                    .find(|prd_story| prd_story.id == story.id)
+                    .map(|prd_story| prd_story.prerequisites.as_slice())
+                    .unwrap_or_default();
+                let working_dir = &self.base_config.working_dir;
+                match check_prerequisites(prerequisites, working_dir).await {
+                    Ok(()) => {
+                        prerequisites_met.insert(story.id.clone());
+                    }
+                    Err(reason) => {
+                        self.execution_state
+                            .write()
+                            .await
+                            .unmet_prerequisites
+                            .insert(story.id.clone(), reason.clone());
+                        record_unmet_prerequisite(&story.id, &reason, &evidence, &run_metrics)
+                            .await;
+                        trace.record(
+                            Some(&story.id),
+                            SchedulerDecision::PrerequisiteUnmet {
+                                reason: reason.clone(),
+                            },
+                        );
+                        if let Some(ref sender) = ui_sender {
+                            let _ = sender.try_send(ParallelUIEvent::PrerequisiteUnmet {
+                                story_id: story.id.clone(),
+                                reason: reason.clone(),
+                            });
+                        }
+                        unmet_prerequisites.insert(story.id.clone(), reason);
+                    }
+                }
+            }
+            let ready_stories: Vec<_> = ready_stories
+                .into_iter()
+                .filter(|story| !unmet_prerequisites.contains_key(&story.id))
+                .collect();
+
+            // Trace stories still waiting on dependencies when what they wait on changes
+            for story in graph.graph().node_weights() {
+                if story.passes || completed.contains(&story.id) || in_flight.contains(&story.id) {
+                    traced_dependency_waits.remove(&story.id);
+                    continue;
+                }
+                let pending: Vec<String> = story
+                    .depends_on
+                    .iter()
+                    .filter(|dependency| !completed.contains(*dependency))
+                    .cloned()
+                    .collect();
+                if !pending.is_empty() && traced_dependency_waits.get(&story.id) != Some(&pending) {
+                    trace.record(
+                        Some(&story.id),
+                        SchedulerDecision::WaitingOnDependencies {
+                            pending: pending.clone(),
+                        },
+                    );
+                    traced_dependency_waits.insert(story.id.clone(), pending);
+                }
+            }
+
+            // Pre-execution conflict detection: filter out lower-priority stories
+            // that have overlapping target_files with higher-priority stories
+            let (ready_stories, conflicts) = filter_conflicting_stories(ready_stories);
+            let ready_empty = ready_stories.is_empty();
+
+            // Trace deferred stories and send ConflictDeferred events
+            for (deferred_id, higher_priority_id) in &conflicts {
+                // Find conflicting files between the two stories
+                let deferred_story = graph.get_story(deferred_id);
+                let blocking_story = graph.get_story(higher_priority_id);
+                let conflicting_files = match (deferred_story, blocking_story) {
+                    (Some(deferred), Some(blocking)) => deferred.conflicting_files(blocking),
+                    _ => Vec::new(),
+                };
+                run_metrics.record_conflict_deferral(deferred_id);
+                trace.record(
+                    Some(deferred_id),
+                    SchedulerDecision::Deferred {
+                        blocking: higher_priority_id.clone(),
+                        files: conflicting_files.clone(),
+                    },
+                );
+
+                if let Some(ref sender) = ui_sender {
+                    let event = ParallelUIEvent::ConflictDeferred {
+                        story_id: deferred_id.clone(),
+                        blocking_story_id: higher_priority_id.clone(),
+                        conflicting_files: conflicting_files.iter().map(PathBuf::from).collect(),
+                    };
+                    let _ = sender.try_send(event);
+                }
+            }
+
+            // Enqueue ready stories with backpressure handling
+            for story in ready_stories {
+                if pending_queue.len() >= self.config.queue_capacity {
+                    match self.config.queue_policy {
+                        // Leave the rest unqueued until dispatch frees room
+                        QueuePolicy::Block => {
+                            trace.record(
+                                Some(&story.id),
+                                SchedulerDecision::QueueFull {
+                                    capacity: self.config.queue_capacity,
+                                },
+                            );
+                            break;
                         }
                         QueuePolicy::Reject => {
+                            trace.record(
+                                Some(&story.id),
+                                SchedulerDecision::Rejected {
+                                    reason: "Queue full - rejected by backpressure policy"
+                                        .to_string(),
+                                },
+                            );
                             let mut state = self.execution_state.write().await;
                             state.failed.insert(
                                 story.id.clone(),
@@ -712,6 +1815,15 @@ impl ParallelRunner {
                         QueuePolicy::DropOldest => {
                             if let Some(dropped) = pending_queue.pop_front() {
                                 queued_ids.remove(&dropped.id);
+                                queued_at.remove(&dropped.id);
+                                ready_order.release(&dropped.id);
+                                lock_waits.clear(&dropped.id);
+                                trace.record(
+                                    Some(&dropped.id),
+                                    SchedulerDecision::Rejected {
+                                        reason: "Queue full - dropped oldest".to_string(),
+                                    },
+                                );
                                 let mut state = self.execution_state.write().await;
                                 state.failed.insert(
                                     dropped.id.clone(),
@@ -744,11 +1856,37 @@ impl ParallelRunner {
                                 }
                             }
                         }
+                        QueuePolicy::EvictLowestPriority => {
+                            let Some(evicted) =
+                                evict_lowest_priority(&mut pending_queue, story.priority)
+                            else {
+                                // Nothing queued ranks below this story; leave it unqueued
+                                trace.record(
+                                    Some(&story.id),
+                                    SchedulerDecision::QueueFull {
+                                        capacity: self.config.queue_capacity,
+                                    },
+                                );
+                                continue;
+                            };
+                            // Not queued, running or failed, so it is ready again next cycle
+                            queued_ids.remove(&evicted.id);
+                            lock_waits.clear(&evicted.id);
+                            trace.record(
+                                Some(&evicted.id),
+                                SchedulerDecision::Evicted {
+                                    by: story.id.clone(),
+                                },
+                            );
+                        }
                     }
                 }
 
                 queued_ids.insert(story.id.clone());
-                pending_queue.push_back(story);
+                queued_at
+                    .entry(story.id.clone())
+                    .or_insert_with(Instant::now);
+                ready_order.enqueue(&mut pending_queue, story);
             }
 
             if let Some(ref sender) = ui_sender {
@@ -764,68 +1902,157 @@ impl ParallelRunner {
                 }
             }
 
-            if blocked_on_queue {
-                tokio::time::sleep(self.config.queue_wait).await;
-                continue;
-            }
-
             // Check if we're done or stuck
-            if ready_empty && in_flight.is_empty() {
-                // No more stories to run and none in flight
+            if ready_empty && pending_queue.is_empty() && tasks.is_empty() {
+                // Stories held back for the end-of-run reconciliation pass get it first
+                if !finished_since_reconcile.is_empty() {
+                    reconcile_at_end = true;
+                    continue;
+                }
+                // No more stories to run, queued or in flight
                 let state = self.execution_state.read().await;
                 let stories_passed = state.completed.len();
-                let has_failures = !state.failed.is_empty();
+                let failed_outcome = RunOutcome::from_failures(
+                    state
+                        .failed
+                        .keys()
+                        .filter_map(|id| failure_categories.get(id).copied()),
+                );
+                let (error_type, error) = if !state.failed.is_empty() {
+                    (Some("failed_steps"), Some("Some stories failed"))
+                } else if !state.skipped.is_empty() {
+                    (Some("skipped_steps"), Some("Some stories were skipped"))
+                } else if !state.unmet_prerequisites.is_empty() {
+                    (
+                        Some("unmet_prerequisites"),
+                        Some("Some stories have unmet prerequisites"),
+                    )
+                } else {
+                    (None, None)
+                };
+                let blocked = state.blocked.clone();
+                let unmet = unmet_prerequisites_summary(&state.unmet_prerequisites);
                 drop(state);
+                let error = match error {
+                    Some(error) => {
+                        let mut error = error.to_string();
+                        if let Some(unmet) = unmet {
+                            error = format!("{}; {}", error, unmet);
+                        }
+                        if let Some(summary) =
+                            record_blocked_stories(&blocked, &evidence, &run_metrics).await
+                        {
+                            error = format!("{}; {}", error, summary);
+                        }
+                        Some(error)
+                    }
+                    None => None,
+                };
+                // Stories that all passed are checked together before success
+                let (error_type, error) = match error {
+                    None if self.base_config.final_verification => {
+                        let failure = run_final_verification(
+                            &self.base_config.working_dir,
+                            evidence_writer(&evidence).await,
+                            self.base_config.display_options.quiet,
+                        );
+                        (
+                            failure.as_ref().map(|_| "final_verification_failed"),
+                            failure,
+                        )
+                    }
+                    error => (error_type, error),
+                };
 
                 emit_run_complete(
                     &evidence,
-                    if has_failures { "failed" } else { "success" },
-                    if has_failures {
-                        Some("failed_steps".to_string())
-                    } else {
-                        None
-                    },
-                    if has_failures {
-                        Some("Some stories failed".to_string())
-                    } else {
-                        None
-                    },
+                    if error.is_some() { "failed" } else { "success" },
+                    error_type.map(str::to_string),
+                    error.clone(),
                 )
                 .await;
                 save_metrics(&run_metrics);
+                if error.is_none() {
+                    self.complete_checkpoint().await;
+                } else if autosaved {
+                    // Bring the autosaved checkpoint up to date, so resuming
+                    // retries exactly the stories that did not pass
+                    self.save_checkpoint(None, PauseReason::Autosave, Vec::new())
+                        .await;
+                }
+                let all_passed = stories_passed == total_stories && error.is_none();
+                let outcome = match error_type {
+                    _ if all_passed => RunOutcome::Passed,
+                    Some("failed_steps") => failed_outcome,
+                    Some("skipped_steps") => RunOutcome::Interrupted,
+                    Some("final_verification_failed") => RunOutcome::GateFailure,
+                    _ => RunOutcome::Error,
+                };
                 return RunResult {
-                    all_passed: stories_passed == total_stories,
+                    all_passed,
                     stories_passed,
                     total_stories,
                     total_iterations,
-                    error: if has_failures {
-                        Some("Some stories failed".to_string())
-                    } else {
-                        None
-                    },
+                    error,
+                    outcome,
                 };
             }
 
-            // If nothing queued and some in flight, wait for them
-            if pending_queue.is_empty() && !in_flight.is_empty() {
-                // Wait for any in-flight task to complete
-                tokio::task::yield_now().await;
-                continue;
-            }
-            if pending_queue.is_empty() {
-                tokio::task::yield_now().await;
-                continue;
+            // A requested pause takes effect once in-flight stories have finished
+            if self.pause_controller.is_pause_requested() && tasks.is_empty() {
+                self.pause_controller.execute_pause();
+                let queued = pending_queue.iter().map(|story| story.id.clone()).collect();
+                self.save_checkpoint(None, PauseReason::UserRequested, queued)
+                    .await;
+                paused = true;
+                if let Some(ref sender) = ui_sender {
+                    let _ = sender.try_send(ParallelUIEvent::RunPaused);
+                }
+            } else if paused && self.pause_controller.is_running() {
+                self.clear_checkpoint();
+                paused = false;
+                if let Some(ref sender) = ui_sender {
+                    let _ = sender.try_send(ParallelUIEvent::RunResumed);
+                }
             }
 
-            if self.semaphore.available_permits() == 0 {
-                tokio::time::sleep(self.config.queue_wait).await;
-                continue;
+            // Stories still queued since the last cycle waited for a free worker slot
+            if let Some(since) = slots_taken_since.take() {
+                let waited = since.elapsed();
+                for story in &pending_queue {
+                    run_metrics.record_semaphore_wait(&story.id, waited);
+                }
             }
 
-            // Spawn tasks for queued stories (up to available semaphore permits)
-            let mut handles = Vec::new();
-            let mut dispatch_slots = self.semaphore.available_permits();
+            // Dispatch queued stories while permits are free, unless paused or a
+            // reconciliation boundary was reached and in-flight stories are draining
+            let draining = !self.pause_controller.is_running()
+                || matches!(self.config.reconcile_mode, ReconcileMode::Every(every)
+                    if finished_since_reconcile.len() >= every);
+            let mut dispatch_slots = if draining {
+                0
+            } else {
+                self.semaphore.available_permits()
+            };
+            if draining && !pending_queue.is_empty() {
+                let reason = if self.pause_controller.is_running() {
+                    "reconciling"
+                } else {
+                    "paused"
+                };
+                trace.record(
+                    None,
+                    SchedulerDecision::DispatchPaused {
+                        reason: reason.to_string(),
+                        queued: pending_queue.len(),
+                    },
+                );
+            }
 
+            // Stories blocked on locks long enough to jump the queue
+            let mut reprioritized = Vec::new();
+            // Stories that cannot start yet; they return to their place in ready order
+            let mut held = Vec::new();
             while dispatch_slots > 0 {
                 let story = match pending_queue.pop_front() {
                     Some(story) => story,
@@ -833,46 +2060,211 @@ impl ParallelRunner {
                 };
                 queued_ids.remove(&story.id);
 
+                // Hold heavy stories back while memory is tight; with nothing in flight
+                // they run anyway so the queue cannot stall
+                let available_mb = available_memory_mb();
+                if story.heavy
+                    && !tasks.is_empty()
+                    && below_memory_headroom(self.config.min_memory_headroom_mb, available_mb)
+                {
+                    trace.record(
+                        Some(&story.id),
+                        SchedulerDecision::HeldForMemory {
+                            available_mb,
+                            min_headroom_mb: self.config.min_memory_headroom_mb,
+                        },
+                    );
+                    queued_ids.insert(story.id.clone());
+                    held.push(story);
+                    continue;
+                }
+
                 let story_id = story.id.clone();
                 let target_files = story.target_files.clone();
+                let read_files = story.read_files.clone();
 
                 let permit = self.semaphore.clone().acquire_owned().await;
 
-                // Try to acquire file locks; requeue if files are locked
+                // Try to acquire file locks; requeue if files are locked, escalating
+                // stories that have waited too long
                 {
                     let mut state = self.execution_state.write().await;
-                    if !state.acquire_locks(&story_id, &target_files) {
+                    if !state.acquire_read_write_locks(&story_id, &target_files, &read_files) {
                         drop(permit);
-                        pending_queue.push_back(story);
-                        queued_ids.insert(story_id.clone());
-                        dispatch_slots = dispatch_slots.saturating_sub(1);
+                        run_metrics.record_lock_failure(&story_id);
+                        let blocker = state.lock_holder(&story_id, &target_files, &read_files);
+                        let action = match &blocker {
+                            Some((file, holder)) => {
+                                lock_waits.record_blocked(&story_id, file, holder, Instant::now())
+                            }
+                            None => LockWaitAction::Wait,
+                        };
+                        if let Some((file, holder)) =
+                            blocker.filter(|_| !matches!(action, LockWaitAction::Fail(_)))
+                        {
+                            let escalation = match action {
+                                LockWaitAction::Warn(_) => Some("warn".to_string()),
+                                LockWaitAction::Reprioritize(_) => Some("reprioritize".to_string()),
+                                _ => None,
+                            };
+                            trace.record(
+                                Some(&story_id),
+                                SchedulerDecision::LockBlocked {
+                                    file: file.display().to_string(),
+                                    holder,
+                                    escalation,
+                                },
+                            );
+                        }
+                        match action {
+                            LockWaitAction::Fail(diagnostic) => {
+                                let message =
+                                    format!("Gave up waiting for file locks: {}", diagnostic);
+                                trace.record(
+                                    Some(&story_id),
+                                    SchedulerDecision::LockWaitFailed {
+                                        reason: message.clone(),
+                                    },
+                                );
+                                state.failed.insert(story_id.clone(), message.clone());
+                                drop(state);
+                                lock_wait_failed.insert(story_id.clone());
+                                queued_at.remove(&story_id);
+                                ready_order.release(&story_id);
+                                run_metrics.start_step(&story_id);
+                                run_metrics.complete_step(
+                                    &story_id,
+                                    false,
+                                    1,
+                                    Duration::ZERO,
+                                    Some(message.clone()),
+                                );
+                                emit_step_event(
+                                    &evidence,
+                                    &run_metrics,
+                                    &story_id,
+                                    "failed",
+                                    Some("lock_wait_timeout".to_string()),
+                                    Some(message.clone()),
+                                )
+                                .await;
+                                if let Some(ref sender) = ui_sender {
+                                    let event = ParallelUIEvent::StoryFailed {
+                                        story_id,
+                                        error: message,
+                                        iteration: 0,
+                                    };
+                                    let _ = sender.try_send(event);
+                                }
+                            }
+                            LockWaitAction::Reprioritize(diagnostic) => {
+                                eprintln!(
+                                    "Warning: {}; moving it to the front of the queue",
+                                    diagnostic
+                                );
+                                queued_ids.insert(story_id);
+                                reprioritized.push(story);
+                            }
+                            LockWaitAction::Warn(diagnostic) => {
+                                eprintln!("Warning: {}", diagnostic);
+                                queued_ids.insert(story_id);
+                                held.push(story);
+                            }
+                            LockWaitAction::Wait => {
+                                queued_ids.insert(story_id);
+                                held.push(story);
+                            }
+                        }
                         continue;
                     }
                     // Mark story as in-flight
                     state.in_flight.insert(story_id.clone());
                 }
+                lock_waits.clear(&story_id);
+                ready_order.release(&story_id);
+                let waited = queued_at.remove(&story_id).map(|queued| queued.elapsed());
+                if let Some(waited) = waited {
+                    run_metrics.record_queue_wait(&story_id, waited);
+                }
 
                 let concurrent_count = {
                     let state = self.execution_state.read().await;
                     state.in_flight.len()
                 };
+                trace.record(
+                    Some(&story_id),
+                    SchedulerDecision::Dispatched {
+                        in_flight: concurrent_count,
+                        waited_ms: waited.map_or(0, |waited| waited.as_millis() as u64),
+                    },
+                );
+
+                // In worktree mode the story runs in its own checkout
+                let worktree = match worktrees {
+                    Some(ref manager) => {
+                        let _git_guard = self.git_mutex.lock().await;
+                        match manager.create(&story_id) {
+                            Ok(worktree) => Some((manager.clone(), worktree)),
+                            Err(err) => {
+                                eprintln!(
+                                    "Warning: Failed to create worktree for {}, using shared working directory: {}",
+                                    story_id, err
+                                );
+                                None
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                let project_root = worktree
+                    .as_ref()
+                    .map(|(_, worktree)| worktree.path.clone())
+                    .unwrap_or_else(|| self.base_config.working_dir.clone());
+                // Per-story handle, so this story's wait for launch slots can be reported
+                let launch_limiter = self.launch_limiter.as_ref().map(LaunchRateLimiter::handle);
 
                 // Clone values for the spawned task
+                let task_remote = self.remote_pool.clone().map(|pool| RemoteStory {
+                    pool,
+                    repo_root: project_root.clone(),
+                    prd_path: self.base_config.prd_path.clone(),
+                    run_id: run_id.clone(),
+                });
+                let story_agent = agents.for_story(&story);
                 let executor_config = ExecutorConfig {
                     prd_path: self.base_config.prd_path.clone(),
-                    project_root: self.base_config.working_dir.clone(),
+                    project_root,
                     progress_path: self.base_config.working_dir.join("progress.txt"),
                     quality_profile: None,
-                    agent_command: agent.clone(),
+                    agent_command: story_agent.agent_command.clone(),
                     max_iterations: self.base_config.max_iterations_per_story,
                     git_mutex: Some(self.git_mutex.clone()),
                     timeout_config: self.config.timeout_config.clone(),
+                    artifacts_dir: artifacts_dir.clone(),
+                    evidence: gate_evidence.clone(),
+                    commit_iterations: worktree.is_some()
+                        && self.config.isolation == IsolationMode::Branch,
+                    resource_limits: self.config.worker_limits.clone(),
+                    launch_limiter: launch_limiter.clone(),
+                    agent_capabilities: agents.capabilities(&story_agent.agent_command).cloned(),
+                    agent_container: self.base_config.agent_container.clone(),
+                    file_context_tokens: self.base_config.file_context_tokens,
+                    model: story_agent.model.clone(),
+                    run_metrics: Some(run_metrics.clone()),
+                    // A story run in a worktree is only marked passing once its
+                    // work has been merged back
+                    prd_writeback: self.base_config.prd_writeback && worktree.is_none(),
+                    retry_policy: Some(self.config.retry_policy.clone()),
+                    retry_budgets: self.retry_budgets.clone(),
                     ..Default::default()
                 };
 
                 let execution_state = self.execution_state.clone();
                 let story_id_clone = story_id.clone();
                 let task_ui_sender = ui_sender.clone();
+                let task_run_log = self.run_log.clone();
+                let task_github_actions = self.github_actions.clone();
+                let task_run_report = self.run_report.clone();
                 let story_info = story_info_map
                     .get(&story_id)
                     .cloned()
@@ -880,16 +2272,32 @@ impl ParallelRunner {
 
                 // Subscribe to shared cancel channel for circuit breaker graceful shutdown
                 let task_cancel_rx = cancel_tx.subscribe();
+                // Per-story cancel channel for cancelling just this story from the UI
+                let (story_cancel_tx, mut story_cancel_rx) = watch::channel(false);
+                story_cancels.insert(story_id.clone(), story_cancel_tx);
 
                 let task_evidence = evidence.clone();
                 let task_run_metrics = run_metrics.clone();
-                let handle = tokio::spawn(async move {
+                let task_git_mutex = self.git_mutex.clone();
+                let task_isolation = self.config.isolation.clone();
+                let task_concurrency = concurrency.clone();
+                let task_retry_policy = self.config.retry_policy.clone();
+                let task_retry_budgets = self.retry_budgets.clone();
+                let max_iterations_per_story = self.base_config.max_iterations_per_story;
+                let story_timeout = self.config.story_timeout;
+                let task_story_iterations = self.story_iterations.clone();
+                let task_prd_writeback = self
+                    .base_config
+                    .prd_writeback
+                    .then(|| self.base_config.prd_path.clone());
+                let story_task = async move {
                     // Hold the permit until the task completes (RAII)
                     let _permit = permit;
 
                     // Send StoryStarted event
                     let start_time = Instant::now();
                     task_run_metrics.start_step(&story_id_clone);
+                    task_run_metrics.record_step_agent(&story_id_clone, story_agent.label());
                     if let Some(ref sender) = task_ui_sender {
                         let event = ParallelUIEvent::StoryStarted {
                             story: story_info.clone(),
@@ -899,39 +2307,232 @@ impl ParallelRunner {
                         let _ = sender.try_send(event);
                     }
 
-                    let executor = StoryExecutor::new(executor_config);
-                    let cancel_rx = task_cancel_rx;
+                    let mut executor = StoryExecutor::new(executor_config);
+                    if let Some(output) = story_output_callback(
+                        &story_id_clone,
+                        task_ui_sender.as_ref(),
+                        task_run_log.as_ref(),
+                        task_github_actions.as_ref(),
+                        task_run_report.as_ref(),
+                    ) {
+                        executor.set_display_callback(output);
+                    }
+                    let mut cancel_rx = task_cancel_rx;
 
                     // Clone for iteration callback closure
                     let iter_story_id = story_id_clone.clone();
                     let iter_ui_sender = task_ui_sender.clone();
+                    let iter_progress = task_story_iterations;
+
+                    // Only running the story is time-boxed; once it finishes the
+                    // outcome is always recorded, so a story that finished right at
+                    // its deadline is never failed by it
+                    let execution = async {
+                        // Re-run the story while its retry budget allows it
+                        let result = loop {
+                            let on_iteration = |iter, max| {
+                                if let Ok(mut progress) = iter_progress.lock() {
+                                    progress.insert(iter_story_id.clone(), iter);
+                                }
+                                if let Some(ref sender) = iter_ui_sender {
+                                    let event = ParallelUIEvent::IterationUpdate {
+                                        story_id: iter_story_id.clone(),
+                                        iteration: iter,
+                                        max_iterations: max,
+                                        message: None,
+                                    };
+                                    let _ = sender.try_send(event);
+                                }
+                            };
+                            let execution = async {
+                                match task_remote {
+                                    Some(ref remote) => {
+                                        remote
+                                            .run(
+                                                &executor,
+                                                &task_git_mutex,
+                                                &story_id_clone,
+                                                max_iterations_per_story,
+                                                cancel_rx.clone(),
+                                                on_iteration,
+                                            )
+                                            .await
+                                    }
+                                    None => {
+                                        executor
+                                            .execute_story(
+                                                &story_id_clone,
+                                                cancel_rx.clone(),
+                                                on_iteration,
+                                            )
+                                            .await
+                                    }
+                                }
+                            };
+                            // Cancelling the story drops the execution, killing its agent
+                            let result = tokio::select! {
+                                result = execution => result,
+                                _ = story_cancel_rx.wait_for(|cancelled| *cancelled) => {
+                                    Err(ExecutorError::Cancelled)
+                                }
+                            };
 
-                    let result = executor
-                        .execute_story(&story_id_clone, cancel_rx, |iter, max| {
-                            if let Some(ref sender) = iter_ui_sender {
+                            let Err(ref e) = result else {
+                                break result;
+                            };
+                            let category = e.classify();
+                            let Some(delay) = task_retry_budgets.spend(
+                                &story_id_clone,
+                                &task_retry_policy,
+                                &category,
+                            ) else {
+                                break result;
+                            };
+                            if *cancel_rx.borrow() || *story_cancel_rx.borrow() {
+                                break result;
+                            }
+
+                            let message = format!(
+                                "Retry {}/{} in {:.1}s: {}",
+                                task_retry_budgets
+                                    .get(&story_id_clone)
+                                    .used(task_retry_policy.label(&category)),
+                                task_retry_policy.max_retries(&category),
+                                delay.as_secs_f64(),
+                                e
+                            );
+                            task_run_metrics.record_retry(&story_id_clone);
+                            emit_step_event(
+                                &task_evidence,
+                                &task_run_metrics,
+                                &story_id_clone,
+                                "retrying",
+                                Some(error_category_label(&category).to_string()),
+                                Some(message.clone()),
+                            )
+                            .await;
+                            if let Some(ref sender) = task_ui_sender {
                                 let event = ParallelUIEvent::IterationUpdate {
-                                    story_id: iter_story_id.clone(),
-                                    iteration: iter,
-                                    max_iterations: max,
-                                    message: None,
+                                    story_id: story_id_clone.clone(),
+                                    iteration: 1,
+                                    max_iterations: max_iterations_per_story,
+                                    message: Some(message),
                                 };
                                 let _ = sender.try_send(event);
                             }
-                        })
-                        .await;
 
+                            // Back off, but stop waiting if the run is cancelled
+                            tokio::select! {
+                                _ = tokio::time::sleep(delay) => {}
+                                _ = cancel_rx.changed() => {}
+                                _ = story_cancel_rx.changed() => {}
+                            }
+                        };
+                        task_run_metrics.record_retry_budget(
+                            &story_id_clone,
+                            task_retry_policy.remaining(&task_retry_budgets.get(&story_id_clone)),
+                        );
+                        result
+                    };
+                    let timed_result = run_story_in_time(
+                        story_timeout,
+                        execution,
+                        worktree,
+                        &task_git_mutex,
+                        &task_isolation,
+                        &execution_state,
+                        task_prd_writeback.as_deref(),
+                    )
+                    .await;
+                    let skipped = *story_cancel_rx.borrow();
+                    if let Some(ref limiter) = launch_limiter {
+                        task_run_metrics.record_launch_wait(&story_id_clone, limiter.waited());
+                    }
                     let duration = start_time.elapsed();
                     let duration_ms = duration.as_millis() as u64;
 
+                    let Ok(result) = timed_result else {
+                        // The story ran out of time; its agent was killed when the
+                        // execution was dropped
+                        let message = format!("Story timed out after {:?}", story_timeout);
+                        let mut state = execution_state.write().await;
+                        state.in_flight.remove(&story_id_clone);
+                        state.release_locks(&story_id_clone);
+                        state.failed.insert(story_id_clone.clone(), message.clone());
+                        drop(state);
+                        task_run_metrics.complete_step(
+                            &story_id_clone,
+                            false,
+                            1,
+                            duration,
+                            Some(message.clone()),
+                        );
+                        emit_step_event(
+                            &task_evidence,
+                            &task_run_metrics,
+                            &story_id_clone,
+                            "failed",
+                            Some("story_timeout".to_string()),
+                            Some(message.clone()),
+                        )
+                        .await;
+                        if let Some(ref sender) = task_ui_sender {
+                            let event = ParallelUIEvent::StoryFailed {
+                                story_id: story_id_clone.clone(),
+                                error: message,
+                                iteration: 1,
+                            };
+                            let _ = sender.try_send(event);
+                        }
+                        // Timeouts are non-transient failures
+                        return (story_id_clone, false, 1, Some(FailureCategory::Timeout));
+                    };
+
+                    // A cancelled story says nothing about how the system is coping
+                    if let Some(controller) = task_concurrency.as_ref().filter(|_| !skipped) {
+                        let outcome = match &result {
+                            Ok(exec_result) if exec_result.success => StoryOutcome::Succeeded,
+                            Ok(_) => StoryOutcome::Failed,
+                            Err(e) => StoryOutcome::from_error_category(&e.classify()),
+                        };
+                        controller.lock().await.record(outcome);
+                    }
+
                     // Update state based on result
                     let mut state = execution_state.write().await;
                     state.in_flight.remove(&story_id_clone);
                     // Release file locks (success or failure)
                     state.release_locks(&story_id_clone);
 
-                    // Result tuple: (story_id, success, iterations, is_transient_failure)
-                    // is_transient_failure is true only for transient errors (not quality gate failures)
+                    // Result tuple: (story_id, success, iterations, failure_category)
+                    // failure_category is None for successes, skips and transient errors
                     let (result_tuple, step_event) = match result {
+                        _ if skipped => {
+                            state.skipped.insert(story_id_clone.clone());
+                            if let Some(ref sender) = task_ui_sender {
+                                let event = ParallelUIEvent::StorySkipped {
+                                    story_id: story_id_clone.clone(),
+                                    reason: STORY_CANCELLED.to_string(),
+                                };
+                                let _ = sender.try_send(event);
+                            }
+                            task_run_metrics.complete_step(
+                                &story_id_clone,
+                                false,
+                                1,
+                                duration,
+                                Some(STORY_CANCELLED.to_string()),
+                            );
+                            // Skipped stories don't count toward the circuit breaker
+                            (
+                                (story_id_clone, false, 1, None),
+                                Some((
+                                    "skipped".to_string(),
+                                    None,
+                                    Some(STORY_CANCELLED.to_string()),
+                                )),
+                            )
+                        }
                         Ok(exec_result) if exec_result.success => {
                             state.completed.insert(story_id_clone.clone());
                             // Send StoryCompleted event
@@ -952,7 +2553,7 @@ impl ParallelRunner {
                                 None,
                             );
                             (
-                                (story_id_clone, true, exec_result.iterations_used, false),
+                                (story_id_clone, true, exec_result.iterations_used, None),
                                 Some(("completed".to_string(), None, None)),
                             )
                         }
@@ -982,7 +2583,12 @@ impl ParallelRunner {
                                 Some(error_msg.clone()),
                             );
                             (
-                                (story_id_clone, false, exec_result.iterations_used, false),
+                                (
+                                    story_id_clone,
+                                    false,
+                                    exec_result.iterations_used,
+                                    Some(FailureCategory::QualityGate),
+                                ),
                                 Some((
                                     "failed".to_string(),
                                     Some("quality_gates_failed".to_string()),
@@ -1002,8 +2608,8 @@ impl ParallelRunner {
                                 let _ = sender.try_send(event);
                             }
                             let category = e.classify();
-                            // Check if this is a transient error
-                            let is_transient = matches!(category, ErrorCategory::Transient(_));
+                            // Transient errors don't count toward the circuit breaker
+                            let failure = FailureCategory::from_error_category(&category);
                             task_run_metrics.complete_step(
                                 &story_id_clone,
                                 false,
@@ -1012,7 +2618,7 @@ impl ParallelRunner {
                                 Some(e.to_string()),
                             );
                             (
-                                (story_id_clone, false, 1, is_transient),
+                                (story_id_clone, false, 1, failure),
                                 Some((
                                     "failed".to_string(),
                                     Some(error_category_label(&category).to_string()),
@@ -1036,237 +2642,105 @@ impl ParallelRunner {
                     }
                     // Permit is dropped here, releasing the semaphore slot
                     result_tuple
-                });
+                };
 
-                handles.push(handle);
+                let task_story_id = story_id.clone();
+                let handle = tasks.spawn(async move { (story_id, story_task.await) });
+                task_stories.insert(handle.id(), (task_story_id, Instant::now()));
                 dispatch_slots = dispatch_slots.saturating_sub(1);
             }
 
-            // Wait for all tasks in this batch to complete (with timeout)
-            if !handles.is_empty() {
-                let batch_story_ids: Vec<String> = {
-                    let state = self.execution_state.read().await;
-                    state.in_flight.iter().cloned().collect()
-                };
-
-                // Wait for all handles to complete, with batch timeout
-                let batch_future = futures::future::join_all(handles);
-                let batch_result =
-                    tokio::time::timeout(self.config.batch_timeout, batch_future).await;
-
-                match batch_result {
-                    Ok(results) => {
-                        // Count non-transient failures in this batch for circuit breaker
-                        let mut batch_non_transient_failures: u32 = 0;
-                        for result in results.into_iter().flatten() {
-                            let (_story_id, success, iterations, is_transient) = result;
-                            total_iterations += iterations;
-                            // Count non-transient failures (quality gate failures or fatal/timeout errors)
-                            if !success && !is_transient {
-                                batch_non_transient_failures += 1;
-                            }
-                        }
-                        cumulative_failures += batch_non_transient_failures;
-
-                        // Send circuit breaker status update
-                        if batch_non_transient_failures > 0 {
-                            if let Some(ref sender) = ui_sender {
-                                let _ = sender.try_send(ParallelUIEvent::CircuitBreakerStatus {
-                                    current_failures: cumulative_failures,
-                                    threshold: circuit_breaker_threshold,
-                                });
-                            }
-                        }
-
-                        // Check circuit breaker threshold
-                        if cumulative_failures >= circuit_breaker_threshold {
-                            // Send cancel signal to any remaining in-flight stories
-                            let _ = cancel_tx.send(true);
-
-                            // Get a failed story ID for the checkpoint
-                            let state = self.execution_state.read().await;
-                            let failed_story_id = state
-                                .failed
-                                .keys()
-                                .next()
-                                .cloned()
-                                .unwrap_or_else(|| "unknown".to_string());
-                            drop(state);
-
-                            // Save checkpoint with circuit breaker reason
-                            self.save_checkpoint(
-                                &failed_story_id,
-                                1,
-                                self.base_config.max_iterations_per_story,
-                                PauseReason::CircuitBreakerTriggered {
-                                    consecutive_failures: cumulative_failures,
-                                    threshold: circuit_breaker_threshold,
-                                },
-                            );
-
-                            let circuit_breaker_msg = format!(
-                                "Circuit breaker triggered: {} failures across batches (threshold: {})",
-                                cumulative_failures, circuit_breaker_threshold
-                            );
-
-                            // Send circuit breaker triggered event
-                            if let Some(ref sender) = ui_sender {
-                                let _ = sender.try_send(ParallelUIEvent::CircuitBreakerTriggered {
-                                    failures: cumulative_failures,
-                                    threshold: circuit_breaker_threshold,
-                                });
-                            }
-
-                            // Print circuit breaker notification
-                            println!();
-                            println!(
-                                "\x1b[48;2;239;68;68m\x1b[38;2;255;255;255m CIRCUIT BREAKER TRIGGERED: {} failures (threshold: {}) \x1b[0m",
-                                cumulative_failures, circuit_breaker_threshold
-                            );
-                            println!("Execution paused. Resume with: ralph --resume");
-                            println!();
+            for story in held {
+                ready_order.enqueue(&mut pending_queue, story);
+            }
+            for story in reprioritized.into_iter().rev() {
+                pending_queue.push_front(story);
+            }
+            if !draining && !pending_queue.is_empty() && self.semaphore.available_permits() == 0 {
+                slots_taken_since = Some(Instant::now());
+            }
 
-                            let state = self.execution_state.read().await;
-                            emit_run_complete(
-                                &evidence,
-                                "failed",
-                                Some("circuit_breaker".to_string()),
-                                Some(circuit_breaker_msg.clone()),
-                            )
-                            .await;
-                            save_metrics(&run_metrics);
-                            return RunResult {
-                                all_passed: false,
-                                stories_passed: state.completed.len(),
-                                total_stories,
-                                total_iterations,
-                                error: Some(format!(
-                                    "{}. Checkpoint saved. Resume with: ralph --resume",
-                                    circuit_breaker_msg
-                                )),
-                            };
-                        }
-                    }
-                    Err(_) => {
-                        // Batch timed out - mark all in-flight stories as failed (non-transient)
-                        let timed_out_count = batch_story_ids.len() as u32;
+            // Save a checkpoint now and then while stories run, so a killed run
+            // can be resumed
+            let autosave_interval = self
+                .base_config
+                .autosave_interval
+                .filter(|_| !tasks.is_empty());
+            if let Some(interval) = autosave_interval {
+                if last_autosave.elapsed() >= interval {
+                    let queued = pending_queue.iter().map(|story| story.id.clone()).collect();
+                    self.save_checkpoint(None, PauseReason::Autosave, queued)
+                        .await;
+                    last_autosave = Instant::now();
+                    autosaved = true;
+                }
+            }
+            let next_autosave = autosave_interval.map(|interval| last_autosave + interval);
+
+            // Wait for the next story to finish, handling UI control events meanwhile and
+            // waking up when a story blocked on locks is due for escalation, an
+            // autosave is due or the stop file appears
+            let joined = tokio::select! {
+                joined = tasks.join_next_with_id() => Ok(joined),
+                Some(event) = recv_control_event(&mut control_rx) => Err(event),
+                _ = sleep_until_due(lock_waits.next_escalation(), self.config.queue_wait) => {
+                    continue;
+                }
+                _ = sleep_until_due(next_autosave, Duration::ZERO) => {
+                    continue;
+                }
+                _ = stop_file.requested() => {
+                    continue;
+                }
+            };
+            let joined = match joined {
+                Ok(joined) => joined,
+                Err(ParallelUIEvent::CancelStoryRequested { story_id }) => {
+                    if let Some(story_cancel) = story_cancels.get(&story_id) {
+                        // The story's task records the skip once its executor stops
+                        let _ = story_cancel.send(true);
+                    } else if graph.get_story(&story_id).is_some() {
+                        // Not started yet: skip it without running
                         let mut state = self.execution_state.write().await;
-                        for story_id in &batch_story_ids {
-                            if state.in_flight.contains(story_id) {
-                                state.in_flight.remove(story_id);
-                                state.release_locks(story_id);
-                                state.failed.insert(
-                                    story_id.clone(),
-                                    format!(
-                                        "Batch timed out after {:?}",
-                                        self.config.batch_timeout
-                                    ),
-                                );
-                                emit_step_event(
-                                    &evidence,
-                                    &run_metrics,
-                                    story_id,
-                                    "failed",
-                                    Some("batch_timeout".to_string()),
-                                    Some(format!(
-                                        "Batch timed out after {:?}",
-                                        self.config.batch_timeout
-                                    )),
-                                )
-                                .await;
-                                // Send StoryFailed event
-                                if let Some(ref sender) = ui_sender {
-                                    let event = ParallelUIEvent::StoryFailed {
-                                        story_id: story_id.clone(),
-                                        error: format!(
-                                            "Batch timed out after {:?}",
-                                            self.config.batch_timeout
-                                        ),
-                                        iteration: 1,
-                                    };
-                                    let _ = sender.try_send(event);
-                                }
-                            }
-                        }
+                        let newly_skipped = !state.completed.contains(&story_id)
+                            && state.skipped.insert(story_id.clone());
                         drop(state);
-
-                        // Batch timeouts are non-transient failures
-                        cumulative_failures += timed_out_count;
-
-                        // Check circuit breaker after timeout
-                        if cumulative_failures >= circuit_breaker_threshold {
-                            let _ = cancel_tx.send(true);
-
-                            let failed_story_id = batch_story_ids
-                                .first()
-                                .cloned()
-                                .unwrap_or_else(|| "unknown".to_string());
-
-                            self.save_checkpoint(
-                                &failed_story_id,
-                                1,
-                                self.base_config.max_iterations_per_story,
-                                PauseReason::CircuitBreakerTriggered {
-                                    consecutive_failures: cumulative_failures,
-                                    threshold: circuit_breaker_threshold,
-                                },
-                            );
-
-                            let circuit_breaker_msg = format!(
-                                "Circuit breaker triggered: {} failures across batches (threshold: {})",
-                                cumulative_failures, circuit_breaker_threshold
-                            );
-                            println!("\n⚡ {}", circuit_breaker_msg);
-
-                            let state = self.execution_state.read().await;
-                            emit_run_complete(
+                        if newly_skipped {
+                            pending_queue.retain(|story| story.id != story_id);
+                            queued_ids.remove(&story_id);
+                            ready_order.release(&story_id);
+                            lock_waits.clear(&story_id);
+                            emit_step_event(
                                 &evidence,
-                                "failed",
-                                Some("circuit_breaker".to_string()),
-                                Some(circuit_breaker_msg.clone()),
+                                &run_metrics,
+                                &story_id,
+                                "skipped",
+                                None,
+                                Some(STORY_CANCELLED.to_string()),
                             )
                             .await;
-                            save_metrics(&run_metrics);
-                            return RunResult {
-                                all_passed: false,
-                                stories_passed: state.completed.len(),
-                                total_stories,
-                                total_iterations,
-                                error: Some(format!(
-                                    "{}. Checkpoint saved. Resume with: ralph --resume",
-                                    circuit_breaker_msg
-                                )),
-                            };
+                            if let Some(ref sender) = ui_sender {
+                                let _ = sender.try_send(ParallelUIEvent::StorySkipped {
+                                    story_id,
+                                    reason: STORY_CANCELLED.to_string(),
+                                });
+                            }
                         }
-
-                        // Continue to next iteration - some stories may have completed
-                        // before the timeout and their results were handled in the spawned task
-                        continue;
                     }
+                    continue;
                 }
+                Err(ParallelUIEvent::ImmediateInterrupt) => {
+                    // Dropping the story tasks kills their agents
+                    let _ = cancel_tx.send(true);
+                    tasks.shutdown().await;
 
-                // Run reconciliation after each batch completes
-                let reconciliation_result = self
-                    .run_reconciliation(
-                        &batch_story_ids,
-                        &graph,
-                        &agent,
-                        &mut total_iterations,
-                        &evidence,
-                        &run_metrics,
-                        &ui_sender,
-                        &story_info_map,
-                    )
-                    .await;
-
-                // If reconciliation failed and we couldn't recover, return error
-                if let Some(error) = reconciliation_result {
+                    let message = "Interrupted by user".to_string();
                     let state = self.execution_state.read().await;
                     emit_run_complete(
                         &evidence,
                         "failed",
-                        Some("reconciliation_failed".to_string()),
-                        Some(error.clone()),
+                        Some("interrupted".to_string()),
+                        Some(message.clone()),
                     )
                     .await;
                     save_metrics(&run_metrics);
@@ -1275,30 +2749,605 @@ impl ParallelRunner {
                         stories_passed: state.completed.len(),
                         total_stories,
                         total_iterations,
-                        error: Some(error),
+                        error: Some(message),
+                        outcome: RunOutcome::Interrupted,
                     };
                 }
+                Err(ParallelUIEvent::TogglePauseRequested) => {
+                    // The loop pauses once in-flight stories finish, or resumes
+                    if self.pause_controller.toggle() == PauseState::PauseRequested
+                        && !tasks.is_empty()
+                    {
+                        if let Some(ref sender) = ui_sender {
+                            let _ = sender.try_send(ParallelUIEvent::RunPausing {
+                                in_flight: tasks.len(),
+                            });
+                        }
+                    }
+                    continue;
+                }
+                Err(ParallelUIEvent::ConcurrencyChangeRequested { delta }) => {
+                    let adjustment = match concurrency {
+                        Some(ref controller) => {
+                            let mut controller = controller.lock().await;
+                            let max = controller.max_concurrency().saturating_add_signed(delta);
+                            controller.set_max_concurrency(max)
+                        }
+                        // An unlimited run stays unlimited
+                        None if concurrency_limit == u32::MAX => None,
+                        None => {
+                            let previous = concurrency_limit;
+                            concurrency_limit = previous.saturating_add_signed(delta).max(1);
+                            (concurrency_limit != previous).then(|| ConcurrencyAdjustment {
+                                previous,
+                                current: concurrency_limit,
+                                reason: "set from the keyboard".to_string(),
+                            })
+                        }
+                    };
+                    if let Some(adjustment) = adjustment {
+                        resize_semaphore(&self.semaphore, adjustment.previous, adjustment.current);
+                        if let Some(ref sender) = ui_sender {
+                            let _ = sender.try_send(ParallelUIEvent::ConcurrencyAdjusted {
+                                previous: adjustment.previous,
+                                current: adjustment.current,
+                                reason: adjustment.reason,
+                            });
+                        }
+                    }
+                    continue;
+                }
+                Err(ParallelUIEvent::VerboseToggleRequested { story_id }) => {
+                    let verbose = verbose_stories.insert(story_id.clone());
+                    if !verbose {
+                        verbose_stories.remove(&story_id);
+                    }
+                    if let Some(ref sender) = ui_sender {
+                        let _ = sender
+                            .try_send(ParallelUIEvent::StoryVerbosityChanged { story_id, verbose });
+                    }
+                    continue;
+                }
+                Err(
+                    event @ (ParallelUIEvent::StorySelected { .. }
+                    | ParallelUIEvent::StoryDetailsToggled { .. }),
+                ) => {
+                    if let Some(ref sender) = ui_sender {
+                        let _ = sender.try_send(event);
+                    }
+                    continue;
+                }
+                Err(_) => continue,
+            };
+            let Some(joined) = joined else {
+                // Nothing in flight; re-evaluate ready stories
+                tokio::time::sleep(self.config.queue_wait).await;
+                continue;
+            };
+
+            let mut new_failure: Option<FailureCategory> = None;
+            let mut failed_story_id: Option<String> = None;
+            match joined {
+                Ok((task_id, (story_id, (_, _, iterations, failure)))) => {
+                    task_stories.remove(&task_id);
+                    story_cancels.remove(&story_id);
+                    total_iterations += iterations;
+                    // Count non-transient failures (quality gate failures or fatal/timeout errors)
+                    if let Some(category) = failure {
+                        new_failure = failure;
+                        failed_story_id = Some(story_id.clone());
+                        failure_categories.insert(story_id.clone(), category);
+                    }
+                    finished_since_reconcile.push(story_id);
+                }
+                Err(join_error) => {
+                    // The story task panicked or was aborted before recording its
+                    // outcome; fail the story so its locks and slot are not leaked
+                    if let Some((story_id, spawned_at)) = task_stories.remove(&join_error.id()) {
+                        story_cancels.remove(&story_id);
+                        let message = story_task_failure_message(join_error);
+                        let mut state = self.execution_state.write().await;
+                        state.in_flight.remove(&story_id);
+                        state.release_locks(&story_id);
+                        state.failed.insert(story_id.clone(), message.clone());
+                        drop(state);
+                        run_metrics.complete_step(
+                            &story_id,
+                            false,
+                            1,
+                            spawned_at.elapsed(),
+                            Some(message.clone()),
+                        );
+                        emit_step_event(
+                            &evidence,
+                            &run_metrics,
+                            &story_id,
+                            "failed",
+                            Some("panic".to_string()),
+                            Some(message.clone()),
+                        )
+                        .await;
+                        if let Some(ref sender) = ui_sender {
+                            let _ = sender.try_send(ParallelUIEvent::StoryFailed {
+                                story_id: story_id.clone(),
+                                error: message,
+                                iteration: 1,
+                            });
+                        }
+                        total_iterations += 1;
+                        new_failure = Some(FailureCategory::Fatal);
+                        failed_story_id = Some(story_id.clone());
+                        failure_categories.insert(story_id.clone(), FailureCategory::Fatal);
+                        finished_since_reconcile.push(story_id);
+                    }
+                }
+            }
+            let trip = new_failure.and_then(|category| circuit_breaker.record(category));
+
+            // Resize the worker pool if adaptive concurrency calls for it
+            if let Some(ref controller) = concurrency {
+                let adjustment = controller.lock().await.evaluate(system_load_per_cpu());
+                if let Some(adjustment) = adjustment {
+                    resize_semaphore(&self.semaphore, adjustment.previous, adjustment.current);
+                    if let Some(ref sender) = ui_sender {
+                        let _ = sender.try_send(ParallelUIEvent::ConcurrencyAdjusted {
+                            previous: adjustment.previous,
+                            current: adjustment.current,
+                            reason: adjustment.reason,
+                        });
+                    }
+                }
+            }
+
+            // Send circuit breaker status update for the failed story's category
+            if let Some(category) = new_failure {
+                if let Some(ref sender) = ui_sender {
+                    let (current_failures, threshold) = circuit_breaker.status(category);
+                    let _ = sender.try_send(ParallelUIEvent::CircuitBreakerStatus {
+                        current_failures,
+                        threshold,
+                    });
+                }
+            }
+
+            // Check circuit breaker threshold
+            if let Some(trip) = trip {
+                // Send cancel signal to any remaining in-flight stories
+                let _ = cancel_tx.send(true);
+
+                let failed_story_id = failed_story_id.unwrap_or_else(|| "unknown".to_string());
+
+                // Save checkpoint with circuit breaker reason
+                self.save_checkpoint(
+                    Some(StoryCheckpoint::new(
+                        &failed_story_id,
+                        1,
+                        self.base_config.max_iterations_per_story,
+                    )),
+                    PauseReason::CircuitBreakerTriggered {
+                        consecutive_failures: trip.failures,
+                        threshold: trip.threshold,
+                        category: Some(trip.category.as_label().to_string()),
+                    },
+                    pending_queue.iter().map(|story| story.id.clone()).collect(),
+                )
+                .await;
+
+                let circuit_breaker_msg = format!(
+                    "Circuit breaker triggered: {} {} failures (threshold: {})",
+                    trip.failures, trip.category, trip.threshold
+                );
+
+                // Send circuit breaker triggered event
+                if let Some(ref sender) = ui_sender {
+                    let _ = sender.try_send(ParallelUIEvent::CircuitBreakerTriggered {
+                        failures: trip.failures,
+                        threshold: trip.threshold,
+                    });
+                }
+
+                // Print circuit breaker notification
+                if self.ui_tx.is_none() {
+                    println!();
+                    println!(
+                        "\x1b[48;2;239;68;68m\x1b[38;2;255;255;255m CIRCUIT BREAKER TRIGGERED: {} {} failures (threshold: {}) \x1b[0m",
+                        trip.failures, trip.category, trip.threshold
+                    );
+                    println!("Execution paused. Resume with: ralph --resume");
+                    println!();
+                }
+
+                let state = self.execution_state.read().await;
+                let blocked = blocked_by_failures(&graph, &state);
+                let circuit_breaker_msg =
+                    match record_blocked_stories(&blocked, &evidence, &run_metrics).await {
+                        Some(summary) => format!("{}; {}", circuit_breaker_msg, summary),
+                        None => circuit_breaker_msg,
+                    };
+                emit_run_complete(
+                    &evidence,
+                    "failed",
+                    Some("circuit_breaker".to_string()),
+                    Some(circuit_breaker_msg.clone()),
+                )
+                .await;
+                save_metrics(&run_metrics);
+                return RunResult {
+                    all_passed: false,
+                    stories_passed: state.completed.len(),
+                    total_stories,
+                    total_iterations,
+                    error: Some(format!(
+                        "{}. Checkpoint saved. Resume with: ralph --resume",
+                        circuit_breaker_msg
+                    )),
+                    outcome: RunOutcome::CircuitBreaker,
+                };
+            }
+        }
+    }
+
+    /// Tries the configured merge resolutions on story branches that conflicted
+    /// on merge, returning the conflicts left for reconciliation.
+    ///
+    /// A story whose branch is merged is completed after all and its branch
+    /// deleted; each story's attempts are recorded in evidence, and the
+    /// strategy that merged it in `resolutions`.
+    #[allow(clippy::too_many_arguments)]
+    async fn resolve_merge_conflicts(
+        &self,
+        merge_conflicts: Vec<ReconciliationIssue>,
+        batch_story_ids: &[String],
+        graph: &DependencyGraph,
+        agents: &AgentRoster,
+        evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+        run_metrics: &RunMetricsCollector,
+        resolutions: &mut BTreeMap<String, String>,
+    ) -> Vec<ReconciliationIssue> {
+        if self.config.merge_resolution.is_empty() || merge_conflicts.is_empty() {
+            return merge_conflicts;
+        }
+        let manager = match WorktreeManager::new(&self.base_config.working_dir) {
+            Ok(manager) => manager,
+            Err(err) => {
+                eprintln!("Warning: Cannot resolve merge conflicts: {}", err);
+                return merge_conflicts;
+            }
+        };
+
+        let mut remaining = Vec::new();
+        for conflict in merge_conflicts {
+            let ReconciliationIssue::MergeConflict {
+                story_id,
+                branch,
+                affected_files,
+            } = &conflict
+            else {
+                remaining.push(conflict);
+                continue;
+            };
+            let _git_guard = self.git_mutex.lock().await;
+            let mut record = MergeResolutionRecord {
+                story_id: story_id.clone(),
+                branch: branch.clone(),
+                files: affected_files.clone(),
+                resolution: None,
+                attempts: Vec::new(),
+            };
+            for &strategy in &self.config.merge_resolution {
+                let mut side = None;
+                let outcome = match strategy {
+                    MergeResolution::Priority => {
+                        let favored = self
+                            .favored_side(story_id, affected_files, batch_story_ids, graph)
+                            .await;
+                        side = Some(favored.as_label());
+                        manager
+                            .start_merge(story_id, branch, &[favored.merge_option()])
+                            .map_err(|e| e.to_string())
+                    }
+                    // Every resolution merge replays what rerere recorded
+                    MergeResolution::Rerere => manager
+                        .start_merge(story_id, branch, &[])
+                        .map_err(|e| e.to_string()),
+                    MergeResolution::Agent => match manager.start_merge(story_id, branch, &[]) {
+                        Ok(files) if !files.is_empty() => {
+                            let executor = self
+                                .merge_executor(story_id, graph, agents, evidence, run_metrics)
+                                .await;
+                            match executor
+                                .resolve_merge_conflicts(story_id, branch, &files)
+                                .await
+                            {
+                                Ok(()) => manager.conclude_merge(&files).map_err(|e| e.to_string()),
+                                Err(err) => Err(err.to_string()),
+                            }
+                        }
+                        other => other.map_err(|e| e.to_string()),
+                    },
+                };
+                let error = match outcome {
+                    Ok(files) if files.is_empty() => None,
+                    Ok(files) => Some(format!("conflicts remain in: {}", files.join(", "))),
+                    Err(err) => Some(err),
+                };
+                let resolved = error.is_none();
+                if !resolved {
+                    // Nothing to abort when the merge did not start
+                    let _ = manager.abort_merge();
+                }
+                record.attempts.push(ResolutionAttempt {
+                    strategy: strategy.as_label(),
+                    side,
+                    resolved,
+                    error,
+                });
+                if resolved {
+                    record.resolution = Some(strategy.as_label());
+                    break;
+                }
+            }
+
+            if let Some(writer) = evidence.as_ref() {
+                writer
+                    .lock()
+                    .await
+                    .emit_record(MERGE_RESOLUTION_EVIDENCE_KIND, &record);
+            }
+            let Some(resolution) = record.resolution else {
+                remaining.push(conflict);
+                continue;
+            };
+            resolutions.insert(story_id.clone(), resolution.to_string());
+            if let Err(err) = manager.delete_branch(branch) {
+                eprintln!("Warning: Failed to delete branch {}: {}", branch, err);
+            }
+            {
+                let mut state = self.execution_state.write().await;
+                state.failed.remove(story_id);
+                state.completed.insert(story_id.clone());
+            }
+            if self.base_config.prd_writeback {
+                write_back_passes(&self.base_config.prd_path, story_id);
+            }
+            run_metrics.succeed_step(story_id);
+            emit_step_event(evidence, run_metrics, story_id, "completed", None, None).await;
+        }
+        remaining
+    }
+
+    /// Side the `priority` merge resolution favours for `story_id`: its branch
+    /// if it outranks the other stories of the batch merged into `files`
+    /// (every other merged story of the batch when none declares them).
+    async fn favored_side(
+        &self,
+        story_id: &str,
+        files: &[String],
+        batch_story_ids: &[String],
+        graph: &DependencyGraph,
+    ) -> MergeSide {
+        let Some(story) = graph.get_story(story_id) else {
+            return MergeSide::Ours;
+        };
+        let state = self.execution_state.read().await;
+        let merged: Vec<&StoryNode> = batch_story_ids
+            .iter()
+            .filter(|id| *id != story_id && state.completed.contains(*id))
+            .filter_map(|id| graph.get_story(id))
+            .collect();
+        let touching: Vec<u32> = merged
+            .iter()
+            .filter(|other| {
+                other
+                    .target_files
+                    .iter()
+                    .any(|pattern| files.iter().any(|file| patterns_match(file, pattern)))
+            })
+            .map(|other| other.priority)
+            .collect();
+        if touching.is_empty() {
+            MergeSide::by_priority(story.priority, merged.iter().map(|other| other.priority))
+        } else {
+            MergeSide::by_priority(story.priority, touching)
+        }
+    }
+
+    /// Executor for the agent resolving `story_id`'s merge conflicts, or the
+    /// reconciliation issues traced to it, in the main working directory.
+    async fn merge_executor(
+        &self,
+        story_id: &str,
+        graph: &DependencyGraph,
+        agents: &AgentRoster,
+        evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+        run_metrics: &RunMetricsCollector,
+    ) -> StoryExecutor {
+        let story_agent = match graph.get_story(story_id) {
+            Some(story) => agents.for_story(story),
+            None => StoryAgent {
+                agent_command: agents.default_agent().to_string(),
+                model: None,
+            },
+        };
+        StoryExecutor::new(ExecutorConfig {
+            prd_path: self.base_config.prd_path.clone(),
+            project_root: self.base_config.working_dir.clone(),
+            agent_command: story_agent.agent_command.clone(),
+            timeout_config: self.config.timeout_config.clone(),
+            artifacts_dir: evidence_artifacts_dir(evidence).await,
+            evidence: evidence_writer(evidence).await,
+            resource_limits: self.config.worker_limits.clone(),
+            launch_limiter: self.launch_limiter.clone(),
+            agent_capabilities: agents.capabilities(&story_agent.agent_command).cloned(),
+            agent_container: self.base_config.agent_container.clone(),
+            model: story_agent.model,
+            run_metrics: Some(run_metrics.clone()),
+            ..Default::default()
+        })
+    }
+
+    /// Has an agent fix the issues reconciliation found in the integrated
+    /// tree, returning the issues left afterwards, or `None` if there were
+    /// only unmerged story branches, which editing the tree cannot fix.
+    ///
+    /// A fix that leaves only unmerged story branches is committed. Any other
+    /// is stashed, so the affected stories are re-run on the tree they left.
+    async fn run_reconciliation_agent(
+        &self,
+        issues: &[ReconciliationIssue],
+        affected_story_ids: &[String],
+        graph: &DependencyGraph,
+        agents: &AgentRoster,
+        evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+        run_metrics: &RunMetricsCollector,
+    ) -> Option<Vec<ReconciliationIssue>> {
+        let (merge_conflicts, tree_issues): (Vec<_>, Vec<_>) = issues
+            .iter()
+            .cloned()
+            .partition(|issue| matches!(issue, ReconciliationIssue::MergeConflict { .. }));
+        if tree_issues.is_empty() {
+            return None;
+        }
+        let root = &self.base_config.working_dir;
+        let files: Vec<String> = tree_issues
+            .iter()
+            .flat_map(ReconciliationIssue::files)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let diff = story_diffs(root, affected_story_ids, &files);
+        // The agent runs with the first affected story's agent and model
+        let story_id = affected_story_ids
+            .first()
+            .map_or(RECONCILIATION_STEP_ID, String::as_str);
+        let executor = self
+            .merge_executor(story_id, graph, agents, evidence, run_metrics)
+            .await;
+
+        let _git_guard = self.git_mutex.lock().await;
+        if let Err(err) = executor
+            .fix_reconciliation_issues(
+                affected_story_ids,
+                tree_issues.iter().map(ToString::to_string).collect(),
+                files,
+                diff,
+            )
+            .await
+        {
+            eprintln!("Warning: Reconciliation agent failed: {}", err);
+        }
+        let remaining = match ReconciliationEngine::new(root.clone())
+            .with_merge_conflicts(merge_conflicts)
+            .reconcile()
+        {
+            ReconciliationResult::Clean => Vec::new(),
+            ReconciliationResult::IssuesFound(remaining) => remaining,
+        };
+
+        let fixed = remaining
+            .iter()
+            .all(|issue| matches!(issue, ReconciliationIssue::MergeConflict { .. }));
+        let changed = git(root, &["status", "--porcelain"]).is_ok_and(|s| !s.trim().is_empty());
+        if fixed && changed {
+            let message = format!("fix: reconcile {}", affected_story_ids.join(", "));
+            let committed = git(root, &["add", "-A"])
+                .and_then(|_| git(root, &["commit", "-q", "-m", &message]));
+            if let Err(err) = committed {
+                eprintln!("Warning: Failed to commit reconciliation fix: {}", err);
+            }
+        } else if changed {
+            let stashed = git(
+                root,
+                &[
+                    "stash",
+                    "push",
+                    "--include-untracked",
+                    "-m",
+                    "ralph: reconciliation fix that left issues",
+                ],
+            );
+            if let Err(err) = stashed {
+                eprintln!("Warning: Failed to stash reconciliation fix: {}", err);
             }
         }
+        Some(remaining)
     }
 
-    /// Runs reconciliation after a batch completes and handles any issues found.
+    /// Runs reconciliation once in-flight stories have drained and handles any issues found.
     ///
     /// Returns `None` if reconciliation passed or issues were resolved via sequential retry.
     /// Returns `Some(error)` if reconciliation found issues that couldn't be resolved.
+    /// What was found and done is reported in evidence and the run metrics.
     #[allow(clippy::too_many_arguments)]
     async fn run_reconciliation(
         &self,
         batch_story_ids: &[String],
         graph: &DependencyGraph,
-        agent: &str,
+        agents: &AgentRoster,
+        total_iterations: &mut u32,
+        evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+        run_metrics: &RunMetricsCollector,
+        ui_sender: &Option<mpsc::Sender<ParallelUIEvent>>,
+        story_info_map: &HashMap<String, StoryDisplayInfo>,
+    ) -> Option<String> {
+        let start_time = Instant::now();
+        let mut report = ReconciliationReport {
+            stories: batch_story_ids.to_vec(),
+            ..Default::default()
+        };
+        let error = self
+            .reconcile_batch(
+                batch_story_ids,
+                graph,
+                agents,
+                total_iterations,
+                evidence,
+                run_metrics,
+                ui_sender,
+                story_info_map,
+                &mut report,
+            )
+            .await;
+        report.duration = start_time.elapsed();
+        if let Some(writer) = evidence.as_ref() {
+            writer
+                .lock()
+                .await
+                .emit_record(RECONCILIATION_EVIDENCE_KIND, &report);
+        }
+        run_metrics.record_reconciliation(report);
+        error
+    }
+
+    /// Reconciles the stories of `batch_story_ids`, filling in `report`.
+    #[allow(clippy::too_many_arguments)]
+    async fn reconcile_batch(
+        &self,
+        batch_story_ids: &[String],
+        graph: &DependencyGraph,
+        agents: &AgentRoster,
         total_iterations: &mut u32,
         evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
         run_metrics: &RunMetricsCollector,
         ui_sender: &Option<mpsc::Sender<ParallelUIEvent>>,
         story_info_map: &HashMap<String, StoryDisplayInfo>,
+        report: &mut ReconciliationReport,
     ) -> Option<String> {
-        let engine = ReconciliationEngine::new(self.base_config.working_dir.clone());
+        let merge_conflicts =
+            std::mem::take(&mut self.execution_state.write().await.merge_conflicts);
+        let merge_conflicts = self
+            .resolve_merge_conflicts(
+                merge_conflicts,
+                batch_story_ids,
+                graph,
+                agents,
+                evidence,
+                run_metrics,
+                &mut report.merge_resolutions,
+            )
+            .await;
+        let engine = ReconciliationEngine::new(self.base_config.working_dir.clone())
+            .with_merge_conflicts(merge_conflicts);
         let result = engine.reconcile();
 
         match result {
@@ -1314,22 +3363,21 @@ impl ParallelRunner {
                 }
                 None
             }
-            ReconciliationResult::IssuesFound(issues) => {
+            ReconciliationResult::IssuesFound(mut issues) => {
                 // Build issue summary message
-                let issue_descriptions: Vec<String> = issues
+                let issue_descriptions: Vec<String> =
+                    issues.iter().map(ToString::to_string).collect();
+                report.issues = issue_descriptions.clone();
+                report.files = issues
                     .iter()
-                    .map(|issue| match issue {
-                        ReconciliationIssue::GitConflict { affected_files } => {
-                            format!("git conflict in: {}", affected_files.join(", "))
-                        }
-                        ReconciliationIssue::TypeMismatch { file, error } => {
-                            format!("type error in {}: {}", file, error)
-                        }
-                        ReconciliationIssue::ImportDuplicate => {
-                            "duplicate import detected".to_string()
-                        }
-                    })
+                    .flat_map(ReconciliationIssue::files)
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
                     .collect();
+                // Stories whose target files overlap the files of the issues
+                let mut affected_story_ids =
+                    self.get_affected_stories(&issues, batch_story_ids, graph);
+                report.affected_stories = affected_story_ids.clone();
 
                 // Send ReconciliationStatus event for issues found
                 if let Some(ref sender) = ui_sender {
@@ -1341,14 +3389,48 @@ impl ParallelRunner {
                     let _ = sender.try_send(event);
                 }
 
+                // Have an agent fix the integrated tree before re-running whole stories
+                if self.config.reconcile_agent {
+                    if let Some(remaining) = self
+                        .run_reconciliation_agent(
+                            &issues,
+                            &affected_story_ids,
+                            graph,
+                            agents,
+                            evidence,
+                            run_metrics,
+                        )
+                        .await
+                    {
+                        let fixed = remaining.iter().all(|issue| {
+                            matches!(issue, ReconciliationIssue::MergeConflict { .. })
+                        });
+                        report.agent_fixed = Some(fixed);
+                        if fixed && remaining.is_empty() {
+                            report.action = ReconciliationAction::AgentFix;
+                            if let Some(ref sender) = ui_sender {
+                                let event = ParallelUIEvent::ReconciliationStatus {
+                                    success: true,
+                                    issues_count: 0,
+                                    message: "Clean after reconciliation agent".to_string(),
+                                };
+                                let _ = sender.try_send(event);
+                            }
+                            return None;
+                        }
+                        if fixed {
+                            // Only the stories whose branches did not merge are left to re-run
+                            affected_story_ids =
+                                self.get_affected_stories(&remaining, batch_story_ids, graph);
+                            issues = remaining;
+                        }
+                    }
+                }
+
                 // If fallback is enabled, retry affected stories sequentially
                 if self.config.fallback_to_sequential {
-                    // Get affected stories - for now, we retry all stories from the batch
-                    // that have issues (based on file overlap with issue files)
-                    let affected_story_ids =
-                        self.get_affected_stories(&issues, batch_story_ids, graph);
-
                     if !affected_story_ids.is_empty() {
+                        report.action = ReconciliationAction::SequentialRetry;
                         // Remove affected stories from completed set so they can be retried
                         {
                             let mut state = self.execution_state.write().await;
@@ -1385,19 +3467,48 @@ impl ParallelRunner {
                                 let _ = sender.try_send(event);
                             }
 
+                            let story_agent = match graph.get_story(story_id) {
+                                Some(story) => agents.for_story(story),
+                                None => StoryAgent {
+                                    agent_command: agents.default_agent().to_string(),
+                                    model: None,
+                                },
+                            };
+                            run_metrics.record_step_agent(story_id, story_agent.label());
                             let executor_config = ExecutorConfig {
                                 prd_path: self.base_config.prd_path.clone(),
                                 project_root: self.base_config.working_dir.clone(),
                                 progress_path: self.base_config.working_dir.join("progress.txt"),
                                 quality_profile: None,
-                                agent_command: agent.to_string(),
+                                agent_command: story_agent.agent_command.clone(),
                                 max_iterations: self.base_config.max_iterations_per_story,
                                 git_mutex: Some(self.git_mutex.clone()),
                                 timeout_config: self.config.timeout_config.clone(),
+                                artifacts_dir: evidence_artifacts_dir(evidence).await,
+                                evidence: evidence_writer(evidence).await,
+                                resource_limits: self.config.worker_limits.clone(),
+                                launch_limiter: self.launch_limiter.clone(),
+                                agent_capabilities: agents
+                                    .capabilities(&story_agent.agent_command)
+                                    .cloned(),
+                                agent_container: self.base_config.agent_container.clone(),
+                                file_context_tokens: self.base_config.file_context_tokens,
+                                model: story_agent.model.clone(),
+                                run_metrics: Some(run_metrics.clone()),
+                                prd_writeback: self.base_config.prd_writeback,
                                 ..Default::default()
                             };
 
-                            let executor = StoryExecutor::new(executor_config);
+                            let mut executor = StoryExecutor::new(executor_config);
+                            if let Some(output) = story_output_callback(
+                                story_id,
+                                ui_sender.as_ref(),
+                                self.run_log.as_ref(),
+                                self.github_actions.as_ref(),
+                                self.run_report.as_ref(),
+                            ) {
+                                executor.set_display_callback(output);
+                            }
                             let (_cancel_tx, cancel_rx) = watch::channel(false);
 
                             // Clone for iteration callback closure
@@ -1426,6 +3537,8 @@ impl ParallelRunner {
                                     let mut state = self.execution_state.write().await;
                                     state.completed.insert(story_id.clone());
                                     *total_iterations += exec_result.iterations_used;
+                                    report.retries.insert(story_id.clone(), true);
+                                    report.retry_iterations += exec_result.iterations_used;
                                     // Record metrics and evidence
                                     let attempts = exec_result.iterations_used.max(1);
                                     run_metrics
@@ -1457,6 +3570,8 @@ impl ParallelRunner {
                                         .unwrap_or_else(|| "Unknown error".to_string());
                                     state.failed.insert(story_id.clone(), error_msg.clone());
                                     *total_iterations += exec_result.iterations_used;
+                                    report.retries.insert(story_id.clone(), false);
+                                    report.retry_iterations += exec_result.iterations_used;
                                     // Record metrics and evidence
                                     let attempts = exec_result.iterations_used.max(1);
                                     run_metrics.complete_step(
@@ -1489,6 +3604,8 @@ impl ParallelRunner {
                                     let mut state = self.execution_state.write().await;
                                     state.failed.insert(story_id.clone(), e.to_string());
                                     *total_iterations += 1;
+                                    report.retries.insert(story_id.clone(), false);
+                                    report.retry_iterations += 1;
                                     // Record metrics and evidence
                                     let category = e.classify();
                                     run_metrics.complete_step(
@@ -1520,8 +3637,11 @@ impl ParallelRunner {
                             }
                         }
 
-                        // Run reconciliation again after sequential retry
-                        let post_retry_result = engine.reconcile();
+                        // Run reconciliation again after sequential retry; merge conflicts
+                        // were resolved by re-running their stories on the integration branch
+                        let post_retry_result =
+                            ReconciliationEngine::new(self.base_config.working_dir.clone())
+                                .reconcile();
                         match post_retry_result {
                             ReconciliationResult::Clean => {
                                 // Send ReconciliationStatus event for clean after retry
@@ -1536,6 +3656,8 @@ impl ParallelRunner {
                                 None
                             }
                             ReconciliationResult::IssuesFound(remaining_issues) => {
+                                report.remaining_issues =
+                                    remaining_issues.iter().map(ToString::to_string).collect();
                                 // Send ReconciliationStatus event for remaining issues
                                 if let Some(ref sender) = ui_sender {
                                     let event = ParallelUIEvent::ReconciliationStatus {
@@ -1556,6 +3678,7 @@ impl ParallelRunner {
                         }
                     } else {
                         // No affected stories identified, but issues exist
+                        report.remaining_issues = issue_descriptions;
                         Some(format!(
                             "Reconciliation failed with {} issues",
                             issues.len()
@@ -1563,6 +3686,8 @@ impl ParallelRunner {
                     }
                 } else {
                     // Fallback disabled, return error
+                    report.action = ReconciliationAction::FallbackDisabled;
+                    report.remaining_issues = issue_descriptions;
                     Some(format!(
                         "Reconciliation failed with {} issues (fallback disabled)",
                         issues.len()
@@ -1574,7 +3699,9 @@ impl ParallelRunner {
 
     /// Identifies stories affected by reconciliation issues.
     ///
-    /// Returns a list of story IDs that should be retried based on the issues found.
+    /// Returns a list of story IDs that should be retried based on the issues found:
+    /// stories whose branches did not merge, and stories whose commits changed a
+    /// file an issue was found in.
     fn get_affected_stories(
         &self,
         issues: &[ReconciliationIssue],
@@ -1583,129 +3710,736 @@ impl ParallelRunner {
     ) -> Vec<String> {
         // Collect all affected files from issues
         let mut affected_files: HashSet<String> = HashSet::new();
+        // Stories whose branches failed to merge are always retried
+        let mut unmerged_story_ids: Vec<String> = Vec::new();
 
         for issue in issues {
             match issue {
-                ReconciliationIssue::GitConflict {
-                    affected_files: files,
-                } => {
-                    affected_files.extend(files.iter().cloned());
-                }
-                ReconciliationIssue::TypeMismatch { file, .. } => {
-                    if file != "unknown" {
-                        affected_files.insert(file.clone());
-                    }
-                }
                 ReconciliationIssue::ImportDuplicate => {
                     // For import duplicates, we can't easily determine which files are affected
                     // So we mark all batch stories as affected
                     return batch_story_ids.to_vec();
                 }
+                ReconciliationIssue::MergeConflict { story_id, .. } => {
+                    if !unmerged_story_ids.contains(story_id) {
+                        unmerged_story_ids.push(story_id.clone());
+                    }
+                }
+                issue => affected_files.extend(issue.files()),
+            }
+        }
+
+        // If we couldn't identify specific files, retry the unmerged stories,
+        // or all batch stories when there are none
+        if affected_files.is_empty() {
+            if !unmerged_story_ids.is_empty() {
+                return unmerged_story_ids;
+            }
+            return batch_story_ids.to_vec();
+        }
+
+        // Find stories that committed changes to affected files; stories with
+        // no commits to go by are matched on their target_files instead
+        let mut affected_story_ids = unmerged_story_ids;
+
+        for story_id in batch_story_ids {
+            if affected_story_ids.contains(story_id) {
+                continue;
+            }
+            let committed = story_files(&self.base_config.working_dir, story_id);
+            let responsible = if committed.is_empty() {
+                graph.get_story(story_id).is_some_and(|story| {
+                    // Check if any affected file matches or is contained in target_file pattern
+                    story.target_files.iter().any(|target_file| {
+                        affected_files.iter().any(|affected_file| {
+                            target_file.contains(affected_file)
+                                || affected_file.contains(target_file)
+                        })
+                    })
+                })
+            } else {
+                committed.iter().any(|file| affected_files.contains(file))
+            };
+            if responsible {
+                affected_story_ids.push(story_id.clone());
+            }
+        }
+
+        // If we still couldn't match any stories, retry all
+        if affected_story_ids.is_empty() {
+            batch_story_ids.to_vec()
+        } else {
+            affected_story_ids
+        }
+    }
+
+    /// Load the PRD file.
+    /// When resuming only some stories, the stories the checkpointed run
+    /// completed satisfy their dependencies.
+    fn mark_resumed_passes(&self, prd: &mut PrdFile) {
+        if let Some(ref checkpoint) = self.resume_state {
+            for story in prd.user_stories.iter_mut() {
+                if !self.config.selects(&story.id) && checkpoint.completed.contains(&story.id) {
+                    story.passes = true;
+                }
+            }
+        }
+    }
+
+    /// Reload a changed PRD and plan it as the run start would.
+    ///
+    /// Returns `None` when no story changed. A change is rejected if it
+    /// removes a story in flight, introduces lint errors (duplicate IDs,
+    /// unknown dependencies, cycles) or no longer fits the story filters.
+    fn replan(
+        &self,
+        current: &PrdFile,
+        in_flight: &HashSet<String>,
+    ) -> Result<Option<(PrdFile, DependencyGraph, PrdChanges)>, String> {
+        let mut prd = self.load_prd()?;
+        self.mark_resumed_passes(&mut prd);
+
+        let mut graph = DependencyGraph::from_stories(&prd.user_stories);
+        if self.config.filters_stories() {
+            graph = graph
+                .select(&self.config.only_stories, &self.config.skip_stories)
+                .map_err(|e| e.to_string())?;
+            prd.user_stories
+                .retain(|story| graph.get_story(&story.id).is_some());
+        }
+
+        let changes = diff_stories(&current.user_stories, &prd.user_stories);
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        if let Some(running) = changes.removed.iter().find(|id| in_flight.contains(*id)) {
+            return Err(format!("{} was removed while running", running));
+        }
+        let errors = introduced_errors(current, &prd);
+        if !errors.is_empty() {
+            let errors: Vec<String> = errors
+                .iter()
+                .map(|finding| format!("{}: {}", finding.story, finding.message))
+                .collect();
+            return Err(errors.join("; "));
+        }
+
+        if self.config.infer_dependencies {
+            graph.infer_dependencies();
+        }
+        graph.validate().map_err(|e| e.to_string())?;
+        Ok(Some((prd, graph, changes)))
+    }
+
+    fn load_prd(&self) -> Result<PrdFile, String> {
+        validate_prd(&self.base_config.prd_path).map_err(|e| e.to_string())
+    }
+
+    /// Save a checkpoint with the current execution state.
+    ///
+    /// `queued` are the stories waiting to be dispatched. Does nothing if
+    /// checkpointing is disabled.
+    async fn save_checkpoint(
+        &self,
+        current_story: Option<StoryCheckpoint>,
+        pause_reason: PauseReason,
+        queued: Vec<String>,
+    ) {
+        if let Some(ref manager) = self.checkpoint_manager {
+            let uncommitted_files = self.get_uncommitted_files().unwrap_or_default();
+            let state = self.execution_state.read().await;
+            let mut parallel_state = state.to_checkpoint(queued);
+            if let Ok(progress) = self.story_iterations.lock() {
+                parallel_state.iterations = state.in_flight_iterations(&progress);
+            }
+            parallel_state.retry_budgets = self
+                .retry_budgets
+                .snapshot(|id| state.in_flight.contains(id));
+            drop(state);
+            // After resuming only some stories, the others keep their checkpointed state
+            if let Some(ref resumed) = self.resume_state {
+                if self.config.filters_stories() {
+                    parallel_state =
+                        resumed.merge_selected(&parallel_state, |id| self.config.selects(id));
+                }
+            }
+            let fingerprint =
+                RunFingerprint::capture(&self.base_config.prd_path, &self.base_config.working_dir);
+            let checkpoint = Checkpoint::new(current_story, pause_reason, uncommitted_files)
+                .with_parallel_state(parallel_state)
+                .with_fingerprint(fingerprint)
+                .with_run_metrics(self.run_metrics.finish());
+
+            if let Err(e) = manager.save(&checkpoint) {
+                eprintln!("Warning: Failed to save checkpoint: {}", e);
+            }
+        }
+    }
+
+    /// Remove the saved checkpoint once every story of the run has passed.
+    ///
+    /// After resuming only some stories, the checkpoint is kept for the others
+    /// if any of them still have to run.
+    async fn complete_checkpoint(&self) {
+        if let Some(ref resumed) = self.resume_state {
+            if self.config.filters_stories() {
+                let current = self.execution_state.read().await.to_checkpoint(Vec::new());
+                let merged = resumed.merge_selected(&current, |id| self.config.selects(id));
+                if merged.has_unfinished() {
+                    self.save_checkpoint(None, PauseReason::UserRequested, Vec::new())
+                        .await;
+                    return;
+                }
+            }
+        }
+        self.clear_checkpoint();
+    }
+
+    /// Remove the saved checkpoint, e.g. after resuming in-process or once
+    /// every story has passed.
+    fn clear_checkpoint(&self) {
+        if let Some(ref manager) = self.checkpoint_manager {
+            if let Err(e) = manager.clear() {
+                eprintln!("Warning: Failed to clear checkpoint: {}", e);
+            }
+        }
+    }
+
+    /// Get list of uncommitted files from git.
+    fn get_uncommitted_files(&self) -> Result<Vec<String>, String> {
+        use std::process::Command;
+
+        let output = Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(&self.base_config.working_dir)
+            .output()
+            .map_err(|e| format!("Failed to run git status: {}", e))?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let files: Vec<String> = stdout
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.len() > 3 {
+                    Some(line[3..].to_string())
+                } else {
+                    None
+                }
+            })
+            .filter(|path| path != ".ralph/checkpoint.json" && path != ".ralph/checkpoint.json.tmp")
+            .collect();
+
+        Ok(files)
+    }
+}
+
+/// Stories that cannot run because a story they depend on, directly or
+/// transitively, failed or was skipped, mapped to that story.
+///
+/// A failed story that later passed does not block anything.
+fn blocked_by_failures(
+    graph: &DependencyGraph,
+    state: &ParallelExecutionState,
+) -> HashMap<String, String> {
+    let mut failed: Vec<&String> = state
+        .failed
+        .keys()
+        .chain(state.skipped.iter())
+        .chain(state.unmet_prerequisites.keys())
+        .filter(|story_id| !state.completed.contains(*story_id))
+        .collect();
+    failed.sort();
+    failed.dedup();
+
+    let mut blocked = HashMap::new();
+    for failed_id in failed {
+        for dependent in graph.transitive_dependents(failed_id) {
+            let runnable = graph
+                .get_story(&dependent)
+                .is_some_and(|story| !story.passes)
+                && !state.completed.contains(&dependent)
+                && !state.in_flight.contains(&dependent)
+                && !state.failed.contains_key(&dependent)
+                && !state.skipped.contains(&dependent)
+                && !state.unmet_prerequisites.contains_key(&dependent);
+            if runnable {
+                blocked
+                    .entry(dependent)
+                    .or_insert_with(|| failed_id.clone());
+            }
+        }
+    }
+    blocked
+}
+
+/// Records a story blocked by an unmet prerequisite in metrics and evidence.
+async fn record_unmet_prerequisite(
+    story_id: &str,
+    reason: &str,
+    evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+    run_metrics: &RunMetricsCollector,
+) {
+    let message = format!("Prerequisite not met: {}", reason);
+    run_metrics.start_step(story_id);
+    run_metrics.complete_step(story_id, false, 0, Duration::ZERO, Some(message.clone()));
+    emit_step_event(
+        evidence,
+        run_metrics,
+        story_id,
+        "blocked",
+        Some("unmet_prerequisite".to_string()),
+        Some(message),
+    )
+    .await;
+}
+
+/// Summary of the stories with unmet prerequisites for the run result, or
+/// `None` if there are none.
+fn unmet_prerequisites_summary(unmet: &HashMap<String, String>) -> Option<String> {
+    if unmet.is_empty() {
+        return None;
+    }
+    let mut unmet: Vec<(&String, &String)> = unmet.iter().collect();
+    unmet.sort();
+    let details: Vec<String> = unmet
+        .iter()
+        .map(|(story_id, reason)| format!("{} ({})", story_id, reason))
+        .collect();
+    Some(format!(
+        "{} with unmet prerequisites: {}",
+        unmet.len(),
+        details.join(", ")
+    ))
+}
+
+/// Records blocked stories in metrics and evidence, returning a summary for
+/// the run result, or `None` if nothing is blocked.
+async fn record_blocked_stories(
+    blocked: &HashMap<String, String>,
+    evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+    run_metrics: &RunMetricsCollector,
+) -> Option<String> {
+    if blocked.is_empty() {
+        return None;
+    }
+
+    let mut blocked: Vec<(&String, &String)> = blocked.iter().collect();
+    blocked.sort();
+    for &(story_id, blocked_by) in &blocked {
+        let message = format!("Blocked by {}", blocked_by);
+        run_metrics.start_step(story_id);
+        run_metrics.complete_step(story_id, false, 0, Duration::ZERO, Some(message.clone()));
+        emit_step_event(
+            evidence,
+            run_metrics,
+            story_id,
+            "blocked",
+            Some("blocked_by_failure".to_string()),
+            Some(message),
+        )
+        .await;
+    }
+
+    let details: Vec<String> = blocked
+        .iter()
+        .map(|(story_id, blocked_by)| format!("{} by {}", story_id, blocked_by))
+        .collect();
+    Some(format!(
+        "{} blocked by failed dependencies: {}",
+        blocked.len(),
+        details.join(", ")
+    ))
+}
+
+/// A story dispatched to a remote worker instead of run locally.
+struct RemoteStory {
+    pool: Arc<RemoteWorkerPool>,
+    /// Checkout the story's commits are merged into
+    repo_root: PathBuf,
+    /// PRD sent to the worker and updated once the story passes
+    prd_path: PathBuf,
+    run_id: String,
+}
+
+impl RemoteStory {
+    /// Runs the story on a remote worker, merges its commits and marks it as
+    /// passing in the local PRD, as a local run would.
+    async fn run<F>(
+        &self,
+        executor: &StoryExecutor,
+        git_mutex: &Mutex<()>,
+        story_id: &str,
+        max_iterations: u32,
+        cancel_rx: watch::Receiver<bool>,
+        on_iteration: F,
+    ) -> Result<ExecutionResult, ExecutorError>
+    where
+        F: FnMut(u32, u32),
+    {
+        if Workspace::is_manifest(&self.prd_path) {
+            return Err(ExecutorError::PrdError(
+                "Remote workers do not support PRD workspaces".to_string(),
+            ));
+        }
+        let prd = std::fs::read_to_string(&self.prd_path)
+            .map_err(|e| ExecutorError::PrdError(format!("Failed to read PRD: {}", e)))?;
+        let result = self
+            .pool
+            .run_story(
+                &self.repo_root,
+                git_mutex,
+                &self.run_id,
+                story_id,
+                prd,
+                max_iterations,
+                cancel_rx,
+                on_iteration,
+            )
+            .await?;
+        if result.success {
+            executor.update_prd_passes(story_id)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Whether any two of `story_ids` target files in the same directory.
+///
+/// A story without target files could touch anything, so it counts as sharing.
+fn share_parent_directory(graph: &DependencyGraph, story_ids: &[String]) -> bool {
+    let mut seen: HashMap<PathBuf, &str> = HashMap::new();
+    for story_id in story_ids {
+        let Some(story) = graph.get_story(story_id) else {
+            continue;
+        };
+        if story.target_files.is_empty() {
+            return story_ids.len() > 1;
+        }
+        for file in &story.target_files {
+            let dir = Path::new(file)
+                .parent()
+                .unwrap_or(Path::new(""))
+                .to_path_buf();
+            match seen.get(&dir) {
+                Some(owner) if *owner != story_id.as_str() => return true,
+                _ => {
+                    seen.insert(dir, story_id);
+                }
             }
         }
+    }
+    false
+}
+
+/// Evidence of the reconciliation mode and each pass run or skipped under it.
+#[derive(Serialize)]
+struct ReconciliationEvidence<'a> {
+    /// Reconcile mode label
+    mode: &'static str,
+    /// Finished stories between passes, for the `every` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    every: Option<usize>,
+    /// "configured" at run start, then "ran" or "skipped" per pass
+    action: &'a str,
+    /// Stories finished since the previous pass
+    stories: &'a [String],
+}
+
+async fn record_reconciliation(
+    evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+    mode: ReconcileMode,
+    action: &str,
+    stories: &[String],
+) {
+    if let Some(writer) = evidence.as_ref() {
+        let every = match mode {
+            ReconcileMode::Every(every) => Some(every),
+            _ => None,
+        };
+        writer.lock().await.emit_record(
+            "reconciliation",
+            &ReconciliationEvidence {
+                mode: mode.as_label(),
+                every,
+                action,
+                stories,
+            },
+        );
+    }
+}
+
+/// A PRD change picked up by `--watch-prd`, as recorded in evidence.
+#[derive(Serialize)]
+struct PrdChangeEvidence<'a> {
+    /// "applied", or "rejected" with the reason in `error`
+    status: &'a str,
+    #[serde(flatten)]
+    changes: &'a PrdChanges,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+/// Record the epics and estimates of the stories selected for the run, so
+/// metrics and step evidence report progress per epic and metrics compare
+/// each story with its estimate.
+async fn record_stories(
+    prd: &PrdFile,
+    graph: &DependencyGraph,
+    run_metrics: &RunMetricsCollector,
+    evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+) {
+    let selected: Vec<PrdUserStory> = prd
+        .user_stories
+        .iter()
+        .filter(|story| graph.get_story(&story.id).is_some())
+        .cloned()
+        .collect();
+    run_metrics.record_stories(&selected);
+    if let Some(writer) = evidence.as_ref() {
+        writer.lock().await.set_story_epics(&selected);
+    }
+}
+
+async fn record_prd_change(
+    evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+    status: &str,
+    changes: &PrdChanges,
+    error: Option<&str>,
+) {
+    if let Some(writer) = evidence.as_ref() {
+        writer.lock().await.emit_record(
+            "prd_change",
+            &PrdChangeEvidence {
+                status,
+                changes,
+                error,
+            },
+        );
+    }
+}
+
+async fn emit_run_complete(
+    evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
+    status: &str,
+    error_type: Option<String>,
+    error_message: Option<String>,
+) {
+    if let Some(writer) = evidence.as_ref() {
+        let mut writer = writer.lock().await;
+        writer.emit_run_complete(status, error_type, error_message);
+    }
+}
+
+async fn evidence_artifacts_dir(evidence: &Option<Arc<Mutex<EvidenceWriter>>>) -> Option<PathBuf> {
+    match evidence.as_ref() {
+        Some(writer) => Some(writer.lock().await.artifacts_dir()),
+        None => None,
+    }
+}
+
+/// Mark a story whose work reached the main checkout as passing in the PRD.
+fn write_back_passes(prd_path: &Path, story_id: &str) {
+    // A workspace story is marked in its member PRD
+    let (prd_path, story_id) = story_prd(prd_path, story_id);
+    if let Err(err) = set_story_passes(&prd_path, &story_id, true) {
+        eprintln!(
+            "Warning: Failed to mark {} as passing in {}: {}",
+            story_id,
+            prd_path.display(),
+            err
+        );
+    }
+}
 
-        // If we couldn't identify specific files, retry all batch stories
-        if affected_files.is_empty() {
-            return batch_story_ids.to_vec();
+/// Run a story's `execution` within `story_timeout`, then merge its worktree
+/// back and remove it.
+///
+/// Only the execution is time-boxed: merging back waits on the git mutex, and
+/// a story that finished in time must not fail or leak its worktree while it
+/// waits. A timed-out story's worktree is removed without merging.
+async fn run_story_in_time(
+    story_timeout: Duration,
+    execution: impl std::future::Future<Output = Result<ExecutionResult, ExecutorError>>,
+    worktree: Option<(WorktreeManager, StoryWorktree)>,
+    git_mutex: &Arc<Mutex<()>>,
+    isolation: &IsolationMode,
+    execution_state: &Arc<RwLock<ParallelExecutionState>>,
+    prd_writeback: Option<&Path>,
+) -> Result<Result<ExecutionResult, ExecutorError>, tokio::time::error::Elapsed> {
+    let timed_result = tokio::time::timeout(story_timeout, execution).await;
+    let Some((manager, worktree)) = worktree else {
+        return timed_result;
+    };
+    match timed_result {
+        Ok(result) => Ok(finish_worktree(
+            &manager,
+            &worktree,
+            git_mutex,
+            isolation,
+            execution_state,
+            prd_writeback,
+            result,
+        )
+        .await),
+        Err(elapsed) => {
+            let timed_out = Err(ExecutorError::Timeout(format!(
+                "Story timed out after {:?}",
+                story_timeout
+            )));
+            let _ = finish_worktree(
+                &manager,
+                &worktree,
+                git_mutex,
+                isolation,
+                execution_state,
+                prd_writeback,
+                timed_out,
+            )
+            .await;
+            Err(elapsed)
         }
+    }
+}
 
-        // Find stories whose target_files overlap with affected files
-        let mut affected_story_ids = Vec::new();
-
-        for story_id in batch_story_ids {
-            if let Some(story) = graph.get_story(story_id) {
-                for target_file in &story.target_files {
-                    // Check if any affected file matches or is contained in target_file pattern
-                    for affected_file in &affected_files {
-                        if target_file.contains(affected_file)
-                            || affected_file.contains(target_file)
-                        {
-                            affected_story_ids.push(story_id.clone());
-                            break;
-                        }
-                    }
+/// Merge a successful story's worktree back into the main checkout and remove it.
+///
+/// A failed merge turns the story into a failure. In branch mode a merge conflict
+/// is also recorded for reconciliation and the story branch is kept; otherwise
+/// the worktree and its branch are removed. Only a merged story is marked
+/// passing in `prd_writeback`, so a lost merge is never recorded as done.
+async fn finish_worktree(
+    manager: &WorktreeManager,
+    worktree: &StoryWorktree,
+    git_mutex: &Arc<Mutex<()>>,
+    isolation: &IsolationMode,
+    execution_state: &Arc<RwLock<ParallelExecutionState>>,
+    prd_writeback: Option<&Path>,
+    result: Result<ExecutionResult, ExecutorError>,
+) -> Result<ExecutionResult, ExecutorError> {
+    let _git_guard = git_mutex.lock().await;
+    let mut keep_branch = false;
+    let result = match result {
+        Ok(mut exec_result) if exec_result.success => {
+            let merged = manager.merge_back(worktree);
+            if let (Ok(()), Some(prd_path)) = (&merged, prd_writeback) {
+                write_back_passes(prd_path, &worktree.story_id);
+            }
+            if let Err(err) = merged {
+                exec_result.success = false;
+                exec_result.error = Some(err.to_string());
+                if let (IsolationMode::Branch, WorktreeError::MergeConflict { branch, files }) =
+                    (isolation, err)
+                {
+                    keep_branch = true;
+                    execution_state.write().await.merge_conflicts.push(
+                        ReconciliationIssue::MergeConflict {
+                            story_id: worktree.story_id.clone(),
+                            branch,
+                            affected_files: files,
+                        },
+                    );
                 }
             }
+            Ok(exec_result)
         }
-
-        // If we still couldn't match any stories, retry all
-        if affected_story_ids.is_empty() {
-            batch_story_ids.to_vec()
-        } else {
-            affected_story_ids
-        }
+        other => other,
+    };
+    let removed = if keep_branch {
+        manager.remove_checkout(worktree)
+    } else {
+        manager.remove(worktree)
+    };
+    if let Err(err) = removed {
+        eprintln!(
+            "Warning: Failed to remove worktree for {}: {}",
+            worktree.story_id, err
+        );
     }
+    result
+}
 
-    /// Load the PRD file.
-    fn load_prd(&self) -> Result<PrdFile, String> {
-        validate_prd(&self.base_config.prd_path).map_err(|e| e.to_string())
+async fn evidence_writer(evidence: &Option<Arc<Mutex<EvidenceWriter>>>) -> Option<EvidenceWriter> {
+    match evidence.as_ref() {
+        Some(writer) => Some(writer.lock().await.clone()),
+        None => None,
     }
+}
 
-    /// Save a checkpoint with the current execution state.
-    ///
-    /// Does nothing if checkpointing is disabled.
-    fn save_checkpoint(
-        &self,
-        story_id: &str,
-        iteration: u32,
-        max_iterations: u32,
-        pause_reason: PauseReason,
-    ) {
-        if let Some(ref manager) = self.checkpoint_manager {
-            let uncommitted_files = self.get_uncommitted_files().unwrap_or_default();
-            let checkpoint = Checkpoint::new(
-                Some(StoryCheckpoint::new(story_id, iteration, max_iterations)),
-                pause_reason,
-                uncommitted_files,
-            );
+/// Aborts a background task when dropped.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
 
-            if let Err(e) = manager.save(&checkpoint) {
-                eprintln!("Warning: Failed to save checkpoint: {}", e);
-            }
-        }
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
     }
+}
 
-    /// Get list of uncommitted files from git.
-    fn get_uncommitted_files(&self) -> Result<Vec<String>, String> {
-        use std::process::Command;
-
-        let output = Command::new("git")
-            .args(["status", "--porcelain"])
-            .current_dir(&self.base_config.working_dir)
-            .output()
-            .map_err(|e| format!("Failed to run git status: {}", e))?;
-
-        if !output.status.success() {
-            return Ok(vec![]);
+/// Sleeps until `deadline` (e.g. the next lock-wait escalation or autosave), or
+/// forever if there is none.
+///
+/// Sleeps at least `min_wait` so an overdue escalation for a story that cannot
+/// be dispatched yet does not spin the scheduler loop.
+async fn sleep_until_due(deadline: Option<Instant>, min_wait: Duration) {
+    match deadline {
+        Some(deadline) => {
+            let deadline = deadline.max(Instant::now() + min_wait);
+            tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await;
         }
+        None => std::future::pending().await,
+    }
+}
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let files: Vec<String> = stdout
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.len() > 3 {
-                    Some(line[3..].to_string())
-                } else {
-                    None
-                }
-            })
-            .filter(|path| path != ".ralph/checkpoint.json" && path != ".ralph/checkpoint.json.tmp")
-            .collect();
+/// Why a story task ended without an outcome: its panic message, or that it
+/// was aborted.
+fn story_task_failure_message(join_error: tokio::task::JoinError) -> String {
+    if !join_error.is_panic() {
+        return "Story task was aborted".to_string();
+    }
+    let panic = join_error.into_panic();
+    let detail = panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    format!("Story task panicked: {}", detail)
+}
 
-        Ok(files)
+/// Display callback of a story's executor, forwarding its agent output to the
+/// UI, recording its agent and gate output in the run log, annotating its
+/// failed gates for GitHub Actions and collecting them for the final report.
+fn story_output_callback(
+    story_id: &str,
+    ui_sender: Option<&mpsc::Sender<ParallelUIEvent>>,
+    run_log: Option<&RunLog>,
+    github_actions: Option<&GithubActions>,
+    run_report: Option<&RunReport>,
+) -> Option<Arc<dyn DisplayCallback>> {
+    let mut output = ui_sender.map(|sender| {
+        Arc::new(StoryOutputForwarder::new(story_id, sender.clone())) as Arc<dyn DisplayCallback>
+    });
+    if let Some(run_report) = run_report {
+        output = Some(run_report.output_callback(story_id, output));
+    }
+    if let Some(github_actions) = github_actions {
+        output = Some(github_actions.output_callback(story_id, output));
     }
+    if let Some(run_log) = run_log {
+        output = Some(run_log.output_callback(story_id, output));
+    }
+    output
 }
 
-async fn emit_run_complete(
-    evidence: &Option<Arc<Mutex<EvidenceWriter>>>,
-    status: &str,
-    error_type: Option<String>,
-    error_message: Option<String>,
-) {
-    if let Some(writer) = evidence.as_ref() {
-        let mut writer = writer.lock().await;
-        writer.emit_run_complete(status, error_type, error_message);
+/// Receives the next UI control event, or waits forever if there is no channel.
+async fn recv_control_event(
+    control_rx: &mut Option<mpsc::Receiver<ParallelUIEvent>>,
+) -> Option<ParallelUIEvent> {
+    match control_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
     }
 }
 
@@ -1758,6 +4492,23 @@ mod tests {
         assert_eq!(config.circuit_breaker_threshold, 0);
     }
 
+    #[test]
+    fn test_parallel_runner_config_selects() {
+        let config = ParallelRunnerConfig::default();
+        assert!(!config.filters_stories());
+        assert!(config.selects("US-001"));
+
+        let config = ParallelRunnerConfig {
+            only_stories: vec!["US-001".to_string(), "US-002".to_string()],
+            skip_stories: vec!["US-002".to_string()],
+            ..Default::default()
+        };
+        assert!(config.filters_stories());
+        assert!(config.selects("US-001"));
+        assert!(!config.selects("US-002"));
+        assert!(!config.selects("US-003"));
+    }
+
     // ============================================================================
     // Cumulative Failure Counter Logic Tests
     // ============================================================================
@@ -1878,26 +4629,188 @@ mod tests {
     }
 
     // ============================================================================
-    // Batch Timeout Failure Tests
+    // Story Timeout Failure Tests
     // ============================================================================
 
     #[test]
-    fn test_batch_timeout_counts_as_non_transient() {
-        // When a batch times out, all in-flight stories count as non-transient failures
+    fn test_story_timeout_counts_as_non_transient() {
+        // Each timed-out story counts as a non-transient failure
         let threshold: u32 = 5;
         let mut cumulative: u32 = 0;
 
-        // Batch 1: 2 regular failures
+        // 2 regular failures
         cumulative += 2;
 
-        // Batch 2: timeout with 3 in-flight stories
-        let timed_out_count = 3;
-        cumulative += timed_out_count;
+        // 3 stories time out as they are joined
+        for _ in 0..3 {
+            cumulative += 1;
+        }
 
         // Should trigger circuit breaker (5 >= 5)
         assert!(cumulative >= threshold);
     }
 
+    #[test]
+    fn test_reconcile_mode_defaults_to_idle() {
+        let config = ParallelRunnerConfig::default();
+        assert_eq!(config.reconcile_mode, ReconcileMode::Idle);
+        assert_eq!(config.story_timeout, Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn test_reconcile_mode_from_label() {
+        assert_eq!(
+            ReconcileMode::from_label("idle", 0),
+            Ok(ReconcileMode::Idle)
+        );
+        // --reconcile-every alone keeps working
+        assert_eq!(
+            ReconcileMode::from_label("idle", 5),
+            Ok(ReconcileMode::Every(5))
+        );
+        assert_eq!(
+            ReconcileMode::from_label("every", 0),
+            Ok(ReconcileMode::Every(1))
+        );
+        assert_eq!(
+            ReconcileMode::from_label("end", 3),
+            Ok(ReconcileMode::AtEnd)
+        );
+        assert_eq!(
+            ReconcileMode::from_label("shared_dirs", 0),
+            Ok(ReconcileMode::SharedDirectories)
+        );
+        assert!(ReconcileMode::from_label("batch", 0).is_err());
+    }
+
+    #[test]
+    fn test_share_parent_directory() {
+        let graph = DependencyGraph::from_stories(&[
+            prd_story("US-001", &["src/api/users.rs", "README.md"]),
+            prd_story("US-002", &["src/api/orders.rs"]),
+            prd_story("US-003", &["src/ui/app.rs", "src/ui/theme.rs"]),
+            prd_story("US-004", &[]),
+        ]);
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        assert!(share_parent_directory(&graph, &ids(&["US-001", "US-002"])));
+        assert!(!share_parent_directory(&graph, &ids(&["US-001", "US-003"])));
+        // A story's own files never count as shared
+        assert!(!share_parent_directory(&graph, &ids(&["US-003"])));
+        // Stories without target files could touch anything
+        assert!(share_parent_directory(&graph, &ids(&["US-003", "US-004"])));
+    }
+
+    #[test]
+    fn test_blocked_by_failures() {
+        let story = |id: &str, depends_on: &[&str]| {
+            let mut story = prd_story(id, &[]);
+            story.depends_on = depends_on.iter().map(|s| s.to_string()).collect();
+            story
+        };
+        let graph = DependencyGraph::from_stories(&[
+            story("US-001", &[]),
+            story("US-002", &["US-001"]),
+            story("US-003", &["US-002"]),
+            story("US-004", &[]),
+            story("US-005", &["US-004"]),
+        ]);
+        let mut state = ParallelExecutionState::default();
+        state
+            .failed
+            .insert("US-001".to_string(), "tests failed".to_string());
+        state.skipped.insert("US-004".to_string());
+
+        let blocked = blocked_by_failures(&graph, &state);
+        assert_eq!(blocked.len(), 3);
+        assert_eq!(blocked["US-002"], "US-001");
+        assert_eq!(blocked["US-003"], "US-001");
+        assert_eq!(blocked["US-005"], "US-004");
+
+        // A failed story that passed on a later attempt no longer blocks
+        state.completed.insert("US-001".to_string());
+        let blocked = blocked_by_failures(&graph, &state);
+        assert_eq!(blocked.keys().collect::<Vec<_>>(), vec!["US-005"]);
+    }
+
+    #[test]
+    fn test_execution_state_checkpoint_restore() {
+        let mut state = ParallelExecutionState::default();
+        state.completed.insert("US-001".to_string());
+        state
+            .failed
+            .insert("US-002".to_string(), "tests failed".to_string());
+        state.skipped.insert("US-003".to_string());
+        state.in_flight.insert("US-004".to_string());
+        state.completed.insert("US-404".to_string());
+
+        let checkpoint = state.to_checkpoint(vec!["US-005".to_string(), "US-004".to_string()]);
+        assert_eq!(checkpoint.completed, vec!["US-001", "US-404"]);
+        assert_eq!(checkpoint.failed["US-002"], "tests failed");
+        assert_eq!(checkpoint.skipped, vec!["US-003"]);
+        assert_eq!(checkpoint.pending, vec!["US-004", "US-005"]);
+
+        // US-404 was removed from the PRD since the checkpoint was written
+        let graph = DependencyGraph::from_stories(&[
+            prd_story("US-001", &[]),
+            prd_story("US-002", &[]),
+            prd_story("US-003", &[]),
+            prd_story("US-004", &[]),
+            prd_story("US-005", &[]),
+        ]);
+        let mut resumed = ParallelExecutionState::default();
+        let restored = resumed.restore_checkpoint(&checkpoint, &graph);
+        assert_eq!(restored, vec!["US-001"]);
+        assert!(resumed.skipped.contains("US-003"));
+        // Failed and interrupted stories run again
+        assert!(resumed.failed.is_empty());
+        assert!(!resumed.completed.contains("US-004"));
+
+        // Stories already passing in the PRD are not reported as restored
+        assert!(resumed.restore_checkpoint(&checkpoint, &graph).is_empty());
+    }
+
+    #[test]
+    fn test_execution_state_in_flight_iterations() {
+        let mut state = ParallelExecutionState::default();
+        state.in_flight.insert("US-002".to_string());
+        let progress = HashMap::from([("US-001".to_string(), 4), ("US-002".to_string(), 2)]);
+
+        // US-001 already finished, so only US-002 is recorded
+        let iterations = state.in_flight_iterations(&progress);
+        assert_eq!(iterations, BTreeMap::from([("US-002".to_string(), 2)]));
+    }
+
+    #[test]
+    fn test_evict_lowest_priority() {
+        let node = |id: &str, priority: u32| {
+            let mut node = StoryNode::from(&prd_story(id, &[]));
+            node.priority = priority;
+            node
+        };
+        let mut queue: VecDeque<StoryNode> =
+            vec![node("US-001", 2), node("US-002", 5), node("US-003", 5)].into();
+
+        // The most recently queued of the lowest-priority stories goes first
+        let evicted = evict_lowest_priority(&mut queue, 1).unwrap();
+        assert_eq!(evicted.id, "US-003");
+        assert_eq!(queue.len(), 2);
+
+        // A newcomer of equal or lower priority evicts nothing
+        assert!(evict_lowest_priority(&mut queue, 5).is_none());
+        assert!(evict_lowest_priority(&mut queue, 9).is_none());
+        assert_eq!(evict_lowest_priority(&mut queue, 4).unwrap().id, "US-002");
+        assert!(evict_lowest_priority(&mut VecDeque::new(), 1).is_none());
+    }
+
+    #[test]
+    fn test_below_memory_headroom() {
+        assert!(below_memory_headroom(Some(2048), Some(1024)));
+        assert!(!below_memory_headroom(Some(2048), Some(4096)));
+        assert!(!below_memory_headroom(None, Some(0)));
+        assert!(!below_memory_headroom(Some(2048), None));
+    }
+
     // ============================================================================
     // Execution State Tests
     // ============================================================================
@@ -1936,6 +4849,60 @@ mod tests {
         assert!(state.locked_files.is_empty());
     }
 
+    #[test]
+    fn test_execution_state_lock_holder() {
+        let mut state = ParallelExecutionState::default();
+        state.acquire_locks("US-001", &["src/a.rs".to_string()]);
+
+        let files = ["src/b.rs".to_string(), "src/a.rs".to_string()];
+        assert_eq!(
+            state.lock_holder("US-002", &files, &[]),
+            Some((PathBuf::from("src/a.rs"), "US-001".to_string()))
+        );
+        // A story's own locks do not block it
+        assert_eq!(state.lock_holder("US-001", &files, &[]), None);
+    }
+
+    #[test]
+    fn test_execution_state_glob_locks() {
+        let mut state = ParallelExecutionState::default();
+        assert!(state.acquire_locks("US-001", &["src/api/*.rs".to_string()]));
+
+        // A concrete path under a locked pattern is blocked, and vice versa
+        assert!(!state.acquire_locks("US-002", &["src/api/users.rs".to_string()]));
+        assert_eq!(
+            state.lock_holder("US-002", &["src/api/users.rs".to_string()], &[]),
+            Some((PathBuf::from("src/api/users.rs"), "US-001".to_string()))
+        );
+        assert!(state.acquire_locks("US-002", &["src/db/users.rs".to_string()]));
+        assert!(!state.acquire_read_write_locks("US-003", &[], &["src/db/*.rs".to_string()]));
+    }
+
+    #[test]
+    fn test_execution_state_read_locks_are_shared() {
+        let mut state = ParallelExecutionState::default();
+        let config = ["Cargo.toml".to_string()];
+
+        // Readers share the file
+        assert!(state.acquire_read_write_locks("US-001", &[], &config));
+        assert!(state.acquire_read_write_locks("US-002", &["src/a.rs".to_string()], &config));
+
+        // A writer waits for every reader
+        assert!(!state.acquire_locks("US-003", &config));
+        assert_eq!(
+            state.lock_holder("US-003", &config, &[]),
+            Some((PathBuf::from("Cargo.toml"), "US-001".to_string()))
+        );
+        state.release_locks("US-001");
+        assert!(!state.acquire_locks("US-003", &config));
+        state.release_locks("US-002");
+        assert!(state.read_locks.is_empty());
+        assert!(state.acquire_locks("US-003", &config));
+
+        // Readers wait for the writer
+        assert!(!state.acquire_read_write_locks("US-004", &[], &config));
+    }
+
     #[test]
     fn test_execution_state_track_in_flight() {
         let mut state = ParallelExecutionState::default();
@@ -1977,6 +4944,10 @@ mod tests {
                 priority: 1,
                 passes: false,
                 target_files: vec!["src/a.rs".to_string()],
+                read_files: Vec::new(),
+                heavy: false,
+                agent: None,
+                model: None,
                 depends_on: vec![],
             },
             StoryNode {
@@ -1984,6 +4955,10 @@ mod tests {
                 priority: 2,
                 passes: false,
                 target_files: vec!["src/b.rs".to_string()],
+                read_files: Vec::new(),
+                heavy: false,
+                agent: None,
+                model: None,
                 depends_on: vec![],
             },
         ];
@@ -2000,6 +4975,10 @@ mod tests {
                 priority: 1, // Higher priority (lower number)
                 passes: false,
                 target_files: vec!["src/shared.rs".to_string()],
+                read_files: Vec::new(),
+                heavy: false,
+                agent: None,
+                model: None,
                 depends_on: vec![],
             },
             StoryNode {
@@ -2007,6 +4986,10 @@ mod tests {
                 priority: 2, // Lower priority (higher number)
                 passes: false,
                 target_files: vec!["src/shared.rs".to_string()],
+                read_files: Vec::new(),
+                heavy: false,
+                agent: None,
+                model: None,
                 depends_on: vec![],
             },
         ];
@@ -2017,6 +5000,64 @@ mod tests {
         assert_eq!(conflicts[0], ("US-002".to_string(), "US-001".to_string()));
     }
 
+    #[test]
+    fn test_detect_preexecution_conflicts_read_write() {
+        let story = |id: &str, priority, writes: &[&str], reads: &[&str]| StoryNode {
+            id: id.to_string(),
+            priority,
+            passes: false,
+            depends_on: vec![],
+            target_files: writes.iter().map(|f| f.to_string()).collect(),
+            read_files: reads.iter().map(|f| f.to_string()).collect(),
+            heavy: false,
+            agent: None,
+            model: None,
+        };
+
+        // Readers of the same file do not conflict
+        let readers = vec![
+            story("US-001", 1, &["src/a.rs"], &["Cargo.toml"]),
+            story("US-002", 2, &["src/b.rs"], &["Cargo.toml"]),
+        ];
+        assert!(detect_preexecution_conflicts(&readers).is_empty());
+
+        // A writer conflicts with a reader
+        let mixed = vec![
+            story("US-001", 1, &[], &["Cargo.toml"]),
+            story("US-002", 2, &["Cargo.toml"], &[]),
+        ];
+        assert_eq!(
+            detect_preexecution_conflicts(&mixed),
+            vec![("US-002".to_string(), "US-001".to_string())]
+        );
+        assert_eq!(mixed[1].conflicting_files(&mixed[0]), vec!["Cargo.toml"]);
+    }
+
+    #[test]
+    fn test_detect_preexecution_conflicts_glob_patterns() {
+        let story = |id: &str, priority, file: &str| StoryNode {
+            id: id.to_string(),
+            priority,
+            passes: false,
+            depends_on: vec![],
+            target_files: vec![file.to_string()],
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
+        };
+
+        let stories = vec![
+            story("US-001", 1, "src/api/*.rs"),
+            story("US-002", 2, "src/api/users.rs"),
+            story("US-003", 3, "src/db/users.rs"),
+        ];
+        assert_eq!(
+            detect_preexecution_conflicts(&stories),
+            vec![("US-002".to_string(), "US-001".to_string())]
+        );
+    }
+
     #[test]
     fn test_filter_conflicting_stories() {
         let stories = vec![
@@ -2025,6 +5066,10 @@ mod tests {
                 priority: 1,
                 passes: false,
                 target_files: vec!["src/shared.rs".to_string()],
+                read_files: Vec::new(),
+                heavy: false,
+                agent: None,
+                model: None,
                 depends_on: vec![],
             },
             StoryNode {
@@ -2032,6 +5077,10 @@ mod tests {
                 priority: 2,
                 passes: false,
                 target_files: vec!["src/shared.rs".to_string()],
+                read_files: Vec::new(),
+                heavy: false,
+                agent: None,
+                model: None,
                 depends_on: vec![],
             },
             StoryNode {
@@ -2039,6 +5088,10 @@ mod tests {
                 priority: 3,
                 passes: false,
                 target_files: vec!["src/other.rs".to_string()],
+                read_files: Vec::new(),
+                heavy: false,
+                agent: None,
+                model: None,
                 depends_on: vec![],
             },
         ];
@@ -2054,4 +5107,457 @@ mod tests {
         // One conflict detected
         assert_eq!(conflicts.len(), 1);
     }
+
+    // ============================================================================
+    // Story Isolation Tests
+    // ============================================================================
+
+    const UNMARKED_PRD: &str = r#"{"userStories": [{"id": "US-001", "passes": false}]}"#;
+
+    /// Outcome of a story that passed its quality gates.
+    fn passed_execution() -> Result<ExecutionResult, ExecutorError> {
+        Ok(ExecutionResult {
+            success: true,
+            commit_hash: None,
+            error: None,
+            iterations_used: 1,
+            gate_results: Vec::new(),
+            files_changed: vec!["lib.rs".to_string()],
+            futility_verdict: None,
+            iteration_context: None,
+            needs_guidance: false,
+            tokens_used: None,
+            estimated_cost_cents: None,
+            budget_exceeded: false,
+        })
+    }
+
+    fn prd_story(id: &str, target_files: &[&str]) -> crate::mcp::tools::load_prd::PrdUserStory {
+        crate::mcp::tools::load_prd::PrdUserStory {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: String::new(),
+            acceptance_criteria: vec![],
+            priority: 1,
+            passes: false,
+            depends_on: vec![],
+            target_files: target_files.iter().map(|f| f.to_string()).collect(),
+            read_files: Vec::new(),
+            heavy: false,
+            agent: None,
+            model: None,
+            epic: None,
+            prerequisites: Vec::new(),
+            estimate: None,
+        }
+    }
+
+    #[test]
+    fn test_isolation_mode_from_label() {
+        assert_eq!(
+            IsolationMode::from_label("worktree"),
+            IsolationMode::Worktree
+        );
+        assert_eq!(IsolationMode::from_label("branch"), IsolationMode::Branch);
+        assert_eq!(IsolationMode::from_label("shared"), IsolationMode::Shared);
+        assert_eq!(IsolationMode::from_label("unknown"), IsolationMode::Shared);
+    }
+
+    #[test]
+    fn test_affected_stories_include_unmerged_branches() {
+        let runner = ParallelRunner::new(
+            ParallelRunnerConfig::default(),
+            RunnerConfig {
+                no_checkpoint: true,
+                ..Default::default()
+            },
+        );
+        let graph = DependencyGraph::from_stories(&[
+            prd_story("US-001", &["src/a.rs"]),
+            prd_story("US-002", &["src/b.rs"]),
+            prd_story("US-003", &["src/c.rs"]),
+        ]);
+        let batch = vec![
+            "US-001".to_string(),
+            "US-002".to_string(),
+            "US-003".to_string(),
+        ];
+        let merge_conflict = ReconciliationIssue::MergeConflict {
+            story_id: "US-002".to_string(),
+            branch: "ralph/US-002".to_string(),
+            affected_files: vec!["src/b.rs".to_string()],
+        };
+
+        // Only the unmerged story is retried
+        let affected =
+            runner.get_affected_stories(std::slice::from_ref(&merge_conflict), &batch, &graph);
+        assert_eq!(affected, vec!["US-002".to_string()]);
+
+        // Combined with a file-based issue, each story appears once
+        let issues = vec![
+            merge_conflict,
+            ReconciliationIssue::TypeMismatch {
+                file: "src/b.rs".to_string(),
+                error: "mismatched types".to_string(),
+            },
+            ReconciliationIssue::GitConflict {
+                affected_files: vec!["src/c.rs".to_string()],
+            },
+        ];
+        let affected = runner.get_affected_stories(&issues, &batch, &graph);
+        assert_eq!(affected, vec!["US-002".to_string(), "US-003".to_string()]);
+
+        // A duplicate definition implicates the stories of every definition
+        let duplicate = ReconciliationIssue::DuplicateDefinition {
+            symbol: "parse".to_string(),
+            locations: vec!["src/a.rs:3".to_string(), "src/c.rs:10".to_string()],
+        };
+        let affected = runner.get_affected_stories(&[duplicate], &batch, &graph);
+        assert_eq!(affected, vec!["US-001".to_string(), "US-003".to_string()]);
+    }
+
+    #[test]
+    fn test_affected_stories_from_committed_files() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let root = repo.path();
+        git(root, &["init", "-q"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        // US-001 declared lib.rs but only touched a.rs; US-002 declared
+        // other.rs but changed lib.rs
+        for (story, file) in [("US-001", "a.rs"), ("US-002", "lib.rs")] {
+            std::fs::write(root.join(file), "fn f() {}\n").unwrap();
+            git(root, &["add", "-A"]).unwrap();
+            let message = format!("feat: {} - Story", story);
+            git(root, &["commit", "-q", "-m", &message]).unwrap();
+        }
+        let runner = ParallelRunner::new(
+            ParallelRunnerConfig::default(),
+            RunnerConfig {
+                working_dir: root.to_path_buf(),
+                no_checkpoint: true,
+                ..Default::default()
+            },
+        );
+        let graph = DependencyGraph::from_stories(&[
+            prd_story("US-001", &["lib.rs"]),
+            prd_story("US-002", &["other.rs"]),
+            prd_story("US-003", &["lib.rs"]),
+        ]);
+        let batch = vec![
+            "US-001".to_string(),
+            "US-002".to_string(),
+            "US-003".to_string(),
+        ];
+        let issue = ReconciliationIssue::CompileError {
+            file: "lib.rs".to_string(),
+            line: Some(1),
+            code: None,
+            message: "cannot find value".to_string(),
+        };
+
+        // US-003 has no commits, so its declared target files count
+        let affected = runner.get_affected_stories(&[issue], &batch, &graph);
+        assert_eq!(affected, vec!["US-002".to_string(), "US-003".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_resolution_completes_conflicting_story() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let root = repo.path();
+        git(root, &["init", "-q", "-b", "main"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(root, &["add", "-A"]).unwrap();
+        git(root, &["commit", "-q", "-m", "init"]).unwrap();
+
+        // US-001 conflicts with US-002, which merged first but ranks lower
+        let manager = WorktreeManager::new(root).unwrap();
+        let worktree = manager.create("US-001").unwrap();
+        std::fs::write(worktree.path.join("lib.rs"), "fn us_001() {}\n").unwrap();
+        git(&worktree.path, &["commit", "-q", "-am", "feat: US-001"]).unwrap();
+        manager.remove_checkout(&worktree).unwrap();
+        std::fs::write(root.join("lib.rs"), "fn us_002() {}\n").unwrap();
+        git(root, &["commit", "-q", "-am", "Merge story US-002"]).unwrap();
+
+        let runner = ParallelRunner::new(
+            ParallelRunnerConfig {
+                merge_resolution: vec![MergeResolution::Rerere, MergeResolution::Priority],
+                ..Default::default()
+            },
+            RunnerConfig {
+                working_dir: root.to_path_buf(),
+                no_checkpoint: true,
+                ..Default::default()
+            },
+        );
+        let mut second = prd_story("US-002", &["lib.rs"]);
+        second.priority = 2;
+        let graph = DependencyGraph::from_stories(&[prd_story("US-001", &["lib.rs"]), second]);
+        let agents = AgentRoster::load(root, "mock", &[], false).await.unwrap();
+        {
+            let mut state = runner.execution_state.write().await;
+            state.completed.insert("US-002".to_string());
+            state
+                .failed
+                .insert("US-001".to_string(), "merge conflict".to_string());
+        }
+        runner.execution_state.write().await.merge_conflicts =
+            vec![ReconciliationIssue::MergeConflict {
+                story_id: "US-001".to_string(),
+                branch: worktree.branch.clone(),
+                affected_files: vec!["lib.rs".to_string()],
+            }];
+
+        let run_metrics = RunMetricsCollector::new("run-test", 2);
+        let error = runner
+            .run_reconciliation(
+                &["US-001".to_string(), "US-002".to_string()],
+                &graph,
+                &agents,
+                &mut 0,
+                &None,
+                &run_metrics,
+                &None,
+                &HashMap::new(),
+            )
+            .await;
+        assert_eq!(error, None);
+        let reports = run_metrics.finish().reconciliations;
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].merge_resolutions["US-001"], "priority");
+        assert_eq!(reports[0].action, ReconciliationAction::None);
+        assert!(reports[0].is_clean());
+        assert_eq!(
+            std::fs::read_to_string(root.join("lib.rs")).unwrap(),
+            "fn us_001() {}\n"
+        );
+        let state = runner.execution_state.read().await;
+        assert!(state.completed.contains("US-001"));
+        assert!(!state.failed.contains_key("US-001"));
+        assert!(git(root, &["branch", "--list", "ralph/US-001"])
+            .unwrap()
+            .trim()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reconciliation_agent_fixes_integrated_tree() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let root = repo.path();
+        git(root, &["init", "-q"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        let write = |path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[package]\nname = \"shop\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        );
+        write(".gitignore", "target\n");
+        write(
+            "prd.json",
+            r#"{"project": "shop", "branchName": "main", "userStories": [
+                {"id": "US-001", "title": "Cart", "priority": 1, "passes": true}
+            ]}"#,
+        );
+        write(
+            ".ralph/mock-agent.toml",
+            "[[responses]]\nstory = \"reconciliation\"\nrole = \"reconcile\"\n\
+             files = { \"src/lib.rs\" = \"pub fn cart() {}\\n\" }\n",
+        );
+        // Two stories both added `cart`
+        write("src/lib.rs", "pub fn cart() {}\npub fn cart() {}\n");
+        git(root, &["add", "-A"]).unwrap();
+        git(root, &["commit", "-q", "-m", "feat: US-001 - Cart"]).unwrap();
+
+        let runner = ParallelRunner::new(
+            ParallelRunnerConfig {
+                reconcile_agent: true,
+                ..Default::default()
+            },
+            RunnerConfig {
+                working_dir: root.to_path_buf(),
+                prd_path: root.join("prd.json"),
+                no_checkpoint: true,
+                ..Default::default()
+            },
+        );
+        let graph = DependencyGraph::from_stories(&[prd_story("US-001", &["src/lib.rs"])]);
+        let agents = AgentRoster::load(root, "mock", &[], false).await.unwrap();
+        let run_metrics = RunMetricsCollector::new("run-test", 1);
+        let error = runner
+            .run_reconciliation(
+                &["US-001".to_string()],
+                &graph,
+                &agents,
+                &mut 0,
+                &None,
+                &run_metrics,
+                &None,
+                &HashMap::new(),
+            )
+            .await;
+
+        assert_eq!(error, None);
+        let metrics = run_metrics.finish();
+        let report = &metrics.reconciliations[0];
+        assert_eq!(report.action, ReconciliationAction::AgentFix);
+        assert_eq!(report.agent_fixed, Some(true));
+        assert!(report.retries.is_empty());
+        assert_eq!(metrics.invocations[0].role, "reconcile");
+        assert_eq!(
+            git(root, &["log", "-1", "--format=%s"]).unwrap().trim(),
+            "fix: reconcile US-001"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "pub fn cart() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_story_finished_in_time_is_merged_despite_git_mutex_wait() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let root = repo.path();
+        git(root, &["init", "-q", "-b", "main"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(root, &["add", "-A"]).unwrap();
+        git(root, &["commit", "-q", "-m", "init"]).unwrap();
+        let manager = WorktreeManager::new(root).unwrap();
+        let worktree = manager.create("US-001").unwrap();
+        std::fs::write(worktree.path.join("lib.rs"), "fn us_001() {}\n").unwrap();
+        git(&worktree.path, &["commit", "-q", "-am", "feat: US-001"]).unwrap();
+        let worktree_path = worktree.path.clone();
+
+        // The story finishes at once, but another story holds the git mutex
+        // past its deadline
+        let git_mutex = Arc::new(Mutex::new(()));
+        let guard = git_mutex.clone().lock_owned().await;
+        let execution_state = Arc::new(RwLock::new(ParallelExecutionState::default()));
+        let prd = tempfile::TempDir::new().unwrap();
+        let prd_path = prd.path().join("prd.json");
+        std::fs::write(&prd_path, UNMARKED_PRD).unwrap();
+        let (timed_result, _) = tokio::join!(
+            run_story_in_time(
+                Duration::from_millis(20),
+                async { passed_execution() },
+                Some((manager, worktree)),
+                &git_mutex,
+                &IsolationMode::Worktree,
+                &execution_state,
+                Some(&prd_path),
+            ),
+            async move {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                drop(guard);
+            }
+        );
+
+        let result = timed_result
+            .expect("merging back is not time-boxed")
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            std::fs::read_to_string(root.join("lib.rs")).unwrap(),
+            "fn us_001() {}\n"
+        );
+        assert!(!worktree_path.exists());
+        assert!(std::fs::read_to_string(&prd_path)
+            .unwrap()
+            .contains(r#""passes": true"#));
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_merge_leaves_story_unmarked() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let root = repo.path();
+        git(root, &["init", "-q", "-b", "main"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(root, &["add", "-A"]).unwrap();
+        git(root, &["commit", "-q", "-m", "init"]).unwrap();
+        let manager = WorktreeManager::new(root).unwrap();
+        let worktree = manager.create("US-001").unwrap();
+        std::fs::write(worktree.path.join("lib.rs"), "fn us_001() {}\n").unwrap();
+        git(&worktree.path, &["commit", "-q", "-am", "feat: US-001"]).unwrap();
+        std::fs::write(root.join("lib.rs"), "fn us_002() {}\n").unwrap();
+        git(root, &["commit", "-q", "-am", "Merge story US-002"]).unwrap();
+        let prd = tempfile::TempDir::new().unwrap();
+        let prd_path = prd.path().join("prd.json");
+        std::fs::write(&prd_path, UNMARKED_PRD).unwrap();
+
+        let result = run_story_in_time(
+            Duration::from_secs(60),
+            async { passed_execution() },
+            Some((manager, worktree)),
+            &Arc::new(Mutex::new(())),
+            &IsolationMode::Worktree,
+            &Arc::new(RwLock::new(ParallelExecutionState::default())),
+            Some(&prd_path),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(std::fs::read_to_string(&prd_path).unwrap(), UNMARKED_PRD);
+    }
+
+    #[tokio::test]
+    async fn test_panicked_story_task_fails_story() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let root = repo.path();
+        git(root, &["init", "-q"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        std::fs::write(
+            root.join("prd.json"),
+            r#"{"project": "shop", "branchName": "main", "userStories": [
+                {"id": "US-001", "title": "Cart", "priority": 1, "passes": false,
+                 "targetFiles": ["src/cart.rs"]}
+            ]}"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join(".ralph")).unwrap();
+        std::fs::write(
+            root.join(".ralph/mock-agent.toml"),
+            "[[responses]]\npanic = \"executor blew up\"\n",
+        )
+        .unwrap();
+        git(root, &["add", "-A"]).unwrap();
+        git(root, &["commit", "-q", "-m", "init"]).unwrap();
+
+        let runner = ParallelRunner::new(
+            ParallelRunnerConfig {
+                isolation: IsolationMode::Shared,
+                ..Default::default()
+            },
+            RunnerConfig {
+                working_dir: root.to_path_buf(),
+                prd_path: root.join("prd.json"),
+                agent_command: Some("mock".to_string()),
+                max_iterations_per_story: 1,
+                no_checkpoint: true,
+                ..Default::default()
+            },
+        );
+        let result = runner.run().await;
+
+        assert!(!result.all_passed);
+        let state = runner.execution_state.read().await;
+        assert!(state.in_flight.is_empty());
+        assert!(state.locked_files.is_empty());
+        assert_eq!(
+            state.failed.get("US-001").map(String::as_str),
+            Some("Story task panicked: executor blew up")
+        );
+    }
 }
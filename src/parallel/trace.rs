@@ -0,0 +1,245 @@
+//! Scheduler decision trace
+//!
+//! Records why each story was dispatched, deferred, rejected or blocked in
+//! every scheduling cycle as JSON lines in `.ralph/runs/<run_id>.scheduler.jsonl`,
+//! so a story that never ran can be explained after the fact.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A scheduling decision about a story (or the whole queue).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum SchedulerDecision {
+    /// The story started running.
+    Dispatched {
+        /// Stories in flight including this one
+        in_flight: usize,
+        /// Time the story spent queued before it was dispatched, in milliseconds
+        #[serde(default)]
+        waited_ms: u64,
+    },
+    /// The story has dependencies that have not completed.
+    WaitingOnDependencies {
+        /// Dependencies still outstanding
+        pending: Vec<String>,
+    },
+    /// A higher-priority ready story conflicts with this one.
+    Deferred {
+        /// The story that runs first
+        blocking: String,
+        /// Files the two stories conflict on
+        files: Vec<String>,
+    },
+    /// The pending queue is full and the story was left unqueued.
+    QueueFull {
+        /// Queue capacity
+        capacity: usize,
+    },
+    /// The story was evicted from the full queue for a higher-priority story and
+    /// becomes ready again next cycle.
+    Evicted {
+        /// The higher-priority story that took its place
+        by: String,
+    },
+    /// The story was rejected or dropped by the queue backpressure policy.
+    Rejected {
+        /// Why the story was rejected
+        reason: String,
+    },
+    /// A heavy story was held back because free memory is low.
+    HeldForMemory {
+        /// Free memory in MB, if known
+        available_mb: Option<u64>,
+        /// Required free memory in MB
+        min_headroom_mb: Option<u64>,
+    },
+    /// The story's files are locked by another story; it was requeued.
+    LockBlocked {
+        /// The first locked file
+        file: String,
+        /// The story holding the lock
+        holder: String,
+        /// Escalation applied, if any ("warn", "reprioritize")
+        escalation: Option<String>,
+    },
+    /// The story gave up waiting for file locks and failed.
+    LockWaitFailed {
+        /// Diagnostic naming the lock holder
+        reason: String,
+    },
+    /// The story cannot run because a story it depends on failed or was skipped.
+    BlockedByFailure {
+        /// The failed or skipped dependency
+        blocked_by: String,
+    },
+    /// The story cannot run because an external prerequisite is not met.
+    PrerequisiteUnmet {
+        /// Why the prerequisite is not met
+        reason: String,
+    },
+    /// Dispatch is stopped; queued stories stay queued.
+    DispatchPaused {
+        /// Why dispatch is stopped ("paused", "reconciling")
+        reason: String,
+        /// Number of queued stories
+        queued: usize,
+    },
+}
+
+/// A single line of the trace.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// When the decision was made
+    pub timestamp: DateTime<Utc>,
+    /// Scheduling cycle the decision was made in
+    pub cycle: u64,
+    /// Story the decision is about, if it concerns a single story
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub story_id: Option<String>,
+    /// The decision
+    #[serde(flatten)]
+    pub decision: SchedulerDecision,
+}
+
+/// Appends scheduler decisions to a run's trace file.
+#[derive(Debug, Default)]
+pub struct SchedulerTrace {
+    /// Trace file; `None` when tracing is disabled or failed
+    file: Option<File>,
+    /// Path of the trace file
+    path: Option<PathBuf>,
+    /// Current scheduling cycle
+    cycle: u64,
+}
+
+impl SchedulerTrace {
+    /// Creates the trace file for `run_id` under `base_dir/.ralph/runs`.
+    pub fn create(base_dir: &Path, run_id: &str) -> io::Result<Self> {
+        let runs_dir = base_dir.join(".ralph").join("runs");
+        fs::create_dir_all(&runs_dir)?;
+        let path = runs_dir.join(format!("{}.scheduler.jsonl", run_id));
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        Ok(Self {
+            file: Some(file),
+            path: Some(path),
+            cycle: 0,
+        })
+    }
+
+    /// A trace that records nothing.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Path of the trace file, if tracing is enabled.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Starts the next scheduling cycle.
+    pub fn next_cycle(&mut self) {
+        self.cycle += 1;
+    }
+
+    /// Records a decision in the current cycle.
+    ///
+    /// A failed write disables the trace after a warning rather than
+    /// interrupting the run.
+    pub fn record(&mut self, story_id: Option<&str>, decision: SchedulerDecision) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let entry = TraceEntry {
+            timestamp: Utc::now(),
+            cycle: self.cycle,
+            story_id: story_id.map(str::to_string),
+            decision,
+        };
+        let result = serde_json::to_string(&entry)
+            .map_err(io::Error::other)
+            .and_then(|json| writeln!(file, "{}", json));
+        if let Err(err) = result {
+            eprintln!("Warning: Failed to write scheduler trace: {}", err);
+            self.file = None;
+        }
+    }
+}
+
+/// Loads the entries of a scheduler trace file, skipping lines that cannot be parsed.
+pub fn load_trace(path: &Path) -> io::Result<Vec<TraceEntry>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_records_decisions_per_cycle() {
+        let dir = std::env::temp_dir().join(format!("ralph-trace-{}", std::process::id()));
+        let mut trace = SchedulerTrace::create(&dir, "run-1").unwrap();
+        let path = trace.path().unwrap().to_path_buf();
+        assert!(path.ends_with(".ralph/runs/run-1.scheduler.jsonl"));
+
+        trace.next_cycle();
+        trace.record(
+            Some("US-001"),
+            SchedulerDecision::Dispatched {
+                in_flight: 1,
+                waited_ms: 0,
+            },
+        );
+        trace.record(
+            Some("US-002"),
+            SchedulerDecision::LockBlocked {
+                file: "src/lib.rs".to_string(),
+                holder: "US-001".to_string(),
+                escalation: None,
+            },
+        );
+        trace.next_cycle();
+        trace.record(
+            None,
+            SchedulerDecision::DispatchPaused {
+                reason: "paused".to_string(),
+                queued: 1,
+            },
+        );
+
+        let entries = load_trace(&path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].cycle, 1);
+        assert_eq!(entries[1].story_id.as_deref(), Some("US-002"));
+        assert_eq!(entries[2].cycle, 2);
+        assert_eq!(entries[2].story_id, None);
+
+        let line = fs::read_to_string(&path).unwrap();
+        assert!(line.contains(r#""decision":"lock_blocked""#));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_disabled_trace_records_nothing() {
+        let mut trace = SchedulerTrace::disabled();
+        trace.record(
+            Some("US-001"),
+            SchedulerDecision::Dispatched {
+                in_flight: 1,
+                waited_ms: 0,
+            },
+        );
+        assert!(trace.path().is_none());
+    }
+}
@@ -0,0 +1,520 @@
+//! Git worktree isolation for parallel story execution
+//!
+//! In worktree mode each story runs in its own `git worktree` on a dedicated
+//! branch, so agents and quality gates never see another story's in-progress
+//! edits. Successful stories are merged back into the main working directory.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use thiserror::Error;
+
+/// Directory (inside the git common dir) that holds story worktrees.
+const WORKTREES_DIR: &str = "ralph-worktrees";
+
+/// Prefix for the per-story branches created for worktrees.
+const BRANCH_PREFIX: &str = "ralph/";
+
+/// `git` options for resolution merges: git rerere records how conflicts are
+/// resolved and resolves them the same way when they recur.
+const RERERE: [&str; 4] = ["-c", "rerere.enabled=true", "-c", "rerere.autoUpdate=true"];
+
+/// Errors that can occur while managing story worktrees.
+#[derive(Debug, Error, PartialEq)]
+pub enum WorktreeError {
+    /// A git command failed.
+    #[error("git {command} failed: {message}")]
+    Git {
+        /// The git subcommand that failed (e.g., "worktree add")
+        command: String,
+        /// Error output from git
+        message: String,
+    },
+    /// Merging a story branch back produced conflicts; the merge was aborted.
+    #[error("Merge of {branch} conflicted in: {}", .files.join(", "))]
+    MergeConflict {
+        /// The story branch that failed to merge
+        branch: String,
+        /// Files with conflicts
+        files: Vec<String>,
+    },
+}
+
+/// A worktree checked out for a single story.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoryWorktree {
+    /// ID of the story using this worktree
+    pub story_id: String,
+    /// Path of the worktree checkout
+    pub path: PathBuf,
+    /// Branch checked out in the worktree
+    pub branch: String,
+}
+
+/// Creates, merges back and removes per-story git worktrees.
+#[derive(Clone, Debug)]
+pub struct WorktreeManager {
+    /// Main working directory of the repository
+    repo_root: PathBuf,
+    /// Directory where story worktrees are created
+    worktrees_dir: PathBuf,
+    /// Run the worktrees belong to, keeping concurrent runs on the same
+    /// repository out of each other's worktrees and branches
+    run_id: Option<String>,
+}
+
+impl WorktreeManager {
+    /// Creates a manager for the repository at `repo_root`.
+    ///
+    /// Worktrees live under the repository's git directory so they are never
+    /// picked up as untracked files by `git add -A` in the main checkout.
+    pub fn new(repo_root: impl Into<PathBuf>) -> Result<Self, WorktreeError> {
+        let repo_root = repo_root.into();
+        let git_dir = git(&repo_root, &["rev-parse", "--git-common-dir"])?;
+        let git_dir = PathBuf::from(git_dir.trim());
+        let git_dir = if git_dir.is_absolute() {
+            git_dir
+        } else {
+            repo_root.join(git_dir)
+        };
+        Ok(Self {
+            worktrees_dir: git_dir.join(WORKTREES_DIR),
+            repo_root,
+            run_id: None,
+        })
+    }
+
+    /// Scope worktrees and branches to `run_id`
+    /// (`ralph-worktrees/<run_id>/<story>` on `ralph/<run_id>/<story>`).
+    pub fn with_run_id(mut self, run_id: impl Into<String>) -> Self {
+        self.run_id = Some(sanitize_name(&run_id.into()));
+        self
+    }
+
+    /// Creates a worktree for `story_id` on a fresh branch at the current `HEAD`.
+    ///
+    /// Any leftover worktree or branch from an interrupted run is replaced.
+    pub fn create(&self, story_id: &str) -> Result<StoryWorktree, WorktreeError> {
+        let name = match self.run_id {
+            Some(ref run_id) => format!("{}/{}", run_id, sanitize_name(story_id)),
+            None => sanitize_name(story_id),
+        };
+        let path = self.worktrees_dir.join(&name);
+        let branch = format!("{}{}", BRANCH_PREFIX, name);
+
+        if path.exists() {
+            let _ = git(
+                &self.repo_root,
+                &["worktree", "remove", "--force", &path.to_string_lossy()],
+            );
+        }
+        git(&self.repo_root, &["worktree", "prune"])?;
+        git(
+            &self.repo_root,
+            &[
+                "worktree",
+                "add",
+                "-B",
+                &branch,
+                &path.to_string_lossy(),
+                "HEAD",
+            ],
+        )?;
+
+        Ok(StoryWorktree {
+            story_id: story_id.to_string(),
+            path,
+            branch,
+        })
+    }
+
+    /// Merges the story branch into the main working directory's current branch.
+    ///
+    /// Fast-forwards when possible. On conflict the merge is aborted, leaving the
+    /// main checkout untouched, and the conflicting files are returned.
+    pub fn merge_back(&self, worktree: &StoryWorktree) -> Result<(), WorktreeError> {
+        let message = format!("Merge story {}", worktree.story_id);
+        match git(
+            &self.repo_root,
+            &["merge", "--no-edit", "-m", &message, &worktree.branch],
+        ) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                let files = self.unmerged_files();
+                if files.is_empty() {
+                    return Err(err);
+                }
+                let _ = git(&self.repo_root, &["merge", "--abort"]);
+                Err(WorktreeError::MergeConflict {
+                    branch: worktree.branch.clone(),
+                    files,
+                })
+            }
+        }
+    }
+
+    /// Starts merging a kept story `branch` into the main working directory,
+    /// passing `options` (e.g. `-Xtheirs`) to `git merge`.
+    ///
+    /// Conflicts git rerere has seen resolved before are resolved the same
+    /// way. Returns the files still conflicted: when there are none the merge
+    /// is committed, otherwise it is left in progress for
+    /// [`WorktreeManager::conclude_merge`] or [`WorktreeManager::abort_merge`].
+    pub fn start_merge(
+        &self,
+        story_id: &str,
+        branch: &str,
+        options: &[&str],
+    ) -> Result<Vec<String>, WorktreeError> {
+        let message = format!("Merge story {}", story_id);
+        let mut args = RERERE.to_vec();
+        args.extend(["merge", "--no-edit", "-m", &message]);
+        args.extend(options);
+        args.push(branch);
+        if let Err(err) = git(&self.repo_root, &args) {
+            if !self.merge_in_progress() {
+                return Err(err);
+            }
+            let files = self.unmerged_files();
+            if !files.is_empty() {
+                return Ok(files);
+            }
+            self.commit_merge()?;
+        }
+        Ok(Vec::new())
+    }
+
+    /// Commits a merge left in progress by [`WorktreeManager::start_merge`]
+    /// once its conflicted `files` have been edited.
+    ///
+    /// Returns the files that still hold conflict markers; the merge is only
+    /// committed when there are none.
+    pub fn conclude_merge(&self, files: &[String]) -> Result<Vec<String>, WorktreeError> {
+        let unresolved: Vec<String> = files
+            .iter()
+            .filter(|file| has_conflict_markers(&self.repo_root.join(file)))
+            .cloned()
+            .collect();
+        if !unresolved.is_empty() {
+            return Ok(unresolved);
+        }
+        git(&self.repo_root, &["add", "-A"])?;
+        self.commit_merge()?;
+        Ok(Vec::new())
+    }
+
+    /// Aborts a merge left in progress by [`WorktreeManager::start_merge`].
+    pub fn abort_merge(&self) -> Result<(), WorktreeError> {
+        git(&self.repo_root, &["merge", "--abort"])?;
+        Ok(())
+    }
+
+    /// Deletes a story branch kept after a failed merge.
+    pub fn delete_branch(&self, branch: &str) -> Result<(), WorktreeError> {
+        git(&self.repo_root, &["branch", "-D", branch])?;
+        Ok(())
+    }
+
+    fn merge_in_progress(&self) -> bool {
+        git(
+            &self.repo_root,
+            &["rev-parse", "-q", "--verify", "MERGE_HEAD"],
+        )
+        .is_ok()
+    }
+
+    fn unmerged_files(&self) -> Vec<String> {
+        git(&self.repo_root, &["diff", "--name-only", "--diff-filter=U"])
+            .map(|out| out.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    fn commit_merge(&self) -> Result<(), WorktreeError> {
+        let mut args = RERERE.to_vec();
+        args.extend(["commit", "--no-edit"]);
+        git(&self.repo_root, &args)?;
+        Ok(())
+    }
+
+    /// Removes the story worktree and deletes its branch.
+    pub fn remove(&self, worktree: &StoryWorktree) -> Result<(), WorktreeError> {
+        self.remove_checkout(worktree)?;
+        git(&self.repo_root, &["branch", "-D", &worktree.branch])?;
+        Ok(())
+    }
+
+    /// Removes the story worktree but keeps its branch (e.g., after a failed merge).
+    pub fn remove_checkout(&self, worktree: &StoryWorktree) -> Result<(), WorktreeError> {
+        git(
+            &self.repo_root,
+            &[
+                "worktree",
+                "remove",
+                "--force",
+                &worktree.path.to_string_lossy(),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// Makes a story ID safe for use as a directory and branch name.
+pub(crate) fn sanitize_name(story_id: &str) -> String {
+    story_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Whether the file at `path` still holds git conflict markers.
+fn has_conflict_markers(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| {
+        content
+            .lines()
+            .any(|line| line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> "))
+    })
+}
+
+/// Runs a git command in `dir` and returns its stdout.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Result<String, WorktreeError> {
+    // Name the subcommand, not the `-c` options before it
+    let mut subcommand = args;
+    while let ["-c", _, rest @ ..] = subcommand {
+        subcommand = rest;
+    }
+    let command = subcommand
+        .iter()
+        .take(2)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| WorktreeError::Git {
+            command: command.clone(),
+            message: e.to_string(),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let message = if stderr.trim().is_empty() {
+            stdout.trim().to_string()
+        } else {
+            stderr.trim().to_string()
+        };
+        return Err(WorktreeError::Git { command, message });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn init_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q", "-b", "main"]).unwrap();
+        git(root, &["config", "user.email", "ralph@example.com"]).unwrap();
+        git(root, &["config", "user.name", "Ralph"]).unwrap();
+        fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(root, &["add", "-A"]).unwrap();
+        git(root, &["commit", "-q", "-m", "init"]).unwrap();
+        dir
+    }
+
+    fn commit_all(dir: &Path, message: &str) {
+        git(dir, &["add", "-A"]).unwrap();
+        git(dir, &["commit", "-q", "-m", message]).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("US-001"), "US-001");
+        assert_eq!(sanitize_name("story 1/a"), "story-1-a");
+    }
+
+    #[test]
+    fn test_worktree_lifecycle_merges_story_changes() {
+        let repo = init_repo();
+        let manager = WorktreeManager::new(repo.path()).unwrap();
+
+        let worktree = manager.create("US-001").unwrap();
+        assert!(worktree.path.join("lib.rs").exists());
+        assert_eq!(worktree.branch, "ralph/US-001");
+        // Worktrees are kept out of the main checkout
+        assert!(worktree.path.starts_with(repo.path().join(".git")));
+        assert!(git(repo.path(), &["status", "--porcelain"])
+            .unwrap()
+            .is_empty());
+
+        fs::write(worktree.path.join("new.rs"), "fn b() {}\n").unwrap();
+        commit_all(&worktree.path, "feat: US-001");
+        // Not visible in the main checkout until merged
+        assert!(!repo.path().join("new.rs").exists());
+
+        manager.merge_back(&worktree).unwrap();
+        assert!(repo.path().join("new.rs").exists());
+
+        manager.remove(&worktree).unwrap();
+        assert!(!worktree.path.exists());
+        assert!(git(repo.path(), &["branch", "--list", "ralph/US-001"])
+            .unwrap()
+            .trim()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_create_replaces_stale_worktree() {
+        let repo = init_repo();
+        let manager = WorktreeManager::new(repo.path()).unwrap();
+
+        let first = manager.create("US-002").unwrap();
+        let second = manager.create("US-002").unwrap();
+        assert_eq!(first.path, second.path);
+        assert!(second.path.join("lib.rs").exists());
+    }
+
+    #[test]
+    fn test_runs_get_their_own_worktrees() {
+        let repo = init_repo();
+        let first_run = WorktreeManager::new(repo.path())
+            .unwrap()
+            .with_run_id("run-1");
+        let second_run = WorktreeManager::new(repo.path())
+            .unwrap()
+            .with_run_id("run-2");
+
+        let first = first_run.create("US-004").unwrap();
+        fs::write(first.path.join("new.rs"), "fn b() {}\n").unwrap();
+        commit_all(&first.path, "feat: US-004");
+        let second = second_run.create("US-004").unwrap();
+
+        assert_eq!(first.branch, "ralph/run-1/US-004");
+        assert_eq!(second.branch, "ralph/run-2/US-004");
+        assert_ne!(first.path, second.path);
+        // The second run left the first one's checkout and commits alone
+        assert!(first.path.join("new.rs").exists());
+        first_run.merge_back(&first).unwrap();
+        assert!(repo.path().join("new.rs").exists());
+    }
+
+    #[test]
+    fn test_merge_back_conflict_is_aborted() {
+        let repo = init_repo();
+        let manager = WorktreeManager::new(repo.path()).unwrap();
+
+        let worktree = manager.create("US-003").unwrap();
+        fs::write(worktree.path.join("lib.rs"), "fn story() {}\n").unwrap();
+        commit_all(&worktree.path, "feat: US-003");
+
+        fs::write(repo.path().join("lib.rs"), "fn main_change() {}\n").unwrap();
+        commit_all(repo.path(), "other story");
+
+        let err = manager.merge_back(&worktree).unwrap_err();
+        assert_eq!(
+            err,
+            WorktreeError::MergeConflict {
+                branch: "ralph/US-003".to_string(),
+                files: vec!["lib.rs".to_string()],
+            }
+        );
+        // The main checkout is left clean
+        assert!(git(repo.path(), &["status", "--porcelain"])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            fs::read_to_string(repo.path().join("lib.rs")).unwrap(),
+            "fn main_change() {}\n"
+        );
+
+        // The unmerged branch survives removal of the checkout
+        manager.remove_checkout(&worktree).unwrap();
+        assert!(!worktree.path.exists());
+        assert!(!git(repo.path(), &["branch", "--list", "ralph/US-003"])
+            .unwrap()
+            .trim()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_resolution_merges() {
+        let repo = init_repo();
+        let manager = WorktreeManager::new(repo.path()).unwrap();
+
+        let worktree = manager.create("US-004").unwrap();
+        fs::write(worktree.path.join("lib.rs"), "fn story() {}\n").unwrap();
+        commit_all(&worktree.path, "feat: US-004");
+        manager.remove_checkout(&worktree).unwrap();
+        fs::write(repo.path().join("lib.rs"), "fn main_change() {}\n").unwrap();
+        commit_all(repo.path(), "other story");
+        let lib = || fs::read_to_string(repo.path().join("lib.rs")).unwrap();
+
+        // Favouring the story branch settles the conflict
+        let files = manager
+            .start_merge("US-004", &worktree.branch, &["-Xtheirs"])
+            .unwrap();
+        assert!(files.is_empty());
+        assert_eq!(lib(), "fn story() {}\n");
+        git(repo.path(), &["reset", "-q", "--hard", "HEAD~1"]).unwrap();
+
+        // Edited conflicts are committed only once no markers are left
+        let files = manager
+            .start_merge("US-004", &worktree.branch, &[])
+            .unwrap();
+        assert_eq!(files, vec!["lib.rs"]);
+        assert_eq!(manager.conclude_merge(&files).unwrap(), files);
+        fs::write(
+            repo.path().join("lib.rs"),
+            "fn main_change() {}\nfn story() {}\n",
+        )
+        .unwrap();
+        assert!(manager.conclude_merge(&files).unwrap().is_empty());
+        assert!(git(repo.path(), &["status", "--porcelain"])
+            .unwrap()
+            .is_empty());
+        git(repo.path(), &["reset", "-q", "--hard", "HEAD~1"]).unwrap();
+
+        // The same conflict is then resolved the way it was before
+        let files = manager
+            .start_merge("US-004", &worktree.branch, &[])
+            .unwrap();
+        assert!(files.is_empty());
+        assert_eq!(lib(), "fn main_change() {}\nfn story() {}\n");
+        assert_eq!(
+            git(repo.path(), &["log", "-1", "--format=%s"])
+                .unwrap()
+                .trim(),
+            "Merge story US-004"
+        );
+
+        manager.delete_branch(&worktree.branch).unwrap();
+        assert!(git(repo.path(), &["branch", "--list", "ralph/US-004"])
+            .unwrap()
+            .trim()
+            .is_empty());
+
+        // A conflict nothing resolves is left for the caller to abort
+        let worktree = manager.create("US-005").unwrap();
+        fs::write(worktree.path.join("lib.rs"), "fn other() {}\n").unwrap();
+        commit_all(&worktree.path, "feat: US-005");
+        fs::write(repo.path().join("lib.rs"), "fn newer() {}\n").unwrap();
+        commit_all(repo.path(), "another story");
+        let files = manager
+            .start_merge("US-005", &worktree.branch, &[])
+            .unwrap();
+        assert_eq!(files, vec!["lib.rs"]);
+        manager.abort_merge().unwrap();
+        assert_eq!(lib(), "fn newer() {}\n");
+    }
+}
@@ -9,6 +9,10 @@ use std::time::Duration;
 
 use crate::error::ErrorCategory;
 
+pub mod stop;
+
+pub use stop::{StopFile, STOP_FILE, STOP_POLL_INTERVAL};
+
 /// State of the pause controller.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum PauseState {
@@ -111,6 +115,36 @@ impl PauseController {
         let state = self.state.read().unwrap();
         *state == PauseState::Running
     }
+
+    /// Toggles between running and paused.
+    ///
+    /// Running requests a pause; a requested or executed pause goes back to
+    /// Running. Returns the new state.
+    pub fn toggle(&self) -> PauseState {
+        let mut state = self.state.write().unwrap();
+        *state = match *state {
+            PauseState::Running => PauseState::PauseRequested,
+            PauseState::PauseRequested | PauseState::Paused => PauseState::Running,
+        };
+        *state
+    }
+}
+
+/// Toggles `controller` whenever the process receives `SIGUSR1`.
+///
+/// The returned task runs until aborted.
+#[cfg(unix)]
+pub fn spawn_signal_toggle(
+    controller: PauseController,
+) -> std::io::Result<tokio::task::JoinHandle<()>> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut signals = signal(SignalKind::user_defined1())?;
+    Ok(tokio::spawn(async move {
+        while signals.recv().await.is_some() {
+            controller.toggle();
+        }
+    }))
 }
 
 /// Configuration for retry behavior with exponential backoff.
@@ -357,6 +391,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_toggle_pauses_and_resumes() {
+        let controller = PauseController::new();
+        assert_eq!(controller.toggle(), PauseState::PauseRequested);
+        // Toggling again before the pause takes effect cancels it
+        assert_eq!(controller.toggle(), PauseState::Running);
+
+        controller.toggle();
+        assert!(controller.execute_pause());
+        assert_eq!(controller.toggle(), PauseState::Running);
+    }
+
     #[test]
     fn test_clone_shares_state() {
         let controller1 = PauseController::new();
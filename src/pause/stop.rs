@@ -0,0 +1,104 @@
+//! Emergency stop file.
+//!
+//! A run polls for `.ralph/STOP` while it works. When the file appears, the
+//! run cancels its in-flight agents, writes a checkpoint and exits, so an
+//! unattended run that has gone wrong can be halted from anywhere with
+//! `touch .ralph/STOP` or `ralph stop`. The file's contents, if any, are the
+//! reason recorded with the checkpoint. The run removes the file once it has
+//! stopped, so `ralph run --resume` continues where it left off.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Stop file, relative to the project root.
+pub const STOP_FILE: &str = ".ralph/STOP";
+
+/// How often a run checks for the stop file.
+pub const STOP_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The stop file of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StopFile {
+    path: PathBuf,
+}
+
+impl StopFile {
+    /// The stop file under `project_root`.
+    pub fn new(project_root: impl AsRef<Path>) -> Self {
+        Self {
+            path: project_root.as_ref().join(STOP_FILE),
+        }
+    }
+
+    /// Path of the stop file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether a stop has been requested.
+    pub fn is_requested(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Wait until a stop is requested, checking every [`STOP_POLL_INTERVAL`].
+    pub async fn requested(&self) {
+        loop {
+            tokio::time::sleep(STOP_POLL_INTERVAL).await;
+            if self.is_requested() {
+                return;
+            }
+        }
+    }
+
+    /// The reason written to the stop file, if any.
+    pub fn reason(&self) -> Option<String> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .map(|content| content.trim().to_string())
+            .filter(|reason| !reason.is_empty())
+    }
+
+    /// Request a stop, recording `reason` in the file.
+    pub fn request(&self, reason: Option<&str>) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(
+            &self.path,
+            reason.map(|r| format!("{}\n", r)).unwrap_or_default(),
+        )
+    }
+
+    /// Withdraw a stop request. Returns whether there was one.
+    pub fn clear(&self) -> io::Result<bool> {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stop_file_lifecycle() {
+        let root = TempDir::new().unwrap();
+        let stop = StopFile::new(root.path());
+        assert!(!stop.is_requested());
+        assert!(!stop.clear().unwrap());
+
+        stop.request(None).unwrap();
+        assert!(stop.is_requested());
+        assert_eq!(stop.reason(), None);
+
+        stop.request(Some("runaway costs")).unwrap();
+        assert_eq!(stop.reason().as_deref(), Some("runaway costs"));
+        assert!(stop.clear().unwrap());
+        assert!(!stop.is_requested());
+    }
+}
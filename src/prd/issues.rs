@@ -0,0 +1,325 @@
+//! Import PRD stories from GitHub issues.
+//!
+//! Teams triage in GitHub and execute in ralph: `ralph prd import --label
+//! ralph` lists the labeled issues with the `gh` CLI and turns each into a
+//! story with the ID `GH-<number>`. The issue title becomes the title, a task
+//! list in the body (`- [ ] ...`) becomes the acceptance criteria and the rest
+//! of the body the description. Priority labels (`P0`-`P3`, `critical`,
+//! `high`, `medium`, `low`, optionally prefixed with `priority:`) set the
+//! priority, and "depends on #12" or "blocked by #12" in the body become
+//! dependencies. Importing again updates the stories from their issues but
+//! keeps whether they pass.
+
+use std::collections::HashSet;
+use std::process::Command;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Prefix of the IDs of imported stories.
+pub const ISSUE_STORY_PREFIX: &str = "GH-";
+
+/// Priority of issues without a priority label.
+pub const DEFAULT_ISSUE_PRIORITY: u32 = 3;
+
+/// Errors from importing issues.
+#[derive(Debug, Error)]
+pub enum IssueImportError {
+    /// `gh` could not be run
+    #[error("Failed to run gh (is the GitHub CLI installed?): {0}")]
+    Spawn(std::io::Error),
+    /// `gh` ran but failed
+    #[error("gh issue list failed: {0}")]
+    Gh(String),
+    /// `gh` printed something other than the expected JSON
+    #[error("Unexpected gh output: {0}")]
+    Output(String),
+    /// The PRD the stories go into is not a JSON object with a story list
+    #[error("PRD has no userStories array")]
+    NotAPrd,
+}
+
+/// A GitHub issue, as listed by `gh issue list --json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct GhIssue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub labels: Vec<GhLabel>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct GhLabel {
+    pub name: String,
+}
+
+/// Which issues to import.
+#[derive(Debug, Clone, Default)]
+pub struct IssueQuery {
+    /// Repository as OWNER/NAME (default: the repository of the current directory)
+    pub repo: Option<String>,
+    /// Labels an issue must all have
+    pub labels: Vec<String>,
+    /// Maximum number of issues
+    pub limit: usize,
+}
+
+/// List open issues matching `query` with the `gh` CLI.
+pub fn fetch_issues(query: &IssueQuery) -> Result<Vec<GhIssue>, IssueImportError> {
+    let mut command = Command::new("gh");
+    command.args([
+        "issue",
+        "list",
+        "--state",
+        "open",
+        "--json",
+        "number,title,body,labels",
+        "--limit",
+        &query.limit.to_string(),
+    ]);
+    if let Some(repo) = &query.repo {
+        command.args(["--repo", repo]);
+    }
+    for label in &query.labels {
+        command.args(["--label", label]);
+    }
+    let output = command.output().map_err(IssueImportError::Spawn)?;
+    if !output.status.success() {
+        return Err(IssueImportError::Gh(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| IssueImportError::Output(e.to_string()))
+}
+
+/// A story made from an issue, in PRD form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IssueStory {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    #[serde(rename = "acceptanceCriteria")]
+    pub acceptance_criteria: Vec<String>,
+    pub priority: u32,
+    pub passes: bool,
+    #[serde(rename = "dependsOn")]
+    pub depends_on: Vec<String>,
+}
+
+/// ID of the story for issue `number`.
+pub fn issue_story_id(number: u64) -> String {
+    format!("{}{}", ISSUE_STORY_PREFIX, number)
+}
+
+/// Turn an issue into a story.
+pub fn issue_to_story(issue: &GhIssue) -> IssueStory {
+    let task = Regex::new(r"^\s*[-*]\s+\[[ xX]\]\s+(.+)$").expect("valid regex");
+    let mut description = Vec::new();
+    let mut acceptance_criteria = Vec::new();
+    for line in issue.body.lines() {
+        match task.captures(line) {
+            Some(captures) => acceptance_criteria.push(captures[1].trim().to_string()),
+            None => description.push(line),
+        }
+    }
+
+    IssueStory {
+        id: issue_story_id(issue.number),
+        title: issue.title.trim().to_string(),
+        description: description.join("\n").trim().to_string(),
+        acceptance_criteria,
+        priority: issue
+            .labels
+            .iter()
+            .filter_map(|label| label_priority(&label.name))
+            .min()
+            .unwrap_or(DEFAULT_ISSUE_PRIORITY),
+        passes: false,
+        depends_on: linked_issues(&issue.body)
+            .into_iter()
+            .filter(|number| *number != issue.number)
+            .map(issue_story_id)
+            .collect(),
+    }
+}
+
+/// Priority named by a label, if it is a priority label.
+fn label_priority(label: &str) -> Option<u32> {
+    let label = label.trim().to_lowercase();
+    let name = ["priority:", "priority/", "priority-", "priority "]
+        .iter()
+        .find_map(|prefix| label.strip_prefix(prefix))
+        .unwrap_or(&label)
+        .trim();
+    match name {
+        "p0" | "critical" | "urgent" => Some(1),
+        "p1" | "high" => Some(2),
+        "p2" | "medium" | "normal" => Some(3),
+        "p3" | "low" => Some(4),
+        _ => None,
+    }
+}
+
+/// Issues an issue body says it depends on, in order of mention.
+fn linked_issues(body: &str) -> Vec<u64> {
+    let mention = Regex::new(
+        r"(?i)\b(?:depends\s+on|blocked\s+by|requires|after)\s+(#\d+(?:\s*(?:,|and|&)\s*#\d+)*)",
+    )
+    .expect("valid regex");
+    let number = Regex::new(r"#(\d+)").expect("valid regex");
+    let mut seen = HashSet::new();
+    mention
+        .captures_iter(body)
+        .flat_map(|captures| {
+            number
+                .captures_iter(&captures[1])
+                .filter_map(|n| n[1].parse().ok())
+                .collect::<Vec<u64>>()
+        })
+        .filter(|n| seen.insert(*n))
+        .collect()
+}
+
+/// Stories added and updated by [`merge_issue_stories`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+}
+
+/// Merge `stories` into the PRD `prd`.
+///
+/// New stories are appended; stories already in the PRD get the issue's
+/// title, description, criteria, priority and dependencies but keep
+/// `passes` and any other fields. Dependencies on issues that are neither
+/// imported nor in the PRD are dropped, so the PRD stays runnable.
+pub fn merge_issue_stories(
+    prd: &mut Value,
+    stories: &[IssueStory],
+) -> Result<ImportSummary, IssueImportError> {
+    let existing = prd
+        .get_mut("userStories")
+        .and_then(Value::as_array_mut)
+        .ok_or(IssueImportError::NotAPrd)?;
+    let known: HashSet<String> = existing
+        .iter()
+        .filter_map(|story| story.get("id").and_then(Value::as_str))
+        .map(String::from)
+        .chain(stories.iter().map(|story| story.id.clone()))
+        .collect();
+
+    let mut summary = ImportSummary::default();
+    for story in stories {
+        let mut story = story.clone();
+        story.depends_on.retain(|dep| known.contains(dep));
+        let value =
+            serde_json::to_value(&story).map_err(|e| IssueImportError::Output(e.to_string()))?;
+
+        let current = existing
+            .iter_mut()
+            .find(|current| current.get("id").and_then(Value::as_str) == Some(&story.id));
+        match current {
+            Some(Value::Object(current)) => {
+                if let Value::Object(fields) = value {
+                    for (key, field) in fields {
+                        if key != "passes" {
+                            current.insert(key, field);
+                        }
+                    }
+                }
+                summary.updated.push(story.id);
+            }
+            _ => {
+                existing.push(value);
+                summary.added.push(story.id);
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(number: u64, body: &str, labels: &[&str]) -> GhIssue {
+        GhIssue {
+            number,
+            title: format!("Issue {}", number),
+            body: body.to_string(),
+            labels: labels
+                .iter()
+                .map(|name| GhLabel {
+                    name: name.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_issue_to_story() {
+        let story = issue_to_story(&issue(
+            12,
+            "Add a login form.\n\nDepends on #10 and #11, blocked by #12.\n\n- [ ] Form renders\n- [x] Errors shown\n",
+            &["ralph", "priority: high", "P2"],
+        ));
+        assert_eq!(story.id, "GH-12");
+        assert_eq!(story.priority, 2);
+        assert_eq!(
+            story.acceptance_criteria,
+            vec!["Form renders", "Errors shown"]
+        );
+        assert_eq!(story.depends_on, vec!["GH-10", "GH-11"]);
+        assert_eq!(
+            story.description,
+            "Add a login form.\n\nDepends on #10 and #11, blocked by #12."
+        );
+        assert_eq!(
+            issue_to_story(&issue(3, "", &["bug"])).priority,
+            DEFAULT_ISSUE_PRIORITY
+        );
+    }
+
+    #[test]
+    fn test_merge_issue_stories() {
+        let mut prd = serde_json::json!({
+            "project": "Demo",
+            "branchName": "demo",
+            "userStories": [
+                {"id": "GH-10", "title": "Old", "priority": 1, "passes": true, "heavy": true}
+            ]
+        });
+        let stories = vec![
+            issue_to_story(&issue(10, "", &[])),
+            issue_to_story(&issue(11, "Depends on #10, #99", &[])),
+        ];
+        let summary = merge_issue_stories(&mut prd, &stories).unwrap();
+        assert_eq!(summary.added, vec!["GH-11"]);
+        assert_eq!(summary.updated, vec!["GH-10"]);
+
+        let updated = &prd["userStories"][0];
+        assert_eq!(updated["title"], "Issue 10");
+        assert_eq!(updated["passes"], true);
+        assert_eq!(updated["heavy"], true);
+        assert_eq!(
+            prd["userStories"][1]["dependsOn"],
+            serde_json::json!(["GH-10"])
+        );
+    }
+
+    #[test]
+    fn test_merge_issue_stories_keeps_key_order() {
+        let content = r#"{"project": "Demo", "userStories": [{"title": "Old", "id": "GH-10", "passes": true}], "branchName": "demo"}"#;
+        let mut prd: Value = serde_json::from_str(content).unwrap();
+        merge_issue_stories(&mut prd, &[issue_to_story(&issue(10, "", &[]))]).unwrap();
+
+        let keys: Vec<_> = prd.as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["project", "userStories", "branchName"]);
+        let story_keys: Vec<_> = prd["userStories"][0].as_object().unwrap().keys().collect();
+        assert_eq!(&story_keys[..3], &["title", "id", "passes"]);
+    }
+}
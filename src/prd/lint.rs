@@ -0,0 +1,330 @@
+//! PRD linter.
+//!
+//! A PRD can satisfy the schema and still be unrunnable or wasteful: two
+//! stories sharing an ID, a dependency on a story that does not exist, a
+//! dependency cycle that leaves stories unreachable. The linter flags those
+//! as errors, and as warnings the things that make agents flounder or runs
+//! serialize: stories without acceptance criteria, `targetFiles` globs that
+//! lock most of the tree, and stories whose prompt and files are estimated to
+//! exceed the per-story token budget.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::budget::TokenEstimator;
+use crate::mcp::tools::load_prd::{pointer_line, PrdFile, PrdUserStory};
+
+/// How serious a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    /// Worth a look, but the PRD runs
+    Warning,
+    /// The PRD cannot run as written
+    Error,
+}
+
+/// The check that produced a finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    DuplicateId,
+    UnknownDependency,
+    Unreachable,
+    NoAcceptanceCriteria,
+    BroadTargetGlob,
+    OverBudget,
+}
+
+impl LintRule {
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            LintRule::DuplicateId | LintRule::UnknownDependency | LintRule::Unreachable => {
+                LintSeverity::Error
+            }
+            LintRule::NoAcceptanceCriteria | LintRule::BroadTargetGlob | LintRule::OverBudget => {
+                LintSeverity::Warning
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintRule::DuplicateId => "duplicate-id",
+            LintRule::UnknownDependency => "unknown-dependency",
+            LintRule::Unreachable => "unreachable",
+            LintRule::NoAcceptanceCriteria => "no-acceptance-criteria",
+            LintRule::BroadTargetGlob => "broad-target-glob",
+            LintRule::OverBudget => "over-budget",
+        }
+    }
+}
+
+/// One problem found by the linter.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    /// Story the finding is about
+    pub story: String,
+    /// JSON pointer to the offending value
+    pub pointer: String,
+    /// 1-based line of the offending value, if it could be located
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Settings for [`lint_prd`].
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    /// Project root `targetFiles` and `readFiles` are resolved against when
+    /// estimating tokens; without it only the story text is counted
+    pub project_root: Option<PathBuf>,
+    /// Maximum tokens per story (0 = unlimited)
+    pub story_budget: u64,
+}
+
+/// Lint `prd`, parsed from `content`, returning findings in document order.
+pub fn lint_prd(content: &str, prd: &PrdFile, options: &LintOptions) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    let mut add = |rule: LintRule, story: &str, pointer: String, message: String| {
+        findings.push(LintFinding {
+            rule,
+            severity: rule.severity(),
+            story: story.to_string(),
+            line: pointer_line(content, &pointer),
+            pointer,
+            message,
+        });
+    };
+
+    let ids: HashSet<&str> = prd.user_stories.iter().map(|s| s.id.as_str()).collect();
+    let mut seen = HashSet::new();
+    let mut missing_dependency = HashSet::new();
+    for (index, story) in prd.user_stories.iter().enumerate() {
+        let pointer = format!("/userStories/{}", index);
+        if !seen.insert(story.id.as_str()) {
+            add(
+                LintRule::DuplicateId,
+                &story.id,
+                format!("{}/id", pointer),
+                format!("story ID {} is used more than once", story.id),
+            );
+        }
+        for (dep_index, dep) in story.depends_on.iter().enumerate() {
+            if !ids.contains(dep.as_str()) {
+                missing_dependency.insert(story.id.as_str());
+                add(
+                    LintRule::UnknownDependency,
+                    &story.id,
+                    format!("{}/dependsOn/{}", pointer, dep_index),
+                    format!("depends on {}, which is not a story in this PRD", dep),
+                );
+            }
+        }
+        if story.acceptance_criteria.is_empty() {
+            add(
+                LintRule::NoAcceptanceCriteria,
+                &story.id,
+                pointer.clone(),
+                "has no acceptance criteria, so nothing tells the agent when it is done"
+                    .to_string(),
+            );
+        }
+        for (file_index, pattern) in story.target_files.iter().enumerate() {
+            if is_broad_glob(pattern) {
+                add(
+                    LintRule::BroadTargetGlob,
+                    &story.id,
+                    format!("{}/targetFiles/{}", pointer, file_index),
+                    format!(
+                        "{} locks most of the tree and keeps other stories from running alongside",
+                        pattern
+                    ),
+                );
+            }
+        }
+        if options.story_budget > 0 {
+            let estimate = estimate_story_tokens(story, options);
+            if estimate > options.story_budget {
+                add(
+                    LintRule::OverBudget,
+                    &story.id,
+                    pointer.clone(),
+                    format!(
+                        "estimated at ~{} tokens per iteration, over the per-story budget of {}",
+                        estimate, options.story_budget
+                    ),
+                );
+            }
+        }
+    }
+
+    let reachable = reachable_stories(&prd.user_stories);
+    for (index, story) in prd.user_stories.iter().enumerate() {
+        if !reachable.contains(story.id.as_str()) && !missing_dependency.contains(story.id.as_str())
+        {
+            add(
+                LintRule::Unreachable,
+                &story.id,
+                format!("/userStories/{}/dependsOn", index),
+                "never becomes ready: its dependencies form a cycle or wait on a story that cannot run"
+                    .to_string(),
+            );
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.line.unwrap_or(usize::MAX));
+    findings
+}
+
+/// IDs of stories that are passing or whose dependencies can all complete.
+fn reachable_stories(stories: &[PrdUserStory]) -> HashSet<&str> {
+    let mut reachable: HashSet<&str> = stories
+        .iter()
+        .filter(|story| story.passes)
+        .map(|story| story.id.as_str())
+        .collect();
+    loop {
+        let ready: Vec<&str> = stories
+            .iter()
+            .filter(|story| !reachable.contains(story.id.as_str()))
+            .filter(|story| {
+                story
+                    .depends_on
+                    .iter()
+                    .all(|dep| reachable.contains(dep.as_str()))
+            })
+            .map(|story| story.id.as_str())
+            .collect();
+        if ready.is_empty() {
+            return reachable;
+        }
+        reachable.extend(ready);
+    }
+}
+
+/// Whether a `targetFiles` pattern covers most of the tree: it starts with a
+/// wildcard, or recurses (`**`) from the root or a top-level directory.
+fn is_broad_glob(pattern: &str) -> bool {
+    let is_wild = |component: &str| component.contains(['*', '?', '[']);
+    let components: Vec<&str> = pattern
+        .trim_start_matches("./")
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .collect();
+    let literal_prefix = components.iter().take_while(|c| !is_wild(c)).count();
+    if literal_prefix == components.len() {
+        return false;
+    }
+    literal_prefix == 0 || (pattern.contains("**") && literal_prefix < 2)
+}
+
+/// Tokens one iteration of `story` is expected to take: its prompt plus the
+/// files it reads and writes that already exist.
+fn estimate_story_tokens(story: &PrdUserStory, options: &LintOptions) -> u64 {
+    let estimator = TokenEstimator::default();
+    let criteria: Vec<String> = story
+        .acceptance_criteria
+        .iter()
+        .map(|criterion| criterion.description.clone())
+        .collect();
+    let mut total = estimator.estimate_story_prompt(&story.title, &story.description, &criteria, 0);
+    let Some(root) = &options.project_root else {
+        return total;
+    };
+
+    let mut counted = HashSet::new();
+    for pattern in story.target_files.iter().chain(&story.read_files) {
+        // A trailing `**` matches only directories; count the files beneath
+        let pattern = if pattern.ends_with("**") {
+            format!("{}/*", pattern)
+        } else {
+            pattern.clone()
+        };
+        let Ok(paths) = glob::glob(&root.join(pattern).to_string_lossy()) else {
+            continue;
+        };
+        for path in paths.flatten() {
+            if !path.is_file() || !counted.insert(path.clone()) {
+                continue;
+            }
+            if let Ok(text) = std::fs::read_to_string(&path) {
+                total += estimator.estimate(&text);
+            }
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::load_prd::validate_prd_content;
+
+    #[test]
+    fn test_lint_prd_findings() {
+        let content = r#"{
+  "project": "Demo",
+  "branchName": "demo",
+  "userStories": [
+    {"id": "US-1", "title": "A", "priority": 1, "passes": false,
+     "acceptanceCriteria": ["works"], "targetFiles": ["**/*.rs", "src/api/**"]},
+    {"id": "US-1", "title": "B", "priority": 2, "passes": false,
+     "acceptanceCriteria": ["works"]},
+    {"id": "US-2", "title": "C", "priority": 3, "passes": false,
+     "acceptanceCriteria": ["works"], "dependsOn": ["US-9"]},
+    {"id": "US-3", "title": "D", "priority": 4, "passes": false,
+     "dependsOn": ["US-4"]},
+    {"id": "US-4", "title": "E", "priority": 5, "passes": false,
+     "acceptanceCriteria": ["works"], "dependsOn": ["US-3"]}
+  ]
+}"#;
+        let prd = validate_prd_content(content).unwrap();
+        let findings = lint_prd(content, &prd, &LintOptions::default());
+        let found: Vec<(LintRule, &str, Option<usize>)> = findings
+            .iter()
+            .map(|f| (f.rule, f.pointer.as_str(), f.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    LintRule::BroadTargetGlob,
+                    "/userStories/0/targetFiles/0",
+                    Some(6)
+                ),
+                (LintRule::DuplicateId, "/userStories/1/id", Some(7)),
+                (
+                    LintRule::UnknownDependency,
+                    "/userStories/2/dependsOn/0",
+                    Some(10)
+                ),
+                (LintRule::NoAcceptanceCriteria, "/userStories/3", Some(11)),
+                (LintRule::Unreachable, "/userStories/3/dependsOn", Some(12)),
+                (LintRule::Unreachable, "/userStories/4/dependsOn", Some(14)),
+            ]
+        );
+
+        let tight = LintOptions {
+            story_budget: 10,
+            ..Default::default()
+        };
+        assert!(lint_prd(content, &prd, &tight)
+            .iter()
+            .any(|f| f.rule == LintRule::OverBudget && f.story == "US-2"));
+    }
+
+    #[test]
+    fn test_is_broad_glob() {
+        for pattern in ["*", "**", "**/*.rs", "*.md", "src/**", "./src/**/*.ts"] {
+            assert!(is_broad_glob(pattern), "{}", pattern);
+        }
+        for pattern in ["src/main.rs", "src/*.rs", "src/api/**", "docs/guide/*.md"] {
+            assert!(!is_broad_glob(pattern), "{}", pattern);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! PRD tooling beyond loading: checks a PRD for problems the schema cannot
+//! express, imports stories from GitHub issues, creates PRDs interactively,
+//! merges the PRDs of a workspace, checks the external prerequisites of
+//! stories and writes story status back.
+
+pub mod issues;
+pub mod lint;
+pub mod prerequisites;
+pub mod wizard;
+pub mod workspace;
+pub mod writeback;
+
+pub use issues::{fetch_issues, issue_to_story, merge_issue_stories, IssueImportError, IssueQuery};
+pub use lint::{lint_prd, LintFinding, LintOptions, LintRule, LintSeverity};
+pub use prerequisites::check_prerequisites;
+pub use wizard::{PrdDraft, PrdWizard, StoryDraft};
+pub use workspace::{story_prd, Workspace, WorkspaceError, WORKSPACE_FILE};
+pub use writeback::{set_story_passes, write_prd, WritebackError};
@@ -0,0 +1,148 @@
+//! Checking the external prerequisites of stories.
+//!
+//! A story can declare conditions outside the PRD that it needs, such as a
+//! running database or a credential in the environment:
+//!
+//! ```json
+//! "prerequisites": [
+//!   {"type": "command", "command": "docker info"},
+//!   {"type": "env", "name": "DATABASE_URL"},
+//!   {"type": "service", "address": "localhost:5432"}
+//! ]
+//! ```
+//!
+//! They are checked before the story is dispatched, so a story whose
+//! environment is missing is reported as blocked with the reason instead of
+//! handed to an agent that fails on it.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+use tokio::process::Command;
+
+use crate::mcp::tools::load_prd::Prerequisite;
+
+/// How long a prerequisite command may run.
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long connecting to a prerequisite service may take.
+pub const SERVICE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Check `prerequisites` in order, running commands in `working_dir`.
+///
+/// Returns why the first unmet prerequisite is not met.
+pub async fn check_prerequisites(
+    prerequisites: &[Prerequisite],
+    working_dir: &Path,
+) -> Result<(), String> {
+    for prerequisite in prerequisites {
+        check(prerequisite, working_dir).await?;
+    }
+    Ok(())
+}
+
+async fn check(prerequisite: &Prerequisite, working_dir: &Path) -> Result<(), String> {
+    match prerequisite {
+        Prerequisite::Env { name } => match std::env::var_os(name) {
+            Some(value) if !value.is_empty() => Ok(()),
+            _ => Err(format!("environment variable {} is not set", name)),
+        },
+        Prerequisite::Command { command } => {
+            let child = Command::new("sh")
+                .args(["-c", command])
+                .current_dir(working_dir)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()
+                .map_err(|e| format!("command `{}` could not start: {}", command, e))?;
+            match tokio::time::timeout(COMMAND_TIMEOUT, child.wait_with_output()).await {
+                Err(_) => Err(format!(
+                    "command `{}` did not finish within {}s",
+                    command,
+                    COMMAND_TIMEOUT.as_secs()
+                )),
+                Ok(Err(e)) => Err(format!("command `{}` failed: {}", command, e)),
+                Ok(Ok(output)) if output.status.success() => Ok(()),
+                Ok(Ok(output)) => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let reason = match stderr.lines().map(str::trim).rfind(|l| !l.is_empty()) {
+                        Some(line) => format!("{}: {}", output.status, line),
+                        None => output.status.to_string(),
+                    };
+                    Err(format!("command `{}` failed ({})", command, reason))
+                }
+            }
+        }
+        Prerequisite::Service { address } => {
+            let connect = TcpStream::connect(address.as_str());
+            match tokio::time::timeout(SERVICE_TIMEOUT, connect).await {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err(e)) => Err(format!("service {} is not reachable: {}", address, e)),
+                Err(_) => Err(format!(
+                    "service {} did not accept a connection within {}s",
+                    address,
+                    SERVICE_TIMEOUT.as_secs()
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_prerequisites() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let open = listener.local_addr().unwrap().to_string();
+        let closed = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+        let prerequisites: Vec<Prerequisite> = serde_json::from_str(&format!(
+            r#"[
+                {{"type": "command", "command": "true"}},
+                {{"type": "env", "name": "PATH"}},
+                {{"type": "service", "address": "{}"}}
+            ]"#,
+            open
+        ))
+        .unwrap();
+        assert_eq!(
+            check_prerequisites(&prerequisites, dir.path()).await,
+            Ok(())
+        );
+
+        let unmet = |prerequisite: Prerequisite| {
+            let dir = dir.path().to_path_buf();
+            async move {
+                check_prerequisites(&[prerequisite], &dir)
+                    .await
+                    .unwrap_err()
+            }
+        };
+        let command = unmet(Prerequisite::Command {
+            command: "echo 'docker daemon not running' >&2; exit 3".to_string(),
+        })
+        .await;
+        assert!(command.contains("exit status: 3: docker daemon not running"));
+        assert_eq!(
+            unmet(Prerequisite::Env {
+                name: "RALPH_TEST_UNSET_PREREQUISITE".to_string()
+            })
+            .await,
+            "environment variable RALPH_TEST_UNSET_PREREQUISITE is not set"
+        );
+        let service = unmet(Prerequisite::Service {
+            address: closed.clone(),
+        })
+        .await;
+        assert!(service.starts_with(&format!("service {} is not reachable", closed)));
+    }
+}
@@ -0,0 +1,282 @@
+//! Interactive PRD creation.
+//!
+//! `ralph prd init` asks for the project, then for each story its title,
+//! description, acceptance criteria, dependencies and target files, and
+//! writes a PRD that passes validation, so first-time users need not
+//! reverse-engineer the format from an example.
+
+use std::io::{self, BufRead, Write};
+
+use serde::Serialize;
+
+/// A PRD built by the wizard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrdDraft {
+    pub project: String,
+    #[serde(rename = "branchName")]
+    pub branch_name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(rename = "userStories")]
+    pub user_stories: Vec<StoryDraft>,
+}
+
+/// A story built by the wizard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoryDraft {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(rename = "acceptanceCriteria")]
+    pub acceptance_criteria: Vec<String>,
+    pub priority: u32,
+    pub passes: bool,
+    #[serde(rename = "dependsOn", skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    #[serde(rename = "targetFiles", skip_serializing_if = "Vec::is_empty")]
+    pub target_files: Vec<String>,
+}
+
+/// The interactive PRD wizard.
+#[derive(Debug, Clone)]
+pub struct PrdWizard {
+    /// Project name offered when the user enters none
+    default_project: String,
+}
+
+impl PrdWizard {
+    pub fn new(default_project: impl Into<String>) -> Self {
+        Self {
+            default_project: default_project.into(),
+        }
+    }
+
+    /// Run the wizard on stdin and stdout.
+    pub fn run(&self) -> io::Result<PrdDraft> {
+        self.run_with_reader_writer(&mut io::stdin().lock(), &mut io::stdout())
+    }
+
+    /// Run the wizard with custom reader/writer (for testing)
+    pub fn run_with_reader_writer<R: BufRead, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> io::Result<PrdDraft> {
+        writeln!(writer, "Create a PRD. Press Enter to accept [defaults].")?;
+        writeln!(writer)?;
+
+        let project = ask(reader, writer, "Project name", Some(&self.default_project))?;
+        let default_branch = format!("ralph/{}", slug(&project));
+        let branch_name = ask(reader, writer, "Branch name", Some(&default_branch))?;
+        let description = ask(reader, writer, "Description (optional)", None)?;
+
+        let mut user_stories: Vec<StoryDraft> = Vec::new();
+        loop {
+            let number = user_stories.len() + 1;
+            writeln!(writer)?;
+            let prompt = if user_stories.is_empty() {
+                format!("Story {} title", number)
+            } else {
+                format!("Story {} title (blank to finish)", number)
+            };
+            let title = ask(reader, writer, &prompt, None)?;
+            if title.is_empty() {
+                if !user_stories.is_empty() {
+                    break;
+                }
+                if at_eof(reader)? {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "no stories entered",
+                    ));
+                }
+                writeln!(writer, "A PRD needs at least one story.")?;
+                continue;
+            }
+
+            let id = format!("US-{:03}", number);
+            let story_description = ask(reader, writer, "  Description (optional)", None)?;
+            writeln!(
+                writer,
+                "  Acceptance criteria, one per line (blank line to finish):"
+            )?;
+            let mut acceptance_criteria = Vec::new();
+            loop {
+                let criterion = ask(reader, writer, "    Criterion", None)?;
+                if criterion.is_empty() {
+                    break;
+                }
+                acceptance_criteria.push(criterion);
+            }
+            let depends_on = if user_stories.is_empty() {
+                Vec::new()
+            } else {
+                self.ask_dependencies(reader, writer, &user_stories)?
+            };
+            let target_files = list(&ask(
+                reader,
+                writer,
+                "  Files it will change, comma-separated (globs allowed, optional)",
+                None,
+            )?);
+
+            writeln!(writer, "  Added {}: {}", id, title)?;
+            user_stories.push(StoryDraft {
+                id,
+                title,
+                description: story_description,
+                acceptance_criteria,
+                priority: number as u32,
+                passes: false,
+                depends_on,
+                target_files,
+            });
+        }
+
+        Ok(PrdDraft {
+            project,
+            branch_name,
+            description,
+            user_stories,
+        })
+    }
+
+    /// Ask which earlier stories a story depends on, until every ID is known.
+    fn ask_dependencies<R: BufRead, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        earlier: &[StoryDraft],
+    ) -> io::Result<Vec<String>> {
+        let ids: Vec<&str> = earlier.iter().map(|story| story.id.as_str()).collect();
+        loop {
+            let prompt = format!(
+                "  Depends on ({}), comma-separated (optional)",
+                ids.join(", ")
+            );
+            let depends_on = list(&ask(reader, writer, &prompt, None)?);
+            let unknown: Vec<&String> = depends_on
+                .iter()
+                .filter(|dep| !ids.contains(&dep.as_str()))
+                .collect();
+            if unknown.is_empty() {
+                return Ok(depends_on);
+            }
+            let unknown: Vec<&str> = unknown.iter().map(|dep| dep.as_str()).collect();
+            writeln!(writer, "  Unknown story: {}", unknown.join(", "))?;
+        }
+    }
+}
+
+/// Print `prompt` and read one trimmed line; an empty answer (or end of
+/// input) gives `default`, or an empty string without one.
+fn ask<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    prompt: &str,
+    default: Option<&str>,
+) -> io::Result<String> {
+    match default {
+        Some(default) => write!(writer, "{} [{}]: ", prompt, default)?,
+        None => write!(writer, "{}: ", prompt)?,
+    }
+    writer.flush()?;
+    let mut input = String::new();
+    reader.read_line(&mut input)?;
+    let input = input.trim();
+    Ok(if input.is_empty() {
+        default.unwrap_or_default().to_string()
+    } else {
+        input.to_string()
+    })
+}
+
+fn at_eof<R: BufRead>(reader: &mut R) -> io::Result<bool> {
+    Ok(reader.fill_buf()?.is_empty())
+}
+
+/// Comma-separated entries of `input`, trimmed, without empty ones.
+fn list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Lowercase, dash-separated form of `name` for branch names.
+fn slug(name: &str) -> String {
+    let slug: Vec<String> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if slug.is_empty() {
+        "feature".to_string()
+    } else {
+        slug.join("-")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::load_prd::validate_prd_content;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_run_with_reader_writer() {
+        let input = "\
+My App
+
+Demo app
+
+Add login
+Users sign in with email
+Form renders
+Bad passwords are rejected
+
+src/auth/*.rs, src/routes.rs
+Add logout
+
+Button works
+
+US-009
+US-001
+
+";
+        let mut reader = Cursor::new(input);
+        let mut writer = Vec::new();
+        let draft = PrdWizard::new("fallback")
+            .run_with_reader_writer(&mut reader, &mut writer)
+            .unwrap();
+
+        assert_eq!(draft.project, "My App");
+        assert_eq!(draft.branch_name, "ralph/my-app");
+        assert_eq!(draft.user_stories.len(), 2);
+        let login = &draft.user_stories[0];
+        assert_eq!(
+            login.acceptance_criteria,
+            vec!["Form renders", "Bad passwords are rejected"]
+        );
+        assert_eq!(login.target_files, vec!["src/auth/*.rs", "src/routes.rs"]);
+        let logout = &draft.user_stories[1];
+        assert_eq!(logout.id, "US-002");
+        assert_eq!(logout.depends_on, vec!["US-001"]);
+        assert!(String::from_utf8(writer)
+            .unwrap()
+            .contains("Unknown story: US-009"));
+
+        let json = serde_json::to_string_pretty(&draft).unwrap();
+        assert!(validate_prd_content(&json).is_ok());
+    }
+
+    #[test]
+    fn test_wizard_requires_a_story() {
+        let mut reader = Cursor::new("\n\n\n");
+        let result = PrdWizard::new("app").run_with_reader_writer(&mut reader, &mut Vec::new());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,302 @@
+//! Multi-PRD workspaces.
+//!
+//! A monorepo can keep one PRD per package and coordinate them in a single
+//! run through a workspace manifest, `ralph-workspace.json`:
+//!
+//! ```json
+//! {
+//!   "project": "shop",
+//!   "branchName": "ralph/shop",
+//!   "members": [
+//!     {"name": "api", "prd": "packages/api/prd.json"},
+//!     {"name": "web", "prd": "packages/web/prd.json"}
+//!   ]
+//! }
+//! ```
+//!
+//! Loading the manifest merges the members' stories into one PRD, so a single
+//! dependency graph covers them all. Story IDs are qualified with the member
+//! name (`api/US-001`); a dependency written as a plain ID refers to the same
+//! member and `web/US-002` to another member's story. Target and read files
+//! are taken relative to the member PRD's directory. When a story passes, the
+//! member PRD it came from is updated.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::mcp::tools::load_prd::{validate_prd, ParallelConfig, PrdFile, PrdValidationError};
+
+/// Default workspace manifest file name.
+pub const WORKSPACE_FILE: &str = "ralph-workspace.json";
+
+/// Separator between the member name and story ID in qualified IDs.
+pub const MEMBER_SEPARATOR: char = '/';
+
+/// Errors from loading a workspace.
+#[derive(Debug, Error)]
+pub enum WorkspaceError {
+    #[error("Failed to read workspace {path}: {message}")]
+    Read { path: PathBuf, message: String },
+    #[error("Invalid workspace {path}: {message}")]
+    Manifest { path: PathBuf, message: String },
+    /// A member PRD failed validation
+    #[error("Workspace member '{member}' ({path}): {error}")]
+    Member {
+        member: String,
+        path: PathBuf,
+        error: PrdValidationError,
+    },
+}
+
+/// A member of a workspace manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkspaceMember {
+    /// Name qualifying the member's story IDs (default: the PRD's directory name)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Member PRD, relative to the manifest
+    pub prd: PathBuf,
+}
+
+/// A workspace manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkspaceManifest {
+    pub project: String,
+    #[serde(rename = "branchName")]
+    pub branch_name: String,
+    #[serde(default)]
+    pub description: String,
+    pub members: Vec<WorkspaceMember>,
+    #[serde(default)]
+    pub parallel: Option<ParallelConfig>,
+}
+
+/// A loaded workspace.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    manifest: WorkspaceManifest,
+    root: PathBuf,
+    /// Resolved member names, in manifest order
+    names: Vec<String>,
+}
+
+impl Workspace {
+    /// Whether the JSON file at `path` is a workspace manifest rather than a PRD.
+    pub fn is_manifest(path: &Path) -> bool {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .is_some_and(|value| {
+                value.get("members").is_some() && value.get("userStories").is_none()
+            })
+    }
+
+    /// Load the manifest at `path`.
+    pub fn load(path: &Path) -> Result<Self, WorkspaceError> {
+        let content = std::fs::read_to_string(path).map_err(|e| WorkspaceError::Read {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        let invalid = |message: String| WorkspaceError::Manifest {
+            path: path.to_path_buf(),
+            message,
+        };
+        let manifest: WorkspaceManifest =
+            serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+        if manifest.members.is_empty() {
+            return Err(invalid("no members".to_string()));
+        }
+
+        let mut names = Vec::new();
+        for member in &manifest.members {
+            let name = member
+                .name
+                .clone()
+                .or_else(|| {
+                    member
+                        .prd
+                        .parent()
+                        .and_then(Path::file_name)
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .filter(|name| !name.trim().is_empty())
+                .ok_or_else(|| invalid(format!("member {} needs a name", member.prd.display())))?;
+            if name.contains(MEMBER_SEPARATOR) {
+                return Err(invalid(format!(
+                    "member name '{}' must not contain '{}'",
+                    name, MEMBER_SEPARATOR
+                )));
+            }
+            if names.contains(&name) {
+                return Err(invalid(format!("member '{}' is listed twice", name)));
+            }
+            names.push(name);
+        }
+
+        Ok(Self {
+            manifest,
+            root: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            names,
+        })
+    }
+
+    /// Member names, in manifest order.
+    pub fn member_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Path of the member PRD named `name`.
+    pub fn member_prd(&self, name: &str) -> Option<PathBuf> {
+        let index = self.names.iter().position(|n| n == name)?;
+        Some(self.root.join(&self.manifest.members[index].prd))
+    }
+
+    /// The member PRD a qualified story ID comes from, and the ID within it.
+    pub fn locate(&self, story_id: &str) -> Option<(PathBuf, String)> {
+        let (member, id) = story_id.split_once(MEMBER_SEPARATOR)?;
+        Some((self.member_prd(member)?, id.to_string()))
+    }
+
+    /// Validate every member and merge their stories into one PRD.
+    pub fn merged(&self) -> Result<PrdFile, WorkspaceError> {
+        let mut user_stories = Vec::new();
+        for (name, member) in self.names.iter().zip(&self.manifest.members) {
+            let path = self.root.join(&member.prd);
+            let prd = validate_prd(&path).map_err(|error| WorkspaceError::Member {
+                member: name.clone(),
+                path: path.clone(),
+                error,
+            })?;
+            let dir = member.prd.parent().unwrap_or(Path::new(""));
+            let in_member = |file: &String| dir.join(file).to_string_lossy().to_string();
+
+            for mut story in prd.user_stories {
+                story.id = format!("{}{}{}", name, MEMBER_SEPARATOR, story.id);
+                for dep in story.depends_on.iter_mut() {
+                    match dep.split_once(MEMBER_SEPARATOR) {
+                        Some((member, _)) if !self.names.iter().any(|n| n == member) => {
+                            return Err(WorkspaceError::Manifest {
+                                path: path.clone(),
+                                message: format!(
+                                    "{} depends on {}, but there is no member '{}'",
+                                    story.id, dep, member
+                                ),
+                            });
+                        }
+                        Some(_) => {}
+                        None => *dep = format!("{}{}{}", name, MEMBER_SEPARATOR, dep),
+                    }
+                }
+                story.target_files = story.target_files.iter().map(in_member).collect();
+                story.read_files = story.read_files.iter().map(in_member).collect();
+                user_stories.push(story);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(story) = user_stories.iter().find(|story| !seen.insert(&story.id)) {
+            return Err(WorkspaceError::Manifest {
+                path: self.root.clone(),
+                message: format!("story {} appears twice", story.id),
+            });
+        }
+
+        Ok(PrdFile {
+            project: self.manifest.project.clone(),
+            branch_name: self.manifest.branch_name.clone(),
+            description: self.manifest.description.clone(),
+            user_stories,
+            parallel: self.manifest.parallel.clone(),
+        })
+    }
+}
+
+/// The PRD file and story ID to update when `story_id` from the run's PRD
+/// `prd_path` passes: the member PRD for a workspace, else the PRD itself.
+pub fn story_prd(prd_path: &Path, story_id: &str) -> (PathBuf, String) {
+    if Workspace::is_manifest(prd_path) {
+        if let Some(located) = Workspace::load(prd_path)
+            .ok()
+            .and_then(|workspace| workspace.locate(story_id))
+        {
+            return located;
+        }
+    }
+    (prd_path.to_path_buf(), story_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_merge_workspace() {
+        let root = TempDir::new().unwrap();
+        write(
+            root.path(),
+            "packages/api/prd.json",
+            r#"{"project": "api", "branchName": "api", "userStories": [
+                {"id": "US-001", "title": "Schema", "priority": 1, "passes": false,
+                 "targetFiles": ["src/schema.rs"]},
+                {"id": "US-002", "title": "Endpoint", "priority": 2, "passes": false,
+                 "dependsOn": ["US-001"]}
+            ]}"#,
+        );
+        write(
+            root.path(),
+            "packages/web/prd.json",
+            r#"{"project": "web", "branchName": "web", "userStories": [
+                {"id": "US-001", "title": "Page", "priority": 1, "passes": true,
+                 "dependsOn": ["api/US-002"]}
+            ]}"#,
+        );
+        write(
+            root.path(),
+            WORKSPACE_FILE,
+            r#"{"project": "shop", "branchName": "ralph/shop", "members": [
+                {"name": "api", "prd": "packages/api/prd.json"},
+                {"prd": "packages/web/prd.json"}
+            ]}"#,
+        );
+
+        let manifest = root.path().join(WORKSPACE_FILE);
+        assert!(Workspace::is_manifest(&manifest));
+        assert!(!Workspace::is_manifest(
+            &root.path().join("packages/api/prd.json")
+        ));
+
+        let prd = validate_prd(&manifest).unwrap();
+        assert_eq!(prd.project, "shop");
+        let ids: Vec<&str> = prd.user_stories.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["api/US-001", "api/US-002", "web/US-001"]);
+        assert_eq!(prd.user_stories[1].depends_on, vec!["api/US-001"]);
+        assert_eq!(prd.user_stories[2].depends_on, vec!["api/US-002"]);
+        assert_eq!(
+            prd.user_stories[0].target_files,
+            vec!["packages/api/src/schema.rs"]
+        );
+
+        assert_eq!(
+            story_prd(&manifest, "web/US-001"),
+            (
+                root.path().join("packages/web/prd.json"),
+                "US-001".to_string()
+            )
+        );
+        let plain = root.path().join("packages/api/prd.json");
+        assert_eq!(
+            story_prd(&plain, "US-001"),
+            (plain.clone(), "US-001".to_string())
+        );
+    }
+}
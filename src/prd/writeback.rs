@@ -0,0 +1,200 @@
+//! Writing story status back to the PRD.
+//!
+//! A passing story is marked by replacing only its `passes` value in the PRD
+//! text, or inserting one after its `id`, so the file keeps its indentation,
+//! key order and line endings. The result is written to a temporary file that
+//! is renamed over the PRD, so a crash never leaves a truncated PRD behind,
+//! and writebacks are serialized so parallel stories finishing together
+//! cannot overwrite each other's updates.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::mcp::tools::load_prd::pointer_span;
+
+/// Held for each read-modify-write of a PRD.
+static WRITEBACK_LOCK: Mutex<()> = Mutex::new(());
+
+/// Errors from writing story status back to the PRD.
+#[derive(Debug, Error)]
+pub enum WritebackError {
+    #[error("Failed to read PRD: {0}")]
+    Read(std::io::Error),
+    #[error("Failed to parse PRD: {0}")]
+    Parse(serde_json::Error),
+    #[error("Failed to write PRD: {0}")]
+    Write(std::io::Error),
+}
+
+/// Set the `passes` flag of `story_id` in the PRD at `prd_path`.
+///
+/// Returns whether the PRD has the story; a PRD without it is left as is.
+pub fn set_story_passes(
+    prd_path: &Path,
+    story_id: &str,
+    passes: bool,
+) -> Result<bool, WritebackError> {
+    let _guard = WRITEBACK_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    let content = fs::read_to_string(prd_path).map_err(WritebackError::Read)?;
+    let Some(updated) = with_story_passes(&content, story_id, passes)? else {
+        return Ok(false);
+    };
+    if updated != content {
+        write_atomic(prd_path, &updated).map_err(WritebackError::Write)?;
+    }
+    Ok(true)
+}
+
+/// `content` with the `passes` flag of `story_id` set, or `None` if no story
+/// has that ID.
+pub fn with_story_passes(
+    content: &str,
+    story_id: &str,
+    passes: bool,
+) -> Result<Option<String>, WritebackError> {
+    let json: Value = serde_json::from_str(content).map_err(WritebackError::Parse)?;
+    let Some(index) = json["userStories"].as_array().and_then(|stories| {
+        stories
+            .iter()
+            .position(|story| story["id"].as_str() == Some(story_id))
+    }) else {
+        return Ok(None);
+    };
+
+    let pointer = format!("/userStories/{}/passes", index);
+    if let Some(span) = pointer_span(content, &pointer) {
+        let mut updated = String::with_capacity(content.len());
+        updated.push_str(&content[..span.start]);
+        updated.push_str(if passes { "true" } else { "false" });
+        updated.push_str(&content[span.end..]);
+        return Ok(Some(updated));
+    }
+
+    // No `passes` value to replace: add one after the story's `id`, on a line
+    // of its own if the `id` has one
+    let Some(id_span) = pointer_span(content, &format!("/userStories/{}/id", index)) else {
+        return Ok(None);
+    };
+    let line_start = content[..id_span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line = &content[line_start..id_span.start];
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let flag = if passes { "true" } else { "false" };
+    let separator = if line.trim_start().starts_with("\"id\"") {
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        format!(",{}{}", newline, indent)
+    } else {
+        ", ".to_string()
+    };
+    let mut updated = String::with_capacity(content.len() + separator.len() + 16);
+    updated.push_str(&content[..id_span.end]);
+    updated.push_str(&separator);
+    updated.push_str("\"passes\": ");
+    updated.push_str(flag);
+    updated.push_str(&content[id_span.end..]);
+    Ok(Some(updated))
+}
+
+/// Replace the PRD at `prd_path` with `content`, with the same atomic,
+/// serialized write as [`set_story_passes`].
+pub fn write_prd(prd_path: &Path, content: &str) -> Result<(), WritebackError> {
+    let _guard = WRITEBACK_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    write_atomic(prd_path, content).map_err(WritebackError::Write)
+}
+
+/// Replace `path` with `content` through a temporary file in the same
+/// directory, keeping the file's permissions.
+fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path: PathBuf = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    if let Ok(metadata) = fs::metadata(path) {
+        fs::set_permissions(&temp_path, metadata.permissions())?;
+    }
+    fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_with_story_passes_keeps_formatting() {
+        let content = "{\r\n\t\"userStories\": [\r\n\t\t{\"id\": \"US-1\", \"passes\": false, \"priority\": 1},\r\n\t\t{\"passes\":false,\"id\":\"US-2\"}\r\n\t]\r\n}";
+
+        let updated = with_story_passes(content, "US-2", true).unwrap().unwrap();
+        assert_eq!(
+            updated,
+            content.replace("\"passes\":false", "\"passes\":true")
+        );
+        assert!(with_story_passes(content, "US-9", true).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_with_story_passes_adds_missing_flag() {
+        let content = "{\n    \"userStories\": [\n        {\"title\": \"A\", \"id\": \"US-1\", \"priority\": 1}\n    ]\n}\n";
+        let updated = with_story_passes(content, "US-1", true).unwrap().unwrap();
+        assert_eq!(
+            updated,
+            "{\n    \"userStories\": [\n        {\"title\": \"A\", \"id\": \"US-1\", \"passes\": true, \"priority\": 1}\n    ]\n}\n"
+        );
+
+        // Key order and the keys' own lines are kept
+        let content = "{\r\n  \"project\": \"shop\",\r\n  \"userStories\": [\r\n    {\r\n      \"title\": \"Cart\",\r\n      \"id\": \"US-1\",\r\n      \"acceptanceCriteria\": []\r\n    }\r\n  ],\r\n  \"branchName\": \"main\"\r\n}";
+        let updated = with_story_passes(content, "US-1", false).unwrap().unwrap();
+        assert_eq!(
+            updated,
+            content.replace("\"US-1\",", "\"US-1\",\r\n      \"passes\": false,")
+        );
+        let json: Value = serde_json::from_str(&updated).unwrap();
+        assert_eq!(json["userStories"][0]["passes"], false);
+    }
+
+    #[test]
+    fn test_concurrent_writebacks_are_serialized() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("prd.json");
+        let stories: Vec<String> = (0..16)
+            .map(|i| format!(r#"{{"id": "US-{}", "passes": false}}"#, i))
+            .collect();
+        fs::write(
+            &path,
+            format!(r#"{{"userStories": [{}]}}"#, stories.join(", ")),
+        )
+        .unwrap();
+
+        std::thread::scope(|scope| {
+            for i in 0..16 {
+                let path = &path;
+                scope.spawn(move || {
+                    assert!(set_story_passes(path, &format!("US-{}", i), true).unwrap());
+                });
+            }
+        });
+
+        let json: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let stories = json["userStories"].as_array().unwrap();
+        assert!(stories.iter().all(|story| story["passes"] == true));
+        assert!(!dir.path().join(".prd.json.tmp").exists());
+    }
+}
@@ -0,0 +1,494 @@
+//! Relevant-file context for story prompts.
+//!
+//! Instead of leaving the agent to find its way around the repository, the
+//! [`FileContextBuilder`] picks the files most relevant to a story and
+//! includes them in the prompt, up to a token cap. Files are scored by how
+//! they relate to the story:
+//!
+//! - the story's `targetFiles` (and, less strongly, `readFiles`)
+//! - files named in the gate failures of earlier iterations, the latest
+//!   iteration weighing most
+//! - files the above import (Rust `mod`/`use crate::`, relative JS/TS
+//!   imports), at half the score of the importing file
+//!
+//! The highest-scoring files that fit under the cap are included in full; the
+//! rest are only listed by name.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::budget::{PromptStrategy, TokenEstimator};
+use crate::iteration::IterationContext;
+use crate::mcp::tools::load_prd::PrdUserStory;
+
+/// Score of a file in the story's `targetFiles`.
+const TARGET_FILE_SCORE: u32 = 100;
+/// Score of a file named in the latest iteration's gate failures.
+const LATEST_FAILURE_SCORE: u32 = 80;
+/// Score of a file in the story's `readFiles`.
+const READ_FILE_SCORE: u32 = 60;
+/// Score of a file named in an earlier iteration's gate failures.
+const EARLIER_FAILURE_SCORE: u32 = 40;
+/// Extra score per gate finding in a file, up to [`MAX_FINDINGS_BONUS`].
+const FINDING_BONUS: u32 = 5;
+const MAX_FINDINGS_BONUS: u32 = 20;
+/// Files scoring below this are not followed to their imports.
+const MIN_IMPORTER_SCORE: u32 = READ_FILE_SCORE;
+/// Files larger than this are never included in full.
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+/// Maximum number of files matched by one `targetFiles` glob.
+const MAX_GLOB_MATCHES: usize = 50;
+
+/// A file selected for the prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RelevantFile {
+    /// Path relative to the project root
+    pub path: String,
+    /// Relevance score (higher is more relevant)
+    pub score: u32,
+    /// Estimated tokens of the content
+    pub tokens: u64,
+    /// Full content of the file
+    pub content: String,
+}
+
+/// Files selected for a story's prompt.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FileContext {
+    /// Files included in full, most relevant first
+    pub files: Vec<RelevantFile>,
+    /// Relevant files left out to stay under the token cap, most relevant first
+    pub omitted: Vec<String>,
+}
+
+impl FileContext {
+    /// Whether no relevant files were found.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.omitted.is_empty()
+    }
+
+    /// Estimated tokens of the included files.
+    pub fn tokens(&self) -> u64 {
+        self.files.iter().map(|f| f.tokens).sum()
+    }
+
+    /// Render the "Relevant Files" prompt section, or an empty string when
+    /// there are no relevant files.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let mut out = String::from("## Relevant Files\n\n");
+        for file in &self.files {
+            let fence = if file.content.contains("```") {
+                "````"
+            } else {
+                "```"
+            };
+            let language = Path::new(&file.path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("");
+            out.push_str(&format!(
+                "### {}\n{}{}\n{}",
+                file.path, fence, language, file.content
+            ));
+            if !file.content.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(fence);
+            out.push_str("\n\n");
+        }
+        if !self.omitted.is_empty() {
+            out.push_str(&format!(
+                "Also relevant (not included to stay within the token budget): {}\n\n",
+                self.omitted.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Selects the files to include in a story's prompt.
+#[derive(Debug, Clone)]
+pub struct FileContextBuilder {
+    project_root: PathBuf,
+    max_tokens: u64,
+    estimator: TokenEstimator,
+}
+
+impl FileContextBuilder {
+    /// Select files under `project_root`, including at most `max_tokens` of
+    /// file content.
+    pub fn new(project_root: impl Into<PathBuf>, max_tokens: u64) -> Self {
+        Self {
+            project_root: project_root.into(),
+            max_tokens,
+            estimator: TokenEstimator::default(),
+        }
+    }
+
+    /// Scale the token cap to the prompt strategy of the remaining budget:
+    /// the full cap for full and standard prompts, a quarter for minimal
+    /// prompts and nothing in critical mode.
+    pub fn with_strategy(mut self, strategy: PromptStrategy) -> Self {
+        self.max_tokens = match strategy {
+            PromptStrategy::Full | PromptStrategy::Standard => self.max_tokens,
+            PromptStrategy::Minimal => self.max_tokens / 4,
+            PromptStrategy::Critical => 0,
+        };
+        self
+    }
+
+    /// Select the files most relevant to `story` given the failures recorded
+    /// in `context`.
+    pub fn build(&self, story: &PrdUserStory, context: &IterationContext) -> FileContext {
+        if self.max_tokens == 0 {
+            return FileContext::default();
+        }
+        let scores = self.score_files(story, context);
+        let mut ranked: Vec<(String, u32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut selected = FileContext::default();
+        let mut remaining = self.max_tokens;
+        for (path, score) in ranked {
+            let Some(content) = self.read(&path) else {
+                continue;
+            };
+            let tokens = self.estimator.estimate(&content);
+            if tokens <= remaining {
+                remaining -= tokens;
+                selected.files.push(RelevantFile {
+                    path,
+                    score,
+                    tokens,
+                    content,
+                });
+            } else {
+                selected.omitted.push(path);
+            }
+        }
+        selected
+    }
+
+    /// Relevance score of every candidate file, by path relative to the
+    /// project root.
+    fn score_files(
+        &self,
+        story: &PrdUserStory,
+        context: &IterationContext,
+    ) -> HashMap<String, u32> {
+        let mut scores: HashMap<String, u32> = HashMap::new();
+
+        for pattern in &story.target_files {
+            for path in self.expand(pattern) {
+                raise(&mut scores, path, TARGET_FILE_SCORE);
+            }
+        }
+        for pattern in &story.read_files {
+            for path in self.expand(pattern) {
+                raise(&mut scores, path, READ_FILE_SCORE);
+            }
+        }
+
+        let latest = context.error_history.last().map(|e| e.iteration);
+        let mut findings: HashMap<String, u32> = HashMap::new();
+        for error in &context.error_history {
+            let base = if Some(error.iteration) == latest {
+                LATEST_FAILURE_SCORE
+            } else {
+                EARLIER_FAILURE_SCORE
+            };
+            for file in error.failures.iter().filter_map(|f| f.file.as_deref()) {
+                let path = self.relative(file);
+                if Some(error.iteration) == latest {
+                    *findings.entry(path.clone()).or_insert(0) += 1;
+                }
+                raise(&mut scores, path, base);
+            }
+        }
+        for (path, count) in findings {
+            if let Some(score) = scores.get_mut(&path) {
+                *score += (count * FINDING_BONUS).min(MAX_FINDINGS_BONUS);
+            }
+        }
+
+        // Follow imports one level from the files directly relevant to the story
+        let importers: Vec<(String, u32)> = scores
+            .iter()
+            .filter(|(_, score)| **score >= MIN_IMPORTER_SCORE)
+            .map(|(path, score)| (path.clone(), *score))
+            .collect();
+        for (path, score) in importers {
+            let Some(content) = self.read(&path) else {
+                continue;
+            };
+            for import in imports(Path::new(&path), &content) {
+                if self.project_root.join(&import).is_file() {
+                    raise(&mut scores, import, score / 2);
+                }
+            }
+        }
+        scores
+    }
+
+    /// Files matching `pattern` (a path or glob relative to the project root).
+    fn expand(&self, pattern: &str) -> Vec<String> {
+        if !pattern.contains(['*', '?', '[']) {
+            return vec![self.relative(pattern)];
+        }
+        let full = self.project_root.join(pattern);
+        let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+            return Vec::new();
+        };
+        paths
+            .filter_map(Result::ok)
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                path.strip_prefix(&self.project_root)
+                    .ok()
+                    .map(|p| p.to_string_lossy().into_owned())
+            })
+            .take(MAX_GLOB_MATCHES)
+            .collect()
+    }
+
+    /// `file` relative to the project root, as gates may report absolute
+    /// paths.
+    fn relative(&self, file: &str) -> String {
+        let path = Path::new(file);
+        path.strip_prefix(&self.project_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .to_string()
+    }
+
+    /// Content of the text file at `path`, if it exists and is not too large.
+    fn read(&self, path: &str) -> Option<String> {
+        let full = self.project_root.join(path);
+        let metadata = std::fs::metadata(&full).ok()?;
+        if !metadata.is_file() || metadata.len() > MAX_FILE_BYTES {
+            return None;
+        }
+        std::fs::read_to_string(full).ok()
+    }
+}
+
+/// Raise the score of `path` to at least `score`.
+fn raise(scores: &mut HashMap<String, u32>, path: String, score: u32) {
+    let entry = scores.entry(path).or_insert(0);
+    *entry = (*entry).max(score);
+}
+
+/// Files imported by the file at `path` (relative to the project root),
+/// resolved to candidate paths relative to the project root.
+fn imports(path: &Path, content: &str) -> Vec<String> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let candidates = match extension {
+        "rs" => rust_imports(path, content),
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => script_imports(path, content),
+        _ => Vec::new(),
+    };
+    candidates
+        .into_iter()
+        .filter_map(|candidate| normalize(&candidate))
+        .collect()
+}
+
+/// Candidate files for `mod name;` and `use crate::...` in a Rust file.
+fn rust_imports(path: &Path, content: &str) -> Vec<PathBuf> {
+    static MOD_RE: OnceLock<Regex> = OnceLock::new();
+    static USE_RE: OnceLock<Regex> = OnceLock::new();
+    let mod_re = MOD_RE.get_or_init(|| {
+        Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_][A-Za-z0-9_]*)\s*;").unwrap()
+    });
+    let use_re = USE_RE.get_or_init(|| {
+        Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+crate::([A-Za-z0-9_:]+)").unwrap()
+    });
+
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    // Submodules of `foo.rs` live in `foo/`; those of `mod.rs`, `lib.rs`
+    // and `main.rs` next to it
+    let module_dir = if matches!(stem, "mod" | "lib" | "main") {
+        parent.to_path_buf()
+    } else {
+        parent.join(stem)
+    };
+
+    let mut candidates = Vec::new();
+    for capture in mod_re.captures_iter(content) {
+        let name = &capture[1];
+        candidates.push(module_dir.join(format!("{}.rs", name)));
+        candidates.push(module_dir.join(name).join("mod.rs"));
+    }
+
+    let Some(src_root) = path
+        .ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == "src"))
+    else {
+        return candidates;
+    };
+    for capture in use_re.captures_iter(content) {
+        let segments: Vec<&str> = capture[1]
+            .split("::")
+            .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_'))
+            .collect();
+        // `use crate::a::b::Item` may name module `a::b` or item `b` of `a`
+        for len in (1..=segments.len()).rev() {
+            let module = segments[..len].iter().collect::<PathBuf>();
+            let file = src_root.join(&module).with_extension("rs");
+            let mod_file = src_root.join(&module).join("mod.rs");
+            candidates.push(file);
+            candidates.push(mod_file);
+        }
+    }
+    candidates
+}
+
+/// Candidate files for relative `import`/`require` in a JS/TS file.
+fn script_imports(path: &Path, content: &str) -> Vec<PathBuf> {
+    static IMPORT_RE: OnceLock<Regex> = OnceLock::new();
+    let import_re = IMPORT_RE.get_or_init(|| {
+        Regex::new(r#"(?:from\s+|require\(\s*|import\s*\(?\s*)["'](\.{1,2}/[^"']+)["']"#).unwrap()
+    });
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let mut candidates = Vec::new();
+    for capture in import_re.captures_iter(content) {
+        let base = parent.join(&capture[1]);
+        candidates.push(base.clone());
+        for extension in ["ts", "tsx", "js", "jsx"] {
+            let mut file = base.clone().into_os_string();
+            file.push(format!(".{}", extension));
+            candidates.push(PathBuf::from(file));
+            candidates.push(base.join(format!("index.{}", extension)));
+        }
+    }
+    candidates
+}
+
+/// Resolve `.` and `..` in a relative path; `None` if it leaves the project.
+fn normalize(path: &Path) -> Option<String> {
+    let mut parts: Vec<&std::ffi::OsStr> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    let path: PathBuf = parts.iter().collect();
+    Some(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iteration::context::ErrorCategory;
+    use crate::iteration::IterationError;
+    use crate::quality::{FailureCategory, GateFailureDetail};
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn story(target_files: &[&str]) -> PrdUserStory {
+        PrdUserStory {
+            id: "US-001".to_string(),
+            title: "Add login".to_string(),
+            description: String::new(),
+            acceptance_criteria: vec![],
+            priority: 1,
+            passes: false,
+            depends_on: vec![],
+            target_files: target_files.iter().map(|f| f.to_string()).collect(),
+            read_files: vec![],
+            heavy: false,
+            agent: None,
+            model: None,
+            epic: None,
+            prerequisites: Vec::new(),
+            estimate: None,
+        }
+    }
+
+    #[test]
+    fn test_selects_targets_failures_and_imports_under_cap() {
+        let root = TempDir::new().unwrap();
+        write(
+            root.path(),
+            "src/auth.rs",
+            "mod session;\nuse crate::db::users::find;\n",
+        );
+        write(root.path(), "src/auth/session.rs", "pub struct Session;\n");
+        write(root.path(), "src/db/users.rs", "pub fn find() {}\n");
+        write(root.path(), "src/config.rs", "pub const PORT: u16 = 80;\n");
+        write(root.path(), "src/big.rs", &"// filler\n".repeat(400));
+        write(
+            root.path(),
+            "web/app.ts",
+            "import { login } from './api';\n",
+        );
+        write(root.path(), "web/api.ts", "export function login() {}\n");
+
+        let mut context = IterationContext::new("US-001", 5);
+        context.record_error(
+            IterationError::new(1, ErrorCategory::Lint, "Gate 'clippy' failed")
+                .with_gate("clippy")
+                .with_failures(vec![GateFailureDetail::new(
+                    FailureCategory::Lint,
+                    "unused",
+                )
+                .with_location(
+                    root.path().join("src/config.rs").to_string_lossy(),
+                    1,
+                    None,
+                )]),
+        );
+
+        let builder = FileContextBuilder::new(root.path(), 200);
+        let selected = builder.build(&story(&["src/auth.rs", "src/big.rs", "web/*.ts"]), &context);
+        let paths: Vec<&str> = selected.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "src/auth.rs",
+                "web/api.ts",
+                "web/app.ts",
+                "src/config.rs",
+                "src/auth/session.rs",
+                "src/db/users.rs",
+            ]
+        );
+        assert_eq!(
+            selected.files[3].score,
+            LATEST_FAILURE_SCORE + FINDING_BONUS
+        );
+        assert_eq!(selected.files[4].score, TARGET_FILE_SCORE / 2);
+        assert_eq!(selected.omitted, vec!["src/big.rs"]);
+        assert!(selected.tokens() <= 200);
+
+        let rendered = selected.render();
+        assert!(rendered.starts_with("## Relevant Files\n\n### src/auth.rs\n```rs\nmod session;"));
+        assert!(rendered.ends_with("token budget): src/big.rs\n\n"));
+
+        let critical =
+            FileContextBuilder::new(root.path(), 200).with_strategy(PromptStrategy::Critical);
+        assert!(critical
+            .build(&story(&["src/auth.rs"]), &context)
+            .is_empty());
+        assert_eq!(FileContext::default().render(), "");
+    }
+}
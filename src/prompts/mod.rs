@@ -0,0 +1,660 @@
+//! Prompt templates for agent runs.
+//!
+//! The prompt an agent receives for a story is rendered from a Handlebars
+//! template. Ralph ships a built-in `story` template; a `story.hbs` file in
+//! `.ralph/prompts/` replaces it, so teams can tune what agents are told
+//! without forking Ralph. Every other `*.hbs` file in that directory is
+//! registered as a partial under its file stem (`{{> conventions}}`).
+//!
+//! When an agent continues its conversation from the previous iteration, the
+//! shorter `continue` template (overridable as `continue.hbs`) is rendered
+//! instead, carrying only what changed since the last run.
+//!
+//! A reviewer agent checking the changes before the quality gates gets the
+//! `review` template (overridable as `review.hbs`), and an agent resolving
+//! the conflicts of a story branch during reconciliation gets the `merge`
+//! template (overridable as `merge.hbs`). An agent fixing the issues
+//! reconciliation found in the integrated tree gets the `reconcile` template
+//! (overridable as `reconcile.hbs`).
+//!
+//! The files most relevant to the story can be included as well; see
+//! [`files`].
+
+pub mod files;
+
+use std::path::{Path, PathBuf};
+
+use handlebars::{
+    Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderErrorReason,
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::budget::{PromptStrategy, TokenBudget};
+use crate::iteration::IterationContext;
+use crate::mcp::tools::load_prd::PrdUserStory;
+use crate::quality::GateFailureDetail;
+
+pub use files::{FileContext, FileContextBuilder, RelevantFile};
+
+/// Directory, relative to the project root, holding user templates.
+pub const PROMPTS_DIR: &str = ".ralph/prompts";
+
+/// Name of the template rendering a story prompt.
+pub const STORY_TEMPLATE: &str = "story";
+
+/// Name of the template rendering a follow-up prompt in a continued session.
+pub const CONTINUE_TEMPLATE: &str = "continue";
+
+/// Name of the template rendering a reviewer's prompt.
+pub const REVIEW_TEMPLATE: &str = "review";
+
+/// Name of the template rendering a merge conflict resolver's prompt.
+pub const MERGE_TEMPLATE: &str = "merge";
+
+/// Name of the template rendering a reconciliation fixer's prompt.
+pub const RECONCILE_TEMPLATE: &str = "reconcile";
+
+/// Extension of template files.
+const TEMPLATE_EXTENSION: &str = "hbs";
+
+/// The built-in story template.
+const DEFAULT_STORY_TEMPLATE: &str = include_str!("story.hbs");
+
+/// The built-in continuation template.
+const DEFAULT_CONTINUE_TEMPLATE: &str = include_str!("continue.hbs");
+
+/// The built-in review template.
+const DEFAULT_REVIEW_TEMPLATE: &str = include_str!("review.hbs");
+
+/// The built-in merge conflict template.
+const DEFAULT_MERGE_TEMPLATE: &str = include_str!("merge.hbs");
+
+/// The built-in reconciliation template.
+const DEFAULT_RECONCILE_TEMPLATE: &str = include_str!("reconcile.hbs");
+
+/// Errors from loading or rendering prompt templates.
+#[derive(Debug, Error)]
+pub enum PromptError {
+    /// A template file could not be read
+    #[error("Failed to read prompt template {path}: {message}")]
+    Read { path: PathBuf, message: String },
+    /// A template does not parse
+    #[error("Invalid prompt template '{name}': {message}")]
+    Template { name: String, message: String },
+    /// A template failed to render
+    #[error("Failed to render prompt template '{name}': {message}")]
+    Render { name: String, message: String },
+}
+
+/// Story fields exposed to templates as `story`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoryVariables {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub acceptance_criteria: Vec<String>,
+    pub priority: u32,
+    pub depends_on: Vec<String>,
+    pub target_files: Vec<String>,
+    pub model: Option<String>,
+}
+
+impl From<&PrdUserStory> for StoryVariables {
+    fn from(story: &PrdUserStory) -> Self {
+        Self {
+            id: story.id.clone(),
+            title: story.title.clone(),
+            description: story.description.clone(),
+            acceptance_criteria: story
+                .acceptance_criteria
+                .iter()
+                .map(|criterion| criterion.description.clone())
+                .collect(),
+            priority: story.priority,
+            depends_on: story.depends_on.clone(),
+            target_files: story.target_files.clone(),
+            model: story.model.clone(),
+        }
+    }
+}
+
+/// A failure from an earlier iteration, exposed in `previous_errors`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorVariables {
+    pub iteration: u32,
+    /// Error category, e.g. "lint" or "test"
+    pub category: String,
+    pub message: String,
+    /// Quality gate that failed, if any
+    pub gate: Option<String>,
+    pub files: Vec<String>,
+    /// Structured findings of the gate: `file`, `line`, `column`,
+    /// `error_code`, `message`, `suggestion`
+    pub failures: Vec<GateFailureDetail>,
+}
+
+/// Budget state exposed to templates as `budget`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetVariables {
+    /// Whether a token budget is being enforced
+    pub enabled: bool,
+    /// Prompt strategy for the remaining budget: "full", "standard",
+    /// "minimal" or "critical"
+    pub strategy: String,
+    /// Tokens used so far across the run
+    pub tokens_used: u64,
+    /// Tokens left in the total budget, if it is limited
+    pub total_remaining: Option<u64>,
+    /// Tokens left for this story, if it is limited
+    pub story_remaining: Option<u64>,
+    /// Share of the total budget used, 0-100
+    pub usage_percent: u32,
+}
+
+impl BudgetVariables {
+    /// Budget variables for `budget`, rendered with `strategy`.
+    pub fn new(budget: Option<&TokenBudget>, strategy: PromptStrategy) -> Self {
+        let limited = |tokens: u64| (tokens != u64::MAX).then_some(tokens);
+        let strategy = match strategy {
+            PromptStrategy::Full => "full",
+            PromptStrategy::Standard => "standard",
+            PromptStrategy::Minimal => "minimal",
+            PromptStrategy::Critical => "critical",
+        }
+        .to_string();
+        match budget {
+            Some(budget) => Self {
+                enabled: true,
+                strategy,
+                tokens_used: budget.total_tokens(),
+                total_remaining: limited(budget.total_remaining()),
+                story_remaining: limited(budget.story_remaining()),
+                usage_percent: (budget.total_usage_percent() * 100.0).round() as u32,
+            },
+            None => Self {
+                enabled: false,
+                strategy,
+                tokens_used: 0,
+                total_remaining: None,
+                story_remaining: None,
+                usage_percent: 0,
+            },
+        }
+    }
+}
+
+/// Everything a story template can refer to.
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptVariables {
+    pub story: StoryVariables,
+    /// Project name from the PRD
+    pub project: String,
+    /// Branch the PRD targets
+    pub branch: String,
+    /// Current iteration, starting at 1
+    pub iteration: u32,
+    pub max_iterations: u32,
+    /// Failures of earlier iterations, oldest first
+    pub previous_errors: Vec<ErrorVariables>,
+    /// Distinct quality gates that failed in earlier iterations
+    pub failed_gates: Vec<String>,
+    pub budget: BudgetVariables,
+    /// Ralph's own "Previous Iteration Context" section, already trimmed to
+    /// the budget; empty on the first iteration
+    pub iteration_context: String,
+    /// Files selected as relevant to the story, included in full
+    pub files: Vec<RelevantFile>,
+    /// Ralph's own "Relevant Files" section; empty when file context is off
+    pub file_context: String,
+}
+
+impl PromptVariables {
+    /// Variables for `story` at the state recorded in `context`.
+    pub fn new(
+        story: &PrdUserStory,
+        project: &str,
+        branch: &str,
+        context: &IterationContext,
+        budget: BudgetVariables,
+        iteration_context: String,
+    ) -> Self {
+        let previous_errors: Vec<ErrorVariables> = context
+            .error_history
+            .iter()
+            .map(|error| ErrorVariables {
+                iteration: error.iteration,
+                category: error.category.as_str().to_string(),
+                message: error.message.clone(),
+                gate: error.failed_gate.clone(),
+                files: error.affected_files.clone(),
+                failures: error.failures.clone(),
+            })
+            .collect();
+        let mut failed_gates: Vec<String> = Vec::new();
+        for gate in previous_errors.iter().filter_map(|e| e.gate.as_ref()) {
+            if !failed_gates.contains(gate) {
+                failed_gates.push(gate.clone());
+            }
+        }
+        Self {
+            story: StoryVariables::from(story),
+            project: project.to_string(),
+            branch: branch.to_string(),
+            iteration: context.current_iteration.max(1),
+            max_iterations: context.max_iterations,
+            previous_errors,
+            failed_gates,
+            budget,
+            iteration_context,
+            files: Vec::new(),
+            file_context: String::new(),
+        }
+    }
+
+    /// Include the files selected for the story.
+    pub fn with_files(mut self, files: FileContext) -> Self {
+        self.file_context = files.render();
+        self.files = files.files;
+        self
+    }
+}
+
+/// Everything a review template can refer to.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewVariables {
+    pub story: StoryVariables,
+    /// Project name from the PRD
+    pub project: String,
+    /// Iteration whose changes are reviewed
+    pub iteration: u32,
+    /// Files the implementing agent changed
+    pub files_changed: Vec<String>,
+    /// The changes as a unified diff against `HEAD`
+    pub diff: String,
+}
+
+impl ReviewVariables {
+    /// Variables for reviewing `diff`, the changes made to `story`.
+    pub fn new(
+        story: &PrdUserStory,
+        project: &str,
+        iteration: u32,
+        files_changed: Vec<String>,
+        diff: String,
+    ) -> Self {
+        Self {
+            story: StoryVariables::from(story),
+            project: project.to_string(),
+            iteration,
+            files_changed,
+            diff,
+        }
+    }
+}
+
+/// Everything a merge conflict template can refer to.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeVariables {
+    pub story: StoryVariables,
+    /// Project name from the PRD
+    pub project: String,
+    /// Story branch being merged
+    pub branch: String,
+    /// Files left with conflicts
+    pub files: Vec<String>,
+}
+
+impl MergeVariables {
+    /// Variables for resolving the conflicts `files` of merging `story`'s `branch`.
+    pub fn new(story: &PrdUserStory, project: &str, branch: &str, files: Vec<String>) -> Self {
+        Self {
+            story: StoryVariables::from(story),
+            project: project.to_string(),
+            branch: branch.to_string(),
+            files,
+        }
+    }
+}
+
+/// Everything a reconciliation template can refer to.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconcileVariables {
+    /// Project name from the PRD
+    pub project: String,
+    /// Stories the issues were traced to
+    pub stories: Vec<StoryVariables>,
+    /// Descriptions of the issues found
+    pub issues: Vec<String>,
+    /// Files the issues were found in
+    pub files: Vec<String>,
+    /// What the stories committed to those files, as a unified diff
+    pub diff: String,
+}
+
+impl ReconcileVariables {
+    /// Variables for fixing `issues` in `files`, traced to `stories`.
+    pub fn new(
+        project: &str,
+        stories: &[&PrdUserStory],
+        issues: Vec<String>,
+        files: Vec<String>,
+        diff: String,
+    ) -> Self {
+        Self {
+            project: project.to_string(),
+            stories: stories
+                .iter()
+                .map(|story| StoryVariables::from(*story))
+                .collect(),
+            issues,
+            files,
+            diff,
+        }
+    }
+}
+
+/// The prompt templates in effect for a project.
+pub struct PromptTemplates {
+    registry: Handlebars<'static>,
+    /// User files that replaced or extended the built-in templates
+    overrides: Vec<PathBuf>,
+}
+
+impl std::fmt::Debug for PromptTemplates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PromptTemplates")
+            .field("overrides", &self.overrides)
+            .finish()
+    }
+}
+
+impl PromptTemplates {
+    /// Only the built-in templates.
+    pub fn builtin() -> Self {
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        registry.register_helper("inc", Box::new(inc_helper));
+        registry
+            .register_template_string(STORY_TEMPLATE, DEFAULT_STORY_TEMPLATE)
+            .expect("built-in story template parses");
+        registry
+            .register_template_string(CONTINUE_TEMPLATE, DEFAULT_CONTINUE_TEMPLATE)
+            .expect("built-in continue template parses");
+        registry
+            .register_template_string(REVIEW_TEMPLATE, DEFAULT_REVIEW_TEMPLATE)
+            .expect("built-in review template parses");
+        registry
+            .register_template_string(MERGE_TEMPLATE, DEFAULT_MERGE_TEMPLATE)
+            .expect("built-in merge template parses");
+        registry
+            .register_template_string(RECONCILE_TEMPLATE, DEFAULT_RECONCILE_TEMPLATE)
+            .expect("built-in reconcile template parses");
+        Self {
+            registry,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// The built-in templates with any from `<project_root>/.ralph/prompts`
+    /// applied on top.
+    pub fn load(project_root: &Path) -> Result<Self, PromptError> {
+        let mut templates = Self::builtin();
+        let dir = project_root.join(PROMPTS_DIR);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(templates);
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path.extension().and_then(|e| e.to_str()) == Some(TEMPLATE_EXTENSION)
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let source = std::fs::read_to_string(&path).map_err(|e| PromptError::Read {
+                path: path.clone(),
+                message: e.to_string(),
+            })?;
+            let name = name.to_string();
+            if [
+                STORY_TEMPLATE,
+                CONTINUE_TEMPLATE,
+                REVIEW_TEMPLATE,
+                MERGE_TEMPLATE,
+                RECONCILE_TEMPLATE,
+            ]
+            .contains(&name.as_str())
+            {
+                templates
+                    .registry
+                    .register_template_string(&name, source)
+                    .map_err(|e| PromptError::Template {
+                        name: name.clone(),
+                        message: e.to_string(),
+                    })?;
+            } else {
+                templates
+                    .registry
+                    .register_partial(&name, source)
+                    .map_err(|e| PromptError::Template {
+                        name: name.clone(),
+                        message: e.to_string(),
+                    })?;
+            }
+            templates.overrides.push(path);
+        }
+        Ok(templates)
+    }
+
+    /// User template files in effect, in load order.
+    pub fn overrides(&self) -> &[PathBuf] {
+        &self.overrides
+    }
+
+    /// Render the prompt for a story.
+    pub fn render_story(&self, variables: &PromptVariables) -> Result<String, PromptError> {
+        self.render(STORY_TEMPLATE, variables)
+    }
+
+    /// Render the follow-up prompt for an agent continuing its session.
+    pub fn render_continuation(&self, variables: &PromptVariables) -> Result<String, PromptError> {
+        self.render(CONTINUE_TEMPLATE, variables)
+    }
+
+    /// Render the prompt for a reviewer of a story's changes.
+    pub fn render_review(&self, variables: &ReviewVariables) -> Result<String, PromptError> {
+        self.render(REVIEW_TEMPLATE, variables)
+    }
+
+    /// Render the prompt for an agent resolving a story branch's merge conflicts.
+    pub fn render_merge(&self, variables: &MergeVariables) -> Result<String, PromptError> {
+        self.render(MERGE_TEMPLATE, variables)
+    }
+
+    /// Render the prompt for an agent fixing the issues reconciliation found.
+    pub fn render_reconcile(&self, variables: &ReconcileVariables) -> Result<String, PromptError> {
+        self.render(RECONCILE_TEMPLATE, variables)
+    }
+
+    fn render(&self, name: &str, variables: &impl Serialize) -> Result<String, PromptError> {
+        self.registry
+            .render(name, variables)
+            .map_err(|e| PromptError::Render {
+                name: name.to_string(),
+                message: e.to_string(),
+            })
+    }
+}
+
+impl Default for PromptTemplates {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// `{{inc n}}`: `n + 1`, for numbering lists from `@index`.
+fn inc_helper(
+    h: &Helper<'_>,
+    _: &Handlebars<'_>,
+    _: &Context,
+    _: &mut RenderContext<'_, '_>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let n = h
+        .param(0)
+        .and_then(|p| p.value().as_u64())
+        .ok_or(RenderErrorReason::ParamNotFoundForIndex("inc", 0))?;
+    out.write(&(n + 1).to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iteration::context::ErrorCategory;
+    use crate::iteration::IterationError;
+    use tempfile::TempDir;
+
+    fn story() -> PrdUserStory {
+        PrdUserStory {
+            id: "US-001".to_string(),
+            title: "Add login".to_string(),
+            description: "Users can log in".to_string(),
+            acceptance_criteria: vec!["Form renders".into(), "Errors <shown>".into()],
+            priority: 2,
+            passes: false,
+            depends_on: vec![],
+            target_files: vec![],
+            read_files: vec![],
+            heavy: false,
+            agent: None,
+            model: None,
+            epic: None,
+            prerequisites: Vec::new(),
+            estimate: None,
+        }
+    }
+
+    fn variables(context: &IterationContext) -> PromptVariables {
+        PromptVariables::new(
+            &story(),
+            "shop",
+            "feature/login",
+            context,
+            BudgetVariables::new(None, PromptStrategy::Standard),
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_builtin_story_template() {
+        let context = IterationContext::new("US-001", 5);
+        let prompt = PromptTemplates::builtin()
+            .render_story(&variables(&context))
+            .unwrap();
+        assert_eq!(
+            prompt,
+            "# Implement User Story: US-001 - Add login\n\n\
+             ## Description\nUsers can log in\n\n\
+             ## Acceptance Criteria\n1. Form renders\n2. Errors <shown>\n\n\
+             ## Project Context\n\
+             - Project: shop\n\
+             - Branch: feature/login\n\
+             - Story Priority: 2\n\n\
+             ## Instructions\n\
+             1. Implement all acceptance criteria\n\
+             2. Ensure code compiles without errors (cargo check)\n\
+             3. Ensure no clippy warnings (cargo clippy -- -D warnings)\n\
+             4. Ensure proper formatting (cargo fmt)\n\
+             5. Keep changes focused and minimal\n"
+        );
+    }
+
+    #[test]
+    fn test_builtin_review_template() {
+        let variables = ReviewVariables::new(
+            &story(),
+            "shop",
+            2,
+            vec!["src/login.rs".to_string()],
+            "+fn login() {}".to_string(),
+        );
+        let prompt = PromptTemplates::builtin().render_review(&variables).unwrap();
+        assert!(prompt.starts_with("# Review User Story: US-001 - Add login\n"));
+        assert!(prompt.contains("1. Form renders\n2. Errors <shown>\n"));
+        assert!(prompt.contains("## Changes (iteration 2)\n```diff\n+fn login() {}\n```"));
+        assert!(prompt.contains("`VERDICT: APPROVE`"));
+    }
+
+    #[test]
+    fn test_builtin_merge_template() {
+        let variables = MergeVariables::new(
+            &story(),
+            "shop",
+            "ralph/US-001",
+            vec!["src/login.rs".to_string()],
+        );
+        let prompt = PromptTemplates::builtin().render_merge(&variables).unwrap();
+        assert!(prompt.starts_with("# Resolve Merge Conflicts: US-001 - Add login\n"));
+        assert!(prompt.contains("the `ralph/US-001` branch"));
+        assert!(prompt.contains("## Conflicted Files\n- src/login.rs\n"));
+    }
+
+    #[test]
+    fn test_builtin_reconcile_template() {
+        let story = story();
+        let variables = ReconcileVariables::new(
+            "shop",
+            &[&story],
+            vec!["login defined more than once: src/login.rs:1, src/login.rs:9".to_string()],
+            vec!["src/login.rs".to_string()],
+            "+fn login() {}".to_string(),
+        );
+        let prompt = PromptTemplates::builtin()
+            .render_reconcile(&variables)
+            .unwrap();
+        assert!(prompt.starts_with("# Fix Integration Issues: shop\n"));
+        assert!(prompt.contains("## Stories\n- US-001 - Add login\n"));
+        assert!(prompt.contains("## Affected Files\n- src/login.rs\n"));
+        assert!(prompt.contains("```diff\n+fn login() {}\n```"));
+    }
+
+    #[test]
+    fn test_project_templates_override_builtin() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join(PROMPTS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("story.hbs"),
+            "{{story.id}} try {{iteration}}/{{max_iterations}}\
+             {{#each failed_gates}} [{{this}}]{{/each}} {{budget.strategy}}\n{{> rules}}",
+        )
+        .unwrap();
+        std::fs::write(dir.join("rules.hbs"), "Use tabs.").unwrap();
+
+        let mut context = IterationContext::new("US-001", 5);
+        context.start_iteration(2);
+        context.record_error(
+            IterationError::new(1, ErrorCategory::Lint, "unused import").with_gate("clippy"),
+        );
+        let templates = PromptTemplates::load(root.path()).unwrap();
+        assert_eq!(templates.overrides().len(), 2);
+        assert_eq!(
+            templates.render_story(&variables(&context)).unwrap(),
+            "US-001 try 2/5 [clippy] standard\nUse tabs."
+        );
+
+        std::fs::write(dir.join("story.hbs"), "{{#if}}").unwrap();
+        assert!(matches!(
+            PromptTemplates::load(root.path()),
+            Err(PromptError::Template { .. })
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+//! Acceptance criteria verified against quality gate results.
+//!
+//! A story's criteria can name how they are verified: a test that must pass
+//! or a quality gate that must pass. After each iteration's gates the
+//! executor evaluates every criterion and records the outcome as evidence, so
+//! a story reports which criteria it met rather than a single pass/fail.
+
+use serde::Serialize;
+
+use crate::mcp::tools::load_prd::{AcceptanceCriterion, Verification};
+use crate::quality::{FailureCategory, GateResult};
+
+/// Evidence kind of per-criterion results.
+pub const CRITERIA_EVIDENCE_KIND: &str = "acceptance_criteria";
+
+/// Outcome of one acceptance criterion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CriterionStatus {
+    Passed,
+    Failed,
+    /// Manual, or its test or gate did not run
+    Unverified,
+}
+
+/// Result of one acceptance criterion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CriterionResult {
+    pub id: String,
+    pub description: String,
+    pub verify: Verification,
+    pub status: CriterionStatus,
+    /// Why the criterion has its status
+    pub detail: String,
+}
+
+/// Criterion results of one iteration, as recorded in evidence.
+#[derive(Debug, Clone, Serialize)]
+pub struct CriteriaReport<'a> {
+    pub story_id: &'a str,
+    pub iteration: u32,
+    pub passed: usize,
+    pub failed: usize,
+    pub unverified: usize,
+    pub criteria: &'a [CriterionResult],
+}
+
+impl<'a> CriteriaReport<'a> {
+    pub fn new(story_id: &'a str, iteration: u32, criteria: &'a [CriterionResult]) -> Self {
+        let count = |status| criteria.iter().filter(|c| c.status == status).count();
+        Self {
+            story_id,
+            iteration,
+            passed: count(CriterionStatus::Passed),
+            failed: count(CriterionStatus::Failed),
+            unverified: count(CriterionStatus::Unverified),
+            criteria,
+        }
+    }
+}
+
+/// Evaluate `criteria` against the gate results of an iteration.
+pub fn evaluate_criteria(
+    criteria: &[AcceptanceCriterion],
+    gate_results: &[GateResult],
+) -> Vec<CriterionResult> {
+    criteria
+        .iter()
+        .enumerate()
+        .map(|(index, criterion)| {
+            let (status, detail) = match &criterion.verify {
+                Verification::Manual => (CriterionStatus::Unverified, "verified manually".into()),
+                Verification::Gate { gate } => verify_gate(gate, gate_results),
+                Verification::Test { name } => verify_test(name, gate_results),
+            };
+            CriterionResult {
+                id: criterion.label(index),
+                description: criterion.description.clone(),
+                verify: criterion.verify.clone(),
+                status,
+                detail,
+            }
+        })
+        .collect()
+}
+
+fn verify_gate(gate: &str, gate_results: &[GateResult]) -> (CriterionStatus, String) {
+    match gate_results.iter().find(|result| result.gate_name == gate) {
+        Some(result) if result.passed => (CriterionStatus::Passed, result.message.clone()),
+        Some(result) => (CriterionStatus::Failed, result.message.clone()),
+        None => (
+            CriterionStatus::Unverified,
+            format!("gate '{}' did not run", gate),
+        ),
+    }
+}
+
+/// A test passes when every test gate that ran passed, or when the failing
+/// gates list their failed tests and it is not among them.
+fn verify_test(name: &str, gate_results: &[GateResult]) -> (CriterionStatus, String) {
+    let test_gates: Vec<&GateResult> = gate_results
+        .iter()
+        .filter(|result| result.gate_name.contains("test"))
+        .collect();
+    if test_gates.is_empty() {
+        return (CriterionStatus::Unverified, "no test gate ran".into());
+    }
+
+    for gate in &test_gates {
+        if let Some(failure) = gate.failures.iter().find(|failure| {
+            failure.category == FailureCategory::Test
+                && failure
+                    .error_code
+                    .as_deref()
+                    .is_some_and(|test| test.contains(name))
+        }) {
+            return (CriterionStatus::Failed, failure.message.clone());
+        }
+    }
+    let unexplained = test_gates.iter().find(|gate| {
+        !gate.passed
+            && !gate
+                .failures
+                .iter()
+                .any(|failure| failure.category == FailureCategory::Test)
+    });
+    match unexplained {
+        Some(gate) => (
+            CriterionStatus::Unverified,
+            format!(
+                "gate '{}' failed without naming its failed tests",
+                gate.gate_name
+            ),
+        ),
+        None => (
+            CriterionStatus::Passed,
+            format!(
+                "no failure of '{}' in {} test gate(s)",
+                name,
+                test_gates.len()
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quality::GateFailureDetail;
+
+    #[test]
+    fn test_evaluate_criteria() {
+        let criteria: Vec<AcceptanceCriterion> = serde_json::from_str(
+            r#"[
+                "Looks right",
+                {"id": "lint-clean", "description": "No lint", "verify": {"type": "gate", "gate": "lint"}},
+                {"description": "Login renders", "verify": {"type": "test", "name": "login_renders"}},
+                {"description": "Logout works", "verify": {"type": "test", "name": "logout_works"}},
+                {"description": "Audited", "verify": {"type": "gate", "gate": "security_audit"}}
+            ]"#,
+        )
+        .unwrap();
+        let gates = vec![
+            GateResult::pass("lint", "No clippy warnings found"),
+            GateResult {
+                failures: vec![GateFailureDetail::new(
+                    FailureCategory::Test,
+                    "Test failed: auth::logout_works",
+                )
+                .with_error_code("auth::logout_works")],
+                ..GateResult::fail("tests", "1 test failed", None, None)
+            },
+        ];
+
+        let results = evaluate_criteria(&criteria, &gates);
+        let statuses: Vec<(&str, CriterionStatus)> = results
+            .iter()
+            .map(|result| (result.id.as_str(), result.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("AC-1", CriterionStatus::Unverified),
+                ("lint-clean", CriterionStatus::Passed),
+                ("AC-3", CriterionStatus::Passed),
+                ("AC-4", CriterionStatus::Failed),
+                ("AC-5", CriterionStatus::Unverified),
+            ]
+        );
+
+        let report = CriteriaReport::new("US-1", 2, &results);
+        assert_eq!((report.passed, report.failed, report.unverified), (2, 1, 2));
+    }
+}
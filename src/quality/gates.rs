@@ -6,10 +6,14 @@
 // Allow dead_code for now - these types will be used in future stories
 #![allow(dead_code)]
 
-use crate::quality::Profile;
+use crate::evidence::{EvidenceWriter, OutputCapture, OutputCaptureConfig};
+use crate::quality::{
+    CoverageReportFormat, GateCommand, GateSeverity, ImpactedTestSelector, Profile, SandboxMode,
+    Suppressions, TestRunner, TestSelectionMode,
+};
+use crate::resources::ResourceLimits;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 /// JSON message format from `cargo clippy --message-format=json`.
@@ -65,6 +69,7 @@ struct ClippySpan {
 /// JSON message format from `cargo test --format=json` (unstable).
 ///
 /// Each line of cargo test's stdout is a separate JSON object with this structure.
+/// cargo-nextest emits the same structure with `--message-format libtest-json-plus`.
 #[derive(Debug, Deserialize)]
 struct TestMessage {
     /// Type of message (e.g., "suite", "test")
@@ -78,6 +83,154 @@ struct TestMessage {
     stdout: Option<String>,
     /// Failure message (for failed tests)
     message: Option<String>,
+    /// Execution time in seconds (for completed tests)
+    exec_time: Option<f64>,
+}
+
+/// JSON output from `semgrep --json`.
+#[derive(Debug, Deserialize)]
+struct SemgrepOutput {
+    /// Findings reported by the rules
+    #[serde(default)]
+    results: Vec<SemgrepFinding>,
+    /// Errors semgrep hit while scanning (e.g., unparsable files or bad rules)
+    #[serde(default)]
+    errors: Vec<serde_json::Value>,
+}
+
+/// A single semgrep finding.
+#[derive(Debug, Deserialize)]
+struct SemgrepFinding {
+    /// Rule identifier (e.g., "rust.lang.security.unsafe-usage")
+    check_id: String,
+    /// Path of the file containing the finding
+    path: String,
+    /// Start position of the finding
+    start: SemgrepPosition,
+    /// Rule message, severity, and metadata
+    extra: SemgrepExtra,
+}
+
+/// Source position in semgrep output.
+#[derive(Debug, Deserialize)]
+struct SemgrepPosition {
+    /// Line number (1-indexed)
+    line: u32,
+    /// Column number (1-indexed)
+    col: u32,
+}
+
+/// Extra information attached to a semgrep finding.
+#[derive(Debug, Deserialize)]
+struct SemgrepExtra {
+    /// Human-readable description of the finding
+    message: String,
+    /// Severity level (INFO, WARNING, ERROR)
+    #[serde(default)]
+    severity: Option<String>,
+    /// Suggested fix, if the rule provides one
+    #[serde(default)]
+    fix: Option<String>,
+    /// Rule metadata; `source` links to the rule documentation
+    #[serde(default)]
+    metadata: serde_json::Value,
+}
+
+/// A single hunk from `cargo fmt --check` textual diff output.
+#[derive(Debug, Clone)]
+struct FormatHunk {
+    /// File the hunk applies to
+    file: String,
+    /// Line number from the "Diff in" header, if present
+    header_line: Option<u32>,
+    /// Current line in the original file while walking the hunk body
+    cursor: u32,
+    /// First and last original lines touched by the hunk
+    changed: Option<(u32, u32)>,
+    /// Whether the previous body lines removed original lines
+    replacing: bool,
+    /// Replacement lines rustfmt expects
+    expected: Vec<String>,
+}
+
+impl FormatHunk {
+    /// Start a hunk from the header's file and line.
+    fn new(file: String, header_line: Option<u32>) -> Self {
+        Self {
+            file,
+            header_line,
+            cursor: header_line.unwrap_or(1),
+            changed: None,
+            replacing: false,
+            expected: Vec::new(),
+        }
+    }
+
+    /// Record that `line` of the original file is affected by the hunk.
+    fn mark_changed(&mut self, line: u32) {
+        self.changed = Some(match self.changed {
+            Some((first, last)) => (first.min(line), last.max(line)),
+            None => (line, line),
+        });
+    }
+
+    /// Consume one line of the hunk body (prefixed by ' ', '-' or '+').
+    fn push_line(&mut self, line: &str) {
+        if line.starts_with('-') {
+            self.mark_changed(self.cursor);
+            self.cursor += 1;
+            self.replacing = true;
+        } else if let Some(added) = line.strip_prefix('+') {
+            // Additions after removals replace those lines; otherwise they are inserted here
+            if !self.replacing {
+                self.mark_changed(self.cursor);
+            }
+            self.expected.push(added.to_string());
+        } else {
+            self.cursor += 1;
+            self.replacing = false;
+        }
+    }
+
+    /// Convert the hunk into a structured failure.
+    fn into_failure(self) -> GateFailureDetail {
+        let mut detail = GateFailureDetail::new(
+            FailureCategory::Format,
+            format!("File needs formatting: {}", self.file),
+        )
+        .with_file(self.file.clone());
+
+        let Some((first, last)) = self.changed else {
+            if let Some(line) = self.header_line {
+                detail = detail.with_line(line);
+            }
+            return detail.with_suggestion("Run cargo fmt to fix".to_string());
+        };
+
+        let range = if first == last {
+            format!("line {}", first)
+        } else {
+            format!("lines {}-{}", first, last)
+        };
+        detail.message = format!("File needs formatting: {} ({})", self.file, range);
+        let suggestion = if self.expected.is_empty() {
+            format!("Remove {}", range)
+        } else {
+            format!("Replace {} with:\n{}", range, self.expected.join("\n"))
+        };
+        detail.with_line(first).with_suggestion(suggestion)
+    }
+}
+
+/// Line coverage for a single source file, as reported by llvm-cov.
+#[derive(Debug, Clone)]
+struct FileCoverage {
+    /// Path of the source file as reported by the coverage tool
+    path: String,
+    /// Number of instrumented lines
+    total_lines: u64,
+    /// Number of instrumented lines that were executed
+    covered_lines: u64,
 }
 
 /// Category of a quality gate failure.
@@ -99,6 +252,8 @@ pub enum FailureCategory {
     Security,
     /// Code coverage below threshold
     Coverage,
+    /// Problem raised by a reviewer agent
+    Review,
 }
 
 /// Structured details about a quality gate failure.
@@ -206,6 +361,37 @@ impl GateFailureDetail {
         self.doc_url = Some(doc_url.into());
         self
     }
+
+    /// One-line rendering for agent prompts:
+    /// `file:line:column [code] message (fix: suggestion)`.
+    ///
+    /// Only the first line of the message and suggestion is kept, so the
+    /// same failure always renders the same way regardless of the tool's
+    /// surrounding output.
+    pub fn compact(&self) -> String {
+        let first_line = |text: &str| text.lines().next().unwrap_or("").trim().to_string();
+        let mut line = String::new();
+        if let Some(ref file) = self.file {
+            line.push_str(file);
+            if let Some(l) = self.line {
+                line.push_str(&format!(":{}", l));
+                if let Some(c) = self.column {
+                    line.push_str(&format!(":{}", c));
+                }
+            }
+            line.push(' ');
+        }
+        if let Some(ref code) = self.error_code {
+            line.push_str(&format!("[{}] ", code));
+        }
+        line.push_str(&first_line(&self.message));
+        if let Some(suggestion) = self.suggestion.as_deref().map(first_line) {
+            if !suggestion.is_empty() {
+                line.push_str(&format!(" (fix: {})", suggestion));
+            }
+        }
+        line
+    }
 }
 
 /// Progress state for a quality gate.
@@ -315,6 +501,12 @@ pub struct GateResult {
     /// Structured failure details for programmatic access
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub failures: Vec<GateFailureDetail>,
+    /// Severity of the gate; warning failures do not block story completion
+    #[serde(default)]
+    pub severity: GateSeverity,
+    /// Known issues the gate found but tolerated, per `.ralph/suppressions.toml`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressed: Vec<GateFailureDetail>,
 }
 
 impl GateResult {
@@ -326,6 +518,8 @@ impl GateResult {
             message: message.into(),
             details: None,
             failures: Vec::new(),
+            severity: GateSeverity::Blocking,
+            suppressed: Vec::new(),
         }
     }
 
@@ -349,6 +543,8 @@ impl GateResult {
             message: message.into(),
             details,
             failures: failures.unwrap_or_default(),
+            severity: GateSeverity::Blocking,
+            suppressed: Vec::new(),
         }
     }
 
@@ -360,30 +556,204 @@ impl GateResult {
             message: format!("Skipped: {}", reason.into()),
             details: None,
             failures: Vec::new(),
+            severity: GateSeverity::Blocking,
+            suppressed: Vec::new(),
+        }
+    }
+}
+
+impl GateResult {
+    /// Set the severity of this gate result.
+    pub fn with_severity(mut self, severity: GateSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Whether this result blocks story completion (a failed blocking gate).
+    pub fn is_blocking_failure(&self) -> bool {
+        !self.passed && self.severity == GateSeverity::Blocking
+    }
+
+    /// Whether this result is a non-blocking warning (a failed warning gate).
+    pub fn is_warning(&self) -> bool {
+        !self.passed && self.severity == GateSeverity::Warning
+    }
+}
+
+/// Aggregated outcome of a gate run, used for machine-readable output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateReport {
+    /// Name of the profile the gates were run against
+    pub profile: String,
+    /// Whether no blocking gate failed
+    pub passed: bool,
+    /// Human-readable summary of the run
+    pub summary: String,
+    /// Results of each gate, in execution order
+    pub results: Vec<GateResult>,
+}
+
+impl GateReport {
+    /// Build a report from the results of a gate run.
+    pub fn new(profile: impl Into<String>, results: Vec<GateResult>) -> Self {
+        Self {
+            profile: profile.into(),
+            passed: QualityGateChecker::all_passed(&results),
+            summary: QualityGateChecker::summary(&results),
+            results,
         }
     }
 }
 
+/// Evidence record kind used for persisted gate results.
+pub const GATE_EVIDENCE_KIND: &str = "quality_gate";
+
+/// Evidence payload for a single gate result.
+#[derive(Serialize)]
+struct GateEvidence<'a> {
+    /// Step (story) the gate ran for
+    step_id: &'a str,
+    /// Whether the gate was skipped because it is disabled in the profile
+    skipped: bool,
+    /// Wall-clock time the gate took, in milliseconds
+    duration_ms: u64,
+    /// The gate result itself
+    #[serde(flatten)]
+    result: &'a GateResult,
+}
+
 /// A checker that runs quality gates based on a profile configuration.
 pub struct QualityGateChecker {
     /// The quality profile to check against
     profile: Profile,
     /// The root directory of the project to check
     project_root: PathBuf,
+    /// Directory where gate artifacts (e.g. coverage reports) are written
+    artifacts_dir: Option<PathBuf>,
+    /// Files the current story targets, used for impacted-test selection
+    target_files: Vec<String>,
+    /// Evidence writer that records every gate result, if configured
+    evidence: Option<EvidenceWriter>,
+    /// Step (story) identifier attached to recorded gate evidence
+    evidence_step: String,
+    /// Resource limits applied to gate subprocesses
+    resource_limits: ResourceLimits,
+    /// Scratch directory exposed to gate subprocesses as `TMPDIR`
+    scratch_dir: Option<PathBuf>,
+    /// Caps on gate output kept in result details
+    output_capture: OutputCaptureConfig,
+    /// Known issues that do not fail the gates
+    suppressions: Suppressions,
 }
 
 impl QualityGateChecker {
     /// Create a new quality gate checker.
     ///
+    /// Known issues listed in the project's `.ralph/suppressions.toml` are
+    /// suppressed.
+    ///
     /// # Arguments
     ///
     /// * `profile` - The quality profile containing gate configurations
     /// * `project_root` - The root directory of the project to check
     pub fn new(profile: Profile, project_root: impl Into<PathBuf>) -> Self {
+        let project_root = project_root.into();
+        let suppressions = Suppressions::load(&project_root).unwrap_or_else(|e| {
+            eprintln!("Warning: {}; no known issues are suppressed", e);
+            Suppressions::default()
+        });
         Self {
             profile,
-            project_root: project_root.into(),
+            project_root,
+            artifacts_dir: None,
+            target_files: Vec::new(),
+            evidence: None,
+            evidence_step: String::new(),
+            resource_limits: ResourceLimits::default(),
+            scratch_dir: None,
+            output_capture: OutputCaptureConfig::default(),
+            suppressions,
+        }
+    }
+
+    /// Replace the known issues that do not fail the gates.
+    pub fn with_suppressions(mut self, suppressions: Suppressions) -> Self {
+        self.suppressions = suppressions;
+        self
+    }
+
+    /// Apply per-worker resource limits to gate subprocesses.
+    ///
+    /// When a scratch directory is given it is used as `TMPDIR` for gates run
+    /// on the host.
+    pub fn with_resource_limits(
+        mut self,
+        limits: ResourceLimits,
+        scratch_dir: Option<PathBuf>,
+    ) -> Self {
+        self.resource_limits = limits;
+        self.scratch_dir = scratch_dir;
+        self
+    }
+
+    /// Record every gate result (including skips and timings) as evidence.
+    ///
+    /// Results are written under the `quality_gate` evidence kind, tagged
+    /// with `step_id` (typically the story ID).
+    pub fn with_evidence(mut self, writer: EvidenceWriter, step_id: impl Into<String>) -> Self {
+        self.evidence = Some(writer);
+        self.evidence_step = step_id.into();
+        self
+    }
+
+    /// Cap the gate output kept in result details.
+    ///
+    /// Longer details keep only their most recent lines; the full output is
+    /// written to `<gate>-output.log` in the artifacts directory, if set.
+    pub fn with_output_capture(mut self, config: OutputCaptureConfig) -> Self {
+        self.output_capture = config;
+        self
+    }
+
+    /// Set the story's target files for impacted-test selection.
+    pub fn with_target_files(mut self, target_files: Vec<String>) -> Self {
+        self.target_files = target_files;
+        self
+    }
+
+    /// Create a gate command rooted at the project, sandboxed per the profile.
+    fn command(&self, program: &str) -> GateCommand {
+        let mut command = GateCommand::new(program, &self.project_root, &self.profile.sandbox);
+        command.limits(&self.resource_limits);
+        if let Some(ref scratch_dir) = self.scratch_dir {
+            if self.profile.sandbox.mode != SandboxMode::Container {
+                command.env("TMPDIR", scratch_dir.to_string_lossy());
+            }
         }
+        command
+    }
+
+    /// Whether the test gate runs only impacted tests.
+    ///
+    /// When true, callers should confirm a passing iteration with
+    /// [`check_tests_full`](Self::check_tests_full) before accepting it.
+    pub fn uses_test_selection(&self) -> bool {
+        self.profile.testing.test_selection == TestSelectionMode::Impacted
+            && !self.target_files.is_empty()
+    }
+
+    /// Set the directory where gate artifacts are exported.
+    ///
+    /// Coverage reports configured via `testing.coverage_reports` are only
+    /// written when an artifacts directory is set.
+    pub fn with_artifacts_dir(mut self, artifacts_dir: impl Into<PathBuf>) -> Self {
+        self.artifacts_dir = Some(artifacts_dir.into());
+        self
+    }
+
+    /// Get the artifacts directory, if configured.
+    pub fn artifacts_dir(&self) -> Option<&PathBuf> {
+        self.artifacts_dir.as_ref()
     }
 
     /// Get the profile being used for quality checks.
@@ -441,19 +811,25 @@ impl QualityGateChecker {
     /// Run cargo-llvm-cov and parse the coverage percentage.
     fn run_llvm_cov(&self) -> Option<GateResult> {
         // Check if cargo-llvm-cov is installed
-        let check_installed = Command::new("cargo")
+        let check_installed = self
+            .command("cargo")
             .args(["llvm-cov", "--version"])
-            .current_dir(&self.project_root)
             .output();
 
         if check_installed.is_err() || !check_installed.unwrap().status.success() {
             return None; // Tool not installed
         }
 
+        let result = self.run_llvm_cov_gate()?;
+        Some(self.export_llvm_cov_reports(result))
+    }
+
+    /// Run cargo-llvm-cov with JSON output and evaluate it against the thresholds.
+    fn run_llvm_cov_gate(&self) -> Option<GateResult> {
         // Run cargo llvm-cov with JSON output for parsing
-        let output = Command::new("cargo")
+        let output = self
+            .command("cargo")
             .args(["llvm-cov", "--json", "--quiet"])
-            .current_dir(&self.project_root)
             .output();
 
         match output {
@@ -470,6 +846,13 @@ impl QualityGateChecker {
                     ));
                 }
 
+                // Apply exclusions and path-scoped thresholds against per-file data
+                if self.has_coverage_path_rules() {
+                    if let Some(files) = Self::parse_llvm_cov_files(&stdout) {
+                        return Some(self.evaluate_file_coverage(&files, "cargo-llvm-cov"));
+                    }
+                }
+
                 // Parse the JSON output for coverage percentage
                 if let Some(coverage) = Self::parse_llvm_cov_json(&stdout) {
                     Some(self.evaluate_coverage(coverage, "cargo-llvm-cov"))
@@ -487,12 +870,90 @@ impl QualityGateChecker {
         }
     }
 
+    /// Get the coverage report export directory, if reports are configured.
+    fn coverage_report_dir(&self) -> Option<&Path> {
+        if self.profile.testing.coverage_reports.is_empty() {
+            return None;
+        }
+        self.artifacts_dir.as_deref()
+    }
+
+    /// Export configured coverage reports from the last llvm-cov run.
+    ///
+    /// Uses `cargo llvm-cov report`, which reuses the profile data collected by
+    /// the gate run instead of re-running the test suite.
+    fn export_llvm_cov_reports(&self, result: GateResult) -> GateResult {
+        let Some(dir) = self.coverage_report_dir() else {
+            return result;
+        };
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            return Self::with_report_note(
+                result,
+                format!("Failed to create coverage report directory: {}", e),
+            );
+        }
+
+        let mut written = Vec::new();
+        let mut errors = Vec::new();
+        for format in &self.profile.testing.coverage_reports {
+            let path = dir.join(format.file_name());
+            let format_flag = match format {
+                CoverageReportFormat::Lcov => "--lcov",
+                CoverageReportFormat::Cobertura => "--cobertura",
+            };
+            let output = self
+                .command("cargo")
+                .args(["llvm-cov", "report", format_flag, "--output-path"])
+                .arg(&path)
+                .output();
+            match output {
+                Ok(output) if output.status.success() => written.push(path),
+                Ok(output) => errors.push(format!(
+                    "{}: {}",
+                    format.file_name(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )),
+                Err(e) => errors.push(format!("{}: {}", format.file_name(), e)),
+            }
+        }
+
+        Self::with_report_note(result, Self::format_report_note(&written, &errors))
+    }
+
+    /// Build a details note listing exported coverage reports and export errors.
+    fn format_report_note(written: &[PathBuf], errors: &[String]) -> String {
+        let mut note = String::new();
+        if !written.is_empty() {
+            let paths: Vec<String> = written.iter().map(|p| p.display().to_string()).collect();
+            note.push_str(&format!("Coverage reports written: {}", paths.join(", ")));
+        }
+        if !errors.is_empty() {
+            if !note.is_empty() {
+                note.push('\n');
+            }
+            note.push_str(&format!(
+                "Coverage report export failed: {}",
+                errors.join("; ")
+            ));
+        }
+        note
+    }
+
+    /// Append a coverage report note to a gate result's details.
+    fn with_report_note(mut result: GateResult, note: String) -> GateResult {
+        if note.is_empty() {
+            return result;
+        }
+        result.details = Some(match result.details.take() {
+            Some(details) => format!("{}\n{}", details, note),
+            None => note,
+        });
+        result
+    }
+
     /// Run cargo-llvm-cov with summary output and parse the percentage.
     fn run_llvm_cov_summary(&self) -> Option<GateResult> {
-        let output = Command::new("cargo")
-            .args(["llvm-cov", "--quiet"])
-            .current_dir(&self.project_root)
-            .output();
+        let output = self.command("cargo").args(["llvm-cov", "--quiet"]).output();
 
         match output {
             Ok(output) => {
@@ -547,23 +1008,174 @@ impl QualityGateChecker {
         }
     }
 
+    /// Parse llvm-cov JSON output into per-file line coverage.
+    ///
+    /// Format: `{ "data": [{ "files": [{ "filename": "...", "summary": { "lines": { "count": N, "covered": M } } }] }] }`
+    fn parse_llvm_cov_files(json_str: &str) -> Option<Vec<FileCoverage>> {
+        let json = serde_json::from_str::<serde_json::Value>(json_str).ok()?;
+        let files = json
+            .get("data")
+            .and_then(|d| d.get(0))
+            .and_then(|d| d.get("files"))
+            .and_then(|f| f.as_array())?;
+
+        let coverage = files
+            .iter()
+            .filter_map(|file| {
+                let filename = file.get("filename")?.as_str()?;
+                let lines = file.get("summary")?.get("lines")?;
+                Some(FileCoverage {
+                    path: filename.to_string(),
+                    total_lines: lines.get("count")?.as_u64()?,
+                    covered_lines: lines.get("covered")?.as_u64()?,
+                })
+            })
+            .collect();
+
+        Some(coverage)
+    }
+
+    /// Whether the profile configures exclusions or path-scoped thresholds.
+    fn has_coverage_path_rules(&self) -> bool {
+        !self.profile.testing.coverage_exclude.is_empty()
+            || !self.profile.testing.path_thresholds.is_empty()
+    }
+
+    /// Get a file path relative to the project root for glob matching.
+    fn relative_coverage_path<'a>(&self, path: &'a str) -> &'a str {
+        let root = self.project_root.to_string_lossy();
+        path.strip_prefix(root.as_ref())
+            .map(|p| p.trim_start_matches('/'))
+            .unwrap_or(path)
+    }
+
+    /// Check whether a relative path matches any of the given glob patterns.
+    fn matches_any_glob(path: &str, patterns: &[String]) -> bool {
+        patterns.iter().any(|pattern| {
+            glob::Pattern::new(pattern)
+                .map(|p| p.matches(path))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Compute aggregate line coverage percentage for a set of files.
+    ///
+    /// Returns `None` if the files contain no instrumented lines.
+    fn aggregate_coverage<'a>(files: impl Iterator<Item = &'a FileCoverage>) -> Option<f64> {
+        let (covered, total) = files.fold((0u64, 0u64), |(covered, total), f| {
+            (covered + f.covered_lines, total + f.total_lines)
+        });
+        if total == 0 {
+            None
+        } else {
+            Some(covered as f64 / total as f64 * 100.0)
+        }
+    }
+
+    /// Evaluate per-file coverage against the global and path-scoped thresholds.
+    ///
+    /// Files matching `coverage_exclude` are dropped before any threshold is
+    /// computed. Path thresholds with no matching files are ignored.
+    fn evaluate_file_coverage(&self, files: &[FileCoverage], tool_name: &str) -> GateResult {
+        let testing = &self.profile.testing;
+        let included: Vec<(&str, &FileCoverage)> = files
+            .iter()
+            .map(|f| (self.relative_coverage_path(&f.path), f))
+            .filter(|(path, _)| !Self::matches_any_glob(path, &testing.coverage_exclude))
+            .collect();
+        let excluded_count = files.len() - included.len();
+
+        let total = Self::aggregate_coverage(included.iter().map(|(_, f)| *f)).unwrap_or(100.0);
+        let mut result = self.evaluate_coverage(total, tool_name);
+
+        let mut failures = Vec::new();
+        for rule in &testing.path_thresholds {
+            let patterns = [rule.path.clone()];
+            let matching = included
+                .iter()
+                .filter(|(path, _)| Self::matches_any_glob(path, &patterns))
+                .map(|(_, f)| *f);
+            let Some(coverage) = Self::aggregate_coverage(matching) else {
+                continue;
+            };
+            if coverage < rule.threshold as f64 {
+                failures.push(
+                    GateFailureDetail::new(
+                        FailureCategory::Coverage,
+                        format!(
+                            "Coverage {:.2}% for '{}' is below threshold of {}%",
+                            coverage, rule.path, rule.threshold
+                        ),
+                    )
+                    .with_file(&rule.path)
+                    .with_suggestion(format!("Add tests covering files under '{}'", rule.path)),
+                );
+            }
+        }
+
+        if excluded_count > 0 {
+            result.message.push_str(&format!(
+                " ({} file{} excluded)",
+                excluded_count,
+                if excluded_count == 1 { "" } else { "s" }
+            ));
+        }
+
+        if !failures.is_empty() {
+            let mut details = format!("{} path threshold(s) not met:\n", failures.len());
+            for (i, failure) in failures.iter().enumerate() {
+                details.push_str(&format!("{}. {}\n", i + 1, failure.message));
+            }
+            if let Some(existing) = result.details.take() {
+                details.push_str(&format!("\n{}", existing));
+            }
+            if result.passed {
+                result.message = format!(
+                    "{} path coverage threshold{} not met",
+                    failures.len(),
+                    if failures.len() == 1 { "" } else { "s" }
+                );
+            }
+            result.passed = false;
+            result.details = Some(details);
+            result.failures.extend(failures);
+        }
+
+        result
+    }
+
     /// Run cargo-tarpaulin and parse the coverage percentage.
     fn run_tarpaulin(&self) -> Option<GateResult> {
         // Check if cargo-tarpaulin is installed
-        let check_installed = Command::new("cargo")
+        let check_installed = self
+            .command("cargo")
             .args(["tarpaulin", "--version"])
-            .current_dir(&self.project_root)
             .output();
 
         if check_installed.is_err() || !check_installed.unwrap().status.success() {
             return None; // Tool not installed
         }
 
-        // Run cargo tarpaulin
-        let output = Command::new("cargo")
-            .args(["tarpaulin", "--skip-clean", "--out", "Stdout"])
-            .current_dir(&self.project_root)
-            .output();
+        // Run cargo tarpaulin, passing exclusions through to the tool
+        let mut command = self.command("cargo");
+        command.args(["tarpaulin", "--skip-clean", "--out", "Stdout"]);
+        for pattern in &self.profile.testing.coverage_exclude {
+            command.args(["--exclude-files", pattern]);
+        }
+        let report_dir = self.coverage_report_dir().map(Path::to_path_buf);
+        if let Some(ref dir) = report_dir {
+            if std::fs::create_dir_all(dir).is_ok() {
+                for format in &self.profile.testing.coverage_reports {
+                    let out = match format {
+                        CoverageReportFormat::Lcov => "Lcov",
+                        CoverageReportFormat::Cobertura => "Xml",
+                    };
+                    command.args(["--out", out]);
+                }
+                command.arg("--output-dir").arg(dir);
+            }
+        }
+        let output = command.output();
 
         match output {
             Ok(output) => {
@@ -573,11 +1185,26 @@ impl QualityGateChecker {
                 // tarpaulin returns exit code 0 even on low coverage
                 // Parse the output for coverage percentage
                 // Format: "XX.XX% coverage"
-                if let Some(coverage) = Self::parse_coverage_percentage(&stdout) {
-                    Some(self.evaluate_coverage(coverage, "cargo-tarpaulin"))
-                } else if let Some(coverage) = Self::parse_coverage_percentage(&stderr) {
+                let coverage = Self::parse_coverage_percentage(&stdout)
                     // Sometimes tarpaulin outputs to stderr
-                    Some(self.evaluate_coverage(coverage, "cargo-tarpaulin"))
+                    .or_else(|| Self::parse_coverage_percentage(&stderr));
+                if let Some(coverage) = coverage {
+                    let result = self.evaluate_coverage(coverage, "cargo-tarpaulin");
+                    let written: Vec<PathBuf> = report_dir
+                        .iter()
+                        .flat_map(|dir| {
+                            self.profile
+                                .testing
+                                .coverage_reports
+                                .iter()
+                                .map(move |format| dir.join(format.file_name()))
+                        })
+                        .filter(|path| path.exists())
+                        .collect();
+                    Some(Self::with_report_note(
+                        result,
+                        Self::format_report_note(&written, &[]),
+                    ))
                 } else {
                     Some(GateResult::fail(
                         "coverage",
@@ -648,10 +1275,92 @@ impl QualityGateChecker {
         }
     }
 
+    /// Check that the project compiles using cargo check.
+    ///
+    /// Runs `cargo check --message-format=json` so pure compilation errors are
+    /// reported quickly and categorized as type errors rather than lint findings.
+    ///
+    /// # Returns
+    ///
+    /// A `GateResult` indicating whether the project compiles.
+    pub fn check_types(&self) -> GateResult {
+        if !self.profile.ci.type_check {
+            return GateResult::skipped("typecheck", "Type checking not enabled in profile");
+        }
+
+        let output = self
+            .command("cargo")
+            .args(["check", "--all-targets", "--message-format=json"])
+            .output();
+
+        match output {
+            Ok(output) => {
+                if output.status.success() {
+                    GateResult::pass("typecheck", "Project compiles without errors")
+                } else {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let mut failures =
+                        Self::parse_compiler_json(&stdout, FailureCategory::TypeCheck, false);
+                    if failures.is_empty() {
+                        failures =
+                            Self::parse_compiler_text(&stderr, FailureCategory::TypeCheck, false);
+                    }
+                    let details = Self::format_clippy_summary(&failures);
+                    GateResult::fail(
+                        "typecheck",
+                        "Compilation failed",
+                        Some(details),
+                        Some(failures),
+                    )
+                }
+            }
+            Err(e) => GateResult::fail(
+                "typecheck",
+                "Failed to run cargo check",
+                Some(format!("Error: {}. Is cargo installed?", e)),
+                None,
+            ),
+        }
+    }
+
+    /// Run the lint gate unless the type-check gate already failed.
+    ///
+    /// Clippy would only repeat the compilation errors, so it is skipped instead.
+    fn check_lint_after(&self, typecheck: &GateResult) -> GateResult {
+        if !typecheck.passed {
+            return GateResult::skipped("lint", "Type check failed; fix compilation errors first");
+        }
+        self.check_lint()
+    }
+
+    /// Lint-level flags passed to clippy after `--`.
+    ///
+    /// Warnings are denied (`-D warnings`) unless the profile sets `lint_allow_warnings`,
+    /// followed by the profile's `-W`, `-D` and `-A` lints and any extra flags.
+    pub fn clippy_args(&self) -> Vec<String> {
+        let ci = &self.profile.ci;
+        let mut args = Vec::new();
+        if !ci.lint_allow_warnings {
+            args.extend(["-D".to_string(), "warnings".to_string()]);
+        }
+        for (flag, lints) in [
+            ("-W", &ci.lint_warn),
+            ("-D", &ci.lint_deny),
+            ("-A", &ci.lint_allow),
+        ] {
+            for lint in lints {
+                args.extend([flag.to_string(), lint.clone()]);
+            }
+        }
+        args.extend(ci.lint_flags.iter().cloned());
+        args
+    }
+
     /// Check code linting using cargo clippy.
     ///
-    /// Runs `cargo clippy --message-format=json -- -D warnings` which treats all warnings as errors
-    /// and outputs structured JSON for parsing.
+    /// Runs `cargo clippy --message-format=json` with the profile's lint levels (see
+    /// [`clippy_args`](Self::clippy_args)) and outputs structured JSON for parsing.
     ///
     /// # Returns
     ///
@@ -661,9 +1370,10 @@ impl QualityGateChecker {
             return GateResult::skipped("lint", "Lint checking not enabled in profile");
         }
 
-        let output = Command::new("cargo")
-            .args(["clippy", "--message-format=json", "--", "-D", "warnings"])
-            .current_dir(&self.project_root)
+        let output = self
+            .command("cargo")
+            .args(["clippy", "--message-format=json", "--"])
+            .args(self.clippy_args())
             .output();
 
         match output {
@@ -698,6 +1408,8 @@ impl QualityGateChecker {
     ///
     /// Runs `cargo test` and parses the output to extract test failure details.
     /// When unit_tests is enabled in the profile, this gate will run.
+    /// When the profile uses impacted test selection and target files are set,
+    /// only the tests owning those files are run.
     ///
     /// # Returns
     ///
@@ -707,24 +1419,162 @@ impl QualityGateChecker {
             return GateResult::skipped("tests", "Unit testing not enabled in profile");
         }
 
-        let output = Command::new("cargo")
-            .args(["test", "--no-fail-fast"])
-            .current_dir(&self.project_root)
-            .output();
+        if self.uses_test_selection() {
+            let selection = ImpactedTestSelector::load(&self.project_root)
+                .and_then(|selector| selector.select(&self.target_files));
+            if let Some(selection) = selection {
+                return self.run_cargo_test(
+                    "tests",
+                    &selection.cargo_args(),
+                    Some(&selection.describe()),
+                );
+            }
+        }
+
+        self.run_cargo_test("tests", &[], None)
+    }
+
+    /// Check tests by running the full test suite, ignoring test selection.
+    ///
+    /// Used for the final gate pass when iterations ran only impacted tests.
+    pub fn check_tests_full(&self) -> GateResult {
+        if !self.profile.testing.unit_tests {
+            return GateResult::skipped("tests", "Unit testing not enabled in profile");
+        }
+
+        self.run_cargo_test("tests", &[], None)
+    }
+
+    /// Whether cargo-nextest is installed.
+    fn nextest_available(&self) -> bool {
+        self.command("cargo")
+            .args(["nextest", "--version"])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Check integration tests separately from unit tests.
+    ///
+    /// Runs `cargo test --test '*'` (or the profile's `integration_test_command`)
+    /// and parses the output to extract test failure details.
+    /// When integration_tests is enabled in the profile, this gate will run.
+    ///
+    /// # Returns
+    ///
+    /// A `GateResult` indicating whether all integration tests passed.
+    pub fn check_integration_tests(&self) -> GateResult {
+        if !self.profile.testing.integration_tests {
+            return GateResult::skipped(
+                "integration_tests",
+                "Integration testing not enabled in profile",
+            );
+        }
+
+        match self.profile.testing.integration_test_command {
+            Some(ref command) => {
+                let output = self.command("sh").args(["-c", command]).output();
+                Self::evaluate_test_output("integration_tests", output, None, command)
+            }
+            None => self.run_cargo_test(
+                "integration_tests",
+                &["--test".to_string(), "*".to_string()],
+                None,
+            ),
+        }
+    }
+
+    /// Run the test suite with extra arguments and build the gate result.
+    ///
+    /// Uses cargo-nextest when the profile's test runner selects it and it is
+    /// installed, and `cargo test` otherwise. `scope` describes the test
+    /// selection, if the run was narrowed.
+    fn run_cargo_test(
+        &self,
+        gate_name: &str,
+        extra_args: &[String],
+        scope: Option<&str>,
+    ) -> GateResult {
+        let use_nextest = match self.profile.testing.test_runner {
+            TestRunner::Cargo => false,
+            TestRunner::Auto => self.nextest_available(),
+            TestRunner::Nextest => {
+                if !self.nextest_available() {
+                    return GateResult::fail(
+                        gate_name,
+                        "cargo-nextest is not installed",
+                        Some("Install cargo-nextest: cargo install cargo-nextest".to_string()),
+                        None,
+                    );
+                }
+                true
+            }
+        };
+
+        let mut command = self.command("cargo");
+        if use_nextest {
+            // libtest-json-plus output is parsed like `cargo test --format=json`
+            command
+                .args([
+                    "nextest",
+                    "run",
+                    "--no-fail-fast",
+                    "--message-format",
+                    "libtest-json-plus",
+                ])
+                .env("NEXTEST_EXPERIMENTAL_LIBTEST_JSON", "1");
+            if self.profile.testing.test_retries > 0 {
+                command.args(["--retries", &self.profile.testing.test_retries.to_string()]);
+            }
+        } else {
+            command.args(["test", "--no-fail-fast"]);
+        }
+        let runner = if use_nextest {
+            "cargo nextest"
+        } else {
+            "cargo test"
+        };
+
+        let output = command.args(extra_args).output();
 
+        Self::evaluate_test_output(gate_name, output, scope, runner)
+    }
+
+    /// Build a test gate result from the output of a test run.
+    ///
+    /// `runner` names the command that was run, for error messages.
+    fn evaluate_test_output(
+        gate_name: &str,
+        output: std::io::Result<std::process::Output>,
+        scope: Option<&str>,
+        runner: &str,
+    ) -> GateResult {
         match output {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 let stderr = String::from_utf8_lossy(&output.stderr);
+                let timings = Self::format_test_timings(&Self::slowest_tests(&stdout));
 
                 if output.status.success() {
-                    GateResult::pass("tests", "All tests passed")
+                    let mut result = match scope {
+                        Some(scope) => GateResult::pass(
+                            gate_name,
+                            format!("All impacted tests passed ({})", scope),
+                        ),
+                        None => GateResult::pass(gate_name, "All tests passed"),
+                    };
+                    result.details = timings;
+                    result
                 } else {
                     // Extract structured failure details from output
                     let failures = Self::extract_test_failures(&stdout, &stderr);
-                    let details = Self::format_test_summary(&failures);
+                    let mut details = Self::format_test_summary(&failures);
+                    if let Some(timings) = timings {
+                        details.push('\n');
+                        details.push_str(&timings);
+                    }
                     GateResult::fail(
-                        "tests",
+                        gate_name,
                         format!(
                             "{} test{} failed",
                             failures.len(),
@@ -736,14 +1586,53 @@ impl QualityGateChecker {
                 }
             }
             Err(e) => GateResult::fail(
-                "tests",
-                "Failed to run cargo test",
+                gate_name,
+                format!("Failed to run {}", runner),
                 Some(format!("Error: {}", e)),
                 None,
             ),
         }
     }
 
+    /// Maximum number of slow tests listed in the test gate details.
+    const MAX_SLOW_TESTS: usize = 5;
+
+    /// Collect the slowest tests from JSON test output, slowest first.
+    ///
+    /// Only available when the runner reports `exec_time` (nextest, or
+    /// `cargo test` with `--report-time`).
+    fn slowest_tests(stdout: &str) -> Vec<(String, f64)> {
+        let mut timings: Vec<(String, f64)> = stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str::<TestMessage>(line).ok())
+            .filter(|msg| msg.msg_type == "test")
+            .filter_map(|msg| Some((Self::display_test_name(&msg.name?), msg.exec_time?)))
+            .collect();
+        timings.sort_by(|a, b| b.1.total_cmp(&a.1));
+        timings.truncate(Self::MAX_SLOW_TESTS);
+        timings
+    }
+
+    /// Format per-test timings for the details field.
+    fn format_test_timings(timings: &[(String, f64)]) -> Option<String> {
+        if timings.is_empty() {
+            return None;
+        }
+
+        let mut summary = "Slowest tests:\n".to_string();
+        for (name, secs) in timings {
+            summary.push_str(&format!("  {:.2}s {}\n", secs, name));
+        }
+        Some(summary)
+    }
+
+    /// Strip the nextest binary prefix (`crate::binary$`) from a test name.
+    fn display_test_name(name: &str) -> String {
+        name.split_once('$')
+            .map_or(name, |(_, test)| test)
+            .to_string()
+    }
+
     /// Maximum number of clippy failures to include in results.
     const MAX_CLIPPY_FAILURES: usize = 20;
 
@@ -772,9 +1661,19 @@ impl QualityGateChecker {
     }
 
     /// Parse clippy JSON output format (from --message-format=json).
+    fn parse_clippy_json(stdout: &str) -> Vec<GateFailureDetail> {
+        Self::parse_compiler_json(stdout, FailureCategory::Lint, true)
+    }
+
+    /// Parse compiler JSON output (from --message-format=json).
     ///
     /// Each line is a separate JSON object representing a compiler message.
-    fn parse_clippy_json(stdout: &str) -> Vec<GateFailureDetail> {
+    /// Warnings are only included when `include_warnings` is set.
+    fn parse_compiler_json(
+        stdout: &str,
+        category: FailureCategory,
+        include_warnings: bool,
+    ) -> Vec<GateFailureDetail> {
         let mut failures = Vec::new();
 
         for line in stdout.lines() {
@@ -792,17 +1691,16 @@ impl QualityGateChecker {
                 // Only process compiler messages with actual diagnostics
                 if msg.reason == "compiler-message" {
                     if let Some(message) = msg.message {
-                        // Only include errors and warnings
-                        if matches!(message.level.as_str(), "error" | "warning") {
+                        // Only include errors and, if requested, warnings
+                        let level = message.level.as_str();
+                        if level == "error" || (include_warnings && level == "warning") {
                             // Skip "aborting due to" messages
                             if message.message.starts_with("aborting due to") {
                                 continue;
                             }
 
-                            let mut detail = GateFailureDetail::new(
-                                FailureCategory::Lint,
-                                message.message.clone(),
-                            );
+                            let mut detail =
+                                GateFailureDetail::new(category, message.message.clone());
 
                             // Extract error code
                             if let Some(code) = &message.code {
@@ -865,9 +1763,19 @@ impl QualityGateChecker {
     }
 
     /// Parse clippy text output (fallback when JSON parsing fails).
+    fn parse_clippy_text(stderr: &str) -> Vec<GateFailureDetail> {
+        Self::parse_compiler_text(stderr, FailureCategory::Lint, true)
+    }
+
+    /// Parse compiler text output (fallback when JSON parsing fails).
     ///
     /// Extracts error information from stderr using regex patterns.
-    fn parse_clippy_text(stderr: &str) -> Vec<GateFailureDetail> {
+    /// Warnings are only included when `include_warnings` is set.
+    fn parse_compiler_text(
+        stderr: &str,
+        category: FailureCategory,
+        include_warnings: bool,
+    ) -> Vec<GateFailureDetail> {
         let mut failures = Vec::new();
         let mut current_message: Option<String> = None;
         let mut current_file: Option<String> = None;
@@ -880,10 +1788,11 @@ impl QualityGateChecker {
             }
 
             // Match error/warning lines: "error[E0001]: message" or "warning: message"
-            if line.starts_with("error") || line.starts_with("warning") {
+            let is_error = line.starts_with("error");
+            if is_error || line.starts_with("warning") {
                 // Save previous error if any
                 if let Some(msg) = current_message.take() {
-                    let mut detail = GateFailureDetail::new(FailureCategory::Lint, msg);
+                    let mut detail = GateFailureDetail::new(category, msg);
                     if let Some(file) = current_file.take() {
                         detail = detail.with_file(file);
                     }
@@ -909,7 +1818,11 @@ impl QualityGateChecker {
                     line.to_string()
                 };
 
-                current_message = Some(msg);
+                // Locations following a skipped warning must not leak into the next error
+                current_file = None;
+                current_line = None;
+                current_column = None;
+                current_message = (is_error || include_warnings).then_some(msg);
             }
             // Match location lines: "  --> src/file.rs:10:5"
             else if line.trim().starts_with("-->") {
@@ -929,7 +1842,7 @@ impl QualityGateChecker {
         // Don't forget the last error
         if let Some(msg) = current_message {
             if failures.len() < Self::MAX_CLIPPY_FAILURES {
-                let mut detail = GateFailureDetail::new(FailureCategory::Lint, msg);
+                let mut detail = GateFailureDetail::new(category, msg);
                 if let Some(file) = current_file {
                     detail = detail.with_file(file);
                 }
@@ -1020,7 +1933,7 @@ impl QualityGateChecker {
             if let Ok(msg) = serde_json::from_str::<TestMessage>(line) {
                 // Only process test events that failed
                 if msg.msg_type == "test" && msg.event.as_deref() == Some("failed") {
-                    if let Some(name) = msg.name {
+                    if let Some(name) = msg.name.as_deref().map(Self::display_test_name) {
                         let mut detail = GateFailureDetail::new(
                             FailureCategory::Test,
                             format!("Test failed: {}", name),
@@ -1355,9 +2268,9 @@ impl QualityGateChecker {
             return GateResult::skipped("format", "Format checking not enabled in profile");
         }
 
-        let output = Command::new("cargo")
-            .args(["fmt", "--check"])
-            .current_dir(&self.project_root)
+        let output = self
+            .command("cargo")
+            .args(["fmt", "--check", "--", "--color", "never"])
             .output();
 
         match output {
@@ -1371,12 +2284,13 @@ impl QualityGateChecker {
                     // Extract structured failure details from output
                     let failures = Self::extract_format_errors(&stdout, &stderr);
                     let details = Self::format_format_summary(&failures);
+                    let file_count = Self::count_format_files(&failures);
                     GateResult::fail(
                         "format",
                         format!(
                             "{} file{} need formatting",
-                            failures.len(),
-                            if failures.len() == 1 { "" } else { "s" }
+                            file_count,
+                            if file_count == 1 { "" } else { "s" }
                         ),
                         Some(details),
                         Some(failures),
@@ -1400,8 +2314,9 @@ impl QualityGateChecker {
 
     /// Extract structured format errors from cargo fmt output.
     ///
-    /// Parses `cargo fmt --check` output to extract file paths from "Diff in" lines
-    /// and creates structured failure details for each unformatted file.
+    /// Parses `cargo fmt --check` output into one failure per diff hunk. Each
+    /// "Diff in" header starts a hunk; the following `-`/`+` lines give the
+    /// affected line range and the replacement text rustfmt expects.
     ///
     /// # Arguments
     ///
@@ -1410,38 +2325,33 @@ impl QualityGateChecker {
     ///
     /// # Returns
     ///
-    /// A vector of `GateFailureDetail` entries, one for each file needing formatting.
+    /// A vector of `GateFailureDetail` entries, one for each hunk needing formatting.
     fn extract_format_errors(stdout: &str, stderr: &str) -> Vec<GateFailureDetail> {
         let mut failures = Vec::new();
+        let mut current: Option<FormatHunk> = None;
 
-        // cargo fmt --check outputs "Diff in <file>" for each unformatted file
-        // Example: "Diff in /path/to/file.rs at line 1:"
+        // cargo fmt --check outputs "Diff in <file>" before each hunk
+        // Example: "Diff in /path/to/file.rs at line 1:" or "Diff in /path/to/file.rs:1:"
         for line in stdout.lines() {
-            if line.starts_with("Diff in") {
-                // Extract file path from "Diff in /path/to/file.rs at line N:"
-                // or "Diff in /path/to/file.rs:"
-                let path = line
-                    .trim_start_matches("Diff in ")
-                    .split(" at line ")
-                    .next()
-                    .unwrap_or("")
-                    .trim_end_matches(':')
-                    .to_string();
+            if let Some(header) = line.strip_prefix("Diff in ") {
+                if let Some(hunk) = current.take() {
+                    failures.push(hunk.into_failure());
+                }
+                if failures.len() >= Self::MAX_FORMAT_FAILURES {
+                    break;
+                }
 
+                let (path, header_line) = Self::parse_format_header(header);
                 if !path.is_empty() {
-                    let failure = GateFailureDetail::new(
-                        FailureCategory::Format,
-                        format!("File needs formatting: {}", path),
-                    )
-                    .with_file(path)
-                    .with_suggestion("Run cargo fmt to fix".to_string());
-
-                    failures.push(failure);
-
-                    if failures.len() >= Self::MAX_FORMAT_FAILURES {
-                        break;
-                    }
+                    current = Some(FormatHunk::new(path, header_line));
                 }
+            } else if let Some(ref mut hunk) = current {
+                hunk.push_line(line);
+            }
+        }
+        if let Some(hunk) = current {
+            if failures.len() < Self::MAX_FORMAT_FAILURES {
+                failures.push(hunk.into_failure());
             }
         }
 
@@ -1456,25 +2366,55 @@ impl QualityGateChecker {
         failures
     }
 
+    /// Split a "Diff in" header into the file path and starting line.
+    ///
+    /// Handles both "<file> at line N:" and "<file>:N:" forms.
+    fn parse_format_header(header: &str) -> (String, Option<u32>) {
+        let header = header.trim_end().trim_end_matches(':');
+        if let Some((path, line)) = header.split_once(" at line ") {
+            return (path.to_string(), line.trim().parse().ok());
+        }
+        match header.rsplit_once(':') {
+            Some((path, line)) if line.parse::<u32>().is_ok() => {
+                (path.to_string(), line.parse().ok())
+            }
+            _ => (header.to_string(), None),
+        }
+    }
+
+    /// Count the distinct files among format failures.
+    fn count_format_files(failures: &[GateFailureDetail]) -> usize {
+        let files: std::collections::HashSet<&str> = failures
+            .iter()
+            .map(|f| f.file.as_deref().unwrap_or(f.message.as_str()))
+            .collect();
+        files.len()
+    }
+
     /// Format a summary of format failures for the details field.
     fn format_format_summary(failures: &[GateFailureDetail]) -> String {
         if failures.is_empty() {
             return "Formatting check failed (no additional details)".to_string();
         }
 
-        let mut summary = format!("{} file(s) need formatting:\n", failures.len());
+        let mut summary = format!(
+            "{} file(s) need formatting:\n",
+            Self::count_format_files(failures)
+        );
 
         for (i, failure) in failures.iter().enumerate().take(Self::MAX_FORMAT_FAILURES) {
-            if let Some(ref file) = failure.file {
-                summary.push_str(&format!("{}. {}\n", i + 1, file));
-            } else {
-                summary.push_str(&format!("{}. {}\n", i + 1, failure.message));
+            match (&failure.file, failure.line) {
+                (Some(file), Some(line)) => {
+                    summary.push_str(&format!("{}. {}:{}\n", i + 1, file, line))
+                }
+                (Some(file), None) => summary.push_str(&format!("{}. {}\n", i + 1, file)),
+                (None, _) => summary.push_str(&format!("{}. {}\n", i + 1, failure.message)),
             }
         }
 
         if failures.len() > Self::MAX_FORMAT_FAILURES {
             summary.push_str(&format!(
-                "... and {} more files\n",
+                "... and {} more hunks\n",
                 failures.len() - Self::MAX_FORMAT_FAILURES
             ));
         }
@@ -1497,10 +2437,7 @@ impl QualityGateChecker {
         }
 
         // Check if cargo-audit is installed
-        let check_installed = Command::new("cargo")
-            .args(["audit", "--version"])
-            .current_dir(&self.project_root)
-            .output();
+        let check_installed = self.command("cargo").args(["audit", "--version"]).output();
 
         match check_installed {
             Ok(output) if output.status.success() => {
@@ -1526,10 +2463,7 @@ impl QualityGateChecker {
     /// Run cargo audit and parse the results.
     fn run_cargo_audit(&self) -> GateResult {
         // Run cargo audit with JSON output for easier parsing
-        let output = Command::new("cargo")
-            .args(["audit", "--json"])
-            .current_dir(&self.project_root)
-            .output();
+        let output = self.command("cargo").args(["audit", "--json"]).output();
 
         match output {
             Ok(output) => {
@@ -1824,6 +2758,160 @@ impl QualityGateChecker {
         summary
     }
 
+    /// Default semgrep ruleset used when the profile configures none.
+    const DEFAULT_SAST_RULESET: &'static str = "p/rust";
+
+    /// Maximum number of SAST findings to include in results.
+    const MAX_SAST_FAILURES: usize = 20;
+
+    /// Check for insecure code patterns using semgrep.
+    ///
+    /// Runs `semgrep scan --json` with the profile's rulesets and converts each
+    /// finding into a Security-category failure with file, line, and rule ID.
+    /// When sast is enabled in the profile, this gate will run.
+    ///
+    /// # Returns
+    ///
+    /// A `GateResult` indicating whether semgrep reported any findings.
+    pub fn check_sast(&self) -> GateResult {
+        if !self.profile.security.sast {
+            return GateResult::skipped("sast", "SAST not enabled in profile");
+        }
+
+        let check_installed = self.command("semgrep").arg("--version").output();
+        if !matches!(check_installed, Ok(ref output) if output.status.success()) {
+            return GateResult::fail(
+                "sast",
+                "semgrep is not installed",
+                Some(
+                    "Install semgrep: pip install semgrep (or brew install semgrep)\n\
+                     semgrep scans source code for insecure patterns."
+                        .to_string(),
+                ),
+                None,
+            );
+        }
+
+        let mut command = self.command("semgrep");
+        command.args(["scan", "--json", "--quiet", "--metrics=off"]);
+        if self.profile.security.sast_rulesets.is_empty() {
+            command.args(["--config", Self::DEFAULT_SAST_RULESET]);
+        } else {
+            for ruleset in &self.profile.security.sast_rulesets {
+                command.args(["--config", ruleset]);
+            }
+        }
+
+        match command.output() {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                Self::parse_semgrep_json(&stdout).unwrap_or_else(|| {
+                    GateResult::fail(
+                        "sast",
+                        "Failed to parse semgrep output",
+                        Some(format!("stderr: {}", stderr)),
+                        None,
+                    )
+                })
+            }
+            Err(e) => GateResult::fail(
+                "sast",
+                "Failed to run semgrep",
+                Some(format!("Error: {}", e)),
+                None,
+            ),
+        }
+    }
+
+    /// Parse `semgrep --json` output into a gate result.
+    ///
+    /// Returns `None` if the output is not valid semgrep JSON.
+    fn parse_semgrep_json(json_str: &str) -> Option<GateResult> {
+        let output: SemgrepOutput = serde_json::from_str(json_str).ok()?;
+        let total = output.results.len();
+
+        if total == 0 {
+            if !output.errors.is_empty() {
+                return Some(GateResult::fail(
+                    "sast",
+                    format!("semgrep reported {} error(s)", output.errors.len()),
+                    Some(
+                        output
+                            .errors
+                            .iter()
+                            .map(|e| {
+                                e.get("message")
+                                    .and_then(|m| m.as_str())
+                                    .unwrap_or("unknown error")
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                    None,
+                ));
+            }
+            return Some(GateResult::pass("sast", "No SAST findings"));
+        }
+
+        let failures: Vec<GateFailureDetail> = output
+            .results
+            .into_iter()
+            .take(Self::MAX_SAST_FAILURES)
+            .map(|finding| {
+                let message = match finding.extra.severity {
+                    Some(ref severity) => {
+                        format!("[{}] {}", severity, finding.extra.message.trim())
+                    }
+                    None => finding.extra.message.trim().to_string(),
+                };
+                let mut detail = GateFailureDetail::new(FailureCategory::Security, message)
+                    .with_location(finding.path, finding.start.line, Some(finding.start.col))
+                    .with_error_code(finding.check_id);
+                if let Some(fix) = finding.extra.fix.filter(|f| !f.is_empty()) {
+                    detail = detail.with_suggestion(fix);
+                }
+                if let Some(source) = finding
+                    .extra
+                    .metadata
+                    .get("source")
+                    .and_then(|s| s.as_str())
+                {
+                    detail = detail.with_doc_url(source);
+                }
+                detail
+            })
+            .collect();
+        let details = Self::format_sast_summary(&failures);
+
+        Some(GateResult::fail(
+            "sast",
+            format!(
+                "Found {} SAST finding{}",
+                total,
+                if total == 1 { "" } else { "s" }
+            ),
+            Some(details),
+            Some(failures),
+        ))
+    }
+
+    /// Format a summary of SAST findings for the details field.
+    fn format_sast_summary(failures: &[GateFailureDetail]) -> String {
+        let mut summary = format!("{} finding(s):\n", failures.len());
+        for (i, failure) in failures.iter().enumerate() {
+            summary.push_str(&format!(
+                "{}. {}:{}: {} ({})\n",
+                i + 1,
+                failure.file.as_deref().unwrap_or("?"),
+                failure.line.unwrap_or(0),
+                failure.message,
+                failure.error_code.as_deref().unwrap_or("unknown rule")
+            ));
+        }
+        summary
+    }
+
     /// Run all quality gates configured in the profile.
     ///
     /// Returns a vector of `GateResult` for each gate that was run.
@@ -1833,13 +2921,13 @@ impl QualityGateChecker {
     ///
     /// A `Vec<GateResult>` containing the results of all gates.
     pub fn run_all(&self) -> Vec<GateResult> {
-        vec![
-            self.check_coverage(),
-            self.check_tests(),
-            self.check_lint(),
-            self.check_format(),
-            self.check_security_audit(),
-        ]
+        self.run_all_gates_with_progress(|_| {})
+    }
+
+    /// Apply the profile's configured severity to a gate result.
+    pub fn apply_severity(&self, result: GateResult) -> GateResult {
+        let severity = self.profile.severity_for(&result.gate_name);
+        result.with_severity(severity)
     }
 
     /// Run all quality gates with progress callbacks.
@@ -1877,74 +2965,125 @@ impl QualityGateChecker {
     where
         F: FnMut(GateProgressUpdate),
     {
-        let mut results = Vec::new();
+        let coverage = self.run_gate_with_progress("coverage", &mut callback, Self::check_coverage);
+        let tests = self.run_gate_with_progress("tests", &mut callback, Self::check_tests);
+        let integration_tests = self.run_gate_with_progress(
+            "integration_tests",
+            &mut callback,
+            Self::check_integration_tests,
+        );
+        let typecheck = self.run_gate_with_progress("typecheck", &mut callback, Self::check_types);
+        let lint = self.run_gate_with_progress("lint", &mut callback, |checker| {
+            checker.check_lint_after(&typecheck)
+        });
+        let format = self.run_gate_with_progress("format", &mut callback, Self::check_format);
+        let security_audit = self.run_gate_with_progress(
+            "security_audit",
+            &mut callback,
+            Self::check_security_audit,
+        );
+        let sast = self.run_gate_with_progress("sast", &mut callback, Self::check_sast);
 
-        // Run coverage check
-        callback(GateProgressUpdate::running("coverage"));
-        let start = Instant::now();
-        let result = self.check_coverage();
-        let duration = start.elapsed();
-        if result.passed {
-            callback(GateProgressUpdate::passed("coverage", duration));
-        } else {
-            callback(GateProgressUpdate::failed("coverage", duration));
-        }
-        results.push(result);
+        vec![
+            coverage,
+            tests,
+            integration_tests,
+            typecheck,
+            lint,
+            format,
+            security_audit,
+            sast,
+        ]
+    }
 
-        // Run tests check
-        callback(GateProgressUpdate::running("tests"));
+    /// Run a single gate outside of [`run_all`](Self::run_all).
+    ///
+    /// The result gets the profile's severity and is recorded as evidence,
+    /// exactly as gates run by `run_all` are.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let tests = checker.run_gate(QualityGateChecker::check_tests_full);
+    /// ```
+    pub fn run_gate<C>(&self, check: C) -> GateResult
+    where
+        C: FnOnce(&Self) -> GateResult,
+    {
         let start = Instant::now();
-        let result = self.check_tests();
-        let duration = start.elapsed();
-        if result.passed {
-            callback(GateProgressUpdate::passed("tests", duration));
-        } else {
-            callback(GateProgressUpdate::failed("tests", duration));
-        }
-        results.push(result);
+        let result = check(self);
+        self.finish_gate(result, start.elapsed())
+    }
 
-        // Run lint check
-        callback(GateProgressUpdate::running("lint"));
+    /// Run a single gate, emitting progress updates around it.
+    fn run_gate_with_progress<F, C>(
+        &self,
+        gate_name: &str,
+        callback: &mut F,
+        check: C,
+    ) -> GateResult
+    where
+        F: FnMut(GateProgressUpdate),
+        C: FnOnce(&Self) -> GateResult,
+    {
+        callback(GateProgressUpdate::running(gate_name));
         let start = Instant::now();
-        let result = self.check_lint();
+        let result = check(self);
         let duration = start.elapsed();
         if result.passed {
-            callback(GateProgressUpdate::passed("lint", duration));
+            callback(GateProgressUpdate::passed(gate_name, duration));
         } else {
-            callback(GateProgressUpdate::failed("lint", duration));
+            callback(GateProgressUpdate::failed(gate_name, duration));
         }
-        results.push(result);
-
-        // Run format check
-        callback(GateProgressUpdate::running("format"));
-        let start = Instant::now();
-        let result = self.check_format();
-        let duration = start.elapsed();
-        if result.passed {
-            callback(GateProgressUpdate::passed("format", duration));
-        } else {
-            callback(GateProgressUpdate::failed("format", duration));
+        self.finish_gate(result, duration)
+    }
+
+    /// Apply suppressions and severity to a completed gate and record it as
+    /// evidence.
+    fn finish_gate(&self, result: GateResult, duration: Duration) -> GateResult {
+        let result = self.suppressions.apply(result);
+        let result = self.apply_severity(self.bound_details(result));
+        if let Some(ref evidence) = self.evidence {
+            evidence.emit_record(
+                GATE_EVIDENCE_KIND,
+                &GateEvidence {
+                    step_id: &self.evidence_step,
+                    skipped: result.passed && result.message.starts_with("Skipped:"),
+                    duration_ms: duration.as_millis() as u64,
+                    result: &result,
+                },
+            );
         }
-        results.push(result);
-
-        // Run security audit
-        callback(GateProgressUpdate::running("security_audit"));
-        let start = Instant::now();
-        let result = self.check_security_audit();
-        let duration = start.elapsed();
-        if result.passed {
-            callback(GateProgressUpdate::passed("security_audit", duration));
-        } else {
-            callback(GateProgressUpdate::failed("security_audit", duration));
+        result
+    }
+
+    /// Trim details that outgrow the output caps to their most recent lines.
+    fn bound_details(&self, mut result: GateResult) -> GateResult {
+        if let Some(details) = result.details.take() {
+            let spill_path = self
+                .artifacts_dir
+                .as_ref()
+                .map(|dir| dir.join(format!("{}-output.log", result.gate_name)));
+            let capture = OutputCapture::from_text(self.output_capture, spill_path, &details);
+            result.details = Some(if capture.is_truncated() {
+                capture.to_report()
+            } else {
+                details
+            });
         }
-        results.push(result);
+        result
+    }
 
-        results
+    /// Check if all gates passed.
+    ///
+    /// Failed gates with warning severity do not count against the result.
+    pub fn all_passed(results: &[GateResult]) -> bool {
+        !results.iter().any(GateResult::is_blocking_failure)
     }
 
-    /// Check if all gates passed.
-    pub fn all_passed(results: &[GateResult]) -> bool {
-        results.iter().all(|r| r.passed)
+    /// Get the gates that failed with warning severity.
+    pub fn warnings(results: &[GateResult]) -> Vec<&GateResult> {
+        results.iter().filter(|r| r.is_warning()).collect()
     }
 
     /// Get a summary of gate results.
@@ -1953,17 +3092,29 @@ impl QualityGateChecker {
         let total = results.len();
         let failed: Vec<&str> = results
             .iter()
-            .filter(|r| !r.passed)
+            .filter(|r| r.is_blocking_failure())
+            .map(|r| r.gate_name.as_str())
+            .collect();
+        let warnings: Vec<&str> = Self::warnings(results)
+            .iter()
             .map(|r| r.gate_name.as_str())
             .collect();
 
-        if failed.is_empty() {
+        let summary = if failed.is_empty() && warnings.is_empty() {
             format!("All {total} gates passed")
+        } else if failed.is_empty() {
+            format!("{passed}/{total} gates passed, no blocking failures")
         } else {
             format!(
                 "{passed}/{total} gates passed. Failed: {}",
                 failed.join(", ")
             )
+        };
+
+        if warnings.is_empty() {
+            summary
+        } else {
+            format!("{summary}. Warnings: {}", warnings.join(", "))
         }
     }
 }
@@ -1986,16 +3137,20 @@ mod tests {
                 coverage_threshold: coverage,
                 unit_tests,
                 integration_tests: false,
+                ..Default::default()
             },
             ci: CiConfig {
                 required: true,
                 lint_check: lint,
                 format_check: format,
+                type_check: false,
+                ..Default::default()
             },
             security: SecurityConfig {
                 cargo_audit: audit,
                 cargo_deny: false,
                 sast: false,
+                sast_rulesets: Vec::new(),
             },
             ..Default::default()
         }
@@ -2039,8 +3194,8 @@ mod tests {
         let checker = QualityGateChecker::new(profile, "/tmp/test");
         let results = checker.run_all();
 
-        // 5 gates: coverage, tests, lint, format, security_audit
-        assert_eq!(results.len(), 5);
+        // 8 gates: coverage, tests, integration_tests, typecheck, lint, format, security_audit, sast
+        assert_eq!(results.len(), 8);
         assert!(QualityGateChecker::all_passed(&results));
     }
 
@@ -2050,7 +3205,7 @@ mod tests {
         let checker = QualityGateChecker::new(profile, "/tmp/test");
         let results = checker.run_all();
 
-        assert_eq!(results.len(), 5);
+        assert_eq!(results.len(), 8);
         // Coverage gate may fail if tools not installed, lint/format/security are still skipped
     }
 
@@ -2095,6 +3250,287 @@ mod tests {
         assert!(summary.contains("gate3"));
     }
 
+    #[test]
+    fn test_warning_gate_does_not_block() {
+        let results = vec![
+            GateResult::pass("tests", "Passed"),
+            GateResult::fail("format", "Failed", None, None).with_severity(GateSeverity::Warning),
+        ];
+
+        assert!(QualityGateChecker::all_passed(&results));
+        assert!(results[1].is_warning());
+        assert!(!results[1].is_blocking_failure());
+        let warnings = QualityGateChecker::warnings(&results);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].gate_name, "format");
+
+        let summary = QualityGateChecker::summary(&results);
+        assert_eq!(
+            summary,
+            "1/2 gates passed, no blocking failures. Warnings: format"
+        );
+    }
+
+    #[test]
+    fn test_summary_failures_and_warnings() {
+        let results = vec![
+            GateResult::fail("tests", "Failed", None, None),
+            GateResult::fail("format", "Failed", None, None).with_severity(GateSeverity::Warning),
+        ];
+
+        assert!(!QualityGateChecker::all_passed(&results));
+        let summary = QualityGateChecker::summary(&results);
+        assert_eq!(summary, "0/2 gates passed. Failed: tests. Warnings: format");
+    }
+
+    #[test]
+    fn test_check_integration_tests_skipped_when_disabled() {
+        let profile = create_test_profile(0, true, false, false, false);
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+
+        let result = checker.check_integration_tests();
+        assert!(result.passed);
+        assert_eq!(result.gate_name, "integration_tests");
+        assert!(result.message.contains("Skipped"));
+    }
+
+    #[test]
+    fn test_check_integration_tests_custom_command() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut profile = create_test_profile(0, false, false, false, false);
+        profile.testing.integration_tests = true;
+        profile.testing.integration_test_command = Some(
+            "printf 'test api::test_create ... FAILED\\n\\nfailures:\\n\\nfailures:\\n    api::test_create\\n'; exit 101"
+                .to_string(),
+        );
+        let checker = QualityGateChecker::new(profile.clone(), temp_dir.path());
+
+        let result = checker.check_integration_tests();
+        assert!(!result.passed);
+        assert_eq!(result.gate_name, "integration_tests");
+        assert!(!result.failures.is_empty());
+        assert_eq!(result.failures[0].category, FailureCategory::Test);
+
+        profile.testing.integration_test_command = Some("true".to_string());
+        let checker = QualityGateChecker::new(profile, temp_dir.path());
+        let result = checker.check_integration_tests();
+        assert!(result.passed);
+        assert_eq!(result.message, "All tests passed");
+    }
+
+    #[test]
+    fn test_check_sast_skipped_when_disabled() {
+        let profile = create_test_profile(0, false, false, false, false);
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+
+        let result = checker.check_sast();
+        assert!(result.passed);
+        assert_eq!(result.gate_name, "sast");
+        assert!(result.message.contains("Skipped"));
+    }
+
+    #[test]
+    fn test_parse_semgrep_json_findings() {
+        let json = r#"{
+            "results": [
+                {
+                    "check_id": "rust.lang.security.args.args",
+                    "path": "src/main.rs",
+                    "start": {"line": 12, "col": 5, "offset": 200},
+                    "end": {"line": 12, "col": 20, "offset": 215},
+                    "extra": {
+                        "message": "Avoid trusting std::env::args for security decisions.\n",
+                        "severity": "WARNING",
+                        "metadata": {"source": "https://semgrep.dev/r/rust.lang.security.args.args"}
+                    }
+                }
+            ],
+            "errors": []
+        }"#;
+
+        let result = QualityGateChecker::parse_semgrep_json(json).unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.message, "Found 1 SAST finding");
+        assert_eq!(result.failures.len(), 1);
+
+        let failure = &result.failures[0];
+        assert_eq!(failure.category, FailureCategory::Security);
+        assert_eq!(failure.file.as_deref(), Some("src/main.rs"));
+        assert_eq!(failure.line, Some(12));
+        assert_eq!(failure.column, Some(5));
+        assert_eq!(
+            failure.error_code.as_deref(),
+            Some("rust.lang.security.args.args")
+        );
+        assert_eq!(
+            failure.message,
+            "[WARNING] Avoid trusting std::env::args for security decisions."
+        );
+        assert_eq!(
+            failure.doc_url.as_deref(),
+            Some("https://semgrep.dev/r/rust.lang.security.args.args")
+        );
+        assert!(result.details.unwrap().contains("src/main.rs:12"));
+    }
+
+    #[test]
+    fn test_parse_semgrep_json_clean_and_errors() {
+        let clean =
+            QualityGateChecker::parse_semgrep_json(r#"{"results": [], "errors": []}"#).unwrap();
+        assert!(clean.passed);
+
+        let errored = QualityGateChecker::parse_semgrep_json(
+            r#"{"results": [], "errors": [{"message": "Invalid rule schema"}]}"#,
+        )
+        .unwrap();
+        assert!(!errored.passed);
+        assert_eq!(errored.details.as_deref(), Some("Invalid rule schema"));
+
+        assert!(QualityGateChecker::parse_semgrep_json("not json").is_none());
+    }
+
+    #[test]
+    fn test_check_types_skipped_when_disabled() {
+        let profile = create_test_profile(0, false, false, false, false);
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+
+        let result = checker.check_types();
+        assert!(result.passed);
+        assert_eq!(result.gate_name, "typecheck");
+        assert!(result.message.contains("Skipped"));
+    }
+
+    #[test]
+    fn test_lint_skipped_after_failed_typecheck() {
+        let profile = create_test_profile(0, false, true, false, false);
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+
+        let typecheck = GateResult::fail("typecheck", "Compilation failed", None, None);
+        let lint = checker.check_lint_after(&typecheck);
+        assert!(lint.passed);
+        assert_eq!(lint.gate_name, "lint");
+        assert!(lint.message.contains("Type check failed"));
+    }
+
+    #[test]
+    fn test_parse_compiler_json_type_errors_only() {
+        let stdout = r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`","level":"warning","code":{"code":"unused_variables","explanation":null},"spans":[{"file_name":"src/lib.rs","line_start":2,"line_end":2,"column_start":9,"column_end":10,"suggested_replacement":null}],"children":[]}}
+{"reason":"compiler-message","message":{"message":"mismatched types","level":"error","code":{"code":"E0308","explanation":null},"spans":[{"file_name":"src/lib.rs","line_start":5,"line_end":5,"column_start":5,"column_end":8,"suggested_replacement":null}],"children":[]}}"#;
+
+        let failures =
+            QualityGateChecker::parse_compiler_json(stdout, FailureCategory::TypeCheck, false);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].category, FailureCategory::TypeCheck);
+        assert_eq!(failures[0].message, "mismatched types");
+        assert_eq!(failures[0].error_code.as_deref(), Some("E0308"));
+        assert_eq!(failures[0].line, Some(5));
+    }
+
+    #[test]
+    fn test_parse_compiler_text_type_errors_only() {
+        let stderr = "warning: unused variable: `x`\n  --> src/lib.rs:2:9\nerror[E0308]: mismatched types\n  --> src/lib.rs:5:5\n";
+
+        let failures =
+            QualityGateChecker::parse_compiler_text(stderr, FailureCategory::TypeCheck, false);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].category, FailureCategory::TypeCheck);
+        assert_eq!(failures[0].message, "mismatched types");
+        assert_eq!(failures[0].file.as_deref(), Some("src/lib.rs"));
+        assert_eq!(failures[0].line, Some(5));
+    }
+
+    #[test]
+    fn test_gate_results_recorded_as_evidence() {
+        use crate::evidence::{EvidenceStore, EvidenceStoreConfig, EvidenceWriter};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let writer = EvidenceWriter::try_new(temp_dir.path(), "run-gates".to_string()).unwrap();
+        let profile = create_test_profile(0, false, false, false, false);
+        let checker =
+            QualityGateChecker::new(profile, temp_dir.path()).with_evidence(writer, "US-001");
+
+        let results = checker.run_all();
+        let rerun = checker.run_gate(QualityGateChecker::check_tests_full);
+
+        let store = EvidenceStore::new(temp_dir.path(), EvidenceStoreConfig::default()).unwrap();
+        let records: Vec<_> = store
+            .load_events("run-gates")
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.kind == GATE_EVIDENCE_KIND)
+            .collect();
+
+        assert_eq!(records.len(), results.len() + 1);
+        for (record, result) in records.iter().zip(&results) {
+            assert_eq!(record.payload["step_id"], "US-001");
+            assert_eq!(record.payload["gate_name"], result.gate_name.as_str());
+            assert_eq!(record.payload["skipped"], true);
+            assert!(record.payload["duration_ms"].is_u64());
+            assert_eq!(record.payload["severity"], "blocking");
+        }
+        assert_eq!(records.last().unwrap().payload["gate_name"], "tests");
+        assert!(rerun.passed);
+    }
+
+    #[test]
+    fn test_gate_report_json_round_trip() {
+        let failure = GateFailureDetail::new(FailureCategory::Lint, "unused variable")
+            .with_file("src/lib.rs")
+            .with_line(7);
+        let results = vec![
+            GateResult::pass("tests", "All tests passed"),
+            GateResult::fail("lint", "1 warning", None, Some(vec![failure])),
+        ];
+
+        let report = GateReport::new("standard", results);
+        assert!(!report.passed);
+        assert_eq!(report.summary, "1/2 gates passed. Failed: lint");
+
+        let json = serde_json::to_string(&report).unwrap();
+        let parsed: GateReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.profile, "standard");
+        assert_eq!(parsed.results.len(), 2);
+        assert_eq!(
+            parsed.results[1].failures[0].category,
+            FailureCategory::Lint
+        );
+        assert_eq!(parsed.results[1].failures[0].line, Some(7));
+    }
+
+    #[test]
+    fn test_apply_severity_from_profile() {
+        let mut profile = create_test_profile(0, false, false, false, false);
+        profile
+            .gate_severity
+            .insert("format".to_string(), GateSeverity::Warning);
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+
+        let format = checker.apply_severity(GateResult::fail("format", "Failed", None, None));
+        let lint = checker.apply_severity(GateResult::fail("lint", "Failed", None, None));
+
+        assert_eq!(format.severity, GateSeverity::Warning);
+        assert_eq!(lint.severity, GateSeverity::Blocking);
+        assert!(checker
+            .run_all()
+            .iter()
+            .any(|r| r.gate_name == "format" && r.severity == GateSeverity::Warning));
+    }
+
+    #[test]
+    fn test_gate_result_severity_serialization() {
+        let result =
+            GateResult::fail("format", "Failed", None, None).with_severity(GateSeverity::Warning);
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains(r#""severity":"warning""#));
+
+        // Results serialized before severity existed default to blocking
+        let legacy: GateResult = serde_json::from_str(
+            r#"{"gate_name":"lint","passed":false,"message":"Failed","details":null}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.severity, GateSeverity::Blocking);
+    }
+
     // Coverage gate tests
 
     #[test]
@@ -2230,6 +3666,156 @@ mod tests {
         assert!(result.passed, "Coverage at exactly threshold should pass");
     }
 
+    fn llvm_cov_files_json() -> &'static str {
+        r#"{
+            "data": [{
+                "files": [
+                    {"filename": "/tmp/test/src/core/lib.rs", "summary": {"lines": {"count": 100, "covered": 95, "percent": 95.0}}},
+                    {"filename": "/tmp/test/src/util.rs", "summary": {"lines": {"count": 100, "covered": 60, "percent": 60.0}}},
+                    {"filename": "/tmp/test/src/generated/api.rs", "summary": {"lines": {"count": 200, "covered": 0, "percent": 0.0}}}
+                ],
+                "totals": {"lines": {"percent": 38.75}}
+            }]
+        }"#
+    }
+
+    #[test]
+    fn test_parse_llvm_cov_files() {
+        let files = QualityGateChecker::parse_llvm_cov_files(llvm_cov_files_json()).unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "/tmp/test/src/core/lib.rs");
+        assert_eq!(files[0].total_lines, 100);
+        assert_eq!(files[0].covered_lines, 95);
+
+        assert!(QualityGateChecker::parse_llvm_cov_files("not json").is_none());
+        assert!(QualityGateChecker::parse_llvm_cov_files("{}").is_none());
+    }
+
+    #[test]
+    fn test_evaluate_file_coverage_with_exclusions() {
+        let mut profile = create_test_profile(70, false, false, false, false);
+        profile.testing.coverage_exclude = vec!["src/generated/**".to_string()];
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+        let files = QualityGateChecker::parse_llvm_cov_files(llvm_cov_files_json()).unwrap();
+
+        let result = checker.evaluate_file_coverage(&files, "test-tool");
+
+        // (95 + 60) / 200 = 77.5% once generated code is excluded
+        assert!(result.passed, "Unexpected failure: {}", result.message);
+        assert!(result.message.contains("77.50%"));
+        assert!(result.message.contains("1 file excluded"));
+    }
+
+    #[test]
+    fn test_evaluate_file_coverage_path_threshold_failure() {
+        let mut profile = create_test_profile(70, false, false, false, false);
+        profile.testing.coverage_exclude = vec!["src/generated/**".to_string()];
+        profile.testing.path_thresholds = vec![
+            crate::quality::PathCoverageThreshold {
+                path: "src/core/**".to_string(),
+                threshold: 90,
+            },
+            crate::quality::PathCoverageThreshold {
+                path: "src/util.rs".to_string(),
+                threshold: 80,
+            },
+        ];
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+        let files = QualityGateChecker::parse_llvm_cov_files(llvm_cov_files_json()).unwrap();
+
+        let result = checker.evaluate_file_coverage(&files, "test-tool");
+
+        assert!(!result.passed);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].category, FailureCategory::Coverage);
+        assert_eq!(result.failures[0].file.as_deref(), Some("src/util.rs"));
+        assert!(result.message.contains("1 path coverage threshold not met"));
+        assert!(result.details.unwrap().contains("60.00%"));
+    }
+
+    #[test]
+    fn test_evaluate_file_coverage_unmatched_path_threshold_ignored() {
+        let mut profile = create_test_profile(30, false, false, false, false);
+        profile.testing.path_thresholds = vec![crate::quality::PathCoverageThreshold {
+            path: "crates/missing/**".to_string(),
+            threshold: 100,
+        }];
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+        let files = QualityGateChecker::parse_llvm_cov_files(llvm_cov_files_json()).unwrap();
+
+        let result = checker.evaluate_file_coverage(&files, "test-tool");
+
+        assert!(result.passed);
+        assert!(result.failures.is_empty());
+    }
+
+    #[test]
+    fn test_coverage_report_dir_requires_formats_and_artifacts_dir() {
+        let profile = create_test_profile(70, false, false, false, false);
+        let checker = QualityGateChecker::new(profile.clone(), "/tmp/test")
+            .with_artifacts_dir("/tmp/test/artifacts");
+        assert!(checker.coverage_report_dir().is_none());
+
+        let mut profile = profile;
+        profile.testing.coverage_reports = vec![CoverageReportFormat::Lcov];
+        let checker = QualityGateChecker::new(profile.clone(), "/tmp/test");
+        assert!(checker.coverage_report_dir().is_none());
+
+        let checker =
+            QualityGateChecker::new(profile, "/tmp/test").with_artifacts_dir("/tmp/test/artifacts");
+        assert_eq!(
+            checker.coverage_report_dir(),
+            Some(Path::new("/tmp/test/artifacts"))
+        );
+    }
+
+    #[test]
+    fn test_with_report_note_appends_to_details() {
+        let result = GateResult::pass("coverage", "Coverage ok");
+        let note = QualityGateChecker::format_report_note(
+            &[PathBuf::from("/tmp/artifacts/lcov.info")],
+            &["cobertura.xml: boom".to_string()],
+        );
+        let result = QualityGateChecker::with_report_note(result, note);
+
+        let details = result.details.unwrap();
+        assert!(details.contains("Coverage reports written: /tmp/artifacts/lcov.info"));
+        assert!(details.contains("Coverage report export failed: cobertura.xml: boom"));
+
+        let result = QualityGateChecker::with_report_note(
+            GateResult::pass("coverage", "Coverage ok"),
+            String::new(),
+        );
+        assert!(result.details.is_none());
+    }
+
+    #[test]
+    fn test_uses_test_selection() {
+        let mut profile = create_test_profile(0, true, false, false, false);
+        let checker = QualityGateChecker::new(profile.clone(), "/tmp/test")
+            .with_target_files(vec!["src/lib.rs".to_string()]);
+        assert!(!checker.uses_test_selection());
+
+        profile.testing.test_selection = TestSelectionMode::Impacted;
+        let checker = QualityGateChecker::new(profile.clone(), "/tmp/test");
+        assert!(!checker.uses_test_selection());
+
+        let checker = QualityGateChecker::new(profile, "/tmp/test")
+            .with_target_files(vec!["src/lib.rs".to_string()]);
+        assert!(checker.uses_test_selection());
+    }
+
+    #[test]
+    fn test_check_tests_full_disabled() {
+        let mut profile = create_test_profile(0, false, false, false, false);
+        profile.testing.test_selection = TestSelectionMode::Impacted;
+        let checker = QualityGateChecker::new(profile, "/tmp/test")
+            .with_target_files(vec!["src/lib.rs".to_string()]);
+
+        assert!(checker.check_tests().message.contains("Skipped"));
+        assert!(checker.check_tests_full().message.contains("Skipped"));
+    }
+
     // Lint gate tests
 
     #[test]
@@ -2244,6 +3830,45 @@ mod tests {
         assert!(result.message.contains("not enabled"));
     }
 
+    #[test]
+    fn test_clippy_args_default_denies_warnings() {
+        let profile = create_test_profile(0, false, true, false, false);
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+
+        assert_eq!(checker.clippy_args(), vec!["-D", "warnings"]);
+    }
+
+    #[test]
+    fn test_clippy_args_from_profile() {
+        let mut profile = create_test_profile(0, false, true, false, false);
+        profile.ci.lint_warn = vec!["clippy::pedantic".to_string()];
+        profile.ci.lint_deny = vec!["clippy::unwrap_used".to_string()];
+        profile.ci.lint_allow = vec!["clippy::module_name_repetitions".to_string()];
+        profile.ci.lint_flags = vec!["-F".to_string(), "unsafe_code".to_string()];
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+
+        assert_eq!(
+            checker.clippy_args(),
+            vec![
+                "-D",
+                "warnings",
+                "-W",
+                "clippy::pedantic",
+                "-D",
+                "clippy::unwrap_used",
+                "-A",
+                "clippy::module_name_repetitions",
+                "-F",
+                "unsafe_code",
+            ]
+        );
+
+        let mut profile = create_test_profile(0, false, true, false, false);
+        profile.ci.lint_allow_warnings = true;
+        let checker = QualityGateChecker::new(profile, "/tmp/test");
+        assert!(checker.clippy_args().is_empty());
+    }
+
     #[test]
     fn test_check_lint_enabled() {
         // This test runs against a real project directory if available
@@ -2517,6 +4142,63 @@ Diff in src/utils/helpers.rs at line 1:
         assert!(summary.contains("cargo fmt"));
     }
 
+    #[test]
+    fn test_extract_format_errors_diff_hunks() {
+        // Output of `cargo fmt --check -- --color never` from rustfmt 1.9
+        let stdout = "Diff in /proj/src/main.rs:1:
+-fn main(){let x=1;
+-    println!(\"{}\",x);}
++fn main() {
++    let x = 1;
++    println!(\"{}\", x);
++}
+ 
+ fn ok() {}
+ 
+Diff in /proj/src/main.rs:6:
+-fn b( a:u8 ){}
++fn b(a: u8) {}
+ 
+";
+        let failures = QualityGateChecker::extract_format_errors(stdout, "");
+
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].file.as_deref(), Some("/proj/src/main.rs"));
+        assert_eq!(failures[0].line, Some(1));
+        assert_eq!(
+            failures[0].message,
+            "File needs formatting: /proj/src/main.rs (lines 1-2)"
+        );
+        assert_eq!(
+            failures[0].suggestion.as_deref(),
+            Some(
+                "Replace lines 1-2 with:\nfn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}"
+            )
+        );
+
+        assert_eq!(failures[1].line, Some(6));
+        assert_eq!(
+            failures[1].suggestion.as_deref(),
+            Some("Replace line 6 with:\nfn b(a: u8) {}")
+        );
+
+        let summary = QualityGateChecker::format_format_summary(&failures);
+        assert!(summary.contains("1 file(s) need formatting"));
+        assert!(summary.contains("1. /proj/src/main.rs:1"));
+        assert!(summary.contains("2. /proj/src/main.rs:6"));
+    }
+
+    #[test]
+    fn test_extract_format_errors_removed_lines() {
+        let stdout = "Diff in src/lib.rs at line 3:\n fn a() {}\n-\n-\n fn b() {}\n";
+        let failures = QualityGateChecker::extract_format_errors(stdout, "");
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].file.as_deref(), Some("src/lib.rs"));
+        assert_eq!(failures[0].line, Some(4));
+        assert_eq!(failures[0].suggestion.as_deref(), Some("Remove lines 4-5"));
+    }
+
     #[test]
     fn test_format_format_summary() {
         let failures = vec![
@@ -2964,8 +4646,8 @@ error: critical issue
             updates.push(update);
         });
 
-        // Should have 10 updates (Running + Passed/Failed for each of 5 gates)
-        assert_eq!(updates.len(), 10);
+        // Should have 16 updates (Running + Passed/Failed for each of 8 gates)
+        assert_eq!(updates.len(), 16);
 
         // First update should be Running for coverage
         assert!(updates[0].is_running());
@@ -2988,10 +4670,20 @@ error: critical issue
             }
         });
 
-        // Should run gates in order: coverage, tests, lint, format, security_audit
+        // Should run gates in order: coverage, tests, integration_tests, typecheck, lint, format,
+        // security_audit, sast
         assert_eq!(
             gate_names,
-            vec!["coverage", "tests", "lint", "format", "security_audit"]
+            vec![
+                "coverage",
+                "tests",
+                "integration_tests",
+                "typecheck",
+                "lint",
+                "format",
+                "security_audit",
+                "sast"
+            ]
         );
     }
 
@@ -3031,16 +4723,19 @@ error: critical issue
             callback_count += 1;
         });
 
-        // Should return 5 gate results
-        assert_eq!(results.len(), 5);
+        // Should return 8 gate results
+        assert_eq!(results.len(), 8);
         assert_eq!(results[0].gate_name, "coverage");
         assert_eq!(results[1].gate_name, "tests");
-        assert_eq!(results[2].gate_name, "lint");
-        assert_eq!(results[3].gate_name, "format");
-        assert_eq!(results[4].gate_name, "security_audit");
+        assert_eq!(results[2].gate_name, "integration_tests");
+        assert_eq!(results[3].gate_name, "typecheck");
+        assert_eq!(results[4].gate_name, "lint");
+        assert_eq!(results[5].gate_name, "format");
+        assert_eq!(results[6].gate_name, "security_audit");
+        assert_eq!(results[7].gate_name, "sast");
 
-        // Callback should be called 10 times (2 per gate)
-        assert_eq!(callback_count, 10);
+        // Callback should be called 16 times (2 per gate)
+        assert_eq!(callback_count, 16);
     }
 
     #[test]
@@ -3054,7 +4749,15 @@ error: critical issue
         });
 
         // For each gate, Running should come before Passed/Failed
-        let gate_order = ["coverage", "lint", "format", "security_audit"];
+        let gate_order = [
+            "coverage",
+            "integration_tests",
+            "typecheck",
+            "lint",
+            "format",
+            "security_audit",
+            "sast",
+        ];
         for gate in gate_order {
             let running_pos = update_sequence
                 .iter()
@@ -3557,6 +5260,45 @@ test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out
         assert_eq!(failures[0].line, Some(25));
     }
 
+    #[test]
+    fn test_extract_test_failures_nextest_json_output() {
+        // Sample cargo nextest --message-format libtest-json-plus output
+        let stdout = r#"{"type":"suite","event":"started","test_count":2,"nextest":{"crate":"demo","test_binary":"demo","kind":"lib"}}
+{"type":"test","event":"started","name":"demo::demo$tests::test_passing"}
+{"type":"test","event":"ok","name":"demo::demo$tests::test_passing","exec_time":0.012}
+{"type":"test","event":"started","name":"demo::demo$tests::test_failing"}
+{"type":"test","event":"failed","name":"demo::demo$tests::test_failing","exec_time":1.5,"stdout":"thread 'tests::test_failing' panicked at src/lib.rs:9:5:\nassertion failed\n"}
+"#;
+
+        let failures = QualityGateChecker::extract_test_failures(stdout, "");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(
+            failures[0].error_code,
+            Some("tests::test_failing".to_string())
+        );
+        assert_eq!(failures[0].file, Some("src/lib.rs".to_string()));
+        assert_eq!(failures[0].line, Some(9));
+
+        let slowest = QualityGateChecker::slowest_tests(stdout);
+        assert_eq!(
+            slowest,
+            vec![
+                ("tests::test_failing".to_string(), 1.5),
+                ("tests::test_passing".to_string(), 0.012),
+            ]
+        );
+        let timings = QualityGateChecker::format_test_timings(&slowest).unwrap();
+        assert!(timings.starts_with("Slowest tests:"));
+        assert!(timings.contains("1.50s tests::test_failing"));
+    }
+
+    #[test]
+    fn test_slowest_tests_without_timings() {
+        let stdout = r#"{"type":"test","name":"tests::test_passing","event":"ok"}"#;
+        assert!(QualityGateChecker::slowest_tests(stdout).is_empty());
+        assert!(QualityGateChecker::format_test_timings(&[]).is_none());
+    }
+
     #[test]
     fn test_extract_test_failures_empty() {
         let failures = QualityGateChecker::extract_test_failures("", "");
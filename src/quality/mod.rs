@@ -3,19 +3,37 @@
 //! This module contains quality profiles and gate checking functionality.
 
 pub mod blog_generator;
+pub mod criteria;
 pub mod gates;
 pub mod profiles;
+pub mod sandbox;
+pub mod suppressions;
+pub mod test_selection;
+pub mod verify;
 
 // Re-exports for convenience - will be used by CLI and MCP in future stories
 #[allow(unused_imports)]
 pub use blog_generator::{slugify, BlogContext, BlogGenerator, BlogGeneratorError, BlogResult};
 #[allow(unused_imports)]
+pub use criteria::{
+    evaluate_criteria, CriteriaReport, CriterionResult, CriterionStatus, CRITERIA_EVIDENCE_KIND,
+};
+#[allow(unused_imports)]
 pub use gates::{
-    FailureCategory, GateFailureDetail, GateProgressState, GateProgressUpdate, GateResult,
-    QualityGateChecker,
+    FailureCategory, GateFailureDetail, GateProgressState, GateProgressUpdate, GateReport,
+    GateResult, QualityGateChecker, GATE_EVIDENCE_KIND,
 };
 #[allow(unused_imports)]
 pub use profiles::{
-    AuditConfig, AuditSections, BlogConfig, CiConfig, DocumentationConfig, Profile, ProfileLevel,
-    QualityConfig, QualityConfigError, SecurityConfig, TestingConfig,
+    AuditConfig, AuditSections, BlogConfig, CiConfig, CoverageReportFormat, DocumentationConfig,
+    GateSeverity, PathCoverageThreshold, Profile, ProfileLevel, QualityConfig, QualityConfigError,
+    SandboxConfig, SandboxMode, SecurityConfig, TestRunner, TestSelectionMode, TestingConfig,
 };
+#[allow(unused_imports)]
+pub use sandbox::GateCommand;
+#[allow(unused_imports)]
+pub use suppressions::{Suppression, Suppressions, SuppressionsError, SUPPRESSIONS_FILE};
+#[allow(unused_imports)]
+pub use test_selection::{ImpactedTestSelector, TestSelection};
+#[allow(unused_imports)]
+pub use verify::{verification_failure, verify_workspace, FINAL_VERIFICATION_STEP};
@@ -7,7 +7,8 @@
 #![allow(dead_code)]
 
 use config::{Config, ConfigError, Environment, File};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
@@ -51,6 +52,82 @@ pub struct TestingConfig {
     /// Minimum code coverage percentage (0-100)
     #[serde(default)]
     pub coverage_threshold: u8,
+    /// Glob patterns (relative to the project root) for files excluded from
+    /// coverage measurement, e.g. generated code or examples
+    #[serde(default)]
+    pub coverage_exclude: Vec<String>,
+    /// Path-scoped coverage thresholds enforced in addition to the global one
+    #[serde(default)]
+    pub path_thresholds: Vec<PathCoverageThreshold>,
+    /// Coverage report formats to export into the evidence directory
+    #[serde(default)]
+    pub coverage_reports: Vec<CoverageReportFormat>,
+    /// Which tests the test gate runs during story iterations
+    #[serde(default)]
+    pub test_selection: TestSelectionMode,
+    /// Which runner executes the test gate
+    #[serde(default)]
+    pub test_runner: TestRunner,
+    /// Number of times nextest retries a failing test before reporting it
+    #[serde(default)]
+    pub test_retries: u32,
+    /// Shell command for the integration test gate (defaults to `cargo test --test '*'`)
+    #[serde(default)]
+    pub integration_test_command: Option<String>,
+}
+
+/// Test runner used by the test gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TestRunner {
+    /// Use cargo-nextest when installed, falling back to `cargo test`
+    #[default]
+    Auto,
+    /// Always use `cargo test`
+    Cargo,
+    /// Always use cargo-nextest; the gate fails if it is not installed
+    Nextest,
+}
+
+/// Test selection strategy for the test gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TestSelectionMode {
+    /// Always run the full test suite
+    #[default]
+    Full,
+    /// Run only tests owning the story's target files during iterations,
+    /// with a full run reserved for the final gate pass
+    Impacted,
+}
+
+/// Coverage report formats that can be exported for external services.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageReportFormat {
+    /// LCOV tracefile (`lcov.info`), accepted by Codecov and Coveralls
+    Lcov,
+    /// Cobertura XML (`cobertura.xml`), accepted by SonarQube and most CI servers
+    Cobertura,
+}
+
+impl CoverageReportFormat {
+    /// File name used when the report is written to an artifacts directory.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            CoverageReportFormat::Lcov => "lcov.info",
+            CoverageReportFormat::Cobertura => "cobertura.xml",
+        }
+    }
+}
+
+/// A coverage threshold that applies only to files matching a glob pattern.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PathCoverageThreshold {
+    /// Glob pattern (relative to the project root) selecting the files
+    pub path: String,
+    /// Minimum line coverage percentage (0-100) for the matching files
+    pub threshold: u8,
 }
 
 /// CI requirements for a profile.
@@ -65,6 +142,24 @@ pub struct CiConfig {
     /// Whether lint checking is required
     #[serde(default)]
     pub lint_check: bool,
+    /// Whether a `cargo check` type-check gate runs before linting
+    #[serde(default)]
+    pub type_check: bool,
+    /// Clippy lints to warn on (`-W`), e.g. `clippy::pedantic`
+    #[serde(default)]
+    pub lint_warn: Vec<String>,
+    /// Clippy lints to deny (`-D`)
+    #[serde(default)]
+    pub lint_deny: Vec<String>,
+    /// Clippy lints to allow (`-A`)
+    #[serde(default)]
+    pub lint_allow: Vec<String>,
+    /// Extra flags passed to clippy after `--`
+    #[serde(default)]
+    pub lint_flags: Vec<String>,
+    /// Whether clippy warnings are tolerated instead of denied with `-D warnings`
+    #[serde(default)]
+    pub lint_allow_warnings: bool,
 }
 
 /// Security requirements for a profile.
@@ -79,6 +174,9 @@ pub struct SecurityConfig {
     /// Whether SAST (Static Application Security Testing) is required
     #[serde(default)]
     pub sast: bool,
+    /// Semgrep rulesets for the SAST gate (defaults to `p/rust`)
+    #[serde(default)]
+    pub sast_rulesets: Vec<String>,
 }
 
 /// Blog generation configuration for a profile.
@@ -172,9 +270,90 @@ fn default_max_high() -> u32 {
     5
 }
 
+/// How gate commands are isolated from the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxMode {
+    /// Run gate commands directly on the host
+    #[default]
+    None,
+    /// Run on the host with a scrubbed environment (only allow-listed variables)
+    Env,
+    /// Run inside a container with no network and bounded resources
+    Container,
+}
+
+/// Sandbox configuration for gate commands.
+///
+/// Resource and network limits are only enforced in `container` mode.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SandboxConfig {
+    /// Isolation mode for gate commands
+    #[serde(default)]
+    pub mode: SandboxMode,
+    /// Container runtime binary (e.g., "docker" or "podman")
+    #[serde(default = "default_sandbox_runtime")]
+    pub runtime: String,
+    /// Container image with the Rust toolchain and gate tools installed
+    #[serde(default = "default_sandbox_image")]
+    pub image: String,
+    /// Whether the container may access the network
+    #[serde(default)]
+    pub network: bool,
+    /// Memory limit passed to the runtime (e.g., "4g")
+    #[serde(default)]
+    pub memory: Option<String>,
+    /// CPU limit passed to the runtime (e.g., "2")
+    #[serde(default)]
+    pub cpus: Option<String>,
+    /// Extra volume mounts in runtime syntax (e.g., "/host/registry:/usr/local/cargo/registry:ro")
+    #[serde(default)]
+    pub mounts: Vec<String>,
+    /// Host environment variables passed through to gate commands
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            mode: SandboxMode::None,
+            runtime: default_sandbox_runtime(),
+            image: default_sandbox_image(),
+            network: false,
+            memory: None,
+            cpus: None,
+            mounts: Vec::new(),
+            env: Vec::new(),
+        }
+    }
+}
+
+fn default_sandbox_runtime() -> String {
+    "docker".to_string()
+}
+
+fn default_sandbox_image() -> String {
+    "rust:latest".to_string()
+}
+
+/// Severity of a quality gate failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GateSeverity {
+    /// Failure blocks story completion
+    #[default]
+    Blocking,
+    /// Failure is reported but does not block story completion
+    Warning,
+}
+
 /// A quality profile containing all configuration sections.
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct Profile {
+    /// Name of the profile this one inherits from, if any
+    #[serde(default)]
+    pub extends: Option<String>,
     /// Human-readable description of this profile
     #[serde(default)]
     pub description: String,
@@ -196,6 +375,22 @@ pub struct Profile {
     /// Audit configuration
     #[serde(default)]
     pub audit: AuditConfig,
+    /// Per-gate severity overrides keyed by gate name (e.g. `format = "warning"`)
+    #[serde(default)]
+    pub gate_severity: HashMap<String, GateSeverity>,
+    /// Isolation for gate commands
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+}
+
+impl Profile {
+    /// Get the configured severity for a gate, defaulting to blocking.
+    pub fn severity_for(&self, gate_name: &str) -> GateSeverity {
+        self.gate_severity
+            .get(gate_name)
+            .copied()
+            .unwrap_or_default()
+    }
 }
 
 /// Errors that can occur when loading quality configuration.
@@ -212,6 +407,14 @@ pub enum QualityConfigError {
     /// The configuration file path is invalid.
     #[error("invalid configuration path: {0}")]
     InvalidPath(String),
+
+    /// The configuration content could not be deserialized.
+    #[error("invalid configuration: {0}")]
+    InvalidFormat(String),
+
+    /// A profile's `extends` chain is invalid (unknown parent or cycle).
+    #[error("invalid profile inheritance: {0}")]
+    Inheritance(String),
 }
 
 /// Root configuration structure containing all quality profiles.
@@ -274,10 +477,30 @@ impl QualityConfig {
             )
             .build()?;
 
-        // Deserialize into QualityConfig
-        let quality_config: QualityConfig = config.try_deserialize()?;
+        // Resolve profile inheritance before deserializing into QualityConfig
+        let value: Value = config.try_deserialize()?;
+        Self::from_value(value)
+    }
 
-        Ok(quality_config)
+    /// Parse quality configuration from a TOML string, resolving inheritance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML is invalid or a profile's `extends` chain
+    /// references an unknown profile or contains a cycle.
+    pub fn from_toml_str(content: &str) -> Result<Self, QualityConfigError> {
+        let value: Value = toml::from_str(content)
+            .map_err(|e| QualityConfigError::InvalidFormat(e.to_string()))?;
+        Self::from_value(value)
+    }
+
+    /// Deserialize a raw configuration value after resolving `extends`.
+    fn from_value(mut value: Value) -> Result<Self, QualityConfigError> {
+        if let Some(profiles) = value.get_mut("profiles").and_then(Value::as_object_mut) {
+            let resolved = resolve_profile_inheritance(profiles)?;
+            *profiles = resolved;
+        }
+        serde_json::from_value(value).map_err(|e| QualityConfigError::InvalidFormat(e.to_string()))
     }
 
     /// Get a profile by its level.
@@ -301,6 +524,90 @@ impl QualityConfig {
     }
 }
 
+/// Resolve `extends` for every profile in a raw `profiles` table.
+///
+/// Merge semantics: the child is deep-merged over its fully resolved parent.
+/// Tables merge key by key; scalars and arrays in the child replace the
+/// parent's value outright. Chains may be arbitrarily deep.
+fn resolve_profile_inheritance(
+    profiles: &Map<String, Value>,
+) -> Result<Map<String, Value>, QualityConfigError> {
+    let mut resolved = Map::new();
+    for name in profiles.keys() {
+        resolve_profile(name, profiles, &mut resolved, &mut Vec::new())?;
+    }
+    Ok(resolved)
+}
+
+/// Resolve a single profile, recursing through its parents.
+///
+/// `chain` holds the profiles currently being resolved and is used to detect cycles.
+fn resolve_profile(
+    name: &str,
+    profiles: &Map<String, Value>,
+    resolved: &mut Map<String, Value>,
+    chain: &mut Vec<String>,
+) -> Result<Value, QualityConfigError> {
+    if let Some(profile) = resolved.get(name) {
+        return Ok(profile.clone());
+    }
+    if chain.iter().any(|n| n == name) {
+        chain.push(name.to_string());
+        return Err(QualityConfigError::Inheritance(format!(
+            "cycle detected: {}",
+            chain.join(" -> ")
+        )));
+    }
+
+    // Callers only pass names of existing profiles
+    let profile = profiles.get(name).cloned().unwrap_or(Value::Null);
+    let merged = match profile.get("extends") {
+        None | Some(Value::Null) => profile,
+        Some(Value::String(parent)) => {
+            if !profiles.contains_key(parent) {
+                return Err(QualityConfigError::Inheritance(format!(
+                    "profile '{}' extends unknown profile '{}'",
+                    name, parent
+                )));
+            }
+            chain.push(name.to_string());
+            let mut base = resolve_profile(parent, profiles, resolved, chain)?;
+            chain.pop();
+            if let Some(table) = base.as_object_mut() {
+                table.remove("extends");
+            }
+            merge_values(&mut base, profile);
+            base
+        }
+        Some(_) => {
+            return Err(QualityConfigError::Inheritance(format!(
+                "profile '{}' has a non-string 'extends' value",
+                name
+            )));
+        }
+    };
+
+    resolved.insert(name.to_string(), merged.clone());
+    Ok(merged)
+}
+
+/// Deep-merge `overlay` into `base`; tables merge, everything else replaces.
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base_table), Value::Object(overlay_table)) => {
+            for (key, value) in overlay_table {
+                match base_table.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base_table.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -533,4 +840,225 @@ mod tests {
         assert_eq!(profile.audit.max_critical_findings, 0);
         assert_eq!(profile.audit.max_high_findings, 5);
     }
+
+    #[test]
+    fn test_deserialize_coverage_path_rules() {
+        let toml_str = r#"
+            [profiles.test.testing]
+            coverage_threshold = 70
+            coverage_exclude = ["src/generated/**", "examples/**"]
+
+            [[profiles.test.testing.path_thresholds]]
+            path = "src/core/**"
+            threshold = 90
+        "#;
+
+        let config: QualityConfig = toml::from_str(toml_str).unwrap();
+        let testing = &config.get_profile_by_name("test").unwrap().testing;
+
+        assert_eq!(testing.coverage_threshold, 70);
+        assert_eq!(
+            testing.coverage_exclude,
+            vec!["src/generated/**".to_string(), "examples/**".to_string()]
+        );
+        assert_eq!(testing.path_thresholds.len(), 1);
+        assert_eq!(testing.path_thresholds[0].path, "src/core/**");
+        assert_eq!(testing.path_thresholds[0].threshold, 90);
+    }
+
+    #[test]
+    fn test_deserialize_coverage_reports() {
+        let toml_str = r#"
+            [profiles.test.testing]
+            coverage_threshold = 70
+            coverage_reports = ["lcov", "cobertura"]
+        "#;
+
+        let config: QualityConfig = toml::from_str(toml_str).unwrap();
+        let testing = &config.get_profile_by_name("test").unwrap().testing;
+
+        assert_eq!(
+            testing.coverage_reports,
+            vec![CoverageReportFormat::Lcov, CoverageReportFormat::Cobertura]
+        );
+        assert_eq!(CoverageReportFormat::Lcov.file_name(), "lcov.info");
+        assert_eq!(CoverageReportFormat::Cobertura.file_name(), "cobertura.xml");
+    }
+
+    #[test]
+    fn test_coverage_path_rules_default_empty() {
+        let testing = TestingConfig::default();
+        assert!(testing.coverage_exclude.is_empty());
+        assert!(testing.path_thresholds.is_empty());
+        assert_eq!(testing.test_selection, TestSelectionMode::Full);
+    }
+
+    #[test]
+    fn test_profile_inheritance_merges_fields() {
+        let toml_str = r#"
+            [profiles.strict]
+            description = "Strict profile"
+
+            [profiles.strict.testing]
+            unit_tests = true
+            integration_tests = true
+            coverage_threshold = 90
+
+            [profiles.strict.ci]
+            required = true
+            format_check = true
+            lint_check = true
+
+            [profiles.relaxed]
+            extends = "strict"
+
+            [profiles.relaxed.testing]
+            coverage_threshold = 60
+
+            [profiles.relaxed.ci]
+            format_check = false
+        "#;
+
+        let config = QualityConfig::from_toml_str(toml_str).unwrap();
+        let relaxed = config.get_profile_by_name("relaxed").unwrap();
+
+        assert_eq!(relaxed.extends.as_deref(), Some("strict"));
+        assert_eq!(relaxed.description, "Strict profile");
+        assert_eq!(relaxed.testing.coverage_threshold, 60);
+        assert!(relaxed.testing.unit_tests);
+        assert!(relaxed.testing.integration_tests);
+        assert!(!relaxed.ci.format_check);
+        assert!(relaxed.ci.lint_check);
+
+        // The parent is unaffected by its child
+        let strict = config.get_profile_by_name("strict").unwrap();
+        assert_eq!(strict.testing.coverage_threshold, 90);
+        assert!(strict.extends.is_none());
+    }
+
+    #[test]
+    fn test_profile_inheritance_multi_level_and_arrays_replace() {
+        let toml_str = r#"
+            [profiles.base.testing]
+            coverage_threshold = 50
+            coverage_exclude = ["examples/**", "benches/**"]
+
+            [profiles.middle]
+            extends = "base"
+            description = "Middle"
+
+            [profiles.leaf]
+            extends = "middle"
+
+            [profiles.leaf.testing]
+            coverage_exclude = ["src/generated/**"]
+        "#;
+
+        let config = QualityConfig::from_toml_str(toml_str).unwrap();
+        let leaf = config.get_profile_by_name("leaf").unwrap();
+
+        assert_eq!(leaf.extends.as_deref(), Some("middle"));
+        assert_eq!(leaf.description, "Middle");
+        assert_eq!(leaf.testing.coverage_threshold, 50);
+        assert_eq!(leaf.testing.coverage_exclude, vec!["src/generated/**"]);
+    }
+
+    #[test]
+    fn test_profile_inheritance_cycle_detected() {
+        let toml_str = r#"
+            [profiles.a]
+            extends = "b"
+
+            [profiles.b]
+            extends = "c"
+
+            [profiles.c]
+            extends = "a"
+        "#;
+
+        let err = QualityConfig::from_toml_str(toml_str).unwrap_err();
+        assert!(matches!(err, QualityConfigError::Inheritance(_)));
+        assert!(err.to_string().contains("cycle detected"), "{}", err);
+    }
+
+    #[test]
+    fn test_profile_inheritance_self_reference_is_cycle() {
+        let toml_str = r#"
+            [profiles.loop]
+            extends = "loop"
+        "#;
+
+        let err = QualityConfig::from_toml_str(toml_str).unwrap_err();
+        assert!(err.to_string().contains("cycle detected: loop -> loop"));
+    }
+
+    #[test]
+    fn test_profile_inheritance_unknown_parent() {
+        let toml_str = r#"
+            [profiles.custom]
+            extends = "missing"
+        "#;
+
+        let err = QualityConfig::from_toml_str(toml_str).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid profile inheritance: profile 'custom' extends unknown profile 'missing'"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_sandbox_config() {
+        let toml_str = r#"
+            [profiles.locked.sandbox]
+            mode = "container"
+            image = "rust:1.80"
+            memory = "4g"
+            cpus = "2"
+            env = ["CARGO_TERM_COLOR"]
+        "#;
+
+        let config = QualityConfig::from_toml_str(toml_str).unwrap();
+        let sandbox = &config.get_profile_by_name("locked").unwrap().sandbox;
+
+        assert_eq!(sandbox.mode, SandboxMode::Container);
+        assert_eq!(sandbox.runtime, "docker");
+        assert_eq!(sandbox.image, "rust:1.80");
+        assert!(!sandbox.network);
+        assert_eq!(sandbox.memory.as_deref(), Some("4g"));
+        assert_eq!(sandbox.cpus.as_deref(), Some("2"));
+        assert_eq!(sandbox.env, vec!["CARGO_TERM_COLOR"]);
+
+        // Sandboxing is off unless configured
+        assert_eq!(Profile::default().sandbox.mode, SandboxMode::None);
+    }
+
+    #[test]
+    fn test_deserialize_gate_severity() {
+        let toml_str = r#"
+            [profiles.test.gate_severity]
+            format = "warning"
+            lint = "blocking"
+        "#;
+
+        let config: QualityConfig = toml::from_str(toml_str).unwrap();
+        let profile = config.get_profile_by_name("test").unwrap();
+
+        assert_eq!(profile.severity_for("format"), GateSeverity::Warning);
+        assert_eq!(profile.severity_for("lint"), GateSeverity::Blocking);
+        assert_eq!(profile.severity_for("tests"), GateSeverity::Blocking);
+    }
+
+    #[test]
+    fn test_deserialize_test_selection() {
+        let toml_str = r#"
+            [profiles.test.testing]
+            unit_tests = true
+            test_selection = "impacted"
+        "#;
+
+        let config: QualityConfig = toml::from_str(toml_str).unwrap();
+        let testing = &config.get_profile_by_name("test").unwrap().testing;
+
+        assert_eq!(testing.test_selection, TestSelectionMode::Impacted);
+    }
 }
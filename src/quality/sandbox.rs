@@ -0,0 +1,310 @@
+//! Sandboxed execution of quality gate commands.
+//!
+//! Gate commands run agent-generated code (build scripts, proc macros, tests).
+//! This module wraps them according to the profile's [`SandboxConfig`] so that
+//! code can be run with a scrubbed environment, or inside a container with no
+//! network and bounded memory/CPU.
+
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use crate::agent::container::{host_user_args, same_path_mount};
+use crate::quality::{SandboxConfig, SandboxMode};
+use crate::resources::ResourceLimits;
+
+/// Host variables always kept in `env` mode so the toolchain keeps working.
+const BASE_ENV_ALLOWLIST: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "TMPDIR",
+    "LANG",
+    "CARGO_HOME",
+    "RUSTUP_HOME",
+    "RUSTUP_TOOLCHAIN",
+];
+
+/// A gate command that is built into a [`Command`] according to a sandbox policy.
+///
+/// Mirrors the subset of the [`Command`] builder used by the gates, so call
+/// sites read the same whether or not sandboxing is enabled.
+#[derive(Debug, Clone)]
+pub struct GateCommand {
+    /// Program to run (e.g., "cargo")
+    program: String,
+    /// Arguments passed to the program
+    args: Vec<OsString>,
+    /// Environment variables set for the program
+    envs: Vec<(String, String)>,
+    /// Working directory (the project root)
+    cwd: PathBuf,
+    /// Sandbox policy to apply
+    sandbox: SandboxConfig,
+    /// Per-worker resource limits to apply
+    limits: ResourceLimits,
+}
+
+impl GateCommand {
+    /// Create a command for `program` run in `cwd` under the given sandbox policy.
+    pub fn new(
+        program: impl Into<String>,
+        cwd: impl Into<PathBuf>,
+        sandbox: &SandboxConfig,
+    ) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            envs: Vec::new(),
+            cwd: cwd.into(),
+            sandbox: sandbox.clone(),
+            limits: ResourceLimits::default(),
+        }
+    }
+
+    /// Apply per-worker resource limits to the command.
+    pub fn limits(&mut self, limits: &ResourceLimits) -> &mut Self {
+        self.limits = limits.clone();
+        self
+    }
+
+    /// Add an argument.
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    /// Add multiple arguments.
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    /// Set an environment variable for the command.
+    pub fn env(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Build the process command, wrapped according to the sandbox mode.
+    pub fn build(&self) -> Command {
+        match self.sandbox.mode {
+            SandboxMode::None => {
+                let (program, args) = self.limits.wrap(&self.program, &self.args);
+                let mut command = Command::new(program);
+                command.args(args).current_dir(&self.cwd);
+                command.envs(self.envs.iter().map(|(k, v)| (k, v)));
+                command
+            }
+            SandboxMode::Env => {
+                let (program, args) = self.limits.wrap(&self.program, &self.args);
+                let mut command = Command::new(program);
+                command.args(args).current_dir(&self.cwd).env_clear();
+                for key in self.allowed_env() {
+                    if let Some(value) = std::env::var_os(key) {
+                        command.env(key, value);
+                    }
+                }
+                command.envs(self.envs.iter().map(|(k, v)| (k, v)));
+                command
+            }
+            SandboxMode::Container => {
+                let mut command = Command::new(&self.sandbox.runtime);
+                command.args(self.container_args());
+                command
+            }
+        }
+    }
+
+    /// Run the command and collect its output.
+    pub fn output(&self) -> io::Result<Output> {
+        self.build().output()
+    }
+
+    /// Host variables passed through in `env` mode.
+    fn allowed_env(&self) -> impl Iterator<Item = &str> {
+        BASE_ENV_ALLOWLIST
+            .iter()
+            .copied()
+            .chain(self.sandbox.env.iter().map(String::as_str))
+    }
+
+    /// Arguments for `<runtime> run ...` executing the command in a container.
+    ///
+    /// The project root is mounted at the same path so file paths in gate
+    /// output match the host, and the command runs as the user owning it so
+    /// build output is not left owned by root. The container gets no host
+    /// environment other than the configured pass-through variables.
+    fn container_args(&self) -> Vec<OsString> {
+        let root = self.cwd.as_os_str();
+        let mut args: Vec<OsString> = vec!["run".into(), "--rm".into()];
+        if !self.sandbox.network {
+            args.extend(["--network".into(), "none".into()]);
+        }
+        if let Some(ref memory) = self.sandbox.memory {
+            args.extend(["--memory".into(), memory.into()]);
+        }
+        if let Some(ref cpus) = self.sandbox.cpus {
+            args.extend(["--cpus".into(), cpus.into()]);
+        }
+        // Worker limits only fill in what the sandbox profile leaves unset
+        let mut limits = self.limits.clone();
+        if self.sandbox.memory.is_some() {
+            limits.max_rss_mb = None;
+        }
+        args.extend(limits.container_args().into_iter().map(OsString::from));
+        args.extend(host_user_args(&self.sandbox.runtime, &self.cwd));
+        args.push("--volume".into());
+        args.push(same_path_mount(&self.cwd));
+        for mount in &self.sandbox.mounts {
+            args.extend(["--volume".into(), mount.into()]);
+        }
+        args.extend(["--workdir".into(), root.to_os_string()]);
+        for key in &self.sandbox.env {
+            args.extend(["--env".into(), key.into()]);
+        }
+        for (key, value) in &self.envs {
+            args.extend(["--env".into(), format!("{}={}", key, value).into()]);
+        }
+        args.push(self.sandbox.image.clone().into());
+        args.push(self.program.clone().into());
+        args.extend(self.args.iter().cloned());
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn args_of(command: &Command) -> Vec<String> {
+        command
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_no_sandbox_runs_program_directly() {
+        let mut gate = GateCommand::new("cargo", "/work/proj", &SandboxConfig::default());
+        gate.args(["fmt", "--check"]).env("FOO", "1");
+        let command = gate.build();
+
+        assert_eq!(command.get_program(), "cargo");
+        assert_eq!(args_of(&command), vec!["fmt", "--check"]);
+        assert_eq!(command.get_current_dir(), Some(Path::new("/work/proj")));
+        assert!(command
+            .get_envs()
+            .any(|(k, v)| k == "FOO" && v == Some(OsStr::new("1"))));
+    }
+
+    #[test]
+    fn test_env_sandbox_clears_environment() {
+        let sandbox = SandboxConfig {
+            mode: SandboxMode::Env,
+            env: vec!["RALPH_SANDBOX_TEST_VAR".to_string()],
+            ..Default::default()
+        };
+        let mut gate = GateCommand::new("sh", "/", &sandbox);
+        gate.args(["-c", "env"]).env("EXTRA", "yes");
+
+        std::env::set_var("RALPH_SANDBOX_TEST_VAR", "kept");
+        std::env::set_var("RALPH_SANDBOX_SECRET", "leaked");
+        let output = gate.output().unwrap();
+        let env = String::from_utf8_lossy(&output.stdout);
+
+        assert!(env.contains("RALPH_SANDBOX_TEST_VAR=kept"));
+        assert!(env.contains("EXTRA=yes"));
+        assert!(!env.contains("RALPH_SANDBOX_SECRET"));
+    }
+
+    #[test]
+    fn test_container_sandbox_wraps_command() {
+        let sandbox = SandboxConfig {
+            mode: SandboxMode::Container,
+            runtime: "podman".to_string(),
+            image: "rust:1.80".to_string(),
+            memory: Some("4g".to_string()),
+            cpus: Some("2".to_string()),
+            mounts: vec!["/cache/registry:/usr/local/cargo/registry:ro".to_string()],
+            env: vec!["CARGO_TERM_COLOR".to_string()],
+            ..Default::default()
+        };
+        let mut gate = GateCommand::new("cargo", "/work/proj", &sandbox);
+        gate.args(["nextest", "run"])
+            .env("NEXTEST_EXPERIMENTAL_LIBTEST_JSON", "1");
+        let command = gate.build();
+
+        assert_eq!(command.get_program(), "podman");
+        assert_eq!(
+            args_of(&command),
+            vec![
+                "run",
+                "--rm",
+                "--network",
+                "none",
+                "--memory",
+                "4g",
+                "--cpus",
+                "2",
+                "--userns",
+                "keep-id",
+                "--volume",
+                "/work/proj:/work/proj",
+                "--volume",
+                "/cache/registry:/usr/local/cargo/registry:ro",
+                "--workdir",
+                "/work/proj",
+                "--env",
+                "CARGO_TERM_COLOR",
+                "--env",
+                "NEXTEST_EXPERIMENTAL_LIBTEST_JSON=1",
+                "rust:1.80",
+                "cargo",
+                "nextest",
+                "run",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_container_sandbox_with_network() {
+        let sandbox = SandboxConfig {
+            mode: SandboxMode::Container,
+            network: true,
+            ..Default::default()
+        };
+        let command = GateCommand::new("cargo", "/p", &sandbox).build();
+        let args = args_of(&command);
+
+        assert_eq!(command.get_program(), "docker");
+        assert!(!args.contains(&"none".to_string()));
+        assert!(args.contains(&"rust:latest".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_container_sandbox_runs_as_project_owner() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let sandbox = SandboxConfig {
+            mode: SandboxMode::Container,
+            ..Default::default()
+        };
+        let args = args_of(&GateCommand::new("cargo", dir.path(), &sandbox).build());
+
+        let metadata = std::fs::metadata(dir.path()).unwrap();
+        let user = format!("{}:{}", metadata.uid(), metadata.gid());
+        let at = args.iter().position(|a| a == "--user").unwrap();
+        assert_eq!(args[at + 1], user);
+    }
+}
@@ -0,0 +1,402 @@
+//! Impacted-test selection for quality gates.
+//!
+//! This module maps a story's target files to the workspace packages, test
+//! targets, and modules that own them (via `cargo metadata`), so that the test
+//! gate can run only the impacted tests during iterations.
+
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// Package information from `cargo metadata --format-version 1`.
+#[derive(Debug, Clone, Deserialize)]
+struct MetadataPackage {
+    /// Package name
+    name: String,
+    /// Absolute path to the package's Cargo.toml
+    manifest_path: PathBuf,
+    /// Build targets (lib, bins, tests, benches, examples)
+    #[serde(default)]
+    targets: Vec<MetadataTarget>,
+}
+
+/// Build target information from `cargo metadata`.
+#[derive(Debug, Clone, Deserialize)]
+struct MetadataTarget {
+    /// Target name
+    name: String,
+    /// Target kinds (e.g., "lib", "bin", "test")
+    #[serde(default)]
+    kind: Vec<String>,
+    /// Absolute path to the target's root source file
+    src_path: PathBuf,
+}
+
+/// Root structure of `cargo metadata` output.
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    /// Workspace packages (only members when run with `--no-deps`)
+    packages: Vec<MetadataPackage>,
+}
+
+/// How narrowly a single target file could be mapped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FileScope {
+    /// File is a module inside a package's `src/` directory
+    Module(String),
+    /// File is the root of an integration test target
+    TestTarget(String),
+    /// File affects the whole package (crate roots, manifests, globs, ...)
+    Package,
+}
+
+/// A selection of test targets to run for a set of changed files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestSelection {
+    /// Packages that own at least one target file
+    pub packages: Vec<String>,
+    /// Integration test targets to run (empty means all targets)
+    pub test_targets: Vec<String>,
+    /// Test name filters (module paths) passed to the test harness
+    pub filters: Vec<String>,
+}
+
+impl TestSelection {
+    /// Build the arguments to append to `cargo test`.
+    pub fn cargo_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for package in &self.packages {
+            args.push("-p".to_string());
+            args.push(package.clone());
+        }
+        for target in &self.test_targets {
+            args.push("--test".to_string());
+            args.push(target.clone());
+        }
+        if !self.filters.is_empty() {
+            args.push("--".to_string());
+            args.extend(self.filters.iter().cloned());
+        }
+        args
+    }
+
+    /// Human-readable description of the selection.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("packages: {}", self.packages.join(", "))];
+        if !self.test_targets.is_empty() {
+            parts.push(format!("tests: {}", self.test_targets.join(", ")));
+        }
+        if !self.filters.is_empty() {
+            parts.push(format!("modules: {}", self.filters.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Selects impacted tests for a set of target files.
+pub struct ImpactedTestSelector {
+    /// Canonical project root
+    project_root: PathBuf,
+    /// Workspace packages from cargo metadata
+    packages: Vec<MetadataPackage>,
+}
+
+impl ImpactedTestSelector {
+    /// Load workspace metadata by running `cargo metadata` in the project root.
+    ///
+    /// Returns `None` if cargo metadata cannot be run or parsed.
+    pub fn load(project_root: &Path) -> Option<Self> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--no-deps", "--format-version", "1"])
+            .current_dir(project_root)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::from_metadata_json(project_root, &String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Build a selector from `cargo metadata` JSON output.
+    fn from_metadata_json(project_root: &Path, json: &str) -> Option<Self> {
+        let metadata: Metadata = serde_json::from_str(json).ok()?;
+        let project_root =
+            std::fs::canonicalize(project_root).unwrap_or_else(|_| project_root.to_path_buf());
+        Some(Self {
+            project_root,
+            packages: metadata.packages,
+        })
+    }
+
+    /// Select the tests impacted by the given target files.
+    ///
+    /// Target files are paths or glob patterns relative to the project root.
+    /// Returns `None` when no file maps to a workspace package, in which case
+    /// the full test suite should be run.
+    pub fn select(&self, target_files: &[String]) -> Option<TestSelection> {
+        let mut packages = BTreeSet::new();
+        let mut scopes = Vec::new();
+
+        for target in target_files {
+            let (path, is_glob) = Self::literal_prefix(target);
+            let absolute = self.project_root.join(&path);
+            let Some(package) = self.owning_package(&absolute) else {
+                continue;
+            };
+            packages.insert(package.name.clone());
+            let scope = if is_glob {
+                FileScope::Package
+            } else {
+                Self::file_scope(package, &absolute)
+            };
+            scopes.push(scope);
+        }
+
+        if packages.is_empty() {
+            return None;
+        }
+
+        let mut selection = TestSelection {
+            packages: packages.into_iter().collect(),
+            ..Default::default()
+        };
+
+        // Narrow further only when every file maps to the same kind of scope;
+        // mixing module filters with test targets would filter out the targets.
+        let modules: BTreeSet<String> = scopes
+            .iter()
+            .filter_map(|s| match s {
+                FileScope::Module(m) => Some(m.clone()),
+                _ => None,
+            })
+            .collect();
+        let tests: BTreeSet<String> = scopes
+            .iter()
+            .filter_map(|s| match s {
+                FileScope::TestTarget(t) => Some(t.clone()),
+                _ => None,
+            })
+            .collect();
+        if scopes.iter().all(|s| matches!(s, FileScope::Module(_))) {
+            selection.filters = modules.into_iter().collect();
+        } else if scopes.iter().all(|s| matches!(s, FileScope::TestTarget(_))) {
+            selection.test_targets = tests.into_iter().collect();
+        }
+
+        Some(selection)
+    }
+
+    /// Find the package whose directory most specifically contains the path.
+    fn owning_package(&self, path: &Path) -> Option<&MetadataPackage> {
+        self.packages
+            .iter()
+            .filter_map(|p| p.manifest_path.parent().map(|dir| (p, dir)))
+            .filter(|(_, dir)| path.starts_with(dir))
+            .max_by_key(|(_, dir)| dir.components().count())
+            .map(|(p, _)| p)
+    }
+
+    /// Determine how narrowly a concrete file within a package can be tested.
+    fn file_scope(package: &MetadataPackage, path: &Path) -> FileScope {
+        if let Some(target) = package
+            .targets
+            .iter()
+            .find(|t| t.src_path == path && t.kind.iter().any(|k| k == "test"))
+        {
+            return FileScope::TestTarget(target.name.clone());
+        }
+
+        let Some(src_dir) = package.manifest_path.parent().map(|d| d.join("src")) else {
+            return FileScope::Package;
+        };
+        match path.strip_prefix(&src_dir) {
+            Ok(relative) => Self::module_path(relative)
+                .map(FileScope::Module)
+                .unwrap_or(FileScope::Package),
+            Err(_) => FileScope::Package,
+        }
+    }
+
+    /// Convert a path relative to `src/` into a Rust module path.
+    ///
+    /// Returns `None` for crate roots (`lib.rs`, `main.rs`) and non-Rust files.
+    fn module_path(relative: &Path) -> Option<String> {
+        if relative.extension().and_then(|e| e.to_str()) != Some("rs") {
+            return None;
+        }
+        let mut segments: Vec<String> = relative
+            .with_extension("")
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => s.to_str().map(String::from),
+                _ => None,
+            })
+            .collect();
+        if segments.last().map(String::as_str) == Some("mod") {
+            segments.pop();
+        }
+        if segments.is_empty() || matches!(segments.as_slice(), [s] if s == "lib" || s == "main") {
+            return None;
+        }
+        Some(segments.join("::"))
+    }
+
+    /// Get the literal (non-glob) prefix of a target file pattern.
+    ///
+    /// Returns the prefix path and whether the pattern contained glob syntax.
+    fn literal_prefix(pattern: &str) -> (PathBuf, bool) {
+        let mut prefix = PathBuf::new();
+        for component in Path::new(pattern).components() {
+            let text = component.as_os_str().to_string_lossy();
+            if text.contains(['*', '?', '[', '{']) {
+                return (prefix, true);
+            }
+            prefix.push(component);
+        }
+        (prefix, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata_json() -> String {
+        r#"{
+            "packages": [
+                {
+                    "name": "app",
+                    "manifest_path": "/work/Cargo.toml",
+                    "targets": [
+                        {"name": "app", "kind": ["lib"], "src_path": "/work/src/lib.rs"},
+                        {"name": "api_tests", "kind": ["test"], "src_path": "/work/tests/api_tests.rs"}
+                    ]
+                },
+                {
+                    "name": "app-cli",
+                    "manifest_path": "/work/cli/Cargo.toml",
+                    "targets": [
+                        {"name": "app", "kind": ["bin"], "src_path": "/work/cli/src/main.rs"}
+                    ]
+                }
+            ]
+        }"#
+        .to_string()
+    }
+
+    fn selector() -> ImpactedTestSelector {
+        ImpactedTestSelector::from_metadata_json(Path::new("/work"), &metadata_json()).unwrap()
+    }
+
+    fn files(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_select_module_filters() {
+        let selection = selector()
+            .select(&files(&[
+                "src/quality/gates.rs",
+                "src/parallel/mod.rs",
+                "src/quality/gates.rs",
+            ]))
+            .unwrap();
+
+        assert_eq!(selection.packages, vec!["app"]);
+        assert!(selection.test_targets.is_empty());
+        assert_eq!(selection.filters, vec!["parallel", "quality::gates"]);
+        assert_eq!(
+            selection.cargo_args(),
+            vec!["-p", "app", "--", "parallel", "quality::gates"]
+        );
+    }
+
+    #[test]
+    fn test_select_integration_test_target() {
+        let selection = selector().select(&files(&["tests/api_tests.rs"])).unwrap();
+
+        assert_eq!(selection.packages, vec!["app"]);
+        assert_eq!(selection.test_targets, vec!["api_tests"]);
+        assert!(selection.filters.is_empty());
+        assert_eq!(
+            selection.cargo_args(),
+            vec!["-p", "app", "--test", "api_tests"]
+        );
+    }
+
+    #[test]
+    fn test_select_nested_package_and_crate_root() {
+        let selection = selector()
+            .select(&files(&["cli/src/main.rs", "src/runner.rs"]))
+            .unwrap();
+
+        // The CLI crate root needs the whole package, so module filters are dropped
+        assert_eq!(selection.packages, vec!["app", "app-cli"]);
+        assert!(selection.filters.is_empty());
+        assert!(selection.test_targets.is_empty());
+    }
+
+    #[test]
+    fn test_select_glob_pattern_is_package_scoped() {
+        let selection = selector().select(&files(&["cli/src/**/*.rs"])).unwrap();
+
+        assert_eq!(selection.packages, vec!["app-cli"]);
+        assert!(selection.filters.is_empty());
+    }
+
+    #[test]
+    fn test_select_no_matching_package() {
+        let selector =
+            ImpactedTestSelector::from_metadata_json(Path::new("/elsewhere"), &metadata_json())
+                .unwrap();
+        assert!(selector.select(&files(&["src/lib.rs"])).is_none());
+        assert!(selector.select(&[]).is_none());
+    }
+
+    #[test]
+    fn test_module_path() {
+        assert_eq!(
+            ImpactedTestSelector::module_path(Path::new("quality/gates.rs")),
+            Some("quality::gates".to_string())
+        );
+        assert_eq!(
+            ImpactedTestSelector::module_path(Path::new("quality/mod.rs")),
+            Some("quality".to_string())
+        );
+        assert_eq!(ImpactedTestSelector::module_path(Path::new("lib.rs")), None);
+        assert_eq!(
+            ImpactedTestSelector::module_path(Path::new("main.rs")),
+            None
+        );
+        assert_eq!(
+            ImpactedTestSelector::module_path(Path::new("data.json")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(
+            ImpactedTestSelector::literal_prefix("src/quality/*.rs"),
+            (PathBuf::from("src/quality"), true)
+        );
+        assert_eq!(
+            ImpactedTestSelector::literal_prefix("src/lib.rs"),
+            (PathBuf::from("src/lib.rs"), false)
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let selection = TestSelection {
+            packages: vec!["app".to_string()],
+            test_targets: vec![],
+            filters: vec!["quality::gates".to_string()],
+        };
+        assert_eq!(
+            selection.describe(),
+            "packages: app; modules: quality::gates"
+        );
+    }
+}
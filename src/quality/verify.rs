@@ -0,0 +1,108 @@
+//! Verifying the integrated tree once every story has finished.
+//!
+//! Each story's gates check the tree as that story left it, so two stories
+//! that pass on their own can still break each other once both are merged.
+//! The final verification runs the complete gate suite once more on the
+//! working directory after the last story, with the full test suite rather
+//! than impacted tests, and the run only succeeds if it passes.
+
+use std::path::Path;
+
+use crate::evidence::EvidenceWriter;
+use crate::quality::gates::{GateResult, QualityGateChecker};
+use crate::quality::profiles::Profile;
+
+/// Step ID the final verification's gate results are recorded under.
+pub const FINAL_VERIFICATION_STEP: &str = "final-verification";
+
+/// Run every gate of `profile` on the integrated tree at `project_root`.
+///
+/// Gate artifacts go to a `final-verification` subdirectory of
+/// `artifacts_dir`, and gate results are recorded in `evidence`.
+pub fn verify_workspace(
+    profile: Profile,
+    project_root: &Path,
+    artifacts_dir: Option<&Path>,
+    evidence: Option<EvidenceWriter>,
+) -> Vec<GateResult> {
+    let mut checker = QualityGateChecker::new(profile, project_root);
+    if let Some(artifacts_dir) = artifacts_dir {
+        checker = checker.with_artifacts_dir(artifacts_dir.join(FINAL_VERIFICATION_STEP));
+    }
+    if let Some(evidence) = evidence {
+        checker = checker.with_evidence(evidence, FINAL_VERIFICATION_STEP);
+    }
+    checker.run_all()
+}
+
+/// Why the final verification failed, naming the blocking gates that
+/// failed, or `None` if it passed.
+pub fn verification_failure(results: &[GateResult]) -> Option<String> {
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|result| result.is_blocking_failure())
+        .map(|result| result.gate_name.as_str())
+        .collect();
+    (!failed.is_empty()).then(|| format!("Final verification failed: {}", failed.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::{EvidenceStore, EvidenceStoreConfig};
+    use crate::quality::gates::GATE_EVIDENCE_KIND;
+    use crate::quality::profiles::{CiConfig, GateSeverity, SecurityConfig, TestingConfig};
+
+    #[test]
+    fn test_verify_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = EvidenceWriter::try_new(dir.path(), "run-verify".to_string()).unwrap();
+        let profile = Profile {
+            testing: TestingConfig {
+                coverage_threshold: 0,
+                unit_tests: false,
+                integration_tests: false,
+                ..Default::default()
+            },
+            ci: CiConfig {
+                lint_check: false,
+                format_check: false,
+                type_check: false,
+                ..Default::default()
+            },
+            security: SecurityConfig {
+                cargo_audit: false,
+                cargo_deny: false,
+                sast: false,
+                sast_rulesets: Vec::new(),
+            },
+            ..Default::default()
+        };
+
+        let results = verify_workspace(profile, dir.path(), None, Some(writer));
+        assert!(verification_failure(&results).is_none());
+
+        let store = EvidenceStore::new(dir.path(), EvidenceStoreConfig::default()).unwrap();
+        let records: Vec<_> = store
+            .load_events("run-verify")
+            .unwrap()
+            .into_iter()
+            .filter(|record| record.kind == GATE_EVIDENCE_KIND)
+            .collect();
+        assert_eq!(records.len(), results.len());
+        assert!(records
+            .iter()
+            .all(|record| record.payload["step_id"] == FINAL_VERIFICATION_STEP));
+
+        let results = vec![
+            GateResult::pass("typecheck", "ok"),
+            GateResult::fail("tests", "2 failed", None, None),
+            GateResult::fail("lint", "1 warning", None, None).with_severity(GateSeverity::Warning),
+            GateResult::fail("format", "unformatted", None, None),
+        ];
+        assert_eq!(
+            verification_failure(&results).as_deref(),
+            Some("Final verification failed: tests, format")
+        );
+    }
+}
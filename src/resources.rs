@@ -0,0 +1,277 @@
+//! Per-worker resource limits for agent and gate subprocesses.
+//!
+//! Limits are applied by launching each subprocess through a small `sh`
+//! wrapper that sets `ulimit` and `nice` before `exec`ing the real program, so
+//! they hold for the process and everything it spawns. Scratch space is a
+//! per-story directory exposed as `TMPDIR` whose size is checked against a quota.
+
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Default CPU weight, matching the cgroup `cpu.shares` default.
+const DEFAULT_CPU_SHARES: u32 = 1024;
+
+/// Script run by `sh -c`; `$0` and `$@` are the wrapped program and its arguments.
+const WRAPPER_EXEC: &str = "exec \"$0\" \"$@\"";
+
+/// Resource constraints applied to every subprocess a worker starts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Maximum memory per subprocess in megabytes. Enforced as an address-space
+    /// limit (`ulimit -v`), which is an upper bound on resident memory.
+    pub max_rss_mb: Option<u64>,
+    /// Relative CPU weight (1-1024, like cgroup `cpu.shares`). Weights below
+    /// the default lower the subprocess priority with `nice`.
+    pub cpu_shares: Option<u32>,
+    /// Maximum size of the story's scratch directory in megabytes.
+    pub scratch_quota_mb: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Whether no limit is configured.
+    pub fn is_empty(&self) -> bool {
+        self.max_rss_mb.is_none() && self.cpu_shares.is_none() && self.scratch_quota_mb.is_none()
+    }
+
+    /// `nice` increment equivalent to the configured CPU weight.
+    ///
+    /// Each nice level changes the scheduler weight by roughly 1.25x, so half
+    /// the default weight maps to about +3. Weights above the default map to 0
+    /// since raising priority needs privileges.
+    pub fn nice_level(&self) -> Option<u8> {
+        let shares = self.cpu_shares?.clamp(1, DEFAULT_CPU_SHARES);
+        let level = (f64::from(DEFAULT_CPU_SHARES) / f64::from(shares))
+            .log(1.25)
+            .round()
+            .min(19.0);
+        (level >= 1.0).then_some(level as u8)
+    }
+
+    /// Shell commands that apply the process limits before `exec`.
+    fn wrapper_script(&self) -> Option<String> {
+        let mut steps = Vec::new();
+        if let Some(max_rss_mb) = self.max_rss_mb {
+            steps.push(format!("ulimit -v {}", max_rss_mb.saturating_mul(1024)));
+        }
+        match self.nice_level() {
+            Some(level) => steps.push(format!("exec nice -n {} \"$0\" \"$@\"", level)),
+            None if steps.is_empty() => return None,
+            None => steps.push(WRAPPER_EXEC.to_string()),
+        }
+        Some(steps.join(" && "))
+    }
+
+    /// Wraps `program` and `args` so the process limits apply when run.
+    ///
+    /// Returns them unchanged when no process limit is set.
+    pub fn wrap<S: AsRef<OsStr>>(&self, program: &str, args: &[S]) -> (OsString, Vec<OsString>) {
+        let args: Vec<OsString> = args.iter().map(|arg| arg.as_ref().to_os_string()).collect();
+        match self.wrapper_script() {
+            Some(script) if cfg!(unix) => {
+                let mut wrapped: Vec<OsString> = vec!["-c".into(), script.into(), program.into()];
+                wrapped.extend(args);
+                ("sh".into(), wrapped)
+            }
+            _ => (program.into(), args),
+        }
+    }
+
+    /// Container runtime flags equivalent to the process limits.
+    pub fn container_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(max_rss_mb) = self.max_rss_mb {
+            args.extend(["--memory".to_string(), format!("{}m", max_rss_mb)]);
+        }
+        if let Some(shares) = self.cpu_shares {
+            args.extend(["--cpu-shares".to_string(), shares.to_string()]);
+        }
+        args
+    }
+}
+
+/// A per-story scratch directory used as `TMPDIR` for worker subprocesses.
+///
+/// The directory is removed when dropped.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ScratchDir {
+    /// Directory path
+    path: PathBuf,
+    /// Maximum size in bytes
+    quota_bytes: u64,
+}
+
+impl ScratchDir {
+    /// Creates (or empties) the scratch directory for `story_id` under the
+    /// system temp dir, named after this process so concurrent Ralph runs on
+    /// the same story do not share or remove each other's directory.
+    pub fn create(story_id: &str, quota_mb: u64) -> io::Result<Self> {
+        let story: String = story_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let name = format!("{}-{}", story, std::process::id());
+        let path = std::env::temp_dir().join("ralph-scratch").join(name);
+        if path.exists() {
+            std::fs::remove_dir_all(&path)?;
+        }
+        std::fs::create_dir_all(&path)?;
+        Ok(Self {
+            path,
+            quota_bytes: quota_mb.saturating_mul(1024 * 1024),
+        })
+    }
+
+    /// Path of the scratch directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns an error message if the directory has grown past its quota.
+    pub fn check_quota(&self) -> Result<(), String> {
+        let used = dir_size(&self.path);
+        if used > self.quota_bytes {
+            return Err(format!(
+                "scratch directory {} uses {} MB, over its {} MB quota",
+                self.path.display(),
+                used / (1024 * 1024),
+                self.quota_bytes / (1024 * 1024)
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Total size of the files under `path`, in bytes.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Memory available for new work in megabytes (`MemAvailable`).
+///
+/// Returns `None` on platforms without `/proc/meminfo`.
+pub fn available_memory_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_limits_leave_command_unchanged() {
+        let (program, args) = ResourceLimits::default().wrap("cargo", &["test"]);
+        assert_eq!(program, "cargo");
+        assert_eq!(args, vec![OsString::from("test")]);
+    }
+
+    #[test]
+    fn test_nice_level_from_cpu_shares() {
+        let limits = |shares| ResourceLimits {
+            cpu_shares: Some(shares),
+            ..Default::default()
+        };
+        assert_eq!(limits(1024).nice_level(), None);
+        assert_eq!(limits(4096).nice_level(), None);
+        assert_eq!(limits(512).nice_level(), Some(3));
+        assert_eq!(limits(1).nice_level(), Some(19));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wrap_applies_memory_and_cpu_limits() {
+        let limits = ResourceLimits {
+            max_rss_mb: Some(256),
+            cpu_shares: Some(512),
+            scratch_quota_mb: None,
+        };
+        let (program, args) = limits.wrap("cargo", &["test", "--lib"]);
+        assert_eq!(program, "sh");
+        assert_eq!(
+            args,
+            vec![
+                "-c",
+                "ulimit -v 262144 && exec nice -n 3 \"$0\" \"$@\"",
+                "cargo",
+                "test",
+                "--lib"
+            ]
+        );
+
+        let output = ResourceLimits {
+            max_rss_mb: Some(1024),
+            ..Default::default()
+        }
+        .wrap("echo", &["hello"]);
+        let output = std::process::Command::new(output.0)
+            .args(output.1)
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+    }
+
+    #[test]
+    fn test_container_args() {
+        let limits = ResourceLimits {
+            max_rss_mb: Some(512),
+            cpu_shares: Some(256),
+            scratch_quota_mb: Some(10),
+        };
+        assert_eq!(
+            limits.container_args(),
+            vec!["--memory", "512m", "--cpu-shares", "256"]
+        );
+    }
+
+    #[test]
+    fn test_scratch_quota() {
+        let scratch = ScratchDir::create("US-quota/test", 1).unwrap();
+        assert!(scratch
+            .path()
+            .ends_with(format!("US-quota_test-{}", std::process::id())));
+        std::fs::write(scratch.path().join("small"), vec![0u8; 1024]).unwrap();
+        assert!(scratch.check_quota().is_ok());
+
+        std::fs::create_dir(scratch.path().join("nested")).unwrap();
+        std::fs::write(
+            scratch.path().join("nested/big"),
+            vec![0u8; 2 * 1024 * 1024],
+        )
+        .unwrap();
+        assert!(scratch
+            .check_quota()
+            .unwrap_err()
+            .contains("over its 1 MB quota"));
+
+        let path = scratch.path().to_path_buf();
+        drop(scratch);
+        assert!(!path.exists());
+    }
+}
@@ -1,25 +1,48 @@
 // Terminal runner for Ralph
 // This module implements the default "run all stories until complete" behavior
 
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashSet};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::watch;
 
 use chrono::Utc;
 
-use crate::budget::TokenBudgetConfig;
-use crate::checkpoint::{Checkpoint, CheckpointManager, PauseReason, StoryCheckpoint};
+use crate::agent::{AgentContainer, AgentRoster, ApiAgentSpec};
+use crate::budget::{TokenBudgetConfig, TokenForecast};
+use crate::checkpoint::{
+    Checkpoint, CheckpointManager, PauseReason, RunFingerprint, StoryCheckpoint,
+};
 use crate::error::classification::ErrorCategory;
 use crate::evidence::{error_category_label, generate_run_id, EvidenceWriter};
-use crate::mcp::tools::executor::{detect_agent, ExecutorConfig, StoryExecutor};
+use crate::mcp::tools::executor::{detect_agent, ExecutorConfig, ExecutorError, StoryExecutor};
 use crate::mcp::tools::load_prd::{PrdFile, PrdUserStory};
-use crate::metrics::{RunMetricsCollector, RunMetricsStore};
+use crate::metrics::{
+    format_epic_progress, format_estimate_report, RunMetricsCollector, RunMetricsStore,
+};
+use crate::notification::bell::BellMode;
+use crate::notification::webhook::{WebhookEvent, WebhookNotifier, WebhooksConfig};
 use crate::notification::Notification;
+use crate::parallel::circuit_breaker::FailureCategory;
+use crate::parallel::dependency::StoryNode;
 use crate::parallel::scheduler::ParallelRunnerConfig;
+use crate::pause::{StopFile, STOP_FILE};
+use crate::prd::prerequisites::check_prerequisites;
+use crate::prd::workspace::Workspace;
+use crate::quality::{verification_failure, verify_workspace, Profile, QualityGateChecker};
 use crate::timeout::TimeoutConfig;
+use crate::ui::events::{RunEvent, StoryInfo};
+use crate::ui::github_actions::GithubActions;
+use crate::ui::jsonl::{print_event, JsonlWriter};
+use crate::ui::run_log::{RunLog, RunLogConfig};
+use crate::ui::run_report::RunReport;
 use crate::ui::{
-    new_shared_activity_state, DisplayOptions, StreamingDisplayCallback, TuiRunnerDisplay,
+    new_shared_activity_state, DisplayCallback, DisplayOptions, StreamingDisplayCallback,
+    TuiRunnerDisplay,
 };
 
 /// User's choice when prompted about an existing checkpoint.
@@ -57,6 +80,10 @@ pub struct RunnerConfig {
     pub resume: bool,
     /// Skip checkpoint prompt (do not resume)
     pub no_resume: bool,
+    /// Resume without prompting when the checkpoint matches the current PRD and branch
+    pub auto_resume: bool,
+    /// Resume without confirmation even if the PRD changed since the checkpoint
+    pub force_resume: bool,
     /// Agent timeout override in seconds (None = use default)
     pub timeout_seconds: Option<u64>,
     /// Heartbeat check interval in seconds (None = use default)
@@ -67,10 +94,38 @@ pub struct RunnerConfig {
     pub startup_grace_period_seconds: Option<u64>,
     /// Disable checkpointing
     pub no_checkpoint: bool,
+    /// How often a checkpoint is saved while a story runs (None = only at
+    /// story boundaries and pauses)
+    pub autosave_interval: Option<Duration>,
     /// Number of consecutive failures before circuit breaker triggers (None = use default of 5)
     pub circuit_breaker_threshold: Option<u32>,
     /// Token budget configuration (None = no budget enforcement)
     pub budget_config: Option<TokenBudgetConfig>,
+    /// Container to run the agent CLI in (None = directly on the host)
+    pub agent_container: Option<AgentContainer>,
+    /// Token cap for relevant files included in story prompts (None = off)
+    pub file_context_tokens: Option<u64>,
+    /// Whether passing stories are marked `passes: true` in the PRD; without
+    /// it the run tracks them in memory only
+    pub prd_writeback: bool,
+    /// Whether the full quality gate suite runs once more on the integrated
+    /// tree after every story passes, failing the run if it does not pass
+    pub final_verification: bool,
+    /// Whether the run's events are printed to stdout as JSON lines instead
+    /// of rendering the terminal UI
+    pub jsonl_events: bool,
+    /// Whether the run reports to GitHub Actions: failed gates as workflow
+    /// command annotations, story results in the job summary and the run's
+    /// result as step outputs
+    pub github_actions: bool,
+    /// Whether a report of the stories, gate durations, failures and cost
+    /// is printed once the run finishes
+    pub summary_report: bool,
+    /// Mirror the run's events and full agent and gate output to a log file
+    /// under `.ralph/logs` (None = no log file)
+    pub run_log: Option<RunLogConfig>,
+    /// How the terminal is alerted when the run fails
+    pub bell: BellMode,
 }
 
 impl Default for RunnerConfig {
@@ -86,17 +141,34 @@ impl Default for RunnerConfig {
             parallel_config: None,
             resume: false,
             no_resume: false,
+            auto_resume: false,
+            force_resume: false,
             timeout_seconds: None,
             heartbeat_interval_seconds: None,
             heartbeat_threshold: None,
             startup_grace_period_seconds: None,
             no_checkpoint: false,
+            autosave_interval: Some(Self::DEFAULT_AUTOSAVE_INTERVAL),
             circuit_breaker_threshold: None,
             budget_config: None,
+            agent_container: None,
+            file_context_tokens: None,
+            prd_writeback: true,
+            final_verification: false,
+            jsonl_events: false,
+            github_actions: false,
+            summary_report: false,
+            run_log: None,
+            bell: BellMode::Off,
         }
     }
 }
 
+impl RunnerConfig {
+    /// Default interval between checkpoint autosaves (5 minutes).
+    pub const DEFAULT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(300);
+}
+
 /// Result of running all stories
 #[derive(Debug)]
 #[allow(dead_code)] // Fields may be used by callers
@@ -111,6 +183,164 @@ pub struct RunResult {
     pub total_iterations: u32,
     /// Error message if failed
     pub error: Option<String>,
+    /// Why the run ended
+    pub outcome: RunOutcome,
+}
+
+/// How a run ended, for choosing its exit code.
+///
+/// When a run fails for several reasons, the later variant is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RunOutcome {
+    /// Every story passed.
+    Passed,
+    /// Quality gates failed, for a story or in the final verification.
+    GateFailure,
+    /// Any other error: the PRD, the agent, unmet prerequisites, ...
+    Error,
+    /// An agent or story timed out.
+    Timeout,
+    /// The iteration limit, token budget or API quota was exhausted.
+    BudgetExhausted,
+    /// The circuit breaker stopped the run.
+    CircuitBreaker,
+    /// The run was stopped or interrupted.
+    Interrupted,
+}
+
+impl RunOutcome {
+    /// All outcomes, in precedence order.
+    pub const ALL: [RunOutcome; 7] = [
+        RunOutcome::Passed,
+        RunOutcome::GateFailure,
+        RunOutcome::Error,
+        RunOutcome::Timeout,
+        RunOutcome::BudgetExhausted,
+        RunOutcome::CircuitBreaker,
+        RunOutcome::Interrupted,
+    ];
+
+    /// Label used in `--exit-codes`.
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::GateFailure => "gate_failure",
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+            Self::BudgetExhausted => "budget",
+            Self::CircuitBreaker => "circuit_breaker",
+            Self::Interrupted => "interrupted",
+        }
+    }
+
+    /// Outcome of a run whose stories failed in `categories`.
+    pub fn from_failures(categories: impl IntoIterator<Item = FailureCategory>) -> Self {
+        categories
+            .into_iter()
+            .map(|category| match category {
+                FailureCategory::QualityGate => Self::GateFailure,
+                FailureCategory::Fatal => Self::Error,
+                FailureCategory::UsageLimit => Self::BudgetExhausted,
+                FailureCategory::Timeout => Self::Timeout,
+            })
+            .max()
+            .unwrap_or(Self::GateFailure)
+    }
+}
+
+impl std::fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
+impl FromStr for RunOutcome {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|outcome| outcome.as_label() == value.trim())
+            .ok_or_else(|| {
+                let labels: Vec<&str> = Self::ALL.iter().map(|o| o.as_label()).collect();
+                format!(
+                    "unknown run outcome '{}' (expected one of: {})",
+                    value.trim(),
+                    labels.join(", ")
+                )
+            })
+    }
+}
+
+/// Exit code of each [`RunOutcome`] (`--exit-codes`, `RALPH_EXIT_CODES`).
+///
+/// By default every failed run exits with 1. The `detailed` preset gives each
+/// outcome its own code so scripts and CI can branch on it, and single
+/// outcomes can be overridden: `detailed,timeout=20` or `gate_failure=3`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExitCodePolicy {
+    /// Codes of the outcomes that do not exit with 1
+    codes: BTreeMap<RunOutcome, u8>,
+}
+
+impl ExitCodePolicy {
+    /// Exit code of failed runs without a code of their own.
+    pub const DEFAULT_FAILURE_CODE: u8 = 1;
+
+    /// A distinct code for each outcome.
+    pub fn detailed() -> Self {
+        Self {
+            codes: BTreeMap::from([
+                (RunOutcome::GateFailure, 10),
+                (RunOutcome::CircuitBreaker, 11),
+                (RunOutcome::BudgetExhausted, 12),
+                (RunOutcome::Timeout, 13),
+                (RunOutcome::Interrupted, 14),
+            ]),
+        }
+    }
+
+    /// Parses `default`, `detailed` and `OUTCOME=CODE` entries, separated by
+    /// commas; later entries override earlier ones.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut policy = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry {
+                "default" => policy = Self::default(),
+                "detailed" => policy = Self::detailed(),
+                _ => {
+                    let (outcome, code) = entry.split_once('=').ok_or_else(|| {
+                        format!(
+                            "expected default, detailed or OUTCOME=CODE, got '{}'",
+                            entry
+                        )
+                    })?;
+                    let outcome: RunOutcome = outcome.parse()?;
+                    if outcome == RunOutcome::Passed {
+                        return Err("a passed run always exits with 0".to_string());
+                    }
+                    let code = code
+                        .trim()
+                        .parse::<u8>()
+                        .map_err(|_| format!("invalid exit code in '{}'", entry))?;
+                    policy.codes.insert(outcome, code);
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Exit code of a run that ended with `outcome`.
+    pub fn code(&self, outcome: RunOutcome) -> u8 {
+        match outcome {
+            RunOutcome::Passed => 0,
+            _ => self
+                .codes
+                .get(&outcome)
+                .copied()
+                .unwrap_or(Self::DEFAULT_FAILURE_CODE),
+        }
+    }
 }
 
 /// The main runner that iterates through stories
@@ -118,6 +348,8 @@ pub struct Runner {
     config: RunnerConfig,
     /// Optional checkpoint manager (None if checkpointing is disabled)
     checkpoint_manager: Option<CheckpointManager>,
+    /// Webhooks told about the run (None if none are configured)
+    webhooks: Option<WebhookNotifier>,
 }
 
 impl Runner {
@@ -127,7 +359,7 @@ impl Runner {
         let checkpoint_manager = if config.no_checkpoint {
             None
         } else {
-            match CheckpointManager::new(&config.working_dir) {
+            match CheckpointManager::for_run(&config.working_dir, &config.prd_path) {
                 Ok(manager) => Some(manager),
                 Err(e) => {
                     // Log warning but continue without checkpointing
@@ -137,15 +369,55 @@ impl Runner {
             }
         };
 
+        let webhooks = match WebhooksConfig::load(&config.working_dir) {
+            Ok(webhooks) if webhooks.webhooks.is_empty() => None,
+            Ok(webhooks) => {
+                let project = Self::project_name(&config);
+                WebhookNotifier::new(project, webhooks)
+            }
+            Err(e) => {
+                eprintln!("Warning: Webhooks disabled: {}", e);
+                None
+            }
+        };
+
         Self {
             config,
             checkpoint_manager,
+            webhooks,
         }
     }
 
+    /// Project name of the PRD, or the working directory's name.
+    fn project_name(config: &RunnerConfig) -> String {
+        std::fs::read_to_string(&config.prd_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|prd| prd["project"].as_str().map(str::to_string))
+            .unwrap_or_else(|| {
+                config
+                    .working_dir
+                    .canonicalize()
+                    .ok()
+                    .and_then(|dir| dir.file_name().map(|name| name.to_string_lossy().into()))
+                    .unwrap_or_else(|| "ralph".to_string())
+            })
+    }
+
     /// Build TimeoutConfig from RunnerConfig, applying any CLI overrides.
     fn build_timeout_config(&self) -> TimeoutConfig {
-        let mut config = TimeoutConfig::default();
+        // Local models get longer defaults; CLI overrides still win
+        let local_agent = self
+            .config
+            .agent_command
+            .as_deref()
+            .and_then(|agent| ApiAgentSpec::parse(agent).ok().flatten())
+            .is_some_and(|spec| spec.provider.is_local());
+        let mut config = if local_agent {
+            TimeoutConfig::local_model()
+        } else {
+            TimeoutConfig::default()
+        };
 
         // Apply CLI overrides if provided
         if let Some(timeout) = self.config.timeout_seconds {
@@ -168,19 +440,179 @@ impl Runner {
     ///
     /// Routes to parallel or sequential execution based on config.parallel.
     pub async fn run(&self) -> RunResult {
-        if self.config.parallel {
+        let started = Instant::now();
+        let jsonl = self.config.jsonl_events.then(JsonlWriter::start);
+        let github = self.config.github_actions.then(GithubActions::from_env);
+        let report = self.config.summary_report.then(RunReport::new);
+        let (result, run_metrics, run_log) = if self.config.parallel {
             // Use parallel execution
             let mut parallel_config = self.config.parallel_config.clone().unwrap_or_default();
             // Apply CLI timeout overrides to parallel config
             parallel_config.timeout_config = self.build_timeout_config();
-            let parallel_runner = crate::parallel::scheduler::ParallelRunner::new(
+            let mut parallel_runner = crate::parallel::scheduler::ParallelRunner::new(
                 parallel_config,
                 self.config.clone(),
             );
-            parallel_runner.run().await
+            // Continue from a checkpointed parallel run, skipping its completed
+            // stories and continuing its metrics
+            if let Some(checkpoint) = self.resolve_checkpoint() {
+                if let Some(resume_state) = checkpoint.parallel_state {
+                    parallel_runner = parallel_runner.with_resume_state(resume_state);
+                }
+                if let Some(metrics) = checkpoint.metrics {
+                    parallel_runner = parallel_runner.with_resume_metrics(metrics);
+                }
+            }
+            if let Some(ref jsonl) = jsonl {
+                parallel_runner = parallel_runner.with_ui_sender(jsonl.sender());
+            }
+            if let Some(ref webhooks) = self.webhooks {
+                parallel_runner = parallel_runner.with_webhooks(webhooks.clone());
+            }
+            let run_id = parallel_runner.run_metrics().run_id();
+            let run_log = self.open_run_log(&run_id);
+            if let Some(ref run_log) = run_log {
+                parallel_runner = parallel_runner.with_run_log(run_log.clone());
+            }
+            if let Some(ref github) = github {
+                parallel_runner = parallel_runner.with_github_actions(github.clone());
+            }
+            if let Some(ref report) = report {
+                parallel_runner = parallel_runner.with_run_report(report.clone());
+            }
+            self.announce_start(jsonl.is_some(), run_log.as_ref(), run_id);
+            let result = parallel_runner.run().await;
+            let run_metrics = parallel_runner.run_metrics();
+            drop(parallel_runner);
+            (result, run_metrics, run_log)
         } else {
-            // Use sequential execution
-            self.run_sequential().await
+            // Use sequential execution; a resumed run continues the
+            // checkpointed run's metrics under the same run ID
+            let checkpoint = self.resolve_checkpoint();
+            let run_metrics = match checkpoint.as_ref().and_then(|c| c.metrics.clone()) {
+                Some(metrics) => RunMetricsCollector::resume(metrics),
+                None => RunMetricsCollector::new(generate_run_id(), 0),
+            };
+            let run_log = self.open_run_log(&run_metrics.run_id());
+            self.announce_start(jsonl.is_some(), run_log.as_ref(), run_metrics.run_id());
+            let result = self
+                .run_sequential(
+                    checkpoint,
+                    &run_metrics,
+                    run_log.as_ref(),
+                    github.as_ref(),
+                    report.as_ref(),
+                )
+                .await;
+            (result, run_metrics, run_log)
+        };
+
+        let cost_cents = run_metrics.invocation_stats().cost_cents;
+        let metrics = run_metrics.finish();
+        if !self.config.display_options.quiet {
+            if !metrics.epics.is_empty() {
+                println!();
+                print!("{}", format_epic_progress(&metrics.epics));
+            }
+            if !metrics.estimates.is_empty() {
+                println!();
+                print!("{}", format_estimate_report(&metrics.estimates));
+            }
+        }
+        let finished = RunEvent::finished(metrics.run_id.clone(), &result, started.elapsed());
+        if let Some(jsonl) = jsonl {
+            jsonl.finish().await;
+            print_event(finished.clone());
+        }
+        if let Some(run_log) = run_log {
+            run_log.record(finished);
+        }
+        if let Some(github) = github {
+            github.finish(&metrics, &result);
+        }
+        if let Some(report) = report {
+            report.finish(&metrics, &result);
+        }
+        if let Some(ref webhooks) = self.webhooks {
+            webhooks.notify(WebhookEvent::run_finished(
+                webhooks.project(),
+                metrics.run_id.clone(),
+                &result,
+                started.elapsed(),
+                cost_cents,
+            ));
+            webhooks.flush().await;
+        }
+        if !result.all_passed {
+            self.config.bell.ring();
+        }
+        result
+    }
+
+    /// Create the log file of run `run_id`, if one was asked for.
+    fn open_run_log(&self, run_id: &str) -> Option<RunLog> {
+        let config = self.config.run_log.clone()?;
+        match RunLog::create(&self.config.working_dir, run_id, config) {
+            Ok(run_log) => {
+                if !self.config.display_options.quiet {
+                    println!("Logging to {}", run_log.path().display());
+                }
+                Some(run_log)
+            }
+            Err(err) => {
+                eprintln!("Warning: Failed to create run log: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Report the start of run `run_id` as a JSON line, in the run log and to
+    /// the webhooks.
+    fn announce_start(&self, jsonl: bool, run_log: Option<&RunLog>, run_id: String) {
+        let started = RunEvent::RunStarted {
+            run_id: run_id.clone(),
+            parallel: self.config.parallel,
+        };
+        if jsonl {
+            print_event(started.clone());
+        }
+        if let Some(run_log) = run_log {
+            run_log.record(started);
+        }
+        if let Some(ref webhooks) = self.webhooks {
+            webhooks.notify(WebhookEvent::RunStarted {
+                project: webhooks.project().to_string(),
+                run_id,
+                total_stories: self
+                    .load_prd()
+                    .map(|prd| prd.user_stories.len())
+                    .unwrap_or(0),
+                parallel: self.config.parallel,
+            });
+        }
+    }
+
+    /// Record in the run log and tell the webhooks that `story_id` failed.
+    fn notify_story_failed(
+        &self,
+        run_log: Option<&RunLog>,
+        story_id: &str,
+        iteration: u32,
+        error: &str,
+    ) {
+        if let Some(run_log) = run_log {
+            run_log.record(RunEvent::StoryFailed {
+                story_id: story_id.to_string(),
+                error: error.to_string(),
+                iteration,
+            });
+        }
+        if let Some(ref webhooks) = self.webhooks {
+            webhooks.notify(WebhookEvent::StoryFailed {
+                project: webhooks.project().to_string(),
+                story_id: story_id.to_string(),
+                error: error.to_string(),
+            });
         }
     }
 
@@ -188,7 +620,14 @@ impl Runner {
     const DEFAULT_CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
 
     /// Run all stories sequentially until all pass or an error occurs
-    async fn run_sequential(&self) -> RunResult {
+    async fn run_sequential(
+        &self,
+        checkpoint: Option<Checkpoint>,
+        run_metrics: &RunMetricsCollector,
+        run_log: Option<&RunLog>,
+        github: Option<&GithubActions>,
+        report: Option<&RunReport>,
+    ) -> RunResult {
         let mut total_iterations: u32 = 0;
         // Circuit breaker: track consecutive failures to prevent cascading API costs
         let mut consecutive_failures: u32 = 0;
@@ -196,8 +635,8 @@ impl Runner {
             .config
             .circuit_breaker_threshold
             .unwrap_or(Self::DEFAULT_CIRCUIT_BREAKER_THRESHOLD);
-        let run_id = generate_run_id();
-        let run_metrics = RunMetricsCollector::new(run_id.clone(), 0);
+        let run_id = run_metrics.run_id();
+        let resume_from = checkpoint.and_then(|checkpoint| checkpoint.current_story);
         let metrics_store = match RunMetricsStore::new(&self.config.working_dir) {
             Ok(store) => Some(store),
             Err(err) => {
@@ -214,6 +653,8 @@ impl Runner {
             }
         };
 
+        let stop_file = StopFile::new(&self.config.working_dir);
+
         // Create TUI display with display options
         let mut display =
             TuiRunnerDisplay::with_display_options(self.config.display_options.clone());
@@ -244,9 +685,6 @@ impl Runner {
             }
         };
 
-        // Handle checkpoint resume at startup
-        let resume_from = self.handle_checkpoint_resume();
-
         // Load and validate PRD
         let prd = match self.load_prd() {
             Ok(prd) => prd,
@@ -258,13 +696,14 @@ impl Runner {
                         Some(format!("Failed to load PRD: {}", e)),
                     );
                 }
-                save_metrics(&run_metrics);
+                save_metrics(run_metrics);
                 return RunResult {
                     all_passed: false,
                     stories_passed: 0,
                     total_stories: 0,
                     total_iterations: 0,
                     error: Some(format!("Failed to load PRD: {}", e)),
+                    outcome: RunOutcome::Error,
                 };
             }
         };
@@ -272,6 +711,22 @@ impl Runner {
         let total_stories = prd.user_stories.len();
         let expected_steps = prd.user_stories.iter().filter(|s| !s.passes).count();
         run_metrics.set_expected_steps(expected_steps);
+        run_metrics.record_stories(&prd.user_stories);
+        if let Some(writer) = evidence.as_mut() {
+            writer.set_story_epics(&prd.user_stories);
+        }
+
+        // Warn up front when the estimated stories cannot fit the budget
+        if let Some(budget_config) = &self.config.budget_config {
+            let history = metrics_store
+                .as_ref()
+                .and_then(|store| store.load_all().ok())
+                .unwrap_or_default();
+            let forecast = TokenForecast::new(&prd.user_stories, &history);
+            for problem in forecast.over_budget(budget_config) {
+                eprintln!("Warning: Budget forecast: {}", problem);
+            }
+        }
 
         // Initialize display with story list
         let story_status: Vec<(String, bool)> = prd
@@ -288,13 +743,14 @@ impl Runner {
             if let Some(writer) = evidence.as_mut() {
                 writer.emit_run_complete("success", None, None);
             }
-            save_metrics(&run_metrics);
+            save_metrics(run_metrics);
             return RunResult {
                 all_passed: true,
                 stories_passed: total_stories,
                 total_stories,
                 total_iterations: 0,
                 error: None,
+                outcome: RunOutcome::Passed,
             };
         }
 
@@ -307,24 +763,61 @@ impl Runner {
                         "failed",
                         Some("fatal".to_string()),
                         Some(
-                            "No agent found. Install Claude Code CLI, Codex CLI, or Amp CLI."
+                            "No agent found. Install Claude Code CLI, Codex CLI, Amp CLI, or Aider."
                                 .to_string(),
                         ),
                     );
                 }
-                save_metrics(&run_metrics);
+                save_metrics(run_metrics);
                 return RunResult {
                     all_passed: false,
                     stories_passed: passing_count,
                     total_stories,
                     total_iterations: 0,
                     error: Some(
-                        "No agent found. Install Claude Code CLI, Codex CLI, or Amp CLI."
+                        "No agent found. Install Claude Code CLI, Codex CLI, Amp CLI, or Aider."
                             .to_string(),
                     ),
+                    outcome: RunOutcome::Error,
+                };
+            }
+        };
+
+        // Probe the agent CLIs now, including those assigned to particular
+        // stories, so a missing agent or unsupported flags surface before the
+        // first story rather than in the middle of one. A containerized agent
+        // is not installed on the host to probe.
+        let probe = AgentRoster::load(
+            &self.config.working_dir,
+            &agent,
+            &prd.user_stories,
+            self.config.agent_container.is_some(),
+        )
+        .await;
+        let agents = match probe {
+            Ok(agents) => agents,
+            Err(e) => {
+                if let Some(writer) = evidence.as_mut() {
+                    writer.emit_run_complete(
+                        "failed",
+                        Some("fatal".to_string()),
+                        Some(e.to_string()),
+                    );
+                }
+                save_metrics(run_metrics);
+                return RunResult {
+                    all_passed: false,
+                    stories_passed: passing_count,
+                    total_stories,
+                    total_iterations: 0,
+                    error: Some(e.to_string()),
+                    outcome: RunOutcome::Error,
                 };
             }
         };
+        if let Some(capabilities) = agents.default_capabilities() {
+            run_metrics.set_agent_capabilities(capabilities.clone());
+        }
 
         // Display startup banner (with resume info if applicable)
         if let Some(ref checkpoint) = resume_from {
@@ -342,11 +835,13 @@ impl Runner {
 
         // Track if we're resuming and need to start from a specific iteration
         let mut resume_state = resume_from;
+        // Stories passed in this run, for when they are not written back to the PRD
+        let mut passed_in_run: HashSet<String> = HashSet::new();
 
         // Main loop - continue until all stories pass
         loop {
             // Reload PRD each iteration to get updated passes status
-            let prd = match self.load_prd() {
+            let mut prd = match self.load_prd() {
                 Ok(prd) => prd,
                 Err(e) => {
                     if let Some(writer) = evidence.as_mut() {
@@ -356,17 +851,24 @@ impl Runner {
                             Some(format!("Failed to reload PRD: {}", e)),
                         );
                     }
-                    save_metrics(&run_metrics);
+                    save_metrics(run_metrics);
                     return RunResult {
                         all_passed: false,
                         stories_passed: self.count_passing_stories().unwrap_or(0),
                         total_stories,
                         total_iterations,
                         error: Some(format!("Failed to reload PRD: {}", e)),
+                        outcome: RunOutcome::Error,
                     };
                 }
             };
 
+            for story in prd.user_stories.iter_mut() {
+                if passed_in_run.contains(&story.id) {
+                    story.passes = true;
+                }
+            }
+
             // Update display with current story states
             let story_status: Vec<(String, bool)> = prd
                 .user_stories
@@ -398,19 +900,45 @@ impl Runner {
 
             match next_story {
                 None => {
-                    // All stories pass! Clear checkpoint on full completion.
+                    // All stories pass; check them together before declaring success
+                    if self.config.final_verification {
+                        if let Some(failure) = run_final_verification(
+                            &self.config.working_dir,
+                            evidence.clone(),
+                            self.config.display_options.quiet,
+                        ) {
+                            if let Some(writer) = evidence.as_mut() {
+                                writer.emit_run_complete(
+                                    "failed",
+                                    Some("final_verification_failed".to_string()),
+                                    Some(failure.clone()),
+                                );
+                            }
+                            save_metrics(run_metrics);
+                            return RunResult {
+                                all_passed: false,
+                                stories_passed: total_stories,
+                                total_stories,
+                                total_iterations,
+                                error: Some(failure),
+                                outcome: RunOutcome::GateFailure,
+                            };
+                        }
+                    }
+                    // Clear checkpoint on full completion.
                     self.clear_checkpoint();
                     display.display_all_complete(total_stories);
                     if let Some(writer) = evidence.as_mut() {
                         writer.emit_run_complete("success", None, None);
                     }
-                    save_metrics(&run_metrics);
+                    save_metrics(run_metrics);
                     return RunResult {
                         all_passed: true,
                         stories_passed: total_stories,
                         total_stories,
                         total_iterations,
                         error: None,
+                        outcome: RunOutcome::Passed,
                     };
                 }
                 Some(story) => {
@@ -420,6 +948,7 @@ impl Runner {
                     {
                         // Save checkpoint on reaching iteration limit
                         self.save_checkpoint(
+                            run_metrics,
                             &story.id,
                             start_iteration,
                             self.config.max_iterations_per_story,
@@ -438,7 +967,7 @@ impl Runner {
                                 )),
                             );
                         }
-                        save_metrics(&run_metrics);
+                        save_metrics(run_metrics);
                         return RunResult {
                             all_passed: false,
                             stories_passed: self.count_passing_stories().unwrap_or(0),
@@ -448,6 +977,7 @@ impl Runner {
                                 "Max total iterations ({}) reached",
                                 self.config.max_total_iterations
                             )),
+                            outcome: RunOutcome::BudgetExhausted,
                         };
                     }
 
@@ -456,48 +986,189 @@ impl Runner {
                         println!("  Resuming from iteration {}", start_iteration);
                     }
                     display.start_story(&story.id, &story.title, story.priority);
+                    if let Some(run_log) = run_log {
+                        run_log.record(RunEvent::StoryStarted {
+                            story: StoryInfo {
+                                id: story.id.clone(),
+                                title: story.title.clone(),
+                                priority: story.priority,
+                            },
+                            iteration: start_iteration,
+                            concurrent_count: 1,
+                        });
+                    }
 
                     // Calculate remaining iterations when resuming
                     let max_iterations = self.config.max_iterations_per_story;
                     let remaining_iterations = max_iterations.saturating_sub(start_iteration - 1);
 
+                    if stop_file.is_requested() {
+                        let message = self.stop_run(
+                            &stop_file,
+                            run_metrics,
+                            evidence.as_mut(),
+                            &story.id,
+                            start_iteration,
+                        );
+                        save_metrics(run_metrics);
+                        return RunResult {
+                            all_passed: false,
+                            stories_passed: self.count_passing_stories().unwrap_or(0),
+                            total_stories,
+                            total_iterations,
+                            error: Some(message),
+                            outcome: RunOutcome::Interrupted,
+                        };
+                    }
+
+                    // Stories run in priority order, so one whose external
+                    // prerequisites are not met stops the run
+                    if let Err(reason) =
+                        check_prerequisites(&story.prerequisites, &self.config.working_dir).await
+                    {
+                        let message = format!("Prerequisite not met: {}", reason);
+                        run_metrics.start_step(&story.id);
+                        run_metrics.complete_step(
+                            &story.id,
+                            false,
+                            0,
+                            Duration::ZERO,
+                            Some(message.clone()),
+                        );
+                        if let Some(writer) = evidence.as_mut() {
+                            writer.emit_step(
+                                &story.id,
+                                "blocked",
+                                Some("unmet_prerequisite".to_string()),
+                                Some(message.clone()),
+                            );
+                            run_metrics.record_evidence_step(&story.id);
+                            writer.emit_run_complete(
+                                "failed",
+                                Some("unmet_prerequisites".to_string()),
+                                Some(format!("{} is blocked: {}", story.id, reason)),
+                            );
+                        }
+                        save_metrics(run_metrics);
+                        return RunResult {
+                            all_passed: false,
+                            stories_passed: self.count_passing_stories().unwrap_or(0),
+                            total_stories,
+                            total_iterations,
+                            error: Some(format!("{} is blocked. {}", story.id, message)),
+                            outcome: RunOutcome::Error,
+                        };
+                    }
+
                     // Execute the story
+                    let story_agent = agents.for_story(&StoryNode::from(story));
                     let executor_config = ExecutorConfig {
                         prd_path: self.config.prd_path.clone(),
                         project_root: self.config.working_dir.clone(),
                         progress_path: self.config.working_dir.join("progress.txt"),
                         quality_profile: None,
-                        agent_command: agent.clone(),
+                        agent_command: story_agent.agent_command.clone(),
                         max_iterations: remaining_iterations,
                         git_mutex: None, // Sequential execution doesn't need mutex
                         timeout_config: self.build_timeout_config(),
                         budget_config: self.config.budget_config.clone(),
+                        artifacts_dir: evidence.as_ref().map(EvidenceWriter::artifacts_dir),
+                        evidence: evidence.clone(),
+                        agent_capabilities: agents
+                            .capabilities(&story_agent.agent_command)
+                            .cloned(),
+                        agent_container: self.config.agent_container.clone(),
+                        file_context_tokens: self.config.file_context_tokens,
+                        model: story_agent.model.clone(),
+                        run_metrics: Some(run_metrics.clone()),
+                        prd_writeback: self.config.prd_writeback,
                         ..Default::default()
                     };
 
-                    let executor = StoryExecutor::new(executor_config)
-                        .with_display_callback(streaming_callback.clone());
+                    let mut output: Arc<dyn DisplayCallback> = streaming_callback.clone();
+                    if let Some(github) = github {
+                        output = github.output_callback(&story.id, Some(output));
+                    }
+                    if let Some(report) = report {
+                        output = report.output_callback(&story.id, Some(output));
+                    }
+                    if let Some(run_log) = run_log {
+                        output = run_log.output_callback(&story.id, Some(output));
+                    }
+                    let executor =
+                        StoryExecutor::new(executor_config).with_display_callback(output);
                     let (_cancel_tx, cancel_rx) = watch::channel(false);
 
                     let story_id = story.id.clone();
                     run_metrics.start_step(&story_id);
+                    run_metrics.record_step_agent(&story_id, story_agent.label());
                     let step_start = Instant::now();
 
                     // Save checkpoint before starting story execution (for recovery if interrupted)
                     self.save_checkpoint(
+                        run_metrics,
                         &story_id,
                         start_iteration,
                         max_iterations,
                         PauseReason::IterationBoundary,
                     );
 
-                    let result = executor
-                        .execute_story(&story_id, cancel_rx, |iter, _max| {
-                            // Adjust iteration display to account for resume offset
-                            let adjusted_iter = iter + start_iteration - 1;
-                            display.update_iteration(adjusted_iter, max_iterations);
-                        })
-                        .await;
+                    let mut last_autosave = Instant::now();
+                    let reached_iteration = Cell::new(start_iteration);
+                    let execution = executor.execute_story(&story_id, cancel_rx, |iter, _max| {
+                        // Adjust iteration display to account for resume offset
+                        let adjusted_iter = iter + start_iteration - 1;
+                        reached_iteration.set(adjusted_iter);
+                        display.update_iteration(adjusted_iter, max_iterations);
+                        if let Some(run_log) = run_log {
+                            run_log.record(RunEvent::IterationUpdate {
+                                story_id: story_id.clone(),
+                                iteration: adjusted_iter,
+                                max_iterations,
+                                message: None,
+                            });
+                        }
+                        // Record the iteration reached now and then, so a
+                        // killed run resumes close to where it stopped
+                        let autosave_due = self
+                            .config
+                            .autosave_interval
+                            .is_some_and(|interval| last_autosave.elapsed() >= interval);
+                        if autosave_due {
+                            self.save_checkpoint(
+                                run_metrics,
+                                &story_id,
+                                adjusted_iter,
+                                max_iterations,
+                                PauseReason::Autosave,
+                            );
+                            last_autosave = Instant::now();
+                        }
+                    });
+                    // The stop file halts the run mid-story; dropping the
+                    // execution kills its agent
+                    let result = tokio::select! {
+                        result = execution => Some(result),
+                        _ = stop_file.requested() => None,
+                    };
+                    let Some(result) = result else {
+                        let message = self.stop_run(
+                            &stop_file,
+                            run_metrics,
+                            evidence.as_mut(),
+                            &story_id,
+                            reached_iteration.get(),
+                        );
+                        save_metrics(run_metrics);
+                        return RunResult {
+                            all_passed: false,
+                            stories_passed: self.count_passing_stories().unwrap_or(0),
+                            total_stories,
+                            total_iterations,
+                            error: Some(message),
+                            outcome: RunOutcome::Interrupted,
+                        };
+                    };
 
                     // Calculate total iterations used (including those before resume)
                     let iterations_this_run =
@@ -507,6 +1178,7 @@ impl Runner {
                     match result {
                         Ok(exec_result) => {
                             if exec_result.success {
+                                passed_in_run.insert(story_id.clone());
                                 // Reset circuit breaker counter on success
                                 consecutive_failures = 0;
                                 display.reset_circuit_breaker();
@@ -520,6 +1192,13 @@ impl Runner {
                                 let attempts = exec_result.iterations_used.max(1);
                                 run_metrics
                                     .complete_step(&story_id, true, attempts, duration, None);
+                                if let Some(run_log) = run_log {
+                                    run_log.record(RunEvent::StoryCompleted {
+                                        story_id: story_id.clone(),
+                                        iterations_used: attempts,
+                                        duration_ms: duration.as_millis() as u64,
+                                    });
+                                }
                                 display
                                     .complete_story(&story_id, exec_result.commit_hash.as_deref());
                             } else {
@@ -537,16 +1216,24 @@ impl Runner {
                                 // Save checkpoint on story failure (quality gates didn't pass)
                                 let final_iteration =
                                     start_iteration + exec_result.iterations_used - 1;
+                                self.notify_story_failed(
+                                    run_log,
+                                    &story_id,
+                                    final_iteration,
+                                    &error_message,
+                                );
 
                                 // Check circuit breaker threshold
                                 if consecutive_failures >= circuit_breaker_threshold {
                                     self.save_checkpoint(
+                                        run_metrics,
                                         &story_id,
                                         final_iteration,
                                         max_iterations,
                                         PauseReason::CircuitBreakerTriggered {
                                             consecutive_failures,
                                             threshold: circuit_breaker_threshold,
+                                            category: None,
                                         },
                                     );
                                     let circuit_breaker_msg = format!(
@@ -558,6 +1245,19 @@ impl Runner {
                                         consecutive_failures,
                                         circuit_breaker_threshold,
                                     );
+                                    if let Some(run_log) = run_log {
+                                        run_log.record(RunEvent::CircuitBreakerTriggered {
+                                            failures: consecutive_failures,
+                                            threshold: circuit_breaker_threshold,
+                                        });
+                                    }
+                                    if let Some(ref webhooks) = self.webhooks {
+                                        webhooks.notify(WebhookEvent::CircuitBreakerTriggered {
+                                            project: webhooks.project().to_string(),
+                                            failures: consecutive_failures,
+                                            threshold: circuit_breaker_threshold,
+                                        });
+                                    }
                                     if let Some(writer) = evidence.as_mut() {
                                         writer.emit_run_complete(
                                             "failed",
@@ -565,7 +1265,7 @@ impl Runner {
                                             Some(circuit_breaker_msg.clone()),
                                         );
                                     }
-                                    save_metrics(&run_metrics);
+                                    save_metrics(run_metrics);
                                     return RunResult {
                                         all_passed: false,
                                         stories_passed: self.count_passing_stories().unwrap_or(0),
@@ -575,10 +1275,12 @@ impl Runner {
                                             "{}. Checkpoint saved. Resume with: ralph --resume",
                                             circuit_breaker_msg
                                         )),
+                                        outcome: RunOutcome::CircuitBreaker,
                                     };
                                 }
 
                                 self.save_checkpoint(
+                                    run_metrics,
                                     &story_id,
                                     final_iteration,
                                     max_iterations,
@@ -625,6 +1327,12 @@ impl Runner {
                                 duration,
                                 Some(e.to_string()),
                             );
+                            self.notify_story_failed(
+                                run_log,
+                                &story_id,
+                                reached_iteration.get(),
+                                &e.to_string(),
+                            );
 
                             // Handle based on error category
                             match &category {
@@ -637,6 +1345,7 @@ impl Runner {
                                     ));
                                     println!("{}", notification);
                                     self.save_checkpoint(
+                                        run_metrics,
                                         &story_id,
                                         start_iteration,
                                         max_iterations,
@@ -653,6 +1362,7 @@ impl Runner {
                                     ));
                                     println!("{}", notification);
                                     self.save_checkpoint(
+                                        run_metrics,
                                         &story_id,
                                         start_iteration,
                                         max_iterations,
@@ -667,7 +1377,7 @@ impl Runner {
                                             Some(format!("Usage limit exceeded: {}", e)),
                                         );
                                     }
-                                    save_metrics(&run_metrics);
+                                    save_metrics(run_metrics);
                                     return RunResult {
                                         all_passed: false,
                                         stories_passed: self.count_passing_stories().unwrap_or(0),
@@ -676,11 +1386,13 @@ impl Runner {
                                         error: Some(
                                             "Usage limit exceeded. Checkpoint saved. Resume later with: ralph --resume".to_string()
                                         ),
+                                        outcome: RunOutcome::BudgetExhausted,
                                     };
                                 }
                                 ErrorCategory::Fatal(_) => {
                                     // For fatal errors, stop execution with clear message
                                     self.save_checkpoint(
+                                        run_metrics,
                                         &story_id,
                                         start_iteration,
                                         max_iterations,
@@ -695,13 +1407,18 @@ impl Runner {
                                             Some(format!("Fatal error: {}", e)),
                                         );
                                     }
-                                    save_metrics(&run_metrics);
+                                    save_metrics(run_metrics);
                                     return RunResult {
                                         all_passed: false,
                                         stories_passed: self.count_passing_stories().unwrap_or(0),
                                         total_stories,
                                         total_iterations,
                                         error: Some(format!("Fatal error: {}", e)),
+                                        outcome: if matches!(e, ExecutorError::BudgetExceeded(_)) {
+                                            RunOutcome::BudgetExhausted
+                                        } else {
+                                            RunOutcome::Error
+                                        },
                                     };
                                 }
                                 ErrorCategory::Timeout(_) => {
@@ -712,6 +1429,7 @@ impl Runner {
                                     );
                                     println!("{}", notification);
                                     self.save_checkpoint(
+                                        run_metrics,
                                         &story_id,
                                         start_iteration,
                                         max_iterations,
@@ -726,7 +1444,7 @@ impl Runner {
                                             Some(format!("Timeout: {}", e)),
                                         );
                                     }
-                                    save_metrics(&run_metrics);
+                                    save_metrics(run_metrics);
                                     return RunResult {
                                         all_passed: false,
                                         stories_passed: self.count_passing_stories().unwrap_or(0),
@@ -736,6 +1454,7 @@ impl Runner {
                                             "Timeout: {}. Checkpoint saved. Resume with: ralph --resume",
                                             e
                                         )),
+                                        outcome: RunOutcome::Timeout,
                                     };
                                 }
                             }
@@ -748,6 +1467,12 @@ impl Runner {
 
     /// Load the PRD file
     fn load_prd(&self) -> Result<PrdFile, String> {
+        if Workspace::is_manifest(&self.config.prd_path) {
+            return Workspace::load(&self.config.prd_path)
+                .and_then(|workspace| workspace.merged())
+                .map_err(|e| e.to_string());
+        }
+
         let content = std::fs::read_to_string(&self.config.prd_path)
             .map_err(|e| format!("Failed to read {}: {}", self.config.prd_path.display(), e))?;
 
@@ -768,11 +1493,52 @@ impl Runner {
         Ok(prd.user_stories.iter().filter(|s| s.passes).count())
     }
 
-    /// Save a checkpoint with the current execution state.
+    /// Save a checkpoint with the current execution state and the run
+    /// metrics collected so far.
     ///
     /// Does nothing if checkpointing is disabled.
+    /// Stop the run for the stop file: checkpoint the story in progress at
+    /// `iteration`, remove the file and record the stop in the evidence log.
+    /// Returns the message the run ends with.
+    fn stop_run(
+        &self,
+        stop_file: &StopFile,
+        run_metrics: &RunMetricsCollector,
+        evidence: Option<&mut EvidenceWriter>,
+        story_id: &str,
+        iteration: u32,
+    ) -> String {
+        let reason = stop_file.reason();
+        self.save_checkpoint(
+            run_metrics,
+            story_id,
+            iteration,
+            self.config.max_iterations_per_story,
+            PauseReason::StopRequested {
+                reason: reason.clone(),
+            },
+        );
+        if let Err(e) = stop_file.clear() {
+            eprintln!(
+                "Warning: Failed to remove {}: {}",
+                stop_file.path().display(),
+                e
+            );
+        }
+
+        let message = match reason {
+            Some(reason) => format!("Stopped by {}: {}", STOP_FILE, reason),
+            None => format!("Stopped by {}", STOP_FILE),
+        };
+        if let Some(writer) = evidence {
+            writer.emit_run_complete("failed", Some("stopped".to_string()), Some(message.clone()));
+        }
+        format!("{}. Checkpoint saved. Resume with: ralph --resume", message)
+    }
+
     fn save_checkpoint(
         &self,
+        run_metrics: &RunMetricsCollector,
         story_id: &str,
         iteration: u32,
         max_iterations: u32,
@@ -784,7 +1550,9 @@ impl Runner {
                 Some(StoryCheckpoint::new(story_id, iteration, max_iterations)),
                 pause_reason,
                 uncommitted_files,
-            );
+            )
+            .with_fingerprint(self.run_fingerprint())
+            .with_run_metrics(run_metrics.finish());
 
             if let Err(e) = manager.save(&checkpoint) {
                 eprintln!("Warning: Failed to save checkpoint: {}", e);
@@ -792,6 +1560,11 @@ impl Runner {
         }
     }
 
+    /// Fingerprint of the PRD and branch this run works on.
+    fn run_fingerprint(&self) -> Option<RunFingerprint> {
+        RunFingerprint::capture(&self.config.prd_path, &self.config.working_dir)
+    }
+
     /// Clear the checkpoint (called on successful completion).
     ///
     /// Does nothing if checkpointing is disabled.
@@ -859,6 +1632,17 @@ impl Runner {
             println!("║  Story:      (none)                                          ║");
         }
 
+        // Parallel run progress
+        if let Some(ref state) = checkpoint.parallel_state {
+            let progress = format!(
+                "{} completed, {} failed, {} pending",
+                state.completed.len(),
+                state.failed.len(),
+                state.pending.len()
+            );
+            println!("║  Parallel:   {:<48} ║", progress);
+        }
+
         // Pause reason
         let reason_str = match &checkpoint.pause_reason {
             PauseReason::UsageLimitExceeded => "Usage limit exceeded".to_string(),
@@ -866,10 +1650,21 @@ impl Runner {
             PauseReason::UserRequested => "User requested".to_string(),
             PauseReason::Timeout => "Timeout".to_string(),
             PauseReason::IterationBoundary => "Iteration boundary".to_string(),
+            PauseReason::Autosave => "Autosave".to_string(),
+            PauseReason::StopRequested { .. } => "Stop requested".to_string(),
             PauseReason::CircuitBreakerTriggered {
                 consecutive_failures,
                 threshold,
+                category: None,
             } => format!("Circuit breaker ({}/{})", consecutive_failures, threshold),
+            PauseReason::CircuitBreakerTriggered {
+                consecutive_failures,
+                threshold,
+                category: Some(category),
+            } => format!(
+                "Circuit breaker ({}/{} {})",
+                consecutive_failures, threshold, category
+            ),
             PauseReason::Error(msg) => {
                 let truncated = if msg.len() > 40 {
                     format!("{}...", &msg[..37])
@@ -950,15 +1745,30 @@ impl Runner {
                 println!("  Type:        Iteration Boundary");
                 println!("  Details:     Checkpoint saved at iteration start for recovery");
             }
+            PauseReason::Autosave => {
+                println!("  Type:        Autosave");
+                println!("  Details:     Checkpoint saved periodically; the run was interrupted");
+            }
+            PauseReason::StopRequested { reason } => {
+                println!("  Type:        Stop Requested");
+                println!(
+                    "  Details:     {}",
+                    reason.as_deref().unwrap_or("The stop file was created")
+                );
+            }
             PauseReason::CircuitBreakerTriggered {
                 consecutive_failures,
                 threshold,
+                category,
             } => {
                 println!("  Type:        Circuit Breaker Triggered");
                 println!(
                     "  Details:     {} consecutive failures (threshold: {})",
                     consecutive_failures, threshold
                 );
+                if let Some(category) = category {
+                    println!("  Category:    {}", category);
+                }
             }
             PauseReason::Error(msg) => {
                 println!("  Type:        Error");
@@ -967,6 +1777,25 @@ impl Runner {
         }
         println!();
 
+        // Parallel run state
+        if let Some(ref state) = checkpoint.parallel_state {
+            println!("Parallel Run:");
+            println!("  Completed:   {}", state.completed.join(", "));
+            for (story_id, error) in &state.failed {
+                println!("  Failed:      {} ({})", story_id, error);
+            }
+            if !state.skipped.is_empty() {
+                println!("  Skipped:     {}", state.skipped.join(", "));
+            }
+            if !state.pending.is_empty() {
+                println!("  Pending:     {}", state.pending.join(", "));
+            }
+            for (story_id, iteration) in &state.iterations {
+                println!("  In flight:   {} (iteration {})", story_id, iteration);
+            }
+            println!();
+        }
+
         // Uncommitted files
         if !checkpoint.uncommitted_files.is_empty() {
             println!(
@@ -1011,10 +1840,11 @@ impl Runner {
         }
     }
 
-    /// Handle the checkpoint resume flow at startup.
+    /// Decide whether to resume from an existing checkpoint.
     ///
-    /// Returns the story checkpoint to resume from, if any.
-    fn handle_checkpoint_resume(&self) -> Option<StoryCheckpoint> {
+    /// Honors `--resume` and `--no-resume`, otherwise prompts the user.
+    /// Returns the checkpoint to resume from, if any.
+    fn resolve_checkpoint(&self) -> Option<Checkpoint> {
         // Check if checkpointing is disabled
         if self.config.no_checkpoint {
             return None;
@@ -1029,20 +1859,66 @@ impl Runner {
             return None;
         }
 
-        // Handle --resume flag: auto-resume without prompt
+        let current = self.run_fingerprint();
+        let stale = checkpoint
+            .fingerprint
+            .as_ref()
+            .zip(current.as_ref())
+            .is_some_and(|(saved, current)| saved.prd_changed(current));
+
+        // Handle --resume flag: auto-resume without prompt, unless the PRD
+        // changed and the user does not confirm
         if self.config.resume {
-            return checkpoint.current_story;
+            if stale && self.confirm_stale_resume(&checkpoint) != Some(true) {
+                println!(
+                    "Starting without the checkpoint. Run with --resume --force to use it anyway."
+                );
+                return None;
+            }
+            return Some(checkpoint);
+        }
+
+        let matches = current
+            .as_ref()
+            .is_some_and(|current| checkpoint.matches(current));
+
+        // Handle --auto-resume flag: resume without prompt if the checkpoint
+        // was written for this PRD and branch
+        if self.config.auto_resume && matches {
+            if !self.config.display_options.quiet {
+                println!("Resuming from checkpoint (matches the current PRD and branch)");
+            }
+            return Some(checkpoint);
         }
 
         // Interactive mode: prompt user
         self.display_checkpoint_summary(&checkpoint);
+        if matches {
+            println!("This checkpoint matches the current PRD and branch.");
+            println!();
+        } else if let Some((saved, current)) = checkpoint.fingerprint.as_ref().zip(current.as_ref())
+        {
+            println!(
+                "This checkpoint may not apply: {}.",
+                saved.differences(current).join(" and ")
+            );
+            Self::display_prd_changes(saved, current);
+            println!();
+        }
 
         loop {
             let choice = self.prompt_resume_choice();
             match choice {
-                ResumeChoice::Resume => {
-                    return checkpoint.current_story;
-                }
+                ResumeChoice::Resume if stale => match self.confirm_stale_resume(&checkpoint) {
+                    Some(true) => return Some(checkpoint),
+                    // Ask again only after a real answer
+                    Some(false) => continue,
+                    None => {
+                        println!("No answer; starting without the checkpoint.");
+                        return None;
+                    }
+                },
+                ResumeChoice::Resume => return Some(checkpoint),
                 ResumeChoice::Discard => {
                     self.clear_checkpoint();
                     return None;
@@ -1055,6 +1931,48 @@ impl Runner {
         }
     }
 
+    /// Print the stories that changed in the PRD and whether HEAD moved since
+    /// a checkpoint was written.
+    fn display_prd_changes(saved: &RunFingerprint, current: &RunFingerprint) {
+        if saved.prd_changed(current) {
+            let changes = saved.prd_changes(current);
+            if changes.is_empty() {
+                println!("  No story was added, removed or modified.");
+            }
+            for line in changes.describe() {
+                println!("  {}", line);
+            }
+        }
+        if let Some((saved_head, current_head)) = saved.head_moved(current) {
+            println!(
+                "  HEAD moved from {} to {}",
+                short_commit(saved_head),
+                short_commit(current_head)
+            );
+        }
+    }
+
+    /// Ask before resuming from a checkpoint whose PRD has since changed.
+    ///
+    /// Returns `true` without asking when `--force` was given, and `None` when
+    /// no answer can be read (e.g. stdin is at end of input).
+    fn confirm_stale_resume(&self, checkpoint: &Checkpoint) -> Option<bool> {
+        if let Some((saved, current)) = checkpoint.fingerprint.as_ref().zip(self.run_fingerprint())
+        {
+            println!("The PRD changed since this checkpoint was written:");
+            Self::display_prd_changes(saved, &current);
+        }
+        if self.config.force_resume {
+            println!("Resuming anyway (--force).");
+            return Some(true);
+        }
+
+        print!("Resume anyway? [y/N]: ");
+        let _ = io::stdout().flush();
+        let answer = read_answer(&mut io::stdin().lock())?;
+        Some(matches!(answer.as_str(), "y" | "yes"))
+    }
+
     /// Format a duration in a human-readable way.
     fn format_duration(duration: chrono::Duration) -> String {
         let total_seconds = duration.num_seconds().unsigned_abs();
@@ -1078,10 +1996,60 @@ impl Runner {
     }
 }
 
+/// Run the final verification on the integrated tree in `working_dir`,
+/// printing its outcome unless `quiet`, and return why it failed.
+pub(crate) fn run_final_verification(
+    working_dir: &Path,
+    evidence: Option<EvidenceWriter>,
+    quiet: bool,
+) -> Option<String> {
+    if !quiet {
+        println!();
+        println!("Final verification: running quality gates on the integrated tree");
+    }
+    let artifacts_dir = evidence.as_ref().map(EvidenceWriter::artifacts_dir);
+    let results = verify_workspace(
+        Profile::default(),
+        working_dir,
+        artifacts_dir.as_deref(),
+        evidence,
+    );
+    if !quiet {
+        println!(
+            "Final verification: {}",
+            QualityGateChecker::summary(&results)
+        );
+    }
+    verification_failure(&results)
+}
+
+/// Abbreviate a commit hash for display.
+fn short_commit(commit: &str) -> &str {
+    commit.get(..7).unwrap_or(commit)
+}
+
+/// Read one answer from `input`, trimmed and lowercased, or `None` at end of
+/// input or on a read error.
+fn read_answer(input: &mut impl io::BufRead) -> Option<String> {
+    let mut answer = String::new();
+    match input.read_line(&mut answer) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(answer.trim().to_lowercase()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_read_answer_stops_at_end_of_input() {
+        assert_eq!(read_answer(&mut &b" Y\n"[..]), Some("y".to_string()));
+        // An empty line is an answer; end of input is not
+        assert_eq!(read_answer(&mut &b"\n"[..]), Some(String::new()));
+        assert_eq!(read_answer(&mut &b""[..]), None);
+    }
+
     #[test]
     fn test_default_circuit_breaker_threshold() {
         // Verify the default circuit breaker threshold is 5
@@ -1104,6 +2072,26 @@ mod tests {
         assert_eq!(config.circuit_breaker_threshold, Some(3));
     }
 
+    #[test]
+    fn test_local_agent_timeout_defaults() {
+        let runner = |agent: &str, timeout_seconds: Option<u64>| {
+            Runner::new(RunnerConfig {
+                agent_command: Some(agent.to_string()),
+                timeout_seconds,
+                no_checkpoint: true,
+                ..Default::default()
+            })
+        };
+        let config = runner("api:ollama:qwen2.5-coder:7b", None).build_timeout_config();
+        assert_eq!(config, TimeoutConfig::local_model());
+
+        let config = runner("api:ollama", Some(120)).build_timeout_config();
+        assert_eq!(config.agent_timeout, Duration::from_secs(120));
+
+        let config = runner("claude", None).build_timeout_config();
+        assert_eq!(config, TimeoutConfig::default());
+    }
+
     #[test]
     fn test_circuit_breaker_threshold_resolution() {
         // Test that None resolves to default
@@ -1172,4 +2160,40 @@ mod tests {
         let consecutive_failures: u32 = 0;
         assert_eq!(consecutive_failures, 0);
     }
+
+    #[test]
+    fn test_exit_code_policy() {
+        let default = ExitCodePolicy::default();
+        assert_eq!(default.code(RunOutcome::Passed), 0);
+        assert_eq!(default.code(RunOutcome::Timeout), 1);
+
+        let policy = ExitCodePolicy::parse("detailed, timeout=20").unwrap();
+        assert_eq!(policy.code(RunOutcome::Passed), 0);
+        assert_eq!(policy.code(RunOutcome::Error), 1);
+        assert_eq!(policy.code(RunOutcome::GateFailure), 10);
+        assert_eq!(policy.code(RunOutcome::CircuitBreaker), 11);
+        assert_eq!(policy.code(RunOutcome::Timeout), 20);
+        assert_eq!(
+            ExitCodePolicy::parse("gate_failure=3")
+                .unwrap()
+                .code(RunOutcome::BudgetExhausted),
+            1
+        );
+        assert!(ExitCodePolicy::parse("passed=2").is_err());
+        assert!(ExitCodePolicy::parse("timeout=300").is_err());
+        assert!(ExitCodePolicy::parse("verbose").is_err());
+    }
+
+    #[test]
+    fn test_run_outcome_from_failures() {
+        assert_eq!(
+            RunOutcome::from_failures([FailureCategory::QualityGate, FailureCategory::Timeout]),
+            RunOutcome::Timeout
+        );
+        assert_eq!(
+            RunOutcome::from_failures([FailureCategory::UsageLimit, FailureCategory::Fatal]),
+            RunOutcome::BudgetExhausted
+        );
+        assert_eq!(RunOutcome::from_failures([]), RunOutcome::GateFailure);
+    }
 }
@@ -78,6 +78,8 @@ pub struct HeartbeatMonitor {
     config: TimeoutConfig,
     /// Timestamp of the last heartbeat pulse.
     last_heartbeat: Arc<Mutex<Instant>>,
+    /// Most recent line of agent output, reported alongside stalls.
+    last_output: Arc<Mutex<Option<String>>>,
     /// Channel sender for heartbeat events.
     sender: mpsc::Sender<HeartbeatEvent>,
     /// Flag to signal the background task to stop.
@@ -107,6 +109,7 @@ impl HeartbeatMonitor {
         let monitor = Self {
             config,
             last_heartbeat: Arc::new(Mutex::new(Instant::now())),
+            last_output: Arc::new(Mutex::new(None)),
             sender,
             stop_flag: Arc::new(AtomicBool::new(false)),
             task_handle: Arc::new(Mutex::new(None)),
@@ -125,6 +128,20 @@ impl HeartbeatMonitor {
         *last = Instant::now();
     }
 
+    /// Records a heartbeat pulse caused by a line of agent output, keeping
+    /// the line so a later stall can say what the agent last did.
+    pub async fn pulse_output(&self, line: &str) {
+        self.pulse().await;
+        if !line.trim().is_empty() {
+            *self.last_output.lock().await = Some(line.trim().to_string());
+        }
+    }
+
+    /// Returns the most recent non-empty line passed to `pulse_output`.
+    pub async fn last_output(&self) -> Option<String> {
+        self.last_output.lock().await.clone()
+    }
+
     /// Starts the background monitoring task.
     ///
     /// The task waits for an initial grace period (to allow agent startup),
@@ -293,6 +310,19 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_pulse_output_keeps_last_line() {
+        let (monitor, _receiver) = HeartbeatMonitor::new(test_config());
+        assert_eq!(monitor.last_output().await, None);
+
+        monitor.pulse_output("Reading src/lib.rs").await;
+        monitor.pulse_output("   ").await;
+        assert_eq!(
+            monitor.last_output().await.as_deref(),
+            Some("Reading src/lib.rs")
+        );
+    }
+
     #[tokio::test]
     async fn test_pulse_updates_timestamp() {
         let config = test_config();
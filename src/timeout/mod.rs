@@ -65,6 +65,20 @@ impl TimeoutConfig {
         Self::default()
     }
 
+    /// Creates a TimeoutConfig suited to models running on this machine.
+    ///
+    /// Local models generate far slower than hosted APIs and may need to be
+    /// loaded into memory before the first token, so the agent gets 30
+    /// minutes, each iteration 40, and the startup grace period 5.
+    pub fn local_model() -> Self {
+        Self {
+            agent_timeout: Duration::from_secs(1800),
+            iteration_timeout: Duration::from_secs(2400),
+            startup_grace_period: Duration::from_secs(300),
+            ..Self::default()
+        }
+    }
+
     /// Creates a TimeoutConfig with custom values.
     pub fn with_values(
         agent_timeout: Duration,
@@ -135,6 +149,15 @@ mod tests {
         assert_eq!(config.agent_timeout, Duration::from_secs(600));
     }
 
+    #[test]
+    fn test_local_model_timeouts() {
+        let config = TimeoutConfig::local_model();
+        assert_eq!(config.agent_timeout, Duration::from_secs(1800));
+        assert_eq!(config.iteration_timeout, Duration::from_secs(2400));
+        assert_eq!(config.startup_grace_period, Duration::from_secs(300));
+        assert_eq!(config.git_timeout, TimeoutConfig::default().git_timeout);
+    }
+
     #[test]
     fn test_default_iteration_timeout() {
         let config = TimeoutConfig::default();
@@ -4,6 +4,9 @@
 
 #![allow(dead_code)]
 
+use std::fmt;
+use std::str::FromStr;
+
 use owo_colors::Rgb;
 
 /// 24-bit RGB color theme for Ralph's terminal UI.
@@ -53,6 +56,103 @@ impl Theme {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Darker colors that stay readable on light terminal backgrounds.
+    pub fn light() -> Self {
+        Self {
+            success: Rgb(21, 128, 61),
+            error: Rgb(185, 28, 28),
+            warning: Rgb(161, 98, 7),
+            in_progress: Rgb(29, 78, 216),
+            muted: Rgb(75, 85, 99),
+            story_id: Rgb(14, 116, 144),
+            active: Rgb(194, 65, 12),
+            completed: Rgb(107, 114, 128),
+            primary: Rgb(17, 24, 39),
+        }
+    }
+
+    /// The Okabe-Ito palette, which keeps success and error apart for red-green
+    /// color blindness.
+    pub fn colorblind() -> Self {
+        Self {
+            success: Rgb(0, 158, 115),
+            error: Rgb(213, 94, 0),
+            warning: Rgb(240, 228, 66),
+            in_progress: Rgb(0, 114, 178),
+            muted: Rgb(153, 153, 153),
+            story_id: Rgb(86, 180, 233),
+            active: Rgb(230, 159, 0),
+            completed: Rgb(128, 128, 128),
+            primary: Rgb(255, 255, 255),
+        }
+    }
+}
+
+/// Which [`Theme`] to color with (`--theme`, `RALPH_THEME`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThemeName {
+    /// The default colors, for dark backgrounds
+    #[default]
+    Dark,
+    /// [`Theme::light`]
+    Light,
+    /// [`Theme::colorblind`]
+    Colorblind,
+    /// No colors at all, as with `--no-color`
+    NoColor,
+}
+
+impl ThemeName {
+    const ALL: [ThemeName; 4] = [
+        ThemeName::Dark,
+        ThemeName::Light,
+        ThemeName::Colorblind,
+        ThemeName::NoColor,
+    ];
+
+    /// The colors of this theme; `NoColor` turns colors off instead.
+    pub fn theme(self) -> Theme {
+        match self {
+            Self::Dark | Self::NoColor => Theme::default(),
+            Self::Light => Theme::light(),
+            Self::Colorblind => Theme::colorblind(),
+        }
+    }
+
+    /// Label used on the command line and in `RALPH_THEME`.
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::Colorblind => "colorblind",
+            Self::NoColor => "no-color",
+        }
+    }
+}
+
+impl fmt::Display for ThemeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
+impl FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|name| name.as_label() == value.trim())
+            .ok_or_else(|| {
+                let labels: Vec<&str> = Self::ALL.iter().map(|name| name.as_label()).collect();
+                format!(
+                    "unknown theme '{}' (expected one of: {})",
+                    value.trim(),
+                    labels.join(", ")
+                )
+            })
+    }
 }
 
 /// ANSI escape codes for text styling.
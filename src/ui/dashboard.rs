@@ -0,0 +1,596 @@
+//! Live web dashboard for parallel runs.
+//!
+//! `ralph --parallel --dashboard 127.0.0.1:8080` serves a page showing every
+//! story with its status and gate results, the queue depth, the token budget
+//! and the tail of the agents' output, so a headless run can be followed from
+//! a browser. The dashboard is built from the public [`RunEvent`] of each
+//! [`ParallelUIEvent`] the terminal UI shows, which it passes on unchanged:
+//!
+//! - `GET /` serves the page, which refreshes as events arrive
+//! - `GET /api/state` returns the current [`DashboardState`] as JSON
+//! - `GET /api/events` streams the run's events as server-sent events, one
+//!   [`EventLine`] per message, as `--output jsonl` prints them
+//!
+//! Only compiled with the `dashboard` feature.
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use futures::Stream;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::mcp::tools::load_prd::PrdUserStory;
+use crate::metrics::RunMetricsCollector;
+use crate::ui::events::{EventLine, RunEvent};
+use crate::ui::parallel_events::{ParallelUIEvent, StoryStatus};
+
+/// The dashboard page.
+const DASHBOARD_PAGE: &str = include_str!("dashboard.html");
+
+/// Agent output lines kept for the log tail.
+pub const LOG_TAIL_LINES: usize = 200;
+
+/// Capacity of the channel the scheduler sends events to the dashboard on.
+const EVENT_CHANNEL_CAPACITY: usize = 100;
+
+/// Events kept for slow `/api/events` subscribers; beyond this they miss
+/// the oldest.
+const EVENT_STREAM_CAPACITY: usize = 256;
+
+/// A quality gate result of a story's current iteration.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GateStatus {
+    pub name: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// A story as shown in the story grid.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct StoryCard {
+    pub id: String,
+    pub title: String,
+    pub priority: u32,
+    pub status: StoryStatus,
+    pub iteration: u32,
+    pub max_iterations: u32,
+    pub gates: Vec<GateStatus>,
+    /// Why the story failed, was skipped, blocked or deferred
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+/// Stories waiting for a free slot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct QueueDepth {
+    pub queued: usize,
+    pub capacity: usize,
+    pub policy: String,
+}
+
+/// Tokens the run's agents used against the run's token budget.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BudgetMeter {
+    pub tokens: u64,
+    /// Total token budget, if the run has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    pub cost_cents: f64,
+}
+
+/// A line of agent output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LogLine {
+    pub story_id: String,
+    pub line: String,
+    pub is_stderr: bool,
+}
+
+/// Everything the dashboard shows.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DashboardState {
+    /// Stories in PRD order
+    pub stories: Vec<StoryCard>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue: Option<QueueDepth>,
+    /// Concurrent stories, once adaptive concurrency adjusted it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<u32>,
+    /// Failures counted by the circuit breaker, and its threshold
+    pub failures: u32,
+    pub failure_threshold: u32,
+    pub circuit_open: bool,
+    pub paused: bool,
+    /// Result of the last reconciliation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reconciliation: Option<String>,
+    pub budget: BudgetMeter,
+    /// The last [`LOG_TAIL_LINES`] lines of agent output
+    pub log: VecDeque<LogLine>,
+}
+
+impl DashboardState {
+    /// State before any story ran; stories passing already show as passed.
+    pub fn new(stories: &[PrdUserStory]) -> Self {
+        Self {
+            stories: stories
+                .iter()
+                .map(|story| StoryCard {
+                    id: story.id.clone(),
+                    title: story.title.clone(),
+                    priority: story.priority,
+                    status: if story.passes {
+                        StoryStatus::Completed
+                    } else {
+                        StoryStatus::Pending
+                    },
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Update the state with what `event` reports.
+    pub fn apply(&mut self, event: &RunEvent) {
+        match event {
+            RunEvent::StoryStarted {
+                story, iteration, ..
+            } => {
+                let card = self.story(&story.id);
+                if card.title.is_empty() {
+                    card.title = story.title.clone();
+                    card.priority = story.priority;
+                }
+                if card.status != StoryStatus::SequentialRetry {
+                    card.status = StoryStatus::InProgress;
+                }
+                card.iteration = *iteration;
+                card.gates.clear();
+                card.message = None;
+            }
+            RunEvent::IterationUpdate {
+                story_id,
+                iteration,
+                max_iterations,
+                ..
+            } => {
+                let card = self.story(story_id);
+                if card.iteration != *iteration {
+                    card.gates.clear();
+                }
+                card.iteration = *iteration;
+                card.max_iterations = *max_iterations;
+            }
+            RunEvent::AgentOutput {
+                story_id,
+                line,
+                is_stderr,
+            } => {
+                if self.log.len() == LOG_TAIL_LINES {
+                    self.log.pop_front();
+                }
+                self.log.push_back(LogLine {
+                    story_id: story_id.clone(),
+                    line: line.clone(),
+                    is_stderr: *is_stderr,
+                });
+            }
+            RunEvent::GateUpdate {
+                story_id,
+                gate_name,
+                passed,
+                message,
+            } => {
+                let gates = &mut self.story(story_id).gates;
+                let gate = GateStatus {
+                    name: gate_name.clone(),
+                    passed: *passed,
+                    message: message.clone(),
+                };
+                match gates.iter_mut().find(|gate| gate.name == *gate_name) {
+                    Some(existing) => *existing = gate,
+                    None => gates.push(gate),
+                }
+            }
+            RunEvent::StoryCompleted {
+                story_id,
+                iterations_used,
+                duration_ms,
+            } => {
+                let card = self.story(story_id);
+                card.status = StoryStatus::Completed;
+                card.iteration = *iterations_used;
+                card.duration_ms = Some(*duration_ms);
+                card.message = None;
+            }
+            RunEvent::StoryFailed {
+                story_id, error, ..
+            } => self.finish(story_id, StoryStatus::Failed, error),
+            RunEvent::StorySkipped { story_id, reason }
+            | RunEvent::PrerequisiteUnmet { story_id, reason } => {
+                self.finish(story_id, StoryStatus::Skipped, reason)
+            }
+            RunEvent::StoryBlocked {
+                story_id,
+                blocked_by,
+            } => self.finish(
+                story_id,
+                StoryStatus::Blocked,
+                &format!("blocked by {}", blocked_by),
+            ),
+            RunEvent::ConflictDeferred {
+                story_id,
+                blocking_story_id,
+                ..
+            } => self.finish(
+                story_id,
+                StoryStatus::Deferred,
+                &format!("waiting for {}", blocking_story_id),
+            ),
+            RunEvent::SequentialRetryStarted { story_id, reason } => {
+                self.finish(story_id, StoryStatus::SequentialRetry, reason)
+            }
+            RunEvent::QueueStatus {
+                queued,
+                capacity,
+                policy,
+            } => {
+                self.queue = Some(QueueDepth {
+                    queued: *queued,
+                    capacity: *capacity,
+                    policy: policy.clone(),
+                });
+            }
+            RunEvent::ReconciliationStatus { message, .. } => {
+                self.reconciliation = Some(message.clone());
+            }
+            RunEvent::CircuitBreakerStatus {
+                current_failures,
+                threshold,
+            } => {
+                self.failures = *current_failures;
+                self.failure_threshold = *threshold;
+            }
+            RunEvent::CircuitBreakerTriggered {
+                failures,
+                threshold,
+            } => {
+                self.failures = *failures;
+                self.failure_threshold = *threshold;
+                self.circuit_open = true;
+            }
+            RunEvent::RunPaused => self.paused = true,
+            RunEvent::RunResumed => self.paused = false,
+            RunEvent::ConcurrencyAdjusted { current, .. } => {
+                self.concurrency = Some(*current);
+            }
+            RunEvent::RunStarted { .. }
+            | RunEvent::RunFinished { .. }
+            | RunEvent::GateOutput { .. }
+            | RunEvent::RunPausing { .. }
+            | RunEvent::Unknown => {}
+        }
+    }
+
+    /// The grid entry of `story_id`, added if the PRD did not list it.
+    fn story(&mut self, story_id: &str) -> &mut StoryCard {
+        let index = match self.stories.iter().position(|story| story.id == story_id) {
+            Some(index) => index,
+            None => {
+                self.stories.push(StoryCard {
+                    id: story_id.to_string(),
+                    ..Default::default()
+                });
+                self.stories.len() - 1
+            }
+        };
+        &mut self.stories[index]
+    }
+
+    fn finish(&mut self, story_id: &str, status: StoryStatus, message: &str) {
+        let card = self.story(story_id);
+        card.status = status;
+        card.message = Some(message.to_string());
+    }
+}
+
+#[derive(Clone)]
+struct DashboardContext {
+    state: Arc<Mutex<DashboardState>>,
+    events: broadcast::Sender<EventLine>,
+    run_metrics: Option<RunMetricsCollector>,
+    token_budget: Option<u64>,
+}
+
+impl DashboardContext {
+    fn snapshot(&self) -> DashboardState {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(ref run_metrics) = self.run_metrics {
+            let stats = run_metrics.invocation_stats();
+            state.budget.tokens = stats.tokens;
+            state.budget.cost_cents = stats.cost_cents;
+        }
+        state.budget.limit = self.token_budget;
+        state
+    }
+}
+
+/// Creates the dashboard router over `state`, streaming the event lines sent
+/// on `events`.
+///
+/// Token usage is read from `run_metrics`, against a total `token_budget`.
+pub fn dashboard_router(
+    state: Arc<Mutex<DashboardState>>,
+    events: broadcast::Sender<EventLine>,
+    run_metrics: Option<RunMetricsCollector>,
+    token_budget: Option<u64>,
+) -> Router {
+    Router::new()
+        .route("/", get(page_handler))
+        .route("/api/state", get(state_handler))
+        .route("/api/events", get(events_handler))
+        .with_state(DashboardContext {
+            state,
+            events,
+            run_metrics,
+            token_budget,
+        })
+}
+
+/// GET /
+async fn page_handler() -> Html<&'static str> {
+    Html(DASHBOARD_PAGE)
+}
+
+/// GET /api/state
+async fn state_handler(State(context): State<DashboardContext>) -> Json<DashboardState> {
+    Json(context.snapshot())
+}
+
+/// GET /api/events
+///
+/// Subscribers that fall behind skip the events they missed.
+async fn events_handler(
+    State(context): State<DashboardContext>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let receiver = context.events.subscribe();
+    let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(line) => return Some((Event::default().json_data(&line), receiver)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// A running dashboard; the server stops when it is dropped.
+pub struct Dashboard {
+    addr: SocketAddr,
+    sender: mpsc::Sender<ParallelUIEvent>,
+    server: JoinHandle<()>,
+}
+
+impl Dashboard {
+    /// Serve the dashboard for `stories` on `addr`.
+    ///
+    /// Events sent to [`Dashboard::sender`] update the dashboard and are
+    /// then passed on to `forward`, the terminal UI, if there is one.
+    pub async fn start(
+        addr: SocketAddr,
+        stories: &[PrdUserStory],
+        run_metrics: Option<RunMetricsCollector>,
+        token_budget: Option<u64>,
+        forward: Option<mpsc::Sender<ParallelUIEvent>>,
+    ) -> std::io::Result<Self> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(DashboardState::new(stories)));
+        let (events, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+        let router = dashboard_router(state.clone(), events.clone(), run_metrics, token_budget);
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+
+        let (sender, mut receiver) = mpsc::channel::<ParallelUIEvent>(EVENT_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if let Some(run_event) = RunEvent::from_ui_event(&event) {
+                    state
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .apply(&run_event);
+                    let _ = events.send(EventLine::new(run_event));
+                }
+                if let Some(ref forward) = forward {
+                    let _ = forward.try_send(event);
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            sender,
+            server,
+        })
+    }
+
+    /// Address the dashboard is served on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Sender for the run's UI events.
+    pub fn sender(&self) -> mpsc::Sender<ParallelUIEvent> {
+        self.sender.clone()
+    }
+}
+
+impl Drop for Dashboard {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::parallel_events::StoryDisplayInfo;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+
+    fn stories() -> Vec<PrdUserStory> {
+        serde_json::from_str(
+            r#"[
+                {"id": "US-001", "title": "Cart", "priority": 1, "passes": false},
+                {"id": "US-002", "title": "Pay", "priority": 2, "passes": false},
+                {"id": "US-003", "title": "Docs", "priority": 3, "passes": true}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dashboard_state_follows_events() {
+        let mut state = DashboardState::new(&stories());
+        for event in [
+            ParallelUIEvent::StoryStarted {
+                story: StoryDisplayInfo::new("US-001", "Cart", 1),
+                iteration: 1,
+                concurrent_count: 1,
+            },
+            ParallelUIEvent::GateUpdate {
+                story_id: "US-001".to_string(),
+                gate_name: "tests".to_string(),
+                passed: false,
+                message: Some("2 failed".to_string()),
+            },
+            ParallelUIEvent::IterationUpdate {
+                story_id: "US-001".to_string(),
+                iteration: 2,
+                max_iterations: 5,
+                message: None,
+            },
+            ParallelUIEvent::GateUpdate {
+                story_id: "US-001".to_string(),
+                gate_name: "tests".to_string(),
+                passed: true,
+                message: None,
+            },
+            ParallelUIEvent::StoryBlocked {
+                story_id: "US-002".to_string(),
+                blocked_by: "US-004".to_string(),
+            },
+            ParallelUIEvent::QueueStatus {
+                queued: 1,
+                capacity: 8,
+                policy: "block".to_string(),
+            },
+        ] {
+            state.apply(&RunEvent::from_ui_event(&event).unwrap());
+        }
+        for index in 0..=LOG_TAIL_LINES {
+            state.apply(&RunEvent::AgentOutput {
+                story_id: "US-001".to_string(),
+                line: format!("line {}", index),
+                is_stderr: false,
+            });
+        }
+
+        let cart = &state.stories[0];
+        assert_eq!(cart.status, StoryStatus::InProgress);
+        assert_eq!((cart.iteration, cart.max_iterations), (2, 5));
+        assert_eq!(
+            cart.gates,
+            vec![GateStatus {
+                name: "tests".to_string(),
+                passed: true,
+                message: None
+            }]
+        );
+        assert_eq!(state.stories[1].status, StoryStatus::Blocked);
+        assert_eq!(
+            state.stories[1].message.as_deref(),
+            Some("blocked by US-004")
+        );
+        assert_eq!(state.stories[2].status, StoryStatus::Completed);
+        assert_eq!(state.queue.as_ref().map(|queue| queue.queued), Some(1));
+        assert_eq!(state.log.len(), LOG_TAIL_LINES);
+        assert_eq!(state.log[0].line, "line 1");
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_serves_state() {
+        let state = Arc::new(Mutex::new(DashboardState::new(&stories())));
+        let run_metrics = RunMetricsCollector::new("run-test", 3);
+        let mut invocation = crate::metrics::AgentInvocation::new("US-001", 1, "agent", "claude");
+        invocation.output_tokens = Some(1_500);
+        run_metrics.record_invocation(invocation);
+        let (events, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+        let app = dashboard_router(state, events.clone(), Some(run_metrics), Some(10_000));
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/state")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 64 * 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["stories"][2]["status"], "completed");
+        assert_eq!(json["budget"]["tokens"], 1_500);
+        assert_eq!(json["budget"]["limit"], 10_000);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/events")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        events.send(EventLine::new(RunEvent::RunPaused)).unwrap();
+        let mut body = response.into_body().into_data_stream();
+        let frame = body.next().await.unwrap().unwrap();
+        let frame = std::str::from_utf8(&frame).unwrap();
+        assert!(frame.starts_with("data: {\"schema_version\":1,"));
+        assert!(frame.contains("\"event\":\"run_paused\""));
+    }
+}
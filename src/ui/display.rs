@@ -54,6 +54,11 @@ pub trait DisplayCallback: Send + Sync {
     ///
     /// Default implementation does nothing.
     fn on_agent_completed(&self, _story_id: &str, _success: bool) {}
+
+    /// Called when a quality gate finished, with its result and output.
+    ///
+    /// Default implementation does nothing.
+    fn on_gate_result(&self, _story_id: &str, _iteration: u32, _result: &GateResult) {}
 }
 
 use std::io::Write;
@@ -275,13 +280,14 @@ impl DisplayCallback for StreamingDisplayCallback {
 
 use crate::mcp::server::ExecutionState;
 use crate::quality::gates::GateResult;
-use crate::ui::colors::Theme;
+use crate::ui::colors::{Theme, ThemeName};
 use crate::ui::ghostty::{GhosttyFeatures, TitleStatus};
 use crate::ui::interrupt::InterruptHandler;
 use crate::ui::quality_gates::{QualityGateRenderer, QualityGateView};
 use crate::ui::spinner::{IterationProgress, ProgressManager, RalphSpinner};
 use crate::ui::story_view::{StoryInfo, StoryView, StoryViewState};
 use crate::ui::summary::{ExecutionSummary, GateStatistics, StoryResult, SummaryRenderer};
+use crate::ui::symbols::SymbolSet;
 
 /// UI mode for terminal display.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -312,6 +318,10 @@ pub struct DisplayOptions {
     pub expand_details: bool,
     /// Verbosity level (0 = normal, 1 = verbose, 2 = very verbose)
     pub verbosity: u8,
+    /// Color theme; `NoColor` disables colors like `--no-color`
+    pub theme: ThemeName,
+    /// Unicode or ASCII status icons and frames
+    pub symbols: SymbolSet,
 }
 
 impl DisplayOptions {
@@ -360,6 +370,18 @@ impl DisplayOptions {
         self
     }
 
+    /// Set the color theme.
+    pub fn with_theme(mut self, theme: ThemeName) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set the symbol set.
+    pub fn with_symbols(mut self, symbols: SymbolSet) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
     /// Set verbosity level.
     ///
     /// - 0: Normal output
@@ -388,9 +410,13 @@ impl DisplayOptions {
     ///
     /// Priority:
     /// 1. Explicit color option from CLI (--no-color)
-    /// 2. NO_COLOR environment variable
-    /// 3. Default to enabled
+    /// 2. The `no-color` theme
+    /// 3. NO_COLOR environment variable
+    /// 4. Default to enabled
     pub fn should_enable_colors(&self) -> bool {
+        if self.theme == ThemeName::NoColor && self.color.is_none() {
+            return false;
+        }
         match self.color {
             Some(enabled) => enabled,
             None => {
@@ -506,7 +532,7 @@ impl RalphDisplay {
 
     /// Create a RalphDisplay with the given options.
     pub fn with_options(options: DisplayOptions) -> Self {
-        let theme = Theme::default();
+        let theme = options.theme.theme();
         let colors_enabled = options.should_enable_colors();
         let rich_ui_enabled = options.should_enable_rich_ui();
         Self {
@@ -0,0 +1,427 @@
+//! Versioned event schema for external consumers.
+//!
+//! [`ParallelUIEvent`] connects the scheduler to the terminal UI and changes
+//! with it, including events that only flow from the UI back to the scheduler.
+//! What a run reports to the outside (`--output jsonl`, the run log and the
+//! dashboard's `/api/events` stream) is a [`RunEvent`] instead, written as an
+//! [`EventLine`]:
+//!
+//! ```text
+//! {"schema_version":1,"timestamp":"2026-01-05T10:00:00.000Z","event":"run_started","run_id":"run-...","parallel":true}
+//! ```
+//!
+//! Within a schema version, events and fields are only ever added; consumers
+//! should ignore events and fields they do not know. Renaming or removing
+//! either, or changing what one means, bumps [`EVENT_SCHEMA_VERSION`].
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::runner::RunResult;
+use crate::ui::parallel_events::ParallelUIEvent;
+
+/// Version of the [`RunEvent`] schema, written to every [`EventLine`].
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// A story, as reported when it starts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoryInfo {
+    /// Story identifier (e.g., "US-001")
+    pub id: String,
+    pub title: String,
+    /// Priority level (1 = highest)
+    pub priority: u32,
+}
+
+/// Something that happened during a run.
+///
+/// Serialized with an `event` tag naming the variant in snake_case.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunEvent {
+    /// The run started.
+    RunStarted {
+        /// Run ID, as in `.ralph/runs/<run-id>.json`
+        run_id: String,
+        /// Whether stories run in parallel
+        parallel: bool,
+    },
+
+    /// The run finished.
+    RunFinished {
+        run_id: String,
+        all_passed: bool,
+        stories_passed: usize,
+        total_stories: usize,
+        total_iterations: u32,
+        duration_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        /// Why the run ended: passed, gate_failure, error, timeout, budget,
+        /// circuit_breaker or interrupted
+        #[serde(default)]
+        outcome: String,
+    },
+
+    /// A story started, or restarted after a failed iteration.
+    StoryStarted {
+        story: StoryInfo,
+        /// Iteration number (1-indexed)
+        iteration: u32,
+        /// Stories running at the same time, this one included
+        concurrent_count: usize,
+    },
+
+    /// A story moved on to another iteration.
+    IterationUpdate {
+        story_id: String,
+        /// Iteration number (1-indexed)
+        iteration: u32,
+        max_iterations: u32,
+        #[serde(default)]
+        message: Option<String>,
+    },
+
+    /// A line of a story's agent output.
+    AgentOutput {
+        story_id: String,
+        /// The line, without its trailing newline
+        line: String,
+        is_stderr: bool,
+    },
+
+    /// A quality gate of a story passed or failed.
+    GateUpdate {
+        story_id: String,
+        gate_name: String,
+        passed: bool,
+        #[serde(default)]
+        message: Option<String>,
+    },
+
+    /// A quality gate finished, with its full output; only in the run log.
+    GateOutput {
+        story_id: String,
+        iteration: u32,
+        gate: String,
+        passed: bool,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+
+    /// A story passed.
+    StoryCompleted {
+        story_id: String,
+        iterations_used: u32,
+        duration_ms: u64,
+    },
+
+    /// A story failed after exhausting its iterations or retries.
+    StoryFailed {
+        story_id: String,
+        error: String,
+        /// Iteration the story failed at
+        iteration: u32,
+    },
+
+    /// A story was cancelled and skipped without failing the run.
+    StorySkipped { story_id: String, reason: String },
+
+    /// A story cannot run because a story it depends on failed or was
+    /// skipped; it runs if that story later passes.
+    StoryBlocked {
+        story_id: String,
+        /// The failed or skipped story it depends on
+        blocked_by: String,
+    },
+
+    /// A story cannot run because an external prerequisite is not met.
+    PrerequisiteUnmet { story_id: String, reason: String },
+
+    /// A story was deferred because it would change files another story is
+    /// changing.
+    ConflictDeferred {
+        story_id: String,
+        blocking_story_id: String,
+        conflicting_files: Vec<PathBuf>,
+    },
+
+    /// A story is retried on its own after failing in parallel.
+    SequentialRetryStarted { story_id: String, reason: String },
+
+    /// Stories waiting for a free slot.
+    QueueStatus {
+        queued: usize,
+        capacity: usize,
+        /// block, reject, drop_oldest or evict_lowest_priority
+        policy: String,
+    },
+
+    /// Result of reconciling the stories' changes.
+    ReconciliationStatus {
+        success: bool,
+        issues_count: usize,
+        message: String,
+    },
+
+    /// Failures counted by the circuit breaker.
+    CircuitBreakerStatus {
+        current_failures: u32,
+        threshold: u32,
+    },
+
+    /// The circuit breaker stopped the run.
+    CircuitBreakerTriggered { failures: u32, threshold: u32 },
+
+    /// A pause was requested; running stories finish first.
+    RunPausing {
+        /// Stories still running
+        in_flight: usize,
+    },
+
+    /// The run is paused.
+    RunPaused,
+
+    /// The run resumed after a pause.
+    RunResumed,
+
+    /// Adaptive concurrency changed the number of concurrent stories.
+    ConcurrencyAdjusted {
+        previous: u32,
+        current: u32,
+        reason: String,
+    },
+
+    /// An event from a later schema addition this build does not know.
+    #[serde(other, skip_serializing)]
+    Unknown,
+}
+
+impl RunEvent {
+    /// The event reporting `result` of run `run_id`.
+    pub fn finished(run_id: impl Into<String>, result: &RunResult, duration: Duration) -> Self {
+        Self::RunFinished {
+            run_id: run_id.into(),
+            all_passed: result.all_passed,
+            stories_passed: result.stories_passed,
+            total_stories: result.total_stories,
+            total_iterations: result.total_iterations,
+            duration_ms: duration.as_millis() as u64,
+            error: result.error.clone(),
+            outcome: result.outcome.as_label().to_string(),
+        }
+    }
+
+    /// The public event reporting `event`, or `None` for the events the UI
+    /// and scheduler only exchange with each other.
+    pub fn from_ui_event(event: &ParallelUIEvent) -> Option<Self> {
+        let event = match event.clone() {
+            ParallelUIEvent::StoryStarted {
+                story,
+                iteration,
+                concurrent_count,
+            } => Self::StoryStarted {
+                story: StoryInfo {
+                    id: story.id,
+                    title: story.title,
+                    priority: story.priority,
+                },
+                iteration,
+                concurrent_count,
+            },
+            ParallelUIEvent::IterationUpdate {
+                story_id,
+                iteration,
+                max_iterations,
+                message,
+            } => Self::IterationUpdate {
+                story_id,
+                iteration,
+                max_iterations,
+                message,
+            },
+            ParallelUIEvent::AgentOutput {
+                story_id,
+                line,
+                is_stderr,
+            } => Self::AgentOutput {
+                story_id,
+                line,
+                is_stderr,
+            },
+            ParallelUIEvent::GateUpdate {
+                story_id,
+                gate_name,
+                passed,
+                message,
+            } => Self::GateUpdate {
+                story_id,
+                gate_name,
+                passed,
+                message,
+            },
+            ParallelUIEvent::StoryCompleted {
+                story_id,
+                iterations_used,
+                duration_ms,
+            } => Self::StoryCompleted {
+                story_id,
+                iterations_used,
+                duration_ms,
+            },
+            ParallelUIEvent::StoryFailed {
+                story_id,
+                error,
+                iteration,
+            } => Self::StoryFailed {
+                story_id,
+                error,
+                iteration,
+            },
+            ParallelUIEvent::StorySkipped { story_id, reason } => {
+                Self::StorySkipped { story_id, reason }
+            }
+            ParallelUIEvent::StoryBlocked {
+                story_id,
+                blocked_by,
+            } => Self::StoryBlocked {
+                story_id,
+                blocked_by,
+            },
+            ParallelUIEvent::PrerequisiteUnmet { story_id, reason } => {
+                Self::PrerequisiteUnmet { story_id, reason }
+            }
+            ParallelUIEvent::ConflictDeferred {
+                story_id,
+                blocking_story_id,
+                conflicting_files,
+            } => Self::ConflictDeferred {
+                story_id,
+                blocking_story_id,
+                conflicting_files,
+            },
+            ParallelUIEvent::SequentialRetryStarted { story_id, reason } => {
+                Self::SequentialRetryStarted { story_id, reason }
+            }
+            ParallelUIEvent::QueueStatus {
+                queued,
+                capacity,
+                policy,
+            } => Self::QueueStatus {
+                queued,
+                capacity,
+                policy,
+            },
+            ParallelUIEvent::ReconciliationStatus {
+                success,
+                issues_count,
+                message,
+            } => Self::ReconciliationStatus {
+                success,
+                issues_count,
+                message,
+            },
+            ParallelUIEvent::CircuitBreakerStatus {
+                current_failures,
+                threshold,
+            } => Self::CircuitBreakerStatus {
+                current_failures,
+                threshold,
+            },
+            ParallelUIEvent::CircuitBreakerTriggered {
+                failures,
+                threshold,
+            } => Self::CircuitBreakerTriggered {
+                failures,
+                threshold,
+            },
+            ParallelUIEvent::RunPausing { in_flight } => Self::RunPausing { in_flight },
+            ParallelUIEvent::RunPaused => Self::RunPaused,
+            ParallelUIEvent::RunResumed => Self::RunResumed,
+            ParallelUIEvent::ConcurrencyAdjusted {
+                previous,
+                current,
+                reason,
+            } => Self::ConcurrencyAdjusted {
+                previous,
+                current,
+                reason,
+            },
+            ParallelUIEvent::CancelStoryRequested { .. }
+            | ParallelUIEvent::KeyboardToggle { .. }
+            | ParallelUIEvent::GracefulQuitRequested
+            | ParallelUIEvent::ImmediateInterrupt
+            | ParallelUIEvent::TogglePauseRequested
+            | ParallelUIEvent::ConcurrencyChangeRequested { .. }
+            | ParallelUIEvent::StorySelected { .. }
+            | ParallelUIEvent::VerboseToggleRequested { .. }
+            | ParallelUIEvent::StoryVerbosityChanged { .. }
+            | ParallelUIEvent::StoryDetailsToggled { .. } => return None,
+        };
+        Some(event)
+    }
+}
+
+/// A [`RunEvent`] as written to a JSON line, with the schema version and
+/// when it happened.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventLine {
+    pub schema_version: u32,
+    /// RFC 3339 time in UTC, with milliseconds
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: RunEvent,
+}
+
+impl EventLine {
+    /// `event`, stamped with the current time.
+    pub fn new(event: RunEvent) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            event,
+        }
+    }
+
+    /// Parses a JSON line written by any version of ralph.
+    ///
+    /// Check [`EventLine::schema_version`] before relying on the event.
+    pub fn parse(line: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::parallel_events::StoryDisplayInfo;
+
+    #[test]
+    fn test_event_lines_round_trip() {
+        let started = RunEvent::from_ui_event(&ParallelUIEvent::StoryStarted {
+            story: StoryDisplayInfo::new("US-001", "Cart", 1),
+            iteration: 1,
+            concurrent_count: 2,
+        })
+        .unwrap();
+        let line = serde_json::to_string(&EventLine::new(started.clone())).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["schema_version"], EVENT_SCHEMA_VERSION);
+        assert_eq!(json["event"], "story_started");
+        assert_eq!(json["story"]["id"], "US-001");
+        assert_eq!(json["concurrent_count"], 2);
+        assert!(json["timestamp"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(EventLine::parse(&line).unwrap().event, started);
+
+        assert_eq!(
+            RunEvent::from_ui_event(&ParallelUIEvent::TogglePauseRequested),
+            None
+        );
+        let line = r#"{"schema_version":1,"timestamp":"2026-01-05T10:00:00.000Z","event":"story_rebased","story_id":"US-001"}"#;
+        assert_eq!(EventLine::parse(line).unwrap().event, RunEvent::Unknown);
+    }
+}
@@ -0,0 +1,347 @@
+//! GitHub Actions output (`--output github`).
+//!
+//! Reports a run to the GitHub Actions runner through its workflow commands:
+//! every failed quality gate becomes an `::error` annotation per failure
+//! location, with the gate's full output folded into a `::group::`. When the
+//! run finishes, a Markdown table of story results is appended to the job
+//! summary (`$GITHUB_STEP_SUMMARY`), and `run_id`, `stories_passed`,
+//! `total_stories`, `all_passed` and `outcome` are set as step outputs
+//! (`$GITHUB_OUTPUT`) for later steps.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::metrics::RunMetrics;
+use crate::quality::gates::{GateFailureDetail, GateResult};
+use crate::quality::GateSeverity;
+use crate::runner::RunResult;
+use crate::ui::display::DisplayCallback;
+
+/// Longest error shown in a job summary row.
+const SUMMARY_ERROR_CHARS: usize = 120;
+
+/// Writes a run's workflow commands, job summary and step outputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GithubActions {
+    /// Job summary file (`$GITHUB_STEP_SUMMARY`), if set
+    summary_path: Option<PathBuf>,
+    /// Step output file (`$GITHUB_OUTPUT`), if set
+    output_path: Option<PathBuf>,
+}
+
+impl GithubActions {
+    /// Reports to the job summary and step output files the runner set.
+    pub fn from_env() -> Self {
+        let path = |name: &str| {
+            std::env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        Self {
+            summary_path: path("GITHUB_STEP_SUMMARY"),
+            output_path: path("GITHUB_OUTPUT"),
+        }
+    }
+
+    /// A display callback that annotates the failed gates of `story_id`, then
+    /// passes everything on to `inner`.
+    pub fn output_callback(
+        &self,
+        story_id: impl Into<String>,
+        inner: Option<Arc<dyn DisplayCallback>>,
+    ) -> Arc<dyn DisplayCallback> {
+        Arc::new(AnnotatedOutput {
+            story_id: story_id.into(),
+            inner,
+        })
+    }
+
+    /// Appends the story results of a finished run to the job summary and
+    /// sets its step outputs.
+    ///
+    /// Failed writes are reported as warnings; outside GitHub Actions there
+    /// is nothing to write.
+    pub fn finish(&self, metrics: &RunMetrics, result: &RunResult) {
+        if let Some(ref path) = self.summary_path {
+            if let Err(err) = append(path, &job_summary(metrics, result)) {
+                eprintln!("Warning: Failed to write job summary: {}", err);
+            }
+        }
+        if let Some(ref path) = self.output_path {
+            let outputs = format!(
+                "run_id={}\nstories_passed={}\ntotal_stories={}\nall_passed={}\noutcome={}\n",
+                metrics.run_id,
+                result.stories_passed,
+                result.total_stories,
+                result.all_passed,
+                result.outcome
+            );
+            if let Err(err) = append(path, &outputs) {
+                eprintln!("Warning: Failed to set step outputs: {}", err);
+            }
+        }
+    }
+}
+
+/// Display callback annotating a story's failed gates before passing its
+/// output on.
+struct AnnotatedOutput {
+    story_id: String,
+    inner: Option<Arc<dyn DisplayCallback>>,
+}
+
+impl DisplayCallback for AnnotatedOutput {
+    fn on_agent_output(&self, line: &str, is_stderr: bool) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_output(line, is_stderr);
+        }
+    }
+
+    fn on_agent_started(&self, story_id: &str, iteration: u32) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_started(story_id, iteration);
+        }
+    }
+
+    fn on_agent_completed(&self, story_id: &str, success: bool) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_completed(story_id, success);
+        }
+    }
+
+    fn on_gate_result(&self, story_id: &str, iteration: u32, result: &GateResult) {
+        if !result.passed {
+            let commands = gate_commands(&self.story_id, iteration, result);
+            let mut stdout = io::stdout().lock();
+            let _ = stdout.write_all(commands.as_bytes());
+            let _ = stdout.flush();
+        }
+        if let Some(ref inner) = self.inner {
+            inner.on_gate_result(story_id, iteration, result);
+        }
+    }
+}
+
+/// Workflow commands reporting the failed gate `result` of `story_id`.
+pub fn gate_commands(story_id: &str, iteration: u32, result: &GateResult) -> String {
+    let level = match result.severity {
+        GateSeverity::Blocking => "error",
+        GateSeverity::Warning => "warning",
+    };
+    let title = format!(
+        "{} ({} iteration {})",
+        result.gate_name, story_id, iteration
+    );
+    let mut output = String::new();
+    if let Some(ref details) = result.details {
+        output.push_str(&format!(
+            "::group::{}: {}\n",
+            escape_data(&title),
+            escape_data(&result.message)
+        ));
+        output.push_str(details.trim_end());
+        output.push_str("\n::endgroup::\n");
+    }
+    if result.failures.is_empty() {
+        output.push_str(&format!(
+            "::{} title={}::{}\n",
+            level,
+            escape_property(&title),
+            escape_data(&result.message)
+        ));
+    }
+    for failure in &result.failures {
+        output.push_str(&annotation(level, &title, failure));
+    }
+    output
+}
+
+/// An annotation command for `failure`, at its location if it has one.
+fn annotation(level: &str, title: &str, failure: &GateFailureDetail) -> String {
+    let mut properties = Vec::new();
+    if let Some(ref file) = failure.file {
+        properties.push(format!("file={}", escape_property(file)));
+        if let Some(line) = failure.line {
+            properties.push(format!("line={}", line));
+            if let Some(column) = failure.column {
+                properties.push(format!("col={}", column));
+            }
+        }
+    }
+    let title = match failure.error_code {
+        Some(ref code) => format!("{} {}", title, code),
+        None => title.to_string(),
+    };
+    properties.push(format!("title={}", escape_property(&title)));
+    let mut message = failure.message.clone();
+    if let Some(ref suggestion) = failure.suggestion {
+        message.push_str(&format!("\nSuggestion: {}", suggestion));
+    }
+    format!(
+        "::{} {}::{}\n",
+        level,
+        properties.join(","),
+        escape_data(&message)
+    )
+}
+
+/// Markdown job summary of a finished run: the result, then one row per
+/// story that ran, in the order they started.
+pub fn job_summary(metrics: &RunMetrics, result: &RunResult) -> String {
+    let mut output = format!("## Ralph run `{}`\n\n", metrics.run_id);
+    output.push_str(&format!(
+        "{} {} of {} stories passed in {:.1}s",
+        if result.all_passed { "✅" } else { "❌" },
+        result.stories_passed,
+        result.total_stories,
+        metrics.run_duration.as_secs_f64()
+    ));
+    match result.error {
+        Some(ref error) => output.push_str(&format!(": {}\n\n", escape_cell(error))),
+        None => output.push_str(".\n\n"),
+    }
+    if metrics.steps.is_empty() {
+        return output;
+    }
+
+    let mut steps: Vec<_> = metrics.steps.iter().collect();
+    steps.sort_by_key(|step| step.started_at);
+    output.push_str("| Story | Result | Attempts | Duration | Error |\n");
+    output.push_str("| --- | --- | --: | --: | --- |\n");
+    for step in steps {
+        let error = step
+            .error
+            .as_deref()
+            .and_then(|error| error.lines().next())
+            .map(|line| {
+                let mut line: String = line.chars().take(SUMMARY_ERROR_CHARS).collect();
+                if line.len() < step.error.as_deref().unwrap_or_default().len() {
+                    line.push('…');
+                }
+                escape_cell(&line)
+            })
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "| {} | {} | {} | {:.1}s | {} |\n",
+            escape_cell(&step.step_id),
+            if step.success {
+                "✅ passed"
+            } else {
+                "❌ failed"
+            },
+            step.attempts,
+            step.duration.as_secs_f64(),
+            error
+        ));
+    }
+    output
+}
+
+fn append(path: &Path, text: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(text.as_bytes())
+}
+
+/// Escapes a workflow command's message.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a workflow command's property value.
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Escapes text for a Markdown table cell.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::RunMetricsCollector;
+    use crate::quality::gates::FailureCategory;
+    use crate::runner::RunOutcome;
+    use std::time::Duration;
+
+    #[test]
+    fn test_gate_commands() {
+        let result = GateResult::fail(
+            "clippy",
+            "2 warnings",
+            Some("warning: unused variable\nwarning: needless borrow\n".to_string()),
+            Some(vec![
+                GateFailureDetail::new(FailureCategory::Lint, "unused variable: `x`")
+                    .with_location("src/lib.rs", 10, Some(5))
+                    .with_error_code("unused_variables"),
+                GateFailureDetail::new(FailureCategory::Lint, "50% of\nlines"),
+            ]),
+        );
+        let commands = gate_commands("US-001", 2, &result);
+        let lines: Vec<&str> = commands.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "::group::clippy (US-001 iteration 2): 2 warnings",
+                "warning: unused variable",
+                "warning: needless borrow",
+                "::endgroup::",
+                "::error file=src/lib.rs,line=10,col=5,title=clippy (US-001 iteration 2) unused_variables::unused variable: `x`",
+                "::error title=clippy (US-001 iteration 2)::50%25 of%0Alines",
+            ]
+        );
+
+        let result = GateResult::fail("tests", "tests failed", None, None)
+            .with_severity(GateSeverity::Warning);
+        assert_eq!(
+            gate_commands("US-001", 1, &result),
+            "::warning title=tests (US-001 iteration 1)::tests failed\n"
+        );
+    }
+
+    #[test]
+    fn test_finish_writes_summary_and_outputs() {
+        let run_metrics = RunMetricsCollector::new("run-1", 2);
+        run_metrics.start_step("US-001");
+        run_metrics.complete_step("US-001", true, 1, Duration::from_secs(3), None);
+        run_metrics.start_step("US-002");
+        run_metrics.complete_step(
+            "US-002",
+            false,
+            3,
+            Duration::from_secs(9),
+            Some("Quality gates failed | clippy\nmore".to_string()),
+        );
+        let metrics = run_metrics.finish();
+        let result = RunResult {
+            all_passed: false,
+            stories_passed: 1,
+            total_stories: 2,
+            total_iterations: 4,
+            error: Some("US-002 failed".to_string()),
+            outcome: RunOutcome::GateFailure,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let github = GithubActions {
+            summary_path: Some(dir.path().join("summary.md")),
+            output_path: Some(dir.path().join("output")),
+        };
+        github.finish(&metrics, &result);
+
+        let summary = std::fs::read_to_string(dir.path().join("summary.md")).unwrap();
+        assert!(summary.starts_with("## Ralph run `run-1`\n\n❌ 1 of 2 stories passed in "));
+        assert!(summary.contains("| US-001 | ✅ passed | 1 | 3.0s |  |\n"));
+        assert!(summary
+            .contains("| US-002 | ❌ failed | 3 | 9.0s | Quality gates failed \\| clippy… |\n"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("output")).unwrap(),
+            "run_id=run-1\nstories_passed=1\ntotal_stories=2\nall_passed=false\noutcome=gate_failure\n"
+        );
+    }
+}
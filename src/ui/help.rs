@@ -167,6 +167,46 @@ pub const COMMANDS: &[CommandInfo] = &[
         "Run quality checks (typecheck, lint, test)",
         None,
     ),
+    CommandInfo::new(
+        "gates",
+        "Run quality gates standalone (text or JSON output)",
+        Some("[-d <DIR>] [--profile <NAME>] [-o json]"),
+    ),
+    CommandInfo::new(
+        "plan",
+        "Preview parallel batches, lock conflicts and durations",
+        Some("[--prd <FILE>] [--max-concurrency <N>] [-o json]"),
+    ),
+    CommandInfo::new(
+        "timeline",
+        "Chart when a past run's stories queued, waited on locks and ran",
+        Some("[RUN_ID] [--width <N>] [--svg <FILE>]"),
+    ),
+    CommandInfo::new(
+        "prd",
+        "Create, validate, lint or import GitHub issues into a PRD",
+        Some("[validate|lint|import|init] [--prd <FILE>]"),
+    ),
+    CommandInfo::new(
+        "compare",
+        "Run one story with several agents or models and compare them",
+        Some("--story <ID> --variant <AGENT[@MODEL]>..."),
+    ),
+    CommandInfo::new(
+        "worker",
+        "Run stories dispatched by a parallel run on another machine",
+        Some("[--bind <ADDR>] [--port <PORT>] [--capacity <N>]"),
+    ),
+    CommandInfo::new(
+        "checkpoint",
+        "List, inspect or delete saved checkpoints",
+        Some("[list|show|delete] [NAME] [--all]"),
+    ),
+    CommandInfo::new(
+        "stop",
+        "Stop a running run, checkpointing it for --resume",
+        Some("[REASON] [--clear]"),
+    ),
 ];
 
 /// Global options for Ralph CLI.
@@ -181,6 +221,16 @@ pub const GLOBAL_OPTIONS: &[CommandInfo] = &[
         "Disable colors (also respects NO_COLOR env)",
         None,
     ),
+    CommandInfo::new(
+        "--theme <THEME>",
+        "Colors: dark (default), light, colorblind, or no-color",
+        None,
+    ),
+    CommandInfo::new(
+        "--symbols <SET>",
+        "Status icons and frames: unicode (default) or ascii",
+        None,
+    ),
     CommandInfo::new("--no-animation", "Disable startup animations", None),
     CommandInfo::new("--quiet, -q", "Suppress all output except errors", None),
     CommandInfo::new(
@@ -190,7 +240,7 @@ pub const GLOBAL_OPTIONS: &[CommandInfo] = &[
     ),
     CommandInfo::new(
         "--agent <CMD>",
-        "Agent command (claude, codex, amp, or custom)",
+        "Agent command (claude, codex, amp, aider, or custom)",
         None,
     ),
     CommandInfo::new("--help, -h", "Print help information", None),
@@ -0,0 +1,118 @@
+//! JSON Lines output of a run (`--output jsonl`).
+//!
+//! Instead of rendering the terminal UI, every event of the run is printed to
+//! stdout as one JSON object per line, so CI systems and wrapper scripts can
+//! follow progress and read the result. Each line is an
+//! [`EventLine`](crate::ui::events::EventLine): the versioned public event
+//! schema, with a `timestamp` and an `event` naming the variant, e.g.:
+//!
+//! ```text
+//! {"schema_version":1,"timestamp":"2026-01-05T10:00:00.000Z","event":"run_started","run_id":"run-...","parallel":true}
+//! {"schema_version":1,"timestamp":"2026-01-05T10:00:00.120Z","event":"story_started","story":{"id":"US-001",...},...}
+//! {"schema_version":1,"timestamp":"2026-01-05T10:04:12.530Z","event":"run_finished","all_passed":true,...}
+//! ```
+
+use std::io::Write;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::ui::events::{EventLine, RunEvent};
+use crate::ui::parallel_events::ParallelUIEvent;
+
+/// Capacity of the channel events are printed from.
+const EVENT_CHANNEL_CAPACITY: usize = 100;
+
+/// How long [`JsonlWriter::finish`] waits for queued events to be printed.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// `event` as a JSON line, stamped with the schema version and the current
+/// time.
+pub fn to_json_line(event: RunEvent) -> serde_json::Result<String> {
+    serde_json::to_string(&EventLine::new(event))
+}
+
+/// Print `event` to stdout as a JSON line.
+pub fn print_event(event: RunEvent) {
+    if let Ok(line) = to_json_line(event) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}", line);
+        let _ = stdout.flush();
+    }
+}
+
+/// Prints the [`ParallelUIEvent`]s sent to it as JSON lines, leaving out
+/// the ones without a [`RunEvent`].
+pub struct JsonlWriter {
+    sender: mpsc::Sender<ParallelUIEvent>,
+    printer: JoinHandle<()>,
+}
+
+impl JsonlWriter {
+    /// Start printing events; must be called within a Tokio runtime.
+    pub fn start() -> Self {
+        let (sender, mut receiver) = mpsc::channel::<ParallelUIEvent>(EVENT_CHANNEL_CAPACITY);
+        let printer = tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if let Some(event) = RunEvent::from_ui_event(&event) {
+                    print_event(event);
+                }
+            }
+        });
+        Self { sender, printer }
+    }
+
+    /// Sender for the run's UI events.
+    pub fn sender(&self) -> mpsc::Sender<ParallelUIEvent> {
+        self.sender.clone()
+    }
+
+    /// Print the events still queued once every sender is dropped.
+    pub async fn finish(self) {
+        drop(self.sender);
+        let mut printer = self.printer;
+        if tokio::time::timeout(FLUSH_TIMEOUT, &mut printer)
+            .await
+            .is_err()
+        {
+            printer.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{RunOutcome, RunResult};
+    use crate::ui::events::EVENT_SCHEMA_VERSION;
+
+    #[test]
+    fn test_json_lines() {
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json_line(RunEvent::RunPaused).unwrap()).unwrap();
+        assert_eq!(json["event"], "run_paused");
+        assert_eq!(json["schema_version"], EVENT_SCHEMA_VERSION);
+
+        let result = RunResult {
+            all_passed: false,
+            stories_passed: 2,
+            total_stories: 3,
+            total_iterations: 7,
+            error: Some("US-003 failed".to_string()),
+            outcome: RunOutcome::GateFailure,
+        };
+        let line = to_json_line(RunEvent::finished(
+            "run-1",
+            &result,
+            Duration::from_secs(90),
+        ))
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["event"], "run_finished");
+        assert_eq!(json["stories_passed"], 2);
+        assert_eq!(json["duration_ms"], 90_000);
+        assert_eq!(json["error"], "US-003 failed");
+        assert_eq!(json["outcome"], "gate_failure");
+    }
+}
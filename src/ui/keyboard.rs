@@ -5,6 +5,7 @@
 
 #![allow(dead_code)]
 
+use std::io::Write;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -12,8 +13,10 @@ use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use crossterm::terminal;
+use tokio::sync::mpsc;
 
-use crate::pause::PauseController;
+use crate::pause::{PauseController, PauseState};
+use crate::ui::parallel_events::ParallelUIEvent;
 
 /// Key bindings for toggle controls.
 #[derive(Debug, Clone, Copy)]
@@ -26,6 +29,22 @@ pub struct KeyBindings {
     pub quit: KeyCode,
     /// Pause execution
     pub pause: KeyCode,
+    /// Cancel a single story (parallel mode)
+    pub cancel_story: KeyCode,
+    /// Select the previous story (parallel mode)
+    pub select_previous: KeyCode,
+    /// Select the next story (parallel mode)
+    pub select_next: KeyCode,
+    /// Skip the selected story (parallel mode)
+    pub skip_selected: KeyCode,
+    /// Toggle verbose output of the selected story (parallel mode)
+    pub toggle_verbose: KeyCode,
+    /// Open or close the detail pane of the selected story (parallel mode)
+    pub toggle_details: KeyCode,
+    /// Run one more story at a time (parallel mode)
+    pub concurrency_up: KeyCode,
+    /// Run one story fewer at a time (parallel mode)
+    pub concurrency_down: KeyCode,
 }
 
 impl Default for KeyBindings {
@@ -35,6 +54,14 @@ impl Default for KeyBindings {
             toggle_expand: KeyCode::Char('e'),
             quit: KeyCode::Char('q'),
             pause: KeyCode::Char('p'),
+            cancel_story: KeyCode::Char('x'),
+            select_previous: KeyCode::Up,
+            select_next: KeyCode::Down,
+            skip_selected: KeyCode::Char('k'),
+            toggle_verbose: KeyCode::Char('v'),
+            toggle_details: KeyCode::Enter,
+            concurrency_up: KeyCode::Char('+'),
+            concurrency_down: KeyCode::Char('-'),
         }
     }
 }
@@ -130,6 +157,111 @@ pub enum ToggleEvent {
     CtrlE,
 }
 
+/// Sends keyboard control events (story cancel and selection, pause, concurrency,
+/// interrupt) to a parallel run.
+#[derive(Debug, Clone)]
+pub struct RunControl {
+    /// Story IDs in display order; a story can be picked by its 1-based position
+    stories: Vec<String>,
+    /// Channel to the parallel scheduler
+    sender: mpsc::Sender<ParallelUIEvent>,
+}
+
+impl RunControl {
+    /// Create a run control for the given stories.
+    pub fn new(stories: Vec<String>, sender: mpsc::Sender<ParallelUIEvent>) -> Self {
+        Self { stories, sender }
+    }
+
+    /// Resolve prompt input (a 1-based position or a story ID) to a story ID.
+    pub fn resolve(&self, input: &str) -> Option<&str> {
+        let input = input.trim();
+        if let Ok(position) = input.parse::<usize>() {
+            return position
+                .checked_sub(1)
+                .and_then(|index| self.stories.get(index))
+                .map(String::as_str);
+        }
+        self.stories
+            .iter()
+            .find(|id| id.eq_ignore_ascii_case(input))
+            .map(String::as_str)
+    }
+
+    /// Request cancellation of the story named by `input`.
+    ///
+    /// Returns the story ID if the request was sent.
+    pub fn request(&self, input: &str) -> Option<String> {
+        let story_id = self.resolve(input)?.to_string();
+        self.cancel(&story_id).then_some(story_id)
+    }
+
+    /// Request cancellation of `story_id`; returns whether the request was sent.
+    pub fn cancel(&self, story_id: &str) -> bool {
+        self.send(ParallelUIEvent::CancelStoryRequested {
+            story_id: story_id.to_string(),
+        })
+    }
+
+    /// Move the selection `offset` stories from `selected`, wrapping around.
+    ///
+    /// With nothing selected, moving forward selects the first story and moving
+    /// back the last one. Returns the new selection.
+    pub fn select(&self, selected: Option<usize>, offset: isize) -> Option<usize> {
+        let count = self.stories.len() as isize;
+        if count == 0 {
+            return None;
+        }
+        let index = match selected {
+            Some(index) => (index as isize + offset).rem_euclid(count),
+            None if offset < 0 => count - 1,
+            None => 0,
+        } as usize;
+        self.send(ParallelUIEvent::StorySelected {
+            story_id: self.stories[index].clone(),
+        });
+        Some(index)
+    }
+
+    /// Story ID at a selection index.
+    pub fn story(&self, index: usize) -> Option<&str> {
+        self.stories.get(index).map(String::as_str)
+    }
+
+    /// Ask the run to pause dispatch, or to resume when paused.
+    pub fn toggle_pause(&self) {
+        self.send(ParallelUIEvent::TogglePauseRequested);
+    }
+
+    /// Ask the run to change its concurrency by `delta`.
+    pub fn change_concurrency(&self, delta: i32) {
+        self.send(ParallelUIEvent::ConcurrencyChangeRequested { delta });
+    }
+
+    /// Ask the run to toggle verbose output of `story_id`.
+    pub fn toggle_verbose(&self, story_id: &str) {
+        self.send(ParallelUIEvent::VerboseToggleRequested {
+            story_id: story_id.to_string(),
+        });
+    }
+
+    /// Ask the display to open or close the detail pane of `story_id`.
+    pub fn toggle_details(&self, story_id: &str) {
+        self.send(ParallelUIEvent::StoryDetailsToggled {
+            story_id: story_id.to_string(),
+        });
+    }
+
+    /// Ask the run to stop immediately (Ctrl+C while raw mode swallows SIGINT).
+    pub fn interrupt(&self) {
+        self.send(ParallelUIEvent::ImmediateInterrupt);
+    }
+
+    fn send(&self, event: ParallelUIEvent) -> bool {
+        self.sender.try_send(event).is_ok()
+    }
+}
+
 /// Keyboard listener for toggle controls.
 ///
 /// Runs in a background thread and updates shared toggle state.
@@ -142,6 +274,8 @@ pub struct KeyboardListener {
     running: Arc<AtomicBool>,
     /// Optional pause controller for pause functionality
     pause_controller: Option<PauseController>,
+    /// Optional control channel to a parallel run
+    run_control: Option<RunControl>,
 }
 
 impl KeyboardListener {
@@ -152,6 +286,7 @@ impl KeyboardListener {
             bindings: KeyBindings::default(),
             running: Arc::new(AtomicBool::new(false)),
             pause_controller: None,
+            run_control: None,
         }
     }
 
@@ -162,6 +297,7 @@ impl KeyboardListener {
             bindings,
             running: Arc::new(AtomicBool::new(false)),
             pause_controller: None,
+            run_control: None,
         }
     }
 
@@ -171,6 +307,14 @@ impl KeyboardListener {
         self
     }
 
+    /// Set the run control for controlling a parallel run.
+    ///
+    /// Pause then goes through the run instead of the pause controller.
+    pub fn with_run_control(mut self, run_control: RunControl) -> Self {
+        self.run_control = Some(run_control);
+        self
+    }
+
     /// Check if the listener is currently running.
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::Relaxed)
@@ -184,12 +328,20 @@ impl KeyboardListener {
         let bindings = self.bindings;
         let running = Arc::clone(&self.running);
         let pause_controller = self.pause_controller.clone();
+        let run_control = self.run_control.clone();
 
         running.store(true, Ordering::Relaxed);
 
         let handle = thread::spawn(move || {
             // Try to enable raw mode for direct key input
             let raw_mode_enabled = terminal::enable_raw_mode().is_ok();
+            if raw_mode_enabled {
+                keep_output_processing();
+            }
+            // Story cancel prompt input, while the prompt is open
+            let mut cancel_input: Option<String> = None;
+            // Index of the selected story in the run control's stories
+            let mut selected: Option<usize> = None;
 
             while running.load(Ordering::Relaxed) {
                 // Poll for events with timeout
@@ -199,6 +351,9 @@ impl KeyboardListener {
                             &state,
                             &bindings,
                             pause_controller.as_ref(),
+                            run_control.as_ref(),
+                            &mut cancel_input,
+                            &mut selected,
                             key_event,
                         );
                     }
@@ -213,7 +368,7 @@ impl KeyboardListener {
 
         ListenerHandle {
             running: Arc::clone(&self.running),
-            _handle: handle,
+            handle: Some(handle),
         }
     }
 
@@ -222,11 +377,37 @@ impl KeyboardListener {
         state: &ToggleState,
         bindings: &KeyBindings,
         pause_controller: Option<&PauseController>,
+        run_control: Option<&RunControl>,
+        cancel_input: &mut Option<String>,
+        selected: &mut Option<usize>,
         event: KeyEvent,
     ) {
         // Check for Ctrl+C (immediate interrupt)
         if event.modifiers.contains(KeyModifiers::CONTROL) && event.code == KeyCode::Char('c') {
             state.request_immediate_interrupt();
+            if let Some(control) = run_control {
+                control.interrupt();
+            }
+            return;
+        }
+
+        // While the cancel prompt is open, keys edit the prompt input
+        if let (Some(control), Some(input)) = (run_control, cancel_input.as_mut()) {
+            match event.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Esc => *cancel_input = None,
+                KeyCode::Enter => {
+                    let input = cancel_input.take().unwrap_or_default();
+                    match control.request(&input) {
+                        Some(story_id) => println!("\r\nCancelling {}...", story_id),
+                        None => println!("\r\nNo story matches '{}'", input.trim()),
+                    }
+                }
+                _ => {}
+            }
             return;
         }
 
@@ -248,13 +429,56 @@ impl KeyboardListener {
                 code if code == bindings.quit => {
                     state.request_quit();
                 }
+                code if code == bindings.cancel_story && run_control.is_some() => {
+                    *cancel_input = Some(String::new());
+                    print!("\r\nCancel story (number or ID, Enter to confirm, Esc to abort): ");
+                    let _ = std::io::stdout().flush();
+                }
+                code if code == bindings.select_previous || code == bindings.select_next => {
+                    if let Some(control) = run_control {
+                        let offset = if code == bindings.select_next { 1 } else { -1 };
+                        *selected = control.select(*selected, offset);
+                    }
+                }
+                code if code == bindings.skip_selected
+                    || code == bindings.toggle_verbose
+                    || code == bindings.toggle_details =>
+                {
+                    if let Some(control) = run_control {
+                        match selected.and_then(|index| control.story(index)) {
+                            Some(story_id) if code == bindings.skip_selected => {
+                                if control.cancel(story_id) {
+                                    println!("\r\nSkipping {}...", story_id);
+                                }
+                            }
+                            Some(story_id) if code == bindings.toggle_verbose => {
+                                control.toggle_verbose(story_id)
+                            }
+                            Some(story_id) => control.toggle_details(story_id),
+                            None => println!("\r\nSelect a story with the arrow keys first"),
+                        }
+                    }
+                }
+                code if code == bindings.concurrency_up || code == bindings.concurrency_down => {
+                    if let Some(control) = run_control {
+                        let delta = if code == bindings.concurrency_up {
+                            1
+                        } else {
+                            -1
+                        };
+                        control.change_concurrency(delta);
+                    }
+                }
                 code if code == bindings.pause => {
-                    if let Some(controller) = pause_controller {
-                        // Only print message if pause was successfully requested
-                        // (i.e., not already paused or pause already requested)
-                        if controller.request_pause() {
-                            // Print message on a new line to avoid corrupting current output
-                            println!("\r\nPausing after current iteration...");
+                    if let Some(control) = run_control {
+                        control.toggle_pause();
+                    } else if let Some(controller) = pause_controller {
+                        // Print message on a new line to avoid corrupting current output
+                        match controller.toggle() {
+                            PauseState::PauseRequested => {
+                                println!("\r\nPausing after current iteration...")
+                            }
+                            _ => println!("\r\nResuming..."),
                         }
                     }
                 }
@@ -264,10 +488,23 @@ impl KeyboardListener {
     }
 }
 
+/// Turn newline translation back on after entering raw mode, so output printed by
+/// other threads while the listener runs still starts at column 0.
+#[cfg(unix)]
+fn keep_output_processing() {
+    let _ = std::process::Command::new("stty")
+        .args(["opost", "onlcr"])
+        .stdin(std::process::Stdio::inherit())
+        .status();
+}
+
+#[cfg(not(unix))]
+fn keep_output_processing() {}
+
 /// Handle to a running keyboard listener.
 pub struct ListenerHandle {
     running: Arc<AtomicBool>,
-    _handle: thread::JoinHandle<()>,
+    handle: Option<thread::JoinHandle<()>>,
 }
 
 impl ListenerHandle {
@@ -280,6 +517,10 @@ impl ListenerHandle {
 impl Drop for ListenerHandle {
     fn drop(&mut self) {
         self.stop();
+        // Wait for the listener to restore the terminal from raw mode
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
     }
 }
 
@@ -378,6 +619,135 @@ mod tests {
         assert!(state2.should_stop());
     }
 
+    #[test]
+    fn test_run_control_resolves_position_or_id() {
+        let (sender, _receiver) = mpsc::channel(4);
+        let control = RunControl::new(vec!["US-001".to_string(), "US-002".to_string()], sender);
+        assert_eq!(control.resolve("2"), Some("US-002"));
+        assert_eq!(control.resolve(" us-001 "), Some("US-001"));
+        assert_eq!(control.resolve("0"), None);
+        assert_eq!(control.resolve("3"), None);
+        assert_eq!(control.resolve("US-404"), None);
+    }
+
+    #[test]
+    fn test_cancel_prompt_sends_cancel_request() {
+        let (sender, mut receiver) = mpsc::channel(4);
+        let control = RunControl::new(vec!["US-001".to_string(), "US-002".to_string()], sender);
+        let state = ToggleState::default();
+        let bindings = KeyBindings::default();
+        let mut input = None;
+        let press = |code, input: &mut Option<String>| {
+            KeyboardListener::handle_key_event(
+                &state,
+                &bindings,
+                None,
+                Some(&control),
+                input,
+                &mut None,
+                KeyEvent::new(code, KeyModifiers::NONE),
+            )
+        };
+
+        press(KeyCode::Char('x'), &mut input);
+        assert_eq!(input.as_deref(), Some(""));
+        // Keys that are normally bindings go to the prompt while it is open
+        press(KeyCode::Char('q'), &mut input);
+        assert!(!state.is_quit_requested());
+        press(KeyCode::Backspace, &mut input);
+        press(KeyCode::Char('2'), &mut input);
+        press(KeyCode::Enter, &mut input);
+        assert!(input.is_none());
+
+        match receiver.try_recv() {
+            Ok(ParallelUIEvent::CancelStoryRequested { story_id }) => {
+                assert_eq!(story_id, "US-002")
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_run_control_keys_send_requests() {
+        let (sender, mut receiver) = mpsc::channel(16);
+        let control = RunControl::new(vec!["US-001".to_string(), "US-002".to_string()], sender);
+        let state = ToggleState::default();
+        let bindings = KeyBindings::default();
+        let mut selected = None;
+        let mut press = |code| {
+            KeyboardListener::handle_key_event(
+                &state,
+                &bindings,
+                None,
+                Some(&control),
+                &mut None,
+                &mut selected,
+                KeyEvent::new(code, KeyModifiers::NONE),
+            )
+        };
+
+        // Without a selection there is nothing to skip
+        press(KeyCode::Char('k'));
+        assert!(receiver.try_recv().is_err());
+
+        // Selection wraps around the story list
+        press(KeyCode::Up);
+        press(KeyCode::Down);
+        press(KeyCode::Char('v'));
+        press(KeyCode::Enter);
+        press(KeyCode::Char('k'));
+        press(KeyCode::Char('+'));
+        press(KeyCode::Char('-'));
+        press(KeyCode::Char('p'));
+
+        let events: Vec<_> = std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+        assert_eq!(
+            events,
+            vec![
+                ParallelUIEvent::StorySelected {
+                    story_id: "US-002".to_string()
+                },
+                ParallelUIEvent::StorySelected {
+                    story_id: "US-001".to_string()
+                },
+                ParallelUIEvent::VerboseToggleRequested {
+                    story_id: "US-001".to_string()
+                },
+                ParallelUIEvent::StoryDetailsToggled {
+                    story_id: "US-001".to_string()
+                },
+                ParallelUIEvent::CancelStoryRequested {
+                    story_id: "US-001".to_string()
+                },
+                ParallelUIEvent::ConcurrencyChangeRequested { delta: 1 },
+                ParallelUIEvent::ConcurrencyChangeRequested { delta: -1 },
+                ParallelUIEvent::TogglePauseRequested,
+            ]
+        );
+        assert_eq!(selected, Some(0));
+    }
+
+    #[test]
+    fn test_ctrl_c_interrupts_parallel_run() {
+        let (sender, mut receiver) = mpsc::channel(4);
+        let control = RunControl::new(Vec::new(), sender);
+        let state = ToggleState::default();
+        KeyboardListener::handle_key_event(
+            &state,
+            &KeyBindings::default(),
+            None,
+            Some(&control),
+            &mut None,
+            &mut None,
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+        );
+        assert!(state.is_immediate_interrupt());
+        assert_eq!(
+            receiver.try_recv().ok(),
+            Some(ParallelUIEvent::ImmediateInterrupt)
+        );
+    }
+
     #[test]
     fn test_render_toggle_hint() {
         let state = ToggleState::new(true, false);
@@ -7,12 +7,17 @@
 
 mod collapsible;
 mod colors;
+#[cfg(feature = "dashboard")]
+pub mod dashboard;
 mod display;
+pub mod events;
 mod ghostty;
+pub mod github_actions;
 mod help;
 mod image_to_ansi;
 mod interrupt;
 mod iteration_view;
+pub mod jsonl;
 mod keyboard;
 mod kitty_graphics;
 mod mascot;
@@ -20,9 +25,12 @@ pub mod parallel_display;
 pub mod parallel_events;
 pub mod parallel_status;
 mod quality_gates;
+pub mod run_log;
+pub mod run_report;
 mod spinner;
 mod story_view;
 mod summary;
+mod symbols;
 pub mod tui;
 mod tui_runner;
 
@@ -31,6 +39,7 @@ pub use collapsible::{
 };
 pub use colors::{
     active_text, ansi, blinking_text, completed_text, muted_text, primary_text, StyledText, Theme,
+    ThemeName,
 };
 pub use display::{
     new_shared_activity_state, DisplayCallback, DisplayOptions, LastActivityInfo, RalphDisplay,
@@ -54,7 +63,7 @@ pub use iteration_view::{
 };
 pub use keyboard::{
     render_compact_hint, render_toggle_hint, KeyBindings, KeyboardListener, ListenerHandle,
-    ToggleEvent, ToggleState,
+    RunControl, ToggleEvent, ToggleState,
 };
 pub use kitty_graphics::{display_mascot, mascot_inline_string, ImagePlacement, KittyGraphics};
 pub use mascot::{random_image_mascot, AnimationConfig, Mascot, MascotRenderer, PeekAnimation};
@@ -65,6 +74,7 @@ pub use spinner::{
 };
 pub use story_view::{StoryInfo, StoryView, StoryViewState};
 pub use summary::{ExecutionSummary, GateStatistics, StoryResult, SummaryRenderer};
+pub use symbols::{SymbolSet, Symbols};
 pub use tui_runner::TuiRunnerDisplay;
 
 // Parallel execution UI
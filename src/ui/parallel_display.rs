@@ -6,17 +6,36 @@
 
 #![allow(dead_code)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
 
 use crate::ui::colors::Theme;
 use crate::ui::display::DisplayOptions;
-use crate::ui::parallel_events::{StoryDisplayInfo, StoryStatus};
-use crate::ui::spinner::spinner_chars;
+use crate::ui::parallel_events::{ParallelUIEvent, StoryDisplayInfo, StoryStatus};
+use crate::ui::symbols::Symbols;
+
+/// Agent output lines shown beneath a running story.
+const OUTPUT_PANEL_LINES: usize = 1;
+
+/// Agent output lines shown beneath a running story when streaming output
+/// is enabled or the story's output is verbose.
+const STREAMING_PANEL_LINES: usize = 5;
+
+/// Inner width of the header and completion boxes, in columns.
+const BANNER_WIDTH: usize = 60;
+
+/// Widest agent output line shown, in characters.
+const OUTPUT_LINE_WIDTH: usize = 100;
+
+/// Agent output lines shown in a story's detail pane.
+const DETAIL_OUTPUT_LINES: usize = 8;
+
+/// Steps of the overall progress bar.
+const OVERALL_BAR_LENGTH: u64 = 1000;
 
 /// Progress state for a single story in parallel execution.
 #[derive(Debug)]
@@ -33,6 +52,56 @@ struct StoryProgressState {
     max_iterations: u32,
 }
 
+/// Recent agent output of a running story, drawn beneath its progress bar.
+#[derive(Debug)]
+struct OutputPanel {
+    bar: ProgressBar,
+    lines: VecDeque<String>,
+}
+
+/// What the detail pane shows about a story, kept for every story so the
+/// pane can be opened at any time.
+#[derive(Debug, Default)]
+struct StoryDetails {
+    title: String,
+    status: StoryStatus,
+    /// Current and maximum iteration, once known
+    iteration: Option<(u32, Option<u32>)>,
+    /// When the story started running
+    started: Option<Instant>,
+    /// When the current iteration started
+    iteration_started: Option<Instant>,
+    /// How long the story ran, once it finished
+    ran_for: Option<Duration>,
+    /// Gate name, whether it passed and its message, for the current iteration
+    gates: Vec<(String, bool, Option<String>)>,
+    /// Iteration the gates ran in
+    gates_iteration: Option<u32>,
+    /// Latest agent output lines
+    output: VecDeque<String>,
+    /// Why the story failed, was skipped or blocked
+    reason: Option<String>,
+}
+
+impl StoryDetails {
+    /// Record that the story stopped running with `status`.
+    fn finish(&mut self, status: StoryStatus, reason: Option<String>) {
+        self.status = status;
+        self.reason = reason;
+        if let Some(started) = self.started.filter(|_| self.ran_for.is_none()) {
+            self.ran_for = Some(started.elapsed());
+        }
+    }
+}
+
+/// How long a story's iterations take and how many it needs, estimated from
+/// previous runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct IterationEstimate {
+    duration: Duration,
+    iterations: u32,
+}
+
 /// Display controller for parallel story execution.
 ///
 /// Manages multiple concurrent progress indicators using indicatif's
@@ -44,6 +113,18 @@ pub struct ParallelRunnerDisplay {
     multi_progress: Arc<MultiProgress>,
     /// Per-story progress bars indexed by story ID
     story_progress: HashMap<String, ProgressBar>,
+    /// Agent output panels of running stories indexed by story ID
+    output_panels: HashMap<String, OutputPanel>,
+    /// Stories whose agent output is shown as when streaming
+    verbose_stories: HashSet<String>,
+    /// Detail pane state of every story indexed by story ID
+    details: HashMap<String, StoryDetails>,
+    /// Story whose detail pane is open, and the pane
+    detail_pane: Option<(String, ProgressBar)>,
+    /// Iteration estimates from previous runs indexed by story ID
+    estimates: HashMap<String, IterationEstimate>,
+    /// Overall progress bar, shown once stories have estimates
+    overall: Option<ProgressBar>,
     /// Color theme for consistent styling
     theme: Theme,
     /// Display options (colors, verbosity, etc.)
@@ -67,13 +148,19 @@ impl Default for ParallelRunnerDisplay {
 impl ParallelRunnerDisplay {
     /// Create a new ParallelRunnerDisplay with default settings.
     pub fn new() -> Self {
-        let theme = Theme::default();
         let display_options = DisplayOptions::default();
+        let theme = display_options.theme.theme();
         let colors_enabled = display_options.should_enable_colors();
 
         Self {
             multi_progress: Arc::new(MultiProgress::new()),
             story_progress: HashMap::new(),
+            output_panels: HashMap::new(),
+            verbose_stories: HashSet::new(),
+            details: HashMap::new(),
+            detail_pane: None,
+            estimates: HashMap::new(),
+            overall: None,
             theme,
             display_options,
             colors_enabled,
@@ -91,6 +178,12 @@ impl ParallelRunnerDisplay {
         Self {
             multi_progress: Arc::new(MultiProgress::new()),
             story_progress: HashMap::new(),
+            output_panels: HashMap::new(),
+            verbose_stories: HashSet::new(),
+            details: HashMap::new(),
+            detail_pane: None,
+            estimates: HashMap::new(),
+            overall: None,
             theme,
             display_options,
             colors_enabled,
@@ -102,12 +195,18 @@ impl ParallelRunnerDisplay {
 
     /// Create a ParallelRunnerDisplay with custom display options.
     pub fn with_display_options(options: DisplayOptions) -> Self {
-        let theme = Theme::default();
+        let theme = options.theme.theme();
         let colors_enabled = options.should_enable_colors();
 
         Self {
             multi_progress: Arc::new(MultiProgress::new()),
             story_progress: HashMap::new(),
+            output_panels: HashMap::new(),
+            verbose_stories: HashSet::new(),
+            details: HashMap::new(),
+            detail_pane: None,
+            estimates: HashMap::new(),
+            overall: None,
             theme,
             display_options: options,
             colors_enabled,
@@ -124,6 +223,12 @@ impl ParallelRunnerDisplay {
         Self {
             multi_progress: Arc::new(MultiProgress::new()),
             story_progress: HashMap::new(),
+            output_panels: HashMap::new(),
+            verbose_stories: HashSet::new(),
+            details: HashMap::new(),
+            detail_pane: None,
+            estimates: HashMap::new(),
+            overall: None,
             theme,
             display_options: options,
             colors_enabled,
@@ -165,6 +270,21 @@ impl ParallelRunnerDisplay {
         self.queue_policy = policy.to_string();
     }
 
+    /// Estimate a story's progress and time left from previous runs: each of
+    /// the `iterations` it is expected to need takes about `iteration`.
+    ///
+    /// Stories with an estimate show an ETA, and an overall progress bar
+    /// shows the run's.
+    pub fn set_story_estimate(&mut self, story_id: &str, iteration: Duration, iterations: u32) {
+        self.estimates.insert(
+            story_id.to_string(),
+            IterationEstimate {
+                duration: iteration,
+                iterations: iterations.max(1),
+            },
+        );
+    }
+
     /// Display a one-line queue status update.
     pub fn display_queue_status(&self, queued: usize, capacity: usize, policy: &str) {
         if self.display_options.quiet {
@@ -184,6 +304,16 @@ impl ParallelRunnerDisplay {
         self.colors_enabled
     }
 
+    /// Get the symbols the display draws with.
+    pub fn symbols(&self) -> &'static Symbols {
+        self.display_options.symbols.symbols()
+    }
+
+    /// Whether emoji are shown; only with colors and Unicode symbols.
+    fn emoji_enabled(&self) -> bool {
+        self.colors_enabled && self.symbols().emoji
+    }
+
     /// Get the underlying MultiProgress instance.
     pub fn multi_progress(&self) -> &MultiProgress {
         &self.multi_progress
@@ -210,25 +340,12 @@ impl ParallelRunnerDisplay {
         };
 
         println!();
-        if self.colors_enabled {
-            let header_rgb = self.theme.active;
-            println!(
-                "\x1b[38;2;{};{};{}m╔════════════════════════════════════════════════════════════╗\x1b[0m",
-                header_rgb.0, header_rgb.1, header_rgb.2
-            );
-            println!(
-                "\x1b[38;2;{};{};{}m║               🥋 RALPH PARALLEL MODE                        ║\x1b[0m",
-                header_rgb.0, header_rgb.1, header_rgb.2
-            );
-            println!(
-                "\x1b[38;2;{};{};{}m╚════════════════════════════════════════════════════════════╝\x1b[0m",
-                header_rgb.0, header_rgb.1, header_rgb.2
-            );
+        let title = if self.emoji_enabled() {
+            "🥋 RALPH PARALLEL MODE"
         } else {
-            println!("╔════════════════════════════════════════════════════════════╗");
-            println!("║               RALPH PARALLEL MODE                          ║");
-            println!("╚════════════════════════════════════════════════════════════╝");
-        }
+            "RALPH PARALLEL MODE"
+        };
+        self.print_banner(title, true, self.theme.active);
         println!();
         println!(
             "  Workers: {}  |  Stories: {}  |  Queue: {} ({})",
@@ -246,50 +363,20 @@ impl ParallelRunnerDisplay {
         }
 
         println!();
-        if self.colors_enabled {
-            let success_rgb = self.theme.success;
-            if completed == total {
-                println!(
-                    "\x1b[38;2;{};{};{}m╔═══════════════════════════════════════════════════════════╗\x1b[0m",
-                    success_rgb.0, success_rgb.1, success_rgb.2
-                );
-                println!(
-                    "\x1b[38;2;{};{};{}m║  🎉 ALL {} STORIES COMPLETE! 🎉                           ║\x1b[0m",
-                    success_rgb.0, success_rgb.1, success_rgb.2, total
-                );
-                println!(
-                    "\x1b[38;2;{};{};{}m╚═══════════════════════════════════════════════════════════╝\x1b[0m",
-                    success_rgb.0, success_rgb.1, success_rgb.2
-                );
+        if completed == total {
+            let text = if self.emoji_enabled() {
+                format!("🎉 ALL {} STORIES COMPLETE! 🎉", total)
             } else {
-                let warning_rgb = self.theme.warning;
-                println!(
-                    "\x1b[38;2;{};{};{}m╔═══════════════════════════════════════════════════════════╗\x1b[0m",
-                    warning_rgb.0, warning_rgb.1, warning_rgb.2
-                );
-                println!(
-                    "\x1b[38;2;{};{};{}m║  ⚠️  {}/{} STORIES COMPLETE                                ║\x1b[0m",
-                    warning_rgb.0, warning_rgb.1, warning_rgb.2, completed, total
-                );
-                println!(
-                    "\x1b[38;2;{};{};{}m╚═══════════════════════════════════════════════════════════╝\x1b[0m",
-                    warning_rgb.0, warning_rgb.1, warning_rgb.2
-                );
-            }
-        } else if completed == total {
-            println!("╔═══════════════════════════════════════════════════════════╗");
-            println!(
-                "║  ALL {} STORIES COMPLETE!                                 ║",
-                total
-            );
-            println!("╚═══════════════════════════════════════════════════════════╝");
+                format!("ALL {} STORIES COMPLETE!", total)
+            };
+            self.print_banner(&text, false, self.theme.success);
         } else {
-            println!("╔═══════════════════════════════════════════════════════════╗");
-            println!(
-                "║  {}/{} STORIES COMPLETE                                   ║",
-                completed, total
-            );
-            println!("╚═══════════════════════════════════════════════════════════╝");
+            let text = if self.emoji_enabled() {
+                format!("⚠️  {}/{} STORIES COMPLETE", completed, total)
+            } else {
+                format!("{}/{} STORIES COMPLETE", completed, total)
+            };
+            self.print_banner(&text, false, self.theme.warning);
         }
 
         if iterations > 0 {
@@ -299,6 +386,45 @@ impl ParallelRunnerDisplay {
         println!();
     }
 
+    /// Print `text` in a box [`BANNER_WIDTH`] columns wide, centered or
+    /// indented, in `color` when colors are enabled.
+    fn print_banner(&self, text: &str, centered: bool, color: owo_colors::Rgb) {
+        let symbols = self.symbols();
+        let [top_left, top_right, bottom_left, bottom_right] = symbols.corners;
+        let [horizontal, vertical] = symbols.edges;
+        let padding = BANNER_WIDTH.saturating_sub(console::measure_text_width(text));
+        let indent = if centered { padding / 2 } else { 2 };
+        let lines = [
+            format!(
+                "{}{}{}",
+                top_left,
+                horizontal.repeat(BANNER_WIDTH),
+                top_right
+            ),
+            format!(
+                "{}{}{}{}{}",
+                vertical,
+                " ".repeat(indent),
+                text,
+                " ".repeat(padding.saturating_sub(indent)),
+                vertical
+            ),
+            format!(
+                "{}{}{}",
+                bottom_left,
+                horizontal.repeat(BANNER_WIDTH),
+                bottom_right
+            ),
+        ];
+        for line in lines {
+            if self.colors_enabled {
+                println!("{}", line.color(color));
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+
     /// Initialize progress bars for all stories that will be executed.
     ///
     /// This sets up a progress bar for each story in the execution queue,
@@ -318,6 +444,13 @@ impl ParallelRunnerDisplay {
             let pb = self.create_story_progress_bar(&story.id, &story.title);
             let pb = self.multi_progress.add(pb);
             self.story_progress.insert(story.id.clone(), pb);
+            self.details.insert(
+                story.id.clone(),
+                StoryDetails {
+                    title: story.title.clone(),
+                    ..Default::default()
+                },
+            );
         }
     }
 
@@ -338,8 +471,6 @@ impl ParallelRunnerDisplay {
 
     /// Create a progress style for pending stories.
     fn create_pending_style(&self) -> ProgressStyle {
-        let spinner_chars = spinner_chars::BRAILLE.join("");
-
         // Fall back to simple output when colors are not enabled
         let template = if self.colors_enabled {
             let muted_rgb = self.theme.muted;
@@ -353,13 +484,11 @@ impl ParallelRunnerDisplay {
 
         ProgressStyle::with_template(&template)
             .unwrap_or_else(|_| ProgressStyle::default_spinner())
-            .tick_strings(&[&spinner_chars, StoryStatus::Pending.icon()])
+            .tick_strings(&self.tick_strings(StoryStatus::Pending))
     }
 
     /// Create a progress style for in-progress stories.
     fn create_in_progress_style(&self) -> ProgressStyle {
-        let spinner_chars = spinner_chars::BRAILLE.join("");
-
         // Fall back to simple output when colors are not enabled
         let template = if self.colors_enabled {
             let in_progress_rgb = self.theme.in_progress;
@@ -373,7 +502,16 @@ impl ParallelRunnerDisplay {
 
         ProgressStyle::with_template(&template)
             .unwrap_or_else(|_| ProgressStyle::default_spinner())
-            .tick_strings(&[&spinner_chars, StoryStatus::InProgress.icon()])
+            .tick_strings(&self.tick_strings(StoryStatus::InProgress))
+    }
+
+    /// Spinner frames, ending with the icon of `status` shown once the
+    /// spinner stops.
+    fn tick_strings(&self, status: StoryStatus) -> Vec<&'static str> {
+        let symbols = self.symbols();
+        let mut frames = symbols.spinner.to_vec();
+        frames.push(symbols.status_icon(status));
+        frames
     }
 
     /// Format the message for a story progress bar.
@@ -384,7 +522,7 @@ impl ParallelRunnerDisplay {
         status: StoryStatus,
         iteration_info: Option<(u32, u32)>,
     ) -> String {
-        let status_icon = status.icon();
+        let status_icon = self.symbols().status_icon(status);
         let status_color = self.get_status_color(status);
 
         let styled_icon = if self.colors_enabled {
@@ -409,10 +547,16 @@ impl ParallelRunnerDisplay {
 
         let mut message = format!("{} {} - {}", styled_icon, styled_id, display_title);
 
-        // Add iteration info with visual progress bar if available
+        // Add iteration info with visual progress bar if available. With an
+        // estimate from previous runs the bar shows the estimated share of the
+        // story's time elapsed, followed by the time left.
         if let Some((current, max)) = iteration_info {
             let iter_text = format!("[{}/{}]", current, max);
-            let progress_bar = Self::format_progress_bar(current, max);
+            let estimate = self.story_estimate(story_id);
+            let progress_bar = match estimate {
+                Some((done, _)) => Self::format_fraction_bar(done),
+                None => Self::format_progress_bar(current, max),
+            };
             let styled_iter = if self.colors_enabled {
                 format!(
                     "{} {}",
@@ -423,6 +567,14 @@ impl ParallelRunnerDisplay {
                 format!("{} {}", iter_text, progress_bar)
             };
             message.push_str(&format!(" {}", styled_iter));
+            if let Some((_, left)) = estimate {
+                let eta = format!("ETA {}", format_eta(left));
+                if self.colors_enabled {
+                    message.push_str(&format!(" {}", eta.color(self.theme.muted)));
+                } else {
+                    message.push_str(&format!(" {}", eta));
+                }
+            }
         }
 
         message
@@ -437,16 +589,21 @@ impl ParallelRunnerDisplay {
     /// # Returns
     /// A string like "====--" representing progress
     fn format_progress_bar(current: u32, max: u32) -> String {
-        // Use a fixed width for the progress bar
-        const BAR_WIDTH: u32 = 6;
-
         if max == 0 {
-            return "-".repeat(BAR_WIDTH as usize);
+            return Self::format_fraction_bar(0.0);
         }
 
         // Calculate filled portion (current is 1-indexed, so current-1 iterations are complete)
         let completed = current.saturating_sub(1);
-        let filled = ((completed as f64 / max as f64) * BAR_WIDTH as f64).round() as u32;
+        Self::format_fraction_bar(completed as f64 / max as f64)
+    }
+
+    /// Format a visual progress bar string filled to `fraction` (0 to 1).
+    fn format_fraction_bar(fraction: f64) -> String {
+        // Use a fixed width for the progress bar
+        const BAR_WIDTH: u32 = 6;
+
+        let filled = (fraction.clamp(0.0, 1.0) * BAR_WIDTH as f64).round() as u32;
         let empty = BAR_WIDTH.saturating_sub(filled);
 
         format!(
@@ -465,6 +622,8 @@ impl ParallelRunnerDisplay {
             StoryStatus::Failed => self.theme.error,
             StoryStatus::Deferred => self.theme.warning,
             StoryStatus::SequentialRetry => self.theme.active,
+            StoryStatus::Skipped => self.theme.muted,
+            StoryStatus::Blocked => self.theme.error,
         }
     }
 
@@ -515,6 +674,134 @@ impl ParallelRunnerDisplay {
         }
     }
 
+    /// Share of a story's estimated time already spent and the time it has
+    /// left, if it has an estimate and has not finished.
+    ///
+    /// A running story is expected to take its estimated iterations, or up to
+    /// its current one once it needs more, ending its current iteration an
+    /// iteration's duration after that started.
+    fn story_estimate(&self, story_id: &str) -> Option<(f64, Duration)> {
+        let estimate = self.estimates.get(story_id)?;
+        let details = self.details.get(story_id)?;
+        match details.status {
+            StoryStatus::Pending | StoryStatus::Deferred => {
+                Some((0.0, estimate.duration * estimate.iterations))
+            }
+            StoryStatus::InProgress | StoryStatus::SequentialRetry => {
+                let (current, max) = details.iteration.unwrap_or((1, None));
+                let mut iterations = estimate.iterations.max(current);
+                if let Some(max) = max {
+                    iterations = iterations.min(max.max(current));
+                }
+                let in_iteration = details
+                    .iteration_started
+                    .map(|started| started.elapsed())
+                    .unwrap_or_default();
+                let left = estimate.duration.saturating_sub(in_iteration)
+                    + estimate.duration * (iterations - current);
+                let spent = details
+                    .started
+                    .map(|started| started.elapsed())
+                    .unwrap_or_default();
+                Some((fraction(spent, left), left))
+            }
+            _ => None,
+        }
+    }
+
+    /// Share of the run's estimated time already spent and the time it has
+    /// left, if stories have estimates.
+    ///
+    /// The stories left are assumed to share the workers evenly, so the run
+    /// takes at least as long as its longest story.
+    fn overall_estimate(&self) -> Option<(f64, Duration)> {
+        if self.estimates.is_empty() {
+            return None;
+        }
+        let mut spent = Duration::ZERO;
+        let mut left = Duration::ZERO;
+        let mut longest = Duration::ZERO;
+        let mut unfinished: u32 = 0;
+        for (story_id, details) in &self.details {
+            if !self.estimates.contains_key(story_id) {
+                continue;
+            }
+            match self.story_estimate(story_id) {
+                Some((_, story_left)) => {
+                    spent += details
+                        .started
+                        .map(|started| started.elapsed())
+                        .unwrap_or_default();
+                    left += story_left;
+                    longest = longest.max(story_left);
+                    unfinished += 1;
+                }
+                None => spent += details.ran_for.unwrap_or_default(),
+            }
+        }
+        let workers = self.max_workers.min(unfinished).max(1);
+        Some((fraction(spent, left), longest.max(left / workers)))
+    }
+
+    /// Redraw the ETAs of running stories and the overall progress bar.
+    ///
+    /// Called periodically so the estimates count down between events.
+    pub fn refresh_progress(&mut self) {
+        if self.display_options.quiet {
+            return;
+        }
+        let Some((done, left)) = self.overall_estimate() else {
+            return;
+        };
+        for (story_id, details) in &self.details {
+            if details.status != StoryStatus::InProgress {
+                continue;
+            }
+            let (Some(pb), Some((current, Some(max)))) =
+                (self.story_progress.get(story_id), details.iteration)
+            else {
+                continue;
+            };
+            pb.set_message(self.format_story_message(
+                story_id,
+                &details.title,
+                StoryStatus::InProgress,
+                Some((current, max)),
+            ));
+        }
+
+        let finished = self
+            .estimates
+            .keys()
+            .filter(|story_id| self.story_estimate(story_id).is_none())
+            .count();
+        let separator = self.symbols().separator;
+        let message = format!(
+            "{}/{} stories {} ETA {}",
+            finished,
+            self.estimates.len(),
+            separator,
+            format_eta(left)
+        );
+        let overall = self.overall.get_or_insert_with(|| {
+            let bar = self
+                .multi_progress
+                .insert(0, ProgressBar::new(OVERALL_BAR_LENGTH));
+            bar.set_style(
+                ProgressStyle::with_template(&format!(
+                    "  Overall [{{bar:30}}] {{percent:>3}}% {} {{msg}}",
+                    separator
+                ))
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("=>-"),
+            );
+            bar
+        });
+        overall.set_position((done * OVERALL_BAR_LENGTH as f64) as u64);
+        overall.set_message(message);
+        self.refresh_detail_pane();
+    }
+
     /// Mark a story as completed successfully.
     ///
     /// # Arguments
@@ -587,6 +874,74 @@ impl ParallelRunnerDisplay {
         }
     }
 
+    /// Mark a story as skipped after it was cancelled.
+    ///
+    /// # Arguments
+    /// * `story_id` - The story identifier
+    /// * `title` - The story title
+    /// * `reason` - Why the story was skipped
+    pub fn story_skipped(&self, story_id: &str, title: &str, reason: &str) {
+        if let Some(pb) = self.story_progress.get(story_id) {
+            let message = self.format_story_message(story_id, title, StoryStatus::Skipped, None);
+            let final_message = format!(
+                "{} {}",
+                message,
+                if self.colors_enabled {
+                    format!("{}", format!("({})", reason).color(self.theme.muted))
+                } else {
+                    format!("({})", reason)
+                }
+            );
+            pb.finish_with_message(final_message);
+        }
+    }
+
+    /// Mark a story as blocked by an unmet external prerequisite.
+    ///
+    /// # Arguments
+    /// * `story_id` - The story identifier
+    /// * `title` - The story title
+    /// * `reason` - Why the prerequisite is not met
+    pub fn story_prerequisite_unmet(&self, story_id: &str, title: &str, reason: &str) {
+        if let Some(pb) = self.story_progress.get(story_id) {
+            let message = self.format_story_message(story_id, title, StoryStatus::Blocked, None);
+            let reason = format!("(prerequisite not met: {})", reason);
+            let final_message = format!(
+                "{} {}",
+                message,
+                if self.colors_enabled {
+                    format!("{}", reason.color(self.theme.muted))
+                } else {
+                    reason
+                }
+            );
+            pb.finish_with_message(final_message);
+        }
+    }
+
+    /// Mark a story as blocked by a failed or skipped dependency.
+    ///
+    /// # Arguments
+    /// * `story_id` - The story identifier
+    /// * `title` - The story title
+    /// * `blocked_by` - ID of the failed or skipped story it depends on
+    pub fn story_blocked(&self, story_id: &str, title: &str, blocked_by: &str) {
+        if let Some(pb) = self.story_progress.get(story_id) {
+            let message = self.format_story_message(story_id, title, StoryStatus::Blocked, None);
+            let reason = format!("(blocked by {})", blocked_by);
+            let final_message = format!(
+                "{} {}",
+                message,
+                if self.colors_enabled {
+                    format!("{}", reason.color(self.theme.muted))
+                } else {
+                    reason
+                }
+            );
+            pb.set_message(final_message);
+        }
+    }
+
     /// Mark a story as deferred due to conflicts.
     ///
     /// # Arguments
@@ -653,6 +1008,341 @@ impl ParallelRunnerDisplay {
         self.story_progress.len()
     }
 
+    /// Show a line of a story's agent output beneath its progress bar.
+    ///
+    /// The panel keeps the latest line, or the last few when streaming output
+    /// is enabled. Lines for stories without a running progress bar are
+    /// ignored.
+    ///
+    /// # Arguments
+    /// * `story_id` - The story identifier
+    /// * `line` - The output line
+    pub fn story_output(&mut self, story_id: &str, line: &str) {
+        let line = sanitize_output_line(line);
+        if line.is_empty() {
+            return;
+        }
+        let Some(story_bar) = self.story_progress.get(story_id) else {
+            return;
+        };
+        if story_bar.is_finished() {
+            return;
+        }
+        let capacity = if self.display_options.should_show_streaming()
+            || self.verbose_stories.contains(story_id)
+        {
+            STREAMING_PANEL_LINES
+        } else {
+            OUTPUT_PANEL_LINES
+        };
+
+        let side = self.symbols().panel[1];
+        let multi_progress = &self.multi_progress;
+        let panel = self
+            .output_panels
+            .entry(story_id.to_string())
+            .or_insert_with(|| {
+                let bar = multi_progress.insert_after(story_bar, ProgressBar::new_spinner());
+                bar.set_style(
+                    ProgressStyle::with_template("{msg}")
+                        .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+                );
+                OutputPanel {
+                    bar,
+                    lines: VecDeque::new(),
+                }
+            });
+        panel.lines.push_back(line);
+        while panel.lines.len() > capacity {
+            panel.lines.pop_front();
+        }
+
+        let message = panel
+            .lines
+            .iter()
+            .map(|line| {
+                let line = format!("    {} {}", side, line);
+                if self.colors_enabled {
+                    format!("{}", line.color(self.theme.muted))
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        panel.bar.set_message(message);
+    }
+
+    /// Show more or fewer agent output lines beneath a story.
+    pub fn set_story_verbose(&mut self, story_id: &str, verbose: bool) {
+        if verbose {
+            self.verbose_stories.insert(story_id.to_string());
+        } else {
+            self.verbose_stories.remove(story_id);
+            if !self.display_options.should_show_streaming() {
+                if let Some(panel) = self.output_panels.get_mut(story_id) {
+                    while panel.lines.len() > OUTPUT_PANEL_LINES {
+                        panel.lines.pop_front();
+                    }
+                }
+            }
+        }
+        if self.display_options.quiet {
+            return;
+        }
+
+        let state = if verbose { "on" } else { "off" };
+        self.print_notice(&format!("Verbose output {} for {}", state, story_id));
+    }
+
+    /// Display the story the keyboard controls now act on.
+    pub fn story_selected(&self, story_id: &str, title: &str) {
+        if self.display_options.quiet {
+            return;
+        }
+
+        self.print_notice(&format!(
+            "Selected {} - {} (k: skip, v: verbose output, enter: details)",
+            story_id, title
+        ));
+    }
+
+    /// Record what a story's detail pane shows from a run event, redrawing the
+    /// pane if it is open on that story.
+    pub fn update_details(&mut self, event: &ParallelUIEvent) {
+        let Some(story_id) = event.story_id() else {
+            return;
+        };
+        let Some(details) = self.details.get_mut(story_id) else {
+            return;
+        };
+        match event {
+            ParallelUIEvent::StoryStarted { iteration, .. } => {
+                details.status = StoryStatus::InProgress;
+                details.started = Some(Instant::now());
+                details.iteration_started = details.started;
+                details.ran_for = None;
+                details.reason = None;
+                details.iteration = Some((*iteration, details.iteration.and_then(|(_, max)| max)));
+            }
+            ParallelUIEvent::IterationUpdate {
+                iteration,
+                max_iterations,
+                ..
+            } => {
+                if details.iteration.map(|(current, _)| current) != Some(*iteration) {
+                    details.iteration_started = Some(Instant::now());
+                }
+                details.iteration = Some((*iteration, Some(*max_iterations)));
+            }
+            ParallelUIEvent::AgentOutput { line, .. } => {
+                let line = sanitize_output_line(line);
+                if line.is_empty() {
+                    return;
+                }
+                details.output.push_back(line);
+                while details.output.len() > DETAIL_OUTPUT_LINES {
+                    details.output.pop_front();
+                }
+            }
+            ParallelUIEvent::GateUpdate {
+                gate_name,
+                passed,
+                message,
+                ..
+            } => {
+                // Gates of a new iteration replace those of the previous one
+                let iteration = details.iteration.map(|(current, _)| current);
+                if details.gates_iteration != iteration {
+                    details.gates.clear();
+                    details.gates_iteration = iteration;
+                }
+                match details.gates.iter_mut().find(|gate| gate.0 == *gate_name) {
+                    Some(gate) => {
+                        gate.1 = *passed;
+                        gate.2 = message.clone();
+                    }
+                    None => details
+                        .gates
+                        .push((gate_name.clone(), *passed, message.clone())),
+                }
+            }
+            ParallelUIEvent::StoryCompleted { .. } => details.finish(StoryStatus::Completed, None),
+            ParallelUIEvent::StoryFailed { error, .. } => {
+                details.finish(StoryStatus::Failed, Some(error.clone()))
+            }
+            ParallelUIEvent::StorySkipped { reason, .. } => {
+                details.finish(StoryStatus::Skipped, Some(reason.clone()))
+            }
+            ParallelUIEvent::StoryBlocked { blocked_by, .. } => details.finish(
+                StoryStatus::Blocked,
+                Some(format!("Blocked by {}", blocked_by)),
+            ),
+            ParallelUIEvent::PrerequisiteUnmet { reason, .. } => {
+                details.finish(StoryStatus::Blocked, Some(reason.clone()))
+            }
+            ParallelUIEvent::ConflictDeferred {
+                blocking_story_id, ..
+            } => {
+                details.status = StoryStatus::Deferred;
+                details.reason = Some(format!("Waiting for {}", blocking_story_id));
+            }
+            ParallelUIEvent::SequentialRetryStarted { reason, .. } => {
+                details.status = StoryStatus::SequentialRetry;
+                details.reason = Some(reason.clone());
+            }
+            _ => return,
+        }
+        if self
+            .detail_pane
+            .as_ref()
+            .is_some_and(|(open, _)| open == story_id)
+        {
+            self.refresh_detail_pane();
+        }
+    }
+
+    /// Open the detail pane of a story beneath it, closing any other open
+    /// pane, or close it if it is already open.
+    pub fn toggle_details(&mut self, story_id: &str) {
+        let already_open = self
+            .detail_pane
+            .as_ref()
+            .is_some_and(|(open, _)| open == story_id);
+        if let Some((_, pane)) = self.detail_pane.take() {
+            pane.finish_and_clear();
+            self.multi_progress.remove(&pane);
+        }
+        if already_open || self.display_options.quiet {
+            return;
+        }
+        let Some(story_bar) = self.story_progress.get(story_id) else {
+            return;
+        };
+        let anchor = self
+            .output_panels
+            .get(story_id)
+            .map_or(story_bar, |panel| &panel.bar);
+        let pane = self
+            .multi_progress
+            .insert_after(anchor, ProgressBar::new_spinner());
+        self.detail_pane = Some((story_id.to_string(), pane));
+        self.refresh_detail_pane();
+    }
+
+    /// Redraw the open detail pane from its story's details.
+    fn refresh_detail_pane(&self) {
+        let Some((ref story_id, ref pane)) = self.detail_pane else {
+            return;
+        };
+        let Some(details) = self.details.get(story_id) else {
+            return;
+        };
+        let muted = |line: String| {
+            if self.colors_enabled {
+                format!("{}", line.color(self.theme.muted))
+            } else {
+                line
+            }
+        };
+
+        let symbols = self.symbols();
+        let [top, side, bottom] = symbols.panel;
+        let separator = symbols.separator;
+        let header = format!(
+            "    {} {} {} - {} {} {}",
+            top,
+            symbols.status_icon(details.status),
+            story_id,
+            details.title,
+            separator,
+            details.status.label()
+        );
+        // A running story's elapsed time is kept current by the pane's tick
+        let running = details.ran_for.is_none() && details.started.is_some();
+        let template = if running {
+            pane.clone().with_elapsed(
+                details
+                    .started
+                    .map(|started| started.elapsed())
+                    .unwrap_or_default(),
+            );
+            pane.enable_steady_tick(Duration::from_secs(1));
+            format!("{{prefix}} {} {{elapsed_precise}}\n{{msg}}", separator)
+        } else {
+            pane.disable_steady_tick();
+            "{prefix}\n{msg}".to_string()
+        };
+        pane.set_style(
+            ProgressStyle::with_template(&template)
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        pane.set_prefix(match details.ran_for {
+            Some(ran_for) => format!("{} {} ran {}", header, separator, format_elapsed(ran_for)),
+            None => header,
+        });
+
+        let mut lines = Vec::new();
+        let mut iteration = match details.iteration {
+            Some((current, Some(max))) => format!("Iteration {}/{}", current, max),
+            Some((current, None)) => format!("Iteration {}", current),
+            None => "Not started".to_string(),
+        };
+        if let Some((_, left)) = self
+            .story_estimate(story_id)
+            .filter(|_| details.status == StoryStatus::InProgress)
+        {
+            iteration.push_str(&format!(" {} ETA {}", separator, format_eta(left)));
+        }
+        lines.push(iteration);
+        if let Some(ref reason) = details.reason {
+            lines.push(sanitize_output_line(reason));
+        }
+        if !details.gates.is_empty() {
+            let gates: Vec<String> = details
+                .gates
+                .iter()
+                .map(|(name, passed, message)| match message {
+                    Some(message) if !passed => format!("{} {} ({})", symbols.fail, name, message),
+                    _ if !passed => format!("{} {}", symbols.fail, name),
+                    _ => format!("{} {}", symbols.pass, name),
+                })
+                .collect();
+            let iteration = details
+                .gates_iteration
+                .map(|iteration| format!(" (iteration {})", iteration))
+                .unwrap_or_default();
+            lines.push(format!("Gates{}: {}", iteration, gates.join("  ")));
+        }
+        if !details.output.is_empty() {
+            lines.push("Recent output:".to_string());
+            lines.extend(details.output.iter().map(|line| format!("  {}", line)));
+        }
+
+        let mut message: Vec<String> = lines
+            .into_iter()
+            .map(|line| muted(format!("    {} {}", side, line)))
+            .collect();
+        message.push(muted(format!("    {}", bottom)));
+        pane.set_message(message.join("\n"));
+    }
+
+    /// Remove the agent output panel of a story that stopped running.
+    pub fn close_output_panel(&mut self, story_id: &str) {
+        if let Some(panel) = self.output_panels.remove(story_id) {
+            panel.bar.finish_and_clear();
+            self.multi_progress.remove(&panel.bar);
+        }
+    }
+
+    /// Output lines currently shown beneath a story.
+    fn output_lines(&self, story_id: &str) -> Vec<String> {
+        self.output_panels
+            .get(story_id)
+            .map(|panel| panel.lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Clear all progress bars.
     pub fn clear(&mut self) {
         // Finish and clear all progress bars
@@ -660,6 +1350,18 @@ impl ParallelRunnerDisplay {
             pb.finish_and_clear();
         }
         self.story_progress.clear();
+        for panel in self.output_panels.values() {
+            panel.bar.finish_and_clear();
+        }
+        self.output_panels.clear();
+        if let Some((_, pane)) = self.detail_pane.take() {
+            pane.finish_and_clear();
+        }
+        self.details.clear();
+        self.estimates.clear();
+        if let Some(overall) = self.overall.take() {
+            overall.finish_and_clear();
+        }
 
         // Clear the multi-progress
         let _ = self.multi_progress.clear();
@@ -670,6 +1372,12 @@ impl ParallelRunnerDisplay {
         for pb in self.story_progress.values() {
             pb.finish();
         }
+        if let Some((_, ref pane)) = self.detail_pane {
+            pane.finish();
+        }
+        if let Some(ref overall) = self.overall {
+            overall.finish();
+        }
     }
 
     /// Display circuit breaker status with color coding.
@@ -746,11 +1454,129 @@ impl ParallelRunnerDisplay {
         println!("Execution paused. Resume with: ralph --resume");
         println!();
     }
+
+    /// Display a notice that a pause was requested while stories still run.
+    pub fn display_pausing(&self, in_flight: usize) {
+        if self.display_options.quiet {
+            return;
+        }
+
+        let message = format!(
+            "Pausing: no new stories start; waiting for {} in-flight {} to finish. Press p to resume.",
+            in_flight,
+            if in_flight == 1 { "story" } else { "stories" }
+        );
+        if self.colors_enabled {
+            println!("{}", message.color(self.theme.warning));
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    /// Display a notice that the run is paused.
+    pub fn display_paused(&self) {
+        if self.display_options.quiet {
+            return;
+        }
+
+        let message =
+            "Paused: in-flight stories finished, checkpoint saved. Press p or send SIGUSR1 to resume.";
+        if self.colors_enabled {
+            println!("{}", message.color(self.theme.warning));
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    /// Display a notice that the run resumed.
+    pub fn display_resumed(&self) {
+        if self.display_options.quiet {
+            return;
+        }
+
+        if self.colors_enabled {
+            println!("{}", "Resumed".color(self.theme.active));
+        } else {
+            println!("Resumed");
+        }
+    }
+
+    /// Display an adaptive concurrency adjustment and update the worker count.
+    pub fn display_concurrency_adjusted(&mut self, previous: u32, current: u32, reason: &str) {
+        self.max_workers = current;
+        if self.display_options.quiet {
+            return;
+        }
+
+        self.print_notice(&format!(
+            "Concurrency: {} -> {} ({})",
+            previous, current, reason
+        ));
+    }
+
+    /// Print a muted one-line notice above the progress bars.
+    fn print_notice(&self, message: &str) {
+        if self.colors_enabled {
+            println!("{}", message.color(self.theme.muted));
+        } else {
+            println!("{}", message);
+        }
+    }
+}
+
+/// Share of the total that `spent` is of `spent + left`.
+fn fraction(spent: Duration, left: Duration) -> f64 {
+    let total = (spent + left).as_secs_f64();
+    if total > 0.0 {
+        spent.as_secs_f64() / total
+    } else {
+        0.0
+    }
+}
+
+/// A time left as shown by ETAs, e.g. `4m 05s`.
+fn format_eta(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// A duration as shown by the progress bars' elapsed time, e.g. `00:01:05`.
+fn format_elapsed(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// Agent output reduced to one printable line that fits the panel.
+fn sanitize_output_line(line: &str) -> String {
+    let stripped = console::strip_ansi_codes(line);
+    let cleaned: String = stripped
+        .trim()
+        .chars()
+        .map(|c| if c == '\t' { ' ' } else { c })
+        .filter(|c| !c.is_control())
+        .collect();
+    if cleaned.chars().count() > OUTPUT_LINE_WIDTH {
+        let truncated: String = cleaned.chars().take(OUTPUT_LINE_WIDTH - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        cleaned
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::colors::ThemeName;
+    use crate::ui::symbols::SymbolSet;
 
     #[test]
     fn test_new_default() {
@@ -824,6 +1650,195 @@ mod tests {
         assert_eq!(display.story_count(), 0);
     }
 
+    #[test]
+    fn test_story_output_panel() {
+        let options = DisplayOptions::default().with_color(false);
+        let mut display = ParallelRunnerDisplay::with_display_options(options);
+        display.init_stories(&[StoryDisplayInfo::new("US-001", "Test", 1)]);
+
+        // Output of unknown stories is ignored
+        display.story_output("US-999", "hello");
+        assert!(display.output_lines("US-999").is_empty());
+
+        display.story_started("US-001", "Test", 1, 5);
+        display.story_output("US-001", "\x1b[1mReading\x1b[0m src/lib.rs\t");
+        display.story_output("US-001", "   ");
+        assert_eq!(display.output_lines("US-001"), vec!["Reading src/lib.rs"]);
+
+        // Without streaming only the latest line is kept
+        display.story_output("US-001", &"x".repeat(200));
+        let lines = display.output_lines("US-001");
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].chars().count(), OUTPUT_LINE_WIDTH);
+
+        display.close_output_panel("US-001");
+        assert!(display.output_lines("US-001").is_empty());
+    }
+
+    #[test]
+    fn test_verbose_story_output_panel() {
+        let options = DisplayOptions::default().with_color(false).with_quiet(true);
+        let mut display = ParallelRunnerDisplay::with_display_options(options);
+        display.init_stories(&[StoryDisplayInfo::new("US-001", "Test", 1)]);
+        display.story_started("US-001", "Test", 1, 5);
+
+        display.set_story_verbose("US-001", true);
+        for i in 0..8 {
+            display.story_output("US-001", &format!("line {}", i));
+        }
+        assert_eq!(display.output_lines("US-001").len(), STREAMING_PANEL_LINES);
+
+        // Turning verbose output off keeps only the latest line
+        display.set_story_verbose("US-001", false);
+        assert_eq!(display.output_lines("US-001"), vec!["line 7"]);
+    }
+
+    #[test]
+    fn test_story_detail_pane() {
+        let options = DisplayOptions::default().with_color(false);
+        let mut display = ParallelRunnerDisplay::with_display_options(options);
+        display.init_stories(&[
+            StoryDisplayInfo::new("US-001", "Cart", 1),
+            StoryDisplayInfo::new("US-002", "Checkout", 2),
+        ]);
+        let pane = |display: &ParallelRunnerDisplay| {
+            display
+                .detail_pane
+                .as_ref()
+                .map(|(id, pane)| (id.clone(), pane.prefix(), pane.message()))
+        };
+
+        display.toggle_details("US-001");
+        let (id, prefix, message) = pane(&display).unwrap();
+        assert_eq!(id, "US-001");
+        assert_eq!(prefix, "    ┌ ○ US-001 - Cart · Pending");
+        assert_eq!(message, "    │ Not started\n    └");
+
+        let story = StoryDisplayInfo::new("US-001", "Cart", 1);
+        for event in [
+            ParallelUIEvent::StoryStarted {
+                story,
+                iteration: 1,
+                concurrent_count: 1,
+            },
+            ParallelUIEvent::GateUpdate {
+                story_id: "US-001".to_string(),
+                gate_name: "tests".to_string(),
+                passed: false,
+                message: Some("1 test failed".to_string()),
+            },
+            ParallelUIEvent::IterationUpdate {
+                story_id: "US-001".to_string(),
+                iteration: 2,
+                max_iterations: 5,
+                message: None,
+            },
+            ParallelUIEvent::AgentOutput {
+                story_id: "US-001".to_string(),
+                line: "Fixing the test".to_string(),
+                is_stderr: false,
+            },
+            ParallelUIEvent::GateUpdate {
+                story_id: "US-001".to_string(),
+                gate_name: "build".to_string(),
+                passed: true,
+                message: None,
+            },
+        ] {
+            display.update_details(&event);
+        }
+        let (_, prefix, message) = pane(&display).unwrap();
+        assert_eq!(prefix, "    ┌ ◉ US-001 - Cart · In Progress");
+        // Gates of the previous iteration are replaced
+        assert_eq!(
+            message,
+            [
+                "    │ Iteration 2/5",
+                "    │ Gates (iteration 2): ✓ build",
+                "    │ Recent output:",
+                "    │   Fixing the test",
+                "    └",
+            ]
+            .join("\n")
+        );
+
+        display.update_details(&ParallelUIEvent::StoryFailed {
+            story_id: "US-001".to_string(),
+            error: "Quality gates failed".to_string(),
+            iteration: 2,
+        });
+        let (_, prefix, message) = pane(&display).unwrap();
+        assert!(prefix.starts_with("    ┌ ✗ US-001 - Cart · Failed · ran 00:00:0"));
+        assert!(message.contains("    │ Quality gates failed\n"));
+
+        // Opening another story's pane closes this one; toggling closes it
+        display.toggle_details("US-002");
+        assert_eq!(pane(&display).unwrap().0, "US-002");
+        display.toggle_details("US-002");
+        assert!(pane(&display).is_none());
+    }
+
+    #[test]
+    fn test_story_estimates() {
+        let options = DisplayOptions::default().with_color(false);
+        let mut display = ParallelRunnerDisplay::with_display_options(options).with_max_workers(2);
+        display.init_stories(&[
+            StoryDisplayInfo::new("US-001", "Cart", 1),
+            StoryDisplayInfo::new("US-002", "Checkout", 2),
+            StoryDisplayInfo::new("US-003", "Search", 3),
+        ]);
+        assert_eq!(display.overall_estimate(), None);
+        display.set_story_estimate("US-001", Duration::from_secs(60), 2);
+        display.set_story_estimate("US-002", Duration::from_secs(60), 1);
+
+        // Pending stories are estimated in full; the longest story bounds the run
+        assert_eq!(
+            display.story_estimate("US-001"),
+            Some((0.0, Duration::from_secs(120)))
+        );
+        assert_eq!(display.story_estimate("US-003"), None);
+        assert_eq!(
+            display.overall_estimate(),
+            Some((0.0, Duration::from_secs(120)))
+        );
+
+        // A story needing more iterations than estimated has only its
+        // current one left
+        let story = StoryDisplayInfo::new("US-001", "Cart", 1);
+        for event in [
+            ParallelUIEvent::StoryStarted {
+                story,
+                iteration: 1,
+                concurrent_count: 1,
+            },
+            ParallelUIEvent::IterationUpdate {
+                story_id: "US-001".to_string(),
+                iteration: 3,
+                max_iterations: 5,
+                message: None,
+            },
+            ParallelUIEvent::StorySkipped {
+                story_id: "US-002".to_string(),
+                reason: "Skipped by user".to_string(),
+            },
+        ] {
+            display.update_details(&event);
+        }
+        let (_, left) = display.story_estimate("US-001").unwrap();
+        assert!(left <= Duration::from_secs(60) && left > Duration::from_secs(55));
+        assert_eq!(display.story_estimate("US-002"), None);
+
+        display.refresh_progress();
+        let overall = display.overall.as_ref().unwrap();
+        assert!(overall.message().starts_with("1/2 stories · ETA "));
+        let story_message = display.story_progress["US-001"].message();
+        assert!(story_message.starts_with("◉ US-001 - Cart [3/5] ------ ETA "));
+
+        assert_eq!(format_eta(Duration::from_secs(42)), "42s");
+        assert_eq!(format_eta(Duration::from_secs(245)), "4m 05s");
+        assert_eq!(format_eta(Duration::from_secs(3900)), "1h 05m");
+    }
+
     #[test]
     fn test_format_story_message() {
         let display = ParallelRunnerDisplay::new();
@@ -853,6 +1868,32 @@ mod tests {
         assert!(!message.contains("truncated to fit"));
     }
 
+    #[test]
+    fn test_ascii_symbols_and_no_color_theme() {
+        let options = DisplayOptions::new()
+            .with_theme(ThemeName::NoColor)
+            .with_symbols(SymbolSet::Ascii);
+        let mut display = ParallelRunnerDisplay::with_display_options(options);
+        assert!(!display.colors_enabled());
+        display.init_stories(&[StoryDisplayInfo::new("US-001", "Cart", 1)]);
+
+        let message =
+            display.format_story_message("US-001", "Cart", StoryStatus::InProgress, Some((2, 5)));
+        assert_eq!(message, "* US-001 - Cart [2/5] =-----");
+
+        display.update_details(&ParallelUIEvent::GateUpdate {
+            story_id: "US-001".to_string(),
+            gate_name: "tests".to_string(),
+            passed: false,
+            message: None,
+        });
+        display.toggle_details("US-001");
+        let (_, pane) = display.detail_pane.as_ref().unwrap();
+        assert_eq!(pane.prefix(), "    + o US-001 - Cart | Pending");
+        assert!(pane.message().is_ascii());
+        assert!(pane.message().contains("    | Gates: x tests\n"));
+    }
+
     #[test]
     fn test_multi_progress_arc() {
         let display = ParallelRunnerDisplay::new();
@@ -891,6 +1932,14 @@ mod tests {
             display.get_status_color(StoryStatus::SequentialRetry),
             display.theme.active
         );
+        assert_eq!(
+            display.get_status_color(StoryStatus::Skipped),
+            display.theme.muted
+        );
+        assert_eq!(
+            display.get_status_color(StoryStatus::Blocked),
+            display.theme.error
+        );
     }
 
     #[test]
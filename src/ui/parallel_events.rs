@@ -6,6 +6,16 @@
 
 use std::path::PathBuf;
 
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::quality::gates::GateResult;
+use crate::ui::display::DisplayCallback;
+
+/// Output events are only sent while the channel has at least this many free
+/// slots, so a chatty agent cannot crowd out story lifecycle events.
+const OUTPUT_EVENT_HEADROOM: usize = 16;
+
 /// Information about a story for display purposes in parallel execution.
 ///
 /// This is a lightweight struct containing only the information needed
@@ -32,9 +42,11 @@ impl StoryDisplayInfo {
 }
 
 /// Status of a story in the parallel execution pipeline.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StoryStatus {
     /// Story is waiting for dependencies or resources.
+    #[default]
     Pending,
     /// Story is currently executing.
     InProgress,
@@ -46,6 +58,10 @@ pub enum StoryStatus {
     Deferred,
     /// Story is retrying in sequential mode.
     SequentialRetry,
+    /// Story was cancelled by the user and skipped.
+    Skipped,
+    /// Story cannot run because a story it depends on failed or was skipped.
+    Blocked,
 }
 
 impl StoryStatus {
@@ -58,6 +74,8 @@ impl StoryStatus {
             Self::Failed => "✗",
             Self::Deferred => "⊘",
             Self::SequentialRetry => "↻",
+            Self::Skipped => "⊖",
+            Self::Blocked => "⊗",
         }
     }
 
@@ -70,6 +88,8 @@ impl StoryStatus {
             Self::Failed => "Failed",
             Self::Deferred => "Deferred",
             Self::SequentialRetry => "Retrying",
+            Self::Skipped => "Skipped",
+            Self::Blocked => "Blocked",
         }
     }
 }
@@ -78,6 +98,8 @@ impl StoryStatus {
 ///
 /// These events allow the UI to track the progress of multiple concurrent
 /// story executions without tight coupling to the scheduler implementation.
+/// They change with the UI; what is reported outside of ralph is the
+/// versioned [`RunEvent`](crate::ui::events::RunEvent) of each event.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ParallelUIEvent {
     /// A story has started execution.
@@ -102,6 +124,16 @@ pub enum ParallelUIEvent {
         message: Option<String>,
     },
 
+    /// A line of output from a story's agent, streamed while it runs.
+    AgentOutput {
+        /// Story identifier.
+        story_id: String,
+        /// The output line, without its trailing newline.
+        line: String,
+        /// Whether the line came from stderr.
+        is_stderr: bool,
+    },
+
     /// Quality gate status update for a story.
     GateUpdate {
         /// Story identifier.
@@ -134,6 +166,40 @@ pub enum ParallelUIEvent {
         iteration: u32,
     },
 
+    /// A story was cancelled and skipped without failing the run.
+    StorySkipped {
+        /// Story identifier.
+        story_id: String,
+        /// Why the story was skipped.
+        reason: String,
+    },
+
+    /// A story cannot run because a story it depends on failed or was skipped.
+    ///
+    /// Not terminal: the story runs if the failed story later passes.
+    StoryBlocked {
+        /// Story identifier.
+        story_id: String,
+        /// The failed or skipped story it depends on.
+        blocked_by: String,
+    },
+
+    /// A story cannot run because an external prerequisite is not met.
+    PrerequisiteUnmet {
+        /// Story identifier.
+        story_id: String,
+        /// Why the prerequisite is not met.
+        reason: String,
+    },
+
+    /// The user asked to cancel a single story.
+    ///
+    /// Unlike the other events this flows from the UI to the scheduler.
+    CancelStoryRequested {
+        /// Story identifier to cancel.
+        story_id: String,
+    },
+
     /// A story was deferred due to file conflicts with another story.
     ConflictDeferred {
         /// Story identifier that was deferred.
@@ -150,7 +216,7 @@ pub enum ParallelUIEvent {
         queued: usize,
         /// Maximum queue capacity.
         capacity: usize,
-        /// Queue policy label (block, reject, drop_oldest).
+        /// Queue policy label (block, reject, drop_oldest, evict_lowest_priority).
         policy: String,
     },
 
@@ -201,6 +267,73 @@ pub enum ParallelUIEvent {
         /// Threshold that was exceeded.
         threshold: u32,
     },
+
+    /// Dispatch is paused and in-flight stories have finished.
+    RunPaused,
+
+    /// Dispatch resumed after a pause.
+    RunResumed,
+
+    /// Adaptive concurrency changed the number of concurrent stories.
+    ConcurrencyAdjusted {
+        /// Concurrency before the adjustment.
+        previous: u32,
+        /// Concurrency after the adjustment.
+        current: u32,
+        /// Why the adjustment was made.
+        reason: String,
+    },
+
+    /// The user asked to pause dispatch, or to resume it when paused.
+    ///
+    /// Flows from the UI to the scheduler.
+    TogglePauseRequested,
+
+    /// A pause was requested; dispatch stops and in-flight stories finish first.
+    RunPausing {
+        /// Number of stories still running.
+        in_flight: usize,
+    },
+
+    /// The user asked to raise or lower the number of concurrent stories.
+    ///
+    /// Flows from the UI to the scheduler.
+    ConcurrencyChangeRequested {
+        /// Change to the concurrency, e.g. `1` or `-1`.
+        delta: i32,
+    },
+
+    /// The user selected a story to act on.
+    ///
+    /// Sent by the UI to the scheduler, which passes it back to the display.
+    StorySelected {
+        /// Story identifier.
+        story_id: String,
+    },
+
+    /// The user asked to show more or less of a story's agent output.
+    ///
+    /// Flows from the UI to the scheduler.
+    VerboseToggleRequested {
+        /// Story identifier.
+        story_id: String,
+    },
+
+    /// Verbose output was turned on or off for a story.
+    StoryVerbosityChanged {
+        /// Story identifier.
+        story_id: String,
+        /// Whether the story's output is shown in full.
+        verbose: bool,
+    },
+
+    /// The user opened or closed the detail pane of a story.
+    ///
+    /// Sent by the UI to the scheduler, which passes it back to the display.
+    StoryDetailsToggled {
+        /// Story identifier.
+        story_id: String,
+    },
 }
 
 impl ParallelUIEvent {
@@ -209,9 +342,14 @@ impl ParallelUIEvent {
         match self {
             Self::StoryStarted { story, .. } => Some(&story.id),
             Self::IterationUpdate { story_id, .. } => Some(story_id),
+            Self::AgentOutput { story_id, .. } => Some(story_id),
             Self::GateUpdate { story_id, .. } => Some(story_id),
             Self::StoryCompleted { story_id, .. } => Some(story_id),
             Self::StoryFailed { story_id, .. } => Some(story_id),
+            Self::StorySkipped { story_id, .. } => Some(story_id),
+            Self::StoryBlocked { story_id, .. } => Some(story_id),
+            Self::PrerequisiteUnmet { story_id, .. } => Some(story_id),
+            Self::CancelStoryRequested { story_id } => Some(story_id),
             Self::ConflictDeferred { story_id, .. } => Some(story_id),
             Self::ReconciliationStatus { .. } => None,
             Self::SequentialRetryStarted { story_id, .. } => Some(story_id),
@@ -221,12 +359,68 @@ impl ParallelUIEvent {
             Self::ImmediateInterrupt => None,
             Self::CircuitBreakerStatus { .. } => None,
             Self::CircuitBreakerTriggered { .. } => None,
+            Self::ConcurrencyAdjusted { .. } => None,
+            Self::RunPaused | Self::RunResumed => None,
+            Self::TogglePauseRequested | Self::RunPausing { .. } => None,
+            Self::ConcurrencyChangeRequested { .. } => None,
+            Self::StorySelected { story_id } => Some(story_id),
+            Self::VerboseToggleRequested { story_id } => Some(story_id),
+            Self::StoryVerbosityChanged { story_id, .. } => Some(story_id),
+            Self::StoryDetailsToggled { story_id } => Some(story_id),
         }
     }
 
-    /// Returns true if this is a terminal event for a story (completed or failed).
+    /// Returns true if this is a terminal event for a story (completed, failed,
+    /// skipped or blocked by a prerequisite).
     pub fn is_terminal(&self) -> bool {
-        matches!(self, Self::StoryCompleted { .. } | Self::StoryFailed { .. })
+        matches!(
+            self,
+            Self::StoryCompleted { .. }
+                | Self::StoryFailed { .. }
+                | Self::StorySkipped { .. }
+                | Self::PrerequisiteUnmet { .. }
+        )
+    }
+}
+
+/// Display callback forwarding a story's agent output to the parallel UI as
+/// [`ParallelUIEvent::AgentOutput`] events.
+///
+/// Lines are dropped rather than awaited when the UI falls behind.
+pub struct StoryOutputForwarder {
+    story_id: String,
+    sender: mpsc::Sender<ParallelUIEvent>,
+}
+
+impl StoryOutputForwarder {
+    /// Creates a forwarder for `story_id`'s output.
+    pub fn new(story_id: impl Into<String>, sender: mpsc::Sender<ParallelUIEvent>) -> Self {
+        Self {
+            story_id: story_id.into(),
+            sender,
+        }
+    }
+}
+
+impl DisplayCallback for StoryOutputForwarder {
+    fn on_agent_output(&self, line: &str, is_stderr: bool) {
+        if self.sender.capacity() <= OUTPUT_EVENT_HEADROOM {
+            return;
+        }
+        let _ = self.sender.try_send(ParallelUIEvent::AgentOutput {
+            story_id: self.story_id.clone(),
+            line: line.to_string(),
+            is_stderr,
+        });
+    }
+
+    fn on_gate_result(&self, _story_id: &str, _iteration: u32, result: &GateResult) {
+        let _ = self.sender.try_send(ParallelUIEvent::GateUpdate {
+            story_id: self.story_id.clone(),
+            gate_name: result.gate_name.clone(),
+            passed: result.passed,
+            message: Some(result.message.clone()),
+        });
     }
 }
 
@@ -260,6 +454,8 @@ mod tests {
         assert_eq!(StoryStatus::Failed.icon(), "✗");
         assert_eq!(StoryStatus::Deferred.icon(), "⊘");
         assert_eq!(StoryStatus::SequentialRetry.icon(), "↻");
+        assert_eq!(StoryStatus::Skipped.icon(), "⊖");
+        assert_eq!(StoryStatus::Blocked.icon(), "⊗");
     }
 
     #[test]
@@ -270,6 +466,8 @@ mod tests {
         assert_eq!(StoryStatus::Failed.label(), "Failed");
         assert_eq!(StoryStatus::Deferred.label(), "Deferred");
         assert_eq!(StoryStatus::SequentialRetry.label(), "Retrying");
+        assert_eq!(StoryStatus::Skipped.label(), "Skipped");
+        assert_eq!(StoryStatus::Blocked.label(), "Blocked");
     }
 
     #[test]
@@ -347,6 +545,42 @@ mod tests {
         assert!(event.is_terminal());
     }
 
+    #[test]
+    fn test_event_story_skipped_and_cancel_request() {
+        let skipped = ParallelUIEvent::StorySkipped {
+            story_id: "US-003".to_string(),
+            reason: "Cancelled by user".to_string(),
+        };
+        assert_eq!(skipped.story_id(), Some("US-003"));
+        assert!(skipped.is_terminal());
+
+        let request = ParallelUIEvent::CancelStoryRequested {
+            story_id: "US-003".to_string(),
+        };
+        assert_eq!(request.story_id(), Some("US-003"));
+        assert!(!request.is_terminal());
+    }
+
+    #[test]
+    fn test_event_story_blocked() {
+        let event = ParallelUIEvent::StoryBlocked {
+            story_id: "US-004".to_string(),
+            blocked_by: "US-001".to_string(),
+        };
+        assert_eq!(event.story_id(), Some("US-004"));
+        assert!(!event.is_terminal());
+    }
+
+    #[test]
+    fn test_event_prerequisite_unmet() {
+        let event = ParallelUIEvent::PrerequisiteUnmet {
+            story_id: "US-005".to_string(),
+            reason: "environment variable DATABASE_URL is not set".to_string(),
+        };
+        assert_eq!(event.story_id(), Some("US-005"));
+        assert!(event.is_terminal());
+    }
+
     #[test]
     fn test_event_conflict_deferred() {
         let event = ParallelUIEvent::ConflictDeferred {
@@ -381,6 +615,38 @@ mod tests {
         assert!(!event.is_terminal());
     }
 
+    #[test]
+    fn test_output_forwarder_keeps_headroom() {
+        let (sender, mut receiver) = mpsc::channel(OUTPUT_EVENT_HEADROOM + 2);
+        let forwarder = StoryOutputForwarder::new("US-001", sender);
+        for i in 0..10 {
+            forwarder.on_agent_output(&format!("line {}", i), false);
+        }
+
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.story_id(), Some("US-001"));
+        assert!(!event.is_terminal());
+        assert_eq!(
+            event,
+            ParallelUIEvent::AgentOutput {
+                story_id: "US-001".to_string(),
+                line: "line 0".to_string(),
+                is_stderr: false,
+            }
+        );
+        // Only two lines fit above the reserved headroom
+        assert!(receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_event_run_paused_and_resumed() {
+        for event in [ParallelUIEvent::RunPaused, ParallelUIEvent::RunResumed] {
+            assert_eq!(event.story_id(), None);
+            assert!(!event.is_terminal());
+        }
+    }
+
     #[test]
     fn test_event_sequential_retry_started() {
         let event = ParallelUIEvent::SequentialRetryStarted {
@@ -21,6 +21,8 @@ pub enum GateStatus {
     Passed,
     /// Gate failed
     Failed,
+    /// Gate failed with warning severity (does not block completion)
+    Warning,
     /// Gate was skipped (not enabled in profile)
     Skipped,
 }
@@ -33,6 +35,7 @@ impl GateStatus {
             Self::Running => "◉",
             Self::Passed => "✓",
             Self::Failed => "✗",
+            Self::Warning => "⚠",
             Self::Skipped => "⊘",
         }
     }
@@ -44,6 +47,7 @@ impl GateStatus {
             Self::Running => "Running",
             Self::Passed => "Passed",
             Self::Failed => "Failed",
+            Self::Warning => "Warning",
             Self::Skipped => "Skipped",
         }
     }
@@ -54,6 +58,8 @@ impl GateStatus {
             Self::Skipped
         } else if result.passed {
             Self::Passed
+        } else if result.is_warning() {
+            Self::Warning
         } else {
             Self::Failed
         }
@@ -176,6 +182,7 @@ impl QualityGateRenderer {
             GateStatus::Running => icon.color(self.theme.in_progress).to_string(),
             GateStatus::Passed => icon.color(self.theme.success).to_string(),
             GateStatus::Failed => icon.color(self.theme.error).to_string(),
+            GateStatus::Warning => icon.color(self.theme.warning).to_string(),
             GateStatus::Skipped => icon.color(self.theme.muted).to_string(),
         };
 
@@ -188,14 +195,19 @@ impl QualityGateRenderer {
 
         output.push_str(&format!("{} {}: {}\n", icon_colored, gate_name, message));
 
-        // Show details for failed gates (indented)
-        if gate.status == GateStatus::Failed {
+        // Show details for failed and warning gates (indented)
+        if matches!(gate.status, GateStatus::Failed | GateStatus::Warning) {
             if let Some(ref details) = gate.details {
+                let detail_color = if gate.status == GateStatus::Warning {
+                    self.theme.warning
+                } else {
+                    self.theme.error
+                };
                 let detail_lines: Vec<&str> = details.lines().take(10).collect();
                 for line in detail_lines {
                     let indented = format!("    {}", line);
                     let truncated = self.truncate_text(&indented, self.width - 2);
-                    output.push_str(&format!("{}\n", truncated.color(self.theme.error)));
+                    output.push_str(&format!("{}\n", truncated.color(detail_color)));
                 }
                 if details.lines().count() > 10 {
                     output.push_str(&format!(
@@ -240,23 +252,28 @@ impl QualityGateRenderer {
 
         // Summary bar
         let (passed, total, failed_names) = Self::count_results(gates);
+        let warning_names = Self::warning_names(gates);
         output.push_str(&format!("├{}┤\n", "─".repeat(inner_width)));
 
-        let summary = if failed_names.is_empty() {
+        let summary_text = if failed_names.is_empty() && warning_names.is_empty() {
             format!("All {} gates passed", total)
-                .color(self.theme.success)
-                .to_string()
+        } else if failed_names.is_empty() {
+            format!(
+                "{}/{} gates passed, {} warning(s)",
+                passed,
+                total,
+                warning_names.len()
+            )
         } else {
             format!("{}/{} gates passed", passed, total)
-                .color(self.theme.warning)
-                .to_string()
         };
-
-        let summary_visible_len = if failed_names.is_empty() {
-            format!("All {} gates passed", total).len()
+        let summary = if failed_names.is_empty() && warning_names.is_empty() {
+            summary_text.color(self.theme.success).to_string()
         } else {
-            format!("{}/{} gates passed", passed, total).len()
+            summary_text.color(self.theme.warning).to_string()
         };
+
+        let summary_visible_len = summary_text.len();
         let summary_padding = if inner_width > summary_visible_len + 2 {
             inner_width - summary_visible_len - 2
         } else {
@@ -274,14 +291,24 @@ impl QualityGateRenderer {
     /// Render a compact summary bar only.
     pub fn render_summary_bar(&self, gates: &[QualityGateView]) -> String {
         let (passed, total, failed_names) = Self::count_results(gates);
+        let warning_names = Self::warning_names(gates);
 
-        if failed_names.is_empty() {
+        if failed_names.is_empty() && warning_names.is_empty() {
             format!(
                 "{} {} All {} gates passed",
                 "✓".color(self.theme.success),
                 "│".color(self.theme.muted),
                 total
             )
+        } else if failed_names.is_empty() {
+            format!(
+                "{} {} {}/{} gates passed. Warnings: {}",
+                "⚠".color(self.theme.warning),
+                "│".color(self.theme.muted),
+                passed,
+                total,
+                warning_names.join(", ")
+            )
         } else {
             format!(
                 "{} {} {}/{} gates passed. Failed: {}",
@@ -362,11 +389,21 @@ impl QualityGateRenderer {
 
         (passed, total, failed_names)
     }
+
+    /// Get names of gates that failed with warning severity.
+    fn warning_names(gates: &[QualityGateView]) -> Vec<String> {
+        gates
+            .iter()
+            .filter(|g| g.status == GateStatus::Warning)
+            .map(|g| g.name.clone())
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::quality::GateSeverity;
 
     #[test]
     fn test_gate_status_icons() {
@@ -445,6 +482,29 @@ mod tests {
         assert!(view.details.is_some());
     }
 
+    #[test]
+    fn test_from_gate_result_warning() {
+        let result = GateResult::fail("format", "Needs formatting", None, None)
+            .with_severity(GateSeverity::Warning);
+        let view = QualityGateView::from_gate_result(&result);
+        assert_eq!(view.status, GateStatus::Warning);
+        assert_eq!(view.status.icon(), "⚠");
+        assert_eq!(view.status.label(), "Warning");
+    }
+
+    #[test]
+    fn test_render_summary_bar_with_warnings() {
+        let renderer = QualityGateRenderer::new();
+        let gates = vec![
+            QualityGateView::new("lint", GateStatus::Passed, "OK"),
+            QualityGateView::new("format", GateStatus::Warning, "Needs formatting"),
+        ];
+
+        let output = renderer.render_summary_bar(&gates);
+        assert!(output.contains("1/2 gates passed. Warnings: format"));
+        assert!(!output.contains("Failed"));
+    }
+
     #[test]
     fn test_from_gate_result_skipped() {
         let result = GateResult::skipped("security_audit", "Not enabled");
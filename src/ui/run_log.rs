@@ -0,0 +1,317 @@
+//! Run log file (`--log`).
+//!
+//! Mirrors what a run shows in the console to `.ralph/logs/<run-id>.jsonl`,
+//! together with the full agent and quality gate output the terminal UI
+//! trims, so the raw record survives the TUI redrawing over it. Lines use the
+//! same versioned schema as `--output jsonl`, plus `gate_output` records:
+//!
+//! ```text
+//! {"schema_version":1,"timestamp":"...","event":"story_started","story":{"id":"US-001",...},...}
+//! {"schema_version":1,"timestamp":"...","event":"agent_output","story_id":"US-001","line":"Reading src/lib.rs","is_stderr":false}
+//! {"schema_version":1,"timestamp":"...","event":"gate_output","story_id":"US-001","iteration":1,"gate":"tests","passed":false,...}
+//! ```
+//!
+//! Once the file reaches its size limit it is rotated to `<run-id>.1.jsonl`,
+//! the previous `.1` to `.2` and so on; the oldest rotated file is dropped.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+use crate::quality::gates::GateResult;
+use crate::ui::display::DisplayCallback;
+use crate::ui::events::RunEvent;
+use crate::ui::jsonl::to_json_line;
+use crate::ui::parallel_events::ParallelUIEvent;
+
+/// Directory run logs are written to, relative to the project.
+pub const RUN_LOGS_DIR: &str = ".ralph/logs";
+
+/// Capacity of the channel events are logged from.
+const EVENT_CHANNEL_CAPACITY: usize = 100;
+
+/// Size and number of a run's log files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunLogConfig {
+    /// Size in bytes at which the log file is rotated
+    pub max_bytes: u64,
+    /// Rotated files kept besides the current one
+    pub max_rotated: usize,
+}
+
+impl Default for RunLogConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            max_rotated: 5,
+        }
+    }
+}
+
+/// A log file that is rotated once it reaches a size limit.
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    config: RunLogConfig,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn open(path: impl Into<PathBuf>, config: RunLogConfig) -> io::Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            config,
+            file,
+            size,
+        })
+    }
+
+    /// Appends `line`, rotating the file first if the line would not fit.
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.config.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// Path of the `n`th rotated file, e.g. `run-1.2.jsonl` for `run-1.jsonl`.
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = match self.path.extension() {
+            Some(ext) => format!("{}.{}.{}", stem, n, ext.to_string_lossy()),
+            None => format!("{}.{}", stem, n),
+        };
+        self.path.with_file_name(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.config.max_rotated == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.rotated_path(self.config.max_rotated));
+            for n in (1..self.config.max_rotated).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Writes a run's events and output to its log file.
+///
+/// Cheap to clone; clones write to the same file.
+#[derive(Clone, Debug)]
+pub struct RunLog {
+    /// Log file; `None` once a write failed
+    file: Arc<Mutex<Option<RotatingFile>>>,
+    /// Path of the current log file
+    path: PathBuf,
+}
+
+impl RunLog {
+    /// Creates the log for `run_id` under `base_dir/.ralph/logs`.
+    pub fn create(base_dir: &Path, run_id: &str, config: RunLogConfig) -> io::Result<Self> {
+        let logs_dir = base_dir.join(RUN_LOGS_DIR);
+        fs::create_dir_all(&logs_dir)?;
+        let path = logs_dir.join(format!("{}.jsonl", run_id));
+        let file = RotatingFile::open(&path, config)?;
+        Ok(Self {
+            file: Arc::new(Mutex::new(Some(file))),
+            path,
+        })
+    }
+
+    /// Path of the current log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends `event` as a JSON line.
+    ///
+    /// A failed write disables the log after a warning rather than
+    /// interrupting the run.
+    pub fn record(&self, event: RunEvent) {
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        let Some(log) = file.as_mut() else {
+            return;
+        };
+        let result = to_json_line(event)
+            .map_err(io::Error::other)
+            .and_then(|line| log.write_line(&line));
+        if let Err(err) = result {
+            eprintln!("Warning: Failed to write run log: {}", err);
+            *file = None;
+        }
+    }
+
+    /// A display callback that logs the full agent and gate output of
+    /// `story_id`, then passes it on to `inner`.
+    pub fn output_callback(
+        &self,
+        story_id: impl Into<String>,
+        inner: Option<Arc<dyn DisplayCallback>>,
+    ) -> Arc<dyn DisplayCallback> {
+        Arc::new(LoggedOutput {
+            log: self.clone(),
+            story_id: story_id.into(),
+            inner,
+        })
+    }
+
+    /// A sender that logs the public [`RunEvent`] of each UI event sent to
+    /// it, then passes every event on to `forward`.
+    ///
+    /// Agent output events are not logged; the output callback records every
+    /// line, while these are dropped when the UI falls behind.
+    pub fn tap(
+        &self,
+        forward: Option<mpsc::Sender<ParallelUIEvent>>,
+    ) -> mpsc::Sender<ParallelUIEvent> {
+        let (sender, mut receiver) = mpsc::channel::<ParallelUIEvent>(EVENT_CHANNEL_CAPACITY);
+        let log = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                match RunEvent::from_ui_event(&event) {
+                    Some(RunEvent::AgentOutput { .. }) | None => {}
+                    Some(logged) => log.record(logged),
+                }
+                if let Some(ref forward) = forward {
+                    let _ = forward.try_send(event);
+                }
+            }
+        });
+        sender
+    }
+}
+
+/// Display callback logging a story's output before passing it on.
+struct LoggedOutput {
+    log: RunLog,
+    story_id: String,
+    inner: Option<Arc<dyn DisplayCallback>>,
+}
+
+impl DisplayCallback for LoggedOutput {
+    fn on_agent_output(&self, line: &str, is_stderr: bool) {
+        self.log.record(RunEvent::AgentOutput {
+            story_id: self.story_id.clone(),
+            line: line.to_string(),
+            is_stderr,
+        });
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_output(line, is_stderr);
+        }
+    }
+
+    fn on_agent_started(&self, story_id: &str, iteration: u32) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_started(story_id, iteration);
+        }
+    }
+
+    fn on_agent_completed(&self, story_id: &str, success: bool) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_completed(story_id, success);
+        }
+    }
+
+    fn on_gate_result(&self, story_id: &str, iteration: u32, result: &GateResult) {
+        self.log.record(RunEvent::GateOutput {
+            story_id: story_id.to_string(),
+            iteration,
+            gate: result.gate_name.clone(),
+            passed: result.passed,
+            message: result.message.clone(),
+            output: result.details.clone(),
+        });
+        if let Some(ref inner) = self.inner {
+            inner.on_gate_result(story_id, iteration, result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file_keeps_limited_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run-1.jsonl");
+        let config = RunLogConfig {
+            max_bytes: 20,
+            max_rotated: 2,
+        };
+        let mut file = RotatingFile::open(&path, config).unwrap();
+        for line in ["first line", "second line", "third line", "fourth line"] {
+            file.write_line(line).unwrap();
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("run-1.jsonl"), "fourth line\n");
+        assert_eq!(read("run-1.1.jsonl"), "third line\n");
+        assert_eq!(read("run-1.2.jsonl"), "second line\n");
+        assert!(!dir.path().join("run-1.3.jsonl").exists());
+    }
+
+    #[test]
+    fn test_run_log_records_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = RunLog::create(dir.path(), "run-1", RunLogConfig::default()).unwrap();
+        assert!(log.path().ends_with(".ralph/logs/run-1.jsonl"));
+
+        let output = log.output_callback("US-001", None);
+        output.on_agent_output("Reading src/lib.rs", false);
+        output.on_gate_result(
+            "US-001",
+            2,
+            &GateResult::fail(
+                "tests",
+                "1 test failed",
+                Some("test it_works ... FAILED".into()),
+                None,
+            ),
+        );
+        log.record(RunEvent::RunPaused);
+
+        let lines: Vec<serde_json::Value> = fs::read_to_string(log.path())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["event"], "agent_output");
+        assert_eq!(lines[0]["line"], "Reading src/lib.rs");
+        assert_eq!(lines[1]["event"], "gate_output");
+        assert_eq!(lines[1]["iteration"], 2);
+        assert_eq!(lines[1]["output"], "test it_works ... FAILED");
+        assert_eq!(lines[2]["event"], "run_paused");
+    }
+}
@@ -0,0 +1,423 @@
+//! Summary-only output (`--output summary`).
+//!
+//! Suppresses the terminal UI and streamed agent output while a run goes and
+//! prints a single report once it finishes, for CI logs that should hold the
+//! outcome rather than the noise:
+//!
+//! ```text
+//! Run run-1: 1 of 2 stories passed in 312.4s
+//!
+//! Story   Result  Attempts  Duration   Cost  Agent
+//! US-001  passed         1     65.0s  $0.12  claude
+//! US-002  failed         3    247.4s  $0.30  claude
+//!
+//! Gate   Runs  Failed  Total   Mean    Max
+//! tests     4       2  80.0s  20.0s  31.2s
+//!
+//! Failures:
+//!   US-002 (iteration 3): Quality gates failed: clippy
+//!     clippy: 2 warnings
+//!       src/lib.rs:10:5: unused variable: `x` [unused_variables]
+//!
+//! Cost: $0.42 (120000 tokens over 4 agent runs)
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::metrics::{format_epic_progress, InvocationStats, RunMetrics};
+use crate::quality::gates::{GateFailureDetail, GateResult};
+use crate::runner::RunResult;
+use crate::ui::display::DisplayCallback;
+
+/// Most failure locations listed per gate.
+const MAX_FAILURES_PER_GATE: usize = 10;
+
+/// Collects what the final report shows beyond the run metrics: the failed
+/// gates of each story's latest iteration, with their failure locations.
+///
+/// Cheap to clone; clones collect into the same report.
+#[derive(Clone, Debug, Default)]
+pub struct RunReport {
+    failed_gates: Arc<Mutex<BTreeMap<String, FailedGates>>>,
+}
+
+/// Failed gates of a story's latest gated iteration.
+#[derive(Clone, Debug, Default)]
+struct FailedGates {
+    iteration: u32,
+    gates: Vec<GateResult>,
+}
+
+impl RunReport {
+    /// An empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A display callback that records the failed gates of `story_id`, then
+    /// passes everything on to `inner`.
+    pub fn output_callback(
+        &self,
+        story_id: impl Into<String>,
+        inner: Option<Arc<dyn DisplayCallback>>,
+    ) -> Arc<dyn DisplayCallback> {
+        Arc::new(ReportedOutput {
+            report: self.clone(),
+            story_id: story_id.into(),
+            inner,
+        })
+    }
+
+    fn record_gate(&self, story_id: &str, iteration: u32, result: &GateResult) {
+        let Ok(mut failed_gates) = self.failed_gates.lock() else {
+            return;
+        };
+        let story = failed_gates.entry(story_id.to_string()).or_default();
+        // Gates of a new iteration replace those of the previous one
+        if story.iteration != iteration {
+            story.iteration = iteration;
+            story.gates.clear();
+        }
+        if !result.passed {
+            story.gates.push(result.clone());
+        }
+    }
+
+    /// Prints the report of a finished run.
+    pub fn finish(&self, metrics: &RunMetrics, result: &RunResult) {
+        print!("{}", self.render(metrics, result));
+    }
+
+    /// The report of a finished run: its result, a row per story that ran,
+    /// quality gate durations, the failures of the stories that did not pass
+    /// and what the agents cost.
+    pub fn render(&self, metrics: &RunMetrics, result: &RunResult) -> String {
+        let mut output = format!(
+            "Run {}: {} of {} stories passed in {:.1}s",
+            metrics.run_id,
+            result.stories_passed,
+            result.total_stories,
+            metrics.run_duration.as_secs_f64()
+        );
+        match result.error {
+            Some(ref error) => output.push_str(&format!(" ({})\n", first_line(error))),
+            None => output.push('\n'),
+        }
+
+        if !metrics.steps.is_empty() {
+            output.push('\n');
+            output.push_str(&story_table(metrics));
+        }
+        let gates = gate_table(metrics);
+        if !gates.is_empty() {
+            output.push('\n');
+            output.push_str(&gates);
+        }
+        let failures = self.failures(metrics);
+        if !failures.is_empty() {
+            output.push_str("\nFailures:\n");
+            output.push_str(&failures);
+        }
+        if !metrics.epics.is_empty() {
+            output.push('\n');
+            output.push_str(&format_epic_progress(&metrics.epics));
+        }
+
+        let stats = InvocationStats::from_invocations(&metrics.invocations);
+        if stats.invocations > 0 {
+            output.push_str(&format!(
+                "\nCost: ${:.2} ({} tokens over {} agent runs)\n",
+                stats.cost_cents / 100.0,
+                stats.tokens,
+                stats.invocations
+            ));
+        }
+        output
+    }
+
+    /// Why each story that did not pass failed, with the failure locations
+    /// of its latest failed gates.
+    fn failures(&self, metrics: &RunMetrics) -> String {
+        let failed_gates = self
+            .failed_gates
+            .lock()
+            .map(|failed_gates| failed_gates.clone())
+            .unwrap_or_default();
+        let mut output = String::new();
+        for step in metrics.steps.iter().filter(|step| !step.success) {
+            let gates = failed_gates.get(&step.step_id);
+            output.push_str(&format!("  {}", step.step_id));
+            if let Some(gates) = gates.filter(|gates| !gates.gates.is_empty()) {
+                output.push_str(&format!(" (iteration {})", gates.iteration));
+            }
+            match step.error {
+                Some(ref error) => output.push_str(&format!(": {}\n", first_line(error))),
+                None => output.push('\n'),
+            }
+            for gate in gates
+                .map(|gates| gates.gates.as_slice())
+                .unwrap_or_default()
+            {
+                output.push_str(&format!("    {}: {}\n", gate.gate_name, gate.message));
+                for failure in gate.failures.iter().take(MAX_FAILURES_PER_GATE) {
+                    output.push_str(&format!("      {}\n", failure_line(failure)));
+                }
+                if gate.failures.len() > MAX_FAILURES_PER_GATE {
+                    output.push_str(&format!(
+                        "      ... {} more\n",
+                        gate.failures.len() - MAX_FAILURES_PER_GATE
+                    ));
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Display callback recording a story's failed gates before passing its
+/// output on.
+struct ReportedOutput {
+    report: RunReport,
+    story_id: String,
+    inner: Option<Arc<dyn DisplayCallback>>,
+}
+
+impl DisplayCallback for ReportedOutput {
+    fn on_agent_output(&self, line: &str, is_stderr: bool) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_output(line, is_stderr);
+        }
+    }
+
+    fn on_agent_started(&self, story_id: &str, iteration: u32) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_started(story_id, iteration);
+        }
+    }
+
+    fn on_agent_completed(&self, story_id: &str, success: bool) {
+        if let Some(ref inner) = self.inner {
+            inner.on_agent_completed(story_id, success);
+        }
+    }
+
+    fn on_gate_result(&self, story_id: &str, iteration: u32, result: &GateResult) {
+        self.report.record_gate(&self.story_id, iteration, result);
+        if let Some(ref inner) = self.inner {
+            inner.on_gate_result(story_id, iteration, result);
+        }
+    }
+}
+
+/// A row per story that ran, in the order they started.
+fn story_table(metrics: &RunMetrics) -> String {
+    let mut cost_cents: BTreeMap<&str, f64> = BTreeMap::new();
+    for invocation in &metrics.invocations {
+        *cost_cents.entry(invocation.step_id.as_str()).or_default() +=
+            invocation.cost_cents.unwrap_or(0.0);
+    }
+    let mut steps: Vec<_> = metrics.steps.iter().collect();
+    steps.sort_by_key(|step| step.started_at);
+    let rows: Vec<[String; 6]> = steps
+        .into_iter()
+        .map(|step| {
+            [
+                step.step_id.clone(),
+                if step.success { "passed" } else { "failed" }.to_string(),
+                step.attempts.to_string(),
+                format!("{:.1}s", step.duration.as_secs_f64()),
+                match cost_cents.get(step.step_id.as_str()) {
+                    Some(cents) => format!("${:.2}", cents / 100.0),
+                    None => "-".to_string(),
+                },
+                step.agent.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    table(
+        ["Story", "Result", "Attempts", "Duration", "Cost", "Agent"],
+        &[false, false, true, true, true, false],
+        &rows,
+    )
+}
+
+/// A row per quality gate that ran, with how often it failed and how long
+/// it took.
+fn gate_table(metrics: &RunMetrics) -> String {
+    let mut durations: BTreeMap<&str, Vec<Duration>> = BTreeMap::new();
+    let mut failures: BTreeMap<&str, u32> = BTreeMap::new();
+    for invocation in &metrics.invocations {
+        for (gate, duration) in &invocation.gate_durations {
+            durations.entry(gate.as_str()).or_default().push(*duration);
+        }
+        for gate in &invocation.failed_gates {
+            *failures.entry(gate.as_str()).or_default() += 1;
+        }
+    }
+    if durations.is_empty() {
+        return String::new();
+    }
+    let rows: Vec<[String; 6]> = durations
+        .into_iter()
+        .map(|(gate, durations)| {
+            let total: Duration = durations.iter().sum();
+            let max = durations.iter().max().copied().unwrap_or_default();
+            [
+                gate.to_string(),
+                durations.len().to_string(),
+                failures.get(gate).copied().unwrap_or(0).to_string(),
+                format!("{:.1}s", total.as_secs_f64()),
+                format!("{:.1}s", (total / durations.len() as u32).as_secs_f64()),
+                format!("{:.1}s", max.as_secs_f64()),
+            ]
+        })
+        .collect();
+    table(
+        ["Gate", "Runs", "Failed", "Total", "Mean", "Max"],
+        &[false, true, true, true, true, true],
+        &rows,
+    )
+}
+
+/// Columns aligned to their widest cell, right-aligned where `numeric`.
+fn table<const N: usize>(header: [&str; N], numeric: &[bool; N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(|title| title.chars().count());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        let cells: Vec<String> = cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                if numeric[i] {
+                    format!("{:>width$}", cell, width = widths[i])
+                } else {
+                    format!("{:<width$}", cell, width = widths[i])
+                }
+            })
+            .collect();
+        format!("{}\n", cells.join("  ").trim_end())
+    };
+    let mut output = line(header.to_vec());
+    for row in rows {
+        output.push_str(&line(row.iter().map(String::as_str).collect()));
+    }
+    output
+}
+
+/// A gate failure with its location, e.g. `src/lib.rs:10:5: message [code]`.
+fn failure_line(failure: &GateFailureDetail) -> String {
+    let mut line = String::new();
+    if let Some(ref file) = failure.file {
+        line.push_str(file);
+        if let Some(number) = failure.line {
+            line.push_str(&format!(":{}", number));
+            if let Some(column) = failure.column {
+                line.push_str(&format!(":{}", column));
+            }
+        }
+        line.push_str(": ");
+    }
+    line.push_str(first_line(&failure.message));
+    if let Some(ref code) = failure.error_code {
+        line.push_str(&format!(" [{}]", code));
+    }
+    line
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{AgentInvocation, RunMetricsCollector};
+    use crate::quality::gates::FailureCategory;
+    use crate::runner::RunOutcome;
+
+    #[test]
+    fn test_render_report() {
+        let run_metrics = RunMetricsCollector::new("run-1", 2);
+        run_metrics.start_step("US-001");
+        run_metrics.record_step_agent("US-001", "claude");
+        run_metrics.complete_step("US-001", true, 1, Duration::from_secs(65), None);
+        run_metrics.start_step("US-002");
+        run_metrics.complete_step(
+            "US-002",
+            false,
+            2,
+            Duration::from_secs(120),
+            Some("Quality gates failed: clippy\nmore".to_string()),
+        );
+        let mut invocation = AgentInvocation::new("US-002", 2, "agent", "claude");
+        invocation.input_tokens = Some(1000);
+        invocation.cost_cents = Some(30.0);
+        invocation
+            .gate_durations
+            .insert("clippy".to_string(), Duration::from_secs(12));
+        invocation.failed_gates = vec!["clippy".to_string()];
+        run_metrics.record_invocation(invocation);
+        let metrics = run_metrics.finish();
+        let result = RunResult {
+            all_passed: false,
+            stories_passed: 1,
+            total_stories: 2,
+            total_iterations: 3,
+            error: None,
+            outcome: RunOutcome::GateFailure,
+        };
+
+        let report = RunReport::new();
+        let output = report.output_callback("US-002", None);
+        output.on_gate_result(
+            "US-002",
+            1,
+            &GateResult::fail("tests", "1 test failed", None, None),
+        );
+        output.on_gate_result("US-002", 2, &GateResult::pass("tests", "passed"));
+        output.on_gate_result(
+            "US-002",
+            2,
+            &GateResult::fail(
+                "clippy",
+                "1 warning",
+                None,
+                Some(vec![GateFailureDetail::new(
+                    FailureCategory::Lint,
+                    "unused variable: `x`",
+                )
+                .with_location("src/lib.rs", 10, Some(5))
+                .with_error_code("unused_variables")]),
+            ),
+        );
+
+        let rendered = report.render(&metrics, &result);
+        let lines: Vec<&str> = rendered.lines().skip(1).collect();
+        assert!(rendered.starts_with("Run run-1: 1 of 2 stories passed in "));
+        assert_eq!(
+            lines,
+            [
+                "",
+                "Story   Result  Attempts  Duration   Cost  Agent",
+                "US-001  passed         1     65.0s      -  claude",
+                "US-002  failed         2    120.0s  $0.30",
+                "",
+                "Gate    Runs  Failed  Total   Mean    Max",
+                "clippy     1       1  12.0s  12.0s  12.0s",
+                "",
+                "Failures:",
+                "  US-002 (iteration 2): Quality gates failed: clippy",
+                "    clippy: 1 warning",
+                "      src/lib.rs:10:5: unused variable: `x` [unused_variables]",
+                "",
+                "Cost: $0.30 (1000 tokens over 1 agent runs)",
+            ]
+        );
+    }
+}
@@ -18,6 +18,9 @@ pub mod spinner_chars {
     /// Braille spinner pattern - smooth animation
     pub const BRAILLE: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
+    /// ASCII spinner pattern - for terminals without Unicode
+    pub const ASCII: &[&str] = &["|", "/", "-", "\\"];
+
     /// Dots spinner pattern - simple animation
     pub const DOTS: &[&str] = &["⠋", "⠙", "⠚", "⠞", "⠖", "⠦", "⠴", "⠲", "⠳", "⠓"];
 
@@ -0,0 +1,126 @@
+//! Symbol sets for terminal output.
+//!
+//! The parallel display draws status icons, spinners and frames with Unicode
+//! characters by default; the ASCII set keeps it readable on terminals and
+//! fonts without them, and for screen readers.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::ui::parallel_events::StoryStatus;
+use crate::ui::spinner::spinner_chars;
+
+/// Characters the parallel display draws with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    /// Spinner frames of a running story
+    pub spinner: &'static [&'static str],
+    /// Mark of a passed gate
+    pub pass: &'static str,
+    /// Mark of a failed gate
+    pub fail: &'static str,
+    /// Separator between parts of a line
+    pub separator: &'static str,
+    /// First, middle and last line prefix of a story's output or details
+    pub panel: [&'static str; 3],
+    /// Top left, top right, bottom left and bottom right corner of the
+    /// header and completion boxes
+    pub corners: [&'static str; 4],
+    /// Horizontal and vertical edge of the boxes
+    pub edges: [&'static str; 2],
+    /// Whether emoji may be shown
+    pub emoji: bool,
+    /// Status icons in [`StoryStatus`] order
+    status: [&'static str; 8],
+}
+
+impl Symbols {
+    /// Box drawing characters, status icons and emoji.
+    pub const UNICODE: Self = Self {
+        spinner: spinner_chars::BRAILLE,
+        pass: "✓",
+        fail: "✗",
+        separator: "·",
+        panel: ["┌", "│", "└"],
+        corners: ["╔", "╗", "╚", "╝"],
+        edges: ["═", "║"],
+        emoji: true,
+        status: ["○", "◉", "✓", "✗", "⊘", "↻", "⊖", "⊗"],
+    };
+
+    /// Plain ASCII only.
+    pub const ASCII: Self = Self {
+        spinner: spinner_chars::ASCII,
+        pass: "+",
+        fail: "x",
+        separator: "|",
+        panel: ["+", "|", "+"],
+        corners: ["+", "+", "+", "+"],
+        edges: ["=", "|"],
+        emoji: false,
+        status: ["o", "*", "+", "x", "~", "r", "-", "#"],
+    };
+
+    /// Icon of a story in `status`.
+    pub fn status_icon(&self, status: StoryStatus) -> &'static str {
+        let index = match status {
+            StoryStatus::Pending => 0,
+            StoryStatus::InProgress => 1,
+            StoryStatus::Completed => 2,
+            StoryStatus::Failed => 3,
+            StoryStatus::Deferred => 4,
+            StoryStatus::SequentialRetry => 5,
+            StoryStatus::Skipped => 6,
+            StoryStatus::Blocked => 7,
+        };
+        self.status[index]
+    }
+}
+
+/// Which [`Symbols`] to draw with (`--symbols`, `RALPH_SYMBOLS`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymbolSet {
+    #[default]
+    Unicode,
+    Ascii,
+}
+
+impl SymbolSet {
+    /// The symbols of this set.
+    pub fn symbols(self) -> &'static Symbols {
+        match self {
+            Self::Unicode => &Symbols::UNICODE,
+            Self::Ascii => &Symbols::ASCII,
+        }
+    }
+
+    /// Label used on the command line and in `RALPH_SYMBOLS`.
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+        }
+    }
+}
+
+impl fmt::Display for SymbolSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
+impl FromStr for SymbolSet {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        [Self::Unicode, Self::Ascii]
+            .into_iter()
+            .find(|set| set.as_label() == value.trim())
+            .ok_or_else(|| {
+                format!(
+                    "unknown symbol set '{}' (expected one of: unicode, ascii)",
+                    value.trim()
+                )
+            })
+    }
+}
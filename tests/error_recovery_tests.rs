@@ -874,6 +874,7 @@ fn test_circuit_breaker_checkpoint_save_and_load() {
         PauseReason::CircuitBreakerTriggered {
             consecutive_failures: 5,
             threshold: 5,
+            category: None,
         },
         vec!["src/broken.rs".to_string()],
     );
@@ -886,6 +887,7 @@ fn test_circuit_breaker_checkpoint_save_and_load() {
         PauseReason::CircuitBreakerTriggered {
             consecutive_failures,
             threshold,
+            ..
         } => {
             assert_eq!(*consecutive_failures, 5);
             assert_eq!(*threshold, 5);
@@ -910,6 +912,7 @@ fn test_circuit_breaker_with_custom_threshold() {
         PauseReason::CircuitBreakerTriggered {
             consecutive_failures: 3,
             threshold: 3,
+            category: None,
         },
         vec![],
     );
@@ -921,6 +924,7 @@ fn test_circuit_breaker_with_custom_threshold() {
         PauseReason::CircuitBreakerTriggered {
             consecutive_failures,
             threshold,
+            ..
         } => {
             assert_eq!(*consecutive_failures, 3);
             assert_eq!(*threshold, 3);
@@ -965,6 +969,7 @@ fn test_circuit_breaker_display_message() {
     let reason = PauseReason::CircuitBreakerTriggered {
         consecutive_failures: 5,
         threshold: 5,
+        category: None,
     };
 
     let display = format!("{}", reason);
@@ -977,6 +982,7 @@ fn test_circuit_breaker_serialization_roundtrip() {
     let reason = PauseReason::CircuitBreakerTriggered {
         consecutive_failures: 7,
         threshold: 5,
+        category: None,
     };
 
     let json = serde_json::to_string(&reason).expect("Failed to serialize");